@@ -0,0 +1,127 @@
+//! Typed API for embedding greentic-dev in other Rust tools, such as editors, GUIs and CI bots.
+//!
+//! Every function here returns a structured report and never prints or exits; the
+//! `greentic-dev` binary is one adapter that renders these reports. The other public modules
+//! exist for the binary and may change between releases without notice.
+//!
+//! ```no_run
+//! use greentic_dev::api::{self, BuildRequest, RunRequest, RunStatus};
+//!
+//! let built = api::build_pack(&BuildRequest::new("flows/hello.ygtc", "dist/hello.gtpack"))?;
+//! let inspected = api::inspect_pack(&built.pack)?;
+//! println!("{} {} ({} flows)", inspected.pack_id, inspected.version, inspected.flows.len());
+//!
+//! let run = api::run_pack(&RunRequest {
+//!     input: Some(serde_json::json!({ "text": "hi" })),
+//!     ..RunRequest::new(&built.pack)
+//! })?;
+//! assert_eq!(run.status, RunStatus::Succeeded);
+//! # anyhow::Ok(())
+//! ```
+//!
+//! Relative build paths resolve against the current directory, which must contain the flow, the
+//! metadata and the component directory of a build, as it does for `pack build`. A run takes its
+//! directory, runner and environment from its [`ExecutionContext`] instead, so runs with separate
+//! contexts can go on in parallel threads.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+
+pub use crate::exec_context::ExecutionContext;
+pub use crate::pack_build::{
+    BackfilledOperation, PackBuildOptions as BuildOptions, PackBuildReport as BuildReport,
+    PackSigning, PhaseTiming,
+};
+pub use crate::pack_inspect::{ComponentSummary, FlowSummary, InspectReport};
+pub use crate::run_result::{RunError, RunReport, RunStatus, TraceEntry, TraceStatus};
+
+use crate::{flow_contract, pack_build, pack_inspect, run_result};
+
+/// A pack build: the flow to package and where to write the gtpack.
+#[derive(Debug, Clone)]
+pub struct BuildRequest {
+    pub flow: PathBuf,
+    pub out: PathBuf,
+    /// `pack.toml` with the pack metadata; defaults are derived from the flow without one.
+    pub meta: Option<PathBuf>,
+    pub component_dir: Option<PathBuf>,
+    pub signing: PackSigning,
+    pub options: BuildOptions,
+}
+
+impl BuildRequest {
+    /// A dev-signed build of `flow` into `out` with the default options.
+    pub fn new(flow: impl Into<PathBuf>, out: impl Into<PathBuf>) -> Self {
+        Self {
+            flow: flow.into(),
+            out: out.into(),
+            meta: None,
+            component_dir: None,
+            signing: PackSigning::Dev,
+            options: BuildOptions::default(),
+        }
+    }
+}
+
+/// A local run of a built pack through greentic-runner-cli.
+#[derive(Debug, Clone)]
+pub struct RunRequest {
+    pub pack: PathBuf,
+    /// Flow to start from; the pack's entry flow without one.
+    pub entry: Option<String>,
+    /// Input document; checked against the flow's input schema before the runner starts.
+    pub input: Option<JsonValue>,
+    /// Further runner arguments, passed through as they are.
+    pub args: Vec<OsString>,
+    /// Where and with what environment the runner runs; `pack` is relative to its root.
+    pub context: ExecutionContext,
+}
+
+impl RunRequest {
+    pub fn new(pack: impl Into<PathBuf>) -> Self {
+        Self {
+            pack: pack.into(),
+            entry: None,
+            input: None,
+            args: Vec::new(),
+            context: ExecutionContext::default(),
+        }
+    }
+}
+
+/// Build a gtpack, as `pack build` does.
+pub fn build_pack(request: &BuildRequest) -> Result<BuildReport> {
+    pack_build::run_with_options(
+        &request.flow,
+        &request.out,
+        request.signing.clone(),
+        request.meta.as_deref(),
+        request.component_dir.as_deref(),
+        request.options,
+    )
+}
+
+/// Summarise a gtpack's metadata, flows and components and check its signature and SBOM.
+pub fn inspect_pack(pack: &Path) -> Result<InspectReport> {
+    pack_inspect::inspect(pack)
+}
+
+/// Run a pack and return its result, as `pack run --json` does. A failed flow is a report with
+/// [`RunStatus::Failed`]; a missing runner or output that breaks the result contract is an error.
+pub fn run_pack(request: &RunRequest) -> Result<RunReport> {
+    let pack = request.context.resolve(&request.pack);
+    let mut args: Vec<OsString> = vec!["--pack".into(), pack.into()];
+    if let Some(entry) = &request.entry {
+        args.extend(["--entry".into(), entry.into()]);
+    }
+    if let Some(input) = &request.input {
+        args.extend(["--input".into(), input.to_string().into()]);
+    }
+    args.extend(request.args.iter().cloned());
+    args.push("--json".into());
+    flow_contract::check_run_input(&args)?;
+    run_result::run_report(request.context.runner_command(&args)?)
+}
@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::pack_build;
+use crate::pack_cli::PackBuildArgs;
+
+/// Workspace-relative location of the capability policy consulted by `pack build`.
+pub const POLICY_PATH: &str = ".greentic/policy.yaml";
+
+/// Capability policy file. `default` applies to every pack without its own entry in `packs`.
+///
+/// Omitted sections are unrestricted; inside a section anything not listed is denied.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityPolicy {
+    #[serde(default)]
+    pub default: Option<PackPolicy>,
+    #[serde(default)]
+    pub packs: BTreeMap<String, PackPolicy>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackPolicy {
+    /// Allowed WASI capabilities: `filesystem`, `env`, `random`, `clocks`.
+    pub wasi: Option<Vec<String>>,
+    /// Environment variables components may read.
+    pub env: Option<Vec<String>>,
+    pub filesystem: Option<FilesystemPolicy>,
+    pub network: Option<NetworkPolicy>,
+    /// Licenses the pack and its components may carry; checked by `pack licenses`.
+    pub licenses: Option<LicensePolicy>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilesystemPolicy {
+    /// Most permissive mode allowed: `none`, `read_only` or `sandbox`.
+    #[serde(default = "default_fs_mode")]
+    pub mode: String,
+    /// Host classes components may mount; omitted means any.
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkPolicy {
+    #[serde(default)]
+    pub client: bool,
+    #[serde(default)]
+    pub server: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub component: String,
+    pub message: String,
+}
+
+/// What a component asked for, flattened for `pack build --explain-capabilities`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RequestedCapabilities {
+    pub wasi: Vec<String>,
+    pub env: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filesystem_mode: Option<String>,
+    pub filesystem_scopes: Vec<String>,
+    pub host: Vec<String>,
+    pub network_client: bool,
+    pub network_server: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LicensePolicy {
+    /// SPDX identifiers that may be shipped; omitted means anything not denied.
+    pub allow: Option<Vec<String>>,
+    /// SPDX identifiers that may never be shipped, even when also allowed.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Accept components that declare no license at all.
+    #[serde(default)]
+    pub allow_unknown: bool,
+}
+
+fn default_fs_mode() -> String {
+    "none".to_string()
+}
+
+impl CapabilityPolicy {
+    /// Load the policy at `path`, or `None` when the workspace has no policy file.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let policy = serde_yaml_bw::from_str(&raw)
+            .with_context(|| format!("invalid capability policy {}", path.display()))?;
+        Ok(Some(policy))
+    }
+
+    pub fn for_pack(&self, pack_id: &str) -> Option<&PackPolicy> {
+        self.packs.get(pack_id).or(self.default.as_ref())
+    }
+}
+
+impl RequestedCapabilities {
+    pub fn from_json(capabilities: &JsonValue) -> Self {
+        let wasi = &capabilities["wasi"];
+        let mut requested = Self::default();
+
+        let fs_mode = wasi
+            .pointer("/filesystem/mode")
+            .and_then(JsonValue::as_str)
+            .filter(|mode| *mode != "none");
+        if let Some(mode) = fs_mode {
+            requested.wasi.push("filesystem".to_string());
+            requested.filesystem_mode = Some(mode.to_string());
+            requested.filesystem_scopes = wasi
+                .pointer("/filesystem/mounts")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|mount| mount["host_class"].as_str().map(str::to_string))
+                .collect();
+        }
+        requested.env = strings(wasi.pointer("/env/allow"));
+        if !requested.env.is_empty() {
+            requested.wasi.push("env".to_string());
+        }
+        for flag in ["random", "clocks"] {
+            if wasi[flag].as_bool() == Some(true) {
+                requested.wasi.push(flag.to_string());
+            }
+        }
+
+        if let Some(host) = capabilities["host"].as_object() {
+            for (name, value) in host {
+                let detail = match value {
+                    JsonValue::Object(fields) if name == "secrets" => {
+                        let keys = fields
+                            .get("required")
+                            .and_then(JsonValue::as_array)
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|req| req.as_str().or_else(|| req["key"].as_str()))
+                            .collect::<Vec<_>>();
+                        keys.join(", ")
+                    }
+                    JsonValue::Object(fields) => fields
+                        .iter()
+                        .filter_map(|(key, value)| match value {
+                            JsonValue::Bool(true) => Some(key.clone()),
+                            JsonValue::String(text) => Some(format!("{key}={text}")),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    JsonValue::Null => continue,
+                    other => other.to_string(),
+                };
+                requested.host.push(if detail.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{name} ({detail})")
+                });
+            }
+            requested.network_client = host_flag(host, "client");
+            requested.network_server = host_flag(host, "server");
+        }
+        requested
+    }
+}
+
+fn host_flag(host: &serde_json::Map<String, JsonValue>, flag: &str) -> bool {
+    host.get("http").and_then(|http| http[flag].as_bool()) == Some(true)
+}
+
+fn strings(value: Option<&JsonValue>) -> Vec<String> {
+    value
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(str::to_string))
+        .collect()
+}
+
+fn fs_mode_rank(mode: &str) -> u8 {
+    match mode {
+        "none" => 0,
+        "read_only" => 1,
+        _ => 2,
+    }
+}
+
+/// Cross-check one component's requested capabilities against a pack policy.
+pub fn check(
+    policy: &PackPolicy,
+    component: &str,
+    requested: &RequestedCapabilities,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut deny = |message: String| {
+        violations.push(Violation {
+            component: component.to_string(),
+            message,
+        })
+    };
+
+    if let Some(allowed) = &policy.wasi {
+        for capability in &requested.wasi {
+            if !allowed.contains(capability) {
+                deny(format!("WASI capability `{capability}` is not allowed"));
+            }
+        }
+    }
+    if let Some(allowed) = &policy.env {
+        for var in &requested.env {
+            if !allowed.contains(var) {
+                deny(format!("environment variable `{var}` is not allowed"));
+            }
+        }
+    }
+    if let (Some(fs_policy), Some(mode)) = (&policy.filesystem, &requested.filesystem_mode) {
+        if fs_mode_rank(mode) > fs_mode_rank(&fs_policy.mode) {
+            deny(format!(
+                "filesystem mode `{mode}` exceeds the allowed `{}`",
+                fs_policy.mode
+            ));
+        }
+        if let Some(scopes) = &fs_policy.scopes {
+            for scope in &requested.filesystem_scopes {
+                if !scopes.contains(scope) {
+                    deny(format!("filesystem scope `{scope}` is not allowed"));
+                }
+            }
+        }
+    }
+    if let Some(network) = &policy.network {
+        if requested.network_client && !network.client {
+            deny("outbound HTTP (http.client) is not allowed".to_string());
+        }
+        if requested.network_server && !network.server {
+            deny("inbound HTTP (http.server) is not allowed".to_string());
+        }
+    }
+    violations
+}
+
+/// `pack build --explain-capabilities`: list what each component requests and any policy
+/// violations, without building.
+pub fn explain(args: &PackBuildArgs) -> Result<()> {
+    let flow = args
+        .flow
+        .as_deref()
+        .context("--explain-capabilities needs --flow")?;
+    let explanation = pack_build::explain_capabilities(
+        flow,
+        args.meta.as_deref(),
+        args.component_dir.as_deref(),
+    )?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&explanation)?);
+    } else {
+        match &explanation.policy {
+            Some(path) => println!("Pack {} (policy: {path})", explanation.pack_id),
+            None => println!("Pack {} (no capability policy)", explanation.pack_id),
+        }
+        for entry in &explanation.components {
+            let requested = &entry.requested;
+            println!(
+                "{}@{} (nodes: {})",
+                entry.component,
+                entry.version,
+                entry.nodes.join(", ")
+            );
+            println!("  wasi:       {}", list_or_none(&requested.wasi));
+            if !requested.env.is_empty() {
+                println!("  env:        {}", requested.env.join(", "));
+            }
+            if let Some(mode) = &requested.filesystem_mode {
+                println!(
+                    "  filesystem: {mode} ({})",
+                    list_or_none(&requested.filesystem_scopes)
+                );
+            }
+            println!("  host:       {}", list_or_none(&requested.host));
+            for violation in &entry.violations {
+                println!("  ✗ {violation}");
+            }
+        }
+    }
+    let violations = explanation
+        .components
+        .iter()
+        .map(|entry| entry.violations.len())
+        .sum::<usize>();
+    if violations > 0 {
+        anyhow::bail!("{violations} capability policy violation(s) in {POLICY_PATH}");
+    }
+    Ok(())
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CapabilityPolicy, RequestedCapabilities, check};
+    use serde_json::json;
+
+    fn echo_capabilities() -> RequestedCapabilities {
+        RequestedCapabilities::from_json(&json!({
+            "wasi": {
+                "filesystem": {
+                    "mode": "read_only",
+                    "mounts": [{ "name": "assets", "host_class": "assets", "guest_path": "/assets" }]
+                },
+                "env": { "allow": ["RUST_LOG"] },
+                "random": true,
+                "clocks": false
+            },
+            "host": {
+                "secrets": { "required": ["KV_API_TOKEN"] },
+                "http": { "client": true, "server": false }
+            }
+        }))
+    }
+
+    #[test]
+    fn requested_capabilities_are_flattened() {
+        let requested = echo_capabilities();
+        assert_eq!(requested.wasi, ["filesystem", "env", "random"]);
+        assert_eq!(requested.filesystem_scopes, ["assets"]);
+        assert!(requested.network_client && !requested.network_server);
+        assert!(
+            requested
+                .host
+                .contains(&"secrets (KV_API_TOKEN)".to_string())
+        );
+        assert!(requested.host.contains(&"http (client)".to_string()));
+    }
+
+    #[test]
+    fn pack_entries_override_the_default() {
+        let policy: CapabilityPolicy = serde_yaml_bw::from_str(
+            r#"
+default:
+  wasi: [random, clocks]
+packs:
+  dev.local.hello:
+    wasi: [filesystem, env, random]
+    env: [RUST_LOG]
+    filesystem:
+      mode: read_only
+      scopes: [assets]
+    network:
+      client: true
+"#,
+        )
+        .unwrap();
+
+        let requested = echo_capabilities();
+        let strict = policy.for_pack("dev.local.other").unwrap();
+        let violations = check(strict, "echo", &requested);
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].message.contains("`filesystem`"));
+
+        let relaxed = policy.for_pack("dev.local.hello").unwrap();
+        assert!(check(relaxed, "echo", &requested).is_empty());
+    }
+
+    #[test]
+    fn filesystem_and_network_limits_are_enforced() {
+        let policy: CapabilityPolicy =
+            serde_yaml_bw::from_str("default:\n  filesystem:\n    scopes: [data]\n  network: {}\n")
+                .unwrap();
+        let violations = check(
+            policy.for_pack("any").unwrap(),
+            "echo",
+            &echo_capabilities(),
+        );
+        let messages = violations
+            .iter()
+            .map(|v| v.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "filesystem mode `read_only` exceeds the allowed `none`",
+                "filesystem scope `assets` is not allowed",
+                "outbound HTTP (http.client) is not allowed",
+            ]
+        );
+    }
+}
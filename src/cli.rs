@@ -8,6 +8,12 @@ use clap::{Arg, ArgAction, Args, CommandFactory, Parser, Subcommand};
 #[command(version)]
 #[command(about = "cli.root.about")]
 pub struct Cli {
+    /// cli.option.lock_timeout
+    #[arg(long = "lock-timeout", value_name = "SECS")]
+    pub lock_timeout: Option<u64>,
+    /// cli.option.events
+    #[arg(long = "events", value_name = "SINK")]
+    pub events: Option<crate::events::EventSink>,
     #[command(subcommand)]
     pub command: Command,
 }
@@ -38,7 +44,13 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
                 .global(true)
                 .value_name("LOCALE")
                 .help(crate::i18n::t(locale, "cli.option.locale")),
-        );
+        )
+        .mut_arg("lock_timeout", |arg| {
+            arg.help(crate::i18n::t(locale, "cli.option.lock_timeout"))
+        })
+        .mut_arg("events", |arg| {
+            arg.help(crate::i18n::t(locale, "cli.option.events"))
+        });
 
     for (name, key) in [
         ("flow", "cli.command.flow.about"),
@@ -51,10 +63,21 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
         ("mcp", "cli.command.mcp.about"),
         ("gui", "cli.command.gui.about"),
         ("secrets", "cli.command.secrets.about"),
+        ("keys", "cli.command.keys.about"),
+        ("org", "cli.command.org.about"),
         ("tools", "cli.command.tools.about"),
         ("install", "cli.command.install.about"),
         ("release", "cli.command.release.about"),
         ("cbor", "cli.command.cbor.about"),
+        ("schema", "cli.command.schema.about"),
+        ("registry", "cli.command.registry.about"),
+        ("runs", "cli.command.runs.about"),
+        ("check", "cli.command.check.about"),
+        ("watch", "cli.command.watch.about"),
+        ("outdated", "cli.command.outdated.about"),
+        ("doctor", "cli.command.doctor.about"),
+        ("explain", "cli.command.explain.about"),
+        ("undo", "cli.command.undo.about"),
         ("wizard", "cli.command.wizard.about"),
     ] {
         command = command.mut_subcommand(name, |sub| sub.about(crate::i18n::t(locale, key)));
@@ -74,6 +97,65 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
                         ))
                     })
             })
+            .mut_subcommand("seed", |sub| {
+                sub.about(crate::i18n::t(locale, "cli.command.secrets.seed.about"))
+                    .mut_subcommand("init", |sub| {
+                        let mut sub = sub.about(crate::i18n::t(
+                            locale,
+                            "cli.command.secrets.seed.init.about",
+                        ));
+                        for (arg, key) in [
+                            ("pack", "cli.command.secrets.seed.init.pack"),
+                            ("environment", "cli.command.secrets.seed.init.environment"),
+                            ("tenant", "cli.command.secrets.seed.init.tenant"),
+                            ("team", "cli.command.secrets.seed.init.team"),
+                            ("out", "cli.command.secrets.seed.init.out"),
+                            ("force", "cli.command.secrets.seed.init.force"),
+                        ] {
+                            sub = sub.mut_arg(arg, |arg| arg.help(crate::i18n::t(locale, key)));
+                        }
+                        sub
+                    })
+                    .mut_subcommand("validate", |sub| {
+                        sub.about(crate::i18n::t(
+                            locale,
+                            "cli.command.secrets.seed.validate.about",
+                        ))
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.secrets.seed.validate.file",
+                            ))
+                        })
+                        .mut_arg("pack", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.secrets.seed.validate.pack",
+                            ))
+                        })
+                    })
+            })
+            .mut_subcommand("set", |sub| {
+                sub.about(crate::i18n::t(locale, "cli.command.secrets.set.about"))
+                    .mut_arg("key", |arg| {
+                        arg.help(crate::i18n::t(locale, "cli.command.secrets.set.key"))
+                    })
+                    .mut_arg("stdin", |arg| {
+                        arg.help(crate::i18n::t(locale, "cli.command.secrets.set.stdin"))
+                    })
+            })
+            .mut_subcommand("get", |sub| {
+                sub.about(crate::i18n::t(locale, "cli.command.secrets.get.about"))
+                    .mut_arg("key", |arg| {
+                        arg.help(crate::i18n::t(locale, "cli.command.secrets.key"))
+                    })
+            })
+            .mut_subcommand("rm", |sub| {
+                sub.about(crate::i18n::t(locale, "cli.command.secrets.rm.about"))
+                    .mut_arg("key", |arg| {
+                        arg.help(crate::i18n::t(locale, "cli.command.secrets.key"))
+                    })
+            })
     });
     command = command
         .mut_subcommand("config", |sub| {
@@ -90,6 +172,33 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
                             arg.help(crate::i18n::t(locale, "cli.command.config.set.file"))
                         })
                 })
+                .mut_subcommand("get", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.config.get.about"))
+                        .mut_arg("key", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.get.key"))
+                        })
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.get.file"))
+                        })
+                })
+                .mut_subcommand("list", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.config.list.about"))
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.list.file"))
+                        })
+                        .mut_arg("json", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.list.json"))
+                        })
+                })
+                .mut_subcommand("unset", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.config.unset.about"))
+                        .mut_arg("key", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.unset.key"))
+                        })
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.config.unset.file"))
+                        })
+                })
         })
         .mut_subcommand("mcp", |sub| {
             sub.about(crate::i18n::t(locale, "cli.command.mcp.about"))
@@ -98,9 +207,27 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
                         .mut_arg("provider", |arg| {
                             arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.provider"))
                         })
+                        .mut_arg("providers", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.providers"))
+                        })
+                        .mut_arg("all", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.all"))
+                        })
+                        .mut_arg("jobs", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.jobs"))
+                        })
                         .mut_arg("json", |arg| {
                             arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.json"))
                         })
+                        .mut_arg("probe", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.mcp.doctor.probe"))
+                        })
+                        .mut_arg("probe_timeout", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.mcp.doctor.probe_timeout",
+                            ))
+                        })
                 })
         })
         .mut_subcommand("tools", |sub| {
@@ -244,6 +371,147 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
                     arg.help(crate::i18n::t(locale, "cli.command.cbor.path"))
                 })
         })
+        .mut_subcommand("schema", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.schema.about"))
+                .mut_subcommand("export", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.schema.export.about"))
+                        .mut_arg("flows", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.schema.export.flows"))
+                        })
+                        .mut_arg("components", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.schema.export.components",
+                            ))
+                        })
+                        .mut_arg("pack_meta", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.schema.export.pack_meta",
+                            ))
+                        })
+                        .mut_arg("component_dir", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.schema.export.component_dir",
+                            ))
+                        })
+                        .mut_arg("out", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.schema.export.out"))
+                        })
+                })
+        })
+        .mut_subcommand("registry", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.registry.about"))
+                .mut_subcommand("export", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.registry.export.about"))
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.registry.export.file"))
+                        })
+                        .mut_arg("component_dir", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.registry.export.component_dir",
+                            ))
+                        })
+                })
+                .mut_subcommand("import", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.registry.import.about"))
+                        .mut_arg("file", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.registry.import.file"))
+                        })
+                        .mut_arg("replace", |arg| {
+                            arg.help(crate::i18n::t(
+                                locale,
+                                "cli.command.registry.import.replace",
+                            ))
+                        })
+                })
+        })
+        .mut_subcommand("runs", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.runs.about"))
+                .mut_subcommand("list", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.runs.list.about"))
+                        .mut_arg("limit", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.list.limit"))
+                        })
+                        .mut_arg("json", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.json"))
+                        })
+                })
+                .mut_subcommand("show", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.runs.show.about"))
+                        .mut_arg("id", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.show.id"))
+                        })
+                        .mut_arg("json", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.json"))
+                        })
+                })
+                .mut_subcommand("diff", |sub| {
+                    sub.about(crate::i18n::t(locale, "cli.command.runs.diff.about"))
+                        .mut_arg("a", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.diff.a"))
+                        })
+                        .mut_arg("b", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.diff.b"))
+                        })
+                        .mut_arg("json", |arg| {
+                            arg.help(crate::i18n::t(locale, "cli.command.runs.json"))
+                        })
+                })
+        })
+        .mut_subcommand("check", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.check.about"))
+                .mut_arg("secrets_env", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.check.secrets_env"))
+                })
+                .mut_arg("json", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.check.json"))
+                })
+        })
+        .mut_subcommand("outdated", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.outdated.about"))
+                .mut_arg("profile", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.outdated.profile"))
+                })
+                .mut_arg("update", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.outdated.update"))
+                })
+                .mut_arg("json", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.outdated.json"))
+                })
+        })
+        .mut_subcommand("doctor", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.doctor.about"))
+                .mut_arg("json", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.doctor.json"))
+                })
+        })
+        .mut_subcommand("explain", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.explain.about"))
+                .mut_arg("code", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.explain.code"))
+                })
+                .mut_arg("list", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.explain.list"))
+                })
+        })
+        .mut_subcommand("undo", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.undo.about"))
+                .mut_arg("list", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.undo.list"))
+                })
+        })
+        .mut_subcommand("watch", |sub| {
+            sub.about(crate::i18n::t(locale, "cli.command.watch.about"))
+                .mut_arg("config", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.watch.config"))
+                })
+                .mut_arg("initial", |arg| {
+                    arg.help(crate::i18n::t(locale, "cli.command.watch.initial"))
+                })
+        })
         .mut_subcommand("coverage", |sub| {
             sub.about(crate::i18n::t(locale, "cli.command.coverage.about"))
                 .mut_arg("skip_run", |arg| {
@@ -363,6 +631,42 @@ pub fn localized_help_command(locale: &str) -> clap::Command {
     localize_help_tree(command, locale, true)
 }
 
+// Localize a command tree whose about/help strings are `cli.*` catalog keys. Used for the
+// built-in subcommands that are parsed from passthrough arguments (e.g. `pack events publish`)
+// rather than through `Cli`.
+pub fn localized_builtin_command(command: clap::Command, locale: &str) -> clap::Command {
+    localize_help_tree(translate_catalog_keys(command, locale), locale, false)
+}
+
+fn translate_catalog_keys(mut command: clap::Command, locale: &str) -> clap::Command {
+    if let Some(key) = command
+        .get_about()
+        .map(|about| about.to_string())
+        .filter(|about| about.starts_with("cli."))
+    {
+        command = command.about(crate::i18n::t(locale, &key));
+    }
+    let arg_keys = command
+        .get_arguments()
+        .filter_map(|arg| {
+            let help = arg.get_help()?.to_string();
+            help.starts_with("cli.")
+                .then(|| (arg.get_id().as_str().to_string(), help))
+        })
+        .collect::<Vec<_>>();
+    for (id, key) in arg_keys {
+        command = command.mut_arg(id, |arg| arg.help(crate::i18n::t(locale, &key)));
+    }
+    let sub_names = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect::<Vec<_>>();
+    for name in sub_names {
+        command = command.mut_subcommand(name, |sub| translate_catalog_keys(sub, locale));
+    }
+    command
+}
+
 fn localize_help_tree(mut command: clap::Command, locale: &str, is_root: bool) -> clap::Command {
     command = command
         .disable_help_subcommand(true)
@@ -426,6 +730,12 @@ pub enum Command {
     /// cli.command.secrets.about
     #[command(subcommand)]
     Secrets(SecretsCommand),
+    /// cli.command.keys.about
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    /// cli.command.org.about
+    #[command(subcommand)]
+    Org(OrgCommand),
     /// cli.command.tools.about
     #[command(subcommand)]
     Tools(ToolsCommand),
@@ -436,6 +746,27 @@ pub enum Command {
     Release(ReleaseCommand),
     /// cli.command.cbor.about
     Cbor(CborArgs),
+    /// cli.command.schema.about
+    #[command(subcommand)]
+    Schema(SchemaCommand),
+    /// cli.command.registry.about
+    #[command(subcommand)]
+    Registry(RegistryCommand),
+    /// cli.command.runs.about
+    #[command(subcommand)]
+    Runs(RunsCommand),
+    /// cli.command.check.about
+    Check(CheckArgs),
+    /// cli.command.watch.about
+    Watch(WatchArgs),
+    /// cli.command.outdated.about
+    Outdated(OutdatedArgs),
+    /// cli.command.doctor.about
+    Doctor(DoctorArgs),
+    /// cli.command.explain.about
+    Explain(ExplainArgs),
+    /// cli.command.undo.about
+    Undo(UndoArgs),
     /// cli.command.wizard.about
     Wizard(Box<WizardCommand>),
 }
@@ -461,16 +792,38 @@ pub enum McpCommand {
 #[derive(Args, Debug)]
 pub struct McpDoctorArgs {
     /// cli.command.mcp.doctor.provider
-    pub provider: String,
+    #[arg(required_unless_present_any = ["providers", "all"], conflicts_with_all = ["providers", "all"])]
+    pub provider: Option<String>,
+    /// cli.command.mcp.doctor.providers
+    #[arg(long = "providers", value_name = "FILE", conflicts_with = "all")]
+    pub providers: Option<PathBuf>,
+    /// cli.command.mcp.doctor.all
+    #[arg(long = "all")]
+    pub all: bool,
+    /// cli.command.mcp.doctor.jobs
+    #[arg(long = "jobs", short = 'j', value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
     /// cli.command.mcp.doctor.json
     #[arg(long = "json")]
     pub json: bool,
+    /// cli.command.mcp.doctor.probe
+    #[arg(long = "probe")]
+    pub probe: bool,
+    /// cli.command.mcp.doctor.probe_timeout
+    #[arg(long = "probe-timeout", value_name = "SECS", default_value_t = 10)]
+    pub probe_timeout: u64,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommand {
     /// cli.command.config.set.about
     Set(ConfigSetArgs),
+    /// cli.command.config.get.about
+    Get(ConfigGetArgs),
+    /// cli.command.config.list.about
+    List(ConfigListArgs),
+    /// cli.command.config.unset.about
+    Unset(ConfigUnsetArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -665,6 +1018,34 @@ pub struct ConfigSetArgs {
     pub file: Option<PathBuf>,
 }
 
+#[derive(Args, Debug)]
+pub struct ConfigGetArgs {
+    /// cli.command.config.get.key
+    pub key: String,
+    /// cli.command.config.get.file
+    #[arg(long = "file")]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigListArgs {
+    /// cli.command.config.list.file
+    #[arg(long = "file")]
+    pub file: Option<PathBuf>,
+    /// cli.command.config.list.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigUnsetArgs {
+    /// cli.command.config.unset.key
+    pub key: String,
+    /// cli.command.config.unset.file
+    #[arg(long = "file")]
+    pub file: Option<PathBuf>,
+}
+
 #[derive(Args, Debug)]
 pub struct CborArgs {
     /// cli.command.cbor.path
@@ -672,6 +1053,229 @@ pub struct CborArgs {
     pub path: PathBuf,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum SchemaCommand {
+    /// cli.command.schema.export.about
+    Export(SchemaExportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SchemaExportArgs {
+    /// cli.command.schema.export.flows
+    #[arg(long = "flows")]
+    pub flows: bool,
+    /// cli.command.schema.export.components
+    #[arg(long = "components")]
+    pub components: bool,
+    /// cli.command.schema.export.pack_meta
+    #[arg(long = "pack-meta")]
+    pub pack_meta: bool,
+    /// cli.command.schema.export.component_dir
+    #[arg(long = "component-dir", default_value = "components")]
+    pub component_dir: PathBuf,
+    /// cli.command.schema.export.out
+    #[arg(long = "out")]
+    pub out: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RegistryCommand {
+    /// cli.command.registry.export.about
+    Export(RegistryExportArgs),
+    /// cli.command.registry.import.about
+    Import(RegistryImportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RegistryExportArgs {
+    /// cli.command.registry.export.file
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+    /// cli.command.registry.export.component_dir
+    #[arg(long = "component-dir", default_value = "components")]
+    pub component_dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct RegistryImportArgs {
+    /// cli.command.registry.import.file
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+    /// cli.command.registry.import.replace
+    #[arg(long = "replace")]
+    pub replace: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RunsCommand {
+    /// cli.command.runs.list.about
+    List(RunsListArgs),
+    /// cli.command.runs.show.about
+    Show(RunsShowArgs),
+    /// cli.command.runs.diff.about
+    Diff(RunsDiffArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RunsListArgs {
+    /// cli.command.runs.list.limit
+    #[arg(long = "limit", default_value_t = 20)]
+    pub limit: usize,
+    /// cli.command.runs.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RunsShowArgs {
+    /// cli.command.runs.show.id
+    pub id: u64,
+    /// cli.command.runs.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RunsDiffArgs {
+    /// cli.command.runs.diff.a
+    pub a: u64,
+    /// cli.command.runs.diff.b
+    pub b: u64,
+    /// cli.command.runs.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KeysCommand {
+    /// cli.command.keys.generate.about
+    Generate(KeysGenerateArgs),
+    /// cli.command.keys.list.about
+    List(KeysListArgs),
+    /// cli.command.keys.export.about
+    Export(KeysExportArgs),
+    /// cli.command.keys.import.about
+    Import(KeysImportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct KeysGenerateArgs {
+    /// cli.command.keys.name
+    pub name: String,
+    /// cli.command.keys.keystore
+    #[arg(long = "keystore")]
+    pub keystore: bool,
+    /// cli.command.keys.generate.subject
+    #[arg(long = "subject", value_name = "CN")]
+    pub subject: Option<String>,
+    /// cli.command.keys.force
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct KeysListArgs {
+    /// cli.command.keys.list.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct KeysExportArgs {
+    /// cli.command.keys.name
+    pub name: String,
+    /// cli.command.keys.export.out
+    #[arg(long = "out", value_name = "FILE")]
+    pub out: PathBuf,
+    /// cli.command.keys.export.public
+    #[arg(long = "public")]
+    pub public: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct KeysImportArgs {
+    /// cli.command.keys.import.file
+    pub file: PathBuf,
+    /// cli.command.keys.import.name
+    #[arg(long = "name")]
+    pub name: Option<String>,
+    /// cli.command.keys.keystore
+    #[arg(long = "keystore")]
+    pub keystore: bool,
+    /// cli.command.keys.force
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OrgCommand {
+    /// cli.command.org.sync.about
+    Sync(OrgSyncArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct OrgSyncArgs {
+    /// cli.command.org.sync.source
+    pub source: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// cli.command.check.secrets_env
+    #[arg(long = "secrets-env", value_name = "FILE")]
+    pub secrets_env: Option<PathBuf>,
+    /// cli.command.check.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct OutdatedArgs {
+    /// cli.command.outdated.profile
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+    /// cli.command.outdated.update
+    #[arg(long = "update")]
+    pub update: bool,
+    /// cli.command.outdated.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// cli.command.doctor.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct UndoArgs {
+    /// cli.command.undo.list
+    #[arg(long = "list")]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// cli.command.explain.code
+    #[arg(value_name = "CODE")]
+    pub code: Option<String>,
+    /// cli.command.explain.list
+    #[arg(long = "list", conflicts_with = "code")]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// cli.command.watch.config
+    #[arg(long = "config", default_value = "dev.yaml")]
+    pub config: PathBuf,
+    /// cli.command.watch.initial
+    #[arg(long = "initial")]
+    pub initial: bool,
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct CoverageArgs {
     /// cli.command.coverage.skip_run
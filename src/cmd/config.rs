@@ -1,16 +1,290 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::cli::{ConfigCommand, ConfigSetArgs};
+use crate::cli::{ConfigCommand, ConfigGetArgs, ConfigListArgs, ConfigSetArgs, ConfigUnsetArgs};
 use anyhow::{Context, Result, anyhow, bail};
-use toml_edit::{DocumentMut, Item, Table, value};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item, Table, TableLike, value};
 
-use crate::config;
+use crate::config::{self, ConfigLocation, ConfigSource};
+
+/// Environment variables that override a config key for every command.
+const ENV_OVERRIDES: &[(&str, &str)] = &[(
+    "distributor.default_profile",
+    "GREENTIC_DISTRIBUTOR_PROFILE",
+)];
 
 pub fn run(command: ConfigCommand) -> Result<()> {
     match command {
         ConfigCommand::Set(args) => set_value(&args),
+        ConfigCommand::Get(args) => get_value(&args),
+        ConfigCommand::List(args) => list_values(&args),
+        ConfigCommand::Unset(args) => unset_value(&args),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigFile {
+    path: PathBuf,
+    source: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigEntry {
+    key: String,
+    value: JsonValue,
+    source: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigListing {
+    file: Option<ConfigFile>,
+    /// Existing config files that lose to `file` in the search order.
+    shadowed: Vec<ConfigFile>,
+    /// Organization defaults (`org sync`) under `file`; its entries have source `org`.
+    org_layer: Option<PathBuf>,
+    entries: Vec<ConfigEntry>,
+}
+
+/// The config file commands read: `--file`, else the one `config::load_with_meta` would load.
+fn effective_file(file: Option<&Path>) -> Option<ConfigFile> {
+    if let Some(path) = file {
+        return Some(ConfigFile {
+            path: path.to_path_buf(),
+            source: "--file".to_string(),
+        });
+    }
+    let resolution = config::resolve_config_path(None);
+    let path = resolution.selected?;
+    let source = match resolution.forced {
+        Some(ConfigSource::Env(var)) => format!("env {var}"),
+        Some(ConfigSource::Arg) => "--file".to_string(),
+        None => location_label(&path),
+    };
+    Some(ConfigFile { path, source })
+}
+
+fn location_label(path: &Path) -> String {
+    let location = config::discovery_candidates()
+        .into_iter()
+        .find(|(candidate, _)| candidate == path)
+        .map(|(_, location)| location);
+    match location {
+        Some(ConfigLocation::Xdg) => "xdg",
+        Some(ConfigLocation::Legacy) | None => "legacy",
+    }
+    .to_string()
+}
+
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn get_value(args: &ConfigGetArgs) -> Result<()> {
+    let segments = key_segments(&args.key)?;
+    if args.file.is_none()
+        && let Some((_, value)) = env_override(&args.key)
+    {
+        println!("{value}");
+        return Ok(());
+    }
+    let file = effective_file(args.file.as_deref());
+    let layers = file
+        .iter()
+        .map(|file| file.path.clone())
+        .chain(org_layer(args.file.as_deref()));
+    for path in layers {
+        let table = read_table(&path)?;
+        let Some(value) = lookup(&table, &segments) else {
+            continue;
+        };
+        match value {
+            toml::Value::String(text) => println!("{text}"),
+            toml::Value::Table(table) => print!("{}", toml::to_string(table)?),
+            other => println!("{other}"),
+        }
+        return Ok(());
+    }
+    match file {
+        Some(file) => bail!("`{}` is not set in {}", args.key, file.path.display()),
+        None => bail!("`{}` is not set (no config file found)", args.key),
+    }
+}
+
+/// The organization defaults layer under the config file, unless `--file` picks a single file.
+fn org_layer(file: Option<&Path>) -> Option<PathBuf> {
+    if file.is_some() {
+        return None;
+    }
+    config::org_layer_path().filter(|path| path.is_file())
+}
+
+fn lookup<'a>(table: &'a toml::Table, segments: &[&str]) -> Option<&'a toml::Value> {
+    let (first, rest) = segments.split_first()?;
+    let value = table.get(*first)?;
+    if rest.is_empty() {
+        return Some(value);
+    }
+    lookup(value.as_table()?, rest)
+}
+
+fn env_override(key: &str) -> Option<(&'static str, String)> {
+    ENV_OVERRIDES
+        .iter()
+        .find(|(overridden, _)| *overridden == key)
+        .and_then(|(_, var)| {
+            std::env::var(var)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(|value| (*var, value))
+        })
+}
+
+fn list_values(args: &ConfigListArgs) -> Result<()> {
+    let file = effective_file(args.file.as_deref());
+    let shadowed = if args.file.is_none() && config::resolve_config_path(None).forced.is_none() {
+        config::discovery_candidates()
+            .into_iter()
+            .filter(|(path, _)| path.exists())
+            .filter(|(path, _)| file.as_ref().is_none_or(|file| &file.path != path))
+            .map(|(path, location)| ConfigFile {
+                source: match location {
+                    ConfigLocation::Xdg => "xdg",
+                    ConfigLocation::Legacy => "legacy",
+                }
+                .to_string(),
+                path,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let org_layer = org_layer(args.file.as_deref());
+    let mut entries = Vec::new();
+    if let Some(path) = &org_layer {
+        flatten(&read_table(path)?, "", "org", &mut entries);
+    }
+    if let Some(file) = &file {
+        let mut local = Vec::new();
+        flatten(&read_table(&file.path)?, "", &file.source, &mut local);
+        entries.retain(|entry| !local.iter().any(|local| local.key == entry.key));
+        entries.extend(local);
+    }
+    if args.file.is_none() {
+        for (key, _) in ENV_OVERRIDES {
+            if let Some((var, value)) = env_override(key) {
+                entries.retain(|entry| entry.key != *key);
+                entries.push(ConfigEntry {
+                    key: key.to_string(),
+                    value: JsonValue::String(value),
+                    source: format!("env {var}"),
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    let listing = ConfigListing {
+        file,
+        shadowed,
+        org_layer,
+        entries,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&listing)?);
+        return Ok(());
+    }
+    match &listing.file {
+        Some(file) => println!("Config file: {} ({})", file.path.display(), file.source),
+        None => println!("No config file found"),
+    }
+    if let Some(path) = &listing.org_layer {
+        println!("Org layer:   {}", path.display());
+    }
+    for file in &listing.shadowed {
+        println!("Ignored:     {} ({})", file.path.display(), file.source);
+    }
+    for entry in &listing.entries {
+        println!("{} = {}  [{}]", entry.key, entry.value, entry.source);
+    }
+    Ok(())
+}
+
+/// Flatten nested tables into dotted keys. Tokens are masked unless they are `env:` references.
+fn flatten(table: &toml::Table, prefix: &str, source: &str, out: &mut Vec<ConfigEntry>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::Table(child) => flatten(child, &path, source, out),
+            toml::Value::String(token) if key == "token" && !token.starts_with("env:") => {
+                out.push(ConfigEntry {
+                    key: path,
+                    value: JsonValue::String("****".to_string()),
+                    source: source.to_string(),
+                })
+            }
+            other => out.push(ConfigEntry {
+                key: path,
+                value: serde_json::to_value(other).unwrap_or(JsonValue::Null),
+                source: source.to_string(),
+            }),
+        }
+    }
+}
+
+fn unset_value(args: &ConfigUnsetArgs) -> Result<()> {
+    let segments = key_segments(&args.key)?;
+    let path = effective_file(args.file.as_deref())
+        .map(|file| file.path)
+        .ok_or_else(|| anyhow!("`{}` is not set (no config file found)", args.key))?;
+    let raw =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut doc = raw
+        .parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    if remove_key(doc.as_table_mut(), &segments).is_none() {
+        bail!("`{}` is not set in {}", args.key, path.display());
+    }
+    fs::write(&path, doc.to_string())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    println!("Removed {} from {}", args.key, path.display());
+    if let Some((var, _)) = env_override(&args.key) {
+        println!("note: {var} still overrides {}", args.key);
+    }
+    Ok(())
+}
+
+/// Remove the key at `segments`, dropping tables the removal leaves empty.
+fn remove_key(table: &mut dyn TableLike, segments: &[&str]) -> Option<Item> {
+    let (first, rest) = segments.split_first()?;
+    if rest.is_empty() {
+        return table.remove(first);
+    }
+    let child = table.get_mut(first)?.as_table_like_mut()?;
+    let removed = remove_key(child, rest)?;
+    if child.is_empty() {
+        table.remove(first);
+    }
+    Some(removed)
+}
+
+fn key_segments(key: &str) -> Result<Vec<&str>> {
+    let segments = key
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    if segments.is_empty() {
+        bail!("config key cannot be empty");
     }
+    Ok(segments)
 }
 
 fn set_value(args: &ConfigSetArgs) -> Result<()> {
@@ -53,13 +327,7 @@ fn ensure_parent(path: &Path) -> Result<()> {
 }
 
 fn apply_key(doc: &mut DocumentMut, key: &str, value_str: &str) -> Result<()> {
-    let segments = key
-        .split('.')
-        .filter(|segment| !segment.is_empty())
-        .collect::<Vec<_>>();
-    if segments.is_empty() {
-        bail!("config key cannot be empty");
-    }
+    let segments = key_segments(key)?;
 
     let mut current = doc.as_table_mut();
     for segment in &segments[..segments.len() - 1] {
@@ -117,4 +385,76 @@ org = "ai.greentic"
         let written = fs::read_to_string(path).unwrap();
         assert!(written.contains("template = \"rust-wasi\""));
     }
+
+    #[test]
+    fn unset_drops_tables_left_empty() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+[defaults.component]
+org = "ai.greentic"
+
+[distributor]
+default_profile = "staging"
+"#,
+        )
+        .unwrap();
+
+        unset_value(&ConfigUnsetArgs {
+            key: "defaults.component.org".into(),
+            file: Some(path.clone()),
+        })
+        .unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("defaults"), "{written}");
+        assert!(written.contains("default_profile = \"staging\""));
+
+        let missing = unset_value(&ConfigUnsetArgs {
+            key: "defaults.component.org".into(),
+            file: Some(path),
+        });
+        assert!(missing.unwrap_err().to_string().contains("is not set"));
+    }
+
+    #[test]
+    fn listing_flattens_keys_and_masks_tokens() {
+        let table: toml::Table = toml::from_str(
+            r#"
+[distributor.profiles.staging]
+url = "https://distributor.example"
+token = "secret"
+
+[distributor.profiles.prod]
+token = "env:PROD_TOKEN"
+"#,
+        )
+        .unwrap();
+        let mut entries = Vec::new();
+        flatten(&table, "", "xdg", &mut entries);
+        let find = |key: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.value.clone())
+        };
+        assert_eq!(
+            find("distributor.profiles.staging.url"),
+            Some(JsonValue::from("https://distributor.example"))
+        );
+        assert_eq!(
+            find("distributor.profiles.staging.token"),
+            Some(JsonValue::from("****"))
+        );
+        assert_eq!(
+            find("distributor.profiles.prod.token"),
+            Some(JsonValue::from("env:PROD_TOKEN"))
+        );
+        assert_eq!(
+            lookup(&table, &["distributor", "profiles", "staging", "url"])
+                .and_then(toml::Value::as_str),
+            Some("https://distributor.example")
+        );
+    }
 }
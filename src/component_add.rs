@@ -4,7 +4,6 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context, Result, anyhow, bail};
-use bytes::Bytes;
 use greentic_distributor_client::{
     DistributorClient, DistributorClientConfig, DistributorEnvironmentId, HttpDistributorClient,
     ResolveComponentRequest,
@@ -14,13 +13,15 @@ use greentic_types::{EnvId, TenantCtx, TenantId};
 use reqwest::blocking::Client;
 use semver::Version;
 use serde_json::json;
-use tokio::runtime::Runtime;
 
 use crate::config;
 use crate::distributor;
+use crate::download::{self, DownloadOptions};
 use crate::pack_init::{
-    PackInitIntent, WorkspaceComponent, WorkspaceManifest, manifest_path, slugify,
+    PackInitIntent, WorkspaceComponent, edit_workspace_manifest, manifest_path, slugify,
 };
+use crate::runtime;
+use crate::state_lock::{StateLock, write_atomic};
 
 #[derive(Debug, Clone)]
 struct SimpleStub {
@@ -72,13 +73,15 @@ pub fn run_component_add(
         };
 
         let client = http_client(&profile)?;
-        let rt = Runtime::new().context("failed to start tokio runtime for distributor client")?;
-        rt.block_on(client.resolve_component(req))?
+        runtime::block_on(async { Ok(client.resolve_component(req).await?) })?
     };
 
-    let artifact_bytes = fetch_artifact(&response.artifact)?;
-    let (cache_dir, cache_path) =
-        write_component_to_cache(&component_id, &version_req, &artifact_bytes)?;
+    let (cache_dir, cache_path) = write_component_to_cache(
+        &component_id,
+        &version_req,
+        &response.artifact,
+        &response.digest.0,
+    )?;
     update_manifest(coordinate, &component_id, &version_req, &cache_path)?;
 
     println!(
@@ -130,27 +133,31 @@ fn http_client(profile: &distributor::DistributorProfile) -> Result<HttpDistribu
     HttpDistributorClient::new(cfg).map_err(Into::into)
 }
 
-fn fetch_artifact(location: &greentic_distributor_client::ArtifactLocation) -> Result<Bytes> {
+/// Write the artifact at `location` to `dest`. HTTP artifacts are downloaded resumably and
+/// checked against `digest`.
+fn fetch_artifact(
+    location: &greentic_distributor_client::ArtifactLocation,
+    digest: &str,
+    dest: &Path,
+) -> Result<()> {
     match location {
         greentic_distributor_client::ArtifactLocation::FilePath { path } => {
             if path.starts_with("http://") || path.starts_with("https://") {
                 let client = Client::new();
-                let resp = client
-                    .get(path)
-                    .send()
-                    .context("failed to download artifact")?;
-                if !resp.status().is_success() {
-                    bail!("artifact download failed with status {}", resp.status());
-                }
-                resp.bytes().map_err(Into::into)
-            } else if let Some(rest) = path.strip_prefix("file://") {
-                fs::read(rest)
-                    .map(Bytes::from)
-                    .with_context(|| format!("failed to read component at {}", rest))
+                let digest = Some(digest).filter(|digest| !digest.is_empty());
+                download::fetch(
+                    || client.get(path),
+                    dest,
+                    digest,
+                    &DownloadOptions::default(),
+                )
+                .context("failed to download artifact")?;
+                Ok(())
             } else {
-                fs::read(path)
-                    .map(Bytes::from)
-                    .with_context(|| format!("failed to read component at {}", path))
+                let source = path.strip_prefix("file://").unwrap_or(path);
+                let bytes = fs::read(source)
+                    .with_context(|| format!("failed to read component at {}", source))?;
+                write_atomic(dest, &bytes)
             }
         }
         greentic_distributor_client::ArtifactLocation::OciReference { reference } => {
@@ -165,15 +172,16 @@ fn fetch_artifact(location: &greentic_distributor_client::ArtifactLocation) -> R
 fn write_component_to_cache(
     component_id: &str,
     version: &str,
-    bytes: &Bytes,
+    location: &greentic_distributor_client::ArtifactLocation,
+    digest: &str,
 ) -> Result<(PathBuf, PathBuf)> {
     let mut path = cache_base_dir()?;
+    let _lock = StateLock::acquire(&path)?;
     let slug = cache_slug_parts(component_id, version);
     path.push(slug);
     fs::create_dir_all(&path).with_context(|| format!("failed to create {}", path.display()))?;
     let file_path = path.join("artifact.wasm");
-    fs::write(&file_path, bytes)
-        .with_context(|| format!("failed to write {}", file_path.display()))?;
+    fetch_artifact(location, digest, &file_path)?;
     Ok((path, file_path))
 }
 
@@ -196,16 +204,6 @@ fn update_manifest(
     version: &str,
     wasm_path: &Path,
 ) -> Result<()> {
-    let manifest_path = manifest_path()?;
-    let mut manifest: WorkspaceManifest = if manifest_path.exists() {
-        let data = fs::read_to_string(&manifest_path)
-            .with_context(|| format!("failed to read {}", manifest_path.display()))?;
-        serde_json::from_str(&data)
-            .with_context(|| format!("failed to parse {}", manifest_path.display()))?
-    } else {
-        WorkspaceManifest::default()
-    };
-
     let entry = WorkspaceComponent {
         coordinate: coordinate.to_string(),
         entry: ComponentEntry {
@@ -220,23 +218,20 @@ fn update_manifest(
         },
     };
 
-    let mut replaced = false;
-    for existing in manifest.components.iter_mut() {
-        if existing.entry.name == entry.entry.name {
-            *existing = entry.clone();
-            replaced = true;
-            break;
+    edit_workspace_manifest(|manifest| {
+        let mut replaced = false;
+        for existing in manifest.components.iter_mut() {
+            if existing.entry.name == entry.entry.name {
+                *existing = entry.clone();
+                replaced = true;
+                break;
+            }
         }
-    }
-    if !replaced {
-        manifest.components.push(entry);
-    }
-
-    let rendered =
-        serde_json::to_string_pretty(&manifest).context("failed to render workspace manifest")?;
-    fs::write(&manifest_path, rendered)
-        .with_context(|| format!("failed to write {}", manifest_path.display()))?;
-    Ok(())
+        if !replaced {
+            manifest.components.push(entry);
+        }
+        Ok(())
+    })
 }
 
 fn detect_pack_id() -> Option<String> {
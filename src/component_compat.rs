@@ -0,0 +1,378 @@
+//! `component compat`: compare a component's actual imports and exports with a target world, and
+//! check every component of a pack against the worlds the runner loads.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde::Serialize;
+use wit_component::{DecodedWasm, decode as decode_component};
+use wit_parser::{Resolve, WorldId, WorldItem};
+
+use crate::component_manifest_cli::ComponentCompatArgs;
+
+/// Worlds greentic-runner loads components for: node components of both ABI generations, and
+/// describe-only components the runner only asks for their schemas.
+pub const RUNNER_WORLDS: &[&str] = &[
+    "greentic:component/component@0.4.0",
+    "greentic:component/component@0.6.0",
+    "greentic:component/component@1.0.0",
+];
+
+/// WIT packages of the worlds greentic-dev knows without `--wit`, dependencies first.
+const BUILTIN_WIT: &[(&str, &str)] = &[
+    (
+        "greentic-component-0.4.0.wit",
+        include_str!("../templates/compat/greentic-component-0.4.0/package.wit"),
+    ),
+    (
+        "greentic-types-core-0.6.0.wit",
+        include_str!("../templates/scaffold/wit/deps/greentic-types-core-0.6.0/package.wit"),
+    ),
+    (
+        "greentic-component-0.6.0.wit",
+        include_str!("../templates/scaffold/wit/deps/greentic-component-0.6.0/package.wit"),
+    ),
+    (
+        "greentic-component-1.0.0.wit",
+        include_str!("../templates/compat/greentic-component-1.0.0/package.wit"),
+    ),
+];
+
+/// How a component lines up with a world. Imports from `wasi:` packages are left out: the runner
+/// host provides WASI whatever the world says.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Compat {
+    pub world: String,
+    /// Exports the world requires that the component does not provide.
+    pub missing_exports: Vec<String>,
+    /// Imports the component needs that the world does not provide.
+    pub extra_imports: Vec<String>,
+    /// Exports the component provides beyond the world; harmless, but unused.
+    pub extra_exports: Vec<String>,
+}
+
+impl Compat {
+    pub fn compatible(&self) -> bool {
+        self.missing_exports.is_empty() && self.extra_imports.is_empty()
+    }
+}
+
+/// The interfaces and functions a world imports and exports, by name.
+#[derive(Debug, Default)]
+struct Surface {
+    imports: BTreeSet<String>,
+    exports: BTreeSet<String>,
+}
+
+impl Surface {
+    fn of(resolve: &Resolve, world: WorldId) -> Self {
+        let world = &resolve.worlds[world];
+        let names = |items: &wit_parser::IndexMap<wit_parser::WorldKey, WorldItem>| {
+            items
+                .iter()
+                .filter(|(_, item)| !matches!(item, WorldItem::Type { .. }))
+                .map(|(key, _)| resolve.name_world_key(key))
+                .collect()
+        };
+        Self {
+            imports: names(&world.imports),
+            exports: names(&world.exports),
+        }
+    }
+
+    fn check(&self, target: &Surface, world: &str) -> Compat {
+        let without = |ours: &BTreeSet<String>, theirs: &BTreeSet<String>| {
+            ours.difference(theirs).cloned().collect::<Vec<_>>()
+        };
+        Compat {
+            world: world.to_string(),
+            missing_exports: without(&target.exports, &self.exports),
+            extra_imports: without(&self.imports, &target.imports)
+                .into_iter()
+                .filter(|name| !name.starts_with("wasi:"))
+                .collect(),
+            extra_exports: without(&self.exports, &target.exports),
+        }
+    }
+}
+
+/// Decode a component's own world from its bytes.
+fn component_surface(wasm: &[u8]) -> Result<Surface> {
+    match decode_component(wasm).context("failed to decode component")? {
+        DecodedWasm::Component(resolve, world) => Ok(Surface::of(&resolve, world)),
+        DecodedWasm::WitPackage(_, _) => {
+            bail!("expected a component artifact but found a WIT package bundle")
+        }
+    }
+}
+
+/// Resolve `world` (`<namespace>:<package>/<world>@<version>`) from the WIT under `wit`, or from
+/// the built-in WIT when `wit` is `None`.
+fn world_surface(world: &str, wit: Option<&Path>) -> Result<Surface> {
+    let mut resolve = Resolve::default();
+    let packages = match wit {
+        Some(dir) => {
+            let (package, _) = resolve
+                .push_dir(dir)
+                .with_context(|| format!("failed to parse WIT in {}", dir.display()))?;
+            vec![package]
+        }
+        None => BUILTIN_WIT
+            .iter()
+            .map(|(path, source)| resolve.push_str(path, source))
+            .collect::<Result<Vec<_>>>()
+            .context("built-in WIT does not parse")?,
+    };
+    let id = resolve
+        .select_world(&packages, Some(world))
+        .map_err(|err| {
+            let hint = match wit {
+                Some(dir) => format!("in {}", dir.display()),
+                None => format!(
+                    "among the built-in worlds ({}); pass --wit <DIR> for others",
+                    RUNNER_WORLDS.join(", ")
+                ),
+            };
+            anyhow!("world `{world}` not found {hint}: {err:#}")
+        })?;
+    Ok(Surface::of(&resolve, id))
+}
+
+pub fn check(wasm: &[u8], world: &str, wit: Option<&Path>) -> Result<Compat> {
+    Ok(component_surface(wasm)?.check(&world_surface(world, wit)?, world))
+}
+
+/// What a built-in `world` requires a component to export.
+pub(crate) fn world_exports(world: &str) -> Result<BTreeSet<String>> {
+    Ok(world_surface(world, None)?.exports)
+}
+
+/// One row of the pack matrix: a component and how it fares against each runner world.
+#[derive(Debug, Serialize)]
+pub struct MatrixRow {
+    pub component: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_world: Option<String>,
+    pub worlds: Vec<Compat>,
+}
+
+impl MatrixRow {
+    pub fn compatible(&self) -> bool {
+        self.worlds.iter().any(Compat::compatible)
+    }
+}
+
+pub fn matrix(pack: &Path) -> Result<Vec<MatrixRow>> {
+    let load = open_pack(pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("failed to open {}: {}", pack.display(), err.message))?;
+    let targets = RUNNER_WORLDS
+        .iter()
+        .map(|world| Ok((*world, world_surface(world, None)?)))
+        .collect::<Result<Vec<_>>>()?;
+    load.manifest
+        .components
+        .iter()
+        .map(|component| {
+            let wasm = load.files.get(&component.file_wasm).ok_or_else(|| {
+                anyhow!(
+                    "{} lists {} but the pack does not contain it",
+                    component.name,
+                    component.file_wasm
+                )
+            })?;
+            let surface =
+                component_surface(wasm).with_context(|| format!("component {}", component.name))?;
+            Ok(MatrixRow {
+                component: component.name.clone(),
+                declared_world: component.world.clone(),
+                worlds: targets
+                    .iter()
+                    .map(|(world, target)| surface.check(target, world))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+pub fn run(args: &ComponentCompatArgs) -> Result<()> {
+    match (&args.pack, &args.wasm) {
+        (Some(pack), _) => run_matrix(pack, args.json),
+        (None, Some(wasm)) => {
+            let bytes =
+                fs::read(wasm).with_context(|| format!("failed to read {}", wasm.display()))?;
+            let compat = check(&bytes, &args.world, args.wit.as_deref())?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&compat)?);
+            } else {
+                print!("{}", describe(&wasm.display().to_string(), &compat));
+            }
+            if !compat.compatible() {
+                bail!("{} is not compatible with {}", wasm.display(), args.world);
+            }
+            Ok(())
+        }
+        (None, None) => bail!("pass a component .wasm or --pack <PACK>"),
+    }
+}
+
+fn describe(name: &str, compat: &Compat) -> String {
+    let mut out = format!(
+        "{name} vs {}: {}\n",
+        compat.world,
+        if compat.compatible() {
+            "compatible"
+        } else {
+            "incompatible"
+        }
+    );
+    for (label, names) in [
+        ("missing export", &compat.missing_exports),
+        ("extra import", &compat.extra_imports),
+        ("extra export", &compat.extra_exports),
+    ] {
+        for name in names {
+            out.push_str(&format!("  {label}: {name}\n"));
+        }
+    }
+    out
+}
+
+fn run_matrix(pack: &Path, json: bool) -> Result<()> {
+    let rows = matrix(pack)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        let width = rows
+            .iter()
+            .map(|row| row.component.len())
+            .chain(std::iter::once("component".len()))
+            .max()
+            .unwrap_or_default();
+        let mut header = format!("{:<width$}", "component");
+        for world in RUNNER_WORLDS {
+            header.push_str(&format!("  {world:<34}"));
+        }
+        println!("{}", header.trim_end());
+        for row in &rows {
+            let mut line = format!("{:<width$}", row.component);
+            for compat in &row.worlds {
+                let cell = if compat.compatible() {
+                    "ok".to_string()
+                } else {
+                    [
+                        (compat.missing_exports.len(), "missing export"),
+                        (compat.extra_imports.len(), "extra import"),
+                    ]
+                    .into_iter()
+                    .filter(|(count, _)| *count > 0)
+                    .map(|(count, label)| {
+                        format!("{count} {label}{}", if count == 1 { "" } else { "s" })
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                };
+                line.push_str(&format!("  {cell:<34}"));
+            }
+            println!("{}", line.trim_end());
+        }
+        println!();
+        for row in &rows {
+            for compat in row.worlds.iter().filter(|compat| !compat.compatible()) {
+                print!("{}", describe(&row.component, compat));
+            }
+        }
+    }
+    let unsupported = rows
+        .iter()
+        .filter(|row| !row.compatible())
+        .map(|row| row.component.as_str())
+        .collect::<Vec<_>>();
+    if !unsupported.is_empty() {
+        bail!(
+            "no runner world fits {}: {}",
+            if unsupported.len() == 1 {
+                "this component"
+            } else {
+                "these components"
+            },
+            unsupported.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wit_component::{ComponentEncoder, StringEncoding, dummy_module, embed_component_metadata};
+    use wit_parser::ManglingAndAbi;
+
+    /// A component built from `wit` (a `test:demo` package with a `demo` world), with the
+    /// built-in WIT available to it.
+    fn component(wit: &str) -> Vec<u8> {
+        let mut resolve = Resolve::default();
+        for (path, source) in BUILTIN_WIT {
+            resolve.push_str(path, source).unwrap();
+        }
+        let package = resolve.push_str("demo.wit", wit).unwrap();
+        let world = resolve.select_world(&[package], Some("demo")).unwrap();
+        let mut module = dummy_module(&resolve, world, ManglingAndAbi::Standard32);
+        embed_component_metadata(&mut module, &resolve, world, StringEncoding::UTF8).unwrap();
+        ComponentEncoder::default()
+            .module(&module)
+            .unwrap()
+            .validate(true)
+            .encode()
+            .unwrap()
+    }
+
+    #[test]
+    fn a_node_component_matches_the_current_world() {
+        let wasm = component(
+            "package test:demo;\nworld demo { include greentic:component/component@0.6.0; }\n",
+        );
+        let compat = check(&wasm, "greentic:component/component@0.6.0", None).unwrap();
+        assert!(compat.compatible(), "{compat:?}");
+        assert!(compat.extra_exports.is_empty(), "{compat:?}");
+
+        let compat = check(&wasm, "greentic:component/component@1.0.0", None).unwrap();
+        assert!(!compat.compatible());
+        assert_eq!(
+            compat.missing_exports,
+            ["greentic:component/describe-v1@1.0.0"]
+        );
+        assert!(
+            compat
+                .extra_exports
+                .contains(&"greentic:component/node@0.6.0".to_string()),
+            "{compat:?}"
+        );
+    }
+
+    #[test]
+    fn imports_the_world_does_not_offer_are_reported() {
+        let wasm = component(
+            "package test:demo;\n\
+             interface clock { now: func() -> u64; }\n\
+             world demo { import clock; export greentic:component/describe-v1@1.0.0; }\n",
+        );
+        let compat = check(&wasm, "greentic:component/component@1.0.0", None).unwrap();
+        assert_eq!(compat.extra_imports, ["test:demo/clock"]);
+        assert!(compat.missing_exports.is_empty());
+        assert!(!compat.compatible());
+    }
+
+    #[test]
+    fn unknown_worlds_point_at_wit() {
+        let wasm = component(
+            "package test:demo;\nworld demo { include greentic:component/component@0.6.0; }\n",
+        );
+        let err = check(&wasm, "greentic:component/component@0.5.0", None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--wit"), "{err}");
+    }
+}
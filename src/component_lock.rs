@@ -0,0 +1,241 @@
+//! `greentic-dev.lock`: the version and wasm hash of every component a local pack build resolved,
+//! so a build on another machine (or in CI) fails instead of silently packing different artifacts.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::component_resolver::ResolvedNode;
+use crate::events;
+use crate::state_lock::{StateLock, write_atomic};
+
+/// Lockfile location, relative to the workspace root.
+pub const LOCK_PATH: &str = "greentic-dev.lock";
+const LOCK_VERSION: u32 = 1;
+const LOCK_HEADER: &str = "# Generated by greentic-dev pack builds. Update it with `greentic-dev pack build --update-lock`.\n";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentLock {
+    pub version: u32,
+    /// Pinned components keyed by component id.
+    #[serde(default, rename = "component")]
+    pub components: BTreeMap<String, LockedComponent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LockedComponent {
+    pub version: String,
+    /// `blake3:<hex>` digest of the component wasm.
+    pub hash: String,
+}
+
+/// A component that resolved differently from its lock entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockDrift {
+    pub component: String,
+    pub locked: LockedComponent,
+    pub resolved: LockedComponent,
+}
+
+impl fmt::Display for LockDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.locked.version != self.resolved.version {
+            write!(
+                f,
+                "{}: locked at {}, resolved {}",
+                self.component, self.locked.version, self.resolved.version
+            )
+        } else {
+            write!(
+                f,
+                "{} {}: wasm hash changed from {} to {}",
+                self.component, self.resolved.version, self.locked.hash, self.resolved.hash
+            )
+        }
+    }
+}
+
+impl Default for ComponentLock {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION,
+            components: BTreeMap::new(),
+        }
+    }
+}
+
+impl ComponentLock {
+    /// Read the lockfile, or `None` when there is none yet.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        let lock: Self =
+            toml::from_str(&raw).with_context(|| format!("invalid {}", path.display()))?;
+        if lock.version != LOCK_VERSION {
+            bail!(
+                "{} has version {}; this greentic-dev understands version {LOCK_VERSION}",
+                path.display(),
+                lock.version
+            );
+        }
+        Ok(Some(lock))
+    }
+
+    fn render(&self) -> Result<String> {
+        Ok(format!("{LOCK_HEADER}{}", toml::to_string(self)?))
+    }
+
+    /// Pin every resolved component, returning the ones whose existing pin differs. Components
+    /// the lock does not mention yet are added without being reported.
+    pub fn reconcile(&mut self, resolved: BTreeMap<String, LockedComponent>) -> Vec<LockDrift> {
+        let mut drift = Vec::new();
+        for (component, entry) in resolved {
+            if let Some(locked) = self.components.get(&component)
+                && *locked != entry
+            {
+                drift.push(LockDrift {
+                    component: component.clone(),
+                    locked: locked.clone(),
+                    resolved: entry.clone(),
+                });
+            }
+            self.components.insert(component, entry);
+        }
+        drift
+    }
+}
+
+/// Check a build's resolved components against `greentic-dev.lock` and pin new ones. A component
+/// that resolved to another version or wasm fails the build, unless `update` is set, in which case
+/// the lock is rewritten with the new resolution and each change is printed as a warning.
+pub fn apply(nodes: &[ResolvedNode], update: bool) -> Result<()> {
+    let resolved = nodes
+        .iter()
+        .map(|node| {
+            (
+                node.component.name.clone(),
+                LockedComponent {
+                    version: node.component.version.to_string(),
+                    hash: node.component.wasm_hash.clone(),
+                },
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    if resolved.is_empty() {
+        return Ok(());
+    }
+
+    let _lock = StateLock::acquire(Path::new(".greentic"))?;
+    let path = Path::new(LOCK_PATH);
+    let existing = ComponentLock::load(path)?;
+    let mut lock = existing.clone().unwrap_or_default();
+    let drift = lock.reconcile(resolved);
+    if !drift.is_empty() {
+        let details = drift
+            .iter()
+            .map(|drift| format!("  - {drift}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !update {
+            bail!(
+                "component resolution differs from {LOCK_PATH}:\n{details}\n(rebuild with `--update-lock` to accept the new resolution)"
+            );
+        }
+        events::warn(format_args!("updating {LOCK_PATH}:\n{details}"));
+    }
+    if existing.as_ref() != Some(&lock) {
+        write_atomic(path, lock.render()?)?;
+    }
+    Ok(())
+}
+
+/// Drop the lock entry of `component` when its version does not satisfy `requirement`, so the
+/// next build pins the new resolution instead of failing on it. Returns the dropped version.
+pub fn release(component: &str, requirement: &VersionReq) -> Result<Option<String>> {
+    let path = Path::new(LOCK_PATH);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let _lock = StateLock::acquire(Path::new(".greentic"))?;
+    let Some(mut lock) = ComponentLock::load(path)? else {
+        return Ok(None);
+    };
+    let satisfied = lock.components.get(component).is_none_or(|locked| {
+        Version::parse(&locked.version).is_ok_and(|version| requirement.matches(&version))
+    });
+    if satisfied {
+        return Ok(None);
+    }
+    let released = lock
+        .components
+        .remove(component)
+        .map(|locked| locked.version);
+    write_atomic(path, lock.render()?)?;
+    Ok(released)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentLock, LockedComponent};
+    use std::collections::BTreeMap;
+
+    fn entry(version: &str, hash: &str) -> LockedComponent {
+        LockedComponent {
+            version: version.to_string(),
+            hash: format!("blake3:{hash}"),
+        }
+    }
+
+    #[test]
+    fn reconcile_pins_new_components_and_reports_drift() {
+        let mut lock = ComponentLock::default();
+        lock.components
+            .insert("dev.greentic.echo".to_string(), entry("0.1.0", "aa"));
+        lock.components
+            .insert("dev.greentic.http".to_string(), entry("1.0.0", "bb"));
+
+        let drift = lock.reconcile(BTreeMap::from([
+            ("dev.greentic.echo".to_string(), entry("0.1.0", "cc")),
+            ("dev.greentic.http".to_string(), entry("1.0.0", "bb")),
+            ("dev.greentic.new".to_string(), entry("0.2.0", "dd")),
+        ]));
+
+        assert_eq!(drift.len(), 1);
+        assert_eq!(
+            drift[0].to_string(),
+            "dev.greentic.echo 0.1.0: wasm hash changed from blake3:aa to blake3:cc"
+        );
+        assert_eq!(lock.components["dev.greentic.echo"], entry("0.1.0", "cc"));
+        assert!(lock.components.contains_key("dev.greentic.new"));
+    }
+
+    #[test]
+    fn lock_round_trips_through_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("greentic-dev.lock");
+        let mut lock = ComponentLock::default();
+        lock.components
+            .insert("dev.greentic.echo".to_string(), entry("0.1.0", "aa"));
+        std::fs::write(&path, lock.render().unwrap()).unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("[component.\"dev.greentic.echo\"]"), "{raw}");
+        assert_eq!(ComponentLock::load(&path).unwrap(), Some(lock));
+        assert_eq!(
+            ComponentLock::load(&dir.path().join("missing.lock")).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,534 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result, anyhow, bail};
+use jsonschema::Validator;
+use jsonschema::error::{TypeKind, ValidationErrorKind};
+use serde_json::{Map, Value as JsonValue, json};
+
+use crate::component_manifest_cli::{ComponentDoctorFixArgs, ComponentHashUpdateArgs};
+use crate::passthrough::{resolve_binary, run_passthrough};
+use crate::state_lock;
+use crate::util::files;
+
+const MANIFEST_FILE: &str = "component.manifest.json";
+
+/// One correction applied to `component.manifest.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEdit {
+    pub field: String,
+    pub change: String,
+}
+
+/// Locate the manifest for a component directory, manifest path or wasm path.
+pub fn manifest_path(target: &Path) -> PathBuf {
+    if target.is_dir() {
+        return target.join(MANIFEST_FILE);
+    }
+    match target.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => target.to_path_buf(),
+        _ => target.with_file_name(MANIFEST_FILE),
+    }
+}
+
+/// One place where a manifest departs from the `ComponentManifest` JSON Schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSchemaIssue {
+    /// JSON pointer to the offending value; empty for the manifest root.
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ManifestSchemaIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let field = if self.field.is_empty() {
+            "/"
+        } else {
+            &self.field
+        };
+        write!(f, "`{field}`: {}", self.message)
+    }
+}
+
+/// Check a manifest against the `ComponentManifest` JSON Schema shipped with greentic-component.
+pub fn schema_issues(manifest: &JsonValue) -> Vec<ManifestSchemaIssue> {
+    static SCHEMA: OnceLock<Validator> = OnceLock::new();
+    let validator = SCHEMA.get_or_init(|| {
+        let schema: JsonValue = serde_json::from_str(greentic_component::manifest::schema())
+            .expect("component manifest schema is JSON");
+        jsonschema::validator_for(&schema).expect("component manifest schema compiles")
+    });
+    validator
+        .iter_errors(manifest)
+        .map(|error| {
+            let message = match error.kind() {
+                ValidationErrorKind::Type { kind } => {
+                    let expected = match kind {
+                        TypeKind::Single(ty) => ty.to_string(),
+                        TypeKind::Multiple(set) => set
+                            .iter()
+                            .map(|ty| ty.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" or "),
+                    };
+                    format!(
+                        "expected {expected}, found {}",
+                        json_type_name(error.instance())
+                    )
+                }
+                ValidationErrorKind::Required { property } => {
+                    format!("missing required field {property}")
+                }
+                _ => error.to_string(),
+            };
+            ManifestSchemaIssue {
+                field: error.instance_path().to_string(),
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Read a manifest and fail with every schema issue, naming the file and field of each.
+pub fn validate_manifest_file(path: &Path) -> Result<()> {
+    let manifest = read_manifest(path)?;
+    let issues = schema_issues(&manifest);
+    if issues.is_empty() {
+        return Ok(());
+    }
+    let mut message = format!(
+        "{} does not match the component manifest schema:",
+        path.display()
+    );
+    for issue in issues {
+        message.push_str(&format!("\n  {issue}"));
+    }
+    bail!(message)
+}
+
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+pub fn read_manifest(path: &Path) -> Result<JsonValue> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let manifest: JsonValue = serde_json::from_str(&raw)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    if !manifest.is_object() {
+        bail!("{} must contain a JSON object", path.display());
+    }
+    Ok(manifest)
+}
+
+/// Write the manifest through a sibling temp file and a rename so readers never see a partial
+/// file.
+pub fn write_manifest(path: &Path, manifest: &JsonValue) -> Result<()> {
+    let mut contents = serde_json::to_string_pretty(manifest)?;
+    contents.push('\n');
+    state_lock::write_atomic(path, contents)
+}
+
+pub fn blake3_digest(path: &Path) -> Result<String> {
+    Ok(format!("blake3:{}", files::blake3_file(path)?))
+}
+
+/// `component doctor --fix`: correct the fixable manifest issues, then re-run the checks.
+pub fn doctor_fix(args: &ComponentDoctorFixArgs) -> Result<()> {
+    let path = manifest_path(&args.target);
+    let mut manifest = read_manifest(&path)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let edits = fix_manifest(&mut manifest, dir)?;
+
+    if edits.is_empty() {
+        println!("No fixable issues in {}", path.display());
+    } else {
+        write_manifest(&path, &manifest)?;
+        println!("Fixed {} issue(s) in {}:", edits.len(), path.display());
+        for edit in &edits {
+            println!("  - {}: {}", edit.field, edit.change);
+        }
+    }
+
+    let raw = fs::read_to_string(&path)?;
+    if let Err(err) = greentic_component::manifest::validate_manifest(&raw) {
+        bail!(
+            "{} still fails validation (not auto-fixable): {err}",
+            path.display()
+        );
+    }
+    println!("✓ manifest validates");
+
+    // Re-run the full doctor when greentic-component is installed.
+    match resolve_binary("greentic-component") {
+        Ok(bin) => {
+            let doctor_args = ["doctor".into(), args.target.clone().into_os_string()];
+            let status = run_passthrough(&bin, &doctor_args, false)?;
+            if !status.success() {
+                bail!("component doctor still reports issues");
+            }
+        }
+        Err(_) => {
+            println!("greentic-component is not installed; skipped the full doctor run");
+        }
+    }
+    Ok(())
+}
+
+/// `component hash update`: recompute the blake3 digest of every file listed under `artifacts`
+/// and store it under the same key in `hashes`.
+pub fn update_hashes(args: &ComponentHashUpdateArgs) -> Result<()> {
+    let path = manifest_path(&args.dir);
+    let mut manifest = read_manifest(&path)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let edits = sync_hashes(&mut manifest, dir)?;
+    if edits.is_empty() {
+        println!("Hashes in {} are up to date", path.display());
+        return Ok(());
+    }
+    write_manifest(&path, &manifest)?;
+    println!("Updated {} hash(es) in {}:", edits.len(), path.display());
+    for edit in &edits {
+        println!("  - {}: {}", edit.field, edit.change);
+    }
+    Ok(())
+}
+
+/// Bring `hashes` in line with the artifacts on disk. Every listed artifact must exist.
+pub fn sync_hashes(manifest: &mut JsonValue, dir: &Path) -> Result<Vec<ManifestEdit>> {
+    let map = object(manifest);
+    let artifacts = map
+        .get("artifacts")
+        .and_then(JsonValue::as_object)
+        .ok_or_else(|| anyhow!("manifest has no `artifacts` section"))?
+        .iter()
+        .filter_map(|(key, value)| value.as_str().map(|file| (key.clone(), file.to_string())))
+        .collect::<Vec<_>>();
+
+    let mut edits = Vec::new();
+    for (key, file) in artifacts {
+        let artifact = dir.join(&file);
+        if !artifact.is_file() {
+            bail!(
+                "artifact `{key}` ({}) does not exist; build the component first",
+                artifact.display()
+            );
+        }
+        let digest = blake3_digest(&artifact)?;
+        let hashes = map.entry("hashes").or_insert_with(|| json!({}));
+        if !hashes.is_object() {
+            *hashes = json!({});
+        }
+        let change = match hashes.get(&key).and_then(JsonValue::as_str) {
+            Some(old) if old == digest => continue,
+            Some(old) => format!("{old} -> {digest}"),
+            None => format!("set to {digest}"),
+        };
+        hashes[&key] = json!(digest);
+        edits.push(ManifestEdit {
+            field: format!("hashes.{key}"),
+            change,
+        });
+    }
+    Ok(edits)
+}
+
+fn object(manifest: &mut JsonValue) -> &mut Map<String, JsonValue> {
+    manifest.as_object_mut().expect("manifest is an object")
+}
+
+fn is_blank(value: Option<&JsonValue>) -> bool {
+    match value {
+        None | Some(JsonValue::Null) => true,
+        Some(JsonValue::String(text)) => text.trim().is_empty(),
+        Some(JsonValue::Array(items)) => items.is_empty(),
+        Some(JsonValue::Object(map)) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Apply every fix the manifest needs; ids, versions and worlds are never guessed.
+pub fn fix_manifest(manifest: &mut JsonValue, dir: &Path) -> Result<Vec<ManifestEdit>> {
+    let mut edits = Vec::new();
+    let mut edit = |field: &str, change: String| {
+        edits.push(ManifestEdit {
+            field: field.to_string(),
+            change,
+        })
+    };
+    let map = object(manifest);
+
+    if is_blank(map.get("name"))
+        && let Some(id) = map
+            .get("id")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    {
+        map.insert("name".into(), json!(id));
+        edit("name", format!("set to the component id `{id}`"));
+    }
+    if is_blank(map.get("supports")) {
+        map.insert("supports".into(), json!(["messaging"]));
+        edit("supports", "defaulted to [\"messaging\"]".to_string());
+    }
+    if !map.contains_key("capabilities") {
+        map.insert("capabilities".into(), json!({ "wasi": {}, "host": {} }));
+        edit("capabilities", "added an empty capability set".to_string());
+    }
+    if !map.contains_key("operations") {
+        map.insert("operations".into(), json!([]));
+        edit("operations", "added an empty operation list".to_string());
+    }
+
+    let wasm = map
+        .get("artifacts")
+        .and_then(|artifacts| artifacts.get("component_wasm"))
+        .and_then(JsonValue::as_str)
+        .map(str::to_string);
+    let wasm = match wasm {
+        Some(wasm) => Some(wasm),
+        None => match single_file_with_extension(dir, "wasm")? {
+            Some(found) => {
+                map.insert("artifacts".into(), json!({ "component_wasm": found }));
+                edit("artifacts.component_wasm", format!("set to `{found}`"));
+                Some(found)
+            }
+            None => None,
+        },
+    };
+    if let Some(wasm) = &wasm {
+        let wasm_path = dir.join(wasm);
+        if wasm_path.is_file() {
+            let digest = blake3_digest(&wasm_path)?;
+            let recorded = map
+                .get("hashes")
+                .and_then(|hashes| hashes.get("component_wasm"))
+                .and_then(JsonValue::as_str);
+            if recorded != Some(digest.as_str()) {
+                let change = match recorded {
+                    Some(old) => format!("updated stale hash {old} -> {digest}"),
+                    None => format!("set to {digest}"),
+                };
+                let hashes = map.entry("hashes").or_insert_with(|| json!({}));
+                if !hashes.is_object() {
+                    *hashes = json!({});
+                }
+                hashes["component_wasm"] = json!(digest);
+                edit("hashes.component_wasm", change);
+            }
+        }
+    }
+
+    // `describe_export` names the `<export>.describe.json` payload shipped next to the wasm.
+    let describe_export = map
+        .get("describe_export")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if !dir
+        .join(format!("{describe_export}.describe.json"))
+        .is_file()
+        && let Some(found) = single_file_with_extension(dir, "describe.json")?
+    {
+        let export = found.trim_end_matches(".describe.json").to_string();
+        map.insert("describe_export".into(), json!(export));
+        edit(
+            "describe_export",
+            if describe_export.is_empty() {
+                format!("set to `{export}`")
+            } else {
+                format!("`{describe_export}` has no describe payload; changed to `{export}`")
+            },
+        );
+    }
+
+    let has_default_flow = map
+        .get("dev_flows")
+        .and_then(|flows| flows.get("default"))
+        .is_some();
+    if !has_default_flow
+        && let Some(id) = map
+            .get("id")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    {
+        let operation = map
+            .get("default_operation")
+            .and_then(JsonValue::as_str)
+            .or_else(|| {
+                map.get("operations")
+                    .and_then(|ops| ops.get(0))
+                    .and_then(|op| op.get("name"))
+                    .and_then(JsonValue::as_str)
+            })
+            .unwrap_or("run")
+            .to_string();
+        let flow = default_dev_flow(&id, &operation);
+        let flows = map.entry("dev_flows").or_insert_with(|| json!({}));
+        if !flows.is_object() {
+            *flows = json!({});
+        }
+        flows["default"] = flow;
+        edit(
+            "dev_flows.default",
+            format!("added a default config flow emitting `{operation}`"),
+        );
+    }
+
+    Ok(edits)
+}
+
+// Minimal component-config flow that emits a node invoking the component with empty input.
+pub(crate) fn default_dev_flow(component_id: &str, operation: &str) -> JsonValue {
+    let node_id = component_id.rsplit('.').next().unwrap_or(component_id);
+    let template = json!({
+        "node_id": node_id,
+        "node": {
+            "component.exec": { "component": component_id, "op": operation, "input": {} },
+            "routing": [{ "to": "NEXT_NODE_PLACEHOLDER" }]
+        }
+    });
+    json!({
+        "format": "flow-ir-json",
+        "graph": {
+            "schema_version": 1,
+            "id": "component.default",
+            "type": "component-config",
+            "start": "emit_config",
+            "nodes": { "emit_config": { "template": template.to_string() } },
+            "edges": []
+        }
+    })
+}
+
+fn single_file_with_extension(dir: &Path, suffix: &str) -> Result<Option<String>> {
+    let mut matches = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| name.ends_with(&format!(".{suffix}")))
+        .collect::<Vec<_>>();
+    Ok(if matches.len() == 1 {
+        matches.pop()
+    } else {
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blake3_digest, fix_manifest, schema_issues, sync_hashes};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn fixes_hashes_describe_export_and_missing_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("component.wasm"), b"\0asm").unwrap();
+        fs::write(dir.path().join("describe.describe.json"), "{}").unwrap();
+        let mut manifest = json!({
+            "id": "acme.echo",
+            "version": "0.1.0",
+            "world": "greentic:component/component@0.4.0",
+            "describe_export": "get-manifest",
+            "operations": [{ "name": "echo", "input_schema": {}, "output_schema": {} }],
+            "artifacts": { "component_wasm": "component.wasm" },
+            "hashes": { "component_wasm": "blake3:00" }
+        });
+
+        let edits = fix_manifest(&mut manifest, dir.path()).unwrap();
+        let fields = edits.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                "name",
+                "supports",
+                "capabilities",
+                "hashes.component_wasm",
+                "describe_export",
+                "dev_flows.default"
+            ]
+        );
+        assert_eq!(
+            manifest["hashes"]["component_wasm"],
+            blake3_digest(&dir.path().join("component.wasm")).unwrap()
+        );
+        assert_eq!(manifest["describe_export"], "describe");
+        let template =
+            manifest["dev_flows"]["default"]["graph"]["nodes"]["emit_config"]["template"]
+                .as_str()
+                .unwrap();
+        assert!(template.contains("\"op\":\"echo\""));
+
+        // A second pass finds nothing left to fix.
+        assert!(fix_manifest(&mut manifest, dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn identity_fields_are_not_guessed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manifest = json!({ "name": "x", "supports": ["messaging"] });
+        let edits = fix_manifest(&mut manifest, dir.path()).unwrap();
+        assert!(manifest.get("id").is_none() && manifest.get("version").is_none());
+        assert!(edits.iter().all(|e| e.field != "dev_flows.default"));
+    }
+
+    #[test]
+    fn hashes_follow_every_listed_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("component.wasm"), b"\0asm").unwrap();
+        fs::write(dir.path().join("schema.json"), "{}").unwrap();
+        let wasm_digest = blake3_digest(&dir.path().join("component.wasm")).unwrap();
+        let mut manifest = json!({
+            "artifacts": { "component_wasm": "component.wasm", "schema": "schema.json" },
+            "hashes": { "component_wasm": wasm_digest }
+        });
+
+        let edits = sync_hashes(&mut manifest, dir.path()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].field, "hashes.schema");
+        assert_eq!(
+            manifest["hashes"]["schema"],
+            blake3_digest(&dir.path().join("schema.json")).unwrap()
+        );
+
+        fs::write(dir.path().join("component.wasm"), b"\0asm rebuilt").unwrap();
+        let edits = sync_hashes(&mut manifest, dir.path()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0].change.starts_with(&wasm_digest));
+
+        fs::remove_file(dir.path().join("schema.json")).unwrap();
+        let err = sync_hashes(&mut manifest, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("artifact `schema`"));
+    }
+
+    #[test]
+    fn schema_issues_name_the_field_and_expected_type() {
+        let issues = schema_issues(&json!({ "id": "acme.echo", "version": 1 }));
+        let version = issues
+            .iter()
+            .find(|issue| issue.field == "/version")
+            .expect("version issue");
+        assert_eq!(version.message, "expected string, found integer");
+        assert!(
+            issues
+                .iter()
+                .any(|issue| issue.field.is_empty() && issue.message.contains("\"world\"")),
+            "{issues:?}"
+        );
+        assert_eq!(
+            version.to_string(),
+            "`/version`: expected string, found integer"
+        );
+    }
+}
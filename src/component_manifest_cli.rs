@@ -0,0 +1,247 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+
+use crate::{component_compat, component_manifest, component_pin, component_scaffold};
+
+// Component subcommands implemented by greentic-dev itself. Everything else under `component`
+// is delegated to `greentic-component`.
+#[derive(Parser, Debug)]
+#[command(name = "component", bin_name = "greentic-dev component")]
+#[command(about = "cli.command.component.about")]
+struct ComponentManifestCli {
+    #[command(subcommand)]
+    command: ComponentCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ComponentCommand {
+    /// cli.command.component.compat.about
+    Compat(ComponentCompatArgs),
+    /// cli.command.component.doctor_fix.about
+    Doctor(ComponentDoctorFixArgs),
+    /// cli.command.component.hash.about
+    Hash {
+        #[command(subcommand)]
+        command: ComponentHashCommand,
+    },
+    /// cli.command.component.pin.about
+    Pin(ComponentPinArgs),
+    /// cli.command.component.scaffold.about
+    Scaffold(ComponentScaffoldArgs),
+    /// cli.command.component.unpin.about
+    Unpin(ComponentUnpinArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ComponentHashCommand {
+    /// cli.command.component.hash.update.about
+    Update(ComponentHashUpdateArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentCompatArgs {
+    /// cli.command.component.compat.wasm
+    #[arg(required_unless_present = "pack")]
+    pub wasm: Option<PathBuf>,
+    /// cli.command.component.compat.world
+    #[arg(long = "world", default_value = component_scaffold::WORLD)]
+    pub world: String,
+    /// cli.command.component.compat.wit
+    #[arg(long = "wit", value_name = "DIR")]
+    pub wit: Option<PathBuf>,
+    /// cli.command.component.compat.pack
+    #[arg(long = "pack", conflicts_with_all = ["wasm", "world", "wit"])]
+    pub pack: Option<PathBuf>,
+    /// cli.command.component.compat.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentDoctorFixArgs {
+    /// cli.command.component.doctor_fix.fix
+    #[arg(long = "fix", required = true)]
+    pub fix: bool,
+    /// cli.command.component.doctor_fix.target
+    #[arg(default_value = ".")]
+    pub target: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentHashUpdateArgs {
+    /// cli.command.component.hash.update.dir
+    #[arg(default_value = ".")]
+    pub dir: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentPinArgs {
+    /// cli.command.component.pin.name
+    pub name: String,
+    /// cli.command.component.pin.version
+    pub version: String,
+    /// cli.command.component.pin.flow
+    #[arg(long = "flow", value_name = "FLOW", required = true)]
+    pub flows: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentUnpinArgs {
+    /// cli.command.component.unpin.name
+    pub name: String,
+    /// cli.command.component.unpin.flow
+    #[arg(long = "flow", value_name = "FLOW", required = true)]
+    pub flows: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ComponentScaffoldArgs {
+    /// cli.command.component.scaffold.name
+    pub name: String,
+    /// cli.command.component.scaffold.lang
+    #[arg(long = "lang", value_enum, default_value_t = ScaffoldLanguage::Rust)]
+    pub lang: ScaffoldLanguage,
+    /// cli.command.component.scaffold.id
+    #[arg(long = "id")]
+    pub id: Option<String>,
+    /// cli.command.component.scaffold.dir
+    #[arg(long = "dir", default_value = "components")]
+    pub dir: PathBuf,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaffoldLanguage {
+    Rust,
+    Tinygo,
+    Js,
+}
+
+// Parse `component` passthrough arguments into a built-in command when greentic-dev implements
+// it. Returns `None` for anything that should keep delegating to greentic-component.
+pub fn parse_builtin(args: &[OsString], locale: &str) -> Option<ComponentCommand> {
+    if !is_builtin(args) {
+        return None;
+    }
+    let argv = std::iter::once(OsString::from("component")).chain(args.iter().cloned());
+    let command = crate::cli::localized_builtin_command(ComponentManifestCli::command(), locale);
+    let matches = command
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|err| err.exit());
+    let cli = ComponentManifestCli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    Some(cli.command)
+}
+
+// Plain `doctor` and `hash <manifest>` stay with greentic-component; only `compat`,
+// `doctor --fix`, `hash update`, `pin`, `scaffold` and `unpin` are handled here.
+fn is_builtin(args: &[OsString]) -> bool {
+    let mut args = args.iter().filter_map(|arg| arg.to_str());
+    match args.next() {
+        Some("doctor") => args.any(|arg| arg == "--fix"),
+        Some("hash") => args.next() == Some("update"),
+        Some("compat" | "pin" | "scaffold" | "unpin") => true,
+        _ => false,
+    }
+}
+
+pub fn run(command: ComponentCommand) -> Result<()> {
+    match command {
+        ComponentCommand::Compat(args) => component_compat::run(&args),
+        ComponentCommand::Doctor(args) => component_manifest::doctor_fix(&args),
+        ComponentCommand::Hash {
+            command: ComponentHashCommand::Update(args),
+        } => component_manifest::update_hashes(&args),
+        ComponentCommand::Pin(args) => component_pin::pin(&args),
+        ComponentCommand::Scaffold(args) => component_scaffold::scaffold(&args),
+        ComponentCommand::Unpin(args) => component_pin::unpin(&args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ComponentCommand, ComponentHashCommand, ScaffoldLanguage, is_builtin, parse_builtin,
+    };
+    use std::ffi::OsString;
+    use std::path::Path;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn upstream_subcommands_are_not_intercepted() {
+        assert!(!is_builtin(&args(&["doctor", "component.wasm"])));
+        assert!(!is_builtin(&args(&["new", "--fix"])));
+        assert!(!is_builtin(&args(&["hash", "component.manifest.json"])));
+        assert!(!is_builtin(&[]));
+    }
+
+    #[test]
+    fn doctor_fix_is_parsed_locally() {
+        let parsed = parse_builtin(&args(&["doctor", "--fix", "components/echo"]), "en")
+            .expect("builtin command");
+        let ComponentCommand::Doctor(doctor) = parsed else {
+            panic!("expected doctor");
+        };
+        assert!(doctor.fix);
+        assert_eq!(doctor.target, Path::new("components/echo"));
+    }
+
+    #[test]
+    fn hash_update_is_parsed_locally() {
+        let parsed =
+            parse_builtin(&args(&["hash", "update", "components/echo"]), "en").expect("builtin");
+        let ComponentCommand::Hash {
+            command: ComponentHashCommand::Update(update),
+        } = parsed
+        else {
+            panic!("expected hash update");
+        };
+        assert_eq!(update.dir, Path::new("components/echo"));
+    }
+
+    #[test]
+    fn scaffold_is_parsed_locally_with_rust_by_default() {
+        let parsed = parse_builtin(&args(&["scaffold", "echo"]), "en").expect("builtin");
+        let ComponentCommand::Scaffold(scaffold) = parsed else {
+            panic!("expected scaffold");
+        };
+        assert_eq!(scaffold.name, "echo");
+        assert_eq!(scaffold.lang, ScaffoldLanguage::Rust);
+        assert_eq!(scaffold.dir, Path::new("components"));
+
+        let parsed =
+            parse_builtin(&args(&["scaffold", "echo", "--lang", "tinygo"]), "en").expect("builtin");
+        let ComponentCommand::Scaffold(scaffold) = parsed else {
+            panic!("expected scaffold");
+        };
+        assert_eq!(scaffold.lang, ScaffoldLanguage::Tinygo);
+    }
+
+    #[test]
+    fn pin_is_parsed_locally_with_repeated_flows() {
+        let parsed = parse_builtin(
+            &args(&[
+                "pin",
+                "dev.greentic.echo",
+                "0.2.0",
+                "--flow",
+                "flows/main.ygtc",
+                "--flow",
+                "flows/billing.ygtc",
+            ]),
+            "en",
+        )
+        .expect("builtin");
+        let ComponentCommand::Pin(pin) = parsed else {
+            panic!("expected pin");
+        };
+        assert_eq!(pin.name, "dev.greentic.echo");
+        assert_eq!(pin.version, "0.2.0");
+        assert_eq!(pin.flows.len(), 2);
+        assert!(is_builtin(&args(&["unpin", "dev.greentic.echo"])));
+    }
+}
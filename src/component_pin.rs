@@ -0,0 +1,401 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_flow::resolve_summary;
+use greentic_types::flow_resolve::{
+    ComponentSourceRefV1, read_flow_resolve, sidecar_path_for_flow, write_flow_resolve,
+};
+use greentic_types::flow_resolve_summary::resolve_summary_path_for_flow;
+use semver::{Version, VersionReq};
+use serde_json::Value as JsonValue;
+
+use crate::component_lock::{self, LOCK_PATH};
+use crate::component_manifest_cli::{ComponentPinArgs, ComponentUnpinArgs};
+use crate::component_resolver::OCI_SCHEME;
+use crate::events;
+use crate::flow_operation::{block_end, find_key, indent_of, is_blank, render_scalar};
+
+/// `component pin`: make every node of the flows that uses the component require `version`.
+/// Resolve sidecars follow an exact pin, and a lock entry the pin rules out is dropped.
+pub fn pin(args: &ComponentPinArgs) -> Result<()> {
+    let pin = Pin::parse(&args.version)?;
+    let rewritten = rewrite_flows(&args.flows, &args.name, Some(&pin.rendered))?;
+    for flow in &rewritten {
+        println!(
+            "Pinned `{}` to `{}` in {} (nodes: {})",
+            args.name,
+            pin.rendered,
+            flow.path.display(),
+            flow.nodes.join(", ")
+        );
+        update_sidecars(flow, &args.name, pin.exact.as_ref())?;
+    }
+    if let Some(locked) = component_lock::release(&args.name, &pin.requirement)? {
+        println!(
+            "Dropped `{}` {locked} from {LOCK_PATH}; the next pack build pins the new resolution",
+            args.name
+        );
+    }
+    Ok(())
+}
+
+/// `component unpin`: let every node of the flows that uses the component take any version.
+pub fn unpin(args: &ComponentUnpinArgs) -> Result<()> {
+    for flow in rewrite_flows(&args.flows, &args.name, None)? {
+        println!(
+            "Unpinned `{}` in {} (nodes: {})",
+            args.name,
+            flow.path.display(),
+            flow.nodes.join(", ")
+        );
+    }
+    Ok(())
+}
+
+struct Pin {
+    /// Written after the `@` of component refs.
+    rendered: String,
+    requirement: VersionReq,
+    /// The version a bare `X.Y.Z` pins exactly; sidecar refs can only name one.
+    exact: Option<Version>,
+}
+
+impl Pin {
+    // A bare version pins exactly (`=X.Y.Z`); any other requirement is written as given.
+    fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if let Ok(version) = Version::parse(raw) {
+            let rendered = format!("={version}");
+            return Ok(Self {
+                requirement: VersionReq::parse(&rendered)?,
+                rendered,
+                exact: Some(version),
+            });
+        }
+        let requirement = VersionReq::parse(raw)
+            .with_context(|| format!("invalid version requirement `{raw}`"))?;
+        Ok(Self {
+            rendered: raw.to_string(),
+            requirement,
+            exact: None,
+        })
+    }
+}
+
+struct RewrittenFlow {
+    path: PathBuf,
+    /// Nodes whose component ref changed.
+    nodes: Vec<String>,
+}
+
+// Every flow is checked and rewritten in memory before any is written.
+fn rewrite_flows(
+    flows: &[PathBuf],
+    name: &str,
+    requirement: Option<&str>,
+) -> Result<Vec<RewrittenFlow>> {
+    let mut updates = Vec::new();
+    for path in flows {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let (updated, nodes) = rewrite_component_refs(&source, name, requirement)
+            .with_context(|| format!("failed to update {}", path.display()))?;
+        updates.push((path, updated, nodes));
+    }
+    let mut rewritten = Vec::new();
+    for (path, updated, nodes) in updates {
+        if nodes.is_empty() {
+            println!("{} already refers to `{name}` that way", path.display());
+            continue;
+        }
+        fs::write(path, updated).with_context(|| format!("failed to write {}", path.display()))?;
+        events::artifact(path);
+        rewritten.push(RewrittenFlow {
+            path: path.clone(),
+            nodes,
+        });
+    }
+    Ok(rewritten)
+}
+
+// Rewrite the `component:` of each `component.exec` node naming `name` to `name@requirement`, or
+// to the bare name. Like `flow set-operation`, the YAML text is edited in place so comments and
+// layout survive, and the result is re-parsed to check the edit. Returns the nodes changed.
+fn rewrite_component_refs(
+    source: &str,
+    name: &str,
+    requirement: Option<&str>,
+) -> Result<(String, Vec<String>)> {
+    let doc: JsonValue = serde_yaml_bw::from_str(source).context("flow is not valid YAML")?;
+    let target = match requirement {
+        Some(requirement) => format!("{name}@{requirement}"),
+        None => name.to_string(),
+    };
+    let uses = component_refs(&doc)
+        .into_iter()
+        .filter(|(_, reference)| component_name(reference) == Some(name))
+        .collect::<Vec<_>>();
+    if uses.is_empty() {
+        bail!("no `component.exec` node uses component `{name}`");
+    }
+    let nodes = uses
+        .into_iter()
+        .filter(|(_, reference)| *reference != target)
+        .map(|(node, _)| node)
+        .collect::<Vec<_>>();
+
+    let mut lines = source.lines().map(str::to_string).collect::<Vec<_>>();
+    for node_id in &nodes {
+        let view = lines.iter().map(String::as_str).collect::<Vec<_>>();
+        let nodes_line = view
+            .iter()
+            .position(|line| line.trim_end() == "nodes:")
+            .ok_or_else(|| anyhow!("flow has no block-style `nodes:` mapping"))?;
+        let (node_line, node_indent) = find_key(&view, nodes_line + 1, 0, node_id)
+            .ok_or_else(|| anyhow!("node `{node_id}` is not written as a block mapping"))?;
+        let (payload_line, payload_indent) =
+            find_key(&view, node_line + 1, node_indent, "component.exec").ok_or_else(|| {
+                anyhow!(
+                    "node `{node_id}` payload `component.exec` is not written as a block mapping"
+                )
+            })?;
+        let payload_end = block_end(&view, payload_line + 1, payload_indent);
+        let child_indent = view[payload_line + 1..payload_end]
+            .iter()
+            .find(|line| !is_blank(line))
+            .map(|line| indent_of(line))
+            .unwrap_or(payload_indent + 2);
+        let line = (payload_line + 1..payload_end)
+            .find(|&idx| {
+                indent_of(view[idx]) == child_indent
+                    && view[idx].trim_start().starts_with("component:")
+            })
+            .ok_or_else(|| anyhow!("node `{node_id}` names its component on no line of its own"))?;
+        // Keep double quotes where the ref had them.
+        let quoted = view[line]
+            .trim_start()
+            .trim_start_matches("component:")
+            .trim_start()
+            .starts_with('"');
+        let rendered = if quoted {
+            serde_json::to_string(&target).expect("string serializes")
+        } else {
+            render_scalar(&target)
+        };
+        lines[line] = format!("{}component: {rendered}", " ".repeat(child_indent));
+    }
+    let mut updated = lines.join("\n");
+    if source.ends_with('\n') {
+        updated.push('\n');
+    }
+
+    let check: JsonValue =
+        serde_yaml_bw::from_str(&updated).context("edited flow is not valid YAML")?;
+    let refs = component_refs(&check);
+    let applied = nodes.iter().all(|node| {
+        refs.iter()
+            .any(|(id, reference)| id == node && *reference == target)
+    });
+    if !applied {
+        bail!("could not rewrite the `{name}` refs; edit the flow manually");
+    }
+    Ok((updated, nodes))
+}
+
+// `(node id, component ref)` of each `component.exec` node.
+fn component_refs(doc: &JsonValue) -> Vec<(String, String)> {
+    doc.get("nodes")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(node, value)| {
+            let reference = value.pointer("/component.exec/component")?.as_str()?;
+            Some((node.clone(), reference.trim().to_string()))
+        })
+        .collect()
+}
+
+// The component a ref names; OCI refs name none that can be pinned.
+fn component_name(reference: &str) -> Option<&str> {
+    if reference.starts_with(OCI_SCHEME) {
+        return None;
+    }
+    Some(
+        reference
+            .split_once('@')
+            .map_or(reference, |(name, _)| name)
+            .trim(),
+    )
+}
+
+// Point the flow's resolve sidecar at the pinned version for the rewritten nodes, and refresh their
+// resolve summary entries.
+fn update_sidecars(flow: &RewrittenFlow, name: &str, version: Option<&Version>) -> Result<()> {
+    let sidecar_path = sidecar_path_for_flow(&flow.path);
+    if !sidecar_path.is_file() {
+        return Ok(());
+    }
+    let mut sidecar = read_flow_resolve(&sidecar_path)
+        .map_err(|err| anyhow!("failed to read {}: {err}", sidecar_path.display()))?;
+    let mut changed = Vec::new();
+    for node in &flow.nodes {
+        let Some(entry) = sidecar.nodes.get_mut(node) else {
+            continue;
+        };
+        let (reference, digest, separator) = match &mut entry.source {
+            ComponentSourceRefV1::Local { .. } => continue,
+            ComponentSourceRefV1::Oci { r#ref, digest } => (r#ref, digest, ':'),
+            ComponentSourceRefV1::Repo { r#ref, digest }
+            | ComponentSourceRefV1::Store { r#ref, digest, .. } => (r#ref, digest, '@'),
+        };
+        let Some(version) = version else {
+            events::warn(format_args!(
+                "{} names exact versions; left node `{node}` at {reference}",
+                sidecar_path.display()
+            ));
+            continue;
+        };
+        let Some(pinned) = with_version(reference, version, separator) else {
+            events::warn(format_args!(
+                "node `{node}` of {} is pinned by digest; left it at {reference}",
+                sidecar_path.display()
+            ));
+            continue;
+        };
+        if pinned != *reference {
+            *reference = pinned;
+            *digest = None;
+            changed.push(node.clone());
+        }
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+    write_flow_resolve(&sidecar_path, &sidecar)
+        .map_err(|err| anyhow!("failed to write {}: {err}", sidecar_path.display()))?;
+    events::artifact(&sidecar_path);
+    println!(
+        "Pointed {} at `{name}` {} (nodes: {})",
+        sidecar_path.display(),
+        version.expect("only exact pins change sidecars"),
+        changed.join(", ")
+    );
+
+    let summary_path = resolve_summary_path_for_flow(&flow.path);
+    if !summary_path.is_file() {
+        return Ok(());
+    }
+    for node in &changed {
+        // The old entry's digest is for the old version: refresh it, or drop it when the pinned
+        // version cannot be resolved now.
+        if let Err(err) =
+            resolve_summary::write_flow_resolve_summary_for_node(&flow.path, node, &sidecar)
+        {
+            resolve_summary::remove_flow_resolve_summary_node(&flow.path, node)?;
+            events::warn(format_args!(
+                "dropped node `{node}` from {}: {err:#}",
+                summary_path.display()
+            ));
+        }
+    }
+    events::artifact(&summary_path);
+    Ok(())
+}
+
+// `oci://ghcr.io/acme/greeter:0.1.0` becomes `oci://ghcr.io/acme/greeter:0.2.0`; a ref without a
+// version gets one with `separator`. Digest-pinned refs are left alone.
+fn with_version(reference: &str, version: &Version, separator: char) -> Option<String> {
+    if reference.contains("@sha256:") {
+        return None;
+    }
+    let (prefix, last) = match reference.rsplit_once('/') {
+        Some((prefix, last)) => (format!("{prefix}/"), last),
+        None => (String::new(), reference),
+    };
+    let (name, separator) = match last.find([':', '@']) {
+        Some(at) => (&last[..at], last[at..].chars().next().unwrap_or(separator)),
+        None => (last, separator),
+    };
+    Some(format!("{prefix}{name}{separator}{version}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOW: &str = r#"id: hello-flow
+type: messaging
+start: start
+nodes:
+  # greet the user
+  start:
+    component.exec:
+      component: "dev.greentic.echo@^0.1"
+      op: "echo"
+    routing:
+      - to: log
+  log:
+    component.exec:
+      op: "echo"
+      component: dev.greentic.echo
+    routing:
+      - to: other
+  other:
+    component.exec:
+      component: "dev.greentic.other"
+    routing:
+      - out: true
+"#;
+
+    #[test]
+    fn rewrites_every_ref_to_the_component_and_keeps_layout() {
+        let (updated, nodes) =
+            rewrite_component_refs(FLOW, "dev.greentic.echo", Some("=0.2.0")).unwrap();
+        assert_eq!(nodes, ["log", "start"]);
+        assert!(updated.contains("  # greet the user\n"));
+        assert!(updated.contains("      component: \"dev.greentic.echo@=0.2.0\"\n"));
+        assert_eq!(updated.matches("dev.greentic.echo@=0.2.0").count(), 2);
+        assert!(updated.contains("      component: \"dev.greentic.other\"\n"));
+
+        let (unpinned, nodes) =
+            rewrite_component_refs(&updated, "dev.greentic.echo", None).unwrap();
+        assert_eq!(nodes, ["log", "start"]);
+        assert_eq!(unpinned.matches("dev.greentic.echo\"").count(), 2);
+
+        let err = rewrite_component_refs(FLOW, "dev.greentic.missing", None).unwrap_err();
+        assert!(err.to_string().contains("no `component.exec` node uses"));
+    }
+
+    #[test]
+    fn bare_versions_pin_exactly() {
+        let pin = Pin::parse("0.2.0").unwrap();
+        assert_eq!(pin.rendered, "=0.2.0");
+        assert_eq!(pin.exact, Some(Version::new(0, 2, 0)));
+        let pin = Pin::parse("^0.2").unwrap();
+        assert_eq!(pin.rendered, "^0.2");
+        assert_eq!(pin.exact, None);
+        assert!(Pin::parse("latest").is_err());
+    }
+
+    #[test]
+    fn sidecar_refs_take_the_pinned_version() {
+        let version = Version::new(0, 2, 0);
+        assert_eq!(
+            with_version("oci://ghcr.io/acme/greeter:0.1.0", &version, ':').as_deref(),
+            Some("oci://ghcr.io/acme/greeter:0.2.0")
+        );
+        assert_eq!(
+            with_version("oci://ghcr.io/acme/greeter", &version, ':').as_deref(),
+            Some("oci://ghcr.io/acme/greeter:0.2.0")
+        );
+        assert_eq!(
+            with_version("repo://greeter@0.1.0", &version, '@').as_deref(),
+            Some("repo://greeter@0.2.0")
+        );
+        assert_eq!(
+            with_version("oci://ghcr.io/acme/greeter@sha256:abc", &version, ':'),
+            None
+        );
+    }
+}
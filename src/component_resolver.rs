@@ -1,26 +1,48 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow};
 use greentic_component::describe::{DescribePayload, DescribeVersion};
 use greentic_component::lifecycle::Lifecycle;
 use greentic_component::manifest::ComponentManifest;
 use greentic_component::prepare::PreparedComponent;
 use greentic_component::prepare_component;
+use greentic_distributor_client::oci_components::{
+    ComponentResolveOptions, ComponentsExtension, ComponentsMode, OciComponentResolver,
+    RegistryClient,
+};
 use greentic_flow::flow_bundle::NodeRef;
 use jsonschema::{Draft, Validator};
 use semver::{Version, VersionReq};
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 
+use crate::component_manifest;
+use crate::download::{DownloadOptions, ResumableRegistryClient};
+use crate::explain::{
+    COMPONENT_MANIFEST_INVALID, CodedError, RESOLVE_COMPONENT_NOT_FOUND, RESOLVE_OCI_PULL_FAILED,
+    RESOLVE_VERSION_MISMATCH,
+};
+use crate::resolve_map::{self, CARGO_CACHE_DIR, ResolveMap};
+use crate::runtime;
+
+/// Component refs with this prefix are pulled from an OCI registry instead of a local directory.
+pub const OCI_SCHEME: &str = "oci://";
+/// Content-addressed cache for pulled OCI components, relative to the workspace root.
+pub const OCI_CACHE_DIR: &str = ".greentic/cache/oci";
+// Cache slot prefix for components built from a Cargo project.
+const CARGO_SCHEME: &str = "cargo:";
+const DEFAULT_OAUTH_USER: &str = "oauth2";
+
 #[derive(Debug, Clone)]
 pub struct ResolvedComponent {
     pub name: String,
     pub version: Version,
     pub wasm_path: PathBuf,
-    #[allow(dead_code)]
     pub manifest_path: PathBuf,
     pub schema_json: Option<String>,
     pub manifest_json: Option<String>,
@@ -49,47 +71,149 @@ pub struct NodeSchemaError {
     pub message: String,
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct ComponentCacheKey {
-    name: String,
-    version: Version,
+/// Sizes and modification times of the files `prepare_component` reads for a component: its
+/// manifest, its wasm and any embedded describe schemas next to the wasm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceStamp(Vec<(PathBuf, Option<(u64, SystemTime)>)>);
+
+impl SourceStamp {
+    fn of(component: &ResolvedComponent) -> Self {
+        let mut paths = vec![component.manifest_path.clone(), component.wasm_path.clone()];
+        if let Some(dir) = component.wasm_path.parent()
+            && let Ok(entries) = fs::read_dir(dir.join("schemas").join("v1"))
+        {
+            let mut schemas = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>();
+            schemas.sort();
+            paths.extend(schemas);
+        }
+        Self(
+            paths
+                .into_iter()
+                .map(|path| {
+                    let stamp = fs::metadata(&path)
+                        .ok()
+                        .and_then(|meta| Some((meta.len(), meta.modified().ok()?)));
+                    (path, stamp)
+                })
+                .collect(),
+        )
+    }
+}
+
+struct CachedComponent {
+    stamp: SourceStamp,
+    component: Arc<ResolvedComponent>,
+    /// For a component built from a Cargo project, the project files it was built from.
+    project_sources: Vec<PathBuf>,
+}
+
+/// How `oci://` component refs are resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OciOptions {
+    /// Content-addressed cache; each component lives in a directory named after its digest.
+    pub cache_dir: PathBuf,
+    /// Resolve only from the cache. Refs must then be pinned by digest.
+    pub offline: bool,
+    /// Accept tag refs (`:latest`, `:1.2.0`) as well as digest-pinned ones (`@sha256:...`).
+    pub allow_tags: bool,
+    /// Cap on the blob download rate, in bytes per second.
+    pub limit_rate: Option<u64>,
 }
 
-impl ComponentCacheKey {
-    fn new(name: impl Into<String>, version: &Version) -> Self {
+impl Default for OciOptions {
+    fn default() -> Self {
         Self {
-            name: name.into(),
-            version: version.clone(),
+            cache_dir: PathBuf::from(OCI_CACHE_DIR),
+            offline: false,
+            allow_tags: false,
+            limit_rate: None,
         }
     }
 }
 
+/// Resolves flow nodes to prepared components. Prepared components are cached by resolver
+/// target and reused until one of their source files changes, so a resolver kept across
+/// rebuilds (`pack build --watch`) only re-prepares the components that were edited.
+///
+/// Nodes can be resolved and validated from several threads at once. Each target has its own
+/// cache slot, locked while its component is prepared, so nodes sharing a component prepare it
+/// once while different components are prepared in parallel.
 pub struct ComponentResolver {
     component_dir: Option<PathBuf>,
-    cache: HashMap<ComponentCacheKey, Arc<ResolvedComponent>>,
-    schema_cache: HashMap<String, Arc<CachedSchema>>,
+    oci: OciOptions,
+    resolve_map: ResolveMap,
+    cache: Mutex<HashMap<String, Arc<CacheSlot>>>,
+    schema_cache: Mutex<HashMap<String, Arc<CachedSchema>>>,
+    prepared: AtomicUsize,
 }
 
+type CacheSlot = Mutex<Option<CachedComponent>>;
+
 struct CachedSchema(Validator);
 
 impl ComponentResolver {
     pub fn new(component_dir: Option<PathBuf>) -> Self {
         Self {
             component_dir,
-            cache: HashMap::new(),
-            schema_cache: HashMap::new(),
+            oci: OciOptions::default(),
+            resolve_map: ResolveMap::default(),
+            cache: Mutex::new(HashMap::new()),
+            schema_cache: Mutex::new(HashMap::new()),
+            prepared: AtomicUsize::new(0),
         }
     }
 
+    pub fn with_oci(mut self, oci: OciOptions) -> Self {
+        self.oci = oci;
+        self
+    }
+
+    /// Build the components `map` names from their Cargo projects, ahead of the component
+    /// directory.
+    pub fn with_resolve_map(mut self, map: ResolveMap) -> Self {
+        self.resolve_map = map;
+        self
+    }
+
+    /// How many times a component has been prepared (rather than reused from the cache).
+    pub fn prepared_count(&self) -> usize {
+        self.prepared.load(Ordering::Relaxed)
+    }
+
+    /// Every source file behind the cached components, for callers that watch for changes.
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        let slots = lock(&self.cache).values().cloned().collect::<Vec<_>>();
+        slots
+            .iter()
+            .flat_map(|slot| {
+                lock(slot)
+                    .as_ref()
+                    .map(|cached| {
+                        cached
+                            .stamp
+                            .0
+                            .iter()
+                            .map(|(path, _)| path.clone())
+                            .chain(cached.project_sources.iter().cloned())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
     pub fn resolve_component(
-        &mut self,
+        &self,
         name: &str,
         version_req: &VersionReq,
     ) -> Result<Arc<ResolvedComponent>> {
         self.load_component(name, version_req)
     }
 
-    pub fn resolve_node(&mut self, node: &NodeRef, flow_doc: &JsonValue) -> Result<ResolvedNode> {
+    pub fn resolve_node(&self, node: &NodeRef, flow_doc: &JsonValue) -> Result<ResolvedNode> {
         let component_key = &node.component;
         let pointer = format!("/nodes/{}/{}", node.node_id, component_key.name);
         let config = extract_node_payload(flow_doc, &node.node_id, &component_key.name)
@@ -124,7 +248,7 @@ impl ComponentResolver {
         })
     }
 
-    pub fn validate_node(&mut self, node: &ResolvedNode) -> Result<Vec<NodeSchemaError>> {
+    pub fn validate_node(&self, node: &ResolvedNode) -> Result<Vec<NodeSchemaError>> {
         let Some(schema_json) = &node.component.schema_json else {
             return Ok(Vec::new());
         };
@@ -150,8 +274,8 @@ impl ComponentResolver {
         Ok(issues)
     }
 
-    fn compile_schema(&mut self, schema_json: &str) -> Result<Arc<CachedSchema>> {
-        if let Some(existing) = self.schema_cache.get(schema_json) {
+    fn compile_schema(&self, schema_json: &str) -> Result<Arc<CachedSchema>> {
+        if let Some(existing) = lock(&self.schema_cache).get(schema_json) {
             return Ok(existing.clone());
         }
 
@@ -161,45 +285,220 @@ impl ComponentResolver {
             .with_draft(Draft::Draft7)
             .build(&schema_value)
             .map_err(|error| anyhow!("failed to compile schema JSON: {error}"))?;
-        let entry = Arc::new(CachedSchema(compiled));
-        self.schema_cache
-            .insert(schema_json.to_string(), entry.clone());
+        // Another thread may have compiled the same schema meanwhile; keep the first one.
+        let entry = lock(&self.schema_cache)
+            .entry(schema_json.to_string())
+            .or_insert_with(|| Arc::new(CachedSchema(compiled)))
+            .clone();
         Ok(entry)
     }
 
     fn load_component(
-        &mut self,
+        &self,
         name: &str,
         version_req: &VersionReq,
     ) -> Result<Arc<ResolvedComponent>> {
+        // Pulled artifacts are immutable, so an OCI ref is only pulled once per resolver.
+        if let Some(reference) = name.strip_prefix(OCI_SCHEME) {
+            let slot = self.slot(name);
+            let mut cached = lock(&slot);
+            if let Some(cached) = cached.as_ref() {
+                let component = cached.component.clone();
+                check_version(name, &component.version, version_req)?;
+                return Ok(component);
+            }
+            let target = ComponentTarget::Path(self.pull_oci(reference)?);
+            return self.prepare(name, &target, version_req, &mut cached);
+        }
+
+        // Mapped components are rebuilt when their project changes, then re-prepared.
+        if let Some(source) = self.resolve_map.components.get(name) {
+            let slot = self.slot(&format!("{CARGO_SCHEME}{name}"));
+            let mut cached = lock(&slot);
+            let build = resolve_map::build(name, &source.path, Path::new(CARGO_CACHE_DIR))
+                .with_context(|| {
+                    format!(
+                        "failed to build component `{name}` from {}",
+                        source.path.display()
+                    )
+                })?;
+            if !build.built
+                && let Some(cached) = cached.as_ref()
+                && cached.stamp == SourceStamp::of(&cached.component)
+            {
+                let component = cached.component.clone();
+                check_version(name, &component.version, version_req)?;
+                return Ok(component);
+            }
+            let target = ComponentTarget::Path(build.dir);
+            let component = self.prepare(name, &target, version_req, &mut cached)?;
+            if let Some(cached) = cached.as_mut() {
+                cached.project_sources = build.sources;
+            }
+            return Ok(component);
+        }
+
         let target = component_target(name, self.component_dir.as_deref());
-        let target_display = match &target {
+        if let ComponentTarget::Path(path) = &target
+            && !path.exists()
+        {
+            return Err(CodedError::new(
+                RESOLVE_COMPONENT_NOT_FOUND,
+                format!("component `{name}` not found at {}", path.display()),
+            )
+            .into());
+        }
+        let slot = self.slot(target.as_ref());
+        let mut cached = lock(&slot);
+        if let Some(cached) = cached.as_ref()
+            && cached.stamp == SourceStamp::of(&cached.component)
+        {
+            let component = cached.component.clone();
+            check_version(name, &component.version, version_req)?;
+            return Ok(component);
+        }
+        self.prepare(name, &target, version_req, &mut cached)
+    }
+
+    /// The cache slot for `key`, created empty on first use.
+    fn slot(&self, key: &str) -> Arc<CacheSlot> {
+        lock(&self.cache)
+            .entry(key.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Prepare the component behind `target` and store it in `cached`, whose slot lock the
+    /// caller holds.
+    fn prepare(
+        &self,
+        name: &str,
+        target: &ComponentTarget,
+        version_req: &VersionReq,
+        cached: &mut Option<CachedComponent>,
+    ) -> Result<Arc<ResolvedComponent>> {
+        let target_display = match target {
             ComponentTarget::Direct(id) => id.clone(),
             ComponentTarget::Path(path) => path.display().to_string(),
         };
 
-        let prepared = prepare_component(target.as_ref()).with_context(|| {
-            format!(
+        // greentic-component only reports the first deserialization failure; check the manifest
+        // against its JSON Schema first so every bad field is named with its file.
+        if let Some(manifest) = target.local_manifest() {
+            component_manifest::validate_manifest_file(&manifest).map_err(|err| {
+                CodedError::new(
+                    COMPONENT_MANIFEST_INVALID,
+                    format!("component `{name}` has an invalid manifest"),
+                )
+                .with_source(err)
+            })?;
+        }
+
+        let prepared = prepare_component(target.as_ref()).map_err(|err| {
+            let message = format!(
                 "resolver looked for `{name}` via `{target_display}` but prepare_component failed"
-            )
+            );
+            match target {
+                // A bare id that is not a path either: nothing on disk matched it.
+                ComponentTarget::Direct(id) if !Path::new(id).exists() => {
+                    CodedError::new(RESOLVE_COMPONENT_NOT_FOUND, message)
+                        .with_source(err.into())
+                        .into()
+                }
+                _ => anyhow::Error::from(err).context(message),
+            }
         })?;
+        self.prepared.fetch_add(1, Ordering::Relaxed);
+        check_version(name, &prepared.manifest.version, version_req)?;
+
+        let component = Arc::new(to_resolved_component(prepared)?);
+        *cached = Some(CachedComponent {
+            stamp: SourceStamp::of(&component),
+            component: component.clone(),
+            project_sources: Vec::new(),
+        });
+        Ok(component)
+    }
 
-        if !version_req.matches(&prepared.manifest.version) {
-            bail!(
-                "component `{name}` version `{}` does not satisfy requirement `{version_req}`",
-                prepared.manifest.version
-            );
-        }
+    /// Pull `reference` into the OCI cache (or find it there) and return the directory holding
+    /// its manifest and wasm. A digest-pinned ref is checked against the pulled content.
+    fn pull_oci(&self, reference: &str) -> Result<PathBuf> {
+        let resolver = OciComponentResolver::with_client(
+            registry_client(
+                &self.oci.cache_dir,
+                DownloadOptions {
+                    limit_rate: self.oci.limit_rate,
+                    ..DownloadOptions::default()
+                },
+            ),
+            ComponentResolveOptions {
+                allow_tags: self.oci.allow_tags,
+                offline: self.oci.offline,
+                cache_dir: self.oci.cache_dir.clone(),
+                ..ComponentResolveOptions::default()
+            },
+        );
+        let extension = ComponentsExtension {
+            refs: vec![reference.to_string()],
+            mode: ComponentsMode::Eager,
+        };
+        let pulled = runtime::block_on(async {
+            resolver
+                .resolve_refs(&extension)
+                .await
+                .map_err(|err| anyhow!(err))
+        })
+        .map_err(|err| {
+            CodedError::new(
+                RESOLVE_OCI_PULL_FAILED,
+                format!("failed to resolve component {OCI_SCHEME}{reference}"),
+            )
+            .with_source(err)
+        })?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("{OCI_SCHEME}{reference} resolved to nothing"))?;
+        pulled.path.parent().map(Path::to_path_buf).ok_or_else(|| {
+            anyhow!(
+                "cached component {} has no directory",
+                pulled.path.display()
+            )
+        })
+    }
+}
 
-        let key = ComponentCacheKey::new(name, &prepared.manifest.version);
-        if let Some(existing) = self.cache.get(&key) {
-            return Ok(existing.clone());
-        }
+// A panic on another resolver thread leaves the caches consistent: entries are only inserted
+// once complete.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
-        let resolved = Arc::new(to_resolved_component(prepared)?);
-        self.cache.insert(key, resolved.clone());
-        Ok(resolved)
+/// Registry client for OCI component pulls, authenticated with `GHCR_TOKEN` or `GITHUB_TOKEN`
+/// when one is set. Layer blobs are downloaded resumably, staged under `<cache_dir>/blobs`.
+pub(crate) fn registry_client(
+    cache_dir: &Path,
+    options: DownloadOptions,
+) -> ResumableRegistryClient {
+    let client = match crate::outdated_cmd::registry_auth() {
+        Some(token) => ResumableRegistryClient::with_basic_auth(DEFAULT_OAUTH_USER, token),
+        None => ResumableRegistryClient::default_client(),
+    };
+    client
+        .with_blob_dir(cache_dir.join("blobs"))
+        .with_options(options)
+}
+
+fn check_version(name: &str, version: &Version, version_req: &VersionReq) -> Result<()> {
+    if !version_req.matches(version) {
+        return Err(CodedError::new(
+            RESOLVE_VERSION_MISMATCH,
+            format!(
+                "component `{name}` version `{version}` does not satisfy requirement `{version_req}`"
+            ),
+        )
+        .into());
     }
+    Ok(())
 }
 
 enum ComponentTarget {
@@ -214,6 +513,18 @@ impl ComponentTarget {
             ComponentTarget::Path(path) => path.to_str().expect("component path utf-8"),
         }
     }
+
+    /// The manifest file behind a local component, if there is one on disk.
+    fn local_manifest(&self) -> Option<PathBuf> {
+        let path = match self {
+            ComponentTarget::Direct(id) => Path::new(id),
+            ComponentTarget::Path(path) => path.as_path(),
+        };
+        if !path.exists() {
+            return None;
+        }
+        Some(component_manifest::manifest_path(path)).filter(|manifest| manifest.is_file())
+    }
 }
 
 fn component_target(name: &str, root: Option<&Path>) -> ComponentTarget {
@@ -0,0 +1,268 @@
+//! `component scaffold`: generate a component project from the built-in templates. Every
+//! language gets the same WIT world, manifest and dev flow; only the sources and the build script
+//! differ.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value as JsonValue, json};
+
+use crate::component_manifest::{default_dev_flow, schema_issues};
+use crate::component_manifest_cli::{ComponentScaffoldArgs, ScaffoldLanguage};
+use crate::interrupt::PartialOutput;
+
+/// The world every scaffolded component targets. Its WIT packages are vendored into `wit/deps`.
+pub const WORLD: &str = "greentic:component/component@0.6.0";
+const OPERATION: &str = "handle";
+const MANIFEST: &str = "component.manifest.json";
+const WASM: &str = "component.wasm";
+
+/// A template file: its path in the generated project and its contents before rendering.
+type Template = (&'static str, &'static str);
+
+macro_rules! template {
+    ($dest:literal, $source:literal) => {
+        (
+            $dest,
+            include_str!(concat!("../templates/scaffold/", $source)),
+        )
+    };
+}
+
+const WIT: &[Template] = &[
+    template!("wit/world.wit", "wit/world.wit"),
+    template!(
+        "wit/deps/greentic-component-0.6.0/package.wit",
+        "wit/deps/greentic-component-0.6.0/package.wit"
+    ),
+    template!(
+        "wit/deps/greentic-types-core-0.6.0/package.wit",
+        "wit/deps/greentic-types-core-0.6.0/package.wit"
+    ),
+];
+
+const RUST: &[Template] = &[
+    template!("Cargo.toml", "rust/Cargo.toml.in"),
+    template!("src/lib.rs", "rust/src/lib.rs"),
+    template!("build.sh", "rust/build.sh"),
+    template!("README.md", "rust/README.md"),
+    template!(".gitignore", "rust/gitignore"),
+];
+
+const TINYGO: &[Template] = &[
+    template!("go.mod", "tinygo/go.mod"),
+    template!("main.go", "tinygo/main.go"),
+    template!("build.sh", "tinygo/build.sh"),
+    template!("README.md", "tinygo/README.md"),
+    template!(".gitignore", "tinygo/gitignore"),
+];
+
+const JS: &[Template] = &[
+    template!("package.json", "js/package.json"),
+    template!("src/component.js", "js/src/component.js"),
+    template!("build.sh", "js/build.sh"),
+    template!("README.md", "js/README.md"),
+    template!(".gitignore", "js/gitignore"),
+];
+
+fn templates(lang: ScaffoldLanguage) -> impl Iterator<Item = &'static Template> {
+    let sources = match lang {
+        ScaffoldLanguage::Rust => RUST,
+        ScaffoldLanguage::Tinygo => TINYGO,
+        ScaffoldLanguage::Js => JS,
+    };
+    WIT.iter().chain(sources)
+}
+
+/// `component scaffold <NAME> [--lang rust|tinygo|js] [--id ID] [--dir DIR]`.
+pub fn scaffold(args: &ComponentScaffoldArgs) -> Result<()> {
+    validate_name(&args.name)?;
+    let id = args.id.as_deref().unwrap_or(&args.name);
+    let dest = args.dir.join(&args.name);
+    if fs::read_dir(&dest).is_ok_and(|mut entries| entries.next().is_some()) {
+        bail!(
+            "{} already exists and is not empty; pick another name or --dir",
+            dest.display()
+        );
+    }
+
+    let partial = PartialOutput::new(&dest);
+    write_project(&dest, &args.name, id, args.lang)?;
+    drop(partial);
+
+    println!(
+        "Scaffolded {} component `{id}` in {}",
+        language_name(args.lang),
+        dest.display()
+    );
+    println!(
+        "Next: run {} to build component.wasm",
+        dest.join("build.sh").display()
+    );
+    Ok(())
+}
+
+/// Render the templates for `lang` and the manifest into `dest`.
+pub fn write_project(dest: &Path, name: &str, id: &str, lang: ScaffoldLanguage) -> Result<()> {
+    let vars = [
+        ("name", name.to_string()),
+        ("id", id.to_string()),
+        ("crate_name", name.replace('-', "_")),
+        ("operation", OPERATION.to_string()),
+    ];
+    for (path, source) in templates(lang) {
+        let target = dest.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(&target, render(source, &vars))
+            .with_context(|| format!("failed to write {}", target.display()))?;
+        if path.ends_with(".sh") {
+            make_executable(&target)?;
+        }
+    }
+
+    let manifest = manifest(id);
+    let issues = schema_issues(&manifest);
+    if let Some(issue) = issues.first() {
+        bail!("generated manifest does not match the component manifest schema: {issue}");
+    }
+    let path = dest.join(MANIFEST);
+    let mut contents = serde_json::to_string_pretty(&manifest)?;
+    contents.push('\n');
+    fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))
+}
+
+// The hash is a placeholder for the empty file until the first build runs
+// `component hash update`.
+fn manifest(id: &str) -> JsonValue {
+    json!({
+        "id": id,
+        "name": id,
+        "version": "0.1.0",
+        "world": WORLD,
+        "describe_export": "describe",
+        "config_schema": { "type": "object" },
+        "operations": [{
+            "name": OPERATION,
+            "input_schema": { "type": "object" },
+            "output_schema": { "type": "object" }
+        }],
+        "default_operation": OPERATION,
+        "supports": ["messaging"],
+        "profiles": { "default": "default", "supported": ["default"] },
+        "capabilities": { "wasi": {}, "host": {} },
+        "artifacts": { "component_wasm": WASM },
+        "hashes": { "component_wasm": format!("blake3:{}", blake3::hash(&[]).to_hex()) },
+        "dev_flows": { "default": default_dev_flow(id, OPERATION) }
+    })
+}
+
+fn render(source: &str, vars: &[(&str, String)]) -> String {
+    vars.iter().fold(source.to_string(), |text, (key, value)| {
+        text.replace(&format!("{{{{{key}}}}}"), value)
+    })
+}
+
+// The name doubles as a WIT identifier, a Cargo package, a Go module and an npm package, so
+// only lowercase kebab-case words that start with a letter are accepted.
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.split('-').all(|word| {
+            word.starts_with(|c: char| c.is_ascii_lowercase())
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if !valid {
+        bail!("invalid component name `{name}`: use lowercase kebab-case, e.g. `weather-lookup`");
+    }
+    Ok(())
+}
+
+fn language_name(lang: ScaffoldLanguage) -> &'static str {
+    match lang {
+        ScaffoldLanguage::Rust => "Rust",
+        ScaffoldLanguage::Tinygo => "TinyGo",
+        ScaffoldLanguage::Js => "JavaScript",
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LANGUAGES: [ScaffoldLanguage; 3] = [
+        ScaffoldLanguage::Rust,
+        ScaffoldLanguage::Tinygo,
+        ScaffoldLanguage::Js,
+    ];
+
+    #[test]
+    fn every_language_renders_all_placeholders_and_a_valid_manifest() {
+        for lang in LANGUAGES {
+            let dir = tempfile::tempdir().unwrap();
+            let dest = dir.path().join("echo-bot");
+            write_project(&dest, "echo-bot", "acme.echo", lang).unwrap();
+
+            for (path, _) in templates(lang) {
+                let text = fs::read_to_string(dest.join(path)).unwrap();
+                assert!(
+                    !has_placeholder(&text),
+                    "{lang:?}: {path} has unrendered placeholders"
+                );
+            }
+            let world = fs::read_to_string(dest.join("wit/world.wit")).unwrap();
+            assert!(world.contains("world echo-bot {"));
+            assert!(world.contains(&format!("include {WORLD};")));
+
+            let manifest: JsonValue =
+                serde_json::from_str(&fs::read_to_string(dest.join(MANIFEST)).unwrap()).unwrap();
+            assert!(schema_issues(&manifest).is_empty());
+            assert_eq!(manifest["id"], "acme.echo");
+            assert_eq!(manifest["dev_flows"]["default"]["format"], "flow-ir-json");
+        }
+    }
+
+    // `{{` alone also opens Go composite literals, so only `{{key}}` counts.
+    fn has_placeholder(text: &str) -> bool {
+        text.split("{{").skip(1).any(|rest| {
+            rest.split_once("}}").is_some_and(|(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            })
+        })
+    }
+
+    #[test]
+    fn build_scripts_target_the_world_and_update_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(dir.path(), "echo", "echo", ScaffoldLanguage::Tinygo).unwrap();
+        let script = fs::read_to_string(dir.path().join("build.sh")).unwrap();
+        assert!(script.contains("--wit-world echo"));
+        assert!(script.contains("greentic-dev component hash update ."));
+    }
+
+    #[test]
+    fn names_must_be_kebab_case() {
+        for name in ["echo", "weather-lookup", "v2-api"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+        for name in ["", "Echo", "echo_bot", "-echo", "echo-", "2fa", "echo--bot"] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 use serde::Deserialize;
@@ -13,6 +13,15 @@ pub struct GreenticConfig {
     pub defaults: DefaultsSection,
     #[serde(default)]
     pub distributor: DistributorSection,
+    #[serde(default)]
+    pub pack: PackSection,
+    #[serde(default)]
+    pub lint: LintSection,
+    #[serde(default)]
+    pub trust: TrustSection,
+    /// Where the organization defaults layer came from; written by `org sync`.
+    #[serde(default)]
+    pub org: Option<OrgSection>,
     /// Backward-compatible root-level [profiles.*] table used for distributor.
     #[serde(default, rename = "profiles")]
     pub legacy_distributor_profiles: HashMap<String, DistributorProfileConfig>,
@@ -44,6 +53,8 @@ pub struct ToolEntry {
 pub struct DefaultsSection {
     #[serde(default)]
     pub component: ComponentDefaults,
+    #[serde(default)]
+    pub capture: CaptureDefaults,
 }
 
 #[allow(dead_code)]
@@ -53,6 +64,62 @@ pub struct ComponentDefaults {
     pub template: Option<String>,
 }
 
+/// `[defaults.capture]`: what `pack run --json` keeps of each node's output when `--capture`
+/// does not say otherwise.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CaptureDefaults {
+    pub nodes: Option<Vec<String>>,
+    pub fields: Option<Vec<String>>,
+    pub max_bytes: Option<usize>,
+}
+
+/// `[pack.meta]`: metadata `pack build` uses for fields the pack's own metadata leaves unset.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct PackSection {
+    #[serde(default)]
+    pub meta: PackMetaDefaults,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackMetaDefaults {
+    pub authors: Option<Vec<String>>,
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub support: Option<String>,
+    pub vendor: Option<String>,
+}
+
+/// `[lint.rules]`: `flow lint` rule switches; a workspace `.greentic/lint.toml` overrides them.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct LintSection {
+    #[serde(default)]
+    pub rules: BTreeMap<String, bool>,
+}
+
+/// `[trust]`: the signing policy `pack verify` and `pack run --validate-only` use without
+/// `--policy`.
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct TrustSection {
+    pub policy: Option<TrustPolicy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustPolicy {
+    Strict,
+    Devok,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+pub struct OrgSection {
+    pub name: Option<String>,
+    /// URL or path `org sync` pulled the defaults from.
+    pub source: Option<String>,
+    pub synced_at: Option<String>,
+}
+
 #[derive(Debug, Default, Deserialize, Clone)]
 pub struct DistributorSection {
     /// Configures the default distributor profile by name or inline struct.
@@ -113,6 +180,8 @@ pub struct LoadedGreenticConfig {
     pub config: GreenticConfig,
     pub loaded_from: Option<PathBuf>,
     pub attempted_paths: Vec<PathBuf>,
+    /// The organization defaults layer merged under the config file, when there is one.
+    pub org_layer: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -140,41 +209,80 @@ pub fn load_with_meta(path_override: Option<&str>) -> Result<LoadedGreenticConfi
     let resolution = resolve_config_path(path_override);
     let forced_source = resolution.forced.clone();
     let attempted_paths = resolution.attempted.clone();
+    let org_layer = org_layer_path().filter(|path| path.is_file());
 
-    let Some(selected) = resolution.selected else {
-        return Ok(LoadedGreenticConfig {
-            config: GreenticConfig::default(),
-            loaded_from: None,
-            attempted_paths,
-        });
+    let mut table = match &org_layer {
+        Some(path) => read_table(path)?,
+        None => toml::Table::new(),
+    };
+    let loaded_from = match resolution.selected {
+        Some(selected) if !selected.exists() => {
+            let reason = match forced_source {
+                Some(ConfigSource::Arg) => "explicit config override",
+                Some(ConfigSource::Env(var)) => var,
+                None => "config discovery",
+            };
+            bail!(
+                "config file {} set via {} does not exist (searched: {})",
+                selected.display(),
+                reason,
+                format_attempted(&resolution.attempted)
+            );
+        }
+        Some(selected) => {
+            merge_tables(&mut table, read_table(&selected)?);
+            Some(selected)
+        }
+        None => None,
     };
 
-    if !selected.exists() {
-        let reason = match forced_source {
-            Some(ConfigSource::Arg) => "explicit config override",
-            Some(ConfigSource::Env(var)) => var,
-            None => "config discovery",
-        };
-        bail!(
-            "config file {} set via {} does not exist (searched: {})",
-            selected.display(),
-            reason,
-            format_attempted(&resolution.attempted)
-        );
-    }
-
-    let raw = fs::read_to_string(&selected)
-        .with_context(|| format!("failed to read config at {}", selected.display()))?;
-    let config: GreenticConfig = toml::from_str(&raw)
-        .with_context(|| format!("failed to parse config at {}", selected.display()))?;
+    let config: GreenticConfig = toml::Value::Table(table).try_into().with_context(|| {
+        let sources = loaded_from
+            .iter()
+            .chain(&org_layer)
+            .cloned()
+            .collect::<Vec<_>>();
+        format!("failed to parse config at {}", format_attempted(&sources))
+    })?;
 
     Ok(LoadedGreenticConfig {
         config,
-        loaded_from: Some(selected),
+        loaded_from,
         attempted_paths,
+        org_layer,
     })
 }
 
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config at {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("failed to parse config at {}", path.display()))
+}
+
+/// Overlay `overlay` onto `base`: tables merge key by key, anything else in `overlay` wins.
+pub fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(child)) => {
+                merge_tables(existing, child)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// File name of the organization defaults layer, kept next to the config file.
+pub const ORG_LAYER_FILE: &str = "org.toml";
+
+/// The organization defaults written by `org sync`: `org.toml` in the directory of the first
+/// config file searched. Its values apply wherever the config file does not set them.
+pub fn org_layer_path() -> Option<PathBuf> {
+    let config = config_path()?;
+    Some(config.parent()?.join(ORG_LAYER_FILE))
+}
+
 fn format_attempted(paths: &[PathBuf]) -> String {
     if paths.is_empty() {
         return "(none)".to_string();
@@ -223,26 +331,10 @@ pub fn resolve_config_path(path_override: Option<&str>) -> ConfigResolution {
         }
     }
 
-    let mut candidates = Vec::new();
-    let xdg_config = std::env::var_os("XDG_CONFIG_HOME")
-        .map(PathBuf::from)
-        .or_else(dirs::config_dir);
-    if let Some(mut dir) = xdg_config {
-        dir.push("greentic-dev");
-        dir.push("config.toml");
-        push_unique(&mut candidates, dir);
-    }
-    if let Some(mut home) = dirs::home_dir() {
-        let mut legacy = home.clone();
-        legacy.push(".config");
-        legacy.push("greentic-dev");
-        legacy.push("config.toml");
-        push_unique(&mut candidates, legacy);
-
-        home.push(".greentic");
-        home.push("config.toml");
-        push_unique(&mut candidates, home);
-    }
+    let candidates = discovery_candidates()
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
 
     let selected = candidates.iter().find(|path| path.exists()).cloned();
     attempted.extend(candidates);
@@ -254,12 +346,46 @@ pub fn resolve_config_path(path_override: Option<&str>) -> ConfigResolution {
     }
 }
 
-pub fn config_path() -> Option<PathBuf> {
-    resolve_config_path(None).attempted.into_iter().next()
+/// Where a discovered config file lives: the XDG location, or one of the legacy home-directory
+/// locations kept for older setups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigLocation {
+    Xdg,
+    Legacy,
 }
 
-fn push_unique(vec: &mut Vec<PathBuf>, path: PathBuf) {
-    if !vec.iter().any(|existing| existing == &path) {
-        vec.push(path);
+/// Config files searched when no override is given, in priority order, without duplicates.
+pub fn discovery_candidates() -> Vec<(PathBuf, ConfigLocation)> {
+    let mut candidates: Vec<(PathBuf, ConfigLocation)> = Vec::new();
+    let mut push = |path: PathBuf, location| {
+        if !candidates.iter().any(|(existing, _)| existing == &path) {
+            candidates.push((path, location));
+        }
+    };
+    let xdg_config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(dirs::config_dir);
+    if let Some(dir) = xdg_config {
+        push(
+            dir.join("greentic-dev").join("config.toml"),
+            ConfigLocation::Xdg,
+        );
+    }
+    if let Some(home) = dirs::home_dir() {
+        push(
+            home.join(".config")
+                .join("greentic-dev")
+                .join("config.toml"),
+            ConfigLocation::Legacy,
+        );
+        push(
+            home.join(".greentic").join("config.toml"),
+            ConfigLocation::Legacy,
+        );
     }
+    candidates
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    resolve_config_path(None).attempted.into_iter().next()
 }
@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use crate::cli::CoverageArgs;
+use crate::events;
 
 const SUCCESS_EXIT_CODE: i32 = 0;
 const POLICY_MISSING_EXIT_CODE: i32 = 2;
@@ -18,7 +19,7 @@ pub fn run(args: CoverageArgs) -> Result<()> {
     if exit_code == SUCCESS_EXIT_CODE {
         return Ok(());
     }
-    std::process::exit(exit_code);
+    events::exit(exit_code);
 }
 
 fn run_inner(args: CoverageArgs) -> Result<i32> {
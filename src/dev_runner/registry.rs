@@ -1,7 +1,38 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
 
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use serde_yaml_bw::Value as YamlValue;
 
+use super::schema::compile_schema;
+use crate::state_lock::write_atomic;
+
+/// Workspace-relative location of the imported describe registry.
+pub const REGISTRY_PATH: &str = ".greentic/registry.json";
+/// Current `version` of the registry file format.
+pub const REGISTRY_FILE_VERSION: u32 = 1;
+
+/// On-disk form of a [`DescribeRegistry`], shipped alongside a component catalog so flows can be
+/// validated without the component wasm.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryFile {
+    pub version: u32,
+    #[serde(default)]
+    pub components: BTreeMap<String, RegistryFileEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryFileEntry {
+    pub schema: JsonValue,
+    #[serde(default, skip_serializing_if = "JsonValue::is_null")]
+    pub defaults: JsonValue,
+}
+
 #[derive(Clone, Debug)]
 pub struct ComponentStub {
     pub schema: String,
@@ -37,6 +68,95 @@ inputs:
         Self { stubs }
     }
 
+    /// A registry without the built-in stubs.
+    pub fn empty() -> Self {
+        Self {
+            stubs: HashMap::new(),
+        }
+    }
+
+    /// Register (or replace) a component; returns the stub it replaced.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        stub: ComponentStub,
+    ) -> Option<ComponentStub> {
+        self.stubs.insert(name.into(), stub)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stubs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stubs.is_empty()
+    }
+
+    /// Build a registry from its file form. Every schema must compile so that a broken catalog is
+    /// rejected on import rather than at flow validation time.
+    pub fn from_file(file: RegistryFile) -> Result<Self> {
+        if file.version != REGISTRY_FILE_VERSION {
+            bail!(
+                "unsupported registry version {} (expected {REGISTRY_FILE_VERSION})",
+                file.version
+            );
+        }
+        let mut registry = Self::empty();
+        for (name, entry) in file.components {
+            let schema = serde_json::to_string(&entry.schema)?;
+            compile_schema(&schema).map_err(|err| anyhow::anyhow!("component `{name}`: {err}"))?;
+            let defaults = serde_json::from_value(entry.defaults)
+                .with_context(|| format!("component `{name}`: invalid defaults"))?;
+            registry.insert(name, ComponentStub { schema, defaults });
+        }
+        Ok(registry)
+    }
+
+    pub fn to_file(&self) -> Result<RegistryFile> {
+        let mut components = BTreeMap::new();
+        for (name, stub) in &self.stubs {
+            let schema = serde_json::from_str(&stub.schema)
+                .with_context(|| format!("component `{name}` has an invalid schema"))?;
+            let defaults = serde_json::to_value(&stub.defaults)
+                .with_context(|| format!("component `{name}` has invalid defaults"))?;
+            components.insert(name.clone(), RegistryFileEntry { schema, defaults });
+        }
+        Ok(RegistryFile {
+            version: REGISTRY_FILE_VERSION,
+            components,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents =
+            fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let file: RegistryFile = serde_json::from_slice(&contents)
+            .with_context(|| format!("failed to parse registry {}", path.display()))?;
+        Self::from_file(file).with_context(|| format!("invalid registry {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let mut contents = serde_json::to_string_pretty(&self.to_file()?)?;
+        contents.push('\n');
+        write_atomic(path, contents)
+    }
+
+    /// The built-in stubs plus whatever was imported into `<root>/.greentic/registry.json`.
+    pub fn for_workspace(root: &Path) -> Result<Self> {
+        let mut registry = Self::new();
+        let path = root.join(REGISTRY_PATH);
+        if path.exists() {
+            for (name, stub) in Self::load(&path)?.stubs {
+                registry.insert(name, stub);
+            }
+        }
+        Ok(registry)
+    }
+
     pub fn get_schema(&self, name: &str) -> Option<&str> {
         self.stubs.get(name).map(|stub| stub.schema.as_str())
     }
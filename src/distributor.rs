@@ -60,6 +60,22 @@ pub struct DevResolveResponse {
     pub metadata: serde_json::Value,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DevPublishRequest {
+    pub pack_id: String,
+    pub version: String,
+    pub tenant_id: String,
+    pub environment_id: String,
+    /// `sha256:<hex>` of the uploaded gtpack.
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DevPublishResponse {
+    pub coordinate: String,
+    pub digest: Option<String>,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DevLicenseRequiredErrorBody {
     pub error: String,
@@ -234,8 +250,19 @@ pub struct DevDistributorClient {
 
 impl DevDistributorClient {
     pub fn from_profile(profile: DistributorProfile) -> Result<Self> {
+        Self::with_timeout(profile, Some(Duration::from_secs(30)))
+    }
+
+    /// A client for pack transfers: connecting still times out, but the transfer itself may take
+    /// as long as it needs.
+    pub fn for_transfers(profile: DistributorProfile) -> Result<Self> {
+        Self::with_timeout(profile, None)
+    }
+
+    fn with_timeout(profile: DistributorProfile, timeout: Option<Duration>) -> Result<Self> {
         let client = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(30))
+            .timeout(timeout)
             .build()
             .context("failed to build HTTP client")?;
         Ok(Self {
@@ -290,4 +317,47 @@ impl DevDistributorClient {
         }
         response.bytes().map_err(DevDistributorError::Http)
     }
+
+    /// Request for an artifact's bytes, for callers that stream or resume the download
+    /// themselves (see [`crate::download::fetch`]).
+    pub fn artifact_request(&self, download_path: &str) -> reqwest::blocking::RequestBuilder {
+        let trimmed_base = self.base_url.trim_end_matches('/');
+        let trimmed_path = download_path.trim_start_matches('/');
+        let mut builder = self.http.get(format!("{trimmed_base}/{trimmed_path}"));
+        if let Some(token) = &self.auth_token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder
+    }
+
+    /// Upload a pack for the profile's tenant and environment.
+    pub fn publish_pack(
+        &self,
+        req: &DevPublishRequest,
+        body: reqwest::blocking::Body,
+    ) -> Result<DevPublishResponse, DevDistributorError> {
+        let url = format!("{}/v1/packs/{}/{}", self.base_url, req.pack_id, req.version);
+        let mut builder = self
+            .http
+            .put(url)
+            .query(&[
+                ("tenant_id", req.tenant_id.as_str()),
+                ("environment_id", req.environment_id.as_str()),
+                ("digest", req.digest.as_str()),
+            ])
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(body);
+        if let Some(token) = &self.auth_token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = builder.send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().ok();
+            return Err(DevDistributorError::Status(status, body));
+        }
+        response
+            .json::<DevPublishResponse>()
+            .map_err(|err| DevDistributorError::InvalidResponse(err.into()))
+    }
 }
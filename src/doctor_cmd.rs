@@ -0,0 +1,346 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::blocking::Client;
+use semver::Version;
+use serde::Serialize;
+
+use crate::cli::DoctorArgs;
+use crate::config::{self, LoadedGreenticConfig};
+use crate::distributor::resolve_profile;
+use crate::pack_preflight::CheckStatus;
+use crate::passthrough::{delegated_binary_name, resolve_binary};
+
+/// Exit code when every check passed (warnings allowed).
+pub const EXIT_OK: i32 = 0;
+/// Exit code when at least one check failed.
+pub const EXIT_FAILED: i32 = 1;
+
+/// Delegated binaries greentic-dev runs, and whether a missing one is a failure or a warning.
+const DELEGATES: &[(&str, bool)] = &[
+    ("greentic-pack", true),
+    ("packc", false),
+    ("greentic-component", true),
+    ("greentic-runner-cli", true),
+    ("greentic-gui", false),
+    ("greentic-secrets", false),
+];
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize)]
+struct Finding {
+    stage: &'static str,
+    subject: String,
+    status: CheckStatus,
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport {
+    ok: bool,
+    version: &'static str,
+    failed: usize,
+    warnings: usize,
+    findings: Vec<Finding>,
+}
+
+/// `greentic-dev doctor`: check the delegated binaries, the config file, the cache directories
+/// and the configured distributor profiles, with one summary table.
+pub fn run(args: &DoctorArgs) -> Result<i32> {
+    let own = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let mut findings = Vec::new();
+    for (name, required) in DELEGATES {
+        findings.push(check_delegate(name, *required, &own));
+    }
+    let config = check_config(&mut findings);
+    for dir in cache_dirs() {
+        findings.push(check_cache_dir(&dir));
+    }
+    if let Some(config) = &config {
+        check_profiles(config, &mut findings);
+    }
+
+    let failed = count(&findings, CheckStatus::Fail);
+    let report = DoctorReport {
+        ok: failed == 0,
+        version: env!("CARGO_PKG_VERSION"),
+        failed,
+        warnings: count(&findings, CheckStatus::Warn),
+        findings,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(if report.ok { EXIT_OK } else { EXIT_FAILED })
+}
+
+fn count(findings: &[Finding], status: CheckStatus) -> usize {
+    findings
+        .iter()
+        .filter(|finding| finding.status == status)
+        .count()
+}
+
+fn print_report(report: &DoctorReport) {
+    let width = report
+        .findings
+        .iter()
+        .map(|finding| finding.subject.len())
+        .max()
+        .unwrap_or(0);
+    for finding in &report.findings {
+        let marker = match finding.status {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        };
+        println!(
+            "{marker} {:<8} {:<width$}  {}",
+            finding.stage, finding.subject, finding.detail
+        );
+    }
+    println!(
+        "greentic-dev {}: {} check(s), {} failed, {} warning(s)",
+        report.version,
+        report.findings.len(),
+        report.failed,
+        report.warnings
+    );
+}
+
+fn check_delegate(name: &str, required: bool, own: &Version) -> Finding {
+    let finding = |status, detail| Finding {
+        stage: "binary",
+        subject: delegated_binary_name(name),
+        status,
+        detail,
+    };
+    let bin = match resolve_binary(name) {
+        Ok(bin) => bin,
+        Err(err) => {
+            let status = if required {
+                CheckStatus::Fail
+            } else {
+                CheckStatus::Warn
+            };
+            return finding(status, format!("{err:#}"));
+        }
+    };
+    let output = match Command::new(&bin)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            return finding(
+                CheckStatus::Fail,
+                format!("{} --version exited with {}", bin.display(), output.status),
+            );
+        }
+        Err(err) => {
+            return finding(
+                CheckStatus::Fail,
+                format!("failed to run {}: {err}", bin.display()),
+            );
+        }
+    };
+    match parse_version(&String::from_utf8_lossy(&output.stdout)) {
+        Some(version) if compatible(own, &version) => {
+            finding(CheckStatus::Ok, format!("{version} ({})", bin.display()))
+        }
+        Some(version) => finding(
+            CheckStatus::Fail,
+            format!(
+                "{version} ({}) is not compatible with greentic-dev {own}; expected {}",
+                bin.display(),
+                expected_series(own)
+            ),
+        ),
+        None => finding(
+            CheckStatus::Warn,
+            format!("{} did not report a semver version", bin.display()),
+        ),
+    }
+}
+
+/// First semver token (a leading `v` is allowed) in `--version` output such as
+/// `greentic-pack 0.5.3`.
+fn parse_version(output: &str) -> Option<Version> {
+    output
+        .split_whitespace()
+        .find_map(|token| Version::parse(token.strip_prefix('v').unwrap_or(token)).ok())
+}
+
+/// The toolchain is released in lockstep: tools are compatible within a major version, and
+/// within a minor version before 1.0.
+fn compatible(own: &Version, other: &Version) -> bool {
+    own.major == other.major && (own.major > 0 || own.minor == other.minor)
+}
+
+fn expected_series(own: &Version) -> String {
+    if own.major == 0 {
+        format!("0.{}.x", own.minor)
+    } else {
+        format!("{}.x", own.major)
+    }
+}
+
+fn check_config(findings: &mut Vec<Finding>) -> Option<LoadedGreenticConfig> {
+    match config::load_with_meta(None) {
+        Ok(loaded) => {
+            let detail = match &loaded.loaded_from {
+                Some(path) => format!("loaded {}", path.display()),
+                None => "no config file found; using defaults".to_string(),
+            };
+            findings.push(Finding {
+                stage: "config",
+                subject: "config.toml".to_string(),
+                status: CheckStatus::Ok,
+                detail,
+            });
+            Some(loaded)
+        }
+        Err(err) => {
+            findings.push(Finding {
+                stage: "config",
+                subject: "config.toml".to_string(),
+                status: CheckStatus::Fail,
+                detail: format!("{err:#}"),
+            });
+            None
+        }
+    }
+}
+
+/// Cache directories the toolchain writes to: greentic's component cache and wasmtime's
+/// compilation cache (used by the runner).
+fn cache_dirs() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        found.push(home.join(".greentic").join("cache"));
+    }
+    if let Some(cache) = dirs::cache_dir() {
+        found.push(if cfg!(target_os = "macos") {
+            cache.join("BytecodeAlliance.wasmtime")
+        } else if cfg!(windows) {
+            cache
+                .join("BytecodeAlliance")
+                .join("wasmtime")
+                .join("cache")
+        } else {
+            cache.join("wasmtime")
+        });
+    }
+    found
+}
+
+fn check_cache_dir(dir: &Path) -> Finding {
+    let finding = |status, detail: &str| Finding {
+        stage: "cache",
+        subject: dir.display().to_string(),
+        status,
+        detail: detail.to_string(),
+    };
+    if !dir.exists() {
+        return finding(CheckStatus::Ok, "not created yet");
+    }
+    if !dir.is_dir() {
+        return finding(CheckStatus::Fail, "exists but is not a directory");
+    }
+    match tempfile::tempfile_in(dir) {
+        Ok(_) => finding(CheckStatus::Ok, "writable"),
+        Err(err) => finding(CheckStatus::Fail, &format!("not writable: {err}")),
+    }
+}
+
+fn check_profiles(config: &LoadedGreenticConfig, findings: &mut Vec<Finding>) {
+    let mut names = config
+        .config
+        .distributor_profiles()
+        .into_keys()
+        .collect::<Vec<_>>();
+    names.sort();
+    if names.is_empty() {
+        return;
+    }
+    let client = match Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            findings.push(Finding {
+                stage: "network",
+                subject: "distributor".to_string(),
+                status: CheckStatus::Fail,
+                detail: format!("failed to build HTTP client: {err}"),
+            });
+            return;
+        }
+    };
+    for name in names {
+        let (status, detail) = match resolve_profile(config, Some(&name)) {
+            // Any HTTP response means the distributor is reachable; auth is checked on use.
+            Ok(profile) => match client.get(&profile.url).send() {
+                Ok(response) => (
+                    CheckStatus::Ok,
+                    format!("{} reachable (HTTP {})", profile.url, response.status()),
+                ),
+                Err(err) => (
+                    CheckStatus::Fail,
+                    format!("{} unreachable: {err}", profile.url),
+                ),
+            },
+            Err(err) => (CheckStatus::Fail, format!("{err:#}")),
+        };
+        findings.push(Finding {
+            stage: "network",
+            subject: format!("profile {name}"),
+            status,
+            detail,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_cache_dir, compatible, expected_series, parse_version};
+    use crate::pack_preflight::CheckStatus;
+    use semver::Version;
+
+    #[test]
+    fn versions_are_read_from_version_output() {
+        assert_eq!(
+            parse_version("greentic-pack 0.5.3\n"),
+            Some(Version::new(0, 5, 3))
+        );
+        assert_eq!(parse_version("packc v1.2.0"), Some(Version::new(1, 2, 0)));
+        assert_eq!(parse_version("greentic-gui (dev build)"), None);
+    }
+
+    #[test]
+    fn compatibility_follows_the_release_series() {
+        let v = |raw: &str| Version::parse(raw).unwrap();
+        assert!(compatible(&v("0.5.2"), &v("0.5.9")));
+        assert!(!compatible(&v("0.5.2"), &v("0.4.9")));
+        assert!(compatible(&v("1.2.0"), &v("1.7.1")));
+        assert!(!compatible(&v("1.2.0"), &v("2.0.0")));
+        assert_eq!(expected_series(&v("0.5.2")), "0.5.x");
+    }
+
+    #[test]
+    fn cache_dirs_must_be_writable_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(check_cache_dir(dir.path()).status, CheckStatus::Ok);
+        assert_eq!(
+            check_cache_dir(&dir.path().join("missing")).status,
+            CheckStatus::Ok
+        );
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        assert_eq!(check_cache_dir(&file).status, CheckStatus::Fail);
+    }
+}
@@ -0,0 +1,543 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use greentic_distributor_client::oci_components::{PulledImage, PulledLayer, RegistryClient};
+use oci_distribution::Reference;
+use oci_distribution::RegistryOperation;
+use oci_distribution::client::{Client, ClientConfig, ClientProtocol};
+use oci_distribution::errors::OciDistributionError;
+use oci_distribution::secrets::RegistryAuth;
+use reqwest::StatusCode;
+use reqwest::blocking::RequestBuilder;
+use reqwest::header::{AUTHORIZATION, CONTENT_RANGE, RANGE};
+use sha2::{Digest, Sha256};
+
+use crate::component_resolver::OCI_CACHE_DIR;
+use crate::events;
+use crate::explain::{CodedError, DOWNLOAD_DIGEST_MISMATCH};
+
+pub(crate) const CHUNK: usize = 64 * 1024;
+/// How many times an interrupted download is resumed before giving up.
+pub const DEFAULT_RETRIES: u32 = 5;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DownloadOptions {
+    /// Cap on the transfer rate, in bytes per second.
+    pub limit_rate: Option<u64>,
+    pub retries: u32,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            limit_rate: None,
+            retries: DEFAULT_RETRIES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Downloaded {
+    pub sha256: String,
+    pub blake3: String,
+    pub size: u64,
+}
+
+/// Parse a `--limit-rate` value: a number of bytes per second with an optional `K`, `M` or `G`
+/// suffix (powers of 1024, as with curl), e.g. `500K` or `1.5M`.
+pub fn parse_rate(raw: &str) -> Result<u64, String> {
+    let trimmed = raw.trim();
+    let (number, unit) = match trimmed.char_indices().last() {
+        Some((idx, ch)) if ch.is_ascii_alphabetic() => (&trimmed[..idx], ch.to_ascii_uppercase()),
+        _ => (trimmed, 'B'),
+    };
+    let scale: u64 = match unit {
+        'B' => 1,
+        'K' => 1024,
+        'M' => 1024 * 1024,
+        'G' => 1024 * 1024 * 1024,
+        other => return Err(format!("unknown rate unit `{other}`; use K, M or G")),
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid rate `{raw}`; expected e.g. 500K or 2M"))?;
+    let rate = (value * scale as f64) as u64;
+    if !value.is_finite() || rate == 0 {
+        return Err(format!("rate `{raw}` must be at least 1 byte per second"));
+    }
+    Ok(rate)
+}
+
+/// Download the body of `request` to `dest`.
+///
+/// Bytes land in `<dest>.partial` first. When that file already exists, from an earlier run or
+/// an earlier attempt, the download continues from its end with a `Range` request; a server that
+/// ignores the range restarts it. Dropped connections and 5xx responses are retried up to
+/// `options.retries` times, and a download that still fails keeps its partial file so the next
+/// run picks up where this one stopped. The finished file is checked against `digest`
+/// (`sha256:<hex>`, `blake3:<hex>` or bare sha256 hex) before it is moved into place.
+pub fn fetch(
+    request: impl Fn() -> RequestBuilder,
+    dest: &Path,
+    digest: Option<&str>,
+    options: &DownloadOptions,
+) -> Result<Downloaded> {
+    let partial = partial_path(dest);
+    let mut attempt = 0;
+    loop {
+        match fetch_attempt(&request, &partial, options) {
+            Ok(()) => break,
+            Err(Attempt::Fatal(err)) => return Err(err),
+            Err(Attempt::Retry(err)) => {
+                attempt += 1;
+                let have = fs::metadata(&partial).map(|meta| meta.len()).unwrap_or(0);
+                if attempt > options.retries {
+                    return Err(err.context(format!(
+                        "download interrupted after {attempt} attempts; run the command again to \
+                         resume from {}",
+                        human_bytes(have)
+                    )));
+                }
+                events::warn(format_args!(
+                    "{err:#}; resuming at {} (attempt {attempt}/{})",
+                    human_bytes(have),
+                    options.retries
+                ));
+                thread::sleep(Duration::from_millis(500) * attempt.min(10));
+            }
+        }
+    }
+
+    let downloaded = file_digests(&partial)?;
+    if let Err(err) = verify_digest(digest, &downloaded.sha256, &downloaded.blake3) {
+        // Resuming a corrupt file would only reproduce the mismatch.
+        let _ = fs::remove_file(&partial);
+        return Err(err);
+    }
+    fs::rename(&partial, dest)
+        .with_context(|| format!("failed to move the download to {}", dest.display()))?;
+    events::artifact(dest);
+    Ok(downloaded)
+}
+
+enum Attempt {
+    Retry(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+fn fetch_attempt(
+    request: &impl Fn() -> RequestBuilder,
+    partial: &Path,
+    options: &DownloadOptions,
+) -> Result<(), Attempt> {
+    let mut offset = fs::metadata(partial).map(|meta| meta.len()).unwrap_or(0);
+    let mut builder = request();
+    if offset > 0 {
+        builder = builder.header(RANGE, format!("bytes={offset}-"));
+    }
+    let mut response = builder
+        .send()
+        .map_err(|err| Attempt::Retry(anyhow!(err).context("request failed")))?;
+    let status = response.status();
+    if offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file already holds the whole body; the digest check decides.
+        return Ok(());
+    }
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(Attempt::Retry(anyhow!(
+            "download failed with status {status}"
+        )));
+    }
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(Attempt::Fatal(anyhow!(
+            "download failed with status {status}: {}",
+            body.trim()
+        )));
+    }
+    if status == StatusCode::PARTIAL_CONTENT {
+        if content_range_start(&response) != Some(offset) {
+            let _ = fs::remove_file(partial);
+            return Err(Attempt::Retry(anyhow!(
+                "the server answered with an unexpected range"
+            )));
+        }
+    } else {
+        // A 200 carries the whole body, whether or not a range was asked for.
+        offset = 0;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(offset == 0)
+        .append(offset > 0)
+        .open(partial)
+        .with_context(|| format!("failed to open {}", partial.display()))
+        .map_err(Attempt::Fatal)?;
+    let total = response.content_length().map(|length| offset + length);
+    let mut progress = Progress::new("downloading", total);
+    progress.done = offset;
+    let mut throttle = options.limit_rate.map(Throttle::new);
+    let chunk = options
+        .limit_rate
+        .map_or(CHUNK, |rate| (rate as usize).clamp(1024, CHUNK));
+    let mut buf = vec![0; chunk];
+    loop {
+        let read = match response.read(&mut buf) {
+            Ok(read) => read,
+            Err(err) => {
+                progress.finish();
+                return Err(Attempt::Retry(anyhow!(err).context("connection dropped")));
+            }
+        };
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buf[..read])
+            .with_context(|| format!("failed to write {}", partial.display()))
+            .map_err(Attempt::Fatal)?;
+        progress.advance(read as u64);
+        if let Some(throttle) = &mut throttle {
+            throttle.pace(read as u64);
+        }
+    }
+    progress.finish();
+    if let Some(total) = total
+        && progress.done < total
+    {
+        return Err(Attempt::Retry(anyhow!(
+            "connection closed after {} of {}",
+            human_bytes(progress.done),
+            human_bytes(total)
+        )));
+    }
+    Ok(())
+}
+
+fn content_range_start(response: &reqwest::blocking::Response) -> Option<u64> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.strip_prefix("bytes ")?;
+    range.split_once('-')?.0.trim().parse().ok()
+}
+
+/// Sleeps just enough to keep the average rate since the start of the attempt under the limit.
+struct Throttle {
+    rate: u64,
+    started: Instant,
+    sent: u64,
+}
+
+impl Throttle {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            started: Instant::now(),
+            sent: 0,
+        }
+    }
+
+    fn pace(&mut self, bytes: u64) {
+        self.sent += bytes;
+        let due = Duration::from_secs_f64(self.sent as f64 / self.rate as f64);
+        if let Some(wait) = due.checked_sub(self.started.elapsed()) {
+            thread::sleep(wait);
+        }
+    }
+}
+
+// The distributor reports `sha256:<hex>` or `blake3:<hex>`; a bare hex digest is taken as sha256.
+pub(crate) fn verify_digest(expected: Option<&str>, sha256: &str, blake3: &str) -> Result<()> {
+    let Some(expected) = expected else {
+        events::warn("no digest was reported; the download was not verified");
+        return Ok(());
+    };
+    let actual = match expected.split_once(':') {
+        Some(("sha256", _)) => sha256,
+        Some(("blake3", _)) => blake3,
+        Some((algorithm, _)) => bail!("unsupported digest algorithm `{algorithm}`"),
+        None => sha256.trim_start_matches("sha256:"),
+    };
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(CodedError::new(
+            DOWNLOAD_DIGEST_MISMATCH,
+            format!("digest mismatch: expected {expected}, the download is {actual}"),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub(crate) fn partial_path(out: &Path) -> PathBuf {
+    let mut name = out.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    out.with_file_name(name)
+}
+
+fn file_digests(path: &Path) -> Result<Downloaded> {
+    let mut file =
+        File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut sha256 = Sha256::new();
+    let mut blake3 = blake3::Hasher::new();
+    let mut size = 0;
+    let mut buf = vec![0; CHUNK];
+    loop {
+        let read = file
+            .read(&mut buf)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        sha256.update(&buf[..read]);
+        blake3.update(&buf[..read]);
+        size += read as u64;
+    }
+    Ok(Downloaded {
+        sha256: format!("sha256:{}", hex(&sha256.finalize())),
+        blake3: format!("blake3:{}", blake3.finalize().to_hex()),
+        size,
+    })
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Transfer progress on stderr: a line rewritten in place on a terminal, and only the final
+/// line otherwise.
+pub(crate) struct Progress {
+    label: &'static str,
+    total: Option<u64>,
+    done: u64,
+    shown: Option<u64>,
+    live: bool,
+}
+
+impl Progress {
+    pub(crate) fn new(label: &'static str, total: Option<u64>) -> Self {
+        Self {
+            label,
+            total,
+            done: 0,
+            shown: None,
+            live: io::stderr().is_terminal(),
+        }
+    }
+
+    pub(crate) fn advance(&mut self, bytes: u64) {
+        self.done += bytes;
+        // Redraw once per percent, or per MiB when the size is unknown.
+        let step = match self.total {
+            Some(total) if total > 0 => self.done * 100 / total,
+            _ => self.done / 1_048_576,
+        };
+        if self.live && self.shown != Some(step) {
+            self.shown = Some(step);
+            eprint!("\r{}", self.line());
+        }
+    }
+
+    pub(crate) fn finish(&self) {
+        if self.live {
+            eprintln!("\r{}", self.line());
+        } else {
+            eprintln!("{}", self.line());
+        }
+    }
+
+    fn line(&self) -> String {
+        match self.total {
+            Some(total) if total > 0 => format!(
+                "{} {} / {} ({}%)",
+                self.label,
+                human_bytes(self.done),
+                human_bytes(total),
+                self.done * 100 / total
+            ),
+            _ => format!("{} {}", self.label, human_bytes(self.done)),
+        }
+    }
+}
+
+pub(crate) fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Registry client for OCI component pulls that fetches layer blobs with [`fetch`], so large
+/// components resume after a dropped connection and honour `--limit-rate`. Blobs are staged
+/// under the blob directory (`.greentic/cache/oci/blobs` by default) until the resolver has
+/// cached them.
+#[derive(Clone)]
+pub struct ResumableRegistryClient {
+    inner: Client,
+    auth: RegistryAuth,
+    blob_dir: PathBuf,
+    options: DownloadOptions,
+}
+
+impl ResumableRegistryClient {
+    pub fn with_basic_auth(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            auth: RegistryAuth::Basic(username.into(), password.into()),
+            ..Self::default_client()
+        }
+    }
+
+    pub fn with_blob_dir(mut self, blob_dir: PathBuf) -> Self {
+        self.blob_dir = blob_dir;
+        self
+    }
+
+    pub fn with_options(mut self, options: DownloadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    async fn pull_layer(
+        &self,
+        reference: &Reference,
+        token: Option<String>,
+        media_type: String,
+        digest: String,
+    ) -> Result<PulledLayer> {
+        let url = format!(
+            "https://{}/v2/{}/blobs/{digest}",
+            reference.resolve_registry(),
+            reference.repository()
+        );
+        let dest = self.blob_dir.join(digest.replace(':', "-"));
+        let auth = self.auth.clone();
+        let options = self.options;
+        let expected = digest.clone();
+        let data = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            fs::create_dir_all(dest.parent().expect("blob dir"))
+                .with_context(|| format!("failed to create {}", dest.display()))?;
+            let http = reqwest::blocking::Client::builder()
+                .connect_timeout(Duration::from_secs(30))
+                .build()?;
+            let request = || {
+                let builder = http.get(&url);
+                match (&token, &auth) {
+                    (Some(token), _) => builder.header(AUTHORIZATION, format!("Bearer {token}")),
+                    (None, RegistryAuth::Basic(username, password)) => {
+                        builder.basic_auth(username, Some(password))
+                    }
+                    (None, RegistryAuth::Anonymous) => builder,
+                }
+            };
+            fetch(request, &dest, Some(&expected), &options)?;
+            let data =
+                fs::read(&dest).with_context(|| format!("failed to read {}", dest.display()));
+            let _ = fs::remove_file(&dest);
+            data
+        })
+        .await??;
+        Ok(PulledLayer {
+            media_type,
+            data,
+            digest: Some(digest),
+        })
+    }
+}
+
+#[async_trait]
+impl RegistryClient for ResumableRegistryClient {
+    fn default_client() -> Self {
+        Self {
+            inner: Client::new(ClientConfig {
+                protocol: ClientProtocol::Https,
+                ..Default::default()
+            }),
+            auth: RegistryAuth::Anonymous,
+            blob_dir: Path::new(OCI_CACHE_DIR).join("blobs"),
+            options: DownloadOptions::default(),
+        }
+    }
+
+    async fn pull(
+        &self,
+        reference: &Reference,
+        accepted_manifest_types: &[&str],
+    ) -> Result<PulledImage, OciDistributionError> {
+        let (manifest, digest) = self
+            .inner
+            .pull_image_manifest(reference, &self.auth)
+            .await?;
+        if manifest.layers.is_empty() {
+            return Err(OciDistributionError::PullNoLayersError);
+        }
+        if let Some(layer) = manifest
+            .layers
+            .iter()
+            .find(|layer| !accepted_manifest_types.contains(&layer.media_type.as_str()))
+        {
+            return Err(OciDistributionError::IncompatibleLayerMediaTypeError(
+                layer.media_type.clone(),
+            ));
+        }
+        let token = self
+            .inner
+            .auth(reference, &self.auth, RegistryOperation::Pull)
+            .await?;
+        let mut layers = Vec::with_capacity(manifest.layers.len());
+        for layer in manifest.layers {
+            let pulled = self
+                .pull_layer(reference, token.clone(), layer.media_type, layer.digest)
+                .await
+                .map_err(|err| OciDistributionError::GenericError(Some(format!("{err:#}"))))?;
+            layers.push(pulled);
+        }
+        Ok(PulledImage {
+            digest: Some(digest),
+            layers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rate, partial_path, verify_digest};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn rates_take_binary_suffixes() {
+        assert_eq!(parse_rate("2048"), Ok(2048));
+        assert_eq!(parse_rate("500K"), Ok(500 * 1024));
+        assert_eq!(parse_rate("1.5m"), Ok(3 * 512 * 1024));
+        assert_eq!(parse_rate("1G"), Ok(1 << 30));
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("10X").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+
+    #[test]
+    fn downloads_are_checked_against_the_reported_digest() {
+        let sha256 = "sha256:2fe1c7fa5dc9a8a7a8ae1cf6c1c6ac3e19f1e3bdc79cb4dd8b3cb6b2b0bc3e3d";
+        let blake3 = "blake3:9a1b4c0fd2b36a40e6d1b5fd0c6c2bd5e4a8d8a9f0e1c2b3a4d5e6f708192a3b";
+
+        assert!(verify_digest(Some(sha256), sha256, blake3).is_ok());
+        assert!(verify_digest(Some(&sha256[7..].to_ascii_uppercase()), sha256, blake3).is_ok());
+        assert!(verify_digest(Some(blake3), sha256, blake3).is_ok());
+        assert!(verify_digest(Some("blake3:00"), sha256, blake3).is_err());
+        assert!(verify_digest(Some("md5:00"), sha256, blake3).is_err());
+        assert!(verify_digest(None, sha256, blake3).is_ok());
+    }
+
+    #[test]
+    fn downloads_land_next_to_the_destination() {
+        assert_eq!(
+            partial_path(Path::new("dist/a-1.0.0.gtpack")),
+            PathBuf::from("dist/a-1.0.0.gtpack.partial")
+        );
+    }
+}
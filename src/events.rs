@@ -0,0 +1,242 @@
+//! `--events <stdout|fd:N>`: progress as JSON Lines, so IDE plugins and the daemon can show
+//! live progress without parsing human text. Each line is one event:
+//!
+//! ```json
+//! {"ts":"2026-01-01T10:00:00Z","event":"phase_started","phase":"pack build"}
+//! {"ts":"2026-01-01T10:00:01Z","event":"phase_finished","phase":"pack build","duration_ms":812.4,"ok":true}
+//! ```
+//!
+//! Without `--events` nothing is emitted and the helpers here only do their human part.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+static SINK: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+static COMMAND: Mutex<Option<Phase>> = Mutex::new(None);
+
+/// Where `--events` writes: stdout, interleaved with the command's normal output, or an
+/// inherited file descriptor that carries nothing else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSink {
+    Stdout,
+    Fd(i32),
+}
+
+impl FromStr for EventSink {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "stdout" || value == "-" {
+            return Ok(Self::Stdout);
+        }
+        value
+            .strip_prefix("fd:")
+            .and_then(|fd| fd.parse().ok())
+            .filter(|fd| *fd >= 0)
+            .map(Self::Fd)
+            .ok_or_else(|| format!("expected `stdout` or `fd:<N>`, got `{value}`"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    PhaseStarted {
+        phase: String,
+    },
+    PhaseFinished {
+        phase: String,
+        duration_ms: f64,
+        ok: bool,
+    },
+    Warning {
+        message: String,
+    },
+    ArtifactWritten {
+        path: PathBuf,
+    },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    ts: String,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Start emitting events to `sink` for the rest of the process.
+pub fn open(sink: EventSink) -> Result<()> {
+    let writer: Box<dyn Write + Send> = match sink {
+        EventSink::Stdout => Box::new(io::stdout()),
+        EventSink::Fd(fd) => Box::new(fd_writer(fd)?),
+    };
+    SINK.set(Mutex::new(writer))
+        .map_err(|_| anyhow!("the event stream is already open"))
+}
+
+#[cfg(unix)]
+fn fd_writer(fd: i32) -> Result<std::fs::File> {
+    use std::os::fd::BorrowedFd;
+    // SAFETY: the descriptor is only borrowed long enough to duplicate it; a descriptor that is
+    // not open makes the duplication fail with EBADF.
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+    let owned = borrowed
+        .try_clone_to_owned()
+        .map_err(|err| anyhow!("--events fd:{fd}: {err}"))?;
+    Ok(owned.into())
+}
+
+#[cfg(not(unix))]
+fn fd_writer(fd: i32) -> Result<std::fs::File> {
+    anyhow::bail!("--events fd:{fd} is only supported on Unix; use --events stdout")
+}
+
+/// Whether `--events` is on.
+pub fn enabled() -> bool {
+    SINK.get().is_some()
+}
+
+/// Write one event line. A sink that stops accepting writes (a closed pipe) drops the event
+/// rather than failing the command.
+pub fn emit(event: Event) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    let line = Line {
+        ts: OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default(),
+        event: &event,
+    };
+    let Ok(json) = serde_json::to_string(&line) else {
+        return;
+    };
+    let mut sink = sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _ = writeln!(sink, "{json}").and_then(|()| sink.flush());
+}
+
+/// Print `warning: <message>` on stderr and emit it as a `warning` event.
+pub fn warn(message: impl fmt::Display) {
+    let message = message.to_string();
+    eprintln!("warning: {message}");
+    emit(Event::Warning { message });
+}
+
+/// Emit an `artifact_written` event for a file the command wrote.
+pub fn artifact(path: &Path) {
+    if enabled() {
+        emit(Event::ArtifactWritten {
+            path: path.to_path_buf(),
+        });
+    }
+}
+
+/// A phase in progress; [`Phase::finish`] emits `phase_finished` with its duration.
+#[derive(Debug)]
+pub struct Phase {
+    name: String,
+    started: Instant,
+}
+
+impl Phase {
+    /// Emit `phase_started` for `name`.
+    pub fn start(name: impl Into<String>) -> Self {
+        let name = name.into();
+        emit(Event::PhaseStarted {
+            phase: name.clone(),
+        });
+        Self {
+            name,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(self, ok: bool) {
+        emit(Event::PhaseFinished {
+            phase: self.name,
+            duration_ms: self.started.elapsed().as_secs_f64() * 1000.0,
+            ok,
+        });
+    }
+}
+
+/// Start the phase that spans the whole command, such as `pack build`.
+pub fn start_command(name: impl Into<String>) {
+    let phase = Phase::start(name);
+    *COMMAND
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(phase);
+}
+
+/// Finish the command's phase, once.
+pub fn finish_command(ok: bool) {
+    let phase = COMMAND
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take();
+    if let Some(phase) = phase {
+        phase.finish(ok);
+    }
+}
+
+/// `std::process::exit` for commands that exit with a child's or a report's code: the command's
+/// phase finishes first, ok when `code` is 0.
+pub fn exit(code: i32) -> ! {
+    finish_command(code == 0);
+    std::process::exit(code)
+}
+
+/// Run `work` as the phase `name`; it finished ok when `work` returns `Ok`.
+pub fn phase<T, E>(name: impl Into<String>, work: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let phase = Phase::start(name);
+    let result = work();
+    phase.finish(result.is_ok());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sinks_are_stdout_or_a_descriptor() {
+        assert_eq!("stdout".parse(), Ok(EventSink::Stdout));
+        assert_eq!("-".parse(), Ok(EventSink::Stdout));
+        assert_eq!("fd:3".parse(), Ok(EventSink::Fd(3)));
+        assert!("fd:-1".parse::<EventSink>().is_err());
+        assert!("events.jsonl".parse::<EventSink>().is_err());
+    }
+
+    #[test]
+    fn events_serialize_with_their_kind_and_a_timestamp() {
+        let event = Event::PhaseFinished {
+            phase: "flow parse".to_string(),
+            duration_ms: 1.5,
+            ok: true,
+        };
+        let line = serde_json::to_value(Line {
+            ts: "2026-01-01T00:00:00Z".to_string(),
+            event: &event,
+        })
+        .unwrap();
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "ts": "2026-01-01T00:00:00Z",
+                "event": "phase_finished",
+                "phase": "flow parse",
+                "duration_ms": 1.5,
+                "ok": true
+            })
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Everything a runner invocation depends on, held explicitly: the directory it runs in, the
+//! runner binary, and the environment it gets on top of greentic-dev's own. Runs built from
+//! separate contexts share no process-global state, so they can run side by side.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+use crate::passthrough::resolve_binary;
+
+/// Variable the runner host reads its wasmtime compilation cache location from.
+pub const WASMTIME_CACHE_VAR: &str = "WASMTIME_CACHE_DIR";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionContext {
+    /// Working directory of the run; relative paths resolve against it. Empty means the current
+    /// directory.
+    pub root: PathBuf,
+    /// greentic-runner-cli to start; resolved like any delegated tool when `None`.
+    pub runner: Option<PathBuf>,
+    /// `HOME` for the runner, so its caches and config stay out of the user's home.
+    pub home: Option<PathBuf>,
+    /// Wasmtime cache for the runner (`WASMTIME_CACHE_DIR`).
+    pub cache_dir: Option<PathBuf>,
+    /// Further runner environment; wins over `home` and `cache_dir`.
+    pub env: BTreeMap<String, String>,
+}
+
+impl ExecutionContext {
+    /// A context rooted at `root` whose runner home and wasmtime cache live under
+    /// `root/.greentic/`, so parallel runs in different roots never touch the same state.
+    pub fn isolated(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let state = root.join(".greentic");
+        Self {
+            home: Some(state.join("home")),
+            cache_dir: Some(state.join("cache").join("wasmtime")),
+            root,
+            ..Self::default()
+        }
+    }
+
+    /// `path` as seen from the run: relative paths are taken from `root`. The result is absolute
+    /// unless `root` is empty, so it means the same inside and outside the run.
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() || self.root.as_os_str().is_empty() {
+            return path.to_path_buf();
+        }
+        let path = self.root.join(path);
+        std::path::absolute(&path).unwrap_or(path)
+    }
+
+    /// The runner invocation for `args`, running in `root` with this context's environment. The
+    /// home and cache directories are created when missing.
+    pub fn runner_command(&self, args: &[OsString]) -> Result<Command> {
+        let runner = match &self.runner {
+            Some(runner) => runner.clone(),
+            None => resolve_binary("greentic-runner-cli")?,
+        };
+        let mut command = Command::new(runner);
+        command.args(args);
+        if !self.root.as_os_str().is_empty() {
+            command.current_dir(&self.root);
+        }
+        for (key, dir) in [("HOME", &self.home), (WASMTIME_CACHE_VAR, &self.cache_dir)] {
+            if let Some(dir) = dir {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("failed to create {}", dir.display()))?;
+                command.env(key, dir);
+            }
+        }
+        command.envs(&self.env);
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolated_contexts_keep_runner_state_under_their_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("a");
+        let mut context = ExecutionContext::isolated(&root);
+        context.runner = Some(PathBuf::from("/bin/runner"));
+        context
+            .env
+            .insert("HOME".to_string(), "/override".to_string());
+        assert_eq!(
+            context.resolve(Path::new("dist/a.gtpack")),
+            root.join("dist/a.gtpack")
+        );
+        assert_eq!(context.resolve(Path::new("/abs")), Path::new("/abs"));
+        assert_eq!(
+            ExecutionContext::default().resolve(Path::new("a.gtpack")),
+            Path::new("a.gtpack")
+        );
+
+        let command = context.runner_command(&["--json".into()]).unwrap();
+        assert_eq!(command.get_program(), "/bin/runner");
+        assert_eq!(command.get_current_dir(), Some(root.as_path()));
+        assert!(root.join(".greentic/home").is_dir());
+        let env = command
+            .get_envs()
+            .map(|(key, value)| (key.to_owned(), value.map(|value| value.to_owned())))
+            .collect::<Vec<_>>();
+        assert!(env.contains(&(
+            WASMTIME_CACHE_VAR.into(),
+            Some(root.join(".greentic/cache/wasmtime").into_os_string())
+        )));
+        // Explicit env wins over the isolated home.
+        assert!(env.contains(&("HOME".into(), Some("/override".into()))));
+    }
+}
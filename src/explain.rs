@@ -0,0 +1,179 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+use anyhow::{Result, bail};
+
+use crate::cli::ExplainArgs;
+
+/// Machine-readable codes for errors that have an entry in the `explain` knowledge base.
+pub const RESOLVE_COMPONENT_NOT_FOUND: &str = "RESOLVE_COMPONENT_NOT_FOUND";
+pub const RESOLVE_VERSION_MISMATCH: &str = "RESOLVE_VERSION_MISMATCH";
+pub const RESOLVE_OCI_PULL_FAILED: &str = "RESOLVE_OCI_PULL_FAILED";
+pub const COMPONENT_MANIFEST_INVALID: &str = "COMPONENT_MANIFEST_INVALID";
+pub const DOWNLOAD_DIGEST_MISMATCH: &str = "DOWNLOAD_DIGEST_MISMATCH";
+
+/// Every known code with its explanation. The texts live in `docs/errors/<CODE>.md` so they can
+/// be read on GitHub too; the first line of each is `# <CODE>: <summary>`.
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        RESOLVE_COMPONENT_NOT_FOUND,
+        include_str!("../docs/errors/RESOLVE_COMPONENT_NOT_FOUND.md"),
+    ),
+    (
+        RESOLVE_VERSION_MISMATCH,
+        include_str!("../docs/errors/RESOLVE_VERSION_MISMATCH.md"),
+    ),
+    (
+        RESOLVE_OCI_PULL_FAILED,
+        include_str!("../docs/errors/RESOLVE_OCI_PULL_FAILED.md"),
+    ),
+    (
+        COMPONENT_MANIFEST_INVALID,
+        include_str!("../docs/errors/COMPONENT_MANIFEST_INVALID.md"),
+    ),
+    (
+        DOWNLOAD_DIGEST_MISMATCH,
+        include_str!("../docs/errors/DOWNLOAD_DIGEST_MISMATCH.md"),
+    ),
+];
+
+/// An error tagged with a code from the knowledge base. Its message points at
+/// `greentic-dev explain <CODE>`; the code itself is recovered with [`code_of`].
+#[derive(Debug)]
+pub struct CodedError {
+    code: &'static str,
+    message: String,
+    source: Option<anyhow::Error>,
+}
+
+impl CodedError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Keep `source` as the cause, so the full chain is still printed.
+    pub fn with_source(mut self, source: anyhow::Error) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}; see `greentic-dev explain {}`]",
+            self.message, self.code, self.code
+        )
+    }
+}
+
+impl StdError for CodedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as _)
+    }
+}
+
+/// The first error code anywhere in `err`'s chain.
+pub fn code_of(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<CodedError>())
+        .map(CodedError::code)
+}
+
+/// The explanation for `code`. Lookup ignores case and accepts `-` for `_`.
+pub fn explanation(code: &str) -> Option<(&'static str, &'static str)> {
+    let wanted = code.trim().replace('-', "_").to_ascii_uppercase();
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| *known == wanted)
+        .copied()
+}
+
+/// `greentic-dev explain [<CODE>] [--list]`.
+pub fn run(args: &ExplainArgs) -> Result<()> {
+    let code = match (&args.code, args.list) {
+        (Some(code), false) => code,
+        _ => {
+            for (code, text) in EXPLANATIONS {
+                println!("{code:<30} {}", summary(code, text));
+            }
+            return Ok(());
+        }
+    };
+    let Some((_, text)) = explanation(code) else {
+        let known = EXPLANATIONS
+            .iter()
+            .map(|(code, _)| *code)
+            .collect::<Vec<_>>();
+        bail!(
+            "no explanation for error code `{code}`; known codes: {}",
+            known.join(", ")
+        );
+    };
+    print!("{text}");
+    Ok(())
+}
+
+fn summary<'a>(code: &str, text: &'a str) -> &'a str {
+    let title = text.lines().next().unwrap_or_default();
+    title
+        .trim_start_matches('#')
+        .trim()
+        .trim_start_matches(code)
+        .trim_start_matches(':')
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn every_explanation_is_titled_with_its_code_and_has_the_sections() {
+        for (code, text) in EXPLANATIONS {
+            assert!(
+                text.starts_with(&format!("# {code}: ")),
+                "{code} has a bad title"
+            );
+            assert!(!summary(code, text).is_empty());
+            for section in ["## Causes", "## Resolution order", "## Fixes"] {
+                assert!(text.contains(section), "{code} lacks {section}");
+            }
+        }
+    }
+
+    #[test]
+    fn lookup_is_lenient_about_case_and_dashes() {
+        assert_eq!(
+            explanation("resolve-component-not-found").map(|(code, _)| code),
+            Some(RESOLVE_COMPONENT_NOT_FOUND)
+        );
+        assert!(explanation("NO_SUCH_CODE").is_none());
+    }
+
+    #[test]
+    fn codes_survive_context_and_keep_their_cause() {
+        let err = Err::<(), _>(
+            CodedError::new(RESOLVE_OCI_PULL_FAILED, "pull failed")
+                .with_source(anyhow::anyhow!("401 Unauthorized")),
+        )
+        .context("node `n1`: failed to prepare component")
+        .unwrap_err();
+
+        assert_eq!(code_of(&err), Some(RESOLVE_OCI_PULL_FAILED));
+        let rendered = format!("{err:#}");
+        assert!(rendered.contains("see `greentic-dev explain RESOLVE_OCI_PULL_FAILED`"));
+        assert!(rendered.contains("401 Unauthorized"));
+        assert_eq!(code_of(&anyhow::anyhow!("plain")), None);
+    }
+}
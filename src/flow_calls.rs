@@ -0,0 +1,160 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::pack_meta::read_typed;
+
+/// Flows (and their entrypoint names) a `flow.call` may target, plus the imported packs whose
+/// flows can be called by `pack`.
+#[derive(Debug, Clone, Default)]
+pub struct CallTargets {
+    flows: BTreeMap<String, BTreeSet<String>>,
+    imports: BTreeSet<String>,
+}
+
+impl CallTargets {
+    pub fn add_flow(&mut self, flow_id: &str, flow_doc: &JsonValue) {
+        self.flows
+            .insert(flow_id.to_string(), entrypoints(flow_doc));
+    }
+
+    pub fn add_import(&mut self, pack_id: &str) {
+        self.imports.insert(pack_id.to_string());
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ImportsMeta {
+    #[serde(default)]
+    imports: Vec<ImportMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportMeta {
+    pack_id: String,
+}
+
+// Entrypoint names a flow exposes: `default` (its start node) plus any declared `entrypoints`.
+fn entrypoints(flow_doc: &JsonValue) -> BTreeSet<String> {
+    let mut names = BTreeSet::from(["default".to_string()]);
+    if let Some(declared) = flow_doc.get("entrypoints").and_then(JsonValue::as_object) {
+        names.extend(declared.keys().cloned());
+    }
+    names
+}
+
+/// Check every `flow.call` node in `flow_doc` against the known targets. Returns one message per
+/// unresolved call.
+pub fn check(flow_id: &str, flow_doc: &JsonValue, targets: &CallTargets) -> Vec<String> {
+    check_nodes(flow_id, flow_doc, targets)
+        .into_iter()
+        .map(|(_, message)| message)
+        .collect()
+}
+
+/// [`check`], with the id of the node each message is about.
+pub fn check_nodes(
+    flow_id: &str,
+    flow_doc: &JsonValue,
+    targets: &CallTargets,
+) -> Vec<(String, String)> {
+    let Some(nodes) = flow_doc.get("nodes").and_then(JsonValue::as_object) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    for (node_id, node) in nodes {
+        let mut push = |message: String| errors.push((node_id.clone(), message));
+        let Some(call) = node.get("flow.call") else {
+            continue;
+        };
+        let field = |key: &str| call.get(key).and_then(JsonValue::as_str);
+        let location = format!("{flow_id}: node `{node_id}`");
+        let Some(target) = field("flow") else {
+            push(format!("{location}: flow.call needs a `flow` target"));
+            continue;
+        };
+        if let Some(pack) = field("pack") {
+            // Flows of imported packs are only known once the import is fetched; the import
+            // itself must at least be declared.
+            if !targets.imports.contains(pack) {
+                push(format!(
+                    "{location}: calls `{target}` in pack `{pack}`, which is not imported"
+                ));
+            }
+            continue;
+        }
+        let Some(available) = targets.flows.get(target) else {
+            let known = targets.flows.keys().cloned().collect::<Vec<_>>().join(", ");
+            push(format!(
+                "{location}: calls unknown flow `{target}` (known flows: {known})"
+            ));
+            continue;
+        };
+        if let Some(entry) = field("entrypoint")
+            && !available.contains(entry)
+        {
+            let known = available.iter().cloned().collect::<Vec<_>>().join(", ");
+            push(format!(
+                "{location}: flow `{target}` has no entrypoint `{entry}` (entrypoints: {known})"
+            ));
+        }
+    }
+    errors
+}
+
+/// Start a target set from the imports declared in pack metadata.
+pub fn import_targets(meta: Option<&Path>) -> Result<CallTargets> {
+    let mut targets = CallTargets::default();
+    if let Some(meta) = meta {
+        for import in read_typed::<ImportsMeta>(meta)?.imports {
+            targets.add_import(&import.pack_id);
+        }
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CallTargets, check};
+    use serde_json::json;
+
+    fn targets() -> CallTargets {
+        let mut targets = CallTargets::default();
+        targets.add_flow(
+            "billing",
+            &json!({ "id": "billing", "entrypoints": { "refund": "refund_start" } }),
+        );
+        targets.add_import("dev.greentic.shared");
+        targets
+    }
+
+    #[test]
+    fn resolvable_calls_pass() {
+        let doc = json!({ "nodes": {
+            "charge": { "flow.call": { "flow": "billing" } },
+            "refund": { "flow.call": { "flow": "billing", "entrypoint": "refund" } },
+            "shared": { "flow.call": { "flow": "notify", "pack": "dev.greentic.shared" } },
+            "echo": { "component.exec": { "component": "dev.greentic.echo" } }
+        }});
+        assert!(check("main", &doc, &targets()).is_empty());
+    }
+
+    #[test]
+    fn unresolved_calls_are_reported() {
+        let doc = json!({ "nodes": {
+            "a": { "flow.call": { "flow": "shipping" } },
+            "b": { "flow.call": { "flow": "billing", "entrypoint": "void" } },
+            "c": { "flow.call": { "flow": "notify", "pack": "dev.greentic.other" } },
+            "d": { "flow.call": {} }
+        }});
+        let errors = check("main", &doc, &targets());
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].contains("unknown flow `shipping` (known flows: billing)"));
+        assert!(errors[1].contains("no entrypoint `void` (entrypoints: default, refund)"));
+        assert!(errors[2].contains("not imported"));
+        assert!(errors[3].contains("needs a `flow` target"));
+    }
+}
@@ -0,0 +1,291 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+
+use crate::sarif::OutputFormat;
+use crate::{
+    flow_fmt, flow_graph, flow_lint, flow_operation, flow_stats, flow_test, flow_validate,
+};
+
+// Flow subcommands implemented by greentic-dev itself. Everything else under `flow` is
+// delegated to `greentic-flow`.
+#[derive(Parser, Debug)]
+#[command(name = "flow", bin_name = "greentic-dev flow")]
+#[command(about = "cli.command.flow.about")]
+struct FlowCli {
+    #[command(subcommand)]
+    command: FlowCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FlowCommand {
+    /// cli.command.flow.set_operation.about
+    SetOperation(FlowSetOperationArgs),
+    /// cli.command.flow.lint.about
+    Lint(FlowLintArgs),
+    /// cli.command.flow.validate.about
+    Validate(FlowValidateArgs),
+    /// cli.command.flow.graph.about
+    Graph(FlowGraphArgs),
+    /// cli.command.flow.fmt.about
+    Fmt(FlowFmtArgs),
+    /// cli.command.flow.stats.about
+    Stats(FlowStatsArgs),
+    /// cli.command.flow.test.about
+    Test(FlowTestArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowSetOperationArgs {
+    /// cli.command.flow.set_operation.flow
+    pub flow: PathBuf,
+    /// cli.command.flow.set_operation.node
+    pub node: String,
+    /// cli.command.flow.set_operation.operation
+    pub operation: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowLintArgs {
+    /// cli.command.flow.lint.flows
+    #[arg(required = true)]
+    pub flows: Vec<PathBuf>,
+    /// cli.command.flow.lint.meta
+    #[arg(long = "meta")]
+    pub meta: Option<PathBuf>,
+    /// cli.command.flow.lint.output
+    #[arg(long = "output", value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+// Only `flow validate --output ...` and `--watch` are handled locally; other validations go to
+// greentic-flow.
+#[derive(Args, Debug, Clone)]
+pub struct FlowValidateArgs {
+    /// cli.command.flow.validate.flows
+    #[arg(required = true)]
+    pub flows: Vec<PathBuf>,
+    /// cli.command.flow.validate.output
+    #[arg(long = "output", value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+    /// cli.command.flow.validate.watch
+    #[arg(long = "watch", conflicts_with = "output")]
+    pub watch: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowGraphArgs {
+    /// cli.command.flow.graph.flow
+    pub flow: PathBuf,
+    /// cli.command.flow.graph.format
+    #[arg(long = "format", value_enum, default_value_t = GraphFormat::Mermaid)]
+    pub format: GraphFormat,
+    /// cli.command.flow.graph.out
+    #[arg(long = "out")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowFmtArgs {
+    /// cli.command.flow.fmt.flows
+    #[arg(required = true)]
+    pub flows: Vec<PathBuf>,
+    /// cli.command.flow.fmt.check
+    #[arg(long = "check")]
+    pub check: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowStatsArgs {
+    /// cli.command.flow.stats.flows
+    #[arg(required = true)]
+    pub flows: Vec<PathBuf>,
+    /// cli.command.flow.stats.json
+    #[arg(long = "json")]
+    pub json: bool,
+    /// cli.command.flow.stats.max_complexity
+    #[arg(long = "max-complexity")]
+    pub max_complexity: Option<usize>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FlowTestArgs {
+    /// cli.command.flow.test.flows
+    #[arg(required = true)]
+    pub flows: Vec<PathBuf>,
+    /// cli.command.flow.test.component_dir
+    #[arg(long = "component-dir")]
+    pub component_dir: Option<PathBuf>,
+    /// cli.command.flow.test.meta
+    #[arg(long = "meta")]
+    pub meta: Option<PathBuf>,
+    /// cli.command.flow.test.junit
+    #[arg(long = "junit", value_name = "FILE")]
+    pub junit: Option<PathBuf>,
+    /// cli.command.flow.test.jobs
+    #[arg(long = "jobs", short = 'j', value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+    /// cli.command.flow.test.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Mermaid,
+    Dot,
+}
+
+// Parse `flow` passthrough arguments into a built-in command when greentic-dev implements it.
+// Returns `None` for anything that should keep delegating to greentic-flow.
+pub fn parse_builtin(args: &[OsString], locale: &str) -> Option<FlowCommand> {
+    if !is_builtin(args) {
+        return None;
+    }
+    let argv = std::iter::once(OsString::from("flow")).chain(args.iter().cloned());
+    let command = crate::cli::localized_builtin_command(FlowCli::command(), locale);
+    let matches = command
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|err| err.exit());
+    let cli = FlowCli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    Some(cli.command)
+}
+
+fn is_builtin(args: &[OsString]) -> bool {
+    match args.first().and_then(|arg| arg.to_str()) {
+        Some("set-operation" | "lint" | "graph" | "fmt" | "stats" | "test") => true,
+        Some("validate") => args.iter().any(|arg| {
+            arg == "--output" || arg == "--watch" || arg.to_string_lossy().starts_with("--output=")
+        }),
+        _ => false,
+    }
+}
+
+pub fn run(command: FlowCommand) -> Result<()> {
+    match command {
+        FlowCommand::SetOperation(args) => flow_operation::set_operation(&args),
+        FlowCommand::Lint(args) => flow_lint::run(&args),
+        FlowCommand::Validate(args) if args.watch => flow_validate::watch(&args),
+        FlowCommand::Validate(args) => flow_validate::run(&args),
+        FlowCommand::Graph(args) => flow_graph::run(&args),
+        FlowCommand::Fmt(args) => flow_fmt::run(&args),
+        FlowCommand::Stats(args) => flow_stats::run(&args),
+        FlowCommand::Test(args) => flow_test::run(&args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlowCommand, GraphFormat, OutputFormat, is_builtin, parse_builtin};
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn upstream_subcommands_are_not_intercepted() {
+        assert!(!is_builtin(&args(&["add-step", "flow.ygtc"])));
+        assert!(!is_builtin(&args(&["doctor"])));
+        assert!(!is_builtin(&[]));
+        assert!(!is_builtin(&args(&["validate", "flow.ygtc"])));
+    }
+
+    #[test]
+    fn validate_is_local_only_with_an_output_format_or_watch() {
+        let parsed =
+            parse_builtin(&args(&["validate", "a.ygtc", "--output=sarif"]), "en").expect("builtin");
+        let FlowCommand::Validate(validate) = parsed else {
+            panic!("expected validate");
+        };
+        assert_eq!(validate.flows.len(), 1);
+        assert_eq!(validate.output, OutputFormat::Sarif);
+        assert!(!validate.watch);
+
+        let parsed = parse_builtin(&args(&["validate", "a.ygtc", "b.ygtc", "--watch"]), "en")
+            .expect("builtin");
+        let FlowCommand::Validate(validate) = parsed else {
+            panic!("expected validate");
+        };
+        assert_eq!(validate.flows.len(), 2);
+        assert!(validate.watch);
+    }
+
+    #[test]
+    fn set_operation_is_parsed_locally() {
+        let parsed = parse_builtin(
+            &args(&["set-operation", "flows/main.ygtc", "start", "echo"]),
+            "en",
+        )
+        .expect("builtin command");
+        let FlowCommand::SetOperation(set) = parsed else {
+            panic!("expected set-operation");
+        };
+        assert_eq!(set.node, "start");
+        assert_eq!(set.operation, "echo");
+    }
+
+    #[test]
+    fn lint_takes_several_flows() {
+        let parsed = parse_builtin(&args(&["lint", "a.ygtc", "b.ygtc"]), "en").expect("builtin");
+        let FlowCommand::Lint(lint) = parsed else {
+            panic!("expected lint");
+        };
+        assert_eq!(lint.flows.len(), 2);
+        assert!(lint.meta.is_none());
+    }
+
+    #[test]
+    fn graph_defaults_to_mermaid() {
+        let parsed = parse_builtin(&args(&["graph", "flows/main.ygtc"]), "en").expect("builtin");
+        let FlowCommand::Graph(graph) = parsed else {
+            panic!("expected graph");
+        };
+        assert_eq!(graph.format, GraphFormat::Mermaid);
+        assert!(graph.out.is_none());
+    }
+
+    #[test]
+    fn fmt_takes_several_flows_and_check() {
+        let parsed =
+            parse_builtin(&args(&["fmt", "--check", "a.ygtc", "b.ygtc"]), "en").expect("builtin");
+        let FlowCommand::Fmt(fmt) = parsed else {
+            panic!("expected fmt");
+        };
+        assert_eq!(fmt.flows.len(), 2);
+        assert!(fmt.check);
+    }
+
+    #[test]
+    fn stats_takes_a_complexity_limit() {
+        let parsed = parse_builtin(
+            &args(&["stats", "a.ygtc", "--json", "--max-complexity", "5"]),
+            "en",
+        )
+        .expect("builtin");
+        let FlowCommand::Stats(stats) = parsed else {
+            panic!("expected stats");
+        };
+        assert_eq!(stats.flows.len(), 1);
+        assert!(stats.json);
+        assert_eq!(stats.max_complexity, Some(5));
+    }
+
+    #[test]
+    fn test_takes_flows_and_a_junit_report() {
+        let parsed = parse_builtin(
+            &args(&["test", "a.ygtc", "b.ygtc", "--junit", "report.xml"]),
+            "en",
+        )
+        .expect("builtin");
+        let FlowCommand::Test(test) = parsed else {
+            panic!("expected test");
+        };
+        assert_eq!(test.flows.len(), 2);
+        assert_eq!(test.junit, Some(PathBuf::from("report.xml")));
+        assert!(!test.json);
+    }
+}
@@ -0,0 +1,173 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde_json::Value as JsonValue;
+
+use crate::pack_preflight::resolve_entry;
+
+/// The typed contract a flow declares through its top-level `input_schema` / `output_schema`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowContract {
+    pub input_schema: Option<JsonValue>,
+    pub output_schema: Option<JsonValue>,
+}
+
+impl FlowContract {
+    pub fn from_flow_doc(flow_doc: &JsonValue) -> Self {
+        let schema = |key: &str| flow_doc.get(key).filter(|value| !value.is_null()).cloned();
+        Self {
+            input_schema: schema("input_schema"),
+            output_schema: schema("output_schema"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input_schema.is_none() && self.output_schema.is_none()
+    }
+
+    /// Make sure both declared schemas compile.
+    pub fn compile(&self) -> Result<()> {
+        for (key, schema) in [
+            ("input_schema", &self.input_schema),
+            ("output_schema", &self.output_schema),
+        ] {
+            if let Some(schema) = schema {
+                jsonschema::validator_for(schema)
+                    .map_err(|err| anyhow!("{key} does not compile: {err}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate a run input against `input_schema`. Flows without one accept any input.
+    pub fn validate_input(&self, input: &JsonValue) -> Result<()> {
+        let Some(schema) = &self.input_schema else {
+            return Ok(());
+        };
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|err| anyhow!("input_schema does not compile: {err}"))?;
+        let errors = validator
+            .iter_errors(input)
+            .map(|err| {
+                let path = err.instance_path().to_string();
+                if path.is_empty() {
+                    format!("  - {err}")
+                } else {
+                    format!("  - {path}: {err}")
+                }
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            bail!(
+                "input does not match the flow's input_schema:\n{}",
+                errors.join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Read the contract of `entry` (or the pack's default entry flow) from an opened pack.
+pub fn from_pack(load: &PackLoad, entry: Option<&str>) -> Result<(String, FlowContract)> {
+    let flow_id = resolve_entry(&load.manifest, entry)?;
+    let flow = load
+        .manifest
+        .flows
+        .iter()
+        .find(|flow| flow.id == flow_id)
+        .ok_or_else(|| anyhow!("pack has no flow `{flow_id}`"))?;
+    let yaml = load
+        .files
+        .get(&flow.file_yaml)
+        .ok_or_else(|| anyhow!("pack is missing {}", flow.file_yaml))?;
+    let doc: JsonValue = serde_yaml_bw::from_slice(yaml)
+        .with_context(|| format!("failed to parse {}", flow.file_yaml))?;
+    Ok((flow_id, FlowContract::from_flow_doc(&doc)))
+}
+
+/// Check `pack run --input` against the entry flow's input schema before the runner starts.
+/// Packs that cannot be opened here are left for the runner to report.
+pub fn check_run_input(run_args: &[OsString]) -> Result<()> {
+    let value = |names: &[&str]| {
+        let mut iter = run_args.iter().filter_map(|arg| arg.to_str());
+        while let Some(arg) = iter.next() {
+            for name in names {
+                if arg == *name {
+                    return iter.next().map(str::to_string);
+                }
+                if let Some(value) = arg.strip_prefix(&format!("{name}=")) {
+                    return Some(value.to_string());
+                }
+            }
+        }
+        None
+    };
+    let (Some(pack), Some(input)) = (value(&["--pack", "-p"]), value(&["--input"])) else {
+        return Ok(());
+    };
+    let Ok(input) = serde_json::from_str::<JsonValue>(&input) else {
+        return Ok(());
+    };
+    let Ok(load) = open_pack(Path::new(&pack), SigningPolicy::DevOk) else {
+        return Ok(());
+    };
+    let (flow_id, contract) = from_pack(&load, value(&["--entry"]).as_deref())?;
+    contract
+        .validate_input(&input)
+        .with_context(|| format!("refusing to run flow `{flow_id}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlowContract;
+    use serde_json::json;
+
+    fn contract() -> FlowContract {
+        let doc = serde_yaml_bw::from_str(
+            r#"
+id: greet
+type: messaging
+start: start
+nodes: {}
+input_schema:
+  type: object
+  required: [name]
+  properties:
+    name: { type: string }
+output_schema:
+  type: object
+"#,
+        )
+        .unwrap();
+        FlowContract::from_flow_doc(&doc)
+    }
+
+    #[test]
+    fn reads_declared_schemas() {
+        let contract = contract();
+        assert!(contract.input_schema.is_some() && contract.output_schema.is_some());
+        contract.compile().unwrap();
+        assert!(FlowContract::from_flow_doc(&json!({ "id": "x" })).is_empty());
+    }
+
+    #[test]
+    fn invalid_schemas_do_not_compile() {
+        let contract = FlowContract {
+            output_schema: Some(json!({ "type": 12 })),
+            ..FlowContract::default()
+        };
+        let err = contract.compile().unwrap_err();
+        assert!(err.to_string().contains("output_schema does not compile"));
+    }
+
+    #[test]
+    fn input_is_checked_against_input_schema() {
+        let contract = contract();
+        contract.validate_input(&json!({ "name": "Ada" })).unwrap();
+        let err = contract.validate_input(&json!({ "name": 7 })).unwrap_err();
+        assert!(err.to_string().contains("/name"), "{err}");
+        assert!(contract.validate_input(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use std::fs;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_yaml_bw::{Mapping, Value as YamlValue};
+
+use crate::events;
+use crate::flow_cli::FlowFmtArgs;
+
+/// Top-level flow keys in the order they are written; other keys follow in file order.
+const FLOW_KEY_ORDER: &[&str] = &[
+    "id",
+    "title",
+    "description",
+    "type",
+    "schema_version",
+    "start",
+    "entry",
+    "entrypoints",
+    "parameters",
+    "input_schema",
+    "output_schema",
+    "nodes",
+];
+
+/// Node keys after the component payload, in the order they are written; `routing` comes last.
+const NODE_KEY_ORDER: &[&str] = &[
+    "operation",
+    "in_map",
+    "out_map",
+    "err_map",
+    "output",
+    "when",
+    "retry",
+    "timeout",
+    "telemetry",
+    "questions",
+    "pack_alias",
+    "annotations",
+    "meta",
+    "routing",
+];
+
+const ROUTE_KEY_ORDER: &[&str] = &["status", "to", "out", "reply"];
+
+/// `flow fmt`: rewrite flows in the canonical layout, or with `--check` list the ones that
+/// are not.
+pub fn run(args: &FlowFmtArgs) -> Result<()> {
+    let mut unformatted = Vec::new();
+    for path in &args.flows {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let formatted =
+            format_flow(&raw).with_context(|| format!("failed to format {}", path.display()))?;
+        if formatted == raw {
+            continue;
+        }
+        if args.check {
+            println!("✗ {} is not formatted", path.display());
+        } else {
+            fs::write(path, &formatted)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            events::artifact(path);
+            println!("Formatted {}", path.display());
+        }
+        unformatted.push(path);
+    }
+    if args.check && !unformatted.is_empty() {
+        bail!(
+            "{} flow(s) need formatting; run `greentic-dev flow fmt` on them",
+            unformatted.len()
+        );
+    }
+    if unformatted.is_empty() {
+        println!("✓ {} flow(s) already formatted", args.flows.len());
+    }
+    Ok(())
+}
+
+/// Re-serialise a flow with its keys in canonical order: top-level keys by `FLOW_KEY_ORDER`,
+/// each node's component payload before its other keys, and routing entries as
+/// `status`, `to`, `out`, `reply`. Node order and payload contents are left as written.
+/// Flows with comments are refused, since re-serialising would drop them.
+pub fn format_flow(raw: &str) -> Result<String> {
+    if let Some(line) = first_comment_line(raw) {
+        bail!("line {line} has a comment, which formatting would drop; remove the comments first");
+    }
+    let doc: YamlValue = serde_yaml_bw::from_str(raw)?;
+    let YamlValue::Mapping(flow) = doc else {
+        bail!("a flow must be a mapping");
+    };
+    let mut flow = reorder(flow, FLOW_KEY_ORDER, false);
+    if let Some(YamlValue::Mapping(nodes)) = flow.get_mut("nodes") {
+        for (node_id, node) in nodes.iter_mut() {
+            let YamlValue::Mapping(mapping) = node else {
+                return Err(anyhow!("node {node_id:?} must be a mapping"));
+            };
+            *mapping = format_node(std::mem::take(mapping));
+        }
+    }
+    let formatted = serde_yaml_bw::to_string(&YamlValue::Mapping(flow))?;
+    Ok(indent_sequences(&formatted))
+}
+
+fn format_node(node: Mapping) -> Mapping {
+    let mut node = reorder(node, NODE_KEY_ORDER, true);
+    if let Some(YamlValue::Sequence(routes)) = node.get_mut("routing") {
+        for route in routes.iter_mut() {
+            if let YamlValue::Mapping(mapping) = route {
+                *mapping = reorder(std::mem::take(mapping), ROUTE_KEY_ORDER, false);
+            }
+        }
+    }
+    node
+}
+
+/// Put the keys named in `order` in that order. The remaining keys keep their file order and
+/// go before the ordered ones when `rest_first` (component payloads), after them otherwise.
+fn reorder(mut mapping: Mapping, order: &[&str], rest_first: bool) -> Mapping {
+    let mut ordered = Vec::new();
+    for key in order {
+        if let Some(entry) = mapping.shift_remove_entry(*key) {
+            ordered.push(entry);
+        }
+    }
+    let rest = mapping.into_iter().collect::<Vec<_>>();
+    let (first, second) = if rest_first {
+        (rest, ordered)
+    } else {
+        (ordered, rest)
+    };
+    first.into_iter().chain(second).collect()
+}
+
+/// Indent block sequences under their parent key (`routing:\n  - to: x`), the layout flows are
+/// written in by hand.
+fn indent_sequences(yaml: &str) -> String {
+    let mut out = String::with_capacity(yaml.len());
+    // Indents of the keys whose block sequences are being shifted, with the shift applied.
+    let mut shifted: Vec<(usize, usize)> = Vec::new();
+    let mut previous_key: Option<usize> = None;
+    for line in yaml.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim_start();
+        while let Some(&(key_indent, _)) = shifted.last() {
+            let inside = indent > key_indent || (indent == key_indent && trimmed.starts_with('-'));
+            if inside {
+                break;
+            }
+            shifted.pop();
+        }
+        if (trimmed.starts_with("- ") || trimmed == "-")
+            && let Some(key_indent) = previous_key.filter(|key_indent| *key_indent == indent)
+        {
+            let extra = shifted.last().map_or(0, |(_, extra)| *extra);
+            shifted.push((key_indent, extra + 2));
+        }
+        let extra = shifted.last().map_or(0, |(_, extra)| *extra);
+        if !line.is_empty() {
+            out.extend(std::iter::repeat_n(' ', extra));
+        }
+        out.push_str(line);
+        out.push('\n');
+        previous_key = trimmed.ends_with(':').then_some(indent);
+    }
+    out
+}
+
+/// The 1-based line of the first YAML comment: a `#` at the start of a token, outside quotes
+/// and block scalars.
+fn first_comment_line(raw: &str) -> Option<usize> {
+    let mut block_indent: Option<usize> = None;
+    for (number, line) in raw.lines().enumerate() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim_start();
+        if let Some(parent) = block_indent {
+            if trimmed.is_empty() || indent > parent {
+                continue;
+            }
+            block_indent = None;
+        }
+        let mut quote = None;
+        let mut previous = ' ';
+        for ch in trimmed.chars() {
+            match (quote, ch) {
+                (None, '#') if previous.is_whitespace() => return Some(number + 1),
+                (None, '\'' | '"') if previous.is_whitespace() || previous == ':' => {
+                    quote = Some(ch)
+                }
+                (Some(open), ch) if ch == open => quote = None,
+                _ => {}
+            }
+            previous = ch;
+        }
+        let value = trimmed.trim_end();
+        if value.ends_with(['|', '>'])
+            || value
+                .rsplit_once(' ')
+                .is_some_and(|(_, last)| last.starts_with(['|', '>']) && last.len() <= 3)
+        {
+            block_indent = Some(indent);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NODE_KEY_ORDER, first_comment_line, format_flow};
+    use crate::flow_operation::RESERVED_NODE_KEYS;
+
+    const MESSY: &str = r#"nodes:
+    start:
+        routing:
+        -   out: true
+            status: done
+        -   to: log
+        operation: echo
+        component.exec:
+            component: dev.greentic.echo
+            input: {message: "hi"}
+    log:
+        component.exec: {component: dev.greentic.echo, input: {message: bye}}
+        routing: [{out: true}]
+type: messaging
+start: start
+id: hello-flow
+"#;
+
+    const CANONICAL: &str = r#"id: hello-flow
+type: messaging
+start: start
+nodes:
+  start:
+    component.exec:
+      component: dev.greentic.echo
+      input:
+        message: hi
+    operation: echo
+    routing:
+      - status: done
+        out: true
+      - to: log
+  log:
+    component.exec:
+      component: dev.greentic.echo
+      input:
+        message: bye
+    routing:
+      - out: true
+"#;
+
+    #[test]
+    fn flows_are_written_in_canonical_order() {
+        assert_eq!(format_flow(MESSY).unwrap(), CANONICAL);
+        assert_eq!(format_flow(CANONICAL).unwrap(), CANONICAL);
+    }
+
+    #[test]
+    fn comments_are_refused_rather_than_dropped() {
+        assert_eq!(first_comment_line("id: a\n# note\nnodes: {}\n"), Some(2));
+        assert_eq!(first_comment_line("id: a # trailing\n"), Some(1));
+        assert_eq!(first_comment_line("id: \"a # b\"\nurl: x#y\n"), None);
+        assert_eq!(
+            first_comment_line("text: |\n  # not a comment\nid: a\n"),
+            None
+        );
+        assert!(format_flow("# header\nid: a\nnodes: {}\n").is_err());
+    }
+
+    #[test]
+    fn every_reserved_node_key_has_a_place() {
+        for key in RESERVED_NODE_KEYS {
+            assert!(NODE_KEY_ORDER.contains(key), "{key}");
+        }
+    }
+}
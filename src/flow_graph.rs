@@ -0,0 +1,387 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value as JsonValue;
+use serde_yaml_bw::Value as YamlValue;
+
+use crate::events;
+use crate::flow_cli::{FlowGraphArgs, GraphFormat};
+use crate::flow_operation::RESERVED_NODE_KEYS;
+
+/// A flow's nodes (in file order) and routing edges, ready to render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowGraph {
+    pub id: String,
+    pub start: Option<String>,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub id: String,
+    /// What the node runs: `component (op)`, or `flow.call → <flow>`.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: EdgeTarget,
+    /// The routing entry's `status`, when it has one.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeTarget {
+    Node(String),
+    /// `out: true`: the flow ends here.
+    Out,
+    /// `reply: true`: the node answers the caller.
+    Reply,
+}
+
+/// `flow graph`: draw a flow's routing as Mermaid or Graphviz DOT.
+pub fn run(args: &FlowGraphArgs) -> Result<()> {
+    let raw = fs::read_to_string(&args.flow)
+        .with_context(|| format!("failed to read {}", args.flow.display()))?;
+    let graph = parse(&raw).with_context(|| format!("failed to parse {}", args.flow.display()))?;
+    for edge in &graph.edges {
+        if let EdgeTarget::Node(to) = &edge.to
+            && !graph.nodes.iter().any(|node| &node.id == to)
+        {
+            events::warn(format_args!(
+                "node `{}` routes to `{to}`, which is not a node of this flow",
+                edge.from
+            ));
+        }
+    }
+    let rendered = match args.format {
+        GraphFormat::Mermaid => render_mermaid(&graph),
+        GraphFormat::Dot => render_dot(&graph),
+    };
+    match &args.out {
+        Some(out) => {
+            fs::write(out, rendered)
+                .with_context(|| format!("failed to write {}", out.display()))?;
+            events::artifact(out);
+            println!("Wrote {}", out.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+pub fn parse(raw: &str) -> Result<FlowGraph> {
+    let doc: YamlValue = serde_yaml_bw::from_str(raw)?;
+    let field = |key: &str| doc.get(key).and_then(YamlValue::as_str).map(str::to_string);
+    let id = field("id").ok_or_else(|| anyhow!("flow has no id"))?;
+    let nodes = doc
+        .get("nodes")
+        .and_then(YamlValue::as_mapping)
+        .ok_or_else(|| anyhow!("flow has no nodes"))?;
+
+    let mut graph = FlowGraph {
+        id,
+        start: field("start").or_else(|| field("entry")),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    for (node_id, node) in nodes {
+        let node_id = node_id
+            .as_str()
+            .ok_or_else(|| anyhow!("node ids must be strings"))?
+            .to_string();
+        let node = serde_json::to_value(node)
+            .with_context(|| format!("failed to read node `{node_id}`"))?;
+        for route in node
+            .get("routing")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let label = route
+                .get("status")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string);
+            let flag = |key: &str| route.get(key).and_then(JsonValue::as_bool) == Some(true);
+            let mut targets = Vec::new();
+            if let Some(to) = route.get("to").and_then(JsonValue::as_str) {
+                targets.push(EdgeTarget::Node(to.to_string()));
+            }
+            if flag("out") {
+                targets.push(EdgeTarget::Out);
+            }
+            if flag("reply") {
+                targets.push(EdgeTarget::Reply);
+            }
+            graph.edges.extend(targets.into_iter().map(|to| GraphEdge {
+                from: node_id.clone(),
+                to,
+                label: label.clone(),
+            }));
+        }
+        graph.nodes.push(GraphNode {
+            detail: node_detail(&node),
+            id: node_id,
+        });
+    }
+    Ok(graph)
+}
+
+fn node_detail(node: &JsonValue) -> Option<String> {
+    let node = node.as_object()?;
+    let (key, payload) = node
+        .iter()
+        .find(|(key, _)| !RESERVED_NODE_KEYS.contains(&key.as_str()))?;
+    let text = |value: &JsonValue, key: &str| {
+        value
+            .get(key)
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    };
+    if key == "flow.call" {
+        let target = text(payload, "flow").unwrap_or_else(|| "?".to_string());
+        return Some(match text(payload, "pack") {
+            Some(pack) => format!("flow.call → {pack}:{target}"),
+            None => format!("flow.call → {target}"),
+        });
+    }
+    let component = if key == "component.exec" {
+        text(payload, "component").unwrap_or_else(|| key.clone())
+    } else {
+        key.clone()
+    };
+    let op = text(payload, "op")
+        .or_else(|| text(payload, "operation"))
+        .or_else(|| {
+            node.get("operation")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+        });
+    Some(match op {
+        Some(op) => format!("{component} ({op})"),
+        None => component,
+    })
+}
+
+pub fn render_mermaid(graph: &FlowGraph) -> String {
+    // Mermaid ids are positional so node names never clash with its syntax; names go in labels.
+    let index = |id: &str| graph.nodes.iter().position(|node| node.id == id);
+    let mut dangling = BTreeSet::new();
+    let mut target_id = |to: &EdgeTarget| match to {
+        EdgeTarget::Node(id) => match index(id) {
+            Some(index) => format!("n{index}"),
+            None => {
+                dangling.insert(id.clone());
+                format!("missing_{}", sanitize(id))
+            }
+        },
+        EdgeTarget::Out => "flow_out".to_string(),
+        EdgeTarget::Reply => "flow_reply".to_string(),
+    };
+
+    let mut out = format!("flowchart TD\n    %% flow {}\n", graph.id);
+    if let Some(start) = &graph.start {
+        let to = target_id(&EdgeTarget::Node(start.clone()));
+        let _ = writeln!(out, "    flow_start(( )) --> {to}");
+    }
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let label = match &node.detail {
+            Some(detail) => format!("{}<br/>{detail}", node.id),
+            None => node.id.clone(),
+        };
+        let _ = writeln!(out, "    n{index}[\"{}\"]", mermaid_escape(&label));
+    }
+    let mut terminals = BTreeSet::new();
+    for edge in &graph.edges {
+        let from = target_id(&EdgeTarget::Node(edge.from.clone()));
+        let to = target_id(&edge.to);
+        if !matches!(edge.to, EdgeTarget::Node(_)) {
+            terminals.insert(to.clone());
+        }
+        let _ = match &edge.label {
+            Some(label) => writeln!(out, "    {from} -->|\"{}\"| {to}", mermaid_escape(label)),
+            None => writeln!(out, "    {from} --> {to}"),
+        };
+    }
+    for terminal in terminals {
+        let label = terminal.trim_start_matches("flow_");
+        let _ = writeln!(out, "    {terminal}([{label}])");
+    }
+    for id in dangling {
+        let _ = writeln!(
+            out,
+            "    missing_{}[\"{} (missing)\"]",
+            sanitize(&id),
+            mermaid_escape(&id)
+        );
+    }
+    out
+}
+
+pub fn render_dot(graph: &FlowGraph) -> String {
+    let mut out = format!("digraph {} {{\n    rankdir=TB;\n", dot_quote(&graph.id));
+    out.push_str("    node [shape=box];\n");
+    if let Some(start) = &graph.start {
+        out.push_str("    \"<start>\" [shape=point];\n");
+        let _ = writeln!(out, "    \"<start>\" -> {};", dot_quote(start));
+    }
+    for node in &graph.nodes {
+        let label = match &node.detail {
+            Some(detail) => format!("{}\n{detail}", node.id),
+            None => node.id.clone(),
+        };
+        let _ = writeln!(
+            out,
+            "    {} [label={}];",
+            dot_quote(&node.id),
+            dot_quote(&label)
+        );
+    }
+    let mut terminals = BTreeSet::new();
+    for edge in &graph.edges {
+        let to = match &edge.to {
+            EdgeTarget::Node(id) => dot_quote(id),
+            EdgeTarget::Out => "\"<out>\"".to_string(),
+            EdgeTarget::Reply => "\"<reply>\"".to_string(),
+        };
+        if !matches!(edge.to, EdgeTarget::Node(_)) {
+            terminals.insert(to.clone());
+        }
+        let _ = match &edge.label {
+            Some(label) => writeln!(
+                out,
+                "    {} -> {to} [label={}];",
+                dot_quote(&edge.from),
+                dot_quote(label)
+            ),
+            None => writeln!(out, "    {} -> {to};", dot_quote(&edge.from)),
+        };
+    }
+    for terminal in terminals {
+        let label = terminal.trim_matches(['"', '<', '>']);
+        let _ = writeln!(out, "    {terminal} [shape=oval, label=\"{label}\"];");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn dot_quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EdgeTarget, parse, render_dot, render_mermaid};
+
+    const FLOW: &str = r#"id: support
+type: messaging
+start: classify
+nodes:
+  classify:
+    component.exec:
+      component: "dev.greentic.classifier"
+      op: "classify"
+    routing:
+      - to: answer
+        status: known
+      - to: escalate
+        status: unknown
+  answer:
+    dev.greentic.echo:
+      op: echo
+    routing:
+      - reply: true
+  escalate:
+    flow.call:
+      flow: human-handoff
+    routing:
+      - out: true
+"#;
+
+    #[test]
+    fn parses_nodes_in_file_order_with_routing() {
+        let graph = parse(FLOW).unwrap();
+        assert_eq!(graph.start.as_deref(), Some("classify"));
+        let ids = graph
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["classify", "answer", "escalate"]);
+        assert_eq!(
+            graph.nodes[0].detail.as_deref(),
+            Some("dev.greentic.classifier (classify)")
+        );
+        assert_eq!(
+            graph.nodes[2].detail.as_deref(),
+            Some("flow.call → human-handoff")
+        );
+        assert_eq!(graph.edges.len(), 4);
+        assert_eq!(graph.edges[0].label.as_deref(), Some("known"));
+        assert_eq!(graph.edges[2].to, EdgeTarget::Reply);
+        assert_eq!(graph.edges[3].to, EdgeTarget::Out);
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let mermaid = render_mermaid(&parse(FLOW).unwrap());
+        assert!(mermaid.starts_with("flowchart TD\n"), "{mermaid}");
+        assert!(mermaid.contains("flow_start(( )) --> n0"), "{mermaid}");
+        assert!(
+            mermaid.contains("n0[\"classify<br/>dev.greentic.classifier (classify)\"]"),
+            "{mermaid}"
+        );
+        assert!(mermaid.contains("n0 -->|\"known\"| n1"), "{mermaid}");
+        assert!(mermaid.contains("n1 --> flow_reply"), "{mermaid}");
+        assert!(mermaid.contains("flow_out([out])"), "{mermaid}");
+    }
+
+    #[test]
+    fn renders_dot() {
+        let dot = render_dot(&parse(FLOW).unwrap());
+        assert!(dot.starts_with("digraph \"support\" {"), "{dot}");
+        assert!(
+            dot.contains("\"classify\" -> \"answer\" [label=\"known\"];"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("\"answer\" [label=\"answer\\ndev.greentic.echo (echo)\"];"),
+            "{dot}"
+        );
+        assert!(dot.contains("\"escalate\" -> \"<out>\";"), "{dot}");
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn unknown_targets_are_drawn_as_missing_nodes() {
+        let graph = parse(
+            "id: f\ntype: messaging\nnodes:\n  a:\n    x.y: {}\n    routing:\n      - to: b-2\n",
+        )
+        .unwrap();
+        let mermaid = render_mermaid(&graph);
+        assert!(mermaid.contains("n0 --> missing_b_2"), "{mermaid}");
+        assert!(
+            mermaid.contains("missing_b_2[\"b-2 (missing)\"]"),
+            "{mermaid}"
+        );
+    }
+}
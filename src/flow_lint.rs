@@ -0,0 +1,463 @@
+//! `flow lint`: checks that go beyond schema validation. Each rule has an id that
+//! `.greentic/lint.toml` can switch off:
+//!
+//! ```toml
+//! [rules]
+//! missing-end = false
+//! ```
+//!
+//! `[lint.rules]` in the greentic-dev config (typically synced with `org sync`) sets the same
+//! switches for every workspace; the workspace file wins where both name a rule.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::config;
+use crate::flow_calls::{self, CallTargets};
+use crate::flow_cli::FlowLintArgs;
+use crate::flow_graph::{self, EdgeTarget, FlowGraph};
+use crate::flow_operation::RESERVED_NODE_KEYS;
+use crate::pack_secrets;
+use crate::sarif::{self, Diagnostic, OutputFormat};
+
+/// Workspace-relative location of the lint configuration.
+pub const CONFIG_PATH: &str = ".greentic/lint.toml";
+
+/// Every rule, with what it reports. All of them run unless the configuration turns them off.
+pub const RULES: &[(&str, &str)] = &[
+    (
+        "unresolved-call",
+        "flow.call nodes whose flow, entrypoint or pack does not resolve",
+    ),
+    (
+        "unknown-route",
+        "routing (or `start`) that names a node the flow does not have",
+    ),
+    (
+        "unreachable-node",
+        "nodes no routing path from the start node reaches",
+    ),
+    (
+        "missing-end",
+        "reachable nodes with no path to `out: true` or `reply: true`",
+    ),
+    (
+        "duplicate-id-case",
+        "node ids that differ only by letter case",
+    ),
+    (
+        "unused-secret",
+        "secret requirements that no flow or component of the pack references",
+    ),
+    (
+        "shadowed-builtin",
+        "node ids that reuse a builtin node kind, node key or routing keyword",
+    ),
+];
+
+// Names a node id should not reuse: node kinds, keys that configure a node, and routing keywords.
+const BUILTIN_NAMES: &[&str] = &[
+    "component.exec",
+    "flow.call",
+    "out",
+    "reply",
+    "to",
+    "status",
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rule id to whether it runs; rules not listed run.
+    #[serde(default)]
+    pub rules: BTreeMap<String, bool>,
+}
+
+impl LintConfig {
+    /// Read `path`, or the default configuration when it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config: Self =
+            toml::from_str(&raw).with_context(|| format!("invalid {}", path.display()))?;
+        if let Some(unknown) = config
+            .rules
+            .keys()
+            .find(|rule| !RULES.iter().any(|(id, _)| id == rule))
+        {
+            let known = RULES.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+            bail!(
+                "{}: unknown lint rule `{unknown}` (rules: {})",
+                path.display(),
+                known.join(", ")
+            );
+        }
+        Ok(config)
+    }
+
+    /// Add the switches in `defaults` for rules this configuration does not mention.
+    pub fn with_defaults(mut self, defaults: &BTreeMap<String, bool>) -> Self {
+        for (rule, enabled) in defaults {
+            self.rules.entry(rule.clone()).or_insert(*enabled);
+        }
+        self
+    }
+
+    pub fn enabled(&self, rule: &str) -> bool {
+        self.rules.get(rule).copied().unwrap_or(true)
+    }
+}
+
+/// One problem found by a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub message: String,
+    /// The node the finding is about, when it is about one.
+    pub node: Option<String>,
+}
+
+/// `flow lint <FLOW>... [--meta pack.toml] [--output sarif]`: run the enabled rules over the
+/// flows. `flow.call` targets resolve against all the given flows, and secrets against the
+/// requirements of the pack that holds the metadata (or the current directory).
+pub fn run(args: &FlowLintArgs) -> Result<()> {
+    let config =
+        LintConfig::load(Path::new(CONFIG_PATH))?.with_defaults(&config::load()?.lint.rules);
+    let mut targets = flow_calls::import_targets(args.meta.as_deref())?;
+    let mut flows = Vec::new();
+    for path in &args.flows {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let doc: JsonValue = serde_yaml_bw::from_str(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let graph = flow_graph::parse(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        targets.add_flow(&graph.id, &doc);
+        flows.push((path, raw, graph, doc));
+    }
+
+    let mut diagnostics = Vec::new();
+    for (path, raw, graph, doc) in &flows {
+        diagnostics.extend(lint_flow(graph, doc, &targets).into_iter().map(|finding| {
+            let line = finding
+                .node
+                .as_deref()
+                .and_then(|node| sarif::node_line(raw, node));
+            Diagnostic::new(finding.rule, finding.message, path).at_line(line)
+        }));
+    }
+    let pack_dir = match args.meta.as_deref().and_then(Path::parent) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    diagnostics.extend(unused_secrets(pack_dir)?);
+    diagnostics.retain(|diagnostic| config.enabled(&diagnostic.rule));
+
+    if args.output == OutputFormat::Sarif {
+        sarif::print(RULES, &diagnostics)?;
+    } else if diagnostics.is_empty() {
+        println!("✓ {} flow(s) checked; no lint findings", flows.len());
+    } else {
+        for diagnostic in &diagnostics {
+            println!("✗ {} [{}]", diagnostic.message, diagnostic.rule);
+        }
+    }
+    if !diagnostics.is_empty() {
+        bail!("{} lint finding(s)", diagnostics.len());
+    }
+    Ok(())
+}
+
+/// Every rule except `unused-secret`, which is checked once for the pack rather than per flow.
+pub fn lint_flow(graph: &FlowGraph, doc: &JsonValue, targets: &CallTargets) -> Vec<Finding> {
+    let finding = |rule, node: &str, message| Finding {
+        rule,
+        message,
+        node: Some(node.to_string()),
+    };
+    let flow = &graph.id;
+    let mut findings = flow_calls::check_nodes(flow, doc, targets)
+        .into_iter()
+        .map(|(node, message)| finding("unresolved-call", &node, message))
+        .collect::<Vec<_>>();
+
+    let ids = graph
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    if let Some(start) = &graph.start
+        && !ids.contains(start.as_str())
+    {
+        findings.push(Finding {
+            rule: "unknown-route",
+            message: format!("{flow}: start node `{start}` is not a node of this flow"),
+            node: None,
+        });
+    }
+    for edge in &graph.edges {
+        if let EdgeTarget::Node(to) = &edge.to
+            && !ids.contains(to.as_str())
+        {
+            findings.push(finding(
+                "unknown-route",
+                &edge.from,
+                format!(
+                    "{flow}: node `{}` routes to `{to}`, which is not a node of this flow",
+                    edge.from
+                ),
+            ));
+        }
+    }
+
+    let reachable = reachable(graph);
+    let can_end = can_end(graph);
+    for node in &graph.nodes {
+        let id = &node.id;
+        if !reachable.contains(id.as_str()) {
+            findings.push(finding(
+                "unreachable-node",
+                id,
+                format!("{flow}: node `{id}` is not reachable from the start node"),
+            ));
+        } else if !can_end.contains(id.as_str()) {
+            findings.push(finding(
+                "missing-end",
+                id,
+                format!("{flow}: node `{id}` has no path to an end (`out: true` or `reply: true`)"),
+            ));
+        }
+    }
+
+    let mut by_case = BTreeMap::<String, Vec<&str>>::new();
+    for node in &graph.nodes {
+        by_case
+            .entry(node.id.to_lowercase())
+            .or_default()
+            .push(&node.id);
+    }
+    for same in by_case.values().filter(|same| same.len() > 1) {
+        let names = same
+            .iter()
+            .map(|id| format!("`{id}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        findings.push(finding(
+            "duplicate-id-case",
+            same[1],
+            format!("{flow}: node ids {names} differ only by case"),
+        ));
+    }
+
+    for node in &graph.nodes {
+        if let Some(builtin) = BUILTIN_NAMES
+            .iter()
+            .chain(RESERVED_NODE_KEYS)
+            .find(|name| name.eq_ignore_ascii_case(&node.id))
+        {
+            findings.push(finding(
+                "shadowed-builtin",
+                &node.id,
+                format!(
+                    "{flow}: node `{}` shadows the builtin name `{builtin}`",
+                    node.id
+                ),
+            ));
+        }
+    }
+    findings
+}
+
+// Nodes reachable from the start node, or from the first node when the flow names none.
+fn reachable(graph: &FlowGraph) -> BTreeSet<&str> {
+    let start = graph
+        .start
+        .as_deref()
+        .or_else(|| graph.nodes.first().map(|node| node.id.as_str()));
+    let mut seen = BTreeSet::new();
+    let mut pending = start.into_iter().collect::<Vec<_>>();
+    while let Some(node) = pending.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        pending.extend(graph.edges.iter().filter_map(|edge| match &edge.to {
+            EdgeTarget::Node(to) if edge.from == node => Some(to.as_str()),
+            _ => None,
+        }));
+    }
+    seen
+}
+
+// Nodes from which some routing path ends the flow or replies.
+fn can_end(graph: &FlowGraph) -> BTreeSet<&str> {
+    let mut ends = graph
+        .edges
+        .iter()
+        .filter(|edge| matches!(edge.to, EdgeTarget::Out | EdgeTarget::Reply))
+        .map(|edge| edge.from.as_str())
+        .collect::<BTreeSet<_>>();
+    loop {
+        let before = ends.len();
+        for edge in &graph.edges {
+            if let EdgeTarget::Node(to) = &edge.to
+                && ends.contains(to.as_str())
+            {
+                ends.insert(edge.from.as_str());
+            }
+        }
+        if ends.len() == before {
+            return ends;
+        }
+    }
+}
+
+// Results point at each unused key in the requirements file.
+fn unused_secrets(pack_dir: &Path) -> Result<Vec<Diagnostic>> {
+    let (path, unused) = pack_secrets::unused_requirements(pack_dir)?;
+    let source = fs::read_to_string(&path).unwrap_or_default();
+    Ok(unused
+        .into_iter()
+        .map(|key| {
+            let line = sarif::line_of(&source, &format!("\"{key}\""));
+            Diagnostic::new(
+                "unused-secret",
+                format!(
+                    "{}: secret `{key}` is declared but no flow or component references it",
+                    path.display()
+                ),
+                &path,
+            )
+            .at_line(line)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(raw: &str) -> Vec<Finding> {
+        let graph = flow_graph::parse(raw).unwrap();
+        let doc: JsonValue = serde_yaml_bw::from_str(raw).unwrap();
+        let mut targets = CallTargets::default();
+        targets.add_flow(&graph.id, &doc);
+        lint_flow(&graph, &doc, &targets)
+    }
+
+    fn rules(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|finding| finding.rule).collect()
+    }
+
+    #[test]
+    fn a_well_formed_flow_has_no_findings() {
+        let findings = lint(
+            r#"
+id: greet
+start: start
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - status: ok
+        to: done
+      - status: retry
+        to: start
+  done:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - reply: true
+"#,
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn routing_problems_are_reported_per_node() {
+        let findings = lint(
+            r#"
+id: greet
+start: start
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: missing
+      - to: loop
+  loop:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: loop
+  orphan:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - out: true
+"#,
+        );
+        assert_eq!(
+            rules(&findings),
+            [
+                "unknown-route",
+                "missing-end",
+                "missing-end",
+                "unreachable-node"
+            ]
+        );
+        assert!(findings[0].message.contains("routes to `missing`"));
+        assert!(findings[3].message.contains("node `orphan`"));
+    }
+
+    #[test]
+    fn names_are_checked_for_case_clashes_and_builtins() {
+        let findings = lint(
+            r#"
+id: greet
+nodes:
+  Reply:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: reply
+  reply:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - out: true
+"#,
+        );
+        assert_eq!(
+            rules(&findings),
+            ["duplicate-id-case", "shadowed-builtin", "shadowed-builtin"]
+        );
+        assert!(findings[0].message.contains("`Reply`, `reply`"));
+    }
+
+    #[test]
+    fn config_toggles_rules_and_rejects_unknown_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lint.toml");
+        assert!(LintConfig::load(&path).unwrap().enabled("missing-end"));
+
+        fs::write(&path, "[rules]\nmissing-end = false\n").unwrap();
+        let config = LintConfig::load(&path).unwrap();
+        assert!(!config.enabled("missing-end"));
+        assert!(config.enabled("unreachable-node"));
+
+        let defaults = BTreeMap::from([
+            ("missing-end".to_string(), true),
+            ("unused-secret".to_string(), false),
+        ]);
+        let config = config.with_defaults(&defaults);
+        assert!(!config.enabled("missing-end"));
+        assert!(!config.enabled("unused-secret"));
+
+        fs::write(&path, "[rules]\nmissing_end = false\n").unwrap();
+        let err = LintConfig::load(&path).unwrap_err().to_string();
+        assert!(err.contains("unknown lint rule `missing_end`"), "{err}");
+    }
+}
@@ -0,0 +1,231 @@
+use std::fs;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value as JsonValue;
+
+use crate::events;
+use crate::flow_cli::FlowSetOperationArgs;
+
+// Node keys that configure the node itself rather than naming the component payload.
+pub(crate) const RESERVED_NODE_KEYS: &[&str] = &[
+    "routing",
+    "telemetry",
+    "output",
+    "in_map",
+    "out_map",
+    "err_map",
+    "retry",
+    "timeout",
+    "when",
+    "annotations",
+    "meta",
+    "operation",
+    "questions",
+    "pack_alias",
+];
+
+/// Pin the operation a node invokes so `pack build` no longer has to guess it.
+pub fn set_operation(args: &FlowSetOperationArgs) -> Result<()> {
+    let source = fs::read_to_string(&args.flow)
+        .with_context(|| format!("failed to read {}", args.flow.display()))?;
+    let updated = set_node_operation(&source, &args.node, &args.operation)
+        .with_context(|| format!("failed to update {}", args.flow.display()))?;
+    fs::write(&args.flow, updated)
+        .with_context(|| format!("failed to write {}", args.flow.display()))?;
+    events::artifact(&args.flow);
+    println!(
+        "Set operation `{}` on node `{}` in {}",
+        args.operation,
+        args.node,
+        args.flow.display()
+    );
+    Ok(())
+}
+
+// Edit the YAML text in place (rather than re-serialising) so comments and layout survive. The
+// result is re-parsed to make sure the edit landed where expected.
+fn set_node_operation(source: &str, node_id: &str, operation: &str) -> Result<String> {
+    if operation.trim().is_empty() {
+        bail!("operation must not be empty");
+    }
+    let doc: JsonValue = serde_yaml_bw::from_str(source).context("flow is not valid YAML")?;
+    let payload_key = payload_key(&doc, node_id)?;
+
+    let lines = source.lines().collect::<Vec<_>>();
+    let nodes_line = lines
+        .iter()
+        .position(|line| line.trim_end() == "nodes:")
+        .ok_or_else(|| anyhow!("flow has no block-style `nodes:` mapping"))?;
+    let (node_line, node_indent) = find_key(&lines, nodes_line + 1, 0, node_id)
+        .ok_or_else(|| anyhow!("node `{node_id}` is not written as a block mapping"))?;
+    let (payload_line, payload_indent) = find_key(&lines, node_line + 1, node_indent, &payload_key)
+        .ok_or_else(|| {
+            anyhow!("node `{node_id}` payload `{payload_key}` is not written as a block mapping")
+        })?;
+    let payload_end = block_end(&lines, payload_line + 1, payload_indent);
+    let child_indent = lines[payload_line + 1..payload_end]
+        .iter()
+        .find(|line| !is_blank(line))
+        .map(|line| indent_of(line))
+        .unwrap_or(payload_indent + 2);
+
+    let rendered = render_scalar(operation);
+    let mut out = lines
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    let mut replaced = false;
+    for line in out.iter_mut().take(payload_end).skip(payload_line + 1) {
+        if indent_of(line) != child_indent {
+            continue;
+        }
+        let trimmed = line.trim_start();
+        if let Some(key) = ["op", "operation"]
+            .into_iter()
+            .find(|key| trimmed.starts_with(&format!("{key}:")))
+        {
+            *line = format!("{}{key}: {rendered}", " ".repeat(child_indent));
+            replaced = true;
+        }
+    }
+    if !replaced {
+        out.insert(
+            payload_line + 1,
+            format!("{}op: {rendered}", " ".repeat(child_indent)),
+        );
+    }
+    let mut updated = out.join("\n");
+    if source.ends_with('\n') {
+        updated.push('\n');
+    }
+
+    let check: JsonValue =
+        serde_yaml_bw::from_str(&updated).context("edited flow is not valid YAML")?;
+    let payload = &check["nodes"][node_id][payload_key.as_str()];
+    let applied = ["op", "operation"]
+        .iter()
+        .filter_map(|key| payload.get(*key))
+        .all(|value| value.as_str() == Some(operation));
+    if !applied || payload.get("op").or(payload.get("operation")).is_none() {
+        bail!("could not set the operation on node `{node_id}`; edit the flow manually");
+    }
+    Ok(updated)
+}
+
+fn payload_key(doc: &JsonValue, node_id: &str) -> Result<String> {
+    let node = doc
+        .get("nodes")
+        .and_then(|nodes| nodes.get(node_id))
+        .and_then(JsonValue::as_object)
+        .ok_or_else(|| anyhow!("flow has no node `{node_id}`"))?;
+    if node.contains_key("component.exec") {
+        return Ok("component.exec".to_string());
+    }
+    let mut candidates = node
+        .keys()
+        .filter(|key| !RESERVED_NODE_KEYS.contains(&key.as_str()));
+    match (candidates.next(), candidates.next()) {
+        (Some(key), None) => Ok(key.clone()),
+        _ => bail!("cannot tell which component payload of node `{node_id}` to update"),
+    }
+}
+
+// Find `key:` (optionally quoted) on its own line, indented deeper than `parent_indent`, within
+// the block that starts at `start`.
+pub(crate) fn find_key(
+    lines: &[&str],
+    start: usize,
+    parent_indent: usize,
+    key: &str,
+) -> Option<(usize, usize)> {
+    let end = block_end(lines, start, parent_indent);
+    let child_indent = lines[start..end]
+        .iter()
+        .find(|line| !is_blank(line))
+        .map(|line| indent_of(line))?;
+    let candidates = [
+        format!("{key}:"),
+        format!("\"{key}\":"),
+        format!("'{key}':"),
+    ];
+    (start..end)
+        .find(|&idx| {
+            indent_of(lines[idx]) == child_indent
+                && candidates.iter().any(|c| lines[idx].trim() == c.as_str())
+        })
+        .map(|idx| (idx, child_indent))
+}
+
+pub(crate) fn block_end(lines: &[&str], start: usize, parent_indent: usize) -> usize {
+    (start..lines.len())
+        .find(|&idx| !is_blank(lines[idx]) && indent_of(lines[idx]) <= parent_indent)
+        .unwrap_or(lines.len())
+}
+
+pub(crate) fn is_blank(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+pub(crate) fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+pub(crate) fn render_scalar(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+    {
+        value.to_string()
+    } else {
+        serde_json::to_string(value).expect("string serializes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::set_node_operation;
+
+    const FLOW: &str = r#"id: hello-flow
+type: messaging
+start: start
+nodes:
+  # greet the user
+  start:
+    component.exec:
+      component: "dev.greentic.echo"
+      input:
+        message: "hi"
+    routing:
+      - to: log
+  log:
+    component.exec:
+      component: "dev.greentic.echo"
+      op: "echo"
+    routing:
+      - out: true
+"#;
+
+    #[test]
+    fn inserts_missing_operation_and_keeps_comments() {
+        let updated = set_node_operation(FLOW, "start", "echo").unwrap();
+        assert!(updated.contains("  # greet the user\n"));
+        assert!(updated.contains(
+            "    component.exec:\n      op: echo\n      component: \"dev.greentic.echo\"\n"
+        ));
+    }
+
+    #[test]
+    fn replaces_existing_operation() {
+        let updated = set_node_operation(FLOW, "log", "shout").unwrap();
+        assert!(updated.contains("      op: shout\n"));
+        assert!(!updated.contains("op: \"echo\""));
+        assert_eq!(updated.matches("op:").count(), 1);
+    }
+
+    #[test]
+    fn unknown_node_is_an_error() {
+        let err = set_node_operation(FLOW, "missing", "echo").unwrap_err();
+        assert!(err.to_string().contains("no node `missing`"));
+    }
+}
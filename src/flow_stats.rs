@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use serde_yaml_bw::Value as YamlValue;
+
+use crate::flow_cli::FlowStatsArgs;
+use crate::flow_graph::{self, EdgeTarget, FlowGraph};
+use crate::flow_operation::RESERVED_NODE_KEYS;
+use crate::pack_secrets;
+
+/// Complexity metrics for one flow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlowStats {
+    pub flow: String,
+    pub id: String,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Most routing targets leaving a single node.
+    pub max_fan_out: usize,
+    /// Nodes on the longest path from the start node, not following loops back.
+    pub max_depth: usize,
+    /// McCabe complexity of the routing: one plus the extra branches of every node.
+    pub cyclomatic: usize,
+    /// `flow.call` nodes and nodes running an HTTP component.
+    pub external_calls: usize,
+    /// Distinct `secrets://` URIs and `secrets.<NAME>` template references.
+    pub secrets: BTreeSet<String>,
+}
+
+/// `flow stats`: print complexity metrics per flow, and with `--max-complexity` fail when a flow
+/// is over the limit.
+pub fn run(args: &FlowStatsArgs) -> Result<()> {
+    let mut all = Vec::new();
+    for path in &args.flows {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let stats = compute(&raw).with_context(|| format!("failed to parse {}", path.display()))?;
+        all.push(FlowStats {
+            flow: path.display().to_string(),
+            ..stats
+        });
+    }
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&all)?);
+    } else {
+        print_table(&all);
+    }
+    if let Some(limit) = args.max_complexity {
+        let over = all
+            .iter()
+            .filter(|stats| stats.cyclomatic > limit)
+            .collect::<Vec<_>>();
+        for stats in &over {
+            eprintln!(
+                "✗ {} has cyclomatic complexity {} (limit {limit})",
+                stats.flow, stats.cyclomatic
+            );
+        }
+        if !over.is_empty() {
+            bail!("{} flow(s) exceed the complexity limit", over.len());
+        }
+    }
+    Ok(())
+}
+
+/// Metrics for a flow document. `flow` is left empty for the caller to fill in.
+pub fn compute(raw: &str) -> Result<FlowStats> {
+    let graph = flow_graph::parse(raw)?;
+    let doc: YamlValue = serde_yaml_bw::from_str(raw)?;
+    let doc = serde_json::to_value(&doc)?;
+
+    let mut fan_out = BTreeMap::<&str, usize>::new();
+    for edge in &graph.edges {
+        *fan_out.entry(edge.from.as_str()).or_default() += 1;
+    }
+    let branches = fan_out
+        .values()
+        .map(|targets| targets.saturating_sub(1))
+        .sum::<usize>();
+
+    let nodes = doc
+        .get("nodes")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten();
+    let mut external_calls = 0;
+    let mut secrets = BTreeSet::new();
+    for (_, node) in nodes {
+        if is_external_call(node) {
+            external_calls += 1;
+        }
+        pack_secrets::references(node, &mut secrets);
+    }
+
+    Ok(FlowStats {
+        flow: String::new(),
+        id: graph.id.clone(),
+        nodes: graph.nodes.len(),
+        edges: graph.edges.len(),
+        max_fan_out: fan_out.values().copied().max().unwrap_or(0),
+        max_depth: max_depth(&graph),
+        cyclomatic: branches + 1,
+        external_calls,
+        secrets,
+    })
+}
+
+fn is_external_call(node: &JsonValue) -> bool {
+    let Some(node) = node.as_object() else {
+        return false;
+    };
+    let Some((key, payload)) = node
+        .iter()
+        .find(|(key, _)| !RESERVED_NODE_KEYS.contains(&key.as_str()))
+    else {
+        return false;
+    };
+    if key == "flow.call" {
+        return true;
+    }
+    let component = match key.as_str() {
+        "component.exec" => payload
+            .get("component")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default(),
+        other => other,
+    };
+    component
+        .split(['.', '-', '_', '/'])
+        .any(|segment| segment.eq_ignore_ascii_case("http"))
+}
+
+/// Nodes on the longest routing path from the start node (or the first node). Edges back to a
+/// node already on the path are loops and are not followed.
+fn max_depth(graph: &FlowGraph) -> usize {
+    let Some(start) = graph
+        .start
+        .as_deref()
+        .or_else(|| graph.nodes.first().map(|node| node.id.as_str()))
+    else {
+        return 0;
+    };
+    let mut on_path = Vec::new();
+    depth_from(graph, start, &mut on_path)
+}
+
+fn depth_from<'a>(graph: &'a FlowGraph, node: &'a str, on_path: &mut Vec<&'a str>) -> usize {
+    if on_path.contains(&node) || !graph.nodes.iter().any(|candidate| candidate.id == node) {
+        return 0;
+    }
+    on_path.push(node);
+    let deepest = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.from == node)
+        .filter_map(|edge| match &edge.to {
+            EdgeTarget::Node(to) => Some(to.as_str()),
+            EdgeTarget::Out | EdgeTarget::Reply => None,
+        })
+        .map(|to| depth_from(graph, to, on_path))
+        .max()
+        .unwrap_or(0);
+    on_path.pop();
+    deepest + 1
+}
+
+fn print_table(all: &[FlowStats]) {
+    let width = all
+        .iter()
+        .map(|stats| stats.flow.len())
+        .chain(std::iter::once("FLOW".len()))
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:<width$}  NODES  FAN-OUT  DEPTH  CYCLOMATIC  EXTERNAL  SECRETS",
+        "FLOW"
+    );
+    for stats in all {
+        println!(
+            "{:<width$}  {:>5}  {:>7}  {:>5}  {:>10}  {:>8}  {:>7}",
+            stats.flow,
+            stats.nodes,
+            stats.max_fan_out,
+            stats.max_depth,
+            stats.cyclomatic,
+            stats.external_calls,
+            stats.secrets.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compute;
+
+    const FLOW: &str = r#"id: support
+start: triage
+nodes:
+  triage:
+    component.exec:
+      component: acme.classifier
+      input:
+        token: "{{ secrets.CLASSIFIER_KEY }}"
+    routing:
+      - status: billing
+        to: billing
+      - status: tech
+        to: lookup
+      - status: other
+        out: true
+  lookup:
+    http.request:
+      url: https://status.example.com
+      auth: secrets://dev/acme/_/configs/status
+    routing:
+      - to: answer
+  billing:
+    flow.call:
+      flow: billing
+    routing:
+      - to: answer
+  answer:
+    component.exec:
+      component: acme.reply
+      input:
+        text: "see secrets.md"
+    routing:
+      - reply: true
+      - to: triage
+"#;
+
+    #[test]
+    fn metrics_cover_routing_calls_and_secrets() {
+        let stats = compute(FLOW).unwrap();
+        assert_eq!(stats.nodes, 4);
+        assert_eq!(stats.edges, 7);
+        assert_eq!(stats.max_fan_out, 3);
+        // triage -> lookup -> answer; the loop back to triage is not followed.
+        assert_eq!(stats.max_depth, 3);
+        // triage branches three ways, answer two: 1 + 2 + 1.
+        assert_eq!(stats.cyclomatic, 4);
+        assert_eq!(stats.external_calls, 2);
+        assert_eq!(
+            stats.secrets.into_iter().collect::<Vec<_>>(),
+            [
+                "secrets.CLASSIFIER_KEY",
+                "secrets://dev/acme/_/configs/status"
+            ]
+        );
+    }
+
+    #[test]
+    fn a_straight_flow_has_complexity_one() {
+        let stats = compute(include_str!("../tests/fixtures/hello-pack/hello-flow.ygtc")).unwrap();
+        assert_eq!(stats.nodes, 2);
+        assert_eq!(stats.max_fan_out, 1);
+        assert_eq!(stats.max_depth, 2);
+        assert_eq!(stats.cyclomatic, 1);
+        assert_eq!(stats.external_calls, 0);
+        assert!(stats.secrets.is_empty());
+    }
+}
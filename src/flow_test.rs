@@ -0,0 +1,563 @@
+//! `flow test`: fixture-driven flow tests. YAML cases in a `tests/` directory next to a flow give
+//! an input, mock settings and what the run must produce; each case runs the flow through
+//! greentic-runner-cli with only its own mocks, and the outcome is reported per case and, for CI,
+//! as JUnit XML.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::events;
+use crate::exec_context::ExecutionContext;
+use crate::flow_cli::FlowTestArgs;
+use crate::mock_scenario::Scenario;
+use crate::pack_build::{self, PackSigning};
+use crate::passthrough::resolve_binary;
+use crate::run_result::{self, RunReport, RunStatus, TraceStatus};
+use crate::util::parallel::parallel_map;
+
+/// Directory next to a flow that holds its test cases.
+pub const TESTS_DIR: &str = "tests";
+
+/// One test case file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestCase {
+    /// Shown in reports; the file name without extension when left out.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Id of the flow under test; only needed when several flows share the directory.
+    #[serde(default)]
+    pub flow: Option<String>,
+    #[serde(default)]
+    pub input: Option<JsonValue>,
+    /// Named mock scenario (`.greentic/mocks/scenarios/<NAME>/`) the case starts from.
+    #[serde(default)]
+    pub scenario: Option<String>,
+    /// `MOCK_*` settings, on top of the scenario's.
+    #[serde(default)]
+    pub mocks: BTreeMap<String, JsonValue>,
+    #[serde(default)]
+    pub expect: Expect,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expect {
+    /// `succeeded` unless given.
+    #[serde(default)]
+    pub status: Option<RunStatus>,
+    /// Fields the flow output must contain; other fields are not compared.
+    #[serde(default)]
+    pub output: Option<JsonValue>,
+    /// Nodes the run must execute, in order; skipped nodes do not count.
+    #[serde(default)]
+    pub visits: Option<Vec<String>>,
+    /// Error code of a failed run.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseOutcome {
+    pub flow: String,
+    pub case: String,
+    pub file: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    /// Expectations the run did not meet.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    /// Why the case could not run at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn run(args: &FlowTestArgs) -> Result<()> {
+    let runner = resolve_binary("greentic-runner-cli")?;
+    let workdir = tempfile::tempdir().context("failed to create a build directory")?;
+    let jobs = args
+        .jobs
+        .map(usize::from)
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+    let mut outcomes = Vec::new();
+    for (index, flow) in args.flows.iter().enumerate() {
+        let dir = workdir.path().join(format!("flow-{index}"));
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        outcomes.extend(test_flow(flow, &dir, &runner, jobs, args)?);
+    }
+    if outcomes.is_empty() {
+        bail!(
+            "no test cases found; put YAML cases in a `{TESTS_DIR}/` directory next to each flow"
+        );
+    }
+
+    if let Some(path) = &args.junit {
+        fs::write(path, junit(&outcomes))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        events::artifact(path);
+    }
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outcomes)?);
+    } else {
+        print_outcomes(&outcomes);
+    }
+    let failed = outcomes.iter().filter(|outcome| !outcome.ok).count();
+    if failed > 0 {
+        bail!("{failed} of {} flow test(s) failed", outcomes.len());
+    }
+    Ok(())
+}
+
+/// Build `flow` into `dir` and run its cases, `jobs` at a time. Each case runs in its own
+/// directory under `dir`, with its own runner home and cache.
+fn test_flow(
+    flow: &Path,
+    dir: &Path,
+    runner: &Path,
+    jobs: usize,
+    args: &FlowTestArgs,
+) -> Result<Vec<CaseOutcome>> {
+    let raw =
+        fs::read_to_string(flow).with_context(|| format!("failed to read {}", flow.display()))?;
+    let doc: JsonValue = serde_yaml_bw::from_str(&raw)
+        .with_context(|| format!("failed to parse {}", flow.display()))?;
+    let flow_id = doc
+        .get("id")
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| anyhow!("{} has no `id`", flow.display()))?
+        .to_string();
+    let cases = cases_for(flow, &flow_id)?;
+    if cases.is_empty() {
+        events::warn(format_args!(
+            "no test cases for {} in {}",
+            flow.display(),
+            tests_dir(flow).display()
+        ));
+        return Ok(Vec::new());
+    }
+
+    // Every case of a flow that does not build is an error, so CI reports still list them.
+    let pack = dir.join("flow.gtpack");
+    let built = pack_build::run(
+        flow,
+        &pack,
+        PackSigning::Dev,
+        args.meta.as_deref(),
+        args.component_dir.as_deref(),
+    );
+    let cases = cases.iter().enumerate().collect::<Vec<_>>();
+    Ok(parallel_map(&cases, jobs, |&(index, (file, case))| {
+        let name = case.name.clone().unwrap_or_else(|| file_stem(file));
+        let started = Instant::now();
+        let context = ExecutionContext {
+            runner: Some(runner.to_path_buf()),
+            ..ExecutionContext::isolated(dir.join(format!("case-{index}")))
+        };
+        let (failures, error) = match &built {
+            Ok(_) => match run_case(case, &pack, &flow_id, &context) {
+                Ok(report) => (check(&case.expect, &report), None),
+                Err(err) => (Vec::new(), Some(format!("{err:#}"))),
+            },
+            Err(err) => (Vec::new(), Some(format!("flow does not build: {err:#}"))),
+        };
+        CaseOutcome {
+            flow: flow_id.clone(),
+            case: name,
+            file: file.display().to_string(),
+            ok: failures.is_empty() && error.is_none(),
+            duration_ms: millis(started.elapsed()),
+            failures,
+            error,
+        }
+    }))
+}
+
+fn tests_dir(flow: &Path) -> PathBuf {
+    flow.parent().unwrap_or(Path::new("")).join(TESTS_DIR)
+}
+
+/// The cases under the flow's `tests/` directory that target it, by file name. A case without
+/// `flow` targets the flow only when it is the only one in its directory.
+fn cases_for(flow: &Path, flow_id: &str) -> Result<Vec<(PathBuf, TestCase)>> {
+    let dir = tests_dir(flow);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = fs::read_dir(&dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml" | "yml")
+            )
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut cases = Vec::new();
+    for file in files {
+        let raw = fs::read_to_string(&file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        let case: TestCase = serde_yaml_bw::from_str(&raw)
+            .with_context(|| format!("{} is not a valid test case", file.display()))?;
+        let targets = match &case.flow {
+            Some(id) => id == flow_id,
+            None => {
+                let flows = sibling_flows(flow)?;
+                if flows > 1 {
+                    bail!(
+                        "{}: set `flow:`; {} holds {flows} flows",
+                        file.display(),
+                        flow.parent().unwrap_or(Path::new(".")).display()
+                    );
+                }
+                true
+            }
+        };
+        if targets {
+            cases.push((file, case));
+        }
+    }
+    Ok(cases)
+}
+
+fn sibling_flows(flow: &Path) -> Result<usize> {
+    let dir = match flow.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Ok(fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("ygtc"))
+        .count())
+}
+
+fn run_case(
+    case: &TestCase,
+    pack: &Path,
+    flow_id: &str,
+    context: &ExecutionContext,
+) -> Result<RunReport> {
+    let mut scenario = match &case.scenario {
+        Some(name) => Scenario::load(name)?,
+        None => Scenario::default(),
+    };
+    for (key, value) in &case.mocks {
+        if !key.starts_with("MOCK_") {
+            bail!("`{key}` is not a MOCK_* setting");
+        }
+        let value = match value {
+            JsonValue::String(value) => value.clone(),
+            JsonValue::Bool(_) | JsonValue::Number(_) => value.to_string(),
+            _ => bail!("mock setting `{key}` must be a string, number or boolean"),
+        };
+        scenario.mocks.insert(key.clone(), value);
+    }
+
+    let mut args: Vec<OsString> = vec![
+        "--pack".into(),
+        pack.into(),
+        "--entry".into(),
+        flow_id.into(),
+    ];
+    if let Some(input) = &case.input {
+        args.extend(["--input".into(), input.to_string().into()]);
+    }
+    args.push("--json".into());
+    let mut command = context.runner_command(&args)?;
+    scenario.apply(&mut command);
+    run_result::run_report(command)
+}
+
+/// What about `report` does not match `expect`.
+fn check(expect: &Expect, report: &RunReport) -> Vec<String> {
+    let mut failures = Vec::new();
+    let status = expect.status.unwrap_or(RunStatus::Succeeded);
+    if report.status != status {
+        let mut failure = format!(
+            "status: expected {}, got {}",
+            status_name(status),
+            status_name(report.status)
+        );
+        if let Some(error) = &report.error {
+            let _ = write!(failure, " ({}: {})", error.code, error.message);
+        }
+        failures.push(failure);
+    }
+    if let Some(code) = &expect.error {
+        match &report.error {
+            Some(error) if &error.code == code => {}
+            Some(error) => failures.push(format!("error: expected {code}, got {}", error.code)),
+            None => failures.push(format!("error: expected {code}, got none")),
+        }
+    }
+    if let Some(expected) = &expect.output {
+        match &report.output {
+            Some(actual) => compare("output", expected, actual, &mut failures),
+            None => failures.push("output: expected an output, got none".to_string()),
+        }
+    }
+    if let Some(expected) = &expect.visits {
+        let visited = report
+            .trace
+            .iter()
+            .filter(|entry| entry.status != TraceStatus::Skipped)
+            .map(|entry| entry.node_id.as_str())
+            .collect::<Vec<_>>();
+        if visited != *expected {
+            failures.push(format!(
+                "visits: expected [{}], got [{}]",
+                expected.join(", "),
+                visited.join(", ")
+            ));
+        }
+    }
+    failures
+}
+
+// Objects only need the expected fields; arrays and scalars must match exactly.
+pub(crate) fn compare(
+    path: &str,
+    expected: &JsonValue,
+    actual: &JsonValue,
+    failures: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (JsonValue::Object(expected), JsonValue::Object(actual)) => {
+            for (key, expected) in expected {
+                let path = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(actual) => compare(&path, expected, actual, failures),
+                    None => failures.push(format!("{path}: missing")),
+                }
+            }
+        }
+        (JsonValue::Array(items), JsonValue::Array(actual)) if items.len() == actual.len() => {
+            for (index, (expected, actual)) in items.iter().zip(actual).enumerate() {
+                compare(&format!("{path}[{index}]"), expected, actual, failures);
+            }
+        }
+        _ if expected == actual => {}
+        _ => failures.push(format!("{path}: expected {expected}, got {actual}")),
+    }
+}
+
+pub(crate) fn status_name(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Succeeded => "succeeded",
+        RunStatus::Failed => "failed",
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn print_outcomes(outcomes: &[CaseOutcome]) {
+    let mut flow = None;
+    for outcome in outcomes {
+        if flow != Some(&outcome.flow) {
+            println!("{}", outcome.flow);
+            flow = Some(&outcome.flow);
+        }
+        let mark = if outcome.ok { "✓" } else { "✗" };
+        println!("  {mark} {} ({} ms)", outcome.case, outcome.duration_ms);
+        for failure in &outcome.failures {
+            println!("      {failure}");
+        }
+        if let Some(error) = &outcome.error {
+            println!("      error: {error}");
+        }
+    }
+    let passed = outcomes.iter().filter(|outcome| outcome.ok).count();
+    println!(
+        "{} case(s): {passed} passed, {} failed",
+        outcomes.len(),
+        outcomes.len() - passed
+    );
+}
+
+/// A JUnit XML report with one test suite per flow. Unmet expectations are failures; cases that
+/// could not run are errors.
+pub fn junit(outcomes: &[CaseOutcome]) -> String {
+    let mut suites: Vec<(&str, Vec<&CaseOutcome>)> = Vec::new();
+    for outcome in outcomes {
+        match suites.iter_mut().find(|(flow, _)| *flow == outcome.flow) {
+            Some((_, cases)) => cases.push(outcome),
+            None => suites.push((&outcome.flow, vec![outcome])),
+        }
+    }
+    let counts = |cases: &[&CaseOutcome]| {
+        (
+            cases.len(),
+            cases
+                .iter()
+                .filter(|case| case.error.is_none() && !case.failures.is_empty())
+                .count(),
+            cases.iter().filter(|case| case.error.is_some()).count(),
+            cases.iter().map(|case| case.duration_ms).sum::<u64>() as f64 / 1000.0,
+        )
+    };
+
+    let all = outcomes.iter().collect::<Vec<_>>();
+    let (tests, failures, errors, time) = counts(&all);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"flow test\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">"
+    );
+    for (flow, cases) in &suites {
+        let (tests, failures, errors, time) = counts(cases);
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">",
+            escape(flow)
+        );
+        for case in cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" time=\"{:.3}\"",
+                escape(&case.case),
+                escape(flow),
+                escape(&case.file),
+                case.duration_ms as f64 / 1000.0
+            );
+            if let Some(error) = &case.error {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <error message=\"{}\">{}</error>\n    </testcase>",
+                    escape(error.lines().next().unwrap_or_default()),
+                    escape(error)
+                );
+            } else if let Some(first) = case.failures.first() {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                    escape(first),
+                    escape(&case.failures.join("\n"))
+                );
+            } else {
+                xml.push_str("/>\n");
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            ch if ch.is_control() && !matches!(ch, '\n' | '\t' | '\r') => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_result::parse;
+    use serde_json::json;
+
+    fn report(value: JsonValue) -> RunReport {
+        parse(&value.to_string()).unwrap()
+    }
+
+    fn expect(yaml: &str) -> Expect {
+        serde_yaml_bw::from_str::<TestCase>(yaml).unwrap().expect
+    }
+
+    #[test]
+    fn expectations_compare_status_output_and_visits() {
+        let report = report(json!({
+            "status": "succeeded",
+            "output": {"greeting": "Hello Ada", "meta": {"lang": "en", "tokens": 3}},
+            "trace": [
+                {"node_id": "start", "status": "succeeded"},
+                {"node_id": "audit", "status": "skipped"},
+                {"node_id": "greet", "status": "succeeded"}
+            ]
+        }));
+        let passing = expect(
+            "expect:\n  output:\n    greeting: Hello Ada\n    meta: {lang: en}\n  visits: [start, greet]\n",
+        );
+        assert!(check(&passing, &report).is_empty());
+
+        let failing = expect(
+            "expect:\n  status: failed\n  error: TIMEOUT\n  output:\n    greeting: Hi\n    missing: 1\n  visits: [start]\n",
+        );
+        assert_eq!(
+            check(&failing, &report),
+            [
+                "status: expected failed, got succeeded",
+                "error: expected TIMEOUT, got none",
+                "output.greeting: expected \"Hi\", got \"Hello Ada\"",
+                "output.missing: missing",
+                "visits: expected [start], got [start, greet]",
+            ]
+        );
+    }
+
+    #[test]
+    fn case_files_reject_unknown_keys() {
+        let err = serde_yaml_bw::from_str::<TestCase>("inputs: {}\n").unwrap_err();
+        assert!(err.to_string().contains("unknown field `inputs`"), "{err}");
+    }
+
+    #[test]
+    fn junit_groups_cases_per_flow_and_escapes_text() {
+        let outcome =
+            |flow: &str, case: &str, failures: &[&str], error: Option<&str>| CaseOutcome {
+                flow: flow.to_string(),
+                case: case.to_string(),
+                file: format!("flows/tests/{case}.yaml"),
+                ok: failures.is_empty() && error.is_none(),
+                duration_ms: 1500,
+                failures: failures.iter().map(|f| f.to_string()).collect(),
+                error: error.map(str::to_string),
+            };
+        let xml = junit(&[
+            outcome("greet", "ok", &[], None),
+            outcome("greet", "bad", &["output.x: expected \"<a>\", got 1"], None),
+            outcome("bill", "broken", &[], Some("runner & co failed")),
+        ]);
+        assert!(xml.contains(
+            "<testsuites name=\"flow test\" tests=\"3\" failures=\"1\" errors=\"1\" time=\"4.500\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"greet\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"3.000\">"
+        ));
+        assert!(xml.contains("classname=\"greet\" file=\"flows/tests/ok.yaml\" time=\"1.500\"/>"));
+        assert!(
+            xml.contains("<failure message=\"output.x: expected &quot;&lt;a&gt;&quot;, got 1\">")
+        );
+        assert!(xml.contains("<error message=\"runner &amp; co failed\">"));
+    }
+}
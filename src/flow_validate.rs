@@ -0,0 +1,228 @@
+//! `flow validate --output text|sarif`: the schema and structure checks greentic-flow runs when
+//! loading a flow, reported per flow. Without `--output` or `--watch`, `flow validate` goes to
+//! greentic-flow.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, bail};
+use greentic_flow::flow_bundle::load_and_validate_bundle;
+
+use crate::flow_cli::FlowValidateArgs;
+use crate::runtime;
+use crate::sarif::{self, Diagnostic, OutputFormat};
+use crate::watch_cmd::{self, Snapshot};
+
+const WATCH_POLL: Duration = Duration::from_millis(300);
+
+pub fn run(args: &FlowValidateArgs) -> Result<()> {
+    let mut diagnostics = Vec::new();
+    let mut failed = 0;
+    for path in &args.flows {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let Err(err) = validate(path, &source) else {
+            if args.output == OutputFormat::Text {
+                println!("✓ {}", path.display());
+            }
+            continue;
+        };
+        failed += 1;
+        if args.output == OutputFormat::Text {
+            println!("✗ {}: {err}", path.display());
+        }
+        diagnostics.extend(err_diagnostics(&err, path, &source));
+    }
+    if args.output == OutputFormat::Sarif {
+        sarif::print(sarif::FLOW_RULES, &diagnostics)?;
+    }
+    if failed > 0 {
+        bail!("{failed} of {} flow(s) failed validation", args.flows.len());
+    }
+    Ok(())
+}
+
+/// `flow validate --watch`: validate the flows, then again whenever one of them changes. Each pass
+/// prints one status line; diagnostics are printed only when a pass fails with problems the
+/// previous pass did not report. Runs until Ctrl-C.
+pub fn watch(args: &FlowValidateArgs) -> Result<()> {
+    let mut seen = snapshot(&args.flows);
+    let mut pass = Pass::run(&args.flows);
+    pass.print(None);
+    println!(
+        "Watching {} flow(s); press Ctrl-C to stop",
+        args.flows.len()
+    );
+    loop {
+        runtime::pause(WATCH_POLL);
+        let current = snapshot(&args.flows);
+        if watch_cmd::changed_paths(&seen, &current).is_empty() {
+            continue;
+        }
+        // Editors often save in several writes; let them settle before validating.
+        runtime::pause(WATCH_POLL);
+        seen = snapshot(&args.flows);
+        let next = Pass::run(&args.flows);
+        next.print(Some(&pass));
+        pass = next;
+    }
+}
+
+/// The result of validating every watched flow once.
+#[derive(Debug, PartialEq, Eq)]
+struct Pass {
+    flows: usize,
+    failed: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Pass {
+    // Unreadable flows count as failures rather than ending the watch; a save in progress can
+    // briefly remove the file.
+    fn run(flows: &[PathBuf]) -> Self {
+        let mut failed = 0;
+        let mut diagnostics = Vec::new();
+        for path in flows {
+            let found = match fs::read_to_string(path) {
+                Ok(source) => match validate(path, &source) {
+                    Ok(()) => continue,
+                    Err(err) => err_diagnostics(&err, path, &source),
+                },
+                Err(err) => vec![Diagnostic::new(
+                    "flow-internal",
+                    format!("failed to read: {err}"),
+                    path,
+                )],
+            };
+            failed += 1;
+            diagnostics.extend(found);
+        }
+        Self {
+            flows: flows.len(),
+            failed,
+            diagnostics,
+        }
+    }
+
+    fn status_line(&self, at: SystemTime) -> String {
+        let at = time::OffsetDateTime::from(at);
+        let clock = format!("{:02}:{:02}:{:02}", at.hour(), at.minute(), at.second());
+        if self.failed == 0 {
+            return format!("[{clock}] ✓ {} flow(s) valid", self.flows);
+        }
+        format!(
+            "[{clock}] ✗ {} error(s) in {} of {} flow(s)",
+            self.diagnostics.len(),
+            self.failed,
+            self.flows
+        )
+    }
+
+    fn print(&self, previous: Option<&Pass>) {
+        println!("{}", self.status_line(SystemTime::now()));
+        let repeated = previous.is_some_and(|previous| previous.diagnostics == self.diagnostics);
+        if repeated {
+            return;
+        }
+        for diagnostic in &self.diagnostics {
+            println!("  {}", diagnostic_line(diagnostic));
+        }
+    }
+}
+
+/// `file:line:column: message [rule]`, with the position parts left out when unknown.
+fn diagnostic_line(diagnostic: &Diagnostic) -> String {
+    let mut location = diagnostic.file.display().to_string();
+    if let Some(line) = diagnostic.line {
+        location.push_str(&format!(":{line}"));
+        if let Some(column) = diagnostic.column {
+            location.push_str(&format!(":{column}"));
+        }
+    }
+    format!("{location}: {} [{}]", diagnostic.message, diagnostic.rule)
+}
+
+fn validate(path: &Path, source: &str) -> Result<()> {
+    load_and_validate_bundle(source, Some(path))?;
+    Ok(())
+}
+
+fn err_diagnostics(err: &anyhow::Error, path: &Path, source: &str) -> Vec<Diagnostic> {
+    sarif::flow_error_diagnostics(err, path, source)
+        .unwrap_or_else(|| vec![Diagnostic::new("flow-internal", err.to_string(), path)])
+}
+
+// Missing flows are left out, so a flow coming back counts as a change.
+fn snapshot(flows: &[PathBuf]) -> Snapshot {
+    flows
+        .iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+            Some((path.clone(), modified))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_count_failed_flows_and_their_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.ygtc");
+        fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hello-pack/hello-flow.ygtc"),
+            &valid,
+        )
+        .unwrap();
+        let broken = dir.path().join("broken.ygtc");
+        fs::write(&broken, "id: [unterminated\n").unwrap();
+        let missing = dir.path().join("missing.ygtc");
+
+        let at = SystemTime::UNIX_EPOCH + Duration::from_secs(3600 + 2 * 60 + 3);
+        let pass = Pass::run(std::slice::from_ref(&valid));
+        assert_eq!(pass.failed, 0);
+        assert_eq!(pass.status_line(at), "[01:02:03] ✓ 1 flow(s) valid");
+
+        let pass = Pass::run(&[valid.clone(), broken.clone(), missing.clone()]);
+        assert_eq!(pass.failed, 2);
+        assert_eq!(
+            pass.status_line(at),
+            format!(
+                "[01:02:03] ✗ {} error(s) in 2 of 3 flow(s)",
+                pass.diagnostics.len()
+            )
+        );
+        assert!(pass.diagnostics.iter().any(|d| d.file == broken));
+        assert!(
+            pass.diagnostics
+                .iter()
+                .any(|d| d.file == missing && d.message.starts_with("failed to read"))
+        );
+        assert_eq!(snapshot(&[valid.clone(), missing]).len(), 1);
+    }
+
+    #[test]
+    fn diagnostic_lines_include_the_known_position() {
+        let file = Path::new("flows/a.ygtc");
+        let diagnostic = Diagnostic::new("flow-yaml", "bad indent", file).at_line(Some(4));
+        assert_eq!(
+            diagnostic_line(&diagnostic),
+            "flows/a.ygtc:4: bad indent [flow-yaml]"
+        );
+        let diagnostic = Diagnostic {
+            column: Some(7),
+            ..diagnostic
+        };
+        assert_eq!(
+            diagnostic_line(&diagnostic),
+            "flows/a.ygtc:4:7: bad indent [flow-yaml]"
+        );
+        assert_eq!(
+            diagnostic_line(&Diagnostic::new("flow-internal", "boom", file)),
+            "flows/a.ygtc: boom [flow-internal]"
+        );
+    }
+}
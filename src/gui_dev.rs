@@ -0,0 +1,396 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::events;
+use crate::passthrough::run_passthrough;
+use crate::runtime;
+use crate::util::files;
+use crate::watch_cmd;
+
+/// Rewritten with the time of every successful restage, for a greentic-gui instance or a
+/// live-reload tool to watch.
+pub const RELOAD_MARKER: &str = ".greentic/gui-reload";
+
+const WATCH_POLL: Duration = Duration::from_millis(300);
+
+// BLAKE3 digests of the watched files, by path relative to the watched directory.
+type Digests = BTreeMap<PathBuf, String>;
+
+/// `gui pack-dev` flags greentic-dev handles itself, and the arguments left for greentic-gui.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackDev {
+    /// Directory to watch, with `--watch`/`--watch-dir`.
+    pub watch_dir: Option<PathBuf>,
+    /// `--build-cmd` commands as argv, run in order before every staging.
+    pub build_cmds: Vec<Vec<String>>,
+    /// `--build-env KEY=VALUE` variables the build commands run with.
+    pub build_env: BTreeMap<String, String>,
+    pub args: Vec<OsString>,
+}
+
+/// Take `--watch`, `--watch-dir`, `--build-cmd` and `--build-env` off a `gui pack-dev`
+/// invocation. `--watch-dir` implies `--watch` and defaults to the current directory. Build
+/// commands are split into argv shell-style but never run through a shell, so quoting works the
+/// same on every platform. Returns `None` when none of these flags are present.
+pub fn take_pack_dev(args: &[OsString]) -> Result<Option<PackDev>> {
+    if args.first().and_then(|arg| arg.to_str()) != Some("pack-dev") {
+        return Ok(None);
+    }
+    let mut watch = false;
+    let mut dir = None;
+    let mut build_cmds = Vec::new();
+    let mut build_env = BTreeMap::new();
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let Some((flag, value)) = arg.to_str().and_then(local_flag) else {
+            rest.push(arg.clone());
+            continue;
+        };
+        if flag == "--watch" {
+            watch = true;
+            continue;
+        }
+        let value = match value {
+            Some(value) => value.to_string(),
+            None => match iter.next().and_then(|value| value.to_str()) {
+                Some(value) => value.to_string(),
+                None => bail!("{flag} needs a value"),
+            },
+        };
+        match flag {
+            "--watch-dir" => {
+                if dir.replace(PathBuf::from(value)).is_some() {
+                    bail!("--watch-dir given more than once");
+                }
+            }
+            "--build-cmd" => build_cmds.push(
+                shlex::split(&value)
+                    .filter(|argv| !argv.is_empty())
+                    .ok_or_else(|| anyhow!("cannot parse build command `{value}`"))?,
+            ),
+            _ => {
+                let Some((key, value)) = value.split_once('=').filter(|(key, _)| !key.is_empty())
+                else {
+                    bail!("--build-env expects KEY=VALUE, got `{value}`");
+                };
+                build_env.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    if !build_env.is_empty() && build_cmds.is_empty() {
+        bail!("--build-env needs at least one --build-cmd");
+    }
+    if !watch && dir.is_none() && build_cmds.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PackDev {
+        watch_dir: (watch || dir.is_some()).then(|| dir.unwrap_or_else(|| PathBuf::from("."))),
+        build_cmds,
+        build_env,
+        args: rest,
+    }))
+}
+
+// `--flag`, `--flag value` or `--flag=value` for the flags handled here.
+fn local_flag(arg: &str) -> Option<(&str, Option<&str>)> {
+    if arg == "--watch" {
+        return Some((arg, None));
+    }
+    ["--watch-dir", "--build-cmd", "--build-env"]
+        .into_iter()
+        .find_map(|flag| match arg.strip_prefix(flag) {
+            Some("") => Some((flag, None)),
+            Some(rest) => rest.strip_prefix('=').map(|value| (flag, Some(value))),
+            None => None,
+        })
+}
+
+/// Run the build commands and stage the pack once, or keep doing so on every change with
+/// `--watch`.
+pub fn run(bin: &Path, pack_dev: &PackDev) -> Result<()> {
+    if pack_dev.watch_dir.is_some() {
+        return watch(bin, pack_dev);
+    }
+    build(pack_dev)?;
+    let status = run_passthrough(bin, &pack_dev.args, false)?;
+    events::exit(status.code().unwrap_or(1));
+}
+
+/// Stage the pack with `greentic-gui pack-dev`, then stage it again whenever the contents of a file
+/// under the watched directory change, rewriting [`RELOAD_MARKER`] after each successful run. Only
+/// files whose timestamp moved are hashed again; a save that leaves the bytes as they were does
+/// not restage. Build output and tool state (`dist/`, `target/`, `.greentic/`, …) are not
+/// watched. Runs until Ctrl-C.
+fn watch(bin: &Path, pack_dev: &PackDev) -> Result<()> {
+    let dir = pack_dev.watch_dir.as_deref().unwrap_or(Path::new("."));
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    restage(bin, pack_dev);
+    // Snapshots are taken after staging, so files the build or pack-dev write under the watched
+    // directory do not trigger another run.
+    let mut snapshot = watch_cmd::scan(dir)?;
+    let mut digests = Digests::new();
+    content_changes(
+        dir,
+        &snapshot.keys().cloned().collect::<Vec<_>>(),
+        &mut digests,
+    );
+    println!(
+        "Watching {} for changes; press Ctrl-C to stop",
+        dir.display()
+    );
+    loop {
+        runtime::pause(WATCH_POLL);
+        let current = watch_cmd::scan(dir)?;
+        let changed = watch_cmd::changed_paths(&snapshot, &current);
+        if changed.is_empty() {
+            continue;
+        }
+        // Editors often save in several writes; let them settle before restaging.
+        runtime::pause(WATCH_POLL);
+        let changed = content_changes(dir, &changed, &mut digests);
+        snapshot = current;
+        if changed.is_empty() {
+            continue;
+        }
+        match changed.len() {
+            1 => println!("\n▶ {} changed; restaging", changed[0].display()),
+            count => println!(
+                "\n▶ {} and {} other file(s) changed; restaging",
+                changed[0].display(),
+                count - 1
+            ),
+        }
+        restage(bin, pack_dev);
+        let staged = watch_cmd::scan(dir)?;
+        content_changes(
+            dir,
+            &watch_cmd::changed_paths(&snapshot, &staged),
+            &mut digests,
+        );
+        snapshot = staged;
+    }
+}
+
+// Hash `paths` again and return those whose contents differ from `digests`, including files that
+// appeared or disappeared. `digests` is updated to match.
+fn content_changes(dir: &Path, paths: &[PathBuf], digests: &mut Digests) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|path| {
+            let digest = files::blake3_file(&dir.join(path)).ok();
+            let previous = match &digest {
+                Some(digest) => digests.insert((*path).clone(), digest.clone()),
+                None => digests.remove(*path),
+            };
+            previous != digest
+        })
+        .cloned()
+        .collect()
+}
+
+/// Run the `--build-cmd` commands in order with the `--build-env` variables, stopping at the
+/// first that fails.
+fn build(pack_dev: &PackDev) -> Result<()> {
+    for argv in &pack_dev.build_cmds {
+        let line =
+            shlex::try_join(argv.iter().map(String::as_str)).unwrap_or_else(|_| argv.join(" "));
+        let status = Command::new(&argv[0])
+            .args(&argv[1..])
+            .envs(&pack_dev.build_env)
+            .spawn()
+            .and_then(crate::interrupt::wait_child)
+            .with_context(|| format!("failed to start build command `{line}`"))?;
+        if !status.success() {
+            bail!(
+                "build command `{line}` exited with {}",
+                status.code().unwrap_or(1)
+            );
+        }
+    }
+    Ok(())
+}
+
+// Failures are reported and the watch goes on, so the next save can fix them. Every outcome says
+// how long the iteration took, split into the build commands and staging when there are any.
+fn restage(bin: &Path, pack_dev: &PackDev) {
+    let started = Instant::now();
+    if let Err(err) = build(pack_dev) {
+        println!("✗ {err:#} (after {} ms)", started.elapsed().as_millis());
+        return;
+    }
+    let built = (!pack_dev.build_cmds.is_empty()).then(|| started.elapsed());
+    let status = Command::new(bin)
+        .args(&pack_dev.args)
+        .spawn()
+        .and_then(crate::interrupt::wait_child);
+    let took = iteration_time(started.elapsed(), built);
+    match status {
+        Ok(status) if status.success() => match touch_reload_marker() {
+            Ok(()) => println!("✓ staged in {took}; wrote {RELOAD_MARKER}"),
+            Err(err) => println!("✓ staged in {took}, but {err:#}"),
+        },
+        Ok(status) => println!(
+            "✗ pack-dev exited with {} after {took}",
+            status.code().unwrap_or(1)
+        ),
+        Err(err) => println!("✗ failed to start {}: {err}", bin.display()),
+    }
+}
+
+// `1250 ms`, or `1250 ms (build 900 ms, pack-dev 350 ms)` when build commands ran.
+fn iteration_time(total: Duration, built: Option<Duration>) -> String {
+    match built {
+        Some(built) => format!(
+            "{} ms (build {} ms, pack-dev {} ms)",
+            total.as_millis(),
+            built.as_millis(),
+            total.saturating_sub(built).as_millis()
+        ),
+        None => format!("{} ms", total.as_millis()),
+    }
+}
+
+fn touch_reload_marker() -> Result<()> {
+    let marker = Path::new(RELOAD_MARKER);
+    if let Some(parent) = marker.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let now = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .context("format reload timestamp")?;
+    fs::write(marker, format!("{now}\n"))
+        .with_context(|| format!("failed to write {}", marker.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Digests, PackDev, content_changes, iteration_time, take_pack_dev};
+    use std::collections::BTreeMap;
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn watch_flags_are_taken_off_pack_dev() {
+        assert_eq!(
+            take_pack_dev(&args(&["pack-dev", "--watch", "--out", "dist"])).unwrap(),
+            Some(PackDev {
+                watch_dir: Some(PathBuf::from(".")),
+                build_cmds: Vec::new(),
+                build_env: BTreeMap::new(),
+                args: args(&["pack-dev", "--out", "dist"]),
+            })
+        );
+        assert_eq!(
+            take_pack_dev(&args(&["pack-dev", "--watch-dir=web", "--out", "dist"]))
+                .unwrap()
+                .and_then(|pack_dev| pack_dev.watch_dir),
+            Some(PathBuf::from("web"))
+        );
+        assert_eq!(
+            take_pack_dev(&args(&["pack-dev", "--out", "dist"])).unwrap(),
+            None
+        );
+        assert_eq!(take_pack_dev(&args(&["serve", "--watch"])).unwrap(), None);
+        assert!(take_pack_dev(&args(&["pack-dev", "--watch-dir"])).is_err());
+    }
+
+    #[test]
+    fn build_commands_are_split_into_argv_in_order() {
+        let pack_dev = take_pack_dev(&args(&[
+            "pack-dev",
+            "--build-cmd",
+            "npm ci",
+            "--build-cmd=npm run build -- --base \"/my app/\"",
+            "--build-env",
+            "NODE_ENV=production",
+            "--build-env=VITE_API=http://localhost:8080/?a=b",
+            "--out",
+            "dist",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(pack_dev.watch_dir, None);
+        assert_eq!(
+            pack_dev.build_cmds,
+            [
+                vec!["npm", "ci"],
+                vec!["npm", "run", "build", "--", "--base", "/my app/"],
+            ]
+        );
+        assert_eq!(
+            pack_dev.build_env,
+            BTreeMap::from([
+                ("NODE_ENV".to_string(), "production".to_string()),
+                (
+                    "VITE_API".to_string(),
+                    "http://localhost:8080/?a=b".to_string()
+                ),
+            ])
+        );
+        assert_eq!(pack_dev.args, args(&["pack-dev", "--out", "dist"]));
+
+        for bad in [
+            &["pack-dev", "--build-cmd", "npm run \"build"][..],
+            &["pack-dev", "--build-cmd", "  "],
+            &[
+                "pack-dev",
+                "--build-cmd",
+                "npm ci",
+                "--build-env",
+                "NODE_ENV",
+            ],
+            &["pack-dev", "--build-env", "NODE_ENV=production"],
+        ] {
+            assert!(take_pack_dev(&args(bad)).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn only_changed_contents_count_as_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("app.css"), "body {}").unwrap();
+        fs::write(dir.path().join("app.js"), "run()").unwrap();
+        let paths = [PathBuf::from("app.css"), PathBuf::from("app.js")];
+        let mut digests = Digests::new();
+        assert_eq!(content_changes(dir.path(), &paths, &mut digests), paths);
+
+        // Saved again with the same bytes.
+        fs::write(dir.path().join("app.css"), "body {}").unwrap();
+        assert!(content_changes(dir.path(), &paths, &mut digests).is_empty());
+
+        fs::write(dir.path().join("app.js"), "run(1)").unwrap();
+        fs::remove_file(dir.path().join("app.css")).unwrap();
+        assert_eq!(content_changes(dir.path(), &paths, &mut digests), paths);
+        assert_eq!(digests.keys().collect::<Vec<_>>(), [&paths[1]]);
+    }
+
+    #[test]
+    fn iterations_report_build_and_staging_time() {
+        assert_eq!(iteration_time(Duration::from_millis(420), None), "420 ms");
+        assert_eq!(
+            iteration_time(
+                Duration::from_millis(1250),
+                Some(Duration::from_millis(900))
+            ),
+            "1250 ms (build 900 ms, pack-dev 350 ms)"
+        );
+    }
+}
@@ -1,5 +1,4 @@
 use std::fs;
-use std::future::Future;
 use std::io::IsTerminal;
 use std::io::{Cursor, Read};
 use std::path::{Component, Path, PathBuf};
@@ -22,6 +21,7 @@ use zip::ZipArchive;
 use crate::cli::InstallArgs;
 use crate::cmd::tools;
 use crate::i18n;
+use crate::runtime;
 
 const CUSTOMERS_TOOLS_REPO: &str = "ghcr.io/greentic-biz/customers-tools";
 const CUSTOMERS_TOOLS_GITHUB_OWNER: &str = "greentic-biz";
@@ -497,7 +497,7 @@ where
     }
 
     fn install_tenant(&self, tenant: &str, token: &str) -> Result<()> {
-        block_on_maybe_runtime(self.install_tenant_async(tenant, token))
+        runtime::block_on(self.install_tenant_async(tenant, token))
     }
 
     async fn install_tenant_async(&self, tenant: &str, token: &str) -> Result<()> {
@@ -730,18 +730,6 @@ where
     }
 }
 
-pub(crate) fn block_on_maybe_runtime<F, T>(future: F) -> Result<T>
-where
-    F: Future<Output = Result<T>>,
-{
-    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-        tokio::task::block_in_place(|| handle.block_on(future))
-    } else {
-        let rt = tokio::runtime::Runtime::new().context("failed to create tokio runtime")?;
-        rt.block_on(future)
-    }
-}
-
 fn apply_tool_locale(tool: &TenantToolEntry, locale: &str) -> TenantToolEntry {
     let mut localized = tool.clone();
     if let Some(translation) = resolve_translation(&tool.i18n, locale) {
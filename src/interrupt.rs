@@ -0,0 +1,201 @@
+//! Ctrl-C handling. Commands register the partial outputs they are writing and the child processes
+//! they wait on; on interrupt the children get a grace period to stop, are killed if they do not,
+//! the registered paths are removed, and the process exits with [`EXIT_INTERRUPTED`]. Work on the
+//! shared runtime is cancelled first (see [`crate::runtime`]).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::runtime;
+
+/// Exit status after an interrupt (128 + SIGINT, as shells report it).
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// How long interrupted children get to exit on their own before they are killed.
+const CHILD_GRACE: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+static INSTALL: Once = Once::new();
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next_id: 0,
+    paths: BTreeMap::new(),
+    children: BTreeMap::new(),
+});
+
+struct Registry {
+    next_id: u64,
+    paths: BTreeMap<u64, PathBuf>,
+    children: BTreeMap<u64, Arc<Mutex<Child>>>,
+}
+
+impl Registry {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Install the Ctrl-C handler on the shared runtime. Safe to call more than once.
+pub fn install() {
+    INSTALL.call_once(|| match runtime::runtime() {
+        Ok(runtime) => {
+            runtime.spawn(async {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    let _ = tokio::task::spawn_blocking(on_interrupt).await;
+                }
+            });
+        }
+        Err(err) => eprintln!("warning: Ctrl-C cleanup is unavailable: {err:#}"),
+    });
+}
+
+fn on_interrupt() {
+    eprintln!("\ninterrupted; cleaning up");
+    // Async work stops at its next await; the callers blocked on it wait for the exit below.
+    runtime::shutdown().cancel();
+    stop_children();
+    let removed = remove_partial_outputs();
+    for path in &removed {
+        eprintln!("  removed partial output {}", path.display());
+    }
+    std::process::exit(EXIT_INTERRUPTED);
+}
+
+fn stop_children() {
+    let children = registry().children.values().cloned().collect::<Vec<_>>();
+    if children.is_empty() {
+        return;
+    }
+    // The terminal sends Ctrl-C to the whole process group, so children normally stop by
+    // themselves; only kill the ones that outlive the grace period.
+    let deadline = Instant::now() + CHILD_GRACE;
+    for child in children {
+        let mut child = child
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) | Err(_) => break,
+                Ok(None) if Instant::now() >= deadline => {
+                    eprintln!("  stopping child process {}", child.id());
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break;
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    }
+}
+
+fn remove_partial_outputs() -> Vec<PathBuf> {
+    let paths = std::mem::take(&mut registry().paths);
+    paths
+        .into_values()
+        .filter(|path| remove_path(path))
+        .collect()
+}
+
+fn remove_path(path: &Path) -> bool {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path).is_ok(),
+        Ok(_) => fs::remove_file(path).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// A file or directory that is removed if Ctrl-C arrives before the guard is dropped. Drop the
+/// guard once the output is complete (or has been cleaned up some other way).
+#[must_use = "the path is only cleaned up while the guard is alive"]
+pub struct PartialOutput {
+    id: u64,
+}
+
+impl PartialOutput {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let mut registry = registry();
+        let id = registry.next_id();
+        registry.paths.insert(id, path.into());
+        Self { id }
+    }
+}
+
+impl Drop for PartialOutput {
+    fn drop(&mut self) {
+        registry().paths.remove(&self.id);
+    }
+}
+
+/// Wait for `child`, stopping it if Ctrl-C arrives first.
+pub fn wait_child(child: Child) -> io::Result<ExitStatus> {
+    let child = Arc::new(Mutex::new(child));
+    let id = {
+        let mut registry = registry();
+        let id = registry.next_id();
+        registry.children.insert(id, child.clone());
+        id
+    };
+    let status = loop {
+        let polled = child
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .try_wait();
+        match polled {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => break Err(err),
+        }
+    };
+    registry().children.remove(&id);
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PartialOutput, registry, remove_path};
+    use crate::util::process::{CommandSpec, run};
+    use std::fs;
+
+    #[test]
+    fn partial_outputs_are_registered_while_the_guard_lives() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("pack.gtpack.tmp");
+        let guard = PartialOutput::new(&file);
+        assert_eq!(registry().paths.get(&guard.id), Some(&file));
+        let id = guard.id;
+        drop(guard);
+        assert!(!registry().paths.contains_key(&id));
+    }
+
+    #[test]
+    fn partial_files_and_directories_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("pack.gtpack.tmp");
+        let bundle = dir.path().join("bundle");
+        fs::write(&file, b"partial").unwrap();
+        fs::create_dir_all(bundle.join("inner")).unwrap();
+
+        assert!(remove_path(&file));
+        assert!(remove_path(&bundle));
+        assert!(!file.exists() && !bundle.exists());
+        assert!(!remove_path(&file), "already gone");
+    }
+
+    #[test]
+    fn waited_children_report_their_status() {
+        let output = run(CommandSpec::shell("exit 3")).unwrap();
+        assert_eq!(output.status.code(), Some(3));
+    }
+}
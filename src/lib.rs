@@ -1,28 +1,100 @@
+pub mod api;
+pub mod capability_policy;
 pub mod cbor_cmd;
 pub mod cli;
 pub mod cmd;
 pub mod component_add;
 pub mod component_cli;
+pub mod component_compat;
+pub mod component_lock;
+pub mod component_manifest;
+pub mod component_manifest_cli;
+pub mod component_pin;
 pub mod component_resolver;
+pub mod component_scaffold;
 pub mod config;
 pub mod coverage_cmd;
 pub mod delegate;
 pub mod dev_runner;
 pub mod distributor;
+pub mod doctor_cmd;
+pub mod download;
+pub mod events;
+pub mod exec_context;
+pub mod explain;
+pub mod flow_calls;
+pub mod flow_cli;
+pub mod flow_contract;
+pub mod flow_fmt;
+pub mod flow_graph;
+pub mod flow_lint;
+pub mod flow_operation;
+pub mod flow_stats;
+pub mod flow_test;
+pub mod flow_validate;
+pub mod gui_dev;
 pub mod i18n;
 pub mod install;
+pub mod interrupt;
 pub mod mcp_cmd;
+pub mod mcp_probe;
+pub mod mock_scenario;
+pub mod org_cmd;
+pub mod outdated_cmd;
+pub mod pack_backup;
 pub mod pack_build;
+pub mod pack_changelog;
+pub mod pack_cli;
+pub mod pack_diff;
+pub mod pack_drift;
+pub mod pack_encrypt;
+pub mod pack_events;
+pub mod pack_events_doctor;
 pub mod pack_init;
+pub mod pack_inspect;
+pub mod pack_interfaces;
+pub mod pack_licenses;
+pub mod pack_messaging;
+pub mod pack_meta;
+pub mod pack_plan;
+pub mod pack_plan_baseline;
+pub mod pack_plan_terraform;
+pub mod pack_preflight;
+pub mod pack_provenance;
+pub mod pack_providers;
+pub mod pack_prune;
+pub mod pack_release;
+pub mod pack_repo;
+pub mod pack_secrets;
+pub mod pack_transfer;
 pub mod pack_verify;
+pub mod pack_yaml;
 pub mod passthrough;
 pub mod path_safety;
+pub mod registry_cmd;
 pub mod release_cmd;
+pub mod resolve_map;
+pub mod run_capture;
+pub mod run_env;
+pub mod run_expect;
+pub mod run_history;
+pub mod run_result;
+pub mod run_usage;
+pub mod runtime;
+pub mod sarif;
+pub mod schema_cmd;
 pub mod secrets_cli;
+pub mod secrets_keyring;
+pub mod secrets_seed;
+pub mod signing_keys;
+pub mod state_lock;
 pub mod toolchain_catalogue;
 pub mod util;
+pub mod watch_cmd;
 pub mod wizard;
+pub mod workspace_check;
 
 pub mod registry {
     pub use crate::dev_runner::DescribeRegistry;
+    pub use crate::dev_runner::registry::{REGISTRY_PATH, RegistryFile, RegistryFileEntry};
 }
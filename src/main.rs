@@ -1,27 +1,57 @@
 use anyhow::Result;
-use clap::Parser;
 use clap::error::ErrorKind;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use std::env;
 use std::ffi::OsString;
 use std::process::{Command as ProcessCommand, Stdio};
+use std::time::Instant;
 
 use greentic_dev::cli::{Cli, Command};
 use greentic_dev::cli::{
     InstallSubcommand, McpCommand, ReleaseCommand, ToolsCommand, WizardSubcommand,
 };
-use greentic_dev::passthrough::{delegated_binary_name, resolve_binary, run_passthrough};
+use greentic_dev::passthrough::{
+    delegated_binary_name, resolve_binary, run_command, run_passthrough,
+};
 
 use greentic_dev::cbor_cmd;
 use greentic_dev::cmd::config;
 use greentic_dev::cmd::tools;
+use greentic_dev::component_manifest_cli;
 use greentic_dev::coverage_cmd;
+use greentic_dev::doctor_cmd;
+use greentic_dev::events;
+use greentic_dev::explain;
+use greentic_dev::flow_cli;
+use greentic_dev::flow_contract;
+use greentic_dev::gui_dev;
 use greentic_dev::install;
+use greentic_dev::interrupt;
 use greentic_dev::mcp_cmd;
+use greentic_dev::mock_scenario::{self, Scenario};
+use greentic_dev::org_cmd;
+use greentic_dev::outdated_cmd;
+use greentic_dev::pack_backup;
+use greentic_dev::pack_cli;
+use greentic_dev::pack_encrypt;
+use greentic_dev::pack_providers;
+use greentic_dev::registry_cmd;
 use greentic_dev::release_cmd;
+use greentic_dev::run_capture;
+use greentic_dev::run_env;
+use greentic_dev::run_expect;
+use greentic_dev::run_history;
+use greentic_dev::run_result;
+use greentic_dev::schema_cmd;
 use greentic_dev::secrets_cli::run_secrets_command;
+use greentic_dev::secrets_keyring;
+use greentic_dev::signing_keys;
+use greentic_dev::watch_cmd;
 use greentic_dev::wizard;
+use greentic_dev::workspace_check;
 
 fn main() -> Result<()> {
+    interrupt::install();
     let argv: Vec<OsString> = env::args_os().collect();
     maybe_delegate_external_subcommand(&argv);
     maybe_delegate_mcp_passthrough(&argv)?;
@@ -30,56 +60,204 @@ fn main() -> Result<()> {
         greentic_dev::i18n::cli_locale_from_argv(&argv).as_deref(),
     );
 
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    if let Some(secs) = cli.lock_timeout {
+        greentic_dev::state_lock::set_timeout(std::time::Duration::from_secs(secs));
+    }
+    if let Some(sink) = cli.events {
+        events::open(sink)?;
+    }
+
+    events::start_command(command_phase(&matches));
+    let result = run(cli.command, &selected_locale);
+    events::finish_command(result.is_ok());
+    result
+}
 
-    match cli.command {
+fn run(command: Command, selected_locale: &str) -> Result<()> {
+    match command {
         Command::Flow(args) => {
+            if let Some(command) = flow_cli::parse_builtin(&args.args, selected_locale) {
+                return flow_cli::run(command);
+            }
             let bin = resolve_binary("greentic-flow")?;
             let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            events::exit(status.code().unwrap_or(1));
         }
         Command::Pack(args) => {
+            if let Some(command) = pack_cli::parse_builtin(&args.args, selected_locale) {
+                return pack_cli::run(command);
+            }
             let subcommand = args.args.first().and_then(|s| s.to_str());
             if subcommand == Some("run") {
+                if run_result::wants_schema(&args.args[1..]) {
+                    run_result::print_schema();
+                    return Ok(());
+                }
                 let bin = resolve_binary("greentic-runner-cli")?;
-                let run_args = &args.args[1..];
-                let status = run_passthrough(&bin, run_args, false)?;
-                std::process::exit(status.code().unwrap_or(1));
+                if let Some(request) = run_history::replay_request(&args.args[1..])? {
+                    let status = run_history::replay(request, &bin)?;
+                    events::exit(status.code().unwrap_or(1));
+                }
+                let (scenario, run_args) = mock_scenario::take_scenario(&args.args[1..])?;
+                let (capture, run_args) = run_capture::take_capture(&run_args)?;
+                let (expectations, run_args) = run_expect::take_expectations(&run_args)?;
+                let scenario = scenario.as_deref().map(Scenario::load).transpose()?;
+                // History keeps `--decrypt-key` with the encrypted pack and the env file flags, so
+                // replays decrypt the pack and read the files again.
+                let (decrypted, runner_args) = pack_encrypt::take_decrypt_key(&run_args)?;
+                flow_contract::check_run_input(&runner_args)?;
+                let (env_files, mut runner_args) = run_env::take_env_files(&runner_args)?;
+                // Expectations are checked against the JSON result.
+                if expectations.is_some() && !run_result::wants_json(&runner_args) {
+                    runner_args.push("--json".into());
+                }
+                let env = run_env::load(&env_files)?;
+                let secrets = secrets_keyring::runner_env(&runner_args, &|key| {
+                    env.contains_key(key)
+                        || scenario
+                            .as_ref()
+                            .is_some_and(|scenario| scenario.secrets.contains_key(key))
+                        || std::env::var_os(key).is_some()
+                })?;
+                let command = || {
+                    let mut command = match &scenario {
+                        Some(scenario) => scenario.command(&bin, &runner_args),
+                        None => {
+                            let mut command = std::process::Command::new(&bin);
+                            command.args(&runner_args);
+                            command
+                        }
+                    };
+                    command.envs(&secrets).envs(&env);
+                    command
+                };
+                let started_at = time::OffsetDateTime::now_utc();
+                let clock = Instant::now();
+                let checked = if run_result::wants_json(&runner_args) {
+                    let capture = capture
+                        .unwrap_or_default()
+                        .with_defaults(&greentic_dev::config::load()?.defaults.capture)
+                        .compile()?;
+                    Some(run_result::run_checked(command(), &capture)?)
+                } else {
+                    if capture.is_some() {
+                        events::warn("--capture only applies to `pack run --json` output");
+                    }
+                    None
+                };
+                let status = match &checked {
+                    Some(checked) => checked.status,
+                    None => run_command(command())?,
+                };
+                if let Err(err) = run_history::record_pack_run(
+                    &run_args,
+                    scenario.as_ref(),
+                    started_at,
+                    clock.elapsed(),
+                    &status,
+                ) {
+                    events::warn(format_args!("failed to record run history: {err:#}"));
+                }
+                drop(decrypted);
+                let code = match (&expectations, &checked) {
+                    (Some(expectations), Some(checked)) => expectations.conclude(checked),
+                    _ => checked.map_or(status.code().unwrap_or(1), |checked| checked.exit_code()),
+                };
+                events::exit(code);
+            }
+            if subcommand == Some("inspect") {
+                let (decrypted, inspect_args) = pack_encrypt::take_decrypt_key(&args.args[1..])?;
+                if decrypted.is_none()
+                    && let Some(pack) = pack_encrypt::pack_argument(&inspect_args)
+                    && let Some(manifest) = pack_encrypt::encryption_manifest(&pack)?
+                {
+                    pack_encrypt::print_summary(&pack, &manifest);
+                    return Ok(());
+                }
+                let bin = resolve_binary("greentic-pack")?;
+                let mut pack_args = vec![args.args[0].clone()];
+                pack_args.extend(inspect_args);
+                let status = run_passthrough(&bin, &pack_args, false)?;
+                drop(decrypted);
+                events::exit(status.code().unwrap_or(1));
             }
 
             let bin = resolve_binary("greentic-pack")?;
-            let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            let (introspect, pack_args) = pack_providers::take_no_introspect(&args.args);
+            let backup = match pack_backup::guarded_target(&pack_args) {
+                Some((command, target)) => pack_backup::backup(&command, &target)?,
+                None => None,
+            };
+            let status = run_passthrough(&bin, &pack_args, false)?;
+            if introspect && status.success() {
+                pack_providers::introspect_new_provider(&pack_args)?;
+            }
+            if let Some(backup) = backup {
+                backup.finish()?;
+            }
+            events::exit(status.code().unwrap_or(1));
         }
         Command::Component(args) => {
+            if let Some(command) =
+                component_manifest_cli::parse_builtin(&args.args, selected_locale)
+            {
+                return component_manifest_cli::run(command);
+            }
             let bin = resolve_binary("greentic-component")?;
             let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            events::exit(status.code().unwrap_or(1));
         }
         Command::Bundle(args) => {
             let bin = resolve_binary("greentic-bundle")?;
             let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            events::exit(status.code().unwrap_or(1));
         }
         Command::Runner(args) => {
             let bin = resolve_binary("greentic-runner")?;
             let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            events::exit(status.code().unwrap_or(1));
         }
         Command::Config(config_cmd) => config::run(config_cmd),
         Command::Coverage(args) => coverage_cmd::run(args),
         Command::Cbor(args) => cbor_cmd::run(args),
+        Command::Schema(command) => schema_cmd::run(command),
+        Command::Registry(command) => registry_cmd::run(command),
+        Command::Runs(command) => run_history::run(command),
+        Command::Keys(command) => signing_keys::run(command),
+        Command::Org(command) => org_cmd::run(command),
+        Command::Watch(args) => watch_cmd::run(args),
+        Command::Outdated(args) => outdated_cmd::run(&args),
+        Command::Doctor(args) => {
+            let code = doctor_cmd::run(&args)?;
+            events::exit(code);
+        }
+        Command::Explain(args) => explain::run(&args),
+        Command::Undo(args) => pack_backup::undo(&args),
+        Command::Check(args) => match workspace_check::run(&args) {
+            Ok(code) => events::exit(code),
+            Err(err) => {
+                eprintln!("Error: {err:?}");
+                events::exit(workspace_check::EXIT_ERROR);
+            }
+        },
         Command::Mcp(mcp) => match mcp {
-            McpCommand::Doctor(args) => mcp_cmd::doctor(&args.provider, args.json),
+            McpCommand::Doctor(args) => match &args.provider {
+                Some(provider) => {
+                    mcp_cmd::doctor(provider, args.json, mcp_cmd::probe_timeout(&args))
+                }
+                None => mcp_cmd::doctor_many(&args),
+            },
         },
         Command::Tools(command) => match command {
-            ToolsCommand::Install(args) => tools::install(args.latest, &selected_locale),
+            ToolsCommand::Install(args) => tools::install(args.latest, selected_locale),
         },
         Command::Install(args) => {
             let install_locale = args
                 .locale
                 .clone()
-                .unwrap_or_else(|| selected_locale.clone());
+                .unwrap_or_else(|| selected_locale.to_string());
             match args.command {
                 Some(InstallSubcommand::Tools(args)) => {
                     tools::install(args.latest, &install_locale)
@@ -101,11 +279,32 @@ fn main() -> Result<()> {
         },
         Command::Gui(args) => {
             let bin = resolve_binary("greentic-gui")?;
+            if let Some(pack_dev) = gui_dev::take_pack_dev(&args.args)? {
+                return gui_dev::run(&bin, &pack_dev);
+            }
             let status = run_passthrough(&bin, &args.args, false)?;
-            std::process::exit(status.code().unwrap_or(1));
+            events::exit(status.code().unwrap_or(1));
         }
-        Command::Secrets(secrets) => run_secrets_command(secrets, &selected_locale),
+        Command::Secrets(secrets) => run_secrets_command(secrets, selected_locale),
+    }
+}
+
+// The `--events` phase for the whole command: its subcommand path, such as `release generate`,
+// plus the delegated subcommand of passthrough groups, such as `pack build`.
+fn command_phase(matches: &ArgMatches) -> String {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        path.push(name.to_string());
+        current = sub;
+    }
+    if let Ok(Some(mut args)) = current.try_get_raw("args")
+        && let Some(first) = args.next().and_then(|arg| arg.to_str())
+        && !first.starts_with('-')
+    {
+        path.push(first.to_string());
     }
+    path.join(" ")
 }
 
 fn maybe_delegate_mcp_passthrough(argv: &[OsString]) -> Result<()> {
@@ -268,6 +467,15 @@ fn is_known_subcommand(subcmd: &str) -> bool {
             | "install"
             | "release"
             | "cbor"
+            | "schema"
+            | "registry"
+            | "runs"
+            | "check"
+            | "watch"
+            | "outdated"
+            | "doctor"
+            | "explain"
+            | "undo"
             | "wizard"
             | "help"
     )
@@ -281,7 +489,8 @@ fn try_delegate_to_prefixed(subcmd: &str, rest: &[OsString]) {
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .status()
+        .spawn()
+        .and_then(interrupt::wait_child)
     {
         Ok(status) => status,
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
@@ -1,35 +1,324 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, btree_map::Entry};
 
+use crate::cli::McpDoctorArgs;
+use crate::mcp_probe::{self, ProbeReport, ServerConfig};
 use crate::path_safety::normalize_under_root;
+use crate::util::parallel::parallel_map;
 
-pub fn doctor(target: &str, json: bool) -> Result<()> {
-    let workspace_root = std::env::current_dir()
+const TOOL_MAP_NAMES: &[&str] = &[
+    "toolmap.yaml",
+    "toolmap.yml",
+    "toolmap.json",
+    "mcp.yaml",
+    "mcp.json",
+];
+
+/// `mcp doctor <PROVIDER>`. With `probe`, the provider's MCP server is also started and
+/// handshaken with, and a failed probe makes the command fail.
+pub fn doctor(target: &str, json: bool, probe: Option<Duration>) -> Result<()> {
+    let report = check(&workspace_root()?, target, probe)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).context("failed to encode JSON report")?
+        );
+    } else {
+        print_report(&report);
+    }
+    if report.probe.as_ref().is_some_and(|probe| !probe.ok()) {
+        bail!("MCP probe of {} failed", report.tool_map_path);
+    }
+
+    Ok(())
+}
+
+/// Timeout for each probe call when `--probe` is given.
+pub fn probe_timeout(args: &McpDoctorArgs) -> Option<Duration> {
+    args.probe.then(|| Duration::from_secs(args.probe_timeout))
+}
+
+fn workspace_root() -> Result<PathBuf> {
+    std::env::current_dir()
         .context("failed to resolve workspace root")?
         .canonicalize()
-        .context("failed to canonicalize workspace root")?;
-    let config_path = locate_toolmap(&workspace_root, target)?;
+        .context("failed to canonicalize workspace root")
+}
+
+fn check(workspace_root: &Path, target: &str, probe: Option<Duration>) -> Result<ToolMapReport> {
+    let config_path = locate_toolmap(workspace_root, target)?;
     let config = load_tool_map_config(&config_path)
         .with_context(|| format!("failed to load MCP tool map from {}", config_path.display()))?;
     let map = ToolMap::from_config(&config).context("tool map contains duplicate tool names")?;
-    let report = ToolMapReport::from_map(&config_path, &map);
+    let mut report = ToolMapReport::from_map(&config_path, &map);
+    if let Some(timeout) = probe {
+        match &config.server {
+            Some(server) => report.probe = Some(probe_server(server, &config_path, &map, timeout)),
+            None => report
+                .warnings
+                .push("no `server` in the tool map; nothing to probe".to_string()),
+        }
+    }
+    Ok(report)
+}
 
-    if json {
+fn probe_server(
+    server: &ServerConfig,
+    config_path: &Path,
+    map: &ToolMap,
+    timeout: Duration,
+) -> ProbeReport {
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut probe = mcp_probe::probe(server, base_dir, timeout).unwrap_or_else(|err| ProbeReport {
+        command: server.command.clone(),
+        requested_protocol_version: mcp_probe::PROTOCOL_VERSION.to_string(),
+        errors: vec![format!("{err:#}")],
+        ..ProbeReport::default()
+    });
+    if probe.ok() {
+        for (name, _) in map.iter() {
+            if !probe.tools.contains(name) {
+                probe
+                    .warnings
+                    .push(format!("tool `{name}` is in the tool map but not served"));
+            }
+        }
+    }
+    probe
+}
+
+/// Providers to check together, from a `--providers` file.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderList {
+    providers: Vec<ProviderEntry>,
+}
+
+/// A provider id or tool map path as accepted by `mcp doctor <PROVIDER>`, optionally named.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ProviderEntry {
+    Target(String),
+    Named { name: String, path: String },
+}
+
+impl ProviderEntry {
+    fn into_pair(self) -> (String, String) {
+        match self {
+            Self::Target(target) => (target.clone(), target),
+            Self::Named { name, path } => (name, path),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AggregateReport {
+    providers: Vec<ProviderOutcome>,
+    summary: AggregateSummary,
+}
+
+#[derive(Debug, Serialize)]
+struct ProviderOutcome {
+    provider: String,
+    target: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<ToolMapReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AggregateSummary {
+    providers: usize,
+    healthy: usize,
+    with_warnings: usize,
+    failed: usize,
+    tools: usize,
+}
+
+/// `mcp doctor --providers <FILE>` / `--all`: check many providers at once and aggregate the
+/// results. Fails when any tool map cannot be loaded; missing components stay warnings.
+pub fn doctor_many(args: &McpDoctorArgs) -> Result<()> {
+    let root = workspace_root()?;
+    let providers = match &args.providers {
+        Some(file) => load_provider_list(file)?,
+        None => discover_providers(&root)?,
+    };
+    if providers.is_empty() {
+        bail!("no MCP providers to check");
+    }
+    let jobs = args
+        .jobs
+        .map(usize::from)
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+    let outcomes = check_parallel(&root, &providers, jobs, probe_timeout(args));
+    let report = AggregateReport {
+        summary: AggregateSummary {
+            providers: outcomes.len(),
+            healthy: outcomes.iter().filter(|outcome| outcome.ok).count(),
+            with_warnings: outcomes
+                .iter()
+                .filter(|outcome| !outcome.ok && outcome.error.is_none())
+                .count(),
+            failed: outcomes
+                .iter()
+                .filter(|outcome| outcome.error.is_some())
+                .count(),
+            tools: outcomes
+                .iter()
+                .filter_map(|outcome| outcome.report.as_ref())
+                .map(|report| report.tool_count)
+                .sum(),
+        },
+        providers: outcomes,
+    };
+
+    if args.json {
         println!(
             "{}",
             serde_json::to_string_pretty(&report).context("failed to encode JSON report")?
         );
     } else {
-        print_report(&report);
+        print_aggregate(&report);
+    }
+    if report.summary.failed > 0 {
+        bail!(
+            "{} of {} MCP provider(s) could not be checked",
+            report.summary.failed,
+            report.summary.providers
+        );
     }
-
     Ok(())
 }
 
+fn load_provider_list(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read provider list {}", path.display()))?;
+    let list: ProviderList = if is_json(path, &content) {
+        serde_json::from_str(&content)
+            .with_context(|| format!("invalid provider list JSON {}", path.display()))?
+    } else {
+        serde_yaml_bw::from_str(&content)
+            .with_context(|| format!("invalid provider list YAML {}", path.display()))?
+    };
+    Ok(list
+        .providers
+        .into_iter()
+        .map(ProviderEntry::into_pair)
+        .collect())
+}
+
+// Every directory under `providers/` that holds a tool map, by directory name.
+fn discover_providers(workspace_root: &Path) -> Result<Vec<(String, String)>> {
+    let dir = workspace_root.join("providers");
+    if !dir.is_dir() {
+        bail!("no providers/ directory in {}", workspace_root.display());
+    }
+    let mut names = fs::read_dir(&dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            TOOL_MAP_NAMES
+                .iter()
+                .any(|name| entry.path().join(name).is_file())
+        })
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names.into_iter().map(|name| (name.clone(), name)).collect())
+}
+
+fn check_parallel(
+    workspace_root: &Path,
+    providers: &[(String, String)],
+    jobs: usize,
+    probe: Option<Duration>,
+) -> Vec<ProviderOutcome> {
+    parallel_map(providers, jobs, |(name, target)| {
+        match check(workspace_root, target, probe) {
+            Ok(report) => {
+                let probe_ok = report.probe.as_ref().is_none_or(ProbeReport::ok);
+                ProviderOutcome {
+                    provider: name.clone(),
+                    target: target.clone(),
+                    ok: report.warnings.is_empty()
+                        && report
+                            .probe
+                            .as_ref()
+                            .is_none_or(|probe| probe.warnings.is_empty())
+                        && probe_ok,
+                    error: (!probe_ok).then(|| "MCP probe failed".to_string()),
+                    report: Some(report),
+                }
+            }
+            Err(err) => ProviderOutcome {
+                provider: name.clone(),
+                target: target.clone(),
+                ok: false,
+                report: None,
+                error: Some(format!("{err:#}")),
+            },
+        }
+    })
+}
+
+fn print_aggregate(report: &AggregateReport) {
+    for outcome in &report.providers {
+        let probe = outcome
+            .report
+            .as_ref()
+            .and_then(|tools| tools.probe.as_ref());
+        match (&outcome.report, &outcome.error) {
+            (_, Some(error)) => {
+                println!("✗ {}: {error}", outcome.provider);
+                for error in probe
+                    .map(|probe| probe.errors.as_slice())
+                    .unwrap_or_default()
+                {
+                    println!("    - {error}");
+                }
+            }
+            (Some(tools), None) if outcome.ok => {
+                println!("✓ {}: {} tool(s)", outcome.provider, tools.tool_count)
+            }
+            (Some(tools), None) => {
+                let warnings = tools
+                    .warnings
+                    .iter()
+                    .chain(
+                        probe
+                            .map(|probe| probe.warnings.as_slice())
+                            .unwrap_or_default(),
+                    )
+                    .collect::<Vec<_>>();
+                println!(
+                    "! {}: {} tool(s), {} warning(s)",
+                    outcome.provider,
+                    tools.tool_count,
+                    warnings.len()
+                );
+                for warning in warnings {
+                    println!("    - {warning}");
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    let summary = &report.summary;
+    println!(
+        "\n{} provider(s): {} healthy, {} with warnings, {} failed; {} tool(s)",
+        summary.providers, summary.healthy, summary.with_warnings, summary.failed, summary.tools
+    );
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct ToolRef {
     name: String,
@@ -46,6 +335,9 @@ struct ToolRef {
 #[derive(Debug, Clone, Deserialize)]
 struct ToolMapConfig {
     tools: Vec<ToolRef>,
+    /// How to start the provider's MCP server, for `mcp doctor --probe`.
+    #[serde(default)]
+    server: Option<ServerConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -116,13 +408,7 @@ fn locate_toolmap(workspace_root: &Path, target: &str) -> Result<PathBuf> {
         }
         if joined.is_dir() {
             let safe_dir = normalize_under_root(workspace_root, &candidate)?;
-            for name in [
-                "toolmap.yaml",
-                "toolmap.yml",
-                "toolmap.json",
-                "mcp.yaml",
-                "mcp.json",
-            ] {
+            for name in TOOL_MAP_NAMES {
                 let file = safe_dir.join(name);
                 if file.is_file() {
                     return Ok(file);
@@ -140,6 +426,8 @@ struct ToolMapReport {
     tool_count: usize,
     tools: Vec<ToolHealth>,
     warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probe: Option<ProbeReport>,
 }
 
 #[derive(Debug, Serialize)]
@@ -197,6 +485,7 @@ impl ToolMapReport {
             tool_count: tools.len(),
             tools,
             warnings,
+            probe: None,
         }
     }
 }
@@ -241,13 +530,59 @@ fn print_report(report: &ToolMapReport) {
             println!("  - {warning}");
         }
     }
+    if let Some(probe) = &report.probe {
+        print_probe(probe);
+    }
+}
+
+fn print_probe(probe: &ProbeReport) {
+    println!("\nProbe: {}", probe.command);
+    if let Some(name) = &probe.server_name {
+        println!(
+            "  server: {name} {}",
+            probe.server_version.as_deref().unwrap_or_default()
+        );
+    }
+    println!(
+        "  protocol: {} (requested {})",
+        probe.protocol_version.as_deref().unwrap_or("unknown"),
+        probe.requested_protocol_version
+    );
+    for call in &probe.calls {
+        let detail = match (&call.error, call.items) {
+            (Some(error), _) => format!("failed: {error}"),
+            (None, Some(items)) => format!("{items} item(s)"),
+            (None, None) => "ok".to_string(),
+        };
+        println!(
+            "  {} {}: {} ms, {detail}",
+            if call.ok { "✓" } else { "✗" },
+            call.method,
+            call.duration_ms
+        );
+    }
+    for (label, names) in [
+        ("tools", &probe.tools),
+        ("resources", &probe.resources),
+        ("prompts", &probe.prompts),
+    ] {
+        if !names.is_empty() {
+            println!("  {label}: {}", names.join(", "));
+        }
+    }
+    for warning in &probe.warnings {
+        println!("  ! {warning}");
+    }
+    for error in &probe.errors {
+        println!("  ✗ {error}");
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        ToolMap, ToolMapConfig, ToolMapReport, ToolRef, is_json, load_tool_map_config,
-        locate_toolmap,
+        ToolMap, ToolMapConfig, ToolMapReport, ToolRef, check_parallel, discover_providers,
+        is_json, load_provider_list, load_tool_map_config, locate_toolmap,
     };
     use tempfile::tempdir;
 
@@ -279,6 +614,7 @@ mod tests {
     fn duplicate_tool_names_are_rejected() {
         let config = ToolMapConfig {
             tools: vec![sample_tool("demo"), sample_tool("demo")],
+            server: None,
         };
 
         let err = ToolMap::from_config(&config).unwrap_err();
@@ -324,6 +660,7 @@ mod tests {
         std::fs::write(&config_path, "tools: []\n").unwrap();
         let map = ToolMap::from_config(&ToolMapConfig {
             tools: vec![sample_tool("demo")],
+            server: None,
         })
         .unwrap();
 
@@ -333,4 +670,54 @@ mod tests {
         assert!(!report.tools[0].exists);
         assert_eq!(report.warnings.len(), 1);
     }
+
+    #[test]
+    fn provider_lists_accept_targets_and_named_paths() {
+        let dir = tempdir().unwrap();
+        let list = dir.path().join("providers.yaml");
+        std::fs::write(
+            &list,
+            "providers:\n  - weather\n  - name: search\n    path: vendor/search/mcp.yaml\n",
+        )
+        .unwrap();
+        assert_eq!(
+            load_provider_list(&list).unwrap(),
+            [
+                ("weather".to_string(), "weather".to_string()),
+                ("search".to_string(), "vendor/search/mcp.yaml".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn providers_are_discovered_and_checked_together() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        for (name, toolmap) in [
+            (
+                "weather",
+                "tools:\n  - name: forecast\n    component: forecast.wasm\n    entry: run\n",
+            ),
+            ("broken", "tools: [oops\n"),
+        ] {
+            let provider = root.join("providers").join(name);
+            std::fs::create_dir_all(&provider).unwrap();
+            std::fs::write(provider.join("toolmap.yaml"), toolmap).unwrap();
+        }
+        std::fs::write(root.join("providers/weather/forecast.wasm"), b"wasm").unwrap();
+        std::fs::create_dir_all(root.join("providers/empty")).unwrap();
+
+        let providers = discover_providers(&root).unwrap();
+        let names = providers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["broken", "weather"]);
+
+        let outcomes = check_parallel(&root, &providers, 2, None);
+        assert!(!outcomes[0].ok);
+        assert!(outcomes[0].error.as_deref().unwrap().contains("toolmap"));
+        assert!(outcomes[1].ok);
+        assert_eq!(outcomes[1].report.as_ref().unwrap().tool_count, 1);
+    }
 }
@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value as JsonValue, json};
+
+/// Protocol revision greentic-dev asks for in `initialize`.
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Revisions a server may answer with and still be usable.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// How to start a provider's MCP server over stdio, from the `server:` section of its tool map.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ProbeReport {
+    pub command: String,
+    pub requested_protocol_version: String,
+    pub protocol_version: Option<String>,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub calls: Vec<ProbeCall>,
+    pub tools: Vec<String>,
+    pub resources: Vec<String>,
+    pub prompts: Vec<String>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl ProbeReport {
+    pub fn ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProbeCall {
+    pub method: String,
+    pub duration_ms: u64,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Start the server, run the `initialize` handshake, then list whatever tools, resources and
+/// prompts it advertises, timing each call. Commands containing a `/` are resolved against
+/// `base_dir`, which is also the server's working directory. Problems end up in the report's
+/// `errors`; only failing to start the server at all is an `Err`.
+pub fn probe(server: &ServerConfig, base_dir: &Path, timeout: Duration) -> Result<ProbeReport> {
+    let program = if server.command.contains('/') {
+        base_dir.join(&server.command)
+    } else {
+        PathBuf::from(&server.command)
+    };
+    let mut report = ProbeReport {
+        command: std::iter::once(server.command.as_str())
+            .chain(server.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" "),
+        requested_protocol_version: PROTOCOL_VERSION.to_string(),
+        ..ProbeReport::default()
+    };
+    let mut session = Session::start(&program, server, base_dir, timeout)
+        .with_context(|| format!("failed to start MCP server `{}`", report.command))?;
+
+    let initialize = session.call(
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "greentic-dev", "version": env!("CARGO_PKG_VERSION") },
+        }),
+    );
+    let capabilities = match record(&mut report, "initialize", initialize, |_| None) {
+        Some(result) => {
+            let version = result
+                .get("protocolVersion")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string);
+            match version.as_deref() {
+                None => report
+                    .errors
+                    .push("initialize result has no protocolVersion".to_string()),
+                Some(PROTOCOL_VERSION) => {}
+                Some(other) if SUPPORTED_PROTOCOL_VERSIONS.contains(&other) => {
+                    report.warnings.push(format!(
+                        "server negotiated protocol {other}; greentic-dev requested {PROTOCOL_VERSION}"
+                    ))
+                }
+                Some(other) => report.errors.push(format!(
+                    "server speaks unsupported protocol {other} (supported: {})",
+                    SUPPORTED_PROTOCOL_VERSIONS.join(", ")
+                )),
+            }
+            report.protocol_version = version;
+            let info = result.get("serverInfo");
+            let field = |name: &str| {
+                info.and_then(|info| info.get(name))
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string)
+            };
+            report.server_name = field("name");
+            report.server_version = field("version");
+            result
+                .get("capabilities")
+                .cloned()
+                .unwrap_or(JsonValue::Null)
+        }
+        None => {
+            session.stop(&mut report);
+            return Ok(report);
+        }
+    };
+    if let Err(err) = session.notify("notifications/initialized") {
+        report.errors.push(format!("{err:#}"));
+    }
+
+    for (capability, method, field, key) in [
+        ("tools", "tools/list", "tools", "name"),
+        ("resources", "resources/list", "resources", "uri"),
+        ("prompts", "prompts/list", "prompts", "name"),
+    ] {
+        if capabilities.get(capability).is_none() {
+            continue;
+        }
+        let outcome = session.call(method, json!({}));
+        let names = record(&mut report, method, outcome, |result| {
+            result
+                .get(field)
+                .and_then(JsonValue::as_array)
+                .map(Vec::len)
+        })
+        .map(|result| {
+            result
+                .get(field)
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item.get(key).and_then(JsonValue::as_str))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+        match capability {
+            "tools" => report.tools = names,
+            "resources" => report.resources = names,
+            _ => report.prompts = names,
+        }
+    }
+    session.stop(&mut report);
+    Ok(report)
+}
+
+// Add a call to the report and hand back its result when it succeeded.
+fn record(
+    report: &mut ProbeReport,
+    method: &str,
+    outcome: (Duration, Result<JsonValue>),
+    items: impl Fn(&JsonValue) -> Option<usize>,
+) -> Option<JsonValue> {
+    let (elapsed, result) = outcome;
+    let duration_ms = elapsed.as_millis() as u64;
+    match result {
+        Ok(result) => {
+            report.calls.push(ProbeCall {
+                method: method.to_string(),
+                duration_ms,
+                ok: true,
+                items: items(&result),
+                error: None,
+            });
+            Some(result)
+        }
+        Err(err) => {
+            let error = format!("{err:#}");
+            report.errors.push(format!("{method}: {error}"));
+            report.calls.push(ProbeCall {
+                method: method.to_string(),
+                duration_ms,
+                ok: false,
+                items: None,
+                error: Some(error),
+            });
+            None
+        }
+    }
+}
+
+/// A running server speaking newline-delimited JSON-RPC on stdin/stdout.
+struct Session {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<String>,
+    stderr: Arc<Mutex<Vec<String>>>,
+    next_id: u64,
+    timeout: Duration,
+}
+
+impl Session {
+    fn start(
+        program: &Path,
+        server: &ServerConfig,
+        base_dir: &Path,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let mut child = Command::new(program)
+            .args(&server.args)
+            .envs(&server.env)
+            .current_dir(base_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().expect("piped stdout");
+        let stderr_pipe = child.stderr.take().expect("piped stderr");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        // Keep the last few stderr lines; a grandchild may hold the pipe open after the kill,
+        // so nothing waits for it to close.
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let captured = stderr.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr_pipe).lines() {
+                let Ok(line) = line else { break };
+                let mut captured = captured.lock().expect("stderr lock");
+                captured.push(line);
+                if captured.len() > 5 {
+                    captured.remove(0);
+                }
+            }
+        });
+        Ok(Self {
+            stdin: child.stdin.take(),
+            child,
+            lines,
+            stderr,
+            next_id: 1,
+            timeout,
+        })
+    }
+
+    fn send(&mut self, message: &JsonValue) -> Result<()> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("server stdin is closed"))?;
+        writeln!(stdin, "{message}")
+            .and_then(|()| stdin.flush())
+            .context("failed to write to the server")
+    }
+
+    fn notify(&mut self, method: &str) -> Result<()> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method }))
+    }
+
+    fn call(&mut self, method: &str, params: JsonValue) -> (Duration, Result<JsonValue>) {
+        let started = Instant::now();
+        let result = self.request(method, params);
+        (started.elapsed(), result)
+    }
+
+    fn request(&mut self, method: &str, params: JsonValue) -> Result<JsonValue> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    bail!("no response within {} ms", self.timeout.as_millis())
+                }
+                Err(RecvTimeoutError::Disconnected) => bail!("the server closed its output"),
+            };
+            let Ok(message) = serde_json::from_str::<JsonValue>(&line) else {
+                bail!("the server wrote a line that is not JSON-RPC: {line}");
+            };
+            // Skip notifications and requests from the server; only our response matters.
+            if message.get("id").and_then(JsonValue::as_u64) != Some(id)
+                || message.get("method").is_some()
+            {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                bail!(
+                    "error {}: {}",
+                    error.get("code").unwrap_or(&JsonValue::Null),
+                    error
+                        .get("message")
+                        .and_then(JsonValue::as_str)
+                        .unwrap_or("(no message)")
+                );
+            }
+            return message
+                .get("result")
+                .cloned()
+                .ok_or_else(|| anyhow!("response has neither result nor error"));
+        }
+    }
+
+    // Close stdin so a well-behaved server exits, give it a moment, then kill it. When the probe
+    // failed, the server's stderr goes into the report.
+    fn stop(mut self, report: &mut ProbeReport) {
+        drop(self.stdin.take());
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let stderr = self.stderr.lock().expect("stderr lock");
+        if !report.ok() && !stderr.is_empty() {
+            report
+                .errors
+                .push(format!("server stderr: {}", stderr.join(" | ")));
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{ServerConfig, probe};
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    const FAKE_SERVER: &str = r#"#!/bin/sh
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{}},"serverInfo":{"name":"fake","version":"1.0.0"}}}\n' "$id" ;;
+    *'"tools/list"'*)
+      printf '{"jsonrpc":"2.0","method":"notifications/message","params":{}}\n'
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"forecast","inputSchema":{"type":"object"}}]}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    fn server(dir: &std::path::Path, script: &str) -> ServerConfig {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("server.sh");
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        ServerConfig {
+            command: "./server.sh".to_string(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn probe_runs_the_handshake_and_lists_advertised_capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = server(dir.path(), FAKE_SERVER);
+        let report = probe(&config, dir.path(), Duration::from_secs(5)).unwrap();
+
+        assert!(report.ok(), "{:?}", report.errors);
+        assert_eq!(report.protocol_version.as_deref(), Some("2024-11-05"));
+        assert_eq!(report.server_name.as_deref(), Some("fake"));
+        assert_eq!(report.tools, ["forecast"]);
+        assert_eq!(
+            report
+                .calls
+                .iter()
+                .map(|call| call.method.as_str())
+                .collect::<Vec<_>>(),
+            ["initialize", "tools/list"]
+        );
+        assert_eq!(report.calls[1].items, Some(1));
+        assert_eq!(report.warnings.len(), 1, "older protocol is reported");
+    }
+
+    #[test]
+    fn silent_servers_time_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = server(dir.path(), "#!/bin/sh\necho booting >&2\nsleep 5\n");
+        let report = probe(&config, dir.path(), Duration::from_millis(300)).unwrap();
+
+        assert!(!report.ok());
+        assert!(report.errors[0].contains("no response within 300 ms"));
+        assert!(report.errors.iter().any(|error| error.contains("booting")));
+    }
+}
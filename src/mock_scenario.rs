@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::events;
+use crate::pack_preflight::read_env_file;
+
+/// Workspace-relative directory holding one sub-directory per named mock scenario.
+pub const SCENARIO_DIR: &str = ".greentic/mocks/scenarios";
+
+const MOCK_ENV_PREFIX: &str = "MOCK_";
+const MOCKS_FILE: &str = "mocks.env";
+const SECRETS_FILE: &str = "secrets.env";
+// Scenario content the runner cannot consume yet; reported instead of silently ignored.
+const UNSUPPORTED: &[&str] = &["cassettes", "tools"];
+
+/// A named mock environment: `MOCK_*` settings plus a secrets seed for `pack run`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scenario {
+    pub name: String,
+    pub mocks: BTreeMap<String, String>,
+    pub secrets: BTreeMap<String, String>,
+    /// Scenario entries present on disk that the runner does not support.
+    pub unsupported: Vec<String>,
+}
+
+impl Scenario {
+    pub fn load(name: &str) -> Result<Self> {
+        Self::load_from(Path::new(SCENARIO_DIR), name)
+    }
+
+    fn load_from(root: &Path, name: &str) -> Result<Self> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            bail!("invalid scenario name `{name}`");
+        }
+        let dir = root.join(name);
+        if !dir.is_dir() {
+            let known = available(root)?;
+            bail!(
+                "unknown mock scenario `{name}` (available: {})",
+                if known.is_empty() {
+                    format!("none in {}", root.display())
+                } else {
+                    known.join(", ")
+                }
+            );
+        }
+
+        let mocks = read_optional(&dir.join(MOCKS_FILE))?;
+        if let Some(key) = mocks.keys().find(|key| !key.starts_with(MOCK_ENV_PREFIX)) {
+            bail!(
+                "{}: `{key}` is not a {MOCK_ENV_PREFIX}* setting; put secrets in {SECRETS_FILE}",
+                dir.join(MOCKS_FILE).display()
+            );
+        }
+        Ok(Self {
+            name: name.to_string(),
+            mocks,
+            secrets: read_optional(&dir.join(SECRETS_FILE))?,
+            unsupported: UNSUPPORTED
+                .iter()
+                .filter(|entry| dir.join(entry).exists())
+                .map(|entry| entry.to_string())
+                .collect(),
+        })
+    }
+
+    /// The runner invocation with this scenario's mocks and secrets in its environment. Ambient
+    /// `MOCK_*` settings are dropped so the scenario alone decides how the mocks behave.
+    pub fn command(&self, bin: &Path, args: &[OsString]) -> Command {
+        let mut command = Command::new(bin);
+        command.args(args);
+        self.apply(&mut command);
+        command
+    }
+
+    /// Put this scenario's mocks and secrets, and only those mock settings, into `command`'s
+    /// environment.
+    pub fn apply(&self, command: &mut Command) {
+        for entry in &self.unsupported {
+            events::warn(format_args!(
+                "scenario `{}` has `{entry}/`, which the runner does not support yet; ignoring it",
+                self.name
+            ));
+        }
+        for (key, _) in std::env::vars().filter(|(key, _)| key.starts_with(MOCK_ENV_PREFIX)) {
+            command.env_remove(key);
+        }
+        command.envs(&self.mocks).envs(&self.secrets);
+    }
+}
+
+/// Split `--scenario <NAME>` out of `pack run` arguments; the runner never sees it.
+pub fn take_scenario(run_args: &[OsString]) -> Result<(Option<String>, Vec<OsString>)> {
+    let mut name = None;
+    let mut rest = Vec::with_capacity(run_args.len());
+    let mut iter = run_args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.to_str() {
+            Some("--scenario") => match iter.next().and_then(|value| value.to_str()) {
+                Some(value) => value.to_string(),
+                None => bail!("--scenario needs a scenario name"),
+            },
+            Some(arg) if arg.starts_with("--scenario=") => arg["--scenario=".len()..].to_string(),
+            _ => {
+                rest.push(arg.clone());
+                continue;
+            }
+        };
+        if name.replace(value).is_some() {
+            bail!("--scenario given more than once");
+        }
+    }
+    Ok((name, rest))
+}
+
+fn read_optional(path: &Path) -> Result<BTreeMap<String, String>> {
+    if path.is_file() {
+        read_env_file(path)
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
+
+fn available(root: &Path) -> Result<Vec<String>> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names = fs::read_dir(root)
+        .with_context(|| format!("failed to read {}", root.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path: PathBuf| path.file_name()?.to_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scenario, take_scenario};
+    use std::ffi::OsString;
+    use std::fs;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn scenario_flag_is_removed_from_runner_args() {
+        let (name, rest) =
+            take_scenario(&args(&["--pack", "a.gtpack", "--scenario", "rainy-day"])).unwrap();
+        assert_eq!(name.as_deref(), Some("rainy-day"));
+        assert_eq!(rest, args(&["--pack", "a.gtpack"]));
+
+        let (name, _) = take_scenario(&args(&["--scenario=outage"])).unwrap();
+        assert_eq!(name.as_deref(), Some("outage"));
+        assert!(take_scenario(&args(&["--scenario"])).is_err());
+        assert!(take_scenario(&args(&["--scenario=a", "--scenario=b"])).is_err());
+    }
+
+    #[test]
+    fn scenarios_load_mocks_and_secrets() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("outage");
+        fs::create_dir_all(dir.join("cassettes")).unwrap();
+        fs::write(dir.join("mocks.env"), "MOCK_HTTP_FAIL_PATTERN=^/billing\n").unwrap();
+        fs::write(dir.join("secrets.env"), "API_TOKEN=\"fake\"\n").unwrap();
+
+        let scenario = Scenario::load_from(root.path(), "outage").unwrap();
+        assert_eq!(scenario.mocks["MOCK_HTTP_FAIL_PATTERN"], "^/billing");
+        assert_eq!(scenario.secrets["API_TOKEN"], "fake");
+        assert_eq!(scenario.unsupported, ["cassettes"]);
+
+        let err = Scenario::load_from(root.path(), "sunny").unwrap_err();
+        assert!(err.to_string().contains("available: outage"), "{err}");
+    }
+
+    #[test]
+    fn secrets_do_not_belong_in_mocks_env() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("leaky");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("mocks.env"), "API_TOKEN=real\n").unwrap();
+        let err = Scenario::load_from(root.path(), "leaky").unwrap_err();
+        assert!(err.to_string().contains("secrets.env"), "{err}");
+        assert!(Scenario::load_from(root.path(), "../leaky").is_err());
+    }
+}
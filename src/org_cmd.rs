@@ -0,0 +1,384 @@
+//! `org sync [SOURCE]`: pull an organization's shared defaults into the organization layer of the
+//! greentic-dev config (`org.toml` next to `config.toml`). The layer sits under the config file:
+//! every value it sets applies unless the developer's own config sets the same key.
+//!
+//! The source is a TOML document shaped like the config file, limited to the sections teams
+//! share:
+//!
+//! ```toml
+//! [org]
+//! name = "acme"
+//!
+//! [pack.meta]            # defaults for pack metadata fields a pack leaves unset
+//! vendor = "Acme Corp"
+//! license = "Apache-2.0"
+//!
+//! [lint.rules]           # `flow lint` rule switches
+//! unused-secret = false
+//!
+//! [trust]                # signing policy for `pack verify` / `pack run --validate-only`
+//! policy = "strict"
+//!
+//! [distributor]          # component registry endpoints
+//! default_profile = "acme"
+//! [distributor.profiles.acme]
+//! base_url = "https://distributor.acme.example"
+//! token = "env:ACME_DISTRIBUTOR_TOKEN"
+//! ```
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::blocking::Client;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::cli::{OrgCommand, OrgSyncArgs};
+use crate::config::{self, DefaultProfileSelection, GreenticConfig, TrustPolicy};
+use crate::flow_lint;
+use crate::state_lock::write_atomic;
+
+/// Top-level tables organization defaults may set. The rest of the config (tool paths in
+/// particular) stays under each developer's control.
+const ORG_SECTIONS: &[&str] = &["org", "defaults", "distributor", "pack", "lint", "trust"];
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn run(command: OrgCommand) -> Result<()> {
+    match command {
+        OrgCommand::Sync(args) => sync(&args),
+    }
+}
+
+fn sync(args: &OrgSyncArgs) -> Result<()> {
+    let target = config::org_layer_path().ok_or_else(|| {
+        anyhow!("failed to resolve the config directory (no home directory found)")
+    })?;
+    let source = match &args.source {
+        Some(source) => source.clone(),
+        None => previous_source(&target)?,
+    };
+    let raw = fetch(&source)?;
+    let (mut table, defaults) = parse(&raw, &source)?;
+
+    let org = table
+        .entry("org")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("{source}: `org` must be a table"))?;
+    org.insert("source".to_string(), toml::Value::String(source.clone()));
+    org.insert(
+        "synced_at".to_string(),
+        toml::Value::String(OffsetDateTime::now_utc().format(&Rfc3339)?),
+    );
+
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let contents = format!(
+        "# Organization defaults synced by `greentic-dev org sync` from {source}.\n\
+         # The next sync replaces this file; set overrides in config.toml instead.\n\n{}",
+        toml::to_string(&table)?
+    );
+    write_atomic(&target, contents)?;
+
+    let name = defaults.org.as_ref().and_then(|org| org.name.as_deref());
+    match name {
+        Some(name) => println!("Synced organization defaults `{name}` from {source}"),
+        None => println!("Synced organization defaults from {source}"),
+    }
+    for line in summary(&defaults) {
+        println!("  {line}");
+    }
+    println!("Wrote {}", target.display());
+
+    let overridden = overridden_keys(&table)?;
+    if !overridden.is_empty() {
+        println!(
+            "note: your config overrides {} of them: {}",
+            overridden.len(),
+            overridden.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// The source recorded by the last sync, so `org sync` alone refreshes the defaults.
+fn previous_source(target: &Path) -> Result<String> {
+    let recorded = if target.is_file() {
+        let raw = fs::read_to_string(target)
+            .with_context(|| format!("failed to read {}", target.display()))?;
+        let table: toml::Table = toml::from_str(&raw)
+            .with_context(|| format!("failed to parse {}", target.display()))?;
+        table
+            .get("org")
+            .and_then(|org| org.get("source"))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string)
+    } else {
+        None
+    };
+    recorded.ok_or_else(|| {
+        anyhow!("pass the URL or path of the organization defaults (no earlier sync recorded one)")
+    })
+}
+
+/// Read `source`: an `http(s)://` URL, a `file://` URL or a local path.
+fn fetch(source: &str) -> Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let client = Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .context("failed to build HTTP client")?;
+        return client
+            .get(source)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .with_context(|| format!("failed to fetch {source}"));
+    }
+    let path = source.strip_prefix("file://").unwrap_or(source);
+    fs::read_to_string(path).with_context(|| format!("failed to read {path}"))
+}
+
+/// Parse and check an organization document: only the shared sections, values the config
+/// accepts, known lint rules, and tokens only as `env:` references.
+fn parse(raw: &str, source: &str) -> Result<(toml::Table, GreenticConfig)> {
+    let table: toml::Table =
+        toml::from_str(raw).with_context(|| format!("{source} is not a valid TOML document"))?;
+    if let Some(section) = table
+        .keys()
+        .find(|key| !ORG_SECTIONS.contains(&key.as_str()))
+    {
+        bail!(
+            "{source}: `{section}` cannot be set by organization defaults (allowed: {})",
+            ORG_SECTIONS.join(", ")
+        );
+    }
+    let defaults: GreenticConfig = toml::Value::Table(table.clone())
+        .try_into()
+        .with_context(|| format!("{source}: invalid organization defaults"))?;
+
+    if let Some(rule) = defaults
+        .lint
+        .rules
+        .keys()
+        .find(|rule| !flow_lint::RULES.iter().any(|(id, _)| id == rule))
+    {
+        let known = flow_lint::RULES
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        bail!(
+            "{source}: unknown lint rule `{rule}` (rules: {})",
+            known.join(", ")
+        );
+    }
+
+    let mut profiles = defaults
+        .distributor_profiles()
+        .into_iter()
+        .collect::<Vec<_>>();
+    if let Some(DefaultProfileSelection::Inline(profile)) = &defaults.distributor.default_profile {
+        profiles.push(("default_profile".to_string(), profile.clone()));
+    }
+    for (name, profile) in profiles {
+        if profile
+            .token
+            .as_deref()
+            .is_some_and(|token| !token.starts_with("env:"))
+        {
+            bail!(
+                "{source}: distributor profile `{name}` has a literal token; organization defaults \
+                 may only reference one as `env:VAR`"
+            );
+        }
+    }
+    Ok((table, defaults))
+}
+
+/// One line per kind of default the document sets.
+fn summary(defaults: &GreenticConfig) -> Vec<String> {
+    let mut lines = Vec::new();
+    let meta = &defaults.pack.meta;
+    let fields = [
+        ("authors", meta.authors.is_some()),
+        ("license", meta.license.is_some()),
+        ("homepage", meta.homepage.is_some()),
+        ("support", meta.support.is_some()),
+        ("vendor", meta.vendor.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(field, set)| set.then_some(field))
+    .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        lines.push(format!("pack metadata: {}", fields.join(", ")));
+    }
+    let component = &defaults.defaults.component;
+    let fields = [
+        ("org", component.org.is_some()),
+        ("template", component.template.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(field, set)| set.then_some(field))
+    .collect::<Vec<_>>();
+    if !fields.is_empty() {
+        lines.push(format!("component defaults: {}", fields.join(", ")));
+    }
+    if !defaults.lint.rules.is_empty() {
+        let rules = defaults
+            .lint
+            .rules
+            .iter()
+            .map(|(rule, on)| format!("{rule} {}", if *on { "on" } else { "off" }))
+            .collect::<Vec<_>>();
+        lines.push(format!("lint rules: {}", rules.join(", ")));
+    }
+    if let Some(policy) = defaults.trust.policy {
+        let policy = match policy {
+            TrustPolicy::Strict => "strict",
+            TrustPolicy::Devok => "devok",
+        };
+        lines.push(format!("trust policy: {policy}"));
+    }
+    let mut endpoints = defaults
+        .distributor_profiles()
+        .into_keys()
+        .collect::<Vec<_>>();
+    endpoints.sort();
+    let default = match &defaults.distributor.default_profile {
+        Some(DefaultProfileSelection::Name(name)) => Some(name.clone()),
+        Some(DefaultProfileSelection::Inline(profile)) => {
+            let name = profile
+                .name
+                .clone()
+                .unwrap_or_else(|| "default".to_string());
+            endpoints.push(name.clone());
+            Some(name)
+        }
+        None => None,
+    };
+    if !endpoints.is_empty() {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|name| {
+                if default.as_ref() == Some(&name) {
+                    format!("{name} (default)")
+                } else {
+                    name
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("registry endpoints: {}", endpoints.join(", ")));
+    }
+    lines
+}
+
+/// Keys the organization sets that the developer's config file sets too (and so overrides).
+fn overridden_keys(org: &toml::Table) -> Result<Vec<String>> {
+    let Some(path) = config::resolve_config_path(None)
+        .selected
+        .filter(|path| path.is_file())
+    else {
+        return Ok(Vec::new());
+    };
+    let raw =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let local: toml::Table =
+        toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))?;
+    let mut keys = Vec::new();
+    shared_keys(org, &local, "", &mut keys);
+    Ok(keys)
+}
+
+fn shared_keys(org: &toml::Table, local: &toml::Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in org {
+        if prefix.is_empty() && key == "org" {
+            continue;
+        }
+        let Some(local_value) = local.get(key) else {
+            continue;
+        };
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (value, local_value) {
+            (toml::Value::Table(org), toml::Value::Table(local)) => {
+                shared_keys(org, local, &path, out)
+            }
+            _ => out.push(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"
+[org]
+name = "acme"
+
+[pack.meta]
+vendor = "Acme Corp"
+license = "Apache-2.0"
+
+[lint.rules]
+unused-secret = false
+
+[trust]
+policy = "strict"
+
+[distributor]
+default_profile = "acme"
+
+[distributor.profiles.acme]
+base_url = "https://distributor.acme.example"
+token = "env:ACME_DISTRIBUTOR_TOKEN"
+"#;
+
+    #[test]
+    fn documents_are_summarised_by_kind_of_default() {
+        let (_, defaults) = parse(DOCUMENT, "org.toml").unwrap();
+        assert_eq!(
+            summary(&defaults),
+            [
+                "pack metadata: license, vendor",
+                "lint rules: unused-secret off",
+                "trust policy: strict",
+                "registry endpoints: acme (default)",
+            ]
+        );
+    }
+
+    #[test]
+    fn documents_are_limited_to_shared_settings() {
+        let reject = |raw: &str| parse(raw, "org.toml").unwrap_err().to_string();
+        let err = reject("[tools.greentic-component]\npath = \"/tmp/evil\"\n");
+        assert!(err.contains("`tools` cannot be set"), "{err}");
+        let err = reject("[lint.rules]\nmissing_end = false\n");
+        assert!(err.contains("unknown lint rule `missing_end`"), "{err}");
+        let err = reject("[trust]\npolicy = \"lenient\"\n");
+        assert!(err.contains("invalid organization defaults"), "{err}");
+        let err = reject("[pack.meta]\npack_id = \"acme.pack\"\n");
+        assert!(err.contains("invalid organization defaults"), "{err}");
+        let err = reject("[distributor.profiles.acme]\ntoken = \"hunter2\"\n");
+        assert!(err.contains("profile `acme` has a literal token"), "{err}");
+    }
+
+    #[test]
+    fn shared_keys_are_reported_as_dotted_paths() {
+        let org: toml::Table = toml::from_str(DOCUMENT).unwrap();
+        let local: toml::Table = toml::from_str(
+            "[org]\nname = \"me\"\n[trust]\npolicy = \"devok\"\n[pack.meta]\nauthors = []\n",
+        )
+        .unwrap();
+        let mut keys = Vec::new();
+        shared_keys(&org, &local, "", &mut keys);
+        assert_eq!(keys, ["trust.policy"]);
+    }
+}
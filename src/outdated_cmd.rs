@@ -0,0 +1,345 @@
+use std::fs;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_distributor_client::oci_components::{
+    ComponentResolveOptions, ComponentsExtension, ComponentsMode, OciComponentResolver,
+};
+use oci_distribution::Reference;
+use oci_distribution::client::{Client, ClientConfig, ClientProtocol};
+use oci_distribution::secrets::RegistryAuth;
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::cli::{CheckArgs, OutdatedArgs};
+use crate::component_resolver::registry_client;
+use crate::config;
+use crate::distributor::{DevDistributorClient, DevIntent, DevResolveRequest, resolve_profile};
+use crate::download::DownloadOptions;
+use crate::pack_init::{
+    PackInitIntent, WorkspaceComponent, WorkspaceManifest, edit_workspace_manifest, manifest_path,
+    run_component_add,
+};
+use crate::runtime;
+use crate::workspace_check;
+
+const OCI_SCHEME: &str = "oci://";
+const DEFAULT_OAUTH_USER: &str = "oauth2";
+
+/// How far the newest available version is from the pinned one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeKind {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+}
+
+/// A pinned component with a newer version available.
+#[derive(Debug, Serialize)]
+pub struct Upgrade {
+    pub name: String,
+    pub coordinate: String,
+    pub current: String,
+    pub latest: String,
+    pub kind: UpgradeKind,
+    /// `latest` is outside the caret range of `current` (`^current`), so it may break callers.
+    pub breaking: bool,
+}
+
+/// `greentic-dev outdated`: compare the components pinned in `.greentic/manifest.json` with the
+/// newest versions in the distributor or their OCI repository, and optionally update them.
+pub fn run(args: &OutdatedArgs) -> Result<()> {
+    let path = manifest_path()?;
+    if !path.is_file() {
+        bail!(
+            "{} not found; add components with `greentic-dev component add` first",
+            path.display()
+        );
+    }
+    let data =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let manifest: WorkspaceManifest = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let mut upgrades = Vec::new();
+    for component in &manifest.components {
+        let latest = latest_version(component, args.profile.as_deref())
+            .with_context(|| format!("failed to look up {}", component.coordinate))?;
+        if let Some(latest) = latest
+            && let Some(upgrade) = classify(component, &latest)
+        {
+            upgrades.push(upgrade);
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&upgrades)?);
+    } else if upgrades.is_empty() {
+        println!(
+            "All {} pinned component(s) are up to date",
+            manifest.components.len()
+        );
+    } else {
+        print_upgrades(&upgrades);
+    }
+    if !args.update || upgrades.is_empty() {
+        return Ok(());
+    }
+
+    for upgrade in &upgrades {
+        match upgrade.coordinate.strip_prefix(OCI_SCHEME) {
+            Some(reference) => update_oci(upgrade, reference)?,
+            None => {
+                let (base, _) = split_coordinate(&upgrade.coordinate);
+                run_component_add(
+                    &format!("{base}@{}", upgrade.latest),
+                    args.profile.as_deref(),
+                    PackInitIntent::Dev,
+                )?;
+            }
+        }
+    }
+    println!("Updated {} component(s)", upgrades.len());
+
+    if std::path::Path::new("flows").is_dir() {
+        println!("Rebuilding the workspace with the updated components");
+        let code = workspace_check::run(&CheckArgs {
+            secrets_env: None,
+            json: false,
+        })?;
+        if code != workspace_check::EXIT_OK {
+            bail!("components were updated, but the workspace no longer builds cleanly");
+        }
+    }
+    Ok(())
+}
+
+/// Classify `latest` against the version pinned for `component`. Returns `None` when `latest` is
+/// not newer.
+pub fn classify(component: &WorkspaceComponent, latest: &Version) -> Option<Upgrade> {
+    let current = &component.entry.version;
+    if latest <= current {
+        return None;
+    }
+    let kind = if latest.major != current.major {
+        UpgradeKind::Major
+    } else if latest.minor != current.minor {
+        UpgradeKind::Minor
+    } else if latest.patch != current.patch {
+        UpgradeKind::Patch
+    } else {
+        UpgradeKind::Prerelease
+    };
+    let compatible = VersionReq::parse(&format!("^{current}"))
+        .map(|req| req.matches(latest))
+        .unwrap_or(false);
+    Some(Upgrade {
+        name: component.entry.name.clone(),
+        coordinate: component.coordinate.clone(),
+        current: current.to_string(),
+        latest: latest.to_string(),
+        kind,
+        breaking: !compatible,
+    })
+}
+
+fn latest_version(
+    component: &WorkspaceComponent,
+    profile: Option<&str>,
+) -> Result<Option<Version>> {
+    if let Some(reference) = component.coordinate.strip_prefix(OCI_SCHEME) {
+        return latest_oci_tag(reference).map(|latest| latest.map(|(version, _)| version));
+    }
+    let config = config::load_with_meta(None)?;
+    let profile = resolve_profile(&config, profile)?;
+    let client = DevDistributorClient::from_profile(profile)?;
+    let (base, _) = split_coordinate(&component.coordinate);
+    let resolved = client
+        .resolve(&DevResolveRequest {
+            coordinate: format!("{base}@*"),
+            intent: DevIntent::Dev,
+            platform: Some("wasm32-wasip2".to_string()),
+            features: Vec::new(),
+        })
+        .map_err(|err| anyhow!(err))?;
+    Version::parse(&resolved.version)
+        .map(Some)
+        .with_context(|| {
+            format!(
+                "distributor returned invalid version `{}`",
+                resolved.version
+            )
+        })
+}
+
+// Newest semver tag (a leading `v` is allowed) in the repository of `reference`, with the tag as
+// written in the registry.
+fn latest_oci_tag(reference: &str) -> Result<Option<(Version, String)>> {
+    let parsed = Reference::from_str(reference)
+        .with_context(|| format!("invalid OCI reference `{reference}`"))?;
+    let client = Client::new(ClientConfig {
+        protocol: ClientProtocol::Https,
+        ..Default::default()
+    });
+    let auth = registry_auth()
+        .map(|token| RegistryAuth::Basic(DEFAULT_OAUTH_USER.to_string(), token))
+        .unwrap_or(RegistryAuth::Anonymous);
+    let response = runtime::block_on(async {
+        client
+            .list_tags(&parsed, &auth, None, None)
+            .await
+            .context("failed to list tags")
+    })?;
+    Ok(response
+        .tags
+        .into_iter()
+        .filter_map(|tag| {
+            Version::parse(tag.strip_prefix('v').unwrap_or(&tag))
+                .ok()
+                .map(|version| (version, tag))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0)))
+}
+
+fn update_oci(upgrade: &Upgrade, reference: &str) -> Result<()> {
+    let parsed = Reference::from_str(reference)
+        .with_context(|| format!("invalid OCI reference `{reference}`"))?;
+    let (_, tag) = latest_oci_tag(reference)?
+        .filter(|(version, _)| version.to_string() == upgrade.latest)
+        .ok_or_else(|| anyhow!("{} {} is no longer listed", reference, upgrade.latest))?;
+    let new_reference = format!("{}/{}:{tag}", parsed.registry(), parsed.repository());
+    let options = ComponentResolveOptions {
+        allow_tags: true,
+        ..ComponentResolveOptions::default()
+    };
+    let resolver = OciComponentResolver::with_client(
+        registry_client(&options.cache_dir, DownloadOptions::default()),
+        options,
+    );
+    let extension = ComponentsExtension {
+        refs: vec![new_reference.clone()],
+        mode: ComponentsMode::Eager,
+    };
+    let resolved = runtime::block_on(async {
+        resolver
+            .resolve_refs(&extension)
+            .await
+            .with_context(|| format!("failed to pull {new_reference}"))
+    })?;
+    let pulled = resolved
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("{new_reference} resolved to nothing"))?;
+
+    edit_workspace_manifest(|manifest| {
+        let entry = manifest
+            .components
+            .iter_mut()
+            .find(|component| component.coordinate == upgrade.coordinate)
+            .ok_or_else(|| anyhow!("{} is no longer in the manifest", upgrade.coordinate))?;
+        entry.coordinate = format!("{OCI_SCHEME}{new_reference}");
+        entry.entry.version = Version::parse(&upgrade.latest)?;
+        entry.entry.file_wasm = pulled.path.display().to_string();
+        entry.entry.hash_blake3 = String::new();
+        Ok(())
+    })?;
+    println!(
+        "Updated {} {} -> {}",
+        upgrade.name, upgrade.current, upgrade.latest
+    );
+    Ok(())
+}
+
+/// Registry token for GHCR pulls, from `GHCR_TOKEN` or `GITHUB_TOKEN`.
+pub(crate) fn registry_auth() -> Option<String> {
+    std::env::var("GHCR_TOKEN")
+        .or_else(|_| std::env::var("GITHUB_TOKEN"))
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
+
+fn split_coordinate(coordinate: &str) -> (&str, Option<&str>) {
+    match coordinate.rsplit_once('@') {
+        Some((base, version)) => (base, Some(version)),
+        None => (coordinate, None),
+    }
+}
+
+fn print_upgrades(upgrades: &[Upgrade]) {
+    let width = upgrades
+        .iter()
+        .map(|upgrade| upgrade.name.len())
+        .max()
+        .unwrap_or(0);
+    for upgrade in upgrades {
+        let kind = match upgrade.kind {
+            UpgradeKind::Major => "major",
+            UpgradeKind::Minor => "minor",
+            UpgradeKind::Patch => "patch",
+            UpgradeKind::Prerelease => "prerelease",
+        };
+        println!(
+            "{:<width$}  {} -> {}  {kind}{}",
+            upgrade.name,
+            upgrade.current,
+            upgrade.latest,
+            if upgrade.breaking { " (breaking)" } else { "" }
+        );
+    }
+    println!(
+        "{} upgrade(s) available; run `greentic-dev outdated --update` to apply them",
+        upgrades.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UpgradeKind, classify, split_coordinate};
+    use crate::pack_init::WorkspaceComponent;
+    use greentic_pack::builder::ComponentEntry;
+    use semver::Version;
+
+    fn pinned(version: &str) -> WorkspaceComponent {
+        WorkspaceComponent {
+            coordinate: "component://greentic/echo@^0.3".to_string(),
+            entry: ComponentEntry {
+                name: "echo".to_string(),
+                version: Version::parse(version).unwrap(),
+                file_wasm: "echo.wasm".to_string(),
+                hash_blake3: String::new(),
+                schema_file: None,
+                manifest_file: None,
+                world: None,
+                capabilities: None,
+            },
+        }
+    }
+
+    #[test]
+    fn classifies_upgrades_by_semver_component() {
+        let latest = |v: &str| Version::parse(v).unwrap();
+        let patch = classify(&pinned("1.2.3"), &latest("1.2.4")).unwrap();
+        assert_eq!(patch.kind, UpgradeKind::Patch);
+        assert!(!patch.breaking);
+        let major = classify(&pinned("1.2.3"), &latest("2.0.0")).unwrap();
+        assert_eq!(major.kind, UpgradeKind::Major);
+        assert!(major.breaking);
+        // Below 1.0 a minor bump is outside the caret range.
+        let minor = classify(&pinned("0.3.2"), &latest("0.4.0")).unwrap();
+        assert_eq!(minor.kind, UpgradeKind::Minor);
+        assert!(minor.breaking);
+        assert!(classify(&pinned("1.2.3"), &latest("1.2.3")).is_none());
+        assert!(classify(&pinned("1.2.3"), &latest("1.0.0")).is_none());
+    }
+
+    #[test]
+    fn splits_version_off_coordinates() {
+        assert_eq!(
+            split_coordinate("component://greentic/echo@^0.3"),
+            ("component://greentic/echo", Some("^0.3"))
+        );
+        assert_eq!(split_coordinate("echo"), ("echo", None));
+    }
+}
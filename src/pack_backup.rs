@@ -0,0 +1,289 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::cli::UndoArgs;
+use crate::state_lock::StateLock;
+
+pub const BACKUP_DIR: &str = ".greentic/backups";
+const BACKUP_META: &str = "backup.json";
+
+/// What was backed up, kept next to the copy as `backup.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupMeta {
+    pub created_at: String,
+    /// The delegated command that was about to rewrite the file, e.g. `pack sign`.
+    pub command: String,
+    /// Absolute path of the file the backup restores.
+    pub original: PathBuf,
+}
+
+/// A backup taken before a delegated command rewrites a pack or manifest.
+#[derive(Debug)]
+pub struct Backup {
+    dir: PathBuf,
+    original: PathBuf,
+    contents: Vec<u8>,
+}
+
+/// The pack or manifest that `pack new-provider`, `pack meta set` and `pack sign` rewrite:
+/// the `--pack` value, or the first `.gtpack`/`.cbor` argument. Other subcommands return `None`.
+pub fn guarded_target(args: &[OsString]) -> Option<(String, PathBuf)> {
+    let words = args.iter().map(|arg| arg.to_str()).collect::<Vec<_>>();
+    let (command, rest) = match words.as_slice() {
+        [Some("meta"), Some("set"), ..] => ("pack meta set".to_string(), &args[2..]),
+        [Some(sub @ ("new-provider" | "sign")), ..] => (format!("pack {sub}"), &args[1..]),
+        _ => return None,
+    };
+    let mut iter = rest.iter();
+    let mut positional = None;
+    while let Some(arg) = iter.next() {
+        let Some(text) = arg.to_str() else {
+            continue;
+        };
+        if let Some(value) = text.strip_prefix("--pack=") {
+            return Some((command, PathBuf::from(value)));
+        }
+        if matches!(text, "--pack" | "-p") {
+            return iter.next().map(|value| (command, PathBuf::from(value)));
+        }
+        let path = Path::new(text);
+        if positional.is_none()
+            && !text.starts_with('-')
+            && path
+                .extension()
+                .is_some_and(|ext| ext == "gtpack" || ext == "cbor")
+        {
+            positional = Some(path.to_path_buf());
+        }
+    }
+    positional.map(|path| (command, path))
+}
+
+/// Copy `path` to a new timestamped directory under `.greentic/backups/` before `command` rewrites
+/// it. Returns `None` when the file does not exist yet, since there is nothing to restore.
+pub fn backup(command: &str, path: &Path) -> Result<Option<Backup>> {
+    backup_in(Path::new(BACKUP_DIR), command, path)
+}
+
+fn backup_in(root: &Path, command: &str, path: &Path) -> Result<Option<Backup>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let original =
+        fs::canonicalize(path).with_context(|| format!("failed to resolve {}", path.display()))?;
+    let contents =
+        fs::read(&original).with_context(|| format!("failed to read {}", path.display()))?;
+    let _lock = StateLock::acquire(root)?;
+    let now = OffsetDateTime::now_utc();
+    let dir = root.join(backup_name(now));
+    fs::create_dir(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let file_name = original
+        .file_name()
+        .ok_or_else(|| anyhow!("{} has no file name", path.display()))?;
+    fs::write(dir.join(file_name), &contents)
+        .with_context(|| format!("failed to back up {}", path.display()))?;
+    let meta = BackupMeta {
+        created_at: now.format(&Rfc3339).context("format backup timestamp")?,
+        command: command.to_string(),
+        original: original.clone(),
+    };
+    fs::write(dir.join(BACKUP_META), serde_json::to_vec_pretty(&meta)?)
+        .with_context(|| format!("failed to write {}", dir.join(BACKUP_META).display()))?;
+    Ok(Some(Backup {
+        dir,
+        original,
+        contents,
+    }))
+}
+
+// Sortable and unique enough for backups taken by hand: `20261017T093012.123456789Z`.
+fn backup_name(at: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}.{:09}Z",
+        at.year(),
+        u8::from(at.month()),
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second(),
+        at.nanosecond()
+    )
+}
+
+impl Backup {
+    /// Keep the backup if the command changed the file, and drop it otherwise.
+    pub fn finish(self) -> Result<()> {
+        let unchanged = fs::read(&self.original).is_ok_and(|now| now == self.contents);
+        if unchanged {
+            return fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("failed to remove {}", self.dir.display()));
+        }
+        eprintln!(
+            "Backed up {} to {}; run `greentic-dev undo` to restore it",
+            self.original.display(),
+            self.dir.display()
+        );
+        Ok(())
+    }
+}
+
+/// `greentic-dev undo`: restore the file saved by the newest backup and drop that backup, so
+/// repeated undos walk further back. `--list` only prints the backups, newest first.
+pub fn undo(args: &UndoArgs) -> Result<()> {
+    undo_in(Path::new(BACKUP_DIR), args.list)
+}
+
+fn undo_in(root: &Path, list: bool) -> Result<()> {
+    if !root.is_dir() {
+        bail!("no backups in {}", root.display());
+    }
+    let _lock = StateLock::acquire(root)?;
+    let backups = read_backups(root)?;
+    if list {
+        if backups.is_empty() {
+            println!("No backups in {}", root.display());
+        }
+        for (dir, meta) in backups.iter().rev() {
+            println!(
+                "{}  {}  {}",
+                dir.file_name().unwrap_or_default().to_string_lossy(),
+                meta.command,
+                meta.original.display()
+            );
+        }
+        return Ok(());
+    }
+    let (dir, meta) = backups
+        .last()
+        .ok_or_else(|| anyhow!("no backups in {}", root.display()))?;
+    let file_name = meta
+        .original
+        .file_name()
+        .ok_or_else(|| anyhow!("{} has no file name", meta.original.display()))?;
+    let saved = dir.join(file_name);
+    fs::copy(&saved, &meta.original).with_context(|| {
+        format!(
+            "failed to restore {} from {}",
+            meta.original.display(),
+            saved.display()
+        )
+    })?;
+    fs::remove_dir_all(dir).with_context(|| format!("failed to remove {}", dir.display()))?;
+    println!(
+        "Restored {} as it was before `greentic-dev {}` ({})",
+        meta.original.display(),
+        meta.command,
+        meta.created_at
+    );
+    Ok(())
+}
+
+/// Backups in `root`, oldest first.
+fn read_backups(root: &Path) -> Result<Vec<(PathBuf, BackupMeta)>> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(root).with_context(|| format!("failed to read {}", root.display()))? {
+        let dir = entry?.path();
+        let meta_path = dir.join(BACKUP_META);
+        if !meta_path.is_file() {
+            continue;
+        }
+        let data = fs::read(&meta_path)
+            .with_context(|| format!("failed to read {}", meta_path.display()))?;
+        let meta: BackupMeta = serde_json::from_slice(&data)
+            .with_context(|| format!("failed to parse {}", meta_path.display()))?;
+        backups.push((dir, meta));
+    }
+    backups.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backup_in, guarded_target, undo_in};
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn only_rewriting_subcommands_are_guarded() {
+        assert_eq!(
+            guarded_target(&args(&[
+                "sign",
+                "--key",
+                "k.pem",
+                "--pack",
+                "dist/a.gtpack"
+            ])),
+            Some(("pack sign".to_string(), PathBuf::from("dist/a.gtpack")))
+        );
+        assert_eq!(
+            guarded_target(&args(&[
+                "new-provider",
+                "--pack",
+                "manifest.cbor",
+                "--manifest",
+                "providers/p/provider.yaml"
+            ])),
+            Some((
+                "pack new-provider".to_string(),
+                PathBuf::from("manifest.cbor")
+            ))
+        );
+        assert_eq!(
+            guarded_target(&args(&["meta", "set", "dist/a.gtpack", "version", "1.0.0"])),
+            Some(("pack meta set".to_string(), PathBuf::from("dist/a.gtpack")))
+        );
+        assert_eq!(
+            guarded_target(&args(&["meta", "get", "dist/a.gtpack"])),
+            None
+        );
+        assert_eq!(guarded_target(&args(&["inspect", "dist/a.gtpack"])), None);
+    }
+
+    #[test]
+    fn undo_restores_the_newest_backup_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let pack = dir.path().join("a.gtpack");
+
+        fs::write(&pack, "v1").unwrap();
+        let first = backup_in(&root, "pack sign", &pack).unwrap().unwrap();
+        fs::write(&pack, "v2").unwrap();
+        first.finish().unwrap();
+        let second = backup_in(&root, "pack meta set", &pack).unwrap().unwrap();
+        fs::write(&pack, "v3").unwrap();
+        second.finish().unwrap();
+
+        undo_in(&root, false).unwrap();
+        assert_eq!(fs::read_to_string(&pack).unwrap(), "v2");
+        undo_in(&root, false).unwrap();
+        assert_eq!(fs::read_to_string(&pack).unwrap(), "v1");
+        assert!(undo_in(&root, false).is_err());
+    }
+
+    #[test]
+    fn unchanged_files_leave_no_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let pack = dir.path().join("manifest.cbor");
+        assert!(backup_in(&root, "pack sign", &pack).unwrap().is_none());
+
+        fs::write(&pack, "same").unwrap();
+        backup_in(&root, "pack sign", &pack)
+            .unwrap()
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert!(undo_in(&root, false).is_err());
+    }
+}
@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow, bail};
 use greentic_flow::flow_bundle::{blake3_hex, canonicalize_json, load_and_validate_bundle};
@@ -15,22 +18,55 @@ use greentic_pack::builder::{
 use greentic_pack::events::EventsSection;
 use greentic_pack::messaging::MessagingSection;
 use greentic_pack::repo::{InterfaceBinding, RepoPackSection};
+use schemars::JsonSchema;
 use semver::Version;
 use semver::VersionReq;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue, json};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
+use crate::capability_policy::{
+    self, CapabilityPolicy, POLICY_PATH, PackPolicy, RequestedCapabilities,
+};
+use crate::component_lock;
 use crate::component_resolver::{
-    ComponentResolver, NodeSchemaError, ResolvedComponent, ResolvedNode,
+    ComponentResolver, NodeSchemaError, OCI_SCHEME, OciOptions, ResolvedComponent, ResolvedNode,
 };
+use crate::events;
+use crate::flow_calls::{self, CallTargets};
+use crate::flow_contract::FlowContract;
+use crate::flow_graph;
+use crate::interrupt::PartialOutput;
+use crate::pack_cli::PackBuildArgs;
+use crate::pack_prune::{self, PrunedComponent};
+use crate::pack_yaml::{PACK_YAML, PackYaml};
 use crate::path_safety::normalize_under_root;
+use crate::resolve_map::{RESOLVE_MAP_PATH, ResolveMap};
+use crate::runtime;
+use crate::sarif::{self, Diagnostic, OutputFormat};
+use crate::signing_keys::{self, NamedKey};
+use crate::state_lock::{StateLock, write_atomic};
+use crate::util::files;
+use crate::util::parallel::parallel_map;
+use crate::watch_cmd;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum PackSigning {
     Dev,
     None,
+    /// A named key from `greentic-dev keys`.
+    Key(Arc<NamedKey>),
+}
+
+impl PackSigning {
+    /// The named key `--sign-key` selects, or dev signing.
+    pub fn for_key(name: Option<&str>) -> Result<Self> {
+        Ok(match name {
+            Some(name) => Self::Key(Arc::new(signing_keys::load(name)?)),
+            None => Self::Dev,
+        })
+    }
 }
 
 impl From<PackSigning> for Signing {
@@ -38,8 +74,118 @@ impl From<PackSigning> for Signing {
         match value {
             PackSigning::Dev => Signing::Dev,
             PackSigning::None => Signing::None,
+            PackSigning::Key(key) => Signing::External(key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackBuildOptions {
+    /// Fail the build when a node omits its operation instead of defaulting to the first
+    /// operation declared by the component.
+    pub require_operations: bool,
+    /// Accept components that resolve differently from `greentic-dev.lock` and rewrite the lock,
+    /// instead of failing the build.
+    pub update_lock: bool,
+    /// Resolve `oci://` components only from the local cache.
+    pub offline: bool,
+    /// Accept `oci://` component refs pinned by tag rather than by digest.
+    pub allow_oci_tags: bool,
+    /// Cap on the download rate of `oci://` component pulls, in bytes per second.
+    pub limit_rate: Option<u64>,
+    /// Leave out components that only nodes unreachable from the entry flows use.
+    pub prune: bool,
+}
+
+impl PackBuildOptions {
+    fn resolver(&self, component_dir: Option<PathBuf>) -> Result<ComponentResolver> {
+        Ok(ComponentResolver::new(component_dir)
+            .with_oci(OciOptions {
+                offline: self.offline,
+                allow_tags: self.allow_oci_tags,
+                limit_rate: self.limit_rate,
+                ..OciOptions::default()
+            })
+            .with_resolve_map(ResolveMap::load(Path::new(RESOLVE_MAP_PATH))?))
+    }
+}
+
+/// What a build produced. Building never prints; the `pack build` adapters render this.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackBuildReport {
+    /// Where the pack was written.
+    pub pack: PathBuf,
+    pub manifest_hash_blake3: String,
+    pub backfilled_operations: Vec<BackfilledOperation>,
+    /// Components `--prune` left out of the pack.
+    pub pruned: Vec<PrunedComponent>,
+    /// Wall-clock time per build phase, in the order the phases ran.
+    pub timings: Vec<PhaseTiming>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub millis: f64,
+}
+
+impl PackBuildReport {
+    // Run `work` as an `--events` phase and record its duration under `phase`.
+    fn time<T, E>(
+        &mut self,
+        phase: impl Into<String>,
+        work: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let phase = phase.into();
+        let started = Instant::now();
+        let result = events::phase(phase.clone(), work);
+        self.record(phase, started.elapsed());
+        result
+    }
+
+    // A phase recorded again (schema validation, once per flow) adds to its first entry.
+    fn record(&mut self, phase: impl Into<String>, elapsed: Duration) {
+        let phase = phase.into();
+        let millis = elapsed.as_secs_f64() * 1000.0;
+        match self.timings.iter_mut().find(|timing| timing.phase == phase) {
+            Some(timing) => timing.millis += millis,
+            None => self.timings.push(PhaseTiming { phase, millis }),
         }
     }
+
+    /// Render the timings as an aligned table with a total row.
+    pub fn timings_table(&self) -> String {
+        let width = self
+            .timings
+            .iter()
+            .map(|timing| timing.phase.len())
+            .chain(["total".len()])
+            .max()
+            .unwrap_or(0);
+        let total: f64 = self.timings.iter().map(|timing| timing.millis).sum();
+        let mut table = String::new();
+        for timing in &self.timings {
+            table.push_str(&format!(
+                "{:<width$}  {:>10.1} ms  {:>5.1}%\n",
+                timing.phase,
+                timing.millis,
+                if total > 0.0 {
+                    timing.millis / total * 100.0
+                } else {
+                    0.0
+                }
+            ));
+        }
+        table.push_str(&format!("{:<width$}  {total:>10.1} ms\n", "total"));
+        table
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackfilledOperation {
+    pub node_id: String,
+    pub component: String,
+    pub operation: String,
 }
 
 pub fn run(
@@ -48,96 +194,553 @@ pub fn run(
     signing: PackSigning,
     meta_path: Option<&Path>,
     component_dir: Option<&Path>,
-) -> Result<()> {
-    let workspace_root = env::current_dir()
+) -> Result<PackBuildReport> {
+    run_with_options(
+        flow_path,
+        output_path,
+        signing,
+        meta_path,
+        component_dir,
+        PackBuildOptions::default(),
+    )
+}
+
+pub fn run_with_options(
+    flow_path: &Path,
+    output_path: &Path,
+    signing: PackSigning,
+    meta_path: Option<&Path>,
+    component_dir: Option<&Path>,
+    options: PackBuildOptions,
+) -> Result<PackBuildReport> {
+    let workspace_root = workspace_root()?;
+    let input = BuildInput::Flow {
+        flow: normalize_under_root(&workspace_root, flow_path)?,
+        meta: meta_path
+            .map(|path| normalize_under_root(&workspace_root, path))
+            .transpose()?,
+    };
+    run_input(&input, output_path, signing, component_dir, options)
+}
+
+/// Build every flow a `pack.yaml` workspace lists into one pack. Components are resolved across
+/// all flows and packaged once each. Without a component directory, `<pack_dir>/components` is
+/// used when it exists.
+pub fn run_pack_dir(
+    pack_dir: &Path,
+    output_path: &Path,
+    signing: PackSigning,
+    component_dir: Option<&Path>,
+    options: PackBuildOptions,
+) -> Result<PackBuildReport> {
+    let pack_dir = normalize_under_root(&workspace_root()?, pack_dir)?;
+    let default_components = pack_dir.join("components");
+    let component_dir = component_dir.or_else(|| {
+        default_components
+            .is_dir()
+            .then_some(default_components.as_path())
+    });
+    run_input(
+        &BuildInput::PackDir(pack_dir),
+        output_path,
+        signing,
+        component_dir,
+        options,
+    )
+}
+
+fn workspace_root() -> Result<PathBuf> {
+    env::current_dir()
         .context("failed to resolve workspace root")?
         .canonicalize()
-        .context("failed to canonicalize workspace root")?;
-    let safe_flow = normalize_under_root(&workspace_root, flow_path)?;
-    let safe_meta = meta_path
-        .map(|path| normalize_under_root(&workspace_root, path))
-        .transpose()?;
+        .context("failed to canonicalize workspace root")
+}
+
+fn run_input(
+    input: &BuildInput,
+    output_path: &Path,
+    signing: PackSigning,
+    component_dir: Option<&Path>,
+    options: PackBuildOptions,
+) -> Result<PackBuildReport> {
     let safe_component_dir = component_dir
-        .map(|dir| normalize_under_root(&workspace_root, dir))
+        .map(|dir| normalize_under_root(&workspace_root()?, dir))
         .transpose()?;
-
-    build_once(
-        &safe_flow,
+    let report = build_once(
+        input,
         output_path,
-        signing,
-        safe_meta.as_deref(),
-        safe_component_dir.as_deref(),
+        signing.clone(),
+        &options.resolver(safe_component_dir.clone())?,
+        options,
     )?;
     if strict_mode_enabled() {
         verify_determinism(
-            &safe_flow,
+            input,
             output_path,
             signing,
-            safe_meta.as_deref(),
             safe_component_dir.as_deref(),
+            options,
         )?;
     }
-    Ok(())
+    Ok(report)
 }
 
-fn build_once(
-    flow_path: &Path,
-    output_path: &Path,
-    signing: PackSigning,
-    meta_path: Option<&Path>,
-    component_dir: Option<&Path>,
-) -> Result<()> {
-    let flow_source = fs::read_to_string(flow_path)
-        .with_context(|| format!("failed to read {}", flow_path.display()))?;
-    let mut flow_doc_json: JsonValue =
-        serde_yaml_bw::from_str(&flow_source).with_context(|| {
-            format!(
-                "failed to parse {} for node resolution",
-                flow_path.display()
-            )
-        })?;
-    let bundle = load_and_validate_bundle(&flow_source, Some(flow_path))
-        .with_context(|| format!("flow validation failed for {}", flow_path.display()))?;
+/// What a local build packages: one flow with optional metadata TOML, or every flow of a
+/// `pack.yaml` workspace.
+#[derive(Debug, Clone)]
+enum BuildInput {
+    Flow {
+        flow: PathBuf,
+        meta: Option<PathBuf>,
+    },
+    PackDir(PathBuf),
+}
 
-    let mut resolver = ComponentResolver::new(component_dir.map(PathBuf::from));
-    let mut resolved_nodes = Vec::new();
-    let mut schema_errors = Vec::new();
+impl BuildInput {
+    // The flow files, with the id `pack.yaml` lists each under.
+    fn flows(&self) -> Result<Vec<(PathBuf, Option<String>)>> {
+        match self {
+            BuildInput::Flow { flow, .. } => Ok(vec![(flow.clone(), None)]),
+            BuildInput::PackDir(dir) => PackYaml::load(dir)?
+                .flows
+                .into_iter()
+                .map(|flow| Ok((normalize_under_root(dir, &flow.file)?, Some(flow.id))))
+                .collect(),
+        }
+    }
+
+    // Pack metadata; `pack.yaml` fields map onto the TOML ones.
+    fn meta_config(&self) -> Result<PackMetaToml> {
+        match self {
+            BuildInput::Flow { meta, .. } => read_pack_meta(meta.as_deref()),
+            BuildInput::PackDir(dir) => {
+                let pack = PackYaml::load(dir)?;
+                Ok(PackMetaToml {
+                    entry_flows: Some(pack.entry_flows()),
+                    pack_id: Some(pack.pack_id),
+                    version: Some(pack.version),
+                    name: pack.name,
+                    kind: pack.kind,
+                    description: pack.description,
+                    vendor: pack.publisher,
+                    ..PackMetaToml::default()
+                })
+            }
+        }
+    }
 
-    for node in &bundle.nodes {
-        if is_builtin_component(&node.component.name) {
-            if node.component.name == "component.exec"
-                && let Some(exec_node) =
-                    resolve_component_exec_node(&mut resolver, node, &flow_doc_json)?
-            {
-                schema_errors.extend(resolver.validate_node(&exec_node)?);
-                resolved_nodes.push(exec_node);
+    // Files a rebuild depends on besides components: the flows and the metadata.
+    fn files(&self) -> Vec<PathBuf> {
+        match self {
+            BuildInput::Flow { flow, meta } => [Some(flow.clone()), meta.clone()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            BuildInput::PackDir(dir) => {
+                let mut files = vec![dir.join(PACK_YAML)];
+                files.extend(
+                    self.flows()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(flow, _)| flow),
+                );
+                files
             }
+        }
+    }
+}
+
+/// `pack build --timings` / `--update-lock`: build locally with dev signing, optionally reporting
+/// where the time went. With `--in`, every flow of the `pack.yaml` workspace is built.
+pub fn run_local(args: &PackBuildArgs) -> Result<()> {
+    let out = local_output_path(args);
+    let signing = PackSigning::for_key(args.sign_key.as_deref())?;
+    let options = local_options(args);
+    let result = match (&args.pack_dir, &args.flow) {
+        (Some(pack_dir), _) => run_pack_dir(
+            pack_dir,
+            &out,
+            signing,
+            args.component_dir.as_deref(),
+            options,
+        ),
+        (None, Some(flow)) => run_with_options(
+            flow,
+            &out,
+            signing,
+            args.meta.as_deref(),
+            args.component_dir.as_deref(),
+            options,
+        ),
+        (None, None) => bail!("pass --flow <FLOW> or --in <DIR>"),
+    };
+    if args.output == OutputFormat::Sarif
+        && let Some(flow) = &args.flow
+    {
+        return print_sarif(flow, result);
+    }
+    let report = result?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report, args.flow.as_deref(), args.timings);
+    }
+    Ok(())
+}
+
+fn local_options(args: &PackBuildArgs) -> PackBuildOptions {
+    PackBuildOptions {
+        update_lock: args.update_lock,
+        offline: args.offline,
+        allow_oci_tags: args.allow_oci_tags,
+        limit_rate: args.limit_rate,
+        prune: args.prune,
+        require_operations: args.require_operations,
+    }
+}
+
+/// Rules `pack build --output sarif` reports under.
+const SARIF_RULES: &[(&str, &str)] = &[(
+    "component-schema",
+    "a node's configuration does not match its component's schema",
+)];
+
+// `--output sarif`: flow validation and component schema failures become results on the flow;
+// a clean build is an empty log. Other failures are not tied to a line and stay plain errors.
+fn print_sarif(flow: &Path, result: Result<PackBuildReport>) -> Result<()> {
+    let Err(err) = result else {
+        return sarif::print(SARIF_RULES, &[]);
+    };
+    let source = fs::read_to_string(flow).unwrap_or_default();
+    let diagnostics = match err.downcast_ref::<SchemaValidationError>() {
+        Some(SchemaValidationError(errors)) => errors
+            .iter()
+            .map(|error| {
+                Diagnostic::new(
+                    "component-schema",
+                    format!(
+                        "node `{}` ({}) {}: {}",
+                        error.node_id, error.component, error.pointer, error.message
+                    ),
+                    flow,
+                )
+                .at_line(sarif::node_line(&source, &error.node_id))
+            })
+            .collect(),
+        None => match sarif::flow_error_diagnostics(&err, flow, &source) {
+            Some(diagnostics) => diagnostics,
+            None => return Err(err),
+        },
+    };
+    let rules = SARIF_RULES
+        .iter()
+        .chain(sarif::FLOW_RULES)
+        .copied()
+        .collect::<Vec<_>>();
+    sarif::print(&rules, &diagnostics)?;
+    Err(err)
+}
+
+// The human-readable build summary: backfilled operations on stderr, then the pack and,
+// with `--timings`, where the time went.
+fn print_report(report: &PackBuildReport, flow: Option<&Path>, timings: bool) {
+    let flow = flow.map_or_else(|| "<flow>".to_string(), |flow| flow.display().to_string());
+    for backfill in &report.backfilled_operations {
+        events::warn(format_args!(
+            "node `{}` ({}) has no operation; defaulted to `{}` (set it with `greentic-dev flow set-operation {} {} <op>`)",
+            backfill.node_id, backfill.component, backfill.operation, flow, backfill.node_id
+        ));
+    }
+    println!(
+        "✓ Pack built at {} (manifest hash {})",
+        report.pack.display(),
+        report.manifest_hash_blake3
+    );
+    if !report.pruned.is_empty() {
+        println!(
+            "  pruned {} component(s) no entry flow reaches:",
+            report.pruned.len()
+        );
+        for pruned in &report.pruned {
+            println!(
+                "    - {}@{} (nodes: {})",
+                pruned.name,
+                pruned.version,
+                pruned.nodes.join(", ")
+            );
+        }
+    }
+    if timings {
+        print!("{}", report.timings_table());
+    }
+}
+
+/// `pack build --watch`: build locally with dev signing, then rebuild whenever the flow, the pack
+/// metadata, the capability policy or a component changes. One resolver is kept for the whole
+/// session, so only components whose files changed are prepared again. Runs until Ctrl-C.
+pub fn watch(args: &PackBuildArgs) -> Result<()> {
+    let workspace_root = workspace_root()?;
+    let (input, watched_path) = match (&args.pack_dir, &args.flow) {
+        (Some(pack_dir), _) => (
+            BuildInput::PackDir(normalize_under_root(&workspace_root, pack_dir)?),
+            pack_dir,
+        ),
+        (None, Some(flow)) => (
+            BuildInput::Flow {
+                flow: normalize_under_root(&workspace_root, flow)?,
+                meta: args
+                    .meta
+                    .as_deref()
+                    .map(|path| normalize_under_root(&workspace_root, path))
+                    .transpose()?,
+            },
+            flow,
+        ),
+        (None, None) => bail!("pass --flow <FLOW> or --in <DIR>"),
+    };
+    let default_components = match &input {
+        BuildInput::PackDir(dir) => Some(dir.join("components")).filter(|dir| dir.is_dir()),
+        BuildInput::Flow { .. } => None,
+    };
+    let component_dir = args
+        .component_dir
+        .as_deref()
+        .map(|dir| normalize_under_root(&workspace_root, dir))
+        .transpose()?
+        .or(default_components);
+    let out = local_output_path(args);
+    let options = local_options(args);
+    let resolver = options.resolver(component_dir.clone())?;
+    // Loaded once, so a keystore passphrase is asked for only at the start of the session.
+    let signing = PackSigning::for_key(args.sign_key.as_deref())?;
+    let watched = WatchedInputs {
+        input: &input,
+        flow: args.flow.as_deref(),
+        component_dir: component_dir.as_deref(),
+    };
+
+    // Snapshots are taken before each build, so edits made while it runs trigger another one.
+    let mut snapshot = watched.snapshot(&resolver)?;
+    rebuild(&watched, &out, &resolver, &signing, options, args.timings);
+    watched.add_new_sources(&mut snapshot, &resolver)?;
+    println!(
+        "Watching {} for changes; press Ctrl-C to stop",
+        watched_path.display()
+    );
+    loop {
+        runtime::pause(WATCH_POLL);
+        let current = watched.snapshot(&resolver)?;
+        let changed = watch_cmd::changed_paths(&snapshot, &current);
+        if changed.is_empty() {
             continue;
         }
-        let resolved = resolver.resolve_node(node, &flow_doc_json)?;
-        schema_errors.extend(resolver.validate_node(&resolved)?);
-        resolved_nodes.push(resolved);
+        // Editors often save in several writes; let them settle before rebuilding.
+        runtime::pause(WATCH_POLL);
+        let first = changed[0]
+            .strip_prefix(&workspace_root)
+            .unwrap_or(&changed[0]);
+        match changed.len() {
+            1 => println!("\n▶ {} changed; rebuilding", first.display()),
+            count => println!(
+                "\n▶ {} and {} other file(s) changed; rebuilding",
+                first.display(),
+                count - 1
+            ),
+        }
+        snapshot = watched.snapshot(&resolver)?;
+        rebuild(&watched, &out, &resolver, &signing, options, args.timings);
+        watched.add_new_sources(&mut snapshot, &resolver)?;
     }
+}
 
+const WATCH_POLL: Duration = Duration::from_millis(300);
+
+// The files a watched build depends on.
+struct WatchedInputs<'a> {
+    input: &'a BuildInput,
+    /// `--flow`, named in operation hints.
+    flow: Option<&'a Path>,
+    component_dir: Option<&'a Path>,
+}
+
+impl WatchedInputs<'_> {
+    // Modification times of the flows, metadata, policy, the component directory and every file
+    // behind a resolved component (whose wasm may live outside the component directory).
+    fn snapshot(&self, resolver: &ComponentResolver) -> Result<watch_cmd::Snapshot> {
+        let mut snapshot = watch_cmd::Snapshot::new();
+        if let Some(dir) = self.component_dir {
+            for (path, modified) in watch_cmd::scan(dir)? {
+                snapshot.insert(dir.join(path), modified);
+            }
+        }
+        let files = self
+            .input
+            .files()
+            .into_iter()
+            .chain([PathBuf::from(POLICY_PATH)])
+            .chain(resolver.source_files());
+        for path in files {
+            if let Ok(modified) = fs::metadata(&path).and_then(|meta| meta.modified()) {
+                snapshot.insert(path, modified);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    // Add files that a build resolved for the first time, such as a newly referenced component.
+    fn add_new_sources(
+        &self,
+        snapshot: &mut watch_cmd::Snapshot,
+        resolver: &ComponentResolver,
+    ) -> Result<()> {
+        for (path, modified) in self.snapshot(resolver)? {
+            snapshot.entry(path).or_insert(modified);
+        }
+        Ok(())
+    }
+}
+
+// Build once for the watch loop. Failures are reported and the loop keeps watching.
+fn rebuild(
+    watched: &WatchedInputs<'_>,
+    out: &Path,
+    resolver: &ComponentResolver,
+    signing: &PackSigning,
+    options: PackBuildOptions,
+    timings: bool,
+) {
+    let started = Instant::now();
+    let prepared = resolver.prepared_count();
+    match build_once(watched.input, out, signing.clone(), resolver, options) {
+        Ok(report) => {
+            print_report(&report, watched.flow, timings);
+            println!(
+                "  built in {} ms; {} component(s) re-resolved",
+                started.elapsed().as_millis(),
+                resolver.prepared_count() - prepared
+            );
+        }
+        Err(err) => eprintln!("✗ build failed: {err:#}"),
+    }
+}
+
+// `--out`, or `dist/<flow stem>.gtpack` (`dist/<pack directory name>.gtpack` with `--in`).
+fn local_output_path(args: &PackBuildArgs) -> PathBuf {
+    args.out.clone().unwrap_or_else(|| {
+        let stem = match (&args.pack_dir, &args.flow) {
+            (Some(dir), _) => dir.canonicalize().ok().and_then(|dir| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            }),
+            (None, Some(flow)) => flow
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            (None, None) => None,
+        }
+        .unwrap_or_else(|| "pack".to_string());
+        PathBuf::from("dist").join(format!("{stem}.gtpack"))
+    })
+}
+
+fn build_once(
+    input: &BuildInput,
+    output_path: &Path,
+    signing: PackSigning,
+    resolver: &ComponentResolver,
+    options: PackBuildOptions,
+) -> Result<PackBuildReport> {
+    let mut report = PackBuildReport::default();
+    let mut flows = report.time("flow parse", || {
+        let flows = input
+            .flows()?
+            .into_iter()
+            .map(|(path, listed_as)| load_flow(path, listed_as.as_deref()))
+            .collect::<Result<Vec<_>>>()?;
+        anyhow::Ok(flows)
+    })?;
+
+    let mut schema_errors = Vec::new();
+    for flow in &mut flows {
+        let (nodes, errors) = resolve_nodes(&flow.bundle, &flow.doc, resolver, &mut report)?;
+        flow.nodes = nodes;
+        schema_errors.extend(errors);
+    }
     if !schema_errors.is_empty() {
         report_schema_errors(&schema_errors)?;
     }
 
     // Newer runner builds expect node.component.operation to be populated; backfill a default using
     // the first operation declared in the component manifest when the flow omitted it.
-    ensure_node_operations(&mut flow_doc_json, &resolved_nodes)?;
+    for flow in &mut flows {
+        let backfilled =
+            ensure_node_operations(&mut flow.doc, &flow.nodes, options.require_operations)
+                .with_context(|| format!("flow {} is missing operations", flow.path.display()))?;
+        report.backfilled_operations.extend(backfilled);
+    }
 
+    // Every flow's nodes; a component several flows use is locked, checked and packaged once.
+    let resolved_nodes = flows
+        .iter()
+        .flat_map(|flow| flow.nodes.iter().cloned())
+        .collect::<Vec<_>>();
+    component_lock::apply(&resolved_nodes, options.update_lock)?;
     write_resolved_configs(&resolved_nodes)?;
+    // Rewritten with the remaining phase timings once the pack is built.
+    write_build_report(&report)?;
 
-    let meta = load_pack_meta(meta_path, &bundle)?;
-    let mut builder = PackBuilder::new(meta)
-        .with_flow(to_pack_flow_bundle(&bundle, &flow_doc_json, &flow_source))
-        .with_signing(signing.into())
-        .with_provenance(build_provenance());
-
-    for artifact in collect_component_artifacts(&resolved_nodes) {
-        builder = builder.with_component(artifact);
+    let meta = report.time("metadata and policy", || {
+        let meta = load_pack_meta(input.meta_config()?, &flows[0].bundle.id)?;
+        enforce_capability_policy(&meta.pack_id, &resolved_nodes)?;
+        check_flow_calls(&flows, &meta)?;
+        anyhow::Ok(meta)
+    })?;
+    if options.prune {
+        report.pruned = report.time("prune", || {
+            let graphs = flows
+                .iter()
+                .map(|flow| {
+                    let graph = flow_graph::parse(&flow.source).with_context(|| {
+                        format!("failed to read the graph of {}", flow.path.display())
+                    })?;
+                    anyhow::Ok((graph, &flow.doc))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let reachable = pack_prune::reachable_nodes(&graphs, &meta.entry_flows);
+            let mut nodes = Vec::new();
+            for flow in &flows {
+                for node in &flow.nodes {
+                    // Node ids are only unique within a flow.
+                    let label = match flows.len() {
+                        1 => node.node_id.clone(),
+                        _ => format!("{}/{}", flow.bundle.id, node.node_id),
+                    };
+                    let key = (flow.bundle.id.clone(), node.node_id.clone());
+                    nodes.push((label, node, reachable.contains(&key)));
+                }
+            }
+            anyhow::Ok(pack_prune::unused_components(nodes))
+        })?;
     }
+    let pruned = report
+        .pruned
+        .iter()
+        .map(|component| format!("{}@{}", component.name, component.version))
+        .collect::<HashSet<_>>();
+    let builder = report.time("artifact collection", || {
+        let mut builder = PackBuilder::new(meta)
+            .with_signing(signing.into())
+            .with_provenance(build_provenance());
+        for flow in &flows {
+            builder = builder.with_flow(to_pack_flow_bundle(&flow.bundle, &flow.doc, &flow.source));
+        }
+        for artifact in collect_component_artifacts(&resolved_nodes) {
+            if pruned.contains(&format!("{}@{}", artifact.name, artifact.version)) {
+                continue;
+            }
+            builder = builder.with_component(artifact);
+        }
+        anyhow::Ok(builder)
+    })?;
 
     if let Some(parent) = output_path.parent()
         && !parent.as_os_str().is_empty()
@@ -146,15 +749,277 @@ fn build_once(
             .with_context(|| format!("failed to create {}", parent.display()))?;
     }
 
-    let build_result = builder
-        .build(output_path)
-        .context("pack build failed (sign/build stage)")?;
-    println!(
-        "✓ Pack built at {} (manifest hash {})",
-        build_result.out_path.display(),
-        build_result.manifest_hash_blake3
-    );
+    // Build next to the output and rename into place, so an interrupted or failed build never
+    // leaves a truncated pack behind. A `.tmp` left by a killed build is replaced.
+    let partial_path = partial_output_path(output_path);
+    let _partial = PartialOutput::new(&partial_path);
+    if partial_path.exists() {
+        fs::remove_file(&partial_path)
+            .with_context(|| format!("failed to remove stale {}", partial_path.display()))?;
+    }
+    // greentic-pack signs the manifest and writes the archive in one call, so they are one phase.
+    let build_result = match report.time("signing and zip write", || builder.build(&partial_path)) {
+        Ok(result) => result,
+        Err(err) => {
+            let _ = fs::remove_file(&partial_path);
+            return Err(err).context("pack build failed (sign/build stage)");
+        }
+    };
+    fs::rename(&partial_path, output_path)
+        .with_context(|| format!("failed to move the pack to {}", output_path.display()))?;
+    events::artifact(output_path);
+    report.pack = output_path.to_path_buf();
+    report.manifest_hash_blake3 = build_result.manifest_hash_blake3;
+    write_build_report(&report)?;
+    Ok(report)
+}
+
+/// A flow going into the pack, with its resolved nodes.
+struct PackFlow {
+    path: PathBuf,
+    source: String,
+    doc: JsonValue,
+    bundle: greentic_flow::flow_bundle::FlowBundle,
+    nodes: Vec<ResolvedNode>,
+}
+
+// Read, validate and contract-check a flow. `listed_as` is the id `pack.yaml` lists it under.
+fn load_flow(path: PathBuf, listed_as: Option<&str>) -> Result<PackFlow> {
+    let source =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let doc: JsonValue = serde_yaml_bw::from_str(&source)
+        .with_context(|| format!("failed to parse {} for node resolution", path.display()))?;
+    let bundle = load_and_validate_bundle(&source, Some(&path))
+        .with_context(|| format!("flow validation failed for {}", path.display()))?;
+    FlowContract::from_flow_doc(&doc)
+        .compile()
+        .with_context(|| format!("flow {} declares an invalid schema", path.display()))?;
+    if let Some(listed_as) = listed_as
+        && listed_as != bundle.id
+    {
+        bail!(
+            "{} declares flow `{}`, but {PACK_YAML} lists it as `{listed_as}`",
+            path.display(),
+            bundle.id
+        );
+    }
+    Ok(PackFlow {
+        path,
+        source,
+        doc,
+        bundle,
+        nodes: Vec::new(),
+    })
+}
+
+/// One node's resolution, with how long resolving and validating it took.
+struct NodeResolution {
+    node: ResolvedNode,
+    schema_errors: Vec<NodeSchemaError>,
+    resolve_time: Duration,
+    validate_time: Duration,
+}
+
+// Nodes are resolved and validated in parallel, but results are gathered in flow order: the
+// timings, the schema errors and the resolution error reported (that of the first failing node)
+// do not depend on scheduling.
+fn resolve_nodes(
+    bundle: &greentic_flow::flow_bundle::FlowBundle,
+    flow_doc_json: &JsonValue,
+    resolver: &ComponentResolver,
+    report: &mut PackBuildReport,
+) -> Result<(Vec<ResolvedNode>, Vec<NodeSchemaError>)> {
+    let nodes = bundle
+        .nodes
+        .iter()
+        .filter(|node| {
+            !is_builtin_component(&node.component.name) || node.component.name == "component.exec"
+        })
+        .collect::<Vec<_>>();
+    let jobs = thread::available_parallelism().map_or(1, usize::from);
+    let results = parallel_map(&nodes, jobs, |node| {
+        events::phase(format!("resolve {}", node.node_id), || {
+            resolve_one(node, flow_doc_json, resolver)
+        })
+    });
+
+    let mut resolved_nodes = Vec::new();
+    let mut schema_errors = Vec::new();
+    let mut validation = Duration::ZERO;
+    for result in results {
+        let Some(resolution) = result? else {
+            continue;
+        };
+        report.record(
+            format!(
+                "resolve {} ({})",
+                resolution.node.node_id, resolution.node.component.name
+            ),
+            resolution.resolve_time,
+        );
+        validation += resolution.validate_time;
+        schema_errors.extend(resolution.schema_errors);
+        resolved_nodes.push(resolution.node);
+    }
+    report.record("schema validation", validation);
+    Ok((resolved_nodes, schema_errors))
+}
+
+fn resolve_one(
+    node: &greentic_flow::flow_bundle::NodeRef,
+    flow_doc_json: &JsonValue,
+    resolver: &ComponentResolver,
+) -> Result<Option<NodeResolution>> {
+    let started = Instant::now();
+    let resolved = if node.component.name == "component.exec" {
+        resolve_component_exec_node(resolver, node, flow_doc_json)?
+    } else {
+        Some(resolver.resolve_node(node, flow_doc_json)?)
+    };
+    let Some(resolved) = resolved else {
+        return Ok(None);
+    };
+    let resolve_time = started.elapsed();
+    let started = Instant::now();
+    let schema_errors = resolver.validate_node(&resolved)?;
+    Ok(Some(NodeResolution {
+        node: resolved,
+        schema_errors,
+        resolve_time,
+        validate_time: started.elapsed(),
+    }))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityExplanation {
+    pub pack_id: String,
+    /// Policy file that applied, if the workspace has one.
+    pub policy: Option<String>,
+    pub components: Vec<ComponentCapabilities>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentCapabilities {
+    pub component: String,
+    pub version: String,
+    pub nodes: Vec<String>,
+    pub requested: RequestedCapabilities,
+    pub violations: Vec<String>,
+}
+
+/// Resolve the flow's components and report the capabilities each one requests, checked against
+/// the workspace capability policy, without building a pack.
+pub fn explain_capabilities(
+    flow_path: &Path,
+    meta_path: Option<&Path>,
+    component_dir: Option<&Path>,
+) -> Result<CapabilityExplanation> {
+    let flow_source = fs::read_to_string(flow_path)
+        .with_context(|| format!("failed to read {}", flow_path.display()))?;
+    let flow_doc_json: JsonValue = serde_yaml_bw::from_str(&flow_source)
+        .with_context(|| format!("failed to parse {}", flow_path.display()))?;
+    let bundle = load_and_validate_bundle(&flow_source, Some(flow_path))
+        .with_context(|| format!("flow validation failed for {}", flow_path.display()))?;
+    let (nodes, _) = resolve_nodes(
+        &bundle,
+        &flow_doc_json,
+        &ComponentResolver::new(component_dir.map(PathBuf::from))
+            .with_resolve_map(ResolveMap::load(Path::new(RESOLVE_MAP_PATH))?),
+        &mut PackBuildReport::default(),
+    )?;
+    let meta = load_pack_meta(read_pack_meta(meta_path)?, &bundle.id)?;
+    let policy = CapabilityPolicy::load(Path::new(POLICY_PATH))?;
+    let pack_policy = policy
+        .as_ref()
+        .and_then(|policy| policy.for_pack(&meta.pack_id));
+
+    let components = component_capabilities(&nodes, pack_policy);
+    Ok(CapabilityExplanation {
+        pack_id: meta.pack_id,
+        policy: pack_policy.map(|_| POLICY_PATH.to_string()),
+        components,
+    })
+}
+
+fn component_capabilities(
+    nodes: &[ResolvedNode],
+    policy: Option<&PackPolicy>,
+) -> Vec<ComponentCapabilities> {
+    let mut components: Vec<ComponentCapabilities> = Vec::new();
+    for node in nodes {
+        let component = &node.component;
+        let version = component.version.to_string();
+        if let Some(existing) = components
+            .iter_mut()
+            .find(|entry| entry.component == component.name && entry.version == version)
+        {
+            existing.nodes.push(node.node_id.clone());
+            continue;
+        }
+        let requested = component
+            .capabilities_json
+            .as_ref()
+            .map(RequestedCapabilities::from_json)
+            .unwrap_or_default();
+        let violations = policy
+            .map(|policy| capability_policy::check(policy, &component.name, &requested))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|violation| violation.message)
+            .collect();
+        components.push(ComponentCapabilities {
+            component: component.name.clone(),
+            version,
+            nodes: vec![node.node_id.clone()],
+            requested,
+            violations,
+        });
+    }
+    components
+}
+
+fn enforce_capability_policy(pack_id: &str, nodes: &[ResolvedNode]) -> Result<()> {
+    let Some(policy) = CapabilityPolicy::load(Path::new(POLICY_PATH))? else {
+        return Ok(());
+    };
+    let details = component_capabilities(nodes, policy.for_pack(pack_id))
+        .into_iter()
+        .flat_map(|entry| {
+            entry
+                .violations
+                .into_iter()
+                .map(move |violation| format!("  - {}: {violation}", entry.component))
+        })
+        .collect::<Vec<_>>();
+    if details.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "components of pack {pack_id} violate the capability policy in {POLICY_PATH}:\n{}\n(run `greentic-dev pack build --explain-capabilities` to see what each component requests)",
+        details.join("\n")
+    )
+}
 
+// A pack built here carries a single flow, so `flow.call` may target that flow or a flow in
+// one of the pack's imports.
+fn check_flow_calls(flows: &[PackFlow], meta: &PackMeta) -> Result<()> {
+    let mut targets = CallTargets::default();
+    for flow in flows {
+        targets.add_flow(&flow.bundle.id, &flow.doc);
+    }
+    for import in &meta.imports {
+        targets.add_import(&import.pack_id);
+    }
+    let errors = flows
+        .iter()
+        .flat_map(|flow| flow_calls::check(&flow.bundle.id, &flow.doc, &targets))
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        bail!(
+            "unresolved flow.call reference(s):\n  - {}",
+            errors.join("\n  - ")
+        );
+    }
     Ok(())
 }
 
@@ -167,25 +1032,38 @@ fn strict_mode_enabled() -> bool {
     )
 }
 
+fn partial_output_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| "pack.gtpack".into());
+    name.push(".tmp");
+    output_path.with_file_name(name)
+}
+
 fn verify_determinism(
-    flow_path: &Path,
+    input: &BuildInput,
     output_path: &Path,
     signing: PackSigning,
-    meta_path: Option<&Path>,
     component_dir: Option<&Path>,
+    options: PackBuildOptions,
 ) -> Result<()> {
     let temp_dir = tempfile::tempdir().context("failed to create tempdir for determinism check")?;
+    let _partial = PartialOutput::new(temp_dir.path());
     let temp_pack = temp_dir.path().join("deterministic.gtpack");
-    build_once(flow_path, &temp_pack, signing, meta_path, component_dir)
-        .context("determinism build failed")?;
-    let workspace_root = env::current_dir()
-        .context("failed to resolve workspace root")?
-        .canonicalize()
-        .context("failed to canonicalize workspace root")?;
-    let safe_output = normalize_under_root(&workspace_root, output_path)?;
-    let expected = fs::read(&safe_output).context("failed to read primary pack for determinism")?;
-    let actual = fs::read(&temp_pack).context("failed to read temp pack for determinism")?;
-    if expected != actual {
+    build_once(
+        input,
+        &temp_pack,
+        signing,
+        // A fresh resolver, so the second build prepares every component again.
+        &options.resolver(component_dir.map(PathBuf::from))?,
+        options,
+    )
+    .context("determinism build failed")?;
+    let safe_output = normalize_under_root(&workspace_root()?, output_path)?;
+    if !files::same_contents(&safe_output, &temp_pack)
+        .context("failed to compare packs for determinism")?
+    {
         bail!("LOCAL_CHECK_STRICT detected non-deterministic pack output");
     }
     println!("LOCAL_CHECK_STRICT verified deterministic pack output");
@@ -223,12 +1101,18 @@ fn to_pack_flow_bundle(
     }
 }
 
-fn ensure_node_operations(flow_doc_json: &mut JsonValue, nodes: &[ResolvedNode]) -> Result<()> {
+fn ensure_node_operations(
+    flow_doc_json: &mut JsonValue,
+    nodes: &[ResolvedNode],
+    require_operations: bool,
+) -> Result<Vec<BackfilledOperation>> {
+    let mut backfilled = Vec::new();
+    let mut missing = Vec::new();
     let Some(nodes_map) = flow_doc_json
         .get_mut("nodes")
         .and_then(|v| v.as_object_mut())
     else {
-        return Ok(());
+        return Ok(backfilled);
     };
 
     for node in nodes {
@@ -238,7 +1122,14 @@ fn ensure_node_operations(flow_doc_json: &mut JsonValue, nodes: &[ResolvedNode])
         else {
             continue;
         };
-        let Some(config) = entry.get_mut(&node.component.name) else {
+        // component.exec nodes keep their config (including `op`) under the exec payload rather
+        // than under the resolved component name.
+        let payload_key = if entry.contains_key(&node.component.name) {
+            node.component.name.as_str()
+        } else {
+            "component.exec"
+        };
+        let Some(config) = entry.get_mut(payload_key) else {
             continue;
         };
         let Some(cfg_map) = config.as_object_mut() else {
@@ -260,15 +1151,34 @@ fn ensure_node_operations(flow_doc_json: &mut JsonValue, nodes: &[ResolvedNode])
             continue;
         }
 
+        if require_operations {
+            missing.push(format!(
+                "- node `{}` ({})",
+                node.node_id, node.component.name
+            ));
+            continue;
+        }
+
         if let Some(op) = default_operation(&node.component)? {
             cfg_map
                 .entry("operation")
                 .or_insert(JsonValue::String(op.clone()));
-            cfg_map.entry("op").or_insert(JsonValue::String(op));
+            cfg_map.entry("op").or_insert(JsonValue::String(op.clone()));
+            backfilled.push(BackfilledOperation {
+                node_id: node.node_id.clone(),
+                component: node.component.name.clone(),
+                operation: op,
+            });
         }
     }
 
-    Ok(())
+    if !missing.is_empty() {
+        bail!(
+            "operations are required but not set (fix with `greentic-dev flow set-operation <flow> <node> <op>`):\n{}",
+            missing.join("\n")
+        );
+    }
+    Ok(backfilled)
 }
 
 fn default_operation(component: &ResolvedComponent) -> Result<Option<String>> {
@@ -286,6 +1196,7 @@ fn default_operation(component: &ResolvedComponent) -> Result<Option<String>> {
 }
 
 fn write_resolved_configs(nodes: &[ResolvedNode]) -> Result<()> {
+    let _lock = StateLock::acquire(Path::new(".greentic"))?;
     let root = Path::new(".greentic").join("resolved_config");
     fs::create_dir_all(&root).context("failed to create .greentic/resolved_config")?;
     for node in nodes {
@@ -296,12 +1207,20 @@ fn write_resolved_configs(nodes: &[ResolvedNode]) -> Result<()> {
             "version": node.component.version.to_string(),
             "config": node.config,
         }))?;
-        fs::write(&path, contents)
-            .with_context(|| format!("failed to write {}", path.display()))?;
+        write_atomic(&path, contents)?;
     }
     Ok(())
 }
 
+fn write_build_report(report: &PackBuildReport) -> Result<()> {
+    let root = Path::new(".greentic");
+    let _lock = StateLock::acquire(root)?;
+    write_atomic(
+        &root.join("build_report.json"),
+        serde_json::to_string_pretty(report)?,
+    )
+}
+
 fn collect_component_artifacts(nodes: &[ResolvedNode]) -> Vec<ComponentArtifact> {
     let mut map: HashMap<String, ComponentArtifact> = HashMap::new();
     for node in nodes {
@@ -320,7 +1239,7 @@ fn is_builtin_component(name: &str) -> bool {
 }
 
 fn resolve_component_exec_node(
-    resolver: &mut ComponentResolver,
+    resolver: &ComponentResolver,
     node: &greentic_flow::flow_bundle::NodeRef,
     flow_doc_json: &JsonValue,
 ) -> Result<Option<ResolvedNode>> {
@@ -354,6 +1273,10 @@ fn resolve_component_exec_node(
 }
 
 fn parse_component_ref(raw: &str) -> Result<(String, VersionReq)> {
+    // An OCI ref's `@` introduces its digest, not a version requirement.
+    if raw.trim().starts_with(OCI_SCHEME) {
+        return Ok((raw.trim().to_string(), VersionReq::default()));
+    }
     if let Some((name, ver)) = raw.split_once('@') {
         let vr = VersionReq::parse(ver.trim())
             .with_context(|| format!("invalid version requirement `{ver}`"))?;
@@ -382,32 +1305,44 @@ fn to_artifact(component: &Arc<ResolvedComponent>) -> ComponentArtifact {
 }
 
 fn report_schema_errors(errors: &[NodeSchemaError]) -> Result<()> {
-    let mut message = String::new();
-    for err in errors {
-        message.push_str(&format!(
-            "- node `{}` ({}) {}: {}\n",
-            err.node_id, err.component, err.pointer, err.message
-        ));
+    Err(SchemaValidationError(errors.to_vec()).into())
+}
+
+/// Nodes whose configuration does not match their component's schema. Kept typed in the error
+/// chain so `--output sarif` can point at each node.
+#[derive(Debug)]
+pub struct SchemaValidationError(pub Vec<NodeSchemaError>);
+
+impl fmt::Display for SchemaValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "component schema validation failed:")?;
+        for err in &self.0 {
+            writeln!(
+                f,
+                "- node `{}` ({}) {}: {}",
+                err.node_id, err.component, err.pointer, err.message
+            )?;
+        }
+        Ok(())
     }
-    bail!("component schema validation failed:\n{message}");
 }
 
-fn load_pack_meta(
-    meta_path: Option<&Path>,
-    bundle: &greentic_flow::flow_bundle::FlowBundle,
-) -> Result<PackMeta> {
-    let config = if let Some(path) = meta_path {
-        let raw = fs::read_to_string(path)
-            .with_context(|| format!("failed to read {}", path.display()))?;
-        toml::from_str::<PackMetaToml>(&raw)
-            .with_context(|| format!("invalid pack metadata {}", path.display()))?
-    } else {
-        PackMetaToml::default()
+impl std::error::Error for SchemaValidationError {}
+
+fn read_pack_meta(meta_path: Option<&Path>) -> Result<PackMetaToml> {
+    let Some(path) = meta_path else {
+        return Ok(PackMetaToml::default());
     };
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("invalid pack metadata {}", path.display()))
+}
 
+// Unset fields default from the (first) flow's id.
+fn load_pack_meta(config: PackMetaToml, flow_id: &str) -> Result<PackMeta> {
     let pack_id = config
         .pack_id
-        .unwrap_or_else(|| format!("dev.local.{}", bundle.id));
+        .unwrap_or_else(|| format!("dev.local.{flow_id}"));
     let version = config
         .version
         .as_deref()
@@ -415,13 +1350,16 @@ fn load_pack_meta(
         .parse::<Version>()
         .context("invalid pack version in metadata")?;
     let pack_version = config.pack_version.unwrap_or(PACK_VERSION);
-    let name = config.name.unwrap_or_else(|| bundle.id.clone());
+    let name = config.name.unwrap_or_else(|| flow_id.to_string());
     let description = config.description;
-    let authors = config.authors.unwrap_or_default();
-    let license = config.license;
-    let homepage = config.homepage;
-    let support = config.support;
-    let vendor = config.vendor;
+    // Organization-wide defaults (`[pack.meta]` in the greentic-dev config) fill what the pack
+    // leaves unset.
+    let defaults = crate::config::load()?.pack.meta;
+    let authors = config.authors.or(defaults.authors).unwrap_or_default();
+    let license = config.license.or(defaults.license);
+    let homepage = config.homepage.or(defaults.homepage);
+    let support = config.support.or(defaults.support);
+    let vendor = config.vendor.or(defaults.vendor);
     let kind = config.kind;
     let events = config.events;
     let repo = config.repo;
@@ -438,7 +1376,7 @@ fn load_pack_meta(
         .collect();
     let entry_flows = config
         .entry_flows
-        .unwrap_or_else(|| vec![bundle.id.clone()]);
+        .unwrap_or_else(|| vec![flow_id.to_string()]);
     let created_at_utc = config.created_at_utc.unwrap_or_else(|| {
         OffsetDateTime::now_utc()
             .format(&Rfc3339)
@@ -473,6 +1411,11 @@ fn load_pack_meta(
     })
 }
 
+/// JSON Schema for the pack metadata TOML accepted by [`run`] (`meta_path`).
+pub fn pack_meta_schema() -> JsonValue {
+    serde_json::to_value(schemars::schema_for!(PackMetaToml)).expect("pack meta schema serializes")
+}
+
 fn toml_to_json_map(table: toml::value::Table) -> serde_json::Map<String, JsonValue> {
     table
         .into_iter()
@@ -517,7 +1460,8 @@ fn git_remote() -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+#[schemars(title = "greentic pack metadata (pack.toml)")]
 struct PackMetaToml {
     pack_version: Option<u32>,
     pack_id: Option<String>,
@@ -536,14 +1480,55 @@ struct PackMetaToml {
     messaging: Option<MessagingSection>,
     interfaces: Option<Vec<InterfaceBinding>>,
     imports: Option<Vec<ImportToml>>,
+    #[schemars(with = "Option<serde_json::Map<String, JsonValue>>")]
     annotations: Option<toml::value::Table>,
     created_at_utc: Option<String>,
     distribution: Option<DistributionSection>,
     components: Option<Vec<ComponentDescriptor>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 struct ImportToml {
     pack_id: String,
     version_req: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{PackBuildReport, PhaseTiming, parse_component_ref};
+    use semver::VersionReq;
+
+    #[test]
+    fn timings_table_lists_phases_with_a_total() {
+        let report = PackBuildReport {
+            timings: vec![
+                PhaseTiming {
+                    phase: "flow parse".to_string(),
+                    millis: 25.0,
+                },
+                PhaseTiming {
+                    phase: "signing and zip write".to_string(),
+                    millis: 75.0,
+                },
+            ],
+            ..PackBuildReport::default()
+        };
+        let table = report.timings_table();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("flow parse ") && lines[0].ends_with("25.0%"));
+        assert!(lines[1].contains("75.0 ms"));
+        assert!(lines[2].starts_with("total") && lines[2].ends_with("100.0 ms"));
+    }
+
+    #[test]
+    fn oci_component_refs_keep_their_digest() {
+        let reference = "oci://ghcr.io/greentic/echo@sha256:4d9cd7b7";
+        let (name, version_req) = parse_component_ref(reference).unwrap();
+        assert_eq!(name, reference);
+        assert_eq!(version_req, VersionReq::default());
+        let (name, version_req) = parse_component_ref("dev.greentic.echo@^0.3").unwrap();
+        assert_eq!(name, "dev.greentic.echo");
+        assert_eq!(version_req, VersionReq::parse("^0.3").unwrap());
+    }
+}
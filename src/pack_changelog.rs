@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use greentic_pack::builder::Provenance;
+use greentic_pack::reader::PackLoad;
+use serde::Serialize;
+
+use crate::events;
+use crate::pack_cli::PackChangelogArgs;
+use crate::pack_diff::{ComponentDiff, component_diffs};
+use crate::pack_plan::{PlanSummary, load_plan, summarize};
+
+// Changelogs compare the packs as deployed for the local defaults; tenant and environment do not
+// change which components, flows, secrets or providers a pack carries.
+const TENANT: &str = "tenant-local";
+const ENVIRONMENT: &str = "local";
+
+/// What changed between two versions of a pack.
+#[derive(Debug, Default, Serialize)]
+pub struct PackChangelog {
+    pub pack_id: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub old_commit: Option<String>,
+    pub new_commit: Option<String>,
+    pub components: Vec<ComponentDiff>,
+    pub flows: Vec<FlowChange>,
+    pub secrets_added: Vec<String>,
+    pub secrets_removed: Vec<String>,
+    pub providers_added: Vec<String>,
+    pub providers_removed: Vec<String>,
+    pub commits: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Updated,
+    Removed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlowChange {
+    pub id: String,
+    pub change: Change,
+}
+
+/// `pack changelog`: diff two gtpacks and render the differences as a markdown changelog.
+pub fn run(args: &PackChangelogArgs) -> Result<()> {
+    let mut changelog = changelog(&args.old, &args.new)?;
+    if args.git {
+        add_commits(&mut changelog, &args.repo)?;
+    }
+    let rendered = if args.json {
+        format!("{}\n", serde_json::to_string_pretty(&changelog)?)
+    } else {
+        render_markdown(&changelog)
+    };
+    match &args.out {
+        Some(path) => {
+            fs::write(path, rendered)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            events::artifact(path);
+            println!("Changelog written to {}", path.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Compare the components, flows, secrets and providers of `old` and `new`.
+pub fn changelog(old: &Path, new: &Path) -> Result<PackChangelog> {
+    let (old_load, old_plan) = load_plan(old, TENANT, ENVIRONMENT)?;
+    let (new_load, new_plan) = load_plan(new, TENANT, ENVIRONMENT)?;
+    let old_meta = &old_load.manifest.meta;
+    let new_meta = &new_load.manifest.meta;
+    let mut notes = Vec::new();
+    if old_meta.pack_id != new_meta.pack_id {
+        notes.push(format!(
+            "comparing different packs: {} and {}",
+            old_meta.pack_id, new_meta.pack_id
+        ));
+    }
+    let old_summary = summarize(&old_load, &old_plan);
+    let new_summary = summarize(&new_load, &new_plan);
+    let (secrets_added, secrets_removed) =
+        set_changes(secret_keys(&old_summary), secret_keys(&new_summary));
+    let (providers_added, providers_removed) = set_changes(
+        old_summary.providers.iter().cloned().collect(),
+        new_summary.providers.iter().cloned().collect(),
+    );
+
+    Ok(PackChangelog {
+        pack_id: new_meta.pack_id.clone(),
+        old_version: old_meta.version.to_string(),
+        new_version: new_meta.version.to_string(),
+        old_commit: provenance_commit(&old_load, old)?,
+        new_commit: provenance_commit(&new_load, new)?,
+        components: component_diffs(&old_load, &new_load),
+        flows: flow_changes(&old_load, &new_load),
+        secrets_added,
+        secrets_removed,
+        providers_added,
+        providers_removed,
+        commits: Vec::new(),
+        notes,
+    })
+}
+
+// Flows are matched by id; a different flow document hash counts as an update.
+fn flow_changes(old: &PackLoad, new: &PackLoad) -> Vec<FlowChange> {
+    let index = |load: &PackLoad| {
+        load.manifest
+            .flows
+            .iter()
+            .map(|flow| (flow.id.clone(), flow.hash_blake3.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    diff_maps(index(old), index(new))
+        .into_iter()
+        .map(|(id, change, _, _)| FlowChange { id, change })
+        .collect()
+}
+
+pub(crate) type Entry<V> = (String, Change, Option<V>, Option<V>);
+
+pub(crate) fn diff_maps<V: PartialEq>(
+    mut old: BTreeMap<String, V>,
+    new: BTreeMap<String, V>,
+) -> Vec<Entry<V>> {
+    let mut changes = Vec::new();
+    for (key, value) in new {
+        match old.remove(&key) {
+            None => changes.push((key, Change::Added, None, Some(value))),
+            Some(previous) if previous != value => {
+                changes.push((key, Change::Updated, Some(previous), Some(value)))
+            }
+            Some(_) => {}
+        }
+    }
+    changes.extend(
+        old.into_iter()
+            .map(|(key, value)| (key, Change::Removed, Some(value), None)),
+    );
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+fn secret_keys(summary: &PlanSummary) -> BTreeSet<String> {
+    summary
+        .secrets
+        .iter()
+        .map(|secret| secret.key.clone())
+        .collect()
+}
+
+fn set_changes(old: BTreeSet<String>, new: BTreeSet<String>) -> (Vec<String>, Vec<String>) {
+    (
+        new.difference(&old).cloned().collect(),
+        old.difference(&new).cloned().collect(),
+    )
+}
+
+// Fill in the commits between the two packs' provenance commits from the git repository at
+// `repo`. Missing provenance is recorded as a note rather than failing the changelog.
+fn add_commits(changelog: &mut PackChangelog, repo: &Path) -> Result<()> {
+    let (Some(from), Some(to)) = (&changelog.old_commit, &changelog.new_commit) else {
+        changelog
+            .notes
+            .push("git history skipped: a pack has no provenance commit".to_string());
+        return Ok(());
+    };
+    if from == to {
+        return Ok(());
+    }
+    let range = format!("{from}..{to}");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["log", "--no-merges", "--format=%h %s", &range])
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git log {range} failed in {}: {}",
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    changelog.commits = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    Ok(())
+}
+
+fn provenance_commit(load: &PackLoad, pack: &Path) -> Result<Option<String>> {
+    let Some(bytes) = load.files.get("provenance.json") else {
+        return Ok(None);
+    };
+    let provenance: Provenance = serde_json::from_slice(bytes)
+        .with_context(|| format!("provenance.json in {} is invalid", pack.display()))?;
+    Ok(provenance.git_commit)
+}
+
+/// Render a changelog as markdown, with one section per kind of change.
+pub fn render_markdown(changelog: &PackChangelog) -> String {
+    let mut out = format!(
+        "# {} {} → {}\n",
+        changelog.pack_id, changelog.old_version, changelog.new_version
+    );
+    let mut section = |title: &str, lines: Vec<String>| {
+        if lines.is_empty() {
+            return;
+        }
+        out.push_str(&format!("\n## {title}\n\n"));
+        for line in lines {
+            out.push_str(&format!("- {line}\n"));
+        }
+    };
+    section(
+        "Components",
+        changelog
+            .components
+            .iter()
+            .map(|component| match component.change {
+                Change::Added => format!(
+                    "Added `{}` {}",
+                    component.name,
+                    component.new_version.as_deref().unwrap_or_default()
+                ),
+                Change::Updated => {
+                    let (old, new) = (
+                        component.old_version.as_deref().unwrap_or_default(),
+                        component.new_version.as_deref().unwrap_or_default(),
+                    );
+                    if old == new {
+                        format!("Rebuilt `{}` {new}", component.name)
+                    } else {
+                        format!("Updated `{}` {old} → {new}", component.name)
+                    }
+                }
+                Change::Removed => format!(
+                    "Removed `{}` {}",
+                    component.name,
+                    component.old_version.as_deref().unwrap_or_default()
+                ),
+            })
+            .collect(),
+    );
+    section(
+        "Flows",
+        changelog
+            .flows
+            .iter()
+            .map(|flow| {
+                let verb = match flow.change {
+                    Change::Added => "Added",
+                    Change::Updated => "Changed",
+                    Change::Removed => "Removed",
+                };
+                format!("{verb} `{}`", flow.id)
+            })
+            .collect(),
+    );
+    section(
+        "Secrets",
+        labelled(&changelog.secrets_added, &changelog.secrets_removed),
+    );
+    section(
+        "Providers",
+        labelled(&changelog.providers_added, &changelog.providers_removed),
+    );
+    section("Commits", changelog.commits.clone());
+    section("Notes", changelog.notes.clone());
+    let empty = changelog.components.is_empty()
+        && changelog.flows.is_empty()
+        && changelog.secrets_added.is_empty()
+        && changelog.secrets_removed.is_empty()
+        && changelog.providers_added.is_empty()
+        && changelog.providers_removed.is_empty();
+    if empty {
+        out.push_str("\nNo changes to components, flows, secrets or providers.\n");
+    }
+    out
+}
+
+fn labelled(added: &[String], removed: &[String]) -> Vec<String> {
+    added
+        .iter()
+        .map(|item| format!("New `{item}`"))
+        .chain(removed.iter().map(|item| format!("Dropped `{item}`")))
+        .collect()
+}
@@ -0,0 +1,1122 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use greentic_pack::messaging::MessagingAdapterKind;
+use greentic_pack::repo::RepoPackKind;
+
+use crate::config::{self, TrustPolicy};
+use crate::sarif::OutputFormat;
+use crate::{
+    capability_policy, pack_build, pack_changelog, pack_diff, pack_drift, pack_encrypt,
+    pack_events, pack_events_doctor, pack_inspect, pack_interfaces, pack_licenses, pack_messaging,
+    pack_plan, pack_preflight, pack_provenance, pack_release, pack_repo, pack_secrets,
+    pack_transfer, pack_verify,
+};
+
+// Pack subcommands implemented by greentic-dev itself. Everything else under `pack` is
+// delegated to `greentic-pack` (or `greentic-runner-cli` for `pack run`).
+#[derive(Parser, Debug)]
+#[command(name = "pack", bin_name = "greentic-dev pack")]
+#[command(about = "cli.command.pack.about")]
+struct PackCli {
+    #[command(subcommand)]
+    command: PackCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackCommand {
+    /// cli.command.pack.events.about
+    #[command(subcommand)]
+    Events(PackEventsCommand),
+    /// cli.command.pack.messaging.about
+    #[command(subcommand)]
+    Messaging(PackMessagingCommand),
+    /// cli.command.pack.interface.about
+    #[command(subcommand)]
+    Interface(PackInterfaceCommand),
+    /// cli.command.pack.repo.about
+    #[command(subcommand)]
+    Repo(PackRepoCommand),
+    /// cli.command.pack.secrets.about
+    #[command(subcommand)]
+    Secrets(PackSecretsCommand),
+    /// cli.command.pack.run_preflight.about
+    Run(PackRunPreflightArgs),
+    /// cli.command.pack.build.about
+    Build(PackBuildArgs),
+    /// cli.command.pack.plan.about
+    Plan(PackPlanArgs),
+    /// cli.command.pack.release.about
+    Release(PackReleaseArgs),
+    /// cli.command.pack.changelog.about
+    Changelog(PackChangelogArgs),
+    /// cli.command.pack.diff.about
+    Diff(PackDiffArgs),
+    /// cli.command.pack.drift.about
+    Drift(PackDriftArgs),
+    /// cli.command.pack.inspect.about
+    Inspect(PackInspectArgs),
+    /// cli.command.pack.encrypt.about
+    Encrypt(PackEncryptArgs),
+    /// cli.command.pack.keygen.about
+    Keygen(PackKeygenArgs),
+    /// cli.command.pack.licenses.about
+    Licenses(PackLicensesArgs),
+    /// cli.command.pack.verify.about
+    Verify(PackVerifyArgs),
+    /// cli.command.pack.provenance.about
+    Provenance(PackProvenanceArgs),
+    /// cli.command.pack.push.about
+    Push(PackPushArgs),
+    /// cli.command.pack.pull.about
+    Pull(PackPullArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackEventsCommand {
+    /// cli.command.pack.events.publish.about
+    Publish(PackEventsPublishArgs),
+    /// cli.command.pack.events.doctor.about
+    Doctor(PackEventsDoctorArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackEventsPublishArgs {
+    /// cli.command.pack.events.publish.pack
+    #[arg(short = 'p', long = "pack")]
+    pub pack: PathBuf,
+    /// cli.command.pack.events.publish.topic
+    #[arg(long = "topic")]
+    pub topic: String,
+    /// cli.command.pack.events.publish.provider
+    #[arg(long = "provider")]
+    pub provider: Option<String>,
+    /// cli.command.pack.events.publish.payload
+    #[arg(long = "payload", value_name = "FILE")]
+    pub payload: Option<PathBuf>,
+    /// cli.command.pack.events.publish.json
+    #[arg(long = "json")]
+    pub json: bool,
+    /// cli.command.pack.events.publish.runner_args
+    #[arg(last = true)]
+    pub runner_args: Vec<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackEventsDoctorArgs {
+    /// cli.command.pack.events.doctor.pack
+    #[arg(short = 'p', long = "pack")]
+    pub pack: PathBuf,
+    /// cli.command.pack.events.doctor.env_file
+    #[arg(long = "env-file", value_name = "FILE")]
+    pub env_file: Option<PathBuf>,
+    /// cli.command.pack.events.doctor.timeout
+    #[arg(long = "timeout", value_name = "SECS", default_value_t = 5)]
+    pub timeout: u64,
+    /// cli.command.pack.events.doctor.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackMessagingCommand {
+    /// cli.command.pack.messaging.add_channel.about
+    AddChannel(PackMessagingAddChannelArgs),
+    /// cli.command.pack.messaging.validate.about
+    Validate(PackMessagingValidateArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackMessagingAddChannelArgs {
+    /// cli.command.pack.messaging.add_channel.name
+    pub name: String,
+    /// cli.command.pack.messaging.add_channel.provider
+    #[arg(long = "provider", value_name = "COMPONENT")]
+    pub provider: String,
+    /// cli.command.pack.messaging.add_channel.kind
+    #[arg(long = "kind", value_enum, default_value_t = MessagingChannelKind::IngressEgress)]
+    pub kind: MessagingChannelKind,
+    /// cli.command.pack.messaging.add_channel.flow
+    #[arg(long = "flow")]
+    pub flow: Option<String>,
+    /// cli.command.pack.messaging.add_channel.feature
+    #[arg(long = "feature")]
+    pub features: Vec<String>,
+    /// cli.command.pack.messaging.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackMessagingValidateArgs {
+    /// cli.command.pack.messaging.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+    /// cli.command.pack.messaging.validate.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackInterfaceCommand {
+    /// cli.command.pack.interface.list.about
+    List(PackInterfaceListArgs),
+    /// cli.command.pack.interface.bind.about
+    Bind(PackInterfaceBindArgs),
+    /// cli.command.pack.interface.unbind.about
+    Unbind(PackInterfaceUnbindArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackInterfaceListArgs {
+    /// cli.command.pack.interface.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+    /// cli.command.pack.interface.list.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackInterfaceBindArgs {
+    /// cli.command.pack.interface.package
+    pub package: String,
+    /// cli.command.pack.interface.bind.world
+    #[arg(long = "world")]
+    pub world: String,
+    /// cli.command.pack.interface.bind.version
+    #[arg(long = "version")]
+    pub version: String,
+    /// cli.command.pack.interface.bind.note
+    #[arg(long = "note")]
+    pub note: Option<String>,
+    /// cli.command.pack.interface.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackInterfaceUnbindArgs {
+    /// cli.command.pack.interface.package
+    pub package: String,
+    /// cli.command.pack.interface.unbind.world
+    #[arg(long = "world")]
+    pub world: Option<String>,
+    /// cli.command.pack.interface.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackSecretsCommand {
+    /// cli.command.pack.secrets.infer.about
+    Infer(PackSecretsInferArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackSecretsInferArgs {
+    /// cli.command.pack.secrets.infer.dir
+    #[arg(long = "in", default_value = ".")]
+    pub dir: PathBuf,
+    /// cli.command.pack.secrets.infer.check
+    #[arg(long = "check")]
+    pub check: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PackRepoCommand {
+    /// cli.command.pack.repo.init.about
+    Init(PackRepoInitArgs),
+    /// cli.command.pack.repo.status.about
+    Status(PackRepoStatusArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackRepoInitArgs {
+    /// cli.command.pack.repo.init.kind
+    #[arg(long = "kind", value_enum)]
+    pub kind: RepoKind,
+    /// cli.command.pack.repo.init.capability
+    #[arg(long = "capability", required = true)]
+    pub capabilities: Vec<String>,
+    /// cli.command.pack.repo.init.component
+    #[arg(long = "component")]
+    pub component: String,
+    /// cli.command.pack.repo.init.package
+    #[arg(long = "package")]
+    pub package: String,
+    /// cli.command.pack.repo.init.world
+    #[arg(long = "world")]
+    pub world: String,
+    /// cli.command.pack.repo.init.version
+    #[arg(long = "version")]
+    pub version: String,
+    /// cli.command.pack.repo.init.entrypoint
+    #[arg(long = "entrypoint")]
+    pub entrypoint: String,
+    /// cli.command.pack.repo.init.profile
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+    /// cli.command.pack.repo.init.force
+    #[arg(long = "force")]
+    pub force: bool,
+    /// cli.command.pack.repo.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackRepoStatusArgs {
+    /// cli.command.pack.repo.meta
+    #[arg(long = "meta", default_value = "pack.toml")]
+    pub meta: PathBuf,
+    /// cli.command.pack.repo.status.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+// Only `pack run --validate-only` is handled locally; real runs go to greentic-runner-cli.
+#[derive(Args, Debug, Clone)]
+#[command(group = clap::ArgGroup::new("local").multiple(true).args(["validate_only"]))]
+pub struct PackRunPreflightArgs {
+    /// cli.command.pack.run_preflight.validate_only
+    #[arg(long = "validate-only", required = true)]
+    pub validate_only: bool,
+    /// cli.command.pack.run_preflight.pack
+    #[arg(short = 'p', long = "pack")]
+    pub pack: PathBuf,
+    /// cli.command.pack.run_preflight.entry
+    #[arg(long = "entry")]
+    pub entry: Option<String>,
+    /// cli.command.pack.run_preflight.input
+    #[arg(long = "input")]
+    pub input: Option<String>,
+    /// cli.command.pack.run_preflight.policy
+    #[arg(long = "policy", value_enum)]
+    pub policy: Option<PreflightPolicy>,
+    /// cli.command.pack.run_preflight.secrets_env
+    #[arg(long = "secrets-env", value_name = "FILE")]
+    pub secrets_env: Option<PathBuf>,
+    /// cli.command.pack.run_preflight.env_file
+    #[arg(long = "env-file", value_name = "FILE")]
+    pub env_files: Vec<PathBuf>,
+    /// cli.command.pack.run_preflight.offline
+    #[arg(long = "offline")]
+    pub offline: bool,
+    /// cli.command.pack.run_preflight.artifacts
+    #[arg(long = "artifacts", value_name = "DIR")]
+    pub artifacts: Option<PathBuf>,
+    /// cli.command.pack.run_preflight.json
+    #[arg(long = "json")]
+    pub json: bool,
+    /// cli.command.pack.run_preflight.decrypt_key
+    #[arg(long = "decrypt-key", value_name = "FILE")]
+    pub decrypt_key: Option<PathBuf>,
+}
+
+// Only `pack build --explain-capabilities`, `--timings`, `--watch`, `--update-lock`, `--sign-key`,
+// `--prune`, `--require-operations` and `--output` are handled locally; other builds go to
+// greentic-pack. With one of them, `--in <DIR>` builds every flow of a `pack.yaml` workspace
+// instead of a single `--flow`.
+#[derive(Args, Debug, Clone)]
+#[command(group = clap::ArgGroup::new("local").required(true).multiple(true).args(["explain_capabilities", "timings", "watch", "update_lock", "sign_key", "prune", "require_operations", "output"]))]
+pub struct PackBuildArgs {
+    /// cli.command.pack.build.explain_capabilities
+    #[arg(long = "explain-capabilities", conflicts_with_all = ["timings", "watch", "update_lock", "out", "output", "sign_key", "prune", "require_operations"])]
+    pub explain_capabilities: bool,
+    /// cli.command.pack.build.timings
+    #[arg(long = "timings")]
+    pub timings: bool,
+    /// cli.command.pack.build.watch
+    #[arg(long = "watch", conflicts_with = "json")]
+    pub watch: bool,
+    /// cli.command.pack.build.update_lock
+    #[arg(long = "update-lock")]
+    pub update_lock: bool,
+    /// cli.command.pack.build.prune
+    #[arg(long = "prune")]
+    pub prune: bool,
+    /// cli.command.pack.build.require_operations
+    #[arg(long = "require-operations")]
+    pub require_operations: bool,
+    /// cli.command.pack.build.flow
+    #[arg(long = "flow", required_unless_present = "pack_dir")]
+    pub flow: Option<PathBuf>,
+    /// cli.command.pack.build.pack_dir
+    #[arg(long = "in", value_name = "DIR", conflicts_with_all = ["flow", "meta", "explain_capabilities", "output"])]
+    pub pack_dir: Option<PathBuf>,
+    /// cli.command.pack.build.out
+    #[arg(long = "out", value_name = "FILE")]
+    pub out: Option<PathBuf>,
+    /// cli.command.pack.build.meta
+    #[arg(long = "meta")]
+    pub meta: Option<PathBuf>,
+    /// cli.command.pack.build.component_dir
+    #[arg(long = "component-dir")]
+    pub component_dir: Option<PathBuf>,
+    /// cli.command.pack.build.offline
+    #[arg(long = "offline")]
+    pub offline: bool,
+    /// cli.command.pack.build.allow_oci_tags
+    #[arg(long = "allow-oci-tags")]
+    pub allow_oci_tags: bool,
+    /// cli.command.pack.build.limit_rate
+    #[arg(long = "limit-rate", value_name = "RATE", value_parser = crate::download::parse_rate)]
+    pub limit_rate: Option<u64>,
+    /// cli.command.pack.build.sign_key
+    #[arg(long = "sign-key", value_name = "NAME")]
+    pub sign_key: Option<String>,
+    /// cli.command.pack.build.json
+    #[arg(long = "json")]
+    pub json: bool,
+    /// cli.command.pack.build.output
+    #[arg(long = "output", value_enum, default_value_t = OutputFormat::Text, conflicts_with_all = ["json", "watch"])]
+    pub output: OutputFormat,
+}
+
+// Only `pack plan --summary`, `--export` and `--baseline` are handled locally; the raw plan JSON
+// comes from greentic-pack.
+#[derive(Args, Debug, Clone)]
+#[command(group = clap::ArgGroup::new("local").multiple(true).args(["summary", "export", "baseline"]))]
+pub struct PackPlanArgs {
+    /// cli.command.pack.plan.summary
+    #[arg(long = "summary", required_unless_present_any = ["export", "baseline"])]
+    pub summary: bool,
+    /// cli.command.pack.plan.export
+    #[arg(
+        long = "export",
+        value_enum,
+        conflicts_with = "summary",
+        requires = "out"
+    )]
+    pub export: Option<PlanExportFormat>,
+    /// cli.command.pack.plan.out
+    #[arg(long = "out", requires = "export")]
+    pub out: Option<PathBuf>,
+    /// cli.command.pack.plan.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.plan.tenant
+    #[arg(long = "tenant", default_value = "tenant-local")]
+    pub tenant: String,
+    /// cli.command.pack.plan.environment
+    #[arg(long = "environment", default_value = "local")]
+    pub environment: String,
+    /// cli.command.pack.plan.json
+    #[arg(long = "json", conflicts_with = "export")]
+    pub json: bool,
+    /// cli.command.pack.plan.baseline
+    #[arg(long = "baseline", value_name = "PLAN", conflicts_with_all = ["summary", "export"])]
+    pub baseline: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanExportFormat {
+    Terraform,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackReleaseArgs {
+    /// cli.command.pack.release.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.release.out
+    #[arg(long = "out", default_value = "dist/release")]
+    pub out: PathBuf,
+    /// cli.command.pack.release.strict
+    #[arg(long = "strict")]
+    pub strict: bool,
+    /// cli.command.pack.release.force
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackProvenanceArgs {
+    /// cli.command.pack.provenance.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.provenance.repo
+    #[arg(long = "repo", value_name = "DIR")]
+    pub repo: Option<PathBuf>,
+    /// cli.command.pack.provenance.offline
+    #[arg(long = "offline", conflicts_with = "repo")]
+    pub offline: bool,
+    /// cli.command.pack.provenance.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackChangelogArgs {
+    /// cli.command.pack.changelog.old
+    pub old: PathBuf,
+    /// cli.command.pack.changelog.new
+    pub new: PathBuf,
+    /// cli.command.pack.changelog.git
+    #[arg(long = "git")]
+    pub git: bool,
+    /// cli.command.pack.changelog.repo
+    #[arg(long = "repo", default_value = ".", requires = "git")]
+    pub repo: PathBuf,
+    /// cli.command.pack.changelog.out
+    #[arg(long = "out")]
+    pub out: Option<PathBuf>,
+    /// cli.command.pack.changelog.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackDiffArgs {
+    /// cli.command.pack.diff.old
+    pub old: PathBuf,
+    /// cli.command.pack.diff.new
+    pub new: PathBuf,
+    /// cli.command.pack.diff.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+// Only `pack inspect --tree` is handled locally; other inspections go to greentic-pack.
+#[derive(Args, Debug, Clone)]
+#[command(group = clap::ArgGroup::new("local").multiple(true).args(["tree"]))]
+pub struct PackInspectArgs {
+    /// cli.command.pack.inspect.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.inspect.tree
+    #[arg(long = "tree", required = true)]
+    pub tree: bool,
+    /// cli.command.pack.inspect.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackDriftArgs {
+    /// cli.command.pack.drift.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.drift.against
+    #[arg(long = "against", value_name = "BASELINE")]
+    pub against: String,
+    /// cli.command.pack.drift.profile
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+    /// cli.command.pack.drift.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackPushArgs {
+    /// cli.command.pack.push.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.push.profile
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackPullArgs {
+    /// cli.command.pack.pull.coordinate
+    #[arg(value_name = "PACK_ID@VERSION")]
+    pub coordinate: String,
+    /// cli.command.pack.pull.out
+    #[arg(short = 'o', long = "out")]
+    pub out: Option<PathBuf>,
+    /// cli.command.pack.pull.profile
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+    /// cli.command.pack.pull.force
+    #[arg(long = "force")]
+    pub force: bool,
+    /// cli.command.pack.pull.limit_rate
+    #[arg(long = "limit-rate", value_name = "RATE", value_parser = crate::download::parse_rate)]
+    pub limit_rate: Option<u64>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackEncryptArgs {
+    /// cli.command.pack.encrypt.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.encrypt.recipient
+    #[arg(long = "recipient", value_name = "KEY", required = true)]
+    pub recipients: Vec<String>,
+    /// cli.command.pack.encrypt.include
+    #[arg(long = "include", value_enum)]
+    pub include: Vec<EncryptScope>,
+    /// cli.command.pack.encrypt.out
+    #[arg(long = "out")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptScope {
+    Flows,
+    Assets,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackKeygenArgs {
+    /// cli.command.pack.keygen.out
+    #[arg(long = "out", default_value = "pack.key")]
+    pub out: PathBuf,
+    /// cli.command.pack.keygen.force
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PackLicensesArgs {
+    /// cli.command.pack.licenses.pack
+    pub pack: PathBuf,
+    /// cli.command.pack.licenses.policy
+    #[arg(long = "policy", default_value = capability_policy::POLICY_PATH)]
+    pub policy: PathBuf,
+    /// cli.command.pack.licenses.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+// Only `pack verify --all` and `--deep` are handled locally; a plain single-pack verify goes to
+// greentic-pack.
+#[derive(Args, Debug, Clone)]
+#[command(group = clap::ArgGroup::new("local").multiple(true).args(["all", "deep"]))]
+pub struct PackVerifyArgs {
+    /// cli.command.pack.verify.all
+    #[arg(
+        long = "all",
+        value_name = "DIR",
+        required_unless_present = "pack",
+        conflicts_with = "pack"
+    )]
+    pub all: Option<PathBuf>,
+    /// cli.command.pack.verify.pack
+    #[arg(long = "pack", short = 'p', value_name = "PACK")]
+    pub pack: Option<PathBuf>,
+    /// cli.command.pack.verify.deep
+    #[arg(long = "deep")]
+    pub deep: bool,
+    /// cli.command.pack.verify.policy
+    #[arg(long = "policy", value_enum)]
+    pub policy: Option<PreflightPolicy>,
+    /// cli.command.pack.verify.jobs
+    #[arg(long = "jobs", short = 'j', value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+    /// cli.command.pack.verify.json
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreflightPolicy {
+    Strict,
+    Devok,
+}
+
+impl PreflightPolicy {
+    // `--policy`, else `[trust] policy` from the config, else `devok`.
+    pub fn resolve(policy: Option<Self>) -> Result<Self> {
+        if let Some(policy) = policy {
+            return Ok(policy);
+        }
+        Ok(match config::load()?.trust.policy {
+            Some(TrustPolicy::Strict) => Self::Strict,
+            Some(TrustPolicy::Devok) | None => Self::Devok,
+        })
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoKind {
+    SourceProvider,
+    Scanner,
+    Signing,
+    Attestation,
+    PolicyEngine,
+    OciProvider,
+    BillingProvider,
+    SearchProvider,
+    RecommendationProvider,
+}
+
+impl From<RepoKind> for RepoPackKind {
+    fn from(kind: RepoKind) -> Self {
+        match kind {
+            RepoKind::SourceProvider => RepoPackKind::SourceProvider,
+            RepoKind::Scanner => RepoPackKind::Scanner,
+            RepoKind::Signing => RepoPackKind::Signing,
+            RepoKind::Attestation => RepoPackKind::Attestation,
+            RepoKind::PolicyEngine => RepoPackKind::PolicyEngine,
+            RepoKind::OciProvider => RepoPackKind::OciProvider,
+            RepoKind::BillingProvider => RepoPackKind::BillingProvider,
+            RepoKind::SearchProvider => RepoPackKind::SearchProvider,
+            RepoKind::RecommendationProvider => RepoPackKind::RecommendationProvider,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessagingChannelKind {
+    Ingress,
+    Egress,
+    IngressEgress,
+}
+
+impl From<MessagingChannelKind> for MessagingAdapterKind {
+    fn from(kind: MessagingChannelKind) -> Self {
+        match kind {
+            MessagingChannelKind::Ingress => MessagingAdapterKind::Ingress,
+            MessagingChannelKind::Egress => MessagingAdapterKind::Egress,
+            MessagingChannelKind::IngressEgress => MessagingAdapterKind::IngressEgress,
+        }
+    }
+}
+
+// Parse `pack` passthrough arguments into a built-in command when greentic-dev implements it.
+// Returns `None` for anything that should keep delegating to the upstream binaries; parse
+// errors (including `--help`) are rendered by clap and terminate the process.
+pub fn parse_builtin(args: &[OsString], locale: &str) -> Option<PackCommand> {
+    if !is_builtin(args) {
+        return None;
+    }
+    let argv = std::iter::once(OsString::from("pack")).chain(args.iter().cloned());
+    let command = crate::cli::localized_builtin_command(PackCli::command(), locale);
+    let matches = command
+        .try_get_matches_from(argv)
+        .unwrap_or_else(|err| err.exit());
+    let cli = PackCli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    Some(cli.command)
+}
+
+// Subcommands shared with the upstream tools. Each marks the flags that select the local
+// implementation with a `local` argument group; without one of them the command is delegated.
+const SHARED_SUBCOMMANDS: [&str; 5] = ["run", "build", "verify", "inspect", "plan"];
+
+fn is_builtin(args: &[OsString]) -> bool {
+    if let Some(name) = args.first().and_then(|arg| arg.to_str())
+        && SHARED_SUBCOMMANDS.contains(&name)
+    {
+        return PackCli::command()
+            .find_subcommand(name)
+            .is_some_and(|command| gives_local_flag(command, &args[1..]));
+    }
+    let mut words = args.iter().map(|arg| arg.to_str());
+    matches!(
+        (words.next().flatten(), words.next().flatten()),
+        (Some("events"), Some("publish" | "doctor"))
+            | (Some("secrets"), Some("infer"))
+            | (
+                Some(
+                    "messaging"
+                        | "interface"
+                        | "repo"
+                        | "release"
+                        | "provenance"
+                        | "changelog"
+                        | "diff"
+                        | "drift"
+                        | "encrypt"
+                        | "keygen"
+                        | "licenses"
+                        | "push"
+                        | "pull"
+                ),
+                _
+            )
+    )
+}
+
+// Whether `args` give one of the flags in `command`'s `local` group. Arguments are walked the
+// way clap reads them: values of the options `command` declares are skipped, and nothing after
+// `--` is a flag.
+fn gives_local_flag(command: &clap::Command, args: &[OsString]) -> bool {
+    let Some(local) = command.get_groups().find(|group| group.get_id() == "local") else {
+        return false;
+    };
+    let local = local.get_args().collect::<Vec<_>>();
+    let mut args = args.iter().map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let (option, inline_value) = if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, _)) => (name, true),
+                None => (long, false),
+            };
+            (
+                command
+                    .get_arguments()
+                    .find(|option| option.get_long() == Some(name)),
+                value,
+            )
+        } else if let Some(short) = arg.strip_prefix('-') {
+            let mut chars = short.chars();
+            let Some(name) = chars.next() else {
+                continue;
+            };
+            (
+                command
+                    .get_arguments()
+                    .find(|option| option.get_short() == Some(name)),
+                !chars.as_str().is_empty(),
+            )
+        } else {
+            continue;
+        };
+        let Some(option) = option else {
+            continue;
+        };
+        if local.contains(&option.get_id()) {
+            return true;
+        }
+        if option.get_action().takes_values() && !inline_value {
+            args.next();
+        }
+    }
+    false
+}
+
+pub fn run(command: PackCommand) -> Result<()> {
+    match command {
+        PackCommand::Events(PackEventsCommand::Publish(args)) => pack_events::publish(&args),
+        PackCommand::Events(PackEventsCommand::Doctor(args)) => pack_events_doctor::run(&args),
+        PackCommand::Messaging(PackMessagingCommand::AddChannel(args)) => {
+            pack_messaging::add_channel(&args)
+        }
+        PackCommand::Messaging(PackMessagingCommand::Validate(args)) => {
+            pack_messaging::validate(&args)
+        }
+        PackCommand::Interface(PackInterfaceCommand::List(args)) => pack_interfaces::list(&args),
+        PackCommand::Interface(PackInterfaceCommand::Bind(args)) => pack_interfaces::bind(&args),
+        PackCommand::Interface(PackInterfaceCommand::Unbind(args)) => {
+            pack_interfaces::unbind(&args)
+        }
+        PackCommand::Repo(PackRepoCommand::Init(args)) => pack_repo::init(&args),
+        PackCommand::Repo(PackRepoCommand::Status(args)) => pack_repo::status(&args),
+        PackCommand::Secrets(PackSecretsCommand::Infer(args)) => pack_secrets::infer(&args),
+        PackCommand::Run(args) => pack_preflight::run(&args),
+        PackCommand::Build(args) if args.explain_capabilities => capability_policy::explain(&args),
+        PackCommand::Build(args) if args.watch => pack_build::watch(&args),
+        PackCommand::Build(args) => pack_build::run_local(&args),
+        PackCommand::Plan(args) => pack_plan::run(&args),
+        PackCommand::Release(args) => pack_release::run(&args),
+        PackCommand::Changelog(args) => pack_changelog::run(&args),
+        PackCommand::Diff(args) => pack_diff::run(&args),
+        PackCommand::Drift(args) => pack_drift::run(&args),
+        PackCommand::Inspect(args) => pack_inspect::run_tree(&args),
+        PackCommand::Encrypt(args) => pack_encrypt::run(&args),
+        PackCommand::Keygen(args) => pack_encrypt::keygen(&args),
+        PackCommand::Licenses(args) => pack_licenses::run(&args),
+        PackCommand::Verify(args) => pack_verify::run_all(&args),
+        PackCommand::Provenance(args) => pack_provenance::run(&args),
+        PackCommand::Push(args) => pack_transfer::push(&args),
+        PackCommand::Pull(args) => pack_transfer::pull(&args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MessagingChannelKind, PackCommand, PackEventsCommand, PackMessagingCommand,
+        PackSecretsCommand, PlanExportFormat, is_builtin, parse_builtin,
+    };
+    use std::ffi::OsString;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn local_flags_are_not_matched_in_values_or_after_double_dash() {
+        assert!(!is_builtin(&args(&[
+            "run",
+            "--pack",
+            "demo.gtpack",
+            "--input",
+            "--validate-only"
+        ])));
+        assert!(!is_builtin(&args(&[
+            "run",
+            "--pack",
+            "demo.gtpack",
+            "--",
+            "--validate-only"
+        ])));
+        assert!(!is_builtin(&args(&[
+            "build",
+            "--flow",
+            "f",
+            "--",
+            "--timings"
+        ])));
+        assert!(!is_builtin(&args(&["verify", "-p", "--deep"])));
+        assert!(is_builtin(&args(&[
+            "verify",
+            "-p",
+            "demo.gtpack",
+            "--deep"
+        ])));
+        assert!(is_builtin(&args(&["verify", "-pdemo.gtpack", "--deep"])));
+        assert!(!is_builtin(&args(&["inspect", "--", "--tree"])));
+        assert!(!is_builtin(&args(&[
+            "plan",
+            "demo.gtpack",
+            "--",
+            "--summary"
+        ])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow=--timings",
+            "--output=dist"
+        ])));
+    }
+
+    #[test]
+    fn upstream_subcommands_are_not_intercepted() {
+        assert!(!is_builtin(&args(&["build", "--in", "."])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--explain-capabilities"
+        ])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--timings"
+        ])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--prune"
+        ])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--require-operations"
+        ])));
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--sign-key=release"
+        ])));
+        assert!(!is_builtin(&args(&["events", "list"])));
+        assert!(!is_builtin(&args(&["verify", "--pack", "demo.gtpack"])));
+        assert!(is_builtin(&args(&["verify", "--all", "dist"])));
+        assert!(is_builtin(&args(&[
+            "verify",
+            "--pack",
+            "demo.gtpack",
+            "--deep"
+        ])));
+        assert!(!is_builtin(&args(&["plan", "demo.gtpack"])));
+        assert!(is_builtin(&args(&["plan", "demo.gtpack", "--summary"])));
+        assert!(is_builtin(&args(&[
+            "plan",
+            "demo.gtpack",
+            "--baseline=plan.json"
+        ])));
+        assert!(is_builtin(&args(&[
+            "plan",
+            "demo.gtpack",
+            "--export=terraform"
+        ])));
+        assert!(!is_builtin(&args(&["run", "--pack", "demo.gtpack"])));
+        assert!(is_builtin(&args(&[
+            "changelog",
+            "old.gtpack",
+            "new.gtpack"
+        ])));
+        assert!(is_builtin(&args(&["encrypt", "demo.gtpack"])));
+        assert!(!is_builtin(&args(&["inspect", "demo.gtpack"])));
+        assert!(is_builtin(&args(&["inspect", "demo.gtpack", "--tree"])));
+        assert!(is_builtin(&args(&["licenses", "demo.gtpack"])));
+        assert!(is_builtin(&args(&["diff", "a.gtpack", "b.gtpack"])));
+        assert!(is_builtin(&args(&[
+            "drift",
+            "a.gtpack",
+            "--against",
+            "b.gtpack"
+        ])));
+        assert!(!is_builtin(&[]));
+    }
+
+    #[test]
+    fn events_publish_is_parsed_locally() {
+        let parsed = parse_builtin(
+            &args(&[
+                "events",
+                "publish",
+                "--pack",
+                "demo.gtpack",
+                "--topic",
+                "orders.created",
+                "--",
+                "--offline",
+            ]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Events(PackEventsCommand::Publish(publish)) = parsed else {
+            panic!("expected events publish");
+        };
+        assert_eq!(publish.topic, "orders.created");
+        assert_eq!(publish.runner_args, vec!["--offline".to_string()]);
+    }
+
+    #[test]
+    fn messaging_add_channel_defaults() {
+        let parsed = parse_builtin(
+            &args(&[
+                "messaging",
+                "add-channel",
+                "slack",
+                "--provider",
+                "greentic.slack",
+            ]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Messaging(PackMessagingCommand::AddChannel(add)) = parsed else {
+            panic!("expected messaging add-channel");
+        };
+        assert_eq!(add.name, "slack");
+        assert_eq!(add.kind, MessagingChannelKind::IngressEgress);
+        assert_eq!(add.meta, std::path::PathBuf::from("pack.toml"));
+    }
+
+    #[test]
+    fn plan_export_is_parsed_locally() {
+        let parsed = parse_builtin(
+            &args(&[
+                "plan",
+                "demo.gtpack",
+                "--export",
+                "terraform",
+                "--out",
+                "infra",
+            ]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Plan(plan) = parsed else {
+            panic!("expected plan");
+        };
+        assert_eq!(plan.export, Some(PlanExportFormat::Terraform));
+        assert_eq!(plan.out, Some(std::path::PathBuf::from("infra")));
+        assert!(!plan.summary);
+    }
+
+    #[test]
+    fn build_timings_is_parsed_locally() {
+        let parsed = parse_builtin(
+            &args(&[
+                "build",
+                "--flow",
+                "flow.ygtc",
+                "--timings",
+                "--out",
+                "x.gtpack",
+            ]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Build(build) = parsed else {
+            panic!("expected build");
+        };
+        assert!(build.timings && !build.explain_capabilities);
+        assert_eq!(build.out, Some(std::path::PathBuf::from("x.gtpack")));
+    }
+
+    #[test]
+    fn build_watch_combines_with_timings_but_not_json() {
+        use clap::Parser;
+
+        assert!(is_builtin(&args(&[
+            "build",
+            "--flow",
+            "flow.ygtc",
+            "--watch"
+        ])));
+        let parsed = parse_builtin(
+            &args(&["build", "--flow", "flow.ygtc", "--watch", "--timings"]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Build(build) = parsed else {
+            panic!("expected build");
+        };
+        assert!(build.watch && build.timings);
+
+        let err =
+            super::PackCli::try_parse_from(["pack", "build", "--flow", "f", "--watch", "--json"])
+                .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn run_is_only_intercepted_for_validate_only() {
+        assert!(!is_builtin(&args(&[
+            "run",
+            "--pack",
+            "demo.gtpack",
+            "--offline"
+        ])));
+        let parsed = parse_builtin(
+            &args(&[
+                "run",
+                "--pack",
+                "demo.gtpack",
+                "--validate-only",
+                "--offline",
+            ]),
+            "en",
+        )
+        .expect("builtin command");
+        let PackCommand::Run(run) = parsed else {
+            panic!("expected run preflight");
+        };
+        assert!(run.validate_only && run.offline);
+        assert_eq!(run.policy, None);
+    }
+
+    #[test]
+    fn secrets_infer_is_parsed_locally() {
+        assert!(parse_builtin(&args(&["secrets", "list"]), "en").is_none());
+        let parsed = parse_builtin(
+            &args(&["secrets", "infer", "--in", "pack", "--check"]),
+            "en",
+        )
+        .expect("builtin");
+        let PackCommand::Secrets(PackSecretsCommand::Infer(infer)) = parsed else {
+            panic!("expected secrets infer");
+        };
+        assert_eq!(infer.dir, std::path::PathBuf::from("pack"));
+        assert!(infer.check);
+    }
+
+    #[test]
+    fn push_and_pull_are_parsed_locally() {
+        let parsed = parse_builtin(
+            &args(&["push", "dist/a.gtpack", "--profile", "staging"]),
+            "en",
+        )
+        .expect("builtin");
+        let PackCommand::Push(push) = parsed else {
+            panic!("expected push");
+        };
+        assert_eq!(push.profile.as_deref(), Some("staging"));
+        let parsed =
+            parse_builtin(&args(&["pull", "dev.acme.hello@1.2.0"]), "en").expect("builtin");
+        let PackCommand::Pull(pull) = parsed else {
+            panic!("expected pull");
+        };
+        assert_eq!(pull.coordinate, "dev.acme.hello@1.2.0");
+        assert!(pull.out.is_none());
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::pack_changelog::{Change, diff_maps};
+use crate::pack_cli::PackDiffArgs;
+
+// Metadata that differs between any two builds, or that the component section already covers.
+const IGNORED_META_KEYS: &[&str] = &["created_at_utc", "components"];
+const MAX_VALUE_WIDTH: usize = 80;
+
+/// Everything that differs between two gtpacks.
+#[derive(Debug, Serialize)]
+pub struct PackDiff {
+    pub old: PackSide,
+    pub new: PackSide,
+    pub manifest: Vec<ValueChange>,
+    pub flows: Vec<FlowDiff>,
+    pub components: Vec<ComponentDiff>,
+    pub sbom: Vec<SbomChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackSide {
+    pub pack_id: String,
+    pub version: String,
+}
+
+/// One value that differs at `path`; a missing side means the value was added or removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueChange {
+    pub path: String,
+    pub old: Option<JsonValue>,
+    pub new: Option<JsonValue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlowDiff {
+    pub id: String,
+    pub change: Change,
+    /// Semantic differences in the flow document of an updated flow.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<ValueChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentDiff {
+    pub name: String,
+    pub change: Change,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SbomChange {
+    pub path: String,
+    pub change: Change,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+impl PackDiff {
+    pub fn is_empty(&self) -> bool {
+        self.manifest.is_empty()
+            && self.flows.is_empty()
+            && self.components.is_empty()
+            && self.sbom.is_empty()
+    }
+}
+
+/// `pack diff`: compare two gtpacks for release review.
+pub fn run(args: &PackDiffArgs) -> Result<()> {
+    let diff = diff(&args.old, &args.new)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", render(&diff));
+    }
+    Ok(())
+}
+
+/// Compare the manifest metadata, flows, components and SBOM of `old` and `new`.
+pub fn diff(old: &Path, new: &Path) -> Result<PackDiff> {
+    let old_load = open(old)?;
+    let new_load = open(new)?;
+    let side = |load: &PackLoad| PackSide {
+        pack_id: load.manifest.meta.pack_id.clone(),
+        version: load.manifest.meta.version.to_string(),
+    };
+
+    let mut manifest = Vec::new();
+    let meta = |load: &PackLoad| -> Result<JsonValue> {
+        let mut value = serde_json::to_value(&load.manifest.meta)?;
+        if let Some(object) = value.as_object_mut() {
+            object.retain(|key, _| !IGNORED_META_KEYS.contains(&key.as_str()));
+        }
+        Ok(value)
+    };
+    diff_values("", &meta(&old_load)?, &meta(&new_load)?, &mut manifest);
+
+    Ok(PackDiff {
+        old: side(&old_load),
+        new: side(&new_load),
+        manifest,
+        flows: flow_diffs(&old_load, &new_load)?,
+        components: component_diffs(&old_load, &new_load),
+        sbom: sbom_changes(&old_load, &new_load),
+    })
+}
+
+fn open(pack: &Path) -> Result<PackLoad> {
+    open_pack(pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("failed to open {}: {}", pack.display(), err.message))
+}
+
+// Flows are matched by id and compared as parsed documents, so formatting, comments and key
+// order do not count as changes.
+fn flow_diffs(old: &PackLoad, new: &PackLoad) -> Result<Vec<FlowDiff>> {
+    let index = |load: &PackLoad| -> Result<BTreeMap<String, JsonValue>> {
+        load.manifest
+            .flows
+            .iter()
+            .map(|flow| {
+                let yaml = load
+                    .files
+                    .get(&flow.file_yaml)
+                    .ok_or_else(|| anyhow!("pack is missing {}", flow.file_yaml))?;
+                let doc = serde_yaml_bw::from_slice::<JsonValue>(yaml)
+                    .with_context(|| format!("failed to parse {}", flow.file_yaml))?;
+                Ok((flow.id.clone(), doc))
+            })
+            .collect()
+    };
+    Ok(diff_maps(index(old)?, index(new)?)
+        .into_iter()
+        .map(|(id, change, old, new)| {
+            let mut changes = Vec::new();
+            if let (Some(old), Some(new)) = (&old, &new) {
+                diff_values("", old, new, &mut changes);
+            }
+            FlowDiff {
+                id,
+                change,
+                changes,
+            }
+        })
+        .collect())
+}
+
+// Components are matched by name; a different version or wasm hash counts as an update.
+pub(crate) fn component_diffs(old: &PackLoad, new: &PackLoad) -> Vec<ComponentDiff> {
+    let index = |load: &PackLoad| {
+        load.manifest
+            .components
+            .iter()
+            .map(|component| {
+                (
+                    component.name.clone(),
+                    (component.version.to_string(), component.hash_blake3.clone()),
+                )
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    diff_maps(index(old), index(new))
+        .into_iter()
+        .map(|(name, change, old, new)| {
+            let (old_version, old_hash) = old.unzip();
+            let (new_version, new_hash) = new.unzip();
+            ComponentDiff {
+                name,
+                change,
+                old_version,
+                new_version,
+                old_hash,
+                new_hash,
+            }
+        })
+        .collect()
+}
+
+fn sbom_changes(old: &PackLoad, new: &PackLoad) -> Vec<SbomChange> {
+    let index = |load: &PackLoad| {
+        load.sbom
+            .iter()
+            .map(|entry| (entry.path.clone(), (entry.hash_blake3.clone(), entry.size)))
+            .collect::<BTreeMap<_, _>>()
+    };
+    diff_maps(index(old), index(new))
+        .into_iter()
+        .map(|(path, change, old, new)| SbomChange {
+            path,
+            change,
+            old_size: old.map(|(_, size)| size),
+            new_size: new.map(|(_, size)| size),
+        })
+        .collect()
+}
+
+/// Collect the leaf differences between two JSON values. Objects are compared key by key and
+/// arrays of the same length element by element; anything else that differs is one change.
+pub fn diff_values(path: &str, old: &JsonValue, new: &JsonValue, out: &mut Vec<ValueChange>) {
+    match (old, new) {
+        (JsonValue::Object(old), JsonValue::Object(new)) => {
+            let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = join_key(path, key);
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(&child, old, new, out),
+                    (old, new) => out.push(ValueChange {
+                        path: child,
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    }),
+                }
+            }
+        }
+        (JsonValue::Array(old), JsonValue::Array(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                diff_values(&format!("{path}[{index}]"), old, new, out);
+            }
+        }
+        (old, new) if old != new => out.push(ValueChange {
+            path: path.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    let simple = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    match (path.is_empty(), simple) {
+        (true, true) => key.to_string(),
+        (false, true) => format!("{path}.{key}"),
+        (_, false) => format!("{path}[{}]", JsonValue::from(key)),
+    }
+}
+
+/// Human-readable rendering: `+` added, `-` removed, `~` changed.
+pub fn render(diff: &PackDiff) -> String {
+    let mut out = format!(
+        "{} {} → {} {}\n",
+        diff.old.pack_id, diff.old.version, diff.new.pack_id, diff.new.version
+    );
+    if diff.is_empty() {
+        out.push_str("\nNo differences.\n");
+        return out;
+    }
+    if !diff.manifest.is_empty() {
+        out.push_str("\nManifest:\n");
+        for change in &diff.manifest {
+            out.push_str(&format!("  {}\n", render_change(change)));
+        }
+    }
+    if !diff.flows.is_empty() {
+        out.push_str("\nFlows:\n");
+        for flow in &diff.flows {
+            out.push_str(&format!("  {} {}\n", marker(flow.change), flow.id));
+            for change in &flow.changes {
+                out.push_str(&format!("      {}\n", render_change(change)));
+            }
+        }
+    }
+    render_components(&diff.components, &mut out);
+    if !diff.sbom.is_empty() {
+        out.push_str("\nSBOM:\n");
+        for entry in &diff.sbom {
+            let size = match (entry.old_size, entry.new_size) {
+                (Some(old), Some(new)) if old != new => format!("{old} → {new} bytes"),
+                (old, new) => format!("{} bytes", new.or(old).unwrap_or_default()),
+            };
+            out.push_str(&format!(
+                "  {} {} ({size})\n",
+                marker(entry.change),
+                entry.path
+            ));
+        }
+    }
+    out
+}
+
+/// The "Components:" section of a rendering; nothing when there are no changes.
+pub(crate) fn render_components(components: &[ComponentDiff], out: &mut String) {
+    if components.is_empty() {
+        return;
+    }
+    out.push_str("\nComponents:\n");
+    for component in components {
+        let version = match (&component.old_version, &component.new_version) {
+            (Some(old), Some(new)) if old != new => format!("{old} → {new}"),
+            (old, new) => new.as_ref().or(old.as_ref()).cloned().unwrap_or_default(),
+        };
+        out.push_str(&format!(
+            "  {} {} {version}\n",
+            marker(component.change),
+            component.name
+        ));
+        if let (Some(old), Some(new)) = (&component.old_hash, &component.new_hash)
+            && old != new
+        {
+            out.push_str(&format!("      {old} → {new}\n"));
+        }
+    }
+}
+
+pub(crate) fn marker(change: Change) -> char {
+    match change {
+        Change::Added => '+',
+        Change::Updated => '~',
+        Change::Removed => '-',
+    }
+}
+
+fn render_change(change: &ValueChange) -> String {
+    match (&change.old, &change.new) {
+        (None, Some(new)) => format!("+ {}: {}", change.path, short(new)),
+        (Some(old), None) => format!("- {}: {}", change.path, short(old)),
+        (old, new) => format!(
+            "~ {}: {} → {}",
+            change.path,
+            old.as_ref().map_or_else(String::new, short),
+            new.as_ref().map_or_else(String::new, short)
+        ),
+    }
+}
+
+fn short(value: &JsonValue) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_VALUE_WIDTH {
+        return text;
+    }
+    let cut = text.chars().take(MAX_VALUE_WIDTH - 1).collect::<String>();
+    format!("{cut}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ValueChange, diff_values, render_change};
+    use serde_json::json;
+
+    #[test]
+    fn value_diff_reports_leaf_paths() {
+        let old = json!({
+            "nodes": {
+                "start": {"component.exec": {"input": {"message": "hi"}}, "routing": [{"to": "log"}]},
+                "log": {"x.y": {}},
+            }
+        });
+        let new = json!({
+            "nodes": {
+                "start": {"component.exec": {"input": {"message": "hello"}}, "routing": [{"to": "audit"}]},
+                "audit": {"x.z": {}},
+            }
+        });
+        let mut changes = Vec::new();
+        diff_values("", &old, &new, &mut changes);
+        let paths = changes
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "nodes.audit",
+                "nodes.log",
+                "nodes.start[\"component.exec\"].input.message",
+                "nodes.start.routing[0].to",
+            ]
+        );
+        assert_eq!(changes[0].old, None);
+        assert_eq!(changes[1].new, None);
+    }
+
+    #[test]
+    fn changes_render_with_markers() {
+        let change = ValueChange {
+            path: "version".to_string(),
+            old: Some(json!("0.1.0")),
+            new: Some(json!("0.2.0")),
+        };
+        assert_eq!(render_change(&change), "~ version: \"0.1.0\" → \"0.2.0\"");
+        let long = ValueChange {
+            path: "description".to_string(),
+            old: None,
+            new: Some(json!("x".repeat(200))),
+        };
+        assert!(render_change(&long).ends_with('…'));
+    }
+}
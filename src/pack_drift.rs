@@ -0,0 +1,203 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::Serialize;
+
+use crate::pack_changelog::{Change, diff_maps};
+use crate::pack_cli::PackDriftArgs;
+use crate::pack_diff::{ComponentDiff, PackSide, component_diffs, marker, render_components};
+use crate::pack_plan::declared_secrets;
+use crate::pack_preflight::secret_requirements;
+use crate::pack_transfer::fetch_pack;
+
+const DISTRIBUTOR_SCHEME: &str = "distributor://";
+const HASH_WIDTH: usize = 12;
+
+/// How a local gtpack differs from its baseline, usually the version published on the
+/// distributor. `→` always reads baseline to local.
+#[derive(Debug, Serialize)]
+pub struct PackDrift {
+    pub local: PackSide,
+    pub baseline: PackSide,
+    /// The `--against` argument the baseline came from.
+    pub against: String,
+    pub components: Vec<ComponentDiff>,
+    pub flows: Vec<FlowDrift>,
+    pub secrets: Vec<SecretDrift>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlowDrift {
+    pub id: String,
+    pub change: Change,
+    pub baseline_hash: Option<String>,
+    pub local_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecretDrift {
+    pub key: String,
+    pub change: Change,
+}
+
+impl PackDrift {
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.flows.is_empty() && self.secrets.is_empty()
+    }
+}
+
+/// `pack drift <GTPACK> --against distributor://<pack-id>@<version>|<FILE>`.
+pub fn run(args: &PackDriftArgs) -> Result<()> {
+    let temp = tempfile::tempdir().context("failed to create a temporary directory")?;
+    let baseline = match args.against.strip_prefix(DISTRIBUTOR_SCHEME) {
+        Some(coordinate) => {
+            if !coordinate
+                .split_once('@')
+                .is_some_and(|(id, version)| !id.is_empty() && !version.is_empty())
+            {
+                bail!(
+                    "expected {DISTRIBUTOR_SCHEME}<pack-id>@<version>, got `{}`",
+                    args.against
+                );
+            }
+            let out = temp.path().join("baseline.gtpack");
+            let (resolved, _) = fetch_pack(args.profile.as_deref(), coordinate, &out, None)?;
+            eprintln!(
+                "Comparing against {}@{} from the distributor",
+                resolved.name, resolved.version
+            );
+            out
+        }
+        None => PathBuf::from(&args.against),
+    };
+
+    let drift = drift(&args.pack, &baseline, &args.against)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&drift)?);
+    } else {
+        print!("{}", render(&drift));
+    }
+    Ok(())
+}
+
+/// Compare the component versions, flow hashes and secret requirements of `local` against
+/// `baseline`.
+pub fn drift(local: &Path, baseline: &Path, against: &str) -> Result<PackDrift> {
+    let local_load = open(local)?;
+    let baseline_load = open(baseline)?;
+    let side = |load: &PackLoad| PackSide {
+        pack_id: load.manifest.meta.pack_id.clone(),
+        version: load.manifest.meta.version.to_string(),
+    };
+    Ok(PackDrift {
+        local: side(&local_load),
+        baseline: side(&baseline_load),
+        against: against.to_string(),
+        components: component_diffs(&baseline_load, &local_load),
+        flows: flow_drift(&baseline_load, &local_load),
+        secrets: secret_drift(&baseline_load, &local_load)?,
+    })
+}
+
+fn open(pack: &Path) -> Result<PackLoad> {
+    open_pack(pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("failed to open {}: {}", pack.display(), err.message))
+}
+
+// The manifest records a blake3 hash of each flow's canonical JSON, so formatting and comments
+// do not count as drift.
+fn flow_drift(baseline: &PackLoad, local: &PackLoad) -> Vec<FlowDrift> {
+    let index = |load: &PackLoad| {
+        load.manifest
+            .flows
+            .iter()
+            .map(|flow| (flow.id.clone(), flow.hash_blake3.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    diff_maps(index(baseline), index(local))
+        .into_iter()
+        .map(|(id, change, baseline_hash, local_hash)| FlowDrift {
+            id,
+            change,
+            baseline_hash,
+            local_hash,
+        })
+        .collect()
+}
+
+// A pack needs a secret when it declares it in its secret requirements or when one of its
+// components requires it.
+fn secret_drift(baseline: &PackLoad, local: &PackLoad) -> Result<Vec<SecretDrift>> {
+    let keys = |load: &PackLoad| -> Result<BTreeSet<String>> {
+        let mut keys = secret_requirements(&load.manifest)
+            .into_keys()
+            .collect::<BTreeSet<_>>();
+        keys.extend(
+            declared_secrets(load)?
+                .into_iter()
+                .flatten()
+                .map(|requirement| requirement.key.as_str().to_string()),
+        );
+        Ok(keys)
+    };
+    let baseline = keys(baseline)?;
+    let local = keys(local)?;
+    let mut drift = local
+        .difference(&baseline)
+        .map(|key| SecretDrift {
+            key: key.clone(),
+            change: Change::Added,
+        })
+        .chain(baseline.difference(&local).map(|key| SecretDrift {
+            key: key.clone(),
+            change: Change::Removed,
+        }))
+        .collect::<Vec<_>>();
+    drift.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(drift)
+}
+
+/// Human-readable rendering: `+` only in the local pack, `-` only in the baseline, `~` changed.
+pub fn render(drift: &PackDrift) -> String {
+    let mut out = format!(
+        "{} {} (local) vs {} {} ({})\n",
+        drift.local.pack_id,
+        drift.local.version,
+        drift.baseline.pack_id,
+        drift.baseline.version,
+        drift.against
+    );
+    if drift.is_empty() {
+        out.push_str("\nNo drift: components, flows and secrets match the baseline.\n");
+        return out;
+    }
+    render_components(&drift.components, &mut out);
+    if !drift.flows.is_empty() {
+        out.push_str("\nFlows:\n");
+        for flow in &drift.flows {
+            let hash = match (&flow.baseline_hash, &flow.local_hash) {
+                (Some(old), Some(new)) => format!(" {} → {}", short_hash(old), short_hash(new)),
+                (old, new) => new
+                    .as_ref()
+                    .or(old.as_ref())
+                    .map(|hash| format!(" {}", short_hash(hash)))
+                    .unwrap_or_default(),
+            };
+            out.push_str(&format!("  {} {}{hash}\n", marker(flow.change), flow.id));
+        }
+    }
+    if !drift.secrets.is_empty() {
+        out.push_str("\nSecrets:\n");
+        for secret in &drift.secrets {
+            out.push_str(&format!("  {} {}\n", marker(secret.change), secret.key));
+        }
+    }
+    out
+}
+
+fn short_hash(hash: &str) -> &str {
+    let hex = hash.strip_prefix("blake3:").unwrap_or(hash);
+    hex.get(..HASH_WIDTH).unwrap_or(hex)
+}
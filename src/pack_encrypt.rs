@@ -0,0 +1,553 @@
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::ExposeSecret;
+use age::x25519;
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::interrupt::PartialOutput;
+use crate::pack_cli::{EncryptScope, PackEncryptArgs, PackKeygenArgs};
+use crate::state_lock::write_atomic;
+
+/// Entry listing the recipients and encrypted entries of an encrypted gtpack. It sits next to
+/// `manifest.cbor` rather than inside it because the manifest is covered by the pack signature.
+pub const ENCRYPTION_ENTRY: &str = "encryption.json";
+
+const FORMAT: &str = "greentic-pack-encryption-v2";
+const CIPHER: &str = "age-x25519+chacha20poly1305";
+const KEY_LEN: usize = 32;
+
+/// Contents of [`ENCRYPTION_ENTRY`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionManifest {
+    pub format: String,
+    pub cipher: String,
+    /// age recipients (`age1...`) that can decrypt the pack.
+    pub recipients: Vec<String>,
+    /// The pack key, as an ASCII-armored age file encrypted to `recipients`.
+    pub pack_key: String,
+    /// Pack entries stored encrypted under the pack key, as `nonce || ciphertext || tag`.
+    pub entries: Vec<String>,
+}
+
+/// `pack keygen`: write a new decryption key and print the recipient to encrypt packs for.
+pub fn keygen(args: &PackKeygenArgs) -> Result<()> {
+    if args.out.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            args.out.display()
+        );
+    }
+    let (identity, recipient) = generate_key();
+    // The age identity file format, so age and rage can use the key too.
+    let contents = format!(
+        "# greentic pack decryption key\n# public key: {recipient}\n{}\n",
+        identity.to_string().expose_secret()
+    );
+    write_atomic(&args.out, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&args.out, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to restrict {}", args.out.display()))?;
+    }
+    println!("Decryption key written to {}", args.out.display());
+    println!("Recipient: {recipient}");
+    Ok(())
+}
+
+/// `pack encrypt`: write a copy of a pack with its flows and/or assets encrypted for the given
+/// recipients.
+pub fn run(args: &PackEncryptArgs) -> Result<()> {
+    let out = args.out.clone().unwrap_or_else(|| {
+        let stem = args
+            .pack
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("pack");
+        args.pack.with_file_name(format!("{stem}.encrypted.gtpack"))
+    });
+    let scopes = if args.include.is_empty() {
+        vec![EncryptScope::Flows, EncryptScope::Assets]
+    } else {
+        args.include.clone()
+    };
+    let manifest = encrypt(&args.pack, &out, &args.recipients, &scopes)?;
+    println!(
+        "Encrypted {} entr{} for {} recipient(s) into {}",
+        manifest.entries.len(),
+        if manifest.entries.len() == 1 {
+            "y"
+        } else {
+            "ies"
+        },
+        manifest.recipients.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+/// Create a new age identity and its `age1...` recipient.
+pub fn generate_key() -> (x25519::Identity, String) {
+    let identity = x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    (identity, recipient)
+}
+
+/// Encrypt the entries of `pack` selected by `scopes` for `recipients` and write the result to
+/// `out`. The pack is verified first; entries are restored byte for byte on decryption, so the
+/// decrypted pack still matches its signature.
+pub fn encrypt(
+    pack: &Path,
+    out: &Path,
+    recipients: &[String],
+    scopes: &[EncryptScope],
+) -> Result<EncryptionManifest> {
+    if recipients.is_empty() {
+        bail!("at least one --recipient is required");
+    }
+    let load = open_pack(pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("pack verification failed: {}", err.message))?;
+    if load.files.contains_key(ENCRYPTION_ENTRY) {
+        bail!("{} is already encrypted", pack.display());
+    }
+
+    let mut selected = BTreeSet::new();
+    if scopes.contains(&EncryptScope::Flows) {
+        for flow in &load.manifest.flows {
+            selected.insert(flow.file_yaml.clone());
+            selected.insert(flow.file_json.clone());
+        }
+    }
+    if scopes.contains(&EncryptScope::Assets) {
+        selected.extend(
+            load.files
+                .keys()
+                .filter(|name| name.starts_with("assets/"))
+                .cloned(),
+        );
+    }
+    selected.retain(|name| load.files.contains_key(name));
+    if selected.is_empty() {
+        bail!("{} has no entries to encrypt", pack.display());
+    }
+
+    let recipients = recipients
+        .iter()
+        .map(|recipient| parse_recipient(recipient))
+        .collect::<Result<Vec<_>>>()?;
+    let file_key = random_key()?;
+    let manifest = EncryptionManifest {
+        format: FORMAT.to_string(),
+        cipher: CIPHER.to_string(),
+        recipients: recipients.iter().map(ToString::to_string).collect(),
+        pack_key: wrap_key(&file_key, &recipients)?,
+        entries: selected.into_iter().collect(),
+    };
+
+    let cipher = entry_cipher(&file_key)?;
+    let _partial = PartialOutput::new(out);
+    rewrite_pack(
+        pack,
+        out,
+        |name| {
+            if manifest.entries.iter().any(|entry| entry == name) {
+                EntryAction::Rewrite
+            } else {
+                EntryAction::Copy
+            }
+        },
+        |name, bytes| seal(&cipher, name, bytes),
+    )
+    .and_then(|mut writer| {
+        writer.start_file(ENCRYPTION_ENTRY, entry_options())?;
+        writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        writer.finish()?;
+        Ok(())
+    })
+    .with_context(|| format!("failed to write {}", out.display()))?;
+    Ok(manifest)
+}
+
+/// Decrypt `pack` with the key in `key_file` and write the plain pack to `out`.
+pub fn decrypt(pack: &Path, key_file: &Path, out: &Path) -> Result<()> {
+    let manifest =
+        encryption_manifest(pack)?.ok_or_else(|| anyhow!("{} is not encrypted", pack.display()))?;
+    let identities = read_identities(key_file)?;
+    let file_key = unwrap_key(&manifest, &identities)
+        .with_context(|| format!("{} cannot decrypt {}", key_file.display(), pack.display()))?;
+    let cipher = entry_cipher(&file_key)?;
+    let _partial = PartialOutput::new(out);
+    rewrite_pack(
+        pack,
+        out,
+        |name| {
+            if name == ENCRYPTION_ENTRY {
+                EntryAction::Drop
+            } else if manifest.entries.iter().any(|entry| entry == name) {
+                EntryAction::Rewrite
+            } else {
+                EntryAction::Copy
+            }
+        },
+        |name, bytes| open(&cipher, name, bytes),
+    )
+    .and_then(|writer| {
+        writer.finish()?;
+        Ok(())
+    })
+    .with_context(|| format!("failed to write {}", out.display()))
+}
+
+/// Read [`ENCRYPTION_ENTRY`] from a gtpack, or `None` for a plain pack.
+pub fn encryption_manifest(pack: &Path) -> Result<Option<EncryptionManifest>> {
+    let file = File::open(pack).with_context(|| format!("failed to open {}", pack.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("{} is not a gtpack archive", pack.display()))?;
+    let Ok(mut entry) = archive.by_name(ENCRYPTION_ENTRY) else {
+        return Ok(None);
+    };
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    let manifest: EncryptionManifest = serde_json::from_slice(&bytes)
+        .with_context(|| format!("invalid {ENCRYPTION_ENTRY} in {}", pack.display()))?;
+    if manifest.format != FORMAT {
+        bail!(
+            "{} uses unsupported encryption format `{}`",
+            pack.display(),
+            manifest.format
+        );
+    }
+    Ok(Some(manifest))
+}
+
+/// A pack decrypted into a temp directory for the duration of a command.
+pub struct DecryptedPack {
+    _dir: TempDir,
+    // `TempDir` is not removed when the process exits on Ctrl-C; the plain pack must not linger.
+    _cleanup: PartialOutput,
+    pub path: PathBuf,
+}
+
+/// Strip `--decrypt-key <FILE>` from delegated `pack run`/`pack inspect` arguments. When it is
+/// present, the pack named by `--pack`/`-p` (or the first positional `.gtpack`) is decrypted to
+/// a temp file and the arguments are rewritten to point at it.
+pub fn take_decrypt_key(args: &[OsString]) -> Result<(Option<DecryptedPack>, Vec<OsString>)> {
+    let mut key = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.to_str() {
+            Some("--decrypt-key") => match iter.next() {
+                Some(value) => PathBuf::from(value),
+                None => bail!("--decrypt-key needs a key file"),
+            },
+            Some(arg) if arg.starts_with("--decrypt-key=") => {
+                PathBuf::from(&arg["--decrypt-key=".len()..])
+            }
+            _ => {
+                rest.push(arg.clone());
+                continue;
+            }
+        };
+        if key.replace(value).is_some() {
+            bail!("--decrypt-key given more than once");
+        }
+    }
+    let Some(key) = key else {
+        return Ok((None, rest));
+    };
+
+    let index = pack_arg_index(&rest)
+        .ok_or_else(|| anyhow!("--decrypt-key needs a pack to decrypt (--pack <FILE>)"))?;
+    let pack = PathBuf::from(&rest[index]);
+    let dir = tempfile::tempdir().context("failed to create a temp dir for the decrypted pack")?;
+    let cleanup = PartialOutput::new(dir.path());
+    let file_name = pack.file_name().unwrap_or_else(|| "pack.gtpack".as_ref());
+    let path = dir.path().join(file_name);
+    decrypt(&pack, &key, &path)?;
+    rest[index] = path.clone().into_os_string();
+    Ok((
+        Some(DecryptedPack {
+            _dir: dir,
+            _cleanup: cleanup,
+            path,
+        }),
+        rest,
+    ))
+}
+
+/// The pack a delegated `pack run`/`pack inspect` command line refers to.
+pub fn pack_argument(args: &[OsString]) -> Option<PathBuf> {
+    pack_arg_index(args).map(|index| PathBuf::from(&args[index]))
+}
+
+fn pack_arg_index(args: &[OsString]) -> Option<usize> {
+    for (index, arg) in args.iter().enumerate() {
+        if matches!(arg.to_str(), Some("--pack" | "-p")) {
+            return (index + 1 < args.len()).then_some(index + 1);
+        }
+    }
+    args.iter().position(|arg| {
+        Path::new(arg)
+            .extension()
+            .is_some_and(|ext| ext == "gtpack")
+    })
+}
+
+/// Print what is encrypted in a pack, for `pack inspect` without `--decrypt-key`.
+pub fn print_summary(pack: &Path, manifest: &EncryptionManifest) {
+    println!("{} is encrypted ({})", pack.display(), manifest.cipher);
+    println!("Recipients:");
+    for recipient in &manifest.recipients {
+        println!("  {recipient}");
+    }
+    println!("Encrypted entries:");
+    for entry in &manifest.entries {
+        println!("  {entry}");
+    }
+    println!("Pass --decrypt-key <FILE> to inspect the pack contents");
+}
+
+// What `rewrite_pack` does with one archive entry.
+enum EntryAction {
+    /// Copy the compressed bytes as they are, without buffering the entry.
+    Copy,
+    Drop,
+    /// Read the entry into memory and pass it through the rewrite closure.
+    Rewrite,
+}
+
+// Copy every entry of `pack` into a new archive at `out`. `select` picks an action per entry
+// name; only entries marked `Rewrite` are decompressed and handed to `rewrite`, so large
+// component artifacts that are not sealed or opened are streamed through unchanged. The writer
+// is returned so callers can append entries.
+fn rewrite_pack(
+    pack: &Path,
+    out: &Path,
+    mut select: impl FnMut(&str) -> EntryAction,
+    mut rewrite: impl FnMut(&str, Vec<u8>) -> Result<Vec<u8>>,
+) -> Result<ZipWriter<File>> {
+    let file = File::open(pack).with_context(|| format!("failed to open {}", pack.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("{} is not a gtpack archive", pack.display()))?;
+    if let Some(parent) = out.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut writer = ZipWriter::new(
+        File::create(out).with_context(|| format!("failed to create {}", out.display()))?,
+    );
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        match select(&name) {
+            EntryAction::Copy => writer
+                .raw_copy_file(entry)
+                .with_context(|| format!("failed to copy {name}"))?,
+            EntryAction::Drop => {}
+            EntryAction::Rewrite => {
+                let mut bytes = Vec::new();
+                entry
+                    .read_to_end(&mut bytes)
+                    .with_context(|| format!("failed to read {name}"))?;
+                let bytes = rewrite(&name, bytes)?;
+                writer.start_file(name.as_str(), entry_options())?;
+                writer.write_all(&bytes)?;
+            }
+        }
+    }
+    Ok(writer)
+}
+
+fn entry_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn random_key() -> Result<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| anyhow!("system random number generator failed"))?;
+    Ok(key)
+}
+
+// The age identities in a key file written by `pack keygen` or `age-keygen`.
+fn read_identities(path: &Path) -> Result<Vec<Box<dyn age::Identity>>> {
+    let file = File::open(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let identities = age::IdentityFile::from_buffer(BufReader::new(file))
+        .with_context(|| format!("{} is not an age key file", path.display()))?
+        .into_identities()
+        .map_err(|err| anyhow!("{} is not an age key file: {err}", path.display()))?;
+    if identities.is_empty() {
+        bail!(
+            "{} has no AGE-SECRET-KEY line; create keys with `greentic-dev pack keygen`",
+            path.display()
+        );
+    }
+    Ok(identities)
+}
+
+fn parse_recipient(recipient: &str) -> Result<x25519::Recipient> {
+    recipient
+        .parse()
+        .map_err(|err| anyhow!("recipient `{recipient}` is not an age1... key ({err})"))
+}
+
+// The pack key as an armored age file that any of `recipients` can decrypt.
+fn wrap_key(file_key: &[u8; KEY_LEN], recipients: &[x25519::Recipient]) -> Result<String> {
+    let encryptor = age::Encryptor::with_recipients(
+        recipients
+            .iter()
+            .map(|recipient| recipient as &dyn age::Recipient),
+    )
+    .map_err(|err| anyhow!("failed to encrypt the pack key: {err}"))?;
+    let mut armored = Vec::new();
+    let mut writer = encryptor.wrap_output(ArmoredWriter::wrap_output(
+        &mut armored,
+        Format::AsciiArmor,
+    )?)?;
+    writer.write_all(file_key)?;
+    writer.finish()?.finish()?;
+    String::from_utf8(armored).context("armored age output is not UTF-8")
+}
+
+fn unwrap_key(
+    manifest: &EncryptionManifest,
+    identities: &[Box<dyn age::Identity>],
+) -> Result<[u8; KEY_LEN]> {
+    let decryptor = age::Decryptor::new_buffered(ArmoredReader::new(manifest.pack_key.as_bytes()))
+        .context("invalid pack key")?;
+    let mut reader = match decryptor.decrypt(identities.iter().map(|identity| identity.as_ref())) {
+        Ok(reader) => reader,
+        Err(age::DecryptError::NoMatchingKeys) => {
+            bail!("the pack is not encrypted for this key")
+        }
+        Err(err) => bail!("the pack key does not decrypt: {err}"),
+    };
+    let mut file_key = Vec::new();
+    reader
+        .read_to_end(&mut file_key)
+        .context("the pack key does not decrypt")?;
+    <[u8; KEY_LEN]>::try_from(file_key.as_slice()).map_err(|_| anyhow!("invalid pack key length"))
+}
+
+fn entry_cipher(file_key: &[u8; KEY_LEN]) -> Result<LessSafeKey> {
+    UnboundKey::new(&CHACHA20_POLY1305, file_key)
+        .map(LessSafeKey::new)
+        .map_err(|_| anyhow!("invalid pack key"))
+}
+
+// The entry name is authenticated so ciphertexts cannot be swapped between entries.
+fn seal(cipher: &LessSafeKey, name: &str, mut bytes: Vec<u8>) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("system random number generator failed"))?;
+    cipher
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(name.as_bytes()),
+            &mut bytes,
+        )
+        .map_err(|_| anyhow!("failed to encrypt {name}"))?;
+    Ok([nonce.as_slice(), &bytes].concat())
+}
+
+fn open(cipher: &LessSafeKey, name: &str, bytes: Vec<u8>) -> Result<Vec<u8>> {
+    if bytes.len() < NONCE_LEN {
+        bail!("{name} is truncated");
+    }
+    let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("bad nonce"))?;
+    let mut sealed = sealed.to_vec();
+    let plain = cipher
+        .open_in_place(nonce, Aad::from(name.as_bytes()), &mut sealed)
+        .map_err(|_| anyhow!("{name} failed to decrypt (tampered or wrong key)"))?;
+    Ok(plain.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CIPHER, EncryptionManifest, FORMAT};
+    use super::{
+        entry_cipher, generate_key, open, parse_recipient, random_key, seal, unwrap_key, wrap_key,
+    };
+    use age::secrecy::ExposeSecret;
+
+    #[test]
+    fn pack_key_unwraps_only_for_listed_recipients() {
+        let (alice, alice_recipient) = generate_key();
+        let (bob, bob_recipient) = generate_key();
+        let (mallory, _) = generate_key();
+        let file_key = random_key().unwrap();
+        let recipients =
+            [&alice_recipient, &bob_recipient].map(|recipient| parse_recipient(recipient).unwrap());
+        let manifest = EncryptionManifest {
+            format: FORMAT.to_string(),
+            cipher: CIPHER.to_string(),
+            recipients: vec![alice_recipient, bob_recipient],
+            pack_key: wrap_key(&file_key, &recipients).unwrap(),
+            entries: Vec::new(),
+        };
+        assert!(
+            manifest
+                .pack_key
+                .starts_with("-----BEGIN AGE ENCRYPTED FILE-----")
+        );
+        for identity in [alice, bob] {
+            assert_eq!(
+                unwrap_key(&manifest, &[Box::new(identity)]).unwrap(),
+                file_key
+            );
+        }
+        let err = unwrap_key(&manifest, &[Box::new(mallory)]).unwrap_err();
+        assert!(err.to_string().contains("not encrypted for"), "{err}");
+    }
+
+    #[test]
+    fn keygen_output_is_an_age_identity_file() {
+        let (identity, recipient) = generate_key();
+        let secret = identity.to_string();
+        let parsed = age::IdentityFile::from_buffer(
+            format!("# public key: {recipient}\n{}\n", secret.expose_secret()).as_bytes(),
+        )
+        .unwrap()
+        .into_identities()
+        .unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert!(recipient.starts_with("age1"), "{recipient}");
+    }
+
+    #[test]
+    fn entries_are_bound_to_their_name() {
+        let cipher = entry_cipher(&random_key().unwrap()).unwrap();
+        let sealed = seal(&cipher, "flows/main.ygtc", b"secret prompt".to_vec()).unwrap();
+        assert_eq!(
+            open(&cipher, "flows/main.ygtc", sealed.clone()).unwrap(),
+            b"secret prompt"
+        );
+        assert!(open(&cipher, "flows/other.ygtc", sealed).is_err());
+    }
+
+    #[test]
+    fn recipients_must_be_age_keys() {
+        assert!(parse_recipient("gtx25519:CQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQk").is_err());
+        let (_, recipient) = generate_key();
+        assert_eq!(parse_recipient(&recipient).unwrap().to_string(), recipient);
+    }
+}
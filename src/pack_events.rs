@@ -0,0 +1,346 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::builder::PackManifest;
+use greentic_pack::events::EventProviderSpec;
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde::Serialize;
+use serde_json::{Value as JsonValue, json};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::pack_cli::PackEventsPublishArgs;
+use crate::passthrough::resolve_binary;
+use crate::util::process::{self, CommandSpec, StreamMode};
+
+#[derive(Debug, Serialize)]
+struct PublishOutcome {
+    pack: String,
+    provider: String,
+    topic: String,
+    flow: String,
+    success: bool,
+    exit_code: Option<i32>,
+    result: JsonValue,
+}
+
+/// Publish a synthetic event into the flow bound to `--topic` and run it via greentic-runner-cli.
+pub fn publish(args: &PackEventsPublishArgs) -> Result<()> {
+    let load = open_pack(&args.pack, SigningPolicy::DevOk).map_err(|err| {
+        anyhow!(
+            "failed to open pack {}: {}",
+            args.pack.display(),
+            err.message
+        )
+    })?;
+    let provider = select_provider(&load.manifest, &args.topic, args.provider.as_deref())?;
+    let flow = target_flow(&load.manifest, provider)?;
+    let payload = read_payload(args.payload.as_deref())?;
+    let event = event_envelope(provider, &args.topic, payload);
+
+    let runner = resolve_binary("greentic-runner-cli")?;
+    let mut spec = CommandSpec::new(runner);
+    spec.args = vec![
+        OsString::from("--pack"),
+        args.pack.clone().into_os_string(),
+        OsString::from("--entry"),
+        OsString::from(&flow),
+        OsString::from("--input"),
+        OsString::from(serde_json::to_string(&event)?),
+        OsString::from("--json"),
+    ];
+    spec.args
+        .extend(args.runner_args.iter().map(OsString::from));
+    spec.stdout = StreamMode::Capture;
+    spec.stderr = StreamMode::Capture;
+    let output = process::run(spec)?;
+
+    let stdout = String::from_utf8_lossy(output.stdout.as_deref().unwrap_or_default());
+    let stderr = String::from_utf8_lossy(output.stderr.as_deref().unwrap_or_default());
+    let result = serde_json::from_str::<JsonValue>(stdout.trim())
+        .unwrap_or_else(|_| JsonValue::String(stdout.trim().to_string()));
+    let outcome = PublishOutcome {
+        pack: args.pack.display().to_string(),
+        provider: provider.name.clone(),
+        topic: args.topic.clone(),
+        flow,
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        result,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+    } else {
+        print_outcome(&outcome);
+    }
+    if !stderr.trim().is_empty() {
+        eprintln!("{}", stderr.trim_end());
+    }
+    if !outcome.success {
+        bail!(
+            "flow `{}` failed for event on topic `{}` (runner exit status {})",
+            outcome.flow,
+            outcome.topic,
+            output.status
+        );
+    }
+    Ok(())
+}
+
+fn select_provider<'a>(
+    manifest: &'a PackManifest,
+    topic: &str,
+    provider_name: Option<&str>,
+) -> Result<&'a EventProviderSpec> {
+    let providers = manifest
+        .meta
+        .events
+        .as_ref()
+        .map(|events| events.providers.as_slice())
+        .unwrap_or_default();
+    if providers.is_empty() {
+        bail!(
+            "pack `{}` does not declare any event providers",
+            manifest.meta.pack_id
+        );
+    }
+
+    let candidates = providers
+        .iter()
+        .filter(|provider| provider_name.is_none_or(|name| provider.name == name))
+        .filter(|provider| {
+            provider.capabilities.topics.is_empty()
+                || provider.capabilities.topics.iter().any(|t| t == topic)
+        })
+        .collect::<Vec<_>>();
+
+    match candidates.as_slice() {
+        [provider] => Ok(provider),
+        [] => {
+            let known = providers
+                .iter()
+                .map(|provider| {
+                    let topics = if provider.capabilities.topics.is_empty() {
+                        "*".to_string()
+                    } else {
+                        provider.capabilities.topics.join(", ")
+                    };
+                    format!("{} ({topics})", provider.name)
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            bail!("no event provider accepts topic `{topic}` (providers: {known})")
+        }
+        many => {
+            let names = many
+                .iter()
+                .map(|provider| provider.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!(
+                "topic `{topic}` is accepted by multiple providers ({names}); pick one with --provider"
+            )
+        }
+    }
+}
+
+fn target_flow(manifest: &PackManifest, provider: &EventProviderSpec) -> Result<String> {
+    let flow = provider
+        .custom_flow
+        .as_ref()
+        .or(provider.default_flow.as_ref())
+        .ok_or_else(|| {
+            anyhow!(
+                "event provider `{}` does not declare a default_flow or custom_flow",
+                provider.name
+            )
+        })?;
+    if !manifest.flows.iter().any(|entry| &entry.id == flow) {
+        bail!(
+            "event provider `{}` routes to flow `{flow}`, which is not part of the pack",
+            provider.name
+        );
+    }
+    Ok(flow.clone())
+}
+
+fn read_payload(path: Option<&Path>) -> Result<JsonValue> {
+    let raw = match path {
+        None => return Ok(JsonValue::Object(Default::default())),
+        Some(path) if path == Path::new("-") => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("failed to read event payload from stdin")?;
+            buf
+        }
+        Some(path) => fs::read_to_string(path)
+            .with_context(|| format!("failed to read event payload {}", path.display()))?,
+    };
+    Ok(parse_payload(&raw))
+}
+
+fn parse_payload(raw: &str) -> JsonValue {
+    serde_json::from_str(raw)
+        .or_else(|_| serde_yaml_bw::from_str(raw))
+        .unwrap_or_else(|_| JsonValue::String(raw.to_string()))
+}
+
+fn event_envelope(provider: &EventProviderSpec, topic: &str, payload: JsonValue) -> JsonValue {
+    let published_at = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default();
+    json!({
+        "event": {
+            "topic": topic,
+            "provider": provider.name,
+            "kind": provider.kind.to_string(),
+            "source": "greentic-dev",
+            "published_at": published_at,
+            "payload": payload,
+        }
+    })
+}
+
+fn print_outcome(outcome: &PublishOutcome) {
+    println!(
+        "Published event on `{}` via provider `{}` -> flow `{}`",
+        outcome.topic, outcome.provider, outcome.flow
+    );
+    match &outcome.result {
+        JsonValue::String(text) if text.is_empty() => {}
+        JsonValue::String(text) => println!("{text}"),
+        other => println!(
+            "{}",
+            serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string())
+        ),
+    }
+    println!(
+        "{} (exit code {})",
+        if outcome.success {
+            "✓ Flow completed"
+        } else {
+            "✗ Flow failed"
+        },
+        outcome
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "n/a".into())
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_payload, select_provider, target_flow};
+    use greentic_pack::builder::{FlowEntry, PACK_VERSION, PackManifest, PackMeta};
+    use greentic_pack::events::{
+        EventProviderCapabilities, EventProviderKind, EventProviderSpec, EventsSection,
+    };
+    use serde_json::json;
+
+    fn provider(name: &str, topics: &[&str], flow: Option<&str>) -> EventProviderSpec {
+        EventProviderSpec {
+            name: name.to_string(),
+            kind: EventProviderKind::Broker,
+            component: "broker.component".to_string(),
+            default_flow: flow.map(str::to_string),
+            custom_flow: None,
+            capabilities: EventProviderCapabilities {
+                topics: topics.iter().map(|t| t.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn manifest(providers: Vec<EventProviderSpec>) -> PackManifest {
+        PackManifest {
+            meta: PackMeta {
+                pack_version: PACK_VERSION,
+                pack_id: "dev.local.events".to_string(),
+                version: semver::Version::new(0, 1, 0),
+                name: "events".to_string(),
+                kind: None,
+                description: None,
+                authors: Vec::new(),
+                license: None,
+                homepage: None,
+                support: None,
+                vendor: None,
+                imports: Vec::new(),
+                entry_flows: vec!["on-order".to_string()],
+                created_at_utc: "2024-01-01T00:00:00Z".to_string(),
+                events: Some(EventsSection { providers }),
+                repo: None,
+                messaging: None,
+                interfaces: Vec::new(),
+                annotations: Default::default(),
+                distribution: None,
+                components: Vec::new(),
+            },
+            flows: vec![FlowEntry {
+                id: "on-order".to_string(),
+                kind: "events".to_string(),
+                entry: "start".to_string(),
+                file_yaml: "flows/on-order/flow.ygtc".to_string(),
+                file_json: "flows/on-order/flow.json".to_string(),
+                hash_blake3: String::new(),
+            }],
+            components: Vec::new(),
+            distribution: None,
+            component_descriptors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn provider_is_selected_by_topic() {
+        let manifest = manifest(vec![
+            provider("orders", &["orders.created"], Some("on-order")),
+            provider("billing", &["invoices.paid"], Some("on-order")),
+        ]);
+        let selected = select_provider(&manifest, "orders.created", None).unwrap();
+        assert_eq!(selected.name, "orders");
+        assert_eq!(target_flow(&manifest, selected).unwrap(), "on-order");
+    }
+
+    #[test]
+    fn ambiguous_topics_require_provider_flag() {
+        let manifest = manifest(vec![
+            provider("orders", &[], Some("on-order")),
+            provider("audit", &["orders.created"], Some("on-order")),
+        ]);
+        let err = select_provider(&manifest, "orders.created", None).unwrap_err();
+        assert!(err.to_string().contains("--provider"));
+        let selected = select_provider(&manifest, "orders.created", Some("audit")).unwrap();
+        assert_eq!(selected.name, "audit");
+    }
+
+    #[test]
+    fn unknown_topic_lists_providers() {
+        let manifest = manifest(vec![provider(
+            "orders",
+            &["orders.created"],
+            Some("on-order"),
+        )]);
+        let err = select_provider(&manifest, "orders.deleted", None).unwrap_err();
+        assert!(err.to_string().contains("orders (orders.created)"));
+    }
+
+    #[test]
+    fn flow_must_exist_in_pack() {
+        let manifest = manifest(vec![provider("orders", &[], Some("missing"))]);
+        let selected = select_provider(&manifest, "anything", None).unwrap();
+        let err = target_flow(&manifest, selected).unwrap_err();
+        assert!(err.to_string().contains("not part of the pack"));
+    }
+
+    #[test]
+    fn payload_accepts_json_yaml_and_text() {
+        assert_eq!(parse_payload(r#"{"id": 1}"#), json!({"id": 1}));
+        assert_eq!(parse_payload("id: 2\n"), json!({"id": 2}));
+        assert_eq!(parse_payload("- not: [valid"), json!("- not: [valid"));
+    }
+}
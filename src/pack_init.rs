@@ -14,6 +14,8 @@ use crate::distributor::{
     DevArtifactKind, DevDistributorClient, DevDistributorError, DevIntent, DevResolveRequest,
     DevResolveResponse, resolve_profile,
 };
+use crate::download::{DownloadOptions, fetch};
+use crate::state_lock::{StateLock, write_atomic};
 
 #[derive(Debug, Clone, Copy)]
 pub enum PackInitIntent {
@@ -24,7 +26,7 @@ pub enum PackInitIntent {
 pub fn run(from: &str, profile: Option<&str>) -> Result<()> {
     let config = config::load_with_meta(None)?;
     let profile = resolve_profile(&config, profile)?;
-    let client = DevDistributorClient::from_profile(profile.clone())?;
+    let client = DevDistributorClient::for_transfers(profile.clone())?;
 
     let resolve = client.resolve(&DevResolveRequest {
         coordinate: from.to_string(),
@@ -42,8 +44,11 @@ pub fn run(from: &str, profile: Option<&str>) -> Result<()> {
         );
     }
 
-    let bytes = client.download_artifact(&resolved.artifact_download_path)?;
-    let cache_path = write_pack_to_cache(&resolved, &bytes)?;
+    let cache_path = download_to_cache(&client, &resolved, "packs", "bundle.gtpack")?;
+    let bytes = Bytes::from(
+        fs::read(&cache_path)
+            .with_context(|| format!("failed to read {}", cache_path.display()))?,
+    );
     let workspace_dir = slug_to_dir(&resolved.name)?;
     fs::create_dir(&workspace_dir).with_context(|| {
         format!(
@@ -77,7 +82,7 @@ pub fn run_component_add(
 ) -> Result<PathBuf> {
     let config = config::load_with_meta(None)?;
     let profile = resolve_profile(&config, profile)?;
-    let client = DevDistributorClient::from_profile(profile.clone())?;
+    let client = DevDistributorClient::for_transfers(profile.clone())?;
 
     let resolve = client.resolve(&DevResolveRequest {
         coordinate: coordinate.to_string(),
@@ -97,8 +102,11 @@ pub fn run_component_add(
         );
     }
 
-    let bytes = client.download_artifact(&resolved.artifact_download_path)?;
-    let (cache_dir, cache_path) = write_component_to_cache(&resolved, &bytes)?;
+    let cache_path = download_to_cache(&client, &resolved, "components", "artifact.wasm")?;
+    let cache_dir = cache_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
     update_workspace_manifest(&resolved, &cache_path)?;
 
     println!(
@@ -117,7 +125,7 @@ fn default_platform() -> String {
     "wasm32-wasip2".to_string()
 }
 
-fn handle_resolve_result(
+pub(crate) fn handle_resolve_result(
     result: Result<DevResolveResponse, DevDistributorError>,
 ) -> Result<DevResolveResponse> {
     match result {
@@ -141,30 +149,27 @@ fn cache_base_dir() -> Result<PathBuf> {
     Ok(base)
 }
 
-fn write_component_to_cache(
+/// Download a resolved artifact into `~/.greentic/cache/<kind>/<slug>/<file_name>`. Interrupted
+/// downloads resume on the next run, and the file only appears once its digest matches.
+fn download_to_cache(
+    client: &DevDistributorClient,
     resolved: &DevResolveResponse,
-    bytes: &Bytes,
-) -> Result<(PathBuf, PathBuf)> {
-    let mut path = cache_base_dir()?;
-    path.push("components");
-    let slug = cache_slug(resolved);
-    path.push(slug);
-    fs::create_dir_all(&path).with_context(|| format!("failed to create {}", path.display()))?;
-    let file_path = path.join("artifact.wasm");
-    fs::write(&file_path, bytes)
-        .with_context(|| format!("failed to write {}", file_path.display()))?;
-    Ok((path, file_path))
-}
-
-fn write_pack_to_cache(resolved: &DevResolveResponse, bytes: &Bytes) -> Result<PathBuf> {
+    kind: &str,
+    file_name: &str,
+) -> Result<PathBuf> {
     let mut path = cache_base_dir()?;
-    path.push("packs");
-    let slug = cache_slug(resolved);
-    path.push(slug);
+    let _lock = StateLock::acquire(&path)?;
+    path.push(kind);
+    path.push(cache_slug(resolved));
     fs::create_dir_all(&path).with_context(|| format!("failed to create {}", path.display()))?;
-    let file_path = path.join("bundle.gtpack");
-    fs::write(&file_path, bytes)
-        .with_context(|| format!("failed to write {}", file_path.display()))?;
+    let file_path = path.join(file_name);
+    fetch(
+        || client.artifact_request(&resolved.artifact_download_path),
+        &file_path,
+        resolved.digest.as_deref(),
+        &DownloadOptions::default(),
+    )
+    .with_context(|| format!("failed to download {}", resolved.coordinate))?;
     Ok(file_path)
 }
 
@@ -209,8 +214,13 @@ pub struct WorkspaceComponent {
     pub entry: ComponentEntry,
 }
 
-pub fn update_workspace_manifest(resolved: &DevResolveResponse, cache_path: &Path) -> Result<()> {
+/// Read-modify-write `.greentic/manifest.json` while holding the `.greentic` state lock, so
+/// concurrent `component add` runs do not drop each other's entries.
+pub fn edit_workspace_manifest(
+    edit: impl FnOnce(&mut WorkspaceManifest) -> Result<()>,
+) -> Result<()> {
     let manifest_path = manifest_path()?;
+    let _lock = StateLock::acquire(manifest_path.parent().unwrap_or_else(|| Path::new(".")))?;
     let mut manifest: WorkspaceManifest = if manifest_path.exists() {
         let data = fs::read_to_string(&manifest_path)
             .with_context(|| format!("failed to read {}", manifest_path.display()))?;
@@ -219,7 +229,13 @@ pub fn update_workspace_manifest(resolved: &DevResolveResponse, cache_path: &Pat
     } else {
         WorkspaceManifest::default()
     };
+    edit(&mut manifest)?;
+    let rendered =
+        serde_json::to_string_pretty(&manifest).context("failed to render workspace manifest")?;
+    write_atomic(&manifest_path, rendered)
+}
 
+pub fn update_workspace_manifest(resolved: &DevResolveResponse, cache_path: &Path) -> Result<()> {
     let version = Version::parse(&resolved.version)
         .with_context(|| format!("invalid semver version `{}`", resolved.version))?;
     let entry = ComponentEntry {
@@ -233,27 +249,24 @@ pub fn update_workspace_manifest(resolved: &DevResolveResponse, cache_path: &Pat
         capabilities: None,
     };
 
-    let mut replaced = false;
-    for existing in manifest.components.iter_mut() {
-        if existing.entry.name == entry.name {
-            existing.coordinate = resolved.coordinate.clone();
-            existing.entry = entry.clone();
-            replaced = true;
-            break;
+    edit_workspace_manifest(|manifest| {
+        let mut replaced = false;
+        for existing in manifest.components.iter_mut() {
+            if existing.entry.name == entry.name {
+                existing.coordinate = resolved.coordinate.clone();
+                existing.entry = entry.clone();
+                replaced = true;
+                break;
+            }
         }
-    }
-    if !replaced {
-        manifest.components.push(WorkspaceComponent {
-            coordinate: resolved.coordinate.clone(),
-            entry,
-        });
-    }
-
-    let rendered =
-        serde_json::to_string_pretty(&manifest).context("failed to render workspace manifest")?;
-    fs::write(&manifest_path, rendered)
-        .with_context(|| format!("failed to write {}", manifest_path.display()))?;
-    Ok(())
+        if !replaced {
+            manifest.components.push(WorkspaceComponent {
+                coordinate: resolved.coordinate.clone(),
+                entry,
+            });
+        }
+        Ok(())
+    })
 }
 
 fn slug_to_dir(name: &str) -> Result<PathBuf> {
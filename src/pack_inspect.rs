@@ -0,0 +1,426 @@
+//! A typed summary of a built gtpack for library callers, and `pack inspect --tree`. A plain
+//! `pack inspect` on the command line still delegates to greentic-pack.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::PackKind;
+use greentic_pack::builder::PackManifest;
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::download::human_bytes;
+use crate::pack_cli::PackInspectArgs;
+
+/// What a gtpack contains and whether its signature and SBOM check out.
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectReport {
+    pub pack_id: String,
+    pub version: String,
+    pub name: String,
+    pub kind: Option<PackKind>,
+    pub flows: Vec<FlowSummary>,
+    pub components: Vec<ComponentSummary>,
+    pub signature_ok: bool,
+    pub sbom_ok: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowSummary {
+    pub id: String,
+    pub kind: String,
+    pub entry: String,
+    pub hash_blake3: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentSummary {
+    pub name: String,
+    pub version: String,
+    pub world: Option<String>,
+    pub hash_blake3: String,
+}
+
+/// Open `pack` with the dev signing policy and summarise its manifest.
+pub fn inspect(pack: &Path) -> Result<InspectReport> {
+    let load = open_pack(pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("failed to open {}: {}", pack.display(), err.message))?;
+    Ok(report(&load))
+}
+
+fn report(load: &PackLoad) -> InspectReport {
+    let meta = &load.manifest.meta;
+    InspectReport {
+        pack_id: meta.pack_id.clone(),
+        version: meta.version.to_string(),
+        name: meta.name.clone(),
+        kind: meta.kind.clone(),
+        flows: load
+            .manifest
+            .flows
+            .iter()
+            .map(|flow| FlowSummary {
+                id: flow.id.clone(),
+                kind: flow.kind.clone(),
+                entry: flow.entry.clone(),
+                hash_blake3: flow.hash_blake3.clone(),
+            })
+            .collect(),
+        components: load
+            .manifest
+            .components
+            .iter()
+            .map(|component| ComponentSummary {
+                name: component.name.clone(),
+                version: component.version.to_string(),
+                world: component.world.clone(),
+                hash_blake3: component.hash_blake3.clone(),
+            })
+            .collect(),
+        signature_ok: load.report.signature_ok,
+        sbom_ok: load.report.sbom_ok,
+        warnings: load.report.warnings.clone(),
+    }
+}
+
+// Archive entries every pack carries besides what its manifest and SBOM list. The SBOM and the
+// signature cover the other files, so they are not listed in the SBOM themselves.
+const PACK_FILES: &[&str] = &[
+    "manifest.cbor",
+    "sbom.json",
+    "sbom.cbor",
+    "signatures/pack.sig",
+    "signatures/chain.pem",
+];
+const HASH_WIDTH: usize = 12;
+
+/// Every entry of a gtpack archive, and the files the pack refers to that the archive lacks.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackTree {
+    pub pack_id: Option<String>,
+    pub version: Option<String>,
+    pub entries: Vec<TreeEntry>,
+    pub missing: Vec<MissingEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeEntry {
+    pub path: String,
+    pub group: EntryGroup,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub compression: String,
+    pub hash_blake3: String,
+    /// Neither the manifest nor the SBOM refers to the entry.
+    pub orphan: bool,
+}
+
+/// A file the manifest or SBOM refers to that is not in the archive.
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingEntry {
+    pub path: String,
+    pub group: EntryGroup,
+    /// What refers to the file, e.g. `flow main` or `sbom`.
+    pub referenced_by: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryGroup {
+    Pack,
+    Flows,
+    Components,
+    Assets,
+    Other,
+}
+
+impl EntryGroup {
+    fn of(path: &str) -> Self {
+        match path.split('/').next() {
+            _ if PACK_FILES.contains(&path) => Self::Pack,
+            Some("manifest.json" | "provenance.json") => Self::Pack,
+            Some("flows") => Self::Flows,
+            Some("components" | "schemas") => Self::Components,
+            Some("assets") => Self::Assets,
+            _ => Self::Other,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pack => "pack",
+            Self::Flows => "flows",
+            Self::Components => "components",
+            Self::Assets => "assets",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SbomFiles {
+    files: Vec<SbomFile>,
+}
+
+#[derive(Deserialize)]
+struct SbomFile {
+    path: String,
+}
+
+/// `pack inspect --tree`: list every archive entry by group. Orphaned entries are flagged; files
+/// the pack refers to but lacks fail the command.
+pub fn run_tree(args: &PackInspectArgs) -> Result<()> {
+    let tree = tree(&args.pack)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&tree)?);
+    } else {
+        print_tree(&args.pack, &tree);
+    }
+    if !tree.missing.is_empty() {
+        bail!(
+            "{} is missing {} referenced file(s)",
+            args.pack.display(),
+            tree.missing.len()
+        );
+    }
+    Ok(())
+}
+
+/// Read the archive of `pack` entry by entry. Nothing is verified, so packs whose SBOM or
+/// signature no longer match can still be listed.
+pub fn tree(pack: &Path) -> Result<PackTree> {
+    let file = File::open(pack).with_context(|| format!("failed to open {}", pack.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .with_context(|| format!("{} is not a gtpack archive", pack.display()))?;
+    let mut entries = Vec::new();
+    let mut manifest = None;
+    let mut sbom = None;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let path = entry.name().to_string();
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("failed to read {path} from {}", pack.display()))?;
+        match path.as_str() {
+            "manifest.cbor" => manifest = serde_cbor::from_slice::<PackManifest>(&bytes).ok(),
+            "sbom.json" => sbom = serde_json::from_slice::<SbomFiles>(&bytes).ok(),
+            "sbom.cbor" => sbom = serde_cbor::from_slice::<SbomFiles>(&bytes).ok(),
+            _ => {}
+        }
+        entries.push(TreeEntry {
+            group: EntryGroup::of(&path),
+            size: entry.size(),
+            compressed_size: entry.compressed_size(),
+            compression: entry.compression().to_string().to_lowercase(),
+            hash_blake3: blake3::hash(&bytes).to_hex().to_string(),
+            orphan: false,
+            path,
+        });
+    }
+
+    let references = references(manifest.as_ref(), sbom.as_ref());
+    let present = entries
+        .iter()
+        .map(|entry| entry.path.clone())
+        .collect::<BTreeSet<_>>();
+    for entry in &mut entries {
+        entry.orphan = entry.group != EntryGroup::Pack && !references.contains_key(&entry.path);
+    }
+    entries.sort_by(|a, b| (a.group, &a.path).cmp(&(b.group, &b.path)));
+    let mut missing = references
+        .into_iter()
+        .filter(|(path, _)| !present.contains(path))
+        .map(|(path, referenced_by)| MissingEntry {
+            group: EntryGroup::of(&path),
+            path,
+            referenced_by,
+        })
+        .collect::<Vec<_>>();
+    if !present.contains("manifest.cbor") {
+        missing.push(MissingEntry {
+            path: "manifest.cbor".to_string(),
+            group: EntryGroup::Pack,
+            referenced_by: "pack".to_string(),
+        });
+    }
+    missing.sort_by(|a, b| (a.group, &a.path).cmp(&(b.group, &b.path)));
+    Ok(PackTree {
+        pack_id: manifest
+            .as_ref()
+            .map(|manifest| manifest.meta.pack_id.clone()),
+        version: manifest.map(|manifest| manifest.meta.version.to_string()),
+        entries,
+        missing,
+    })
+}
+
+// Referenced path -> what refers to it; the manifest names win over the SBOM.
+fn references(
+    manifest: Option<&PackManifest>,
+    sbom: Option<&SbomFiles>,
+) -> BTreeMap<String, String> {
+    let mut references = BTreeMap::new();
+    for file in sbom.iter().flat_map(|sbom| &sbom.files) {
+        references.insert(file.path.clone(), "sbom".to_string());
+    }
+    let Some(manifest) = manifest else {
+        return references;
+    };
+    for flow in &manifest.flows {
+        for path in [&flow.file_yaml, &flow.file_json] {
+            references.insert(path.clone(), format!("flow {}", flow.id));
+        }
+    }
+    for component in &manifest.components {
+        let files = [Some(&component.file_wasm)].into_iter().chain([
+            component.schema_file.as_ref(),
+            component.manifest_file.as_ref(),
+        ]);
+        for path in files.flatten() {
+            references.insert(path.clone(), format!("component {}", component.name));
+        }
+    }
+    references
+}
+
+fn print_tree(pack: &Path, tree: &PackTree) {
+    let size = tree.entries.iter().map(|entry| entry.size).sum();
+    let compressed = tree.entries.iter().map(|entry| entry.compressed_size).sum();
+    let id = match (&tree.pack_id, &tree.version) {
+        (Some(id), Some(version)) => format!(" ({id} {version})"),
+        _ => String::new(),
+    };
+    println!(
+        "{}{id}: {} entries, {} ({} compressed)",
+        pack.display(),
+        tree.entries.len(),
+        human_bytes(size),
+        human_bytes(compressed)
+    );
+    let width = tree
+        .entries
+        .iter()
+        .map(|entry| entry.path.len())
+        .max()
+        .unwrap_or(0);
+    let mut group = None;
+    for entry in &tree.entries {
+        if group != Some(entry.group) {
+            println!("{}/", entry.group.label());
+            group = Some(entry.group);
+        }
+        println!(
+            "  {:<width$}  {:>10}  {:<8}  {}{}",
+            entry.path,
+            human_bytes(entry.size),
+            entry.compression,
+            &entry.hash_blake3[..HASH_WIDTH],
+            if entry.orphan { "  ✗ orphan" } else { "" }
+        );
+    }
+    for missing in &tree.missing {
+        println!(
+            "✗ missing {} (referenced by {})",
+            missing.path, missing.referenced_by
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    #[test]
+    fn tree_groups_entries_and_flags_orphans_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("demo.gtpack");
+        let manifest = serde_json::json!({
+            "meta": {
+                "pack_version": 1,
+                "pack_id": "dev.demo",
+                "version": "0.1.0",
+                "name": "demo",
+                "entry_flows": ["main"],
+                "created_at_utc": "2026-01-01T00:00:00Z"
+            },
+            "flows": [{
+                "id": "main",
+                "kind": "messaging",
+                "entry": "start",
+                "file_yaml": "flows/main/flow.ygtc",
+                "file_json": "flows/main/flow.json",
+                "hash_blake3": ""
+            }],
+            "components": [{
+                "name": "dev.echo",
+                "version": "0.1.0",
+                "file_wasm": "components/dev.echo/component.wasm",
+                "hash_blake3": "",
+                "schema_file": null,
+                "manifest_file": null,
+                "world": null,
+                "capabilities": null
+            }]
+        });
+        let manifest: PackManifest = serde_json::from_value(manifest).unwrap();
+        let sbom = serde_json::json!({
+            "format": "greentic-sbom-v1",
+            "files": [{"path": "assets/logo.png", "size": 4, "hash_blake3": "", "media_type": "image/png"}]
+        });
+        let mut zip = ZipWriter::new(File::create(&pack).unwrap());
+        let entries: [(&str, Vec<u8>); 6] = [
+            ("manifest.cbor", serde_cbor::to_vec(&manifest).unwrap()),
+            ("sbom.json", serde_json::to_vec(&sbom).unwrap()),
+            ("flows/main/flow.ygtc", b"id: main\n".to_vec()),
+            ("flows/main/flow.json", b"{}".to_vec()),
+            ("assets/logo.png", b"logo".to_vec()),
+            ("notes.txt", b"left behind".to_vec()),
+        ];
+        for (path, bytes) in entries {
+            let options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            zip.start_file(path, options).unwrap();
+            zip.write_all(&bytes).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let tree = tree(&pack).unwrap();
+        assert_eq!(tree.pack_id.as_deref(), Some("dev.demo"));
+        let listed = tree
+            .entries
+            .iter()
+            .map(|entry| (entry.group, entry.path.as_str(), entry.orphan))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            [
+                (EntryGroup::Pack, "manifest.cbor", false),
+                (EntryGroup::Pack, "sbom.json", false),
+                (EntryGroup::Flows, "flows/main/flow.json", false),
+                (EntryGroup::Flows, "flows/main/flow.ygtc", false),
+                (EntryGroup::Assets, "assets/logo.png", false),
+                (EntryGroup::Other, "notes.txt", true),
+            ]
+        );
+        let logo = &tree.entries[4];
+        assert_eq!((logo.size, logo.compressed_size), (4, 4));
+        assert_eq!(logo.compression, "stored");
+        assert_eq!(logo.hash_blake3, blake3::hash(b"logo").to_hex().to_string());
+        assert_eq!(tree.missing.len(), 1);
+        assert_eq!(tree.missing[0].path, "components/dev.echo/component.wasm");
+        assert_eq!(tree.missing[0].group, EntryGroup::Components);
+        assert_eq!(tree.missing[0].referenced_by, "component dev.echo");
+    }
+}
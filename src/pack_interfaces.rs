@@ -0,0 +1,207 @@
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::repo::InterfaceBinding;
+use semver::VersionReq;
+use serde::Deserialize;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, value};
+
+use crate::pack_cli::{PackInterfaceBindArgs, PackInterfaceListArgs, PackInterfaceUnbindArgs};
+use crate::pack_meta::{read_document, read_typed, write_document};
+
+#[derive(Debug, Default, Deserialize)]
+struct InterfacesMeta {
+    #[serde(default)]
+    interfaces: Vec<InterfaceBinding>,
+}
+
+/// Print the interface bindings declared in the pack metadata.
+pub fn list(args: &PackInterfaceListArgs) -> Result<()> {
+    let meta = read_typed::<InterfacesMeta>(&args.meta)?;
+    validate_bindings(&meta.interfaces)
+        .with_context(|| format!("invalid interfaces in {}", args.meta.display()))?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&meta.interfaces)?);
+        return Ok(());
+    }
+    if meta.interfaces.is_empty() {
+        println!("No interfaces bound in {}", args.meta.display());
+    }
+    for binding in &meta.interfaces {
+        print!("{}/{}@{}", binding.package, binding.world, binding.version);
+        match &binding.note {
+            Some(note) => println!("  # {note}"),
+            None => println!(),
+        }
+    }
+    Ok(())
+}
+
+/// Bind the pack to a WIT interface (package + world + version).
+pub fn bind(args: &PackInterfaceBindArgs) -> Result<()> {
+    let binding = InterfaceBinding {
+        package: args.package.clone(),
+        world: args.world.clone(),
+        version: args.version.clone(),
+        note: args.note.clone(),
+    };
+    let mut doc = read_document(&args.meta)?;
+    insert_binding(&mut doc, &binding)?;
+    let meta = toml::from_str::<InterfacesMeta>(&doc.to_string())
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?;
+    validate_bindings(&meta.interfaces)
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?;
+    write_document(&args.meta, &doc)?;
+    println!(
+        "Bound {}/{}@{} in {}",
+        binding.package,
+        binding.world,
+        binding.version,
+        args.meta.display()
+    );
+    Ok(())
+}
+
+/// Remove interface bindings for a package (optionally narrowed to one world).
+pub fn unbind(args: &PackInterfaceUnbindArgs) -> Result<()> {
+    let mut doc = read_document(&args.meta)?;
+    let removed = remove_bindings(&mut doc, &args.package, args.world.as_deref())?;
+    if removed == 0 {
+        bail!(
+            "no interface binding for `{}` in {}",
+            args.package,
+            args.meta.display()
+        );
+    }
+    write_document(&args.meta, &doc)?;
+    println!(
+        "Removed {removed} interface binding(s) for `{}` from {}",
+        args.package,
+        args.meta.display()
+    );
+    Ok(())
+}
+
+fn validate_bindings(bindings: &[InterfaceBinding]) -> Result<()> {
+    let mut seen = std::collections::BTreeSet::new();
+    for binding in bindings {
+        binding.validate("interfaces")?;
+        VersionReq::parse(&binding.version).with_context(|| {
+            format!(
+                "interfaces[{}].version `{}` is not a valid semver requirement",
+                binding.package, binding.version
+            )
+        })?;
+        if !seen.insert((binding.package.as_str(), binding.world.as_str())) {
+            bail!(
+                "interface {}/{} is bound more than once",
+                binding.package,
+                binding.world
+            );
+        }
+    }
+    Ok(())
+}
+
+fn insert_binding(doc: &mut DocumentMut, binding: &InterfaceBinding) -> Result<()> {
+    let interfaces = doc
+        .as_table_mut()
+        .entry("interfaces")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow!("`interfaces` must be an array of tables"))?;
+    if interfaces
+        .iter()
+        .any(|table| matches_binding(table, &binding.package, Some(&binding.world)))
+    {
+        bail!(
+            "interface {}/{} is already bound; unbind it first to change the version",
+            binding.package,
+            binding.world
+        );
+    }
+
+    let mut table = Table::new();
+    table.insert("package", value(&binding.package));
+    table.insert("world", value(&binding.world));
+    table.insert("version", value(&binding.version));
+    if let Some(note) = &binding.note {
+        table.insert("note", value(note));
+    }
+    interfaces.push(table);
+    Ok(())
+}
+
+fn remove_bindings(doc: &mut DocumentMut, package: &str, world: Option<&str>) -> Result<usize> {
+    let Some(item) = doc.as_table_mut().get_mut("interfaces") else {
+        return Ok(0);
+    };
+    let interfaces = item
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow!("`interfaces` must be an array of tables"))?;
+    let before = interfaces.len();
+    interfaces.retain(|table| !matches_binding(table, package, world));
+    let removed = before - interfaces.len();
+    if interfaces.is_empty() {
+        doc.as_table_mut().remove("interfaces");
+    }
+    Ok(removed)
+}
+
+fn matches_binding(table: &Table, package: &str, world: Option<&str>) -> bool {
+    table.get("package").and_then(Item::as_str) == Some(package)
+        && world.is_none_or(|world| table.get("world").and_then(Item::as_str) == Some(world))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InterfacesMeta, insert_binding, remove_bindings, validate_bindings};
+    use greentic_pack::repo::InterfaceBinding;
+    use toml_edit::DocumentMut;
+
+    fn binding(world: &str, version: &str) -> InterfaceBinding {
+        InterfaceBinding {
+            package: "greentic:repo".to_string(),
+            world: world.to_string(),
+            version: version.to_string(),
+            note: None,
+        }
+    }
+
+    fn parse(doc: &DocumentMut) -> Vec<InterfaceBinding> {
+        toml::from_str::<InterfacesMeta>(&doc.to_string())
+            .unwrap()
+            .interfaces
+    }
+
+    #[test]
+    fn bind_and_unbind_round_trip() {
+        let mut doc = "pack_id = \"dev.local.repo\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        insert_binding(&mut doc, &binding("scanner", "^1.0")).unwrap();
+        insert_binding(&mut doc, &binding("signing", "1.2.0")).unwrap();
+        assert_eq!(parse(&doc).len(), 2);
+
+        assert_eq!(
+            remove_bindings(&mut doc, "greentic:repo", Some("scanner")).unwrap(),
+            1
+        );
+        assert_eq!(parse(&doc), vec![binding("signing", "1.2.0")]);
+        assert_eq!(remove_bindings(&mut doc, "greentic:repo", None).unwrap(), 1);
+        assert_eq!(doc.to_string(), "pack_id = \"dev.local.repo\"\n");
+    }
+
+    #[test]
+    fn duplicate_world_is_rejected() {
+        let mut doc = DocumentMut::new();
+        insert_binding(&mut doc, &binding("scanner", "^1.0")).unwrap();
+        let err = insert_binding(&mut doc, &binding("scanner", "^2.0")).unwrap_err();
+        assert!(err.to_string().contains("already bound"));
+    }
+
+    #[test]
+    fn invalid_versions_are_reported() {
+        let err = validate_bindings(&[binding("scanner", "latest")]).unwrap_err();
+        assert!(err.to_string().contains("not a valid semver requirement"));
+        assert!(validate_bindings(&[binding("", "1.0.0")]).is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow, bail};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::Serialize;
+
+use crate::capability_policy::{CapabilityPolicy, LicensePolicy};
+use crate::pack_cli::PackLicensesArgs;
+
+const LICENSES_ANNOTATION: &str = "licenses";
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+// SPDX tags live in file headers; do not scan whole assets for them.
+const SPDX_HEADER_LINES: usize = 20;
+
+/// Licenses found in a pack, with any policy violations.
+#[derive(Debug, Serialize)]
+pub struct LicenseReport {
+    pub pack_id: String,
+    pub version: String,
+    pub entries: Vec<LicenseEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LicenseEntry {
+    /// `pack`, a component name, or a file path from the SBOM.
+    pub subject: String,
+    pub kind: SubjectKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// SPDX license expression, `None` when nothing was declared.
+    pub license: Option<String>,
+    /// Where the license was read from.
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectKind {
+    Pack,
+    Component,
+    File,
+}
+
+impl LicenseReport {
+    pub fn violations(&self) -> impl Iterator<Item = &LicenseEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.violation.is_some())
+    }
+}
+
+/// `pack licenses`: list the licenses a pack ships and check them against the `licenses` section
+/// of the workspace policy.
+pub fn run(args: &PackLicensesArgs) -> Result<()> {
+    let load = open_pack(&args.pack, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
+    let policy = CapabilityPolicy::load(&args.policy)?;
+    let licenses = policy
+        .as_ref()
+        .and_then(|policy| policy.for_pack(&load.manifest.meta.pack_id))
+        .and_then(|policy| policy.licenses.as_ref());
+    let report = scan(&load, licenses);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report, licenses.map(|_| args.policy.as_path()));
+    }
+    let violations = report.violations().count();
+    if violations > 0 {
+        bail!(
+            "{violations} license policy violation(s) in {}",
+            args.policy.display()
+        );
+    }
+    Ok(())
+}
+
+/// Collect the licenses declared for the pack, its components and, via SPDX headers, the files
+/// in its SBOM, checking each against `policy` when there is one.
+pub fn scan(load: &PackLoad, policy: Option<&LicensePolicy>) -> LicenseReport {
+    let meta = &load.manifest.meta;
+    let mut entries = vec![LicenseEntry {
+        subject: "pack".to_string(),
+        kind: SubjectKind::Pack,
+        version: Some(meta.version.to_string()),
+        license: meta.license.clone(),
+        source: "pack manifest".to_string(),
+        violation: None,
+    }];
+
+    // Component manifests have no license field of their own, so packs declare them in the
+    // `licenses` annotation (`[annotations.licenses]` in pack.toml), keyed by component name.
+    let annotated = meta.annotations.get(LICENSES_ANNOTATION);
+    for component in &load.manifest.components {
+        let (license, source) = match annotated.and_then(|map| map[&component.name].as_str()) {
+            Some(license) => (
+                Some(license.to_string()),
+                format!("annotations.{LICENSES_ANNOTATION}"),
+            ),
+            None => (None, "not declared".to_string()),
+        };
+        entries.push(LicenseEntry {
+            subject: component.name.clone(),
+            kind: SubjectKind::Component,
+            version: Some(component.version.to_string()),
+            license,
+            source,
+            violation: None,
+        });
+    }
+
+    for file in &load.sbom {
+        let Some(license) = load
+            .files
+            .get(&file.path)
+            .and_then(|bytes| spdx_header(bytes))
+        else {
+            continue;
+        };
+        entries.push(LicenseEntry {
+            subject: file.path.clone(),
+            kind: SubjectKind::File,
+            version: None,
+            license: Some(license),
+            source: "SPDX header".to_string(),
+            violation: None,
+        });
+    }
+
+    if let Some(policy) = policy {
+        for entry in &mut entries {
+            entry.violation = check(policy, entry.license.as_deref()).err();
+        }
+    }
+    LicenseReport {
+        pack_id: meta.pack_id.clone(),
+        version: meta.version.to_string(),
+        entries,
+    }
+}
+
+fn spdx_header(bytes: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    text.lines().take(SPDX_HEADER_LINES).find_map(|line| {
+        let (_, rest) = line.split_once(SPDX_TAG)?;
+        let expression = rest
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        (!expression.is_empty()).then(|| expression.to_string())
+    })
+}
+
+/// Check one declared license expression against a license policy.
+pub fn check(policy: &LicensePolicy, license: Option<&str>) -> Result<(), String> {
+    let license = license
+        .map(str::trim)
+        .filter(|license| !license.is_empty() && *license != "NOASSERTION");
+    let Some(license) = license else {
+        return if policy.allow_unknown {
+            Ok(())
+        } else {
+            Err("no license declared".to_string())
+        };
+    };
+    let expression = parse_expression(license)
+        .ok_or_else(|| format!("`{license}` is not a valid SPDX expression"))?;
+    evaluate(policy, &expression)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Expression {
+    License(String),
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+}
+
+fn parse_expression(text: &str) -> Option<Expression> {
+    let spaced = text.replace('(', " ( ").replace(')', " ) ");
+    let tokens = spaced.split_whitespace().collect::<Vec<_>>();
+    let mut pos = 0;
+    let expression = parse_or(&tokens, &mut pos)?;
+    (pos == tokens.len()).then_some(expression)
+}
+
+fn parse_or(tokens: &[&str], pos: &mut usize) -> Option<Expression> {
+    let mut terms = vec![parse_and(tokens, pos)?];
+    while tokens
+        .get(*pos)
+        .is_some_and(|token| token.eq_ignore_ascii_case("OR"))
+    {
+        *pos += 1;
+        terms.push(parse_and(tokens, pos)?);
+    }
+    Some(if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        Expression::Or(terms)
+    })
+}
+
+fn parse_and(tokens: &[&str], pos: &mut usize) -> Option<Expression> {
+    let mut terms = vec![parse_term(tokens, pos)?];
+    while tokens
+        .get(*pos)
+        .is_some_and(|token| token.eq_ignore_ascii_case("AND"))
+    {
+        *pos += 1;
+        terms.push(parse_term(tokens, pos)?);
+    }
+    Some(if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        Expression::And(terms)
+    })
+}
+
+fn parse_term(tokens: &[&str], pos: &mut usize) -> Option<Expression> {
+    let token = *tokens.get(*pos)?;
+    *pos += 1;
+    if token == "(" {
+        let inner = parse_or(tokens, pos)?;
+        if tokens.get(*pos) != Some(&")") {
+            return None;
+        }
+        *pos += 1;
+        return Some(inner);
+    }
+    if token == ")"
+        || ["AND", "OR", "WITH"]
+            .iter()
+            .any(|op| token.eq_ignore_ascii_case(op))
+    {
+        return None;
+    }
+    // An exception (`GPL-2.0-only WITH Classpath-exception-2.0`) narrows the license; the
+    // policy is written in terms of the license itself.
+    if tokens
+        .get(*pos)
+        .is_some_and(|next| next.eq_ignore_ascii_case("WITH"))
+    {
+        tokens.get(*pos + 1)?;
+        *pos += 2;
+    }
+    Some(Expression::License(token.to_string()))
+}
+
+fn evaluate(policy: &LicensePolicy, expression: &Expression) -> Result<(), String> {
+    match expression {
+        Expression::License(id) => {
+            let listed = |list: &[String]| list.iter().any(|item| item.eq_ignore_ascii_case(id));
+            if listed(&policy.deny) {
+                Err(format!("`{id}` is denied"))
+            } else if policy.allow.as_deref().is_some_and(|allow| !listed(allow)) {
+                Err(format!("`{id}` is not allowed"))
+            } else {
+                Ok(())
+            }
+        }
+        Expression::And(terms) => terms.iter().try_for_each(|term| evaluate(policy, term)),
+        Expression::Or(terms) => {
+            let mut errors = Vec::new();
+            for term in terms {
+                match evaluate(policy, term) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => errors.push(err),
+                }
+            }
+            Err(errors.join(", "))
+        }
+    }
+}
+
+fn print_report(report: &LicenseReport, policy: Option<&Path>) {
+    match policy {
+        Some(path) => println!(
+            "Pack {} {} (policy: {})",
+            report.pack_id,
+            report.version,
+            path.display()
+        ),
+        None => println!(
+            "Pack {} {} (no license policy)",
+            report.pack_id, report.version
+        ),
+    }
+    for entry in &report.entries {
+        let subject = match (&entry.kind, &entry.version) {
+            (SubjectKind::Component, Some(version)) => format!("{}@{version}", entry.subject),
+            _ => entry.subject.clone(),
+        };
+        println!(
+            "  {subject}: {} ({})",
+            entry.license.as_deref().unwrap_or("unknown"),
+            entry.source
+        );
+        if let Some(violation) = &entry.violation {
+            println!("    ✗ {violation}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expression, check, parse_expression, spdx_header};
+    use crate::capability_policy::LicensePolicy;
+
+    fn policy() -> LicensePolicy {
+        LicensePolicy {
+            allow: Some(vec!["MIT".to_string(), "Apache-2.0".to_string()]),
+            deny: vec!["GPL-3.0-only".to_string()],
+            allow_unknown: false,
+        }
+    }
+
+    #[test]
+    fn expressions_bind_and_tighter_than_or() {
+        let license = |id: &str| Expression::License(id.to_string());
+        assert_eq!(
+            parse_expression("MIT OR Apache-2.0 AND BSD-3-Clause").unwrap(),
+            Expression::Or(vec![
+                license("MIT"),
+                Expression::And(vec![license("Apache-2.0"), license("BSD-3-Clause")]),
+            ])
+        );
+        assert_eq!(
+            parse_expression("(MIT OR Apache-2.0) AND GPL-2.0-only WITH Classpath-exception-2.0")
+                .unwrap(),
+            Expression::And(vec![
+                Expression::Or(vec![license("MIT"), license("Apache-2.0")]),
+                license("GPL-2.0-only"),
+            ])
+        );
+        assert!(parse_expression("MIT AND").is_none());
+        assert!(parse_expression("(MIT").is_none());
+    }
+
+    #[test]
+    fn policy_accepts_any_allowed_alternative() {
+        let policy = policy();
+        assert!(check(&policy, Some("mit")).is_ok());
+        assert!(check(&policy, Some("GPL-3.0-only OR Apache-2.0")).is_ok());
+        assert_eq!(
+            check(&policy, Some("MIT AND GPL-3.0-only")).unwrap_err(),
+            "`GPL-3.0-only` is denied"
+        );
+        assert_eq!(
+            check(&policy, Some("BSD-3-Clause")).unwrap_err(),
+            "`BSD-3-Clause` is not allowed"
+        );
+        assert_eq!(check(&policy, None).unwrap_err(), "no license declared");
+        let lenient = LicensePolicy {
+            allow_unknown: true,
+            ..policy
+        };
+        assert!(check(&lenient, Some("NOASSERTION")).is_ok());
+    }
+
+    #[test]
+    fn spdx_headers_are_read_from_the_top_of_text_files() {
+        assert_eq!(
+            spdx_header(b"/* SPDX-License-Identifier: MIT OR Apache-2.0 */\nbody").as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            spdx_header(b"<!-- SPDX-License-Identifier: CC-BY-4.0 -->").as_deref(),
+            Some("CC-BY-4.0")
+        );
+        assert!(spdx_header(b"no header here").is_none());
+        assert!(spdx_header(&[0, 159, 146, 150]).is_none());
+    }
+}
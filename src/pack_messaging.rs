@@ -0,0 +1,185 @@
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::messaging::{MessagingAdapter, MessagingSection};
+use serde::Deserialize;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Table, value};
+
+use crate::pack_cli::{PackMessagingAddChannelArgs, PackMessagingValidateArgs};
+use crate::pack_meta::{read_document, write_document};
+
+// Only the `[messaging]` table is interpreted here; the rest of the pack metadata is left to
+// `pack_build`, which owns the full schema.
+#[derive(Debug, Default, Deserialize)]
+struct MessagingMeta {
+    messaging: Option<MessagingSection>,
+}
+
+/// Append a messaging adapter (channel) to the pack metadata, keeping the rest of the file intact.
+pub fn add_channel(args: &PackMessagingAddChannelArgs) -> Result<()> {
+    let mut doc = read_document(&args.meta)?;
+    let channel = MessagingAdapter {
+        name: args.name.clone(),
+        kind: args.kind.into(),
+        component: args.provider.clone(),
+        default_flow: args.flow.clone(),
+        custom_flow: None,
+        capabilities: None,
+    };
+    insert_channel(&mut doc, &channel, &args.features)?;
+
+    let section = parse_section(&doc.to_string())
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?;
+    section
+        .validate()
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?;
+
+    write_document(&args.meta, &doc)?;
+    println!(
+        "Added messaging channel `{}` ({}) to {}",
+        channel.name,
+        args.provider,
+        args.meta.display()
+    );
+    Ok(())
+}
+
+/// Validate the `[messaging]` section of the pack metadata without building the pack.
+pub fn validate(args: &PackMessagingValidateArgs) -> Result<()> {
+    let raw = std::fs::read_to_string(&args.meta)
+        .with_context(|| format!("failed to read {}", args.meta.display()))?;
+    let section = parse_section(&raw)
+        .and_then(|section| section.validate().map(|_| section))
+        .with_context(|| format!("invalid messaging section in {}", args.meta.display()))?;
+    let channels = section.adapters.as_deref().unwrap_or_default();
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "meta": args.meta.display().to_string(),
+                "valid": true,
+                "channels": channels,
+            }))?
+        );
+    } else {
+        println!(
+            "✓ {}: messaging section is valid ({} channel(s))",
+            args.meta.display(),
+            channels.len()
+        );
+        for channel in channels {
+            println!("  - {} -> {}", channel.name, channel.component);
+        }
+    }
+    Ok(())
+}
+
+fn parse_section(raw: &str) -> Result<MessagingSection> {
+    let meta =
+        toml::from_str::<MessagingMeta>(raw).context("messaging section does not match schema")?;
+    Ok(meta.messaging.unwrap_or_default())
+}
+
+fn insert_channel(
+    doc: &mut DocumentMut,
+    channel: &MessagingAdapter,
+    features: &[String],
+) -> Result<()> {
+    let messaging = doc
+        .as_table_mut()
+        .entry("messaging")
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("`messaging` is not a table in the pack metadata"))?;
+    let adapters = messaging
+        .entry("adapters")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow!("`messaging.adapters` must be an array of tables"))?;
+    if adapters
+        .iter()
+        .any(|table| table.get("name").and_then(Item::as_str) == Some(channel.name.as_str()))
+    {
+        bail!("messaging channel `{}` already exists", channel.name);
+    }
+
+    let kind = serde_json::to_value(&channel.kind)?;
+    let mut table = Table::new();
+    table.insert("name", value(&channel.name));
+    table.insert("kind", value(kind.as_str().unwrap_or_default()));
+    table.insert("component", value(&channel.component));
+    if let Some(flow) = &channel.default_flow {
+        table.insert("default_flow", value(flow));
+    }
+    if !features.is_empty() {
+        let mut capabilities = Table::new();
+        capabilities.insert("features", value(features.iter().collect::<Array>()));
+        table.insert("capabilities", Item::Table(capabilities));
+    }
+    adapters.push(table);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_channel, parse_section};
+    use greentic_pack::messaging::{MessagingAdapter, MessagingAdapterKind};
+    use toml_edit::DocumentMut;
+
+    fn channel(name: &str) -> MessagingAdapter {
+        MessagingAdapter {
+            name: name.to_string(),
+            kind: MessagingAdapterKind::IngressEgress,
+            component: "greentic.messaging.slack".to_string(),
+            default_flow: Some("support".to_string()),
+            custom_flow: None,
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn add_channel_preserves_existing_metadata() {
+        let mut doc = "# pack metadata\npack_id = \"dev.local.support\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        insert_channel(&mut doc, &channel("slack"), &["threads".to_string()]).unwrap();
+        let rendered = doc.to_string();
+        assert!(rendered.starts_with("# pack metadata\npack_id = \"dev.local.support\""));
+
+        let section = parse_section(&rendered).unwrap();
+        section.validate().unwrap();
+        let adapters = section.adapters.unwrap();
+        assert_eq!(adapters.len(), 1);
+        assert_eq!(adapters[0].kind, MessagingAdapterKind::IngressEgress);
+        assert_eq!(adapters[0].default_flow.as_deref(), Some("support"));
+        assert_eq!(
+            adapters[0].capabilities.as_ref().unwrap().features,
+            ["threads"]
+        );
+    }
+
+    #[test]
+    fn duplicate_channels_are_rejected() {
+        let mut doc = DocumentMut::new();
+        insert_channel(&mut doc, &channel("slack"), &[]).unwrap();
+        let err = insert_channel(&mut doc, &channel("slack"), &[]).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn schema_errors_are_reported() {
+        let err = parse_section(
+            "[[messaging.adapters]]\nname = \"slack\"\nkind = \"sideways\"\ncomponent = \"c\"\n",
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("sideways"));
+
+        let section = parse_section(
+            "[[messaging.adapters]]\nname = \"slack\"\nkind = \"ingress\"\ncomponent = \" \"\n",
+        )
+        .unwrap();
+        assert!(section.validate().is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use toml_edit::DocumentMut;
+
+// Helpers shared by the `pack` subcommands that edit the pack metadata TOML in place. Edits go
+// through `toml_edit` so comments and ordering survive; typed views are re-parsed with `toml`
+// so the same schema `pack_build` enforces is checked before anything is written.
+
+pub(crate) fn read_document(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    raw.parse::<DocumentMut>()
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
+
+pub(crate) fn read_typed<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("invalid pack metadata {}", path.display()))
+}
+
+pub(crate) fn write_document(path: &Path, doc: &DocumentMut) -> Result<()> {
+    fs::write(path, doc.to_string()).with_context(|| format!("failed to write {}", path.display()))
+}
@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::plan::infer_base_deployment_plan;
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use greentic_types::component::ComponentManifest;
+use greentic_types::deployment::DeploymentPlan;
+use greentic_types::{EnvId, SecretRequirement, TenantCtx, TenantId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::pack_cli::{PackPlanArgs, PlanExportFormat};
+use crate::pack_plan_baseline;
+use crate::pack_plan_terraform;
+use crate::pack_preflight::secret_requirements;
+
+/// Reviewable digest of a pack's deployment plan. `--json` output reads back as a `--baseline`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlanSummary {
+    pub pack_id: String,
+    pub pack_version: String,
+    pub tenant: String,
+    pub environment: String,
+    pub components: Vec<ComponentSummary>,
+    pub providers: Vec<String>,
+    pub secrets: Vec<SecretSummary>,
+    pub connectors: Vec<String>,
+    pub resources: Resources,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentSummary {
+    pub name: String,
+    pub version: String,
+    pub wasm_bytes: u64,
+    pub flows: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretSummary {
+    pub key: String,
+    pub required: bool,
+    pub components: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Resources {
+    pub runners: usize,
+    pub replicas: u32,
+    pub wasm_bytes: u64,
+    pub messaging_subjects: usize,
+    pub telemetry: bool,
+}
+
+/// `pack plan --summary` / `--export` / `--baseline`: infer the deployment plan like
+/// `greentic-pack plan`, then render it for review (with warnings for likely mistakes), export it,
+/// or compare it with a saved summary.
+pub fn run(args: &PackPlanArgs) -> Result<()> {
+    if let Some(baseline) = &args.baseline {
+        let saved = pack_plan_baseline::load(baseline)?;
+        let summary = plan_summary(&args.pack, &args.tenant, &args.environment)?;
+        let drift = pack_plan_baseline::drift(&saved, &summary, baseline);
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&drift)?);
+        } else {
+            print!("{}", pack_plan_baseline::render(&drift));
+        }
+        if !drift.is_empty() {
+            bail!(
+                "plan drifted from {}: {} change(s)",
+                baseline.display(),
+                drift.len()
+            );
+        }
+        return Ok(());
+    }
+    if let (Some(PlanExportFormat::Terraform), Some(out)) = (args.export, &args.out) {
+        let (load, plan) = load_plan(&args.pack, &args.tenant, &args.environment)?;
+        let written = pack_plan_terraform::write_module(&load.manifest, &plan, out)?;
+        println!(
+            "Terraform module for {}@{} written to {}",
+            plan.pack_id,
+            plan.pack_version,
+            out.display()
+        );
+        for path in written {
+            println!("  {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let summary = plan_summary(&args.pack, &args.tenant, &args.environment)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        print_summary(&summary);
+    }
+    Ok(())
+}
+
+/// Infer the deployment plan for `pack` and digest it into a [`PlanSummary`].
+pub fn plan_summary(pack: &Path, tenant: &str, environment: &str) -> Result<PlanSummary> {
+    let (load, plan) = load_plan(pack, tenant, environment)?;
+    Ok(summarize(&load, &plan))
+}
+
+/// Open `pack` and infer its deployment plan for `tenant` in `environment`.
+pub fn load_plan(
+    pack: &Path,
+    tenant: &str,
+    environment: &str,
+) -> Result<(PackLoad, DeploymentPlan)> {
+    let load = open_pack(pack, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
+    let ctx = TenantCtx::new(
+        EnvId::from_str(environment)
+            .with_context(|| format!("invalid environment id `{environment}`"))?,
+        TenantId::from_str(tenant).with_context(|| format!("invalid tenant id `{tenant}`"))?,
+    );
+    let plan = infer_plan(&load, &ctx, environment)?;
+    Ok((load, plan))
+}
+
+fn infer_plan(load: &PackLoad, tenant: &TenantCtx, environment: &str) -> Result<DeploymentPlan> {
+    let mut components = HashMap::new();
+    for component in &load.manifest.components {
+        let Some(path) = component.manifest_file.as_deref() else {
+            continue;
+        };
+        let bytes = load
+            .files
+            .get(path)
+            .ok_or_else(|| anyhow!("component manifest `{path}` missing from the pack"))?;
+        let manifest: ComponentManifest = serde_json::from_slice(bytes)
+            .with_context(|| format!("failed to parse component manifest `{path}`"))?;
+        components.insert(component.name.clone(), manifest);
+    }
+    Ok(infer_base_deployment_plan(
+        &load.manifest.meta,
+        &load.manifest.flows,
+        load.manifest.meta.annotations.get("connectors"),
+        &components,
+        declared_secrets(load)?,
+        tenant,
+        environment,
+    ))
+}
+
+// Secret requirements the pack declares explicitly, as `greentic-pack plan` reads them.
+pub(crate) fn declared_secrets(load: &PackLoad) -> Result<Option<Vec<SecretRequirement>>> {
+    if let Some(manifest) = &load.gpack_manifest
+        && !manifest.secret_requirements.is_empty()
+    {
+        return Ok(Some(manifest.secret_requirements.clone()));
+    }
+    for name in [
+        "assets/secret-requirements.json",
+        "secret-requirements.json",
+    ] {
+        if let Some(bytes) = load.files.get(name) {
+            return serde_json::from_slice(bytes)
+                .map(Some)
+                .with_context(|| format!("{name} is not valid JSON"));
+        }
+    }
+    Ok(None)
+}
+
+/// Digest an inferred plan, flagging components, secrets and requirements that do not line up.
+pub(crate) fn summarize(load: &PackLoad, plan: &DeploymentPlan) -> PlanSummary {
+    let manifest = &load.manifest;
+    let usage = component_usage(load);
+    let mut warnings = Vec::new();
+
+    let components = manifest
+        .components
+        .iter()
+        .map(|component| {
+            let flows = usage
+                .get(&component.name)
+                .map(|flows| flows.iter().cloned().collect())
+                .unwrap_or_default();
+            ComponentSummary {
+                name: component.name.clone(),
+                version: component.version.to_string(),
+                wasm_bytes: load
+                    .files
+                    .get(&component.file_wasm)
+                    .map_or(0, |wasm| wasm.len() as u64),
+                flows,
+            }
+        })
+        .collect::<Vec<_>>();
+    for component in components.iter().filter(|c| c.flows.is_empty()) {
+        warnings.push(format!(
+            "component {} is not used by any flow",
+            component.name
+        ));
+    }
+
+    let requested = secret_requirements(manifest);
+    let planned = plan
+        .secrets
+        .iter()
+        .map(|secret| secret.key.as_str().to_string())
+        .collect::<BTreeSet<_>>();
+    let mut secrets = plan
+        .secrets
+        .iter()
+        .map(|secret| SecretSummary {
+            key: secret.key.as_str().to_string(),
+            required: secret.required,
+            components: requested
+                .get(secret.key.as_str())
+                .map(|(_, users)| users.iter().cloned().collect())
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    for secret in secrets.iter().filter(|secret| secret.components.is_empty()) {
+        warnings.push(format!(
+            "secret {} is in the plan but no component requires it",
+            secret.key
+        ));
+    }
+    for (key, (required, users)) in &requested {
+        if planned.contains(key) {
+            continue;
+        }
+        let users = users.iter().cloned().collect::<Vec<_>>();
+        warnings.push(format!(
+            "secret {key} is requested by {} but missing from the plan's secret requirements",
+            users.join(", ")
+        ));
+        secrets.push(SecretSummary {
+            key: key.clone(),
+            required: *required,
+            components: users,
+        });
+    }
+
+    let mut providers = plan
+        .oauth
+        .iter()
+        .map(|oauth| format!("oauth:{}", oauth.provider_id))
+        .collect::<BTreeSet<_>>();
+    if let Some(messaging) = &plan.messaging {
+        providers.insert(format!("messaging:{}", messaging.logical_cluster));
+    }
+    for channel in &plan.channels {
+        providers.insert(format!("channel:{}", channel.kind));
+    }
+    let connectors = plan
+        .channels
+        .iter()
+        .map(|channel| format!("{} ({}) -> {}", channel.name, channel.kind, channel.flow_id))
+        .collect();
+
+    let resources = Resources {
+        runners: plan.runners.len(),
+        replicas: plan.runners.iter().map(|runner| runner.replicas).sum(),
+        wasm_bytes: components.iter().map(|c| c.wasm_bytes).sum(),
+        messaging_subjects: plan
+            .messaging
+            .as_ref()
+            .map_or(0, |messaging| messaging.subjects.len()),
+        telemetry: plan
+            .telemetry
+            .as_ref()
+            .is_some_and(|telemetry| telemetry.required),
+    };
+
+    PlanSummary {
+        pack_id: plan.pack_id.clone(),
+        pack_version: plan.pack_version.to_string(),
+        tenant: plan.tenant.clone(),
+        environment: plan.environment.clone(),
+        components,
+        providers: providers.into_iter().collect(),
+        secrets,
+        connectors,
+        resources,
+        warnings,
+    }
+}
+
+// Flows that reference each component, keyed by component name.
+fn component_usage(load: &PackLoad) -> BTreeMap<String, BTreeSet<String>> {
+    let names = load
+        .manifest
+        .components
+        .iter()
+        .map(|component| component.name.as_str())
+        .collect::<BTreeSet<_>>();
+    let mut usage: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for flow in &load.manifest.flows {
+        let Some(doc) = load
+            .files
+            .get(&flow.file_yaml)
+            .and_then(|yaml| serde_yaml_bw::from_slice::<JsonValue>(yaml).ok())
+        else {
+            continue;
+        };
+        let nodes = doc.get("nodes").and_then(JsonValue::as_object);
+        for node in nodes.into_iter().flat_map(|nodes| nodes.values()) {
+            let Some(node) = node.as_object() else {
+                continue;
+            };
+            for (key, payload) in node {
+                let component = if key == "component.exec" {
+                    payload.get("component").and_then(JsonValue::as_str)
+                } else {
+                    Some(key.as_str())
+                };
+                if let Some(component) = component.filter(|name| names.contains(name)) {
+                    usage
+                        .entry(component.to_string())
+                        .or_default()
+                        .insert(flow.id.clone());
+                }
+            }
+        }
+    }
+    usage
+}
+
+fn print_summary(summary: &PlanSummary) {
+    println!(
+        "Deployment plan for {}@{} (tenant {}, environment {})",
+        summary.pack_id, summary.pack_version, summary.tenant, summary.environment
+    );
+    println!("\nComponents ({}):", summary.components.len());
+    for component in &summary.components {
+        let flows = if component.flows.is_empty() {
+            "no flows".to_string()
+        } else {
+            component.flows.join(", ")
+        };
+        println!(
+            "  {}@{}  {}  [{flows}]",
+            component.name,
+            component.version,
+            human_bytes(component.wasm_bytes)
+        );
+    }
+    println!("\nProviders:  {}", list_or_none(&summary.providers));
+    println!("Connectors: {}", list_or_none(&summary.connectors));
+    println!("\nSecrets ({}):", summary.secrets.len());
+    for secret in &summary.secrets {
+        let users = if secret.components.is_empty() {
+            "-".to_string()
+        } else {
+            secret.components.join(", ")
+        };
+        println!(
+            "  {} ({})  used by {users}",
+            secret.key,
+            if secret.required {
+                "required"
+            } else {
+                "optional"
+            }
+        );
+    }
+    let resources = &summary.resources;
+    println!("\nEstimated resources:");
+    println!(
+        "  runners: {} ({} replica(s))",
+        resources.runners, resources.replicas
+    );
+    println!("  wasm:    {}", human_bytes(resources.wasm_bytes));
+    println!("  messaging subjects: {}", resources.messaging_subjects);
+    println!(
+        "  telemetry: {}",
+        if resources.telemetry {
+            "required"
+        } else {
+            "optional"
+        }
+    );
+    if !summary.warnings.is_empty() {
+        println!("\nWarnings:");
+        for warning in &summary.warnings {
+            println!("  ! {warning}");
+        }
+    }
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
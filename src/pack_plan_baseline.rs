@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::pack_changelog::{Change, diff_maps};
+use crate::pack_diff::marker;
+use crate::pack_plan::PlanSummary;
+
+/// How an inferred plan differs from a baseline saved with `pack plan --summary --json`.
+/// Changes read baseline to current.
+#[derive(Debug, Serialize)]
+pub struct PlanDrift {
+    pub pack_id: String,
+    pub pack_version: String,
+    /// The `--baseline` file.
+    pub baseline: String,
+    pub components: Vec<PlanChange>,
+    pub connectors: Vec<PlanChange>,
+    pub secrets: Vec<PlanChange>,
+}
+
+/// One added, removed or changed entry, with what the baseline and the current plan say about it.
+#[derive(Debug, Serialize)]
+pub struct PlanChange {
+    pub name: String,
+    pub change: Change,
+    pub baseline: Option<String>,
+    pub current: Option<String>,
+}
+
+impl PlanDrift {
+    pub fn len(&self) -> usize {
+        self.components.len() + self.connectors.len() + self.secrets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Read a plan summary saved with `pack plan --summary --json`.
+pub fn load(path: &Path) -> Result<PlanSummary> {
+    let raw = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&raw).with_context(|| {
+        format!(
+            "{} is not a saved plan (write one with `pack plan <PACK> --summary --json`)",
+            path.display()
+        )
+    })
+}
+
+/// Compare components by name (version), connectors by name (kind and flow) and secrets by key
+/// (required or optional).
+pub fn drift(baseline: &PlanSummary, current: &PlanSummary, baseline_path: &Path) -> PlanDrift {
+    let components = |summary: &PlanSummary| {
+        summary
+            .components
+            .iter()
+            .map(|component| (component.name.clone(), component.version.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let connectors = |summary: &PlanSummary| {
+        summary
+            .connectors
+            .iter()
+            .map(|connector| split_connector(connector))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let secrets = |summary: &PlanSummary| {
+        summary
+            .secrets
+            .iter()
+            .map(|secret| {
+                let requirement = if secret.required {
+                    "required"
+                } else {
+                    "optional"
+                };
+                (secret.key.clone(), requirement.to_string())
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    PlanDrift {
+        pack_id: current.pack_id.clone(),
+        pack_version: current.pack_version.clone(),
+        baseline: baseline_path.display().to_string(),
+        components: changes(components(baseline), components(current)),
+        connectors: changes(connectors(baseline), connectors(current)),
+        secrets: changes(secrets(baseline), secrets(current)),
+    }
+}
+
+fn changes(
+    baseline: BTreeMap<String, String>,
+    current: BTreeMap<String, String>,
+) -> Vec<PlanChange> {
+    diff_maps(baseline, current)
+        .into_iter()
+        .map(|(name, change, baseline, current)| PlanChange {
+            name,
+            change,
+            baseline,
+            current,
+        })
+        .collect()
+}
+
+// Summaries render connectors as `name (kind) -> flow`; the name identifies the connector.
+fn split_connector(connector: &str) -> (String, String) {
+    match connector.split_once(" (") {
+        Some((name, rest)) => (name.to_string(), format!("({rest}")),
+        None => (connector.to_string(), String::new()),
+    }
+}
+
+/// `+` only in the current plan, `-` only in the baseline, `~` changed.
+pub fn render(drift: &PlanDrift) -> String {
+    let mut out = format!(
+        "{}@{} vs {}\n",
+        drift.pack_id, drift.pack_version, drift.baseline
+    );
+    if drift.is_empty() {
+        out.push_str("\nNo drift: components, connectors and secrets match the baseline.\n");
+        return out;
+    }
+    for (title, changes) in [
+        ("Components", &drift.components),
+        ("Connectors", &drift.connectors),
+        ("Secrets", &drift.secrets),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n{title}:\n"));
+        for change in changes {
+            let detail = match (&change.baseline, &change.current) {
+                (Some(old), Some(new)) => format!(" {old} → {new}"),
+                (old, new) => new
+                    .as_ref()
+                    .or(old.as_ref())
+                    .filter(|detail| !detail.is_empty())
+                    .map(|detail| format!(" {detail}"))
+                    .unwrap_or_default(),
+            };
+            out.push_str(&format!(
+                "  {} {}{detail}\n",
+                marker(change.change),
+                change.name
+            ));
+        }
+    }
+    out
+}
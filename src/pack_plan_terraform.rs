@@ -0,0 +1,442 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use greentic_pack::builder::PackManifest;
+use greentic_types::deployment::DeploymentPlan;
+use serde_json::Value as JsonValue;
+
+use crate::events;
+use crate::pack_preflight::secret_requirements;
+
+// The module only uses the built-in `terraform_data` resource, which needs Terraform 1.4.
+const REQUIRED_VERSION: &str = ">= 1.4";
+
+// Secret keys mapped to (required, components requesting them).
+type SecretUsers = BTreeMap<String, (bool, BTreeSet<String>)>;
+
+struct SecretVar {
+    key: String,
+    variable: String,
+    required: bool,
+    description: String,
+}
+
+/// Write a Terraform module for `plan` into `out`: variables for tenant, environment and every
+/// secret, and one `terraform_data` resource per component, runner, provider and channel. Teams
+/// swap those resources for their platform's own while keeping the inputs. Returns the files
+/// written.
+pub fn write_module(
+    manifest: &PackManifest,
+    plan: &DeploymentPlan,
+    out: &Path,
+) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(out).with_context(|| format!("failed to create {}", out.display()))?;
+    let secret_users = secret_requirements(manifest);
+    let secrets = secret_vars(plan, &secret_users);
+    let header = format!(
+        "# Generated by `greentic-dev pack plan --export terraform` from {}@{}.\n\
+         # Re-run the export after changing the pack instead of editing this file.\n\n",
+        plan.pack_id, plan.pack_version
+    );
+
+    let files = [
+        ("versions.tf", render_versions()),
+        ("variables.tf", render_variables(plan, &secrets)),
+        (
+            "main.tf",
+            render_main(manifest, plan, &secrets, &secret_users),
+        ),
+        ("outputs.tf", render_outputs(manifest, plan)),
+    ];
+    let mut written = Vec::with_capacity(files.len());
+    for (name, body) in files {
+        let path = out.join(name);
+        fs::write(&path, format!("{header}{body}"))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        events::artifact(&path);
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// Secrets from the plan plus any a component requests that the plan left out.
+fn secret_vars(plan: &DeploymentPlan, secret_users: &SecretUsers) -> Vec<SecretVar> {
+    let mut by_key: BTreeMap<String, (bool, Option<String>)> = BTreeMap::new();
+    for secret in &plan.secrets {
+        by_key.insert(
+            secret.key.as_str().to_string(),
+            (secret.required, secret.description.clone()),
+        );
+    }
+    for (key, (required, _)) in secret_users {
+        by_key.entry(key.clone()).or_insert((*required, None));
+    }
+
+    let mut names = Names::default();
+    by_key
+        .into_iter()
+        .map(|(key, (required, description))| {
+            let users = secret_users
+                .get(&key)
+                .map(|(_, users)| users.iter().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let description = match (description, users.is_empty()) {
+                (Some(description), _) => description,
+                (None, true) => format!("Secret {key}"),
+                (None, false) => format!("Secret {key} used by {}", users.join(", ")),
+            };
+            SecretVar {
+                variable: names.unique("secret", &key),
+                key,
+                required,
+                description,
+            }
+        })
+        .collect()
+}
+
+fn render_versions() -> String {
+    format!(
+        "terraform {{\n  required_version = {}\n}}\n",
+        hcl_string(REQUIRED_VERSION)
+    )
+}
+
+fn render_variables(plan: &DeploymentPlan, secrets: &[SecretVar]) -> String {
+    let mut blocks = vec![
+        block(
+            "variable \"tenant\"",
+            &[
+                ("description", hcl_string("Tenant the pack is deployed for")),
+                ("type", "string".to_string()),
+                ("default", hcl_string(&plan.tenant)),
+            ],
+        ),
+        block(
+            "variable \"environment\"",
+            &[
+                (
+                    "description",
+                    hcl_string("Environment the pack is deployed to"),
+                ),
+                ("type", "string".to_string()),
+                ("default", hcl_string(&plan.environment)),
+            ],
+        ),
+    ];
+    for secret in secrets {
+        let mut attrs = vec![
+            ("description", hcl_string(&secret.description)),
+            ("type", "string".to_string()),
+            ("sensitive", "true".to_string()),
+        ];
+        if !secret.required {
+            attrs.push(("default", "null".to_string()));
+        }
+        blocks.push(block(&format!("variable \"{}\"", secret.variable), &attrs));
+    }
+    blocks.join("\n")
+}
+
+fn render_main(
+    manifest: &PackManifest,
+    plan: &DeploymentPlan,
+    secrets: &[SecretVar],
+    secret_users: &SecretUsers,
+) -> String {
+    let mut locals = vec![
+        ("pack_id", hcl_string(&plan.pack_id)),
+        ("pack_version", hcl_string(&plan.pack_version.to_string())),
+    ];
+    let secret_map = secrets
+        .iter()
+        .map(|secret| (secret.key.as_str(), format!("var.{}", secret.variable)))
+        .collect::<Vec<_>>();
+    locals.push(("secrets", object(&secret_map, 2)));
+    let mut blocks = vec![block("locals", &locals)];
+
+    let mut names = Names::default();
+    for component in &manifest.components {
+        let used = secret_users
+            .iter()
+            .filter(|(_, (_, users))| users.contains(&component.name))
+            .map(|(key, _)| hcl_string(key))
+            .collect::<Vec<_>>();
+        blocks.push(resource(
+            &names.unique("component", &component.name),
+            &[
+                ("name", hcl_string(&component.name)),
+                ("version", hcl_string(&component.version.to_string())),
+                ("wasm", hcl_string(&component.file_wasm)),
+                (
+                    "digest",
+                    hcl_string(&format!("blake3:{}", component.hash_blake3)),
+                ),
+                ("secrets", format!("[{}]", used.join(", "))),
+            ],
+        ));
+    }
+    for runner in &plan.runners {
+        blocks.push(resource(
+            &names.unique("runner", &runner.name),
+            &[
+                ("name", hcl_string(&runner.name)),
+                ("replicas", runner.replicas.to_string()),
+                ("capabilities", json_value(&runner.capabilities)),
+            ],
+        ));
+    }
+    for oauth in &plan.oauth {
+        blocks.push(resource(
+            &names.unique("oauth", &oauth.provider_id),
+            &[
+                ("provider_id", hcl_string(&oauth.provider_id)),
+                ("logical_client_id", hcl_string(&oauth.logical_client_id)),
+                ("redirect_path", hcl_string(&oauth.redirect_path)),
+            ],
+        ));
+    }
+    for channel in &plan.channels {
+        blocks.push(resource(
+            &names.unique("channel", &channel.name),
+            &[
+                ("name", hcl_string(&channel.name)),
+                ("kind", hcl_string(&channel.kind)),
+                ("flow_id", hcl_string(&channel.flow_id)),
+                ("config", json_value(&channel.config)),
+            ],
+        ));
+    }
+    if let Some(messaging) = &plan.messaging {
+        let subjects = messaging
+            .subjects
+            .iter()
+            .map(|subject| hcl_string(&subject.name))
+            .collect::<Vec<_>>();
+        blocks.push(resource(
+            &names.unique("messaging", &messaging.logical_cluster),
+            &[
+                ("logical_cluster", hcl_string(&messaging.logical_cluster)),
+                ("subjects", format!("[{}]", subjects.join(", "))),
+            ],
+        ));
+    }
+    blocks.join("\n")
+}
+
+fn render_outputs(manifest: &PackManifest, plan: &DeploymentPlan) -> String {
+    let pack = block(
+        "output \"pack\"",
+        &[
+            ("description", hcl_string("Pack and deployment target")),
+            (
+                "value",
+                object(
+                    &[
+                        ("id", "local.pack_id".to_string()),
+                        ("version", "local.pack_version".to_string()),
+                        ("tenant", "var.tenant".to_string()),
+                        ("environment", "var.environment".to_string()),
+                    ],
+                    2,
+                ),
+            ),
+        ],
+    );
+    // Resource names are allocated in the same order as in main.tf.
+    let mut names = Names::default();
+    let components = manifest
+        .components
+        .iter()
+        .map(|component| {
+            let name = names.unique("component", &component.name);
+            (
+                component.name.as_str(),
+                format!("terraform_data.{name}.output"),
+            )
+        })
+        .collect::<Vec<_>>();
+    let runner_replicas = plan
+        .runners
+        .iter()
+        .map(|runner| runner.replicas)
+        .sum::<u32>();
+    let components = block(
+        "output \"components\"",
+        &[
+            (
+                "description",
+                hcl_string("Component inputs keyed by component name"),
+            ),
+            ("value", object(&components, 2)),
+        ],
+    );
+    let replicas = block(
+        "output \"runner_replicas\"",
+        &[
+            (
+                "description",
+                hcl_string("Total runner replicas in the plan"),
+            ),
+            ("value", runner_replicas.to_string()),
+        ],
+    );
+    [pack, components, replicas].join("\n")
+}
+
+// A `terraform_data` resource whose input also carries the deployment target.
+fn resource(name: &str, attrs: &[(&str, String)]) -> String {
+    let mut input = vec![
+        ("pack_id", "local.pack_id".to_string()),
+        ("tenant", "var.tenant".to_string()),
+        ("environment", "var.environment".to_string()),
+    ];
+    input.extend(attrs.iter().cloned());
+    block(
+        &format!("resource \"terraform_data\" \"{name}\""),
+        &[("input", object(&input, 2))],
+    )
+}
+
+// A block with `=` aligned the way `terraform fmt` does it.
+fn block(header: &str, attrs: &[(&str, String)]) -> String {
+    format!("{header} {}\n", object_body(attrs, 0))
+}
+
+fn object(attrs: &[(&str, String)], indent: usize) -> String {
+    if attrs.is_empty() {
+        return "{}".to_string();
+    }
+    object_body(attrs, indent)
+}
+
+fn object_body(attrs: &[(&str, String)], indent: usize) -> String {
+    let keys = attrs
+        .iter()
+        .map(|(key, _)| hcl_key(key))
+        .collect::<Vec<_>>();
+    // `terraform fmt` only aligns consecutive single-line attributes.
+    let mut lines = vec!["{".to_string()];
+    let mut start = 0;
+    while start < attrs.len() {
+        let mut end = start;
+        while end < attrs.len() && !attrs[end].1.contains('\n') {
+            end += 1;
+        }
+        let end = end.max(start + 1);
+        let width = keys[start..end].iter().map(String::len).max().unwrap_or(0);
+        for index in start..end {
+            let value = &attrs[index].1;
+            lines.push(format!(
+                "{:indent$}  {:width$} = {value}",
+                "",
+                keys[index],
+                indent = indent
+            ));
+        }
+        start = end;
+    }
+    lines.push(format!("{:indent$}}}", "", indent = indent));
+    lines.join("\n")
+}
+
+// Attribute names that are not plain identifiers (secret keys, component names) are quoted.
+fn hcl_key(key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        key.to_string()
+    } else {
+        hcl_string(key)
+    }
+}
+
+// JSON string escaping is valid HCL once template sequences are escaped too.
+fn hcl_string(value: &str) -> String {
+    serde_json::to_string(value)
+        .expect("strings serialize")
+        .replace("${", "$${")
+        .replace("%{", "%%{")
+}
+
+// Opaque plan values are passed through verbatim as JSON.
+fn json_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "null".to_string(),
+        value => format!("jsondecode({})", hcl_string(&value.to_string())),
+    }
+}
+
+// Allocates Terraform identifiers that stay unique within one file.
+#[derive(Default)]
+struct Names {
+    used: BTreeSet<String>,
+}
+
+impl Names {
+    fn unique(&mut self, prefix: &str, name: &str) -> String {
+        let mut base = format!("{prefix}_");
+        base.extend(name.chars().map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        }));
+        let mut candidate = base.clone();
+        let mut suffix = 2;
+        while !self.used.insert(candidate.clone()) {
+            candidate = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Names, block, hcl_key, hcl_string};
+
+    #[test]
+    fn strings_escape_template_sequences() {
+        assert_eq!(hcl_string("a\"b"), r#""a\"b""#);
+        assert_eq!(hcl_string("${var.x} %{if}"), r#""$${var.x} %%{if}""#);
+        assert_eq!(hcl_key("API_TOKEN"), "API_TOKEN");
+        assert_eq!(hcl_key("dev.greentic.echo"), r#""dev.greentic.echo""#);
+    }
+
+    #[test]
+    fn names_are_sanitized_and_unique() {
+        let mut names = Names::default();
+        assert_eq!(
+            names.unique("component", "dev.greentic.Echo"),
+            "component_dev_greentic_echo"
+        );
+        assert_eq!(
+            names.unique("component", "dev-greentic-echo"),
+            "component_dev_greentic_echo_2"
+        );
+    }
+
+    #[test]
+    fn blocks_align_attributes() {
+        let rendered = block(
+            "variable \"tenant\"",
+            &[
+                ("type", "string".to_string()),
+                ("default", "\"t\"".to_string()),
+            ],
+        );
+        assert_eq!(
+            rendered,
+            "variable \"tenant\" {\n  type    = string\n  default = \"t\"\n}\n"
+        );
+    }
+}
@@ -0,0 +1,516 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use greentic_pack::builder::PackManifest;
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::flow_contract;
+use crate::interrupt::PartialOutput;
+use crate::pack_cli::{PackRunPreflightArgs, PreflightPolicy};
+use crate::pack_encrypt;
+use crate::run_env;
+use crate::secrets_keyring::{self, SecretsBackend};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    pub(crate) name: &'static str,
+    pub(crate) status: CheckStatus,
+    pub(crate) detail: String,
+}
+
+impl Check {
+    pub(crate) fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PreflightReport {
+    pack: String,
+    entry: Option<String>,
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+/// `pack run --validate-only`: check everything a run needs without starting the runner.
+pub fn run(args: &PackRunPreflightArgs) -> Result<()> {
+    // `--env-file` variables, and with SECRETS_BACKEND=keyring keychain entries, are part of the
+    // run's environment, as they are for a real run.
+    let env_files = run_env::load(&args.env_files)?;
+    let keychain = SecretsBackend::from_env()? == SecretsBackend::Keyring;
+    let (checks, entry) = preflight(args, &|key| {
+        env_files
+            .get(key)
+            .cloned()
+            .or_else(|| std::env::var(key).ok())
+            .or_else(|| {
+                keychain
+                    .then(|| secrets_keyring::get(key).ok().flatten())
+                    .flatten()
+            })
+    })?;
+    let report = PreflightReport {
+        pack: args.pack.display().to_string(),
+        entry,
+        ok: checks.iter().all(|check| check.status != CheckStatus::Fail),
+        checks,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Pre-flight for {}", report.pack);
+        for check in &report.checks {
+            let marker = match check.status {
+                CheckStatus::Ok => "✓",
+                CheckStatus::Warn => "!",
+                CheckStatus::Fail => "✗",
+            };
+            println!("{marker} {:<8} {}", check.name, check.detail);
+        }
+    }
+    if !report.ok {
+        bail!("pack pre-flight failed for {}", report.pack);
+    }
+    Ok(())
+}
+
+fn preflight(
+    args: &PackRunPreflightArgs,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<(Vec<Check>, Option<String>)> {
+    let mut checks = Vec::new();
+    let policy = match PreflightPolicy::resolve(args.policy)? {
+        PreflightPolicy::Devok => SigningPolicy::DevOk,
+        PreflightPolicy::Strict => SigningPolicy::Strict,
+    };
+    let decrypted = match &args.decrypt_key {
+        Some(key) => {
+            let dir = tempfile::tempdir().context("failed to create a temp dir")?;
+            let cleanup = PartialOutput::new(dir.path());
+            let path = dir.path().join("pack.gtpack");
+            if let Err(err) = pack_encrypt::decrypt(&args.pack, key, &path) {
+                checks.push(Check::new("decrypt", CheckStatus::Fail, format!("{err:#}")));
+                return Ok((checks, args.entry.clone()));
+            }
+            checks.push(Check::new("decrypt", CheckStatus::Ok, "pack decrypted"));
+            Some((dir, cleanup, path))
+        }
+        None => None,
+    };
+    let pack = decrypted
+        .as_ref()
+        .map_or(args.pack.as_path(), |(_, _, path)| path);
+    let load = match open_pack(pack, policy) {
+        Ok(load) => load,
+        Err(err) => {
+            checks.push(Check::new("signing", CheckStatus::Fail, err.message));
+            return Ok((checks, args.entry.clone()));
+        }
+    };
+    let signature = if load.report.signature_ok {
+        "signature verified".to_string()
+    } else {
+        "unsigned or dev-signed pack accepted by dev policy".to_string()
+    };
+    checks.push(Check::new(
+        "signing",
+        if load.report.signature_ok {
+            CheckStatus::Ok
+        } else {
+            CheckStatus::Warn
+        },
+        signature,
+    ));
+    for warning in &load.report.warnings {
+        checks.push(Check::new("signing", CheckStatus::Warn, warning.clone()));
+    }
+
+    let entry = resolve_entry(&load.manifest, args.entry.as_deref());
+    checks.push(match &entry {
+        Ok(flow) => Check::new("entry", CheckStatus::Ok, format!("flow `{flow}`")),
+        Err(err) => Check::new("entry", CheckStatus::Fail, err.to_string()),
+    });
+
+    if let Some(input) = &args.input {
+        checks.push(input_check(&load, args.entry.as_deref(), input));
+    }
+
+    let secrets_file = match &args.secrets_env {
+        Some(path) => Some(read_env_file(path)?),
+        None => None,
+    };
+    let lookup = |key: &str| {
+        secrets_file
+            .as_ref()
+            .and_then(|vars| vars.get(key).cloned())
+            .or_else(|| env(key))
+    };
+    let source = args
+        .secrets_env
+        .as_ref()
+        .map(|path| format!("{} + environment", path.display()))
+        .unwrap_or_else(|| "environment".to_string());
+    checks.extend(secret_checks(&load.manifest, &source, &lookup));
+    checks.extend(mock_checks(env));
+    checks.push(network_check(&load.manifest, args.offline));
+
+    Ok((checks, entry.ok()))
+}
+
+pub(crate) fn resolve_entry(manifest: &PackManifest, requested: Option<&str>) -> Result<String> {
+    let flow = match requested {
+        Some(flow) => flow.to_string(),
+        None => manifest
+            .meta
+            .entry_flows
+            .first()
+            .cloned()
+            .or_else(|| manifest.flows.first().map(|flow| flow.id.clone()))
+            .context("pack declares no entry flows")?,
+    };
+    if !manifest.flows.iter().any(|entry| entry.id == flow) {
+        let known = manifest
+            .flows
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!("entry flow `{flow}` is not in the pack (flows: {known})");
+    }
+    Ok(flow)
+}
+
+fn input_check(load: &PackLoad, entry: Option<&str>, input: &str) -> Check {
+    let input = match serde_json::from_str::<JsonValue>(input) {
+        Ok(input) => input,
+        Err(err) => {
+            return Check::new(
+                "input",
+                CheckStatus::Fail,
+                format!("--input is not valid JSON: {err}"),
+            );
+        }
+    };
+    let contract = match flow_contract::from_pack(load, entry) {
+        Ok((_, contract)) => contract,
+        // The entry check already reports unresolvable flows.
+        Err(_) => return Check::new("input", CheckStatus::Ok, "valid JSON"),
+    };
+    if contract.input_schema.is_none() {
+        return Check::new(
+            "input",
+            CheckStatus::Ok,
+            "valid JSON (flow declares no input_schema)",
+        );
+    }
+    match contract.validate_input(&input) {
+        Ok(()) => Check::new("input", CheckStatus::Ok, "matches the flow's input_schema"),
+        Err(err) => Check::new("input", CheckStatus::Fail, err.to_string()),
+    }
+}
+
+// Secret requirements come from each component's `capabilities.host.secrets.required`.
+pub(crate) fn secret_requirements(
+    manifest: &PackManifest,
+) -> BTreeMap<String, (bool, BTreeSet<String>)> {
+    let mut secrets: BTreeMap<String, (bool, BTreeSet<String>)> = BTreeMap::new();
+    for component in &manifest.components {
+        let required = component
+            .capabilities
+            .as_ref()
+            .and_then(|caps| caps.pointer("/host/secrets/required"))
+            .and_then(JsonValue::as_array);
+        for requirement in required.into_iter().flatten() {
+            let Some(key) = requirement.get("key").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            let mandatory = requirement
+                .get("required")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(true);
+            let entry = secrets.entry(key.to_string()).or_default();
+            entry.0 |= mandatory;
+            entry.1.insert(component.name.clone());
+        }
+    }
+    secrets
+}
+
+pub(crate) fn secret_checks(
+    manifest: &PackManifest,
+    source: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Vec<Check> {
+    let requirements = secret_requirements(manifest);
+    if requirements.is_empty() {
+        return vec![Check::new(
+            "secrets",
+            CheckStatus::Ok,
+            "no secrets required",
+        )];
+    }
+    requirements
+        .into_iter()
+        .map(|(key, (mandatory, components))| {
+            let users = components.into_iter().collect::<Vec<_>>().join(", ");
+            if lookup(&key).is_some() {
+                Check::new(
+                    "secrets",
+                    CheckStatus::Ok,
+                    format!("{key} resolved from {source}"),
+                )
+            } else {
+                Check::new(
+                    "secrets",
+                    if mandatory {
+                        CheckStatus::Fail
+                    } else {
+                        CheckStatus::Warn
+                    },
+                    format!("{key} (needed by {users}) not found in {source}"),
+                )
+            }
+        })
+        .collect()
+}
+
+fn mock_checks(env: &dyn Fn(&str) -> Option<String>) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for key in ["MOCK_HTTP_PORT", "MOCK_NATS_PORT"] {
+        if let Some(value) = env(key) {
+            checks.push(match value.parse::<u16>() {
+                Ok(port) if port > 0 => {
+                    Check::new("mocks", CheckStatus::Ok, format!("{key}={port}"))
+                }
+                _ => Check::new(
+                    "mocks",
+                    CheckStatus::Fail,
+                    format!("{key}=`{value}` is not a valid port"),
+                ),
+            });
+        }
+    }
+    if let Some(value) = env("MOCK_NATS_DROP_RATE") {
+        checks.push(match value.parse::<f64>() {
+            Ok(rate) if (0.0..=1.0).contains(&rate) => Check::new(
+                "mocks",
+                CheckStatus::Ok,
+                format!("MOCK_NATS_DROP_RATE={rate}"),
+            ),
+            _ => Check::new(
+                "mocks",
+                CheckStatus::Fail,
+                format!("MOCK_NATS_DROP_RATE=`{value}` must be a number between 0 and 1"),
+            ),
+        });
+    }
+    if let Some(value) = env("MOCK_HTTP_FAIL_PATTERN") {
+        checks.push(Check::new(
+            "mocks",
+            if value.trim().is_empty() {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Ok
+            },
+            format!("MOCK_HTTP_FAIL_PATTERN=`{value}`"),
+        ));
+    }
+    if env("MOCK_VAULT_SEAL_AT_START").is_some() {
+        checks.push(Check::new(
+            "mocks",
+            CheckStatus::Warn,
+            "MOCK_VAULT_SEAL_AT_START is set; the vault mock starts sealed",
+        ));
+    }
+    if checks.is_empty() {
+        checks.push(Check::new(
+            "mocks",
+            CheckStatus::Ok,
+            "runner defaults (no MOCK_* overrides)",
+        ));
+    }
+    checks
+}
+
+fn network_check(manifest: &PackManifest, offline: bool) -> Check {
+    let networked = manifest
+        .components
+        .iter()
+        .filter(|component| {
+            component
+                .capabilities
+                .as_ref()
+                .and_then(|caps| caps.pointer("/host/http"))
+                .is_some_and(|http| !http.is_null())
+        })
+        .map(|component| component.name.as_str())
+        .collect::<Vec<_>>();
+    match (offline, networked.is_empty()) {
+        (_, true) => Check::new(
+            "network",
+            CheckStatus::Ok,
+            "no component requests HTTP access",
+        ),
+        (true, false) => Check::new(
+            "network",
+            CheckStatus::Warn,
+            format!(
+                "offline run; HTTP calls from {} will only reach the runner mocks",
+                networked.join(", ")
+            ),
+        ),
+        (false, false) => Check::new(
+            "network",
+            CheckStatus::Ok,
+            format!("HTTP allowed for {}", networked.join(", ")),
+        ),
+    }
+}
+
+pub(crate) fn read_env_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("failed to read env file {}", path.display()))?;
+    Ok(parse_env_file(&raw))
+}
+
+fn parse_env_file(raw: &str) -> BTreeMap<String, String> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CheckStatus, mock_checks, network_check, parse_env_file, resolve_entry, secret_checks,
+    };
+    use greentic_pack::builder::{ComponentEntry, FlowEntry, PACK_VERSION, PackManifest, PackMeta};
+    use serde_json::json;
+
+    fn manifest(capabilities: serde_json::Value) -> PackManifest {
+        PackManifest {
+            meta: PackMeta {
+                pack_version: PACK_VERSION,
+                pack_id: "dev.local.preflight".to_string(),
+                version: semver::Version::new(0, 1, 0),
+                name: "preflight".to_string(),
+                kind: None,
+                description: None,
+                authors: Vec::new(),
+                license: None,
+                homepage: None,
+                support: None,
+                vendor: None,
+                imports: Vec::new(),
+                entry_flows: vec!["main".to_string()],
+                created_at_utc: "2024-01-01T00:00:00Z".to_string(),
+                events: None,
+                repo: None,
+                messaging: None,
+                interfaces: Vec::new(),
+                annotations: Default::default(),
+                distribution: None,
+                components: Vec::new(),
+            },
+            flows: vec![FlowEntry {
+                id: "main".to_string(),
+                kind: "messaging".to_string(),
+                entry: "start".to_string(),
+                file_yaml: "flows/main/flow.ygtc".to_string(),
+                file_json: "flows/main/flow.json".to_string(),
+                hash_blake3: String::new(),
+            }],
+            components: vec![ComponentEntry {
+                name: "acme.weather".to_string(),
+                version: semver::Version::new(0, 1, 0),
+                file_wasm: "components/acme.weather.wasm".to_string(),
+                hash_blake3: String::new(),
+                schema_file: None,
+                manifest_file: None,
+                world: None,
+                capabilities: Some(capabilities),
+            }],
+            distribution: None,
+            component_descriptors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn entry_defaults_to_first_entry_flow() {
+        let manifest = manifest(json!({}));
+        assert_eq!(resolve_entry(&manifest, None).unwrap(), "main");
+        let err = resolve_entry(&manifest, Some("other")).unwrap_err();
+        assert!(err.to_string().contains("flows: main"));
+    }
+
+    #[test]
+    fn missing_required_secret_fails() {
+        let manifest = manifest(json!({
+            "host": { "secrets": { "required": [
+                { "key": "WEATHER_API_KEY" },
+                { "key": "OPTIONAL_TOKEN", "required": false }
+            ] } }
+        }));
+        let checks = secret_checks(&manifest, "environment", &|_| None);
+        let statuses = checks.iter().map(|c| c.status).collect::<Vec<_>>();
+        assert_eq!(statuses, vec![CheckStatus::Warn, CheckStatus::Fail]);
+
+        let checks = secret_checks(&manifest, "environment", &|key| {
+            (key == "WEATHER_API_KEY").then(|| "x".to_string())
+        });
+        assert_eq!(checks[1].status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn invalid_mock_settings_fail() {
+        let checks = mock_checks(&|key| match key {
+            "MOCK_HTTP_PORT" => Some("http".to_string()),
+            "MOCK_NATS_DROP_RATE" => Some("0.25".to_string()),
+            _ => None,
+        });
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert_eq!(checks[1].status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn offline_runs_warn_about_http_components() {
+        let manifest = manifest(json!({ "host": { "http": { "client": true } } }));
+        assert_eq!(network_check(&manifest, true).status, CheckStatus::Warn);
+        assert_eq!(network_check(&manifest, false).status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn env_file_parsing_handles_quotes_and_comments() {
+        let vars = parse_env_file("# secrets\nexport API_KEY=\"abc\"\nTOKEN = xyz\n\n");
+        assert_eq!(vars["API_KEY"], "abc");
+        assert_eq!(vars["TOKEN"], "xyz");
+    }
+}
@@ -0,0 +1,504 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::builder::{PackMeta, Provenance};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use x509_parser::pem::parse_x509_pem;
+
+use crate::interrupt::PartialOutput;
+use crate::pack_cli::PackProvenanceArgs;
+use crate::pack_preflight::{Check, CheckStatus};
+
+const PROVENANCE_PATH: &str = "provenance.json";
+const SIGNATURE_PATH: &str = "signatures/pack.sig";
+const SIGNATURE_CHAIN_PATH: &str = "signatures/chain.pem";
+const DEV_CERT_CN: &str = "greentic-dev-local";
+
+#[derive(Debug, Serialize)]
+struct ProvenanceReport {
+    pack: String,
+    pack_id: String,
+    version: String,
+    ok: bool,
+    provenance: Option<Provenance>,
+    signature: Option<SignatureIdentity>,
+    checks: Vec<Check>,
+}
+
+/// Who signed the pack, from `signatures/pack.sig` and the first certificate of the chain.
+#[derive(Debug, Serialize)]
+struct SignatureIdentity {
+    algorithm: Option<String>,
+    key_fingerprint: Option<String>,
+    signed_at_utc: Option<String>,
+    subject: Option<String>,
+    issuer: Option<String>,
+    not_before: Option<String>,
+    not_after: Option<String>,
+    chain_length: usize,
+    /// Self-signed development certificate (`greentic-dev-local`).
+    dev_key: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SignatureFile {
+    alg: Option<String>,
+    signed_at_utc: Option<String>,
+    key_fingerprint: Option<String>,
+}
+
+/// `pack provenance`: show the pack's provenance next to its signing identity, check that the
+/// provenance commit exists, and flag provenance that disagrees with the pack metadata.
+pub fn run(args: &PackProvenanceArgs) -> Result<()> {
+    let load = open_pack(&args.pack, SigningPolicy::DevOk)
+        .map_err(|err| anyhow!("pack verification failed: {}", err.message))?;
+    let provenance = match load.files.get(PROVENANCE_PATH) {
+        Some(bytes) => Some(
+            serde_json::from_slice::<Provenance>(bytes)
+                .context("provenance.json in the pack is invalid")?,
+        ),
+        None => None,
+    };
+    let signature = signature_identity(&load)?;
+
+    let mut checks = vec![signature_check(&load, signature.as_ref())];
+    checks.push(match &provenance {
+        Some(provenance) => Check::new(
+            "provenance",
+            CheckStatus::Ok,
+            format!("built by {}", provenance.builder),
+        ),
+        None => Check::new(
+            "provenance",
+            CheckStatus::Warn,
+            "the pack has no provenance.json",
+        ),
+    });
+    if let Some(provenance) = &provenance {
+        checks.push(commit_check(provenance, args));
+        checks.extend(consistency_checks(
+            &load.manifest.meta,
+            provenance,
+            signature.as_ref(),
+        ));
+    }
+
+    let meta = &load.manifest.meta;
+    let report = ProvenanceReport {
+        pack: args.pack.display().to_string(),
+        pack_id: meta.pack_id.clone(),
+        version: meta.version.to_string(),
+        ok: checks.iter().all(|check| check.status != CheckStatus::Fail),
+        provenance,
+        signature,
+        checks,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", render(&report));
+    }
+    if !report.ok {
+        bail!("provenance checks failed for {}", report.pack);
+    }
+    Ok(())
+}
+
+fn render(report: &ProvenanceReport) -> String {
+    let mut out = format!("{} {} ({})\n", report.pack_id, report.version, report.pack);
+    let field = |out: &mut String, label: &str, value: Option<&str>| {
+        out.push_str(&format!("  {label:<13} {}\n", value.unwrap_or("–")));
+    };
+    out.push_str("Provenance\n");
+    match &report.provenance {
+        Some(provenance) => {
+            field(&mut out, "builder", Some(&provenance.builder));
+            field(&mut out, "git repo", provenance.git_repo.as_deref());
+            field(&mut out, "git commit", provenance.git_commit.as_deref());
+            field(&mut out, "toolchain", provenance.toolchain.as_deref());
+            field(&mut out, "host", provenance.host.as_deref());
+            field(&mut out, "built at", Some(&provenance.built_at_utc));
+            if let Some(notes) = &provenance.notes {
+                field(&mut out, "notes", Some(notes));
+            }
+        }
+        None => out.push_str("  (none)\n"),
+    }
+    out.push_str("Signature\n");
+    match &report.signature {
+        Some(signature) => {
+            field(&mut out, "algorithm", signature.algorithm.as_deref());
+            field(&mut out, "key", signature.key_fingerprint.as_deref());
+            field(&mut out, "subject", signature.subject.as_deref());
+            field(&mut out, "issuer", signature.issuer.as_deref());
+            let validity = match (&signature.not_before, &signature.not_after) {
+                (Some(from), Some(to)) => Some(format!("{from} to {to}")),
+                _ => None,
+            };
+            field(&mut out, "valid", validity.as_deref());
+            field(&mut out, "signed at", signature.signed_at_utc.as_deref());
+            let chain = format!(
+                "{} certificate(s){}",
+                signature.chain_length,
+                if signature.dev_key {
+                    ", development key"
+                } else {
+                    ""
+                }
+            );
+            field(&mut out, "chain", Some(&chain));
+        }
+        None => out.push_str("  (unsigned)\n"),
+    }
+    out.push_str("Checks\n");
+    for check in &report.checks {
+        let marker = match check.status {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        };
+        out.push_str(&format!("{marker} {:<11} {}\n", check.name, check.detail));
+    }
+    out
+}
+
+fn signature_identity(load: &PackLoad) -> Result<Option<SignatureIdentity>> {
+    let (Some(signature), Some(chain)) = (
+        load.files.get(SIGNATURE_PATH),
+        load.files.get(SIGNATURE_CHAIN_PATH),
+    ) else {
+        return Ok(None);
+    };
+    let signature: SignatureFile = serde_json::from_slice(signature).unwrap_or_default();
+
+    let mut identity = SignatureIdentity {
+        algorithm: signature.alg,
+        key_fingerprint: signature.key_fingerprint,
+        signed_at_utc: signature.signed_at_utc,
+        subject: None,
+        issuer: None,
+        not_before: None,
+        not_after: None,
+        chain_length: 0,
+        dev_key: false,
+    };
+    let mut rest = chain.as_slice();
+    while let Ok((remaining, pem)) = parse_x509_pem(rest) {
+        rest = remaining;
+        identity.chain_length += 1;
+        if identity.chain_length > 1 {
+            continue;
+        }
+        let cert = pem
+            .parse_x509()
+            .map_err(|err| anyhow!("signing certificate is invalid: {err}"))?;
+        let subject = cert.subject();
+        identity.subject = Some(subject.to_string());
+        identity.issuer = Some(cert.issuer().to_string());
+        identity.not_before = rfc3339(cert.validity().not_before.to_datetime());
+        identity.not_after = rfc3339(cert.validity().not_after.to_datetime());
+        identity.dev_key = subject == cert.issuer()
+            && subject
+                .iter_common_name()
+                .filter_map(|cn| cn.as_str().ok())
+                .any(|cn| cn == DEV_CERT_CN);
+    }
+    Ok(Some(identity))
+}
+
+fn rfc3339(at: OffsetDateTime) -> Option<String> {
+    at.format(&Rfc3339).ok()
+}
+
+fn signature_check(load: &PackLoad, signature: Option<&SignatureIdentity>) -> Check {
+    let Some(signature) = signature else {
+        return Check::new("signature", CheckStatus::Warn, "the pack is not signed");
+    };
+    if !load.report.signature_ok {
+        return Check::new(
+            "signature",
+            CheckStatus::Fail,
+            "the signature does not verify",
+        );
+    }
+    let key = signature
+        .key_fingerprint
+        .as_deref()
+        .unwrap_or("unknown key");
+    if signature.dev_key {
+        Check::new(
+            "signature",
+            CheckStatus::Warn,
+            format!("verifies, but with the development key {key}"),
+        )
+    } else {
+        Check::new(
+            "signature",
+            CheckStatus::Ok,
+            format!(
+                "verifies with key {key} ({})",
+                signature.subject.as_deref().unwrap_or("no subject")
+            ),
+        )
+    }
+}
+
+// Look the provenance commit up in `--repo`, or fetch it from the provenance repository unless
+// `--offline`.
+fn commit_check(provenance: &Provenance, args: &PackProvenanceArgs) -> Check {
+    let Some(commit) = &provenance.git_commit else {
+        return Check::new(
+            "commit",
+            CheckStatus::Warn,
+            "no git commit recorded; the pack cannot be traced to a source revision",
+        );
+    };
+    if let Some(repo) = &args.repo {
+        return match git(repo, &["cat-file", "-e", &format!("{commit}^{{commit}}")]) {
+            Ok(()) => Check::new(
+                "commit",
+                CheckStatus::Ok,
+                format!("{commit} exists in {}", repo.display()),
+            ),
+            Err(err) => Check::new(
+                "commit",
+                CheckStatus::Fail,
+                format!("{commit} not found in {}: {err:#}", repo.display()),
+            ),
+        };
+    }
+    let Some(remote) = &provenance.git_repo else {
+        return Check::new(
+            "commit",
+            CheckStatus::Warn,
+            format!("{commit} not verified: no git repository recorded (pass --repo)"),
+        );
+    };
+    if args.offline {
+        return Check::new(
+            "commit",
+            CheckStatus::Warn,
+            format!("{commit} not verified (offline)"),
+        );
+    }
+    match fetch_commit(remote, commit) {
+        Ok(()) => Check::new(
+            "commit",
+            CheckStatus::Ok,
+            format!("{commit} exists in {remote}"),
+        ),
+        Err(err) => Check::new(
+            "commit",
+            CheckStatus::Fail,
+            format!("{commit} could not be fetched from {remote}: {err:#}"),
+        ),
+    }
+}
+
+// Fetch just the commit into a scratch repository; servers that refuse fetching a bare commit id
+// make this fail like a missing commit.
+fn fetch_commit(remote: &str, commit: &str) -> Result<()> {
+    let scratch = tempfile::tempdir().context("failed to create a scratch repository")?;
+    let _partial = PartialOutput::new(scratch.path());
+    git(scratch.path(), &["init", "--bare", "--quiet"])?;
+    git(
+        scratch.path(),
+        &["fetch", "--quiet", "--depth=1", "--no-tags", remote, commit],
+    )
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "git {} failed{}",
+            args[0],
+            match stderr.trim() {
+                "" => String::new(),
+                stderr => format!(": {stderr}"),
+            }
+        );
+    }
+    Ok(())
+}
+
+// Provenance that contradicts the pack metadata or the signature.
+fn consistency_checks(
+    meta: &PackMeta,
+    provenance: &Provenance,
+    signature: Option<&SignatureIdentity>,
+) -> Vec<Check> {
+    let mut mismatches = Vec::new();
+    let built_at = parse_time(&provenance.built_at_utc);
+    if let (Some(built_at), Some(created_at)) = (built_at, parse_time(&meta.created_at_utc))
+        && created_at > built_at
+    {
+        mismatches.push(format!(
+            "metadata created_at_utc {} is after the build at {}",
+            meta.created_at_utc, provenance.built_at_utc
+        ));
+    }
+    if let (Some(built_at), Some(signed_at)) = (
+        built_at,
+        signature
+            .and_then(|signature| signature.signed_at_utc.as_deref())
+            .and_then(parse_time),
+    ) && signed_at < built_at
+    {
+        mismatches.push(format!(
+            "signed at {} before the build at {}",
+            signature
+                .and_then(|signature| signature.signed_at_utc.as_deref())
+                .unwrap_or_default(),
+            provenance.built_at_utc
+        ));
+    }
+    if let Some(git_repo) = &provenance.git_repo {
+        for (label, declared) in declared_repositories(meta) {
+            if repo_key(&declared) != repo_key(git_repo) {
+                mismatches.push(format!(
+                    "metadata {label} {declared} differs from the provenance repository {git_repo}"
+                ));
+            }
+        }
+    }
+
+    if mismatches.is_empty() {
+        return vec![Check::new(
+            "consistency",
+            CheckStatus::Ok,
+            "provenance agrees with the pack metadata and signature",
+        )];
+    }
+    mismatches
+        .into_iter()
+        .map(|detail| Check::new("consistency", CheckStatus::Warn, detail))
+        .collect()
+}
+
+fn parse_time(value: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(value, &Rfc3339).ok()
+}
+
+// Repository URLs the metadata declares: a `repository` annotation, and the homepage when it is
+// on the same host as the provenance repository (a project page elsewhere is not a mismatch).
+fn declared_repositories(meta: &PackMeta) -> Vec<(&'static str, String)> {
+    let mut declared = Vec::new();
+    if let Some(JsonValue::String(repository)) = meta.annotations.get("repository") {
+        declared.push(("annotation `repository`", repository.clone()));
+    }
+    if let Some(homepage) = &meta.homepage {
+        declared.push(("homepage", homepage.clone()));
+    }
+    declared
+}
+
+// `host/owner/name`, so https, ssh and scp-style URLs of one repository compare equal.
+fn repo_key(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url,
+    };
+    let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+    rest.replacen(':', "/", 1).to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{consistency_checks, repo_key};
+    use crate::pack_preflight::CheckStatus;
+    use greentic_pack::builder::{PackMeta, Provenance};
+
+    fn meta(created_at: &str, homepage: Option<&str>) -> PackMeta {
+        serde_json::from_value(serde_json::json!({
+            "pack_version": 1,
+            "pack_id": "dev.local.demo",
+            "version": "0.1.0",
+            "name": "demo",
+            "entry_flows": ["main"],
+            "created_at_utc": created_at,
+            "homepage": homepage,
+        }))
+        .unwrap()
+    }
+
+    fn provenance(built_at: &str, repo: Option<&str>) -> Provenance {
+        Provenance {
+            builder: "greentic-dev 0.5.2".to_string(),
+            git_commit: Some("abc123".to_string()),
+            git_repo: repo.map(str::to_string),
+            toolchain: None,
+            built_at_utc: built_at.to_string(),
+            host: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn repository_urls_compare_across_schemes() {
+        assert_eq!(
+            repo_key("https://github.com/Greentic-AI/demo.git"),
+            repo_key("git@github.com:greentic-ai/demo")
+        );
+        assert_eq!(
+            repo_key("ssh://git@github.com/greentic-ai/demo/"),
+            "github.com/greentic-ai/demo"
+        );
+    }
+
+    #[test]
+    fn matching_provenance_passes() {
+        let checks = consistency_checks(
+            &meta(
+                "2026-01-01T00:00:00Z",
+                Some("https://github.com/greentic-ai/demo"),
+            ),
+            &provenance(
+                "2026-01-02T00:00:00Z",
+                Some("git@github.com:greentic-ai/demo.git"),
+            ),
+            None,
+        );
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn mismatches_are_flagged() {
+        let checks = consistency_checks(
+            &meta(
+                "2026-03-01T00:00:00Z",
+                Some("https://github.com/greentic-ai/other"),
+            ),
+            &provenance(
+                "2026-01-02T00:00:00Z",
+                Some("https://github.com/greentic-ai/demo"),
+            ),
+            None,
+        );
+        let details = checks
+            .iter()
+            .map(|check| check.detail.as_str())
+            .collect::<Vec<_>>();
+        assert!(
+            checks.iter().all(|check| check.status == CheckStatus::Warn),
+            "{details:?}"
+        );
+        assert!(details[0].contains("is after the build"), "{details:?}");
+        assert!(details[1].contains("homepage"), "{details:?}");
+    }
+}
@@ -0,0 +1,218 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use greentic_types::provider::ProviderDecl;
+use greentic_types::{decode_pack_manifest, encode_pack_manifest};
+use serde_json::Value as JsonValue;
+
+use crate::component_manifest;
+use crate::events;
+
+/// Take `--no-introspect` off a `pack new-provider` invocation. Returns whether the provider
+/// should be introspected afterwards, and the arguments left for greentic-pack.
+pub fn take_no_introspect(args: &[OsString]) -> (bool, Vec<OsString>) {
+    if args.first().and_then(|arg| arg.to_str()) != Some("new-provider") {
+        return (false, args.to_vec());
+    }
+    let rest = args
+        .iter()
+        .filter(|arg| *arg != "--no-introspect")
+        .cloned()
+        .collect::<Vec<_>>();
+    (rest.len() == args.len(), rest)
+}
+
+/// After `pack new-provider` added a provider to a `manifest.cbor`, fill its empty `ops` and
+/// `capabilities` from the runtime component's manifest: the operation names, and the host
+/// capabilities it requests. Values already set are left alone. Signed `.gtpack`s are not
+/// rewritten, since that would break their signature.
+pub fn introspect_new_provider(args: &[OsString]) -> Result<()> {
+    let (Some(pack), Some(id)) = (arg_value(args, "--pack"), arg_value(args, "--id")) else {
+        return Ok(());
+    };
+    let pack = PathBuf::from(pack);
+    if pack.extension().and_then(|ext| ext.to_str()) != Some("cbor") {
+        events::warn(format_args!(
+            "not introspecting provider {id}: {} is not a manifest.cbor",
+            pack.display()
+        ));
+        return Ok(());
+    }
+    let bytes = fs::read(&pack).with_context(|| format!("failed to read {}", pack.display()))?;
+    let mut manifest = decode_pack_manifest(&bytes)
+        .with_context(|| format!("failed to decode {}", pack.display()))?;
+    let components = manifest
+        .components
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    let base = pack.parent().unwrap_or(Path::new("."));
+    let decl = manifest
+        .provider_extension_inline_mut()
+        .and_then(|inline| {
+            inline
+                .providers
+                .iter_mut()
+                .find(|provider| provider.provider_type == id)
+        })
+        .ok_or_else(|| anyhow!("{} has no provider {id}", pack.display()))?;
+    if !decl.ops.is_empty() && !decl.capabilities.is_empty() {
+        return Ok(());
+    }
+    let component_ref = decl.runtime.component_ref.clone();
+    let Some(component) = find_component(&component_ref, &components, base)? else {
+        events::warn(format_args!(
+            "no manifest found for runtime component {component_ref}; ops and capabilities left empty"
+        ));
+        return Ok(());
+    };
+    if !fill_from_component(decl, &component) {
+        return Ok(());
+    }
+    println!(
+        "Introspected {component_ref}: {} op(s), {} capability(ies) for provider {id}",
+        decl.ops.len(),
+        decl.capabilities.len()
+    );
+    let encoded = encode_pack_manifest(&manifest)
+        .with_context(|| format!("failed to encode {}", pack.display()))?;
+    fs::write(&pack, encoded).with_context(|| format!("failed to write {}", pack.display()))
+}
+
+// The runtime component's manifest: a component of the pack with that id, or a component
+// directory (or manifest file) relative to the pack manifest.
+fn find_component(
+    component_ref: &str,
+    components: &[JsonValue],
+    base: &Path,
+) -> Result<Option<JsonValue>> {
+    if let Some(component) = components
+        .iter()
+        .find(|component| component["id"].as_str() == Some(component_ref))
+    {
+        return Ok(Some(component.clone()));
+    }
+    let path = component_manifest::manifest_path(&base.join(component_ref));
+    if !path.is_file() {
+        return Ok(None);
+    }
+    component_manifest::read_manifest(&path).map(Some)
+}
+
+/// Fill the empty `ops` and `capabilities` of `decl` from a component manifest. Returns whether
+/// anything changed.
+pub fn fill_from_component(decl: &mut ProviderDecl, component: &JsonValue) -> bool {
+    let mut changed = false;
+    if decl.ops.is_empty() {
+        decl.ops = component["operations"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|operation| operation["name"].as_str().map(str::to_string))
+            .collect();
+        changed |= !decl.ops.is_empty();
+    }
+    if decl.capabilities.is_empty() {
+        decl.capabilities = host_capabilities(&component["capabilities"]["host"]);
+        changed |= !decl.capabilities.is_empty();
+    }
+    changed
+}
+
+// Host capabilities as dotted names: `http.client` for `{"http": {"client": true}}`, and the bare
+// surface name for surfaces without flags (`secrets`, `state`).
+fn host_capabilities(host: &JsonValue) -> Vec<String> {
+    let mut capabilities = Vec::new();
+    for (surface, value) in host.as_object().into_iter().flatten() {
+        match value {
+            JsonValue::Null | JsonValue::Bool(false) => {}
+            JsonValue::Object(fields) => {
+                let flags = fields
+                    .iter()
+                    .filter(|(_, value)| value.as_bool() == Some(true))
+                    .map(|(flag, _)| format!("{surface}.{flag}"))
+                    .collect::<Vec<_>>();
+                if flags.is_empty() {
+                    capabilities.push(surface.clone());
+                } else {
+                    capabilities.extend(flags);
+                }
+            }
+            _ => capabilities.push(surface.clone()),
+        }
+    }
+    capabilities
+}
+
+fn arg_value(args: &[OsString], name: &str) -> Option<String> {
+    let mut iter = args.iter().filter_map(|arg| arg.to_str());
+    while let Some(arg) = iter.next() {
+        if arg == name {
+            return iter.next().map(str::to_string);
+        }
+        if let Some(value) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_from_component, take_no_introspect};
+    use greentic_types::provider::{ProviderDecl, ProviderRuntimeRef};
+    use serde_json::json;
+    use std::ffi::OsString;
+
+    fn decl(ops: &[&str]) -> ProviderDecl {
+        ProviderDecl {
+            provider_type: "dev.local.hello.provider".to_string(),
+            capabilities: Vec::new(),
+            ops: ops.iter().map(|op| op.to_string()).collect(),
+            config_schema_ref: "schemas/config.json".to_string(),
+            state_schema_ref: None,
+            runtime: ProviderRuntimeRef {
+                component_ref: "components/hello-world".to_string(),
+                export: "greentic_provider".to_string(),
+                world: "greentic:provider/runtime".to_string(),
+            },
+            docs_ref: None,
+        }
+    }
+
+    #[test]
+    fn empty_ops_and_capabilities_come_from_the_component() {
+        let component = json!({
+            "operations": [{"name": "send"}, {"name": "receive"}],
+            "capabilities": {"host": {
+                "http": {"client": true, "server": false},
+                "secrets": {"required": [{"key": "TOKEN"}]},
+                "state": null
+            }}
+        });
+        let mut empty = decl(&[]);
+        assert!(fill_from_component(&mut empty, &component));
+        assert_eq!(empty.ops, ["send", "receive"]);
+        assert_eq!(empty.capabilities, ["http.client", "secrets"]);
+
+        let mut declared = decl(&["send"]);
+        fill_from_component(&mut declared, &component);
+        assert_eq!(declared.ops, ["send"]);
+    }
+
+    #[test]
+    fn no_introspect_is_taken_off_new_provider() {
+        let args = |values: &[&str]| values.iter().map(OsString::from).collect::<Vec<_>>();
+        let (introspect, rest) =
+            take_no_introspect(&args(&["new-provider", "--no-introspect", "--id", "x"]));
+        assert!(!introspect);
+        assert_eq!(rest, args(&["new-provider", "--id", "x"]));
+        assert!(take_no_introspect(&args(&["new-provider", "--id", "x"])).0);
+        assert!(!take_no_introspect(&args(&["inspect", "x.gtpack"])).0);
+    }
+}
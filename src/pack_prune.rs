@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::component_resolver::ResolvedNode;
+use crate::flow_graph::{EdgeTarget, FlowGraph};
+
+/// A component left out of the pack by `pack build --prune`: only nodes no entry flow can reach
+/// use it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrunedComponent {
+    pub name: String,
+    pub version: String,
+    /// The unreachable nodes that use it.
+    pub nodes: Vec<String>,
+}
+
+/// `(flow id, node id)` of every node reachable from the entry flows. A flow is entered at its
+/// start node (or its first node when it names none) and at each declared entrypoint; routing and
+/// `flow.call`s to other flows of the pack are followed. Calls into imported packs are not.
+pub fn reachable_nodes(
+    flows: &[(FlowGraph, &JsonValue)],
+    entry_flows: &[String],
+) -> BTreeSet<(String, String)> {
+    let by_id = flows
+        .iter()
+        .map(|(graph, doc)| (graph.id.as_str(), (graph, *doc)))
+        .collect::<BTreeMap<_, _>>();
+    let mut pending = Vec::new();
+    for flow in entry_flows {
+        let Some((graph, doc)) = by_id.get(flow.as_str()) else {
+            continue;
+        };
+        pending.extend(start_node(graph).map(|node| (flow.clone(), node)));
+        pending.extend(
+            doc.get("entrypoints")
+                .and_then(JsonValue::as_object)
+                .into_iter()
+                .flat_map(|declared| declared.values())
+                .filter_map(JsonValue::as_str)
+                .map(|node| (flow.clone(), node.to_string())),
+        );
+    }
+
+    let mut seen = BTreeSet::new();
+    while let Some((flow, node)) = pending.pop() {
+        let Some((graph, doc)) = by_id.get(flow.as_str()) else {
+            continue;
+        };
+        if !seen.insert((flow.clone(), node.clone())) {
+            continue;
+        }
+        pending.extend(graph.edges.iter().filter_map(|edge| match &edge.to {
+            EdgeTarget::Node(to) if edge.from == node => Some((flow.clone(), to.clone())),
+            _ => None,
+        }));
+        if let Some(call) = doc.pointer(&format!("/nodes/{node}/flow.call"))
+            && call.get("pack").is_none()
+            && let Some(target) = call.get("flow").and_then(JsonValue::as_str)
+            && let Some((target_graph, target_doc)) = by_id.get(target)
+        {
+            let entry = call
+                .get("entrypoint")
+                .and_then(JsonValue::as_str)
+                .filter(|entry| *entry != "default");
+            let node = match entry {
+                Some(entry) => target_doc
+                    .pointer(&format!("/entrypoints/{entry}"))
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string),
+                None => start_node(target_graph),
+            };
+            pending.extend(node.map(|node| (target.to_string(), node)));
+        }
+    }
+    seen
+}
+
+fn start_node(graph: &FlowGraph) -> Option<String> {
+    graph
+        .start
+        .clone()
+        .or_else(|| graph.nodes.first().map(|node| node.id.clone()))
+}
+
+/// Components that no reachable node uses, with the nodes that do use them. Each node comes with
+/// the label it is listed under and whether it is reachable.
+pub fn unused_components<'a>(
+    nodes: impl IntoIterator<Item = (String, &'a ResolvedNode, bool)>,
+) -> Vec<PrunedComponent> {
+    let key = |node: &ResolvedNode| {
+        (
+            node.component.name.clone(),
+            node.component.version.to_string(),
+        )
+    };
+    let nodes = nodes.into_iter().collect::<Vec<_>>();
+    let kept = nodes
+        .iter()
+        .filter(|(_, _, reachable)| *reachable)
+        .map(|(_, node, _)| key(node))
+        .collect::<BTreeSet<_>>();
+    let mut pruned = BTreeMap::<_, Vec<String>>::new();
+    for (label, node, _) in nodes {
+        if !kept.contains(&key(node)) {
+            pruned.entry(key(node)).or_default().push(label);
+        }
+    }
+    pruned
+        .into_iter()
+        .map(|((name, version), nodes)| PrunedComponent {
+            name,
+            version,
+            nodes,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow_graph;
+
+    fn flow(raw: &str) -> (FlowGraph, JsonValue) {
+        (
+            flow_graph::parse(raw).unwrap(),
+            serde_yaml_bw::from_str(raw).unwrap(),
+        )
+    }
+
+    #[test]
+    fn follows_routing_entrypoints_and_local_flow_calls() {
+        let main = flow(
+            r#"
+id: main
+nodes:
+  greet:
+    hello.component: {}
+    routing:
+      - to: handoff
+  handoff:
+    flow.call: { flow: billing, entrypoint: refund }
+  orphan:
+    old.component: {}
+"#,
+        );
+        let billing = flow(
+            r#"
+id: billing
+entrypoints:
+  refund: refund_start
+nodes:
+  charge:
+    pay.component: {}
+  refund_start:
+    refund.component: {}
+    routing:
+      - out: true
+"#,
+        );
+        let unused = flow("id: unused\nnodes:\n  only:\n    x.component: {}\n");
+        let flows = [
+            (main.0, &main.1),
+            (billing.0, &billing.1),
+            (unused.0, &unused.1),
+        ];
+        let reachable = reachable_nodes(&flows, &["main".to_string()]);
+        let names = reachable
+            .iter()
+            .map(|(flow, node)| format!("{flow}/{node}"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["billing/refund_start", "main/greet", "main/handoff"]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::builder::{PackManifest, Provenance};
+use greentic_pack::reader::{PackLoad, SigningPolicy, open_pack};
+use serde_json::{Value as JsonValue, json};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::events;
+use crate::interrupt::PartialOutput;
+use crate::pack_cli::PackReleaseArgs;
+use crate::util::files;
+
+const SIGNATURE_PATH: &str = "signatures/pack.sig";
+const SIGNATURE_CHAIN_PATH: &str = "signatures/chain.pem";
+const PROVENANCE_PATH: &str = "provenance.json";
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+/// `pack release`: verify a signed gtpack and lay out everything needed to publish it in
+/// `<out>/<pack_id>/<version>/`.
+pub fn run(args: &PackReleaseArgs) -> Result<()> {
+    let policy = if args.strict {
+        SigningPolicy::Strict
+    } else {
+        SigningPolicy::DevOk
+    };
+    let load = open_pack(&args.pack, policy)
+        .map_err(|err| anyhow!("pack verification failed: {}", err.message))?;
+    let dir = create_bundle(&args.pack, &load, &args.out, args.force)?;
+    println!("Release bundle written to {}", dir.display());
+    for warning in &load.report.warnings {
+        println!("warning: {warning}");
+    }
+    Ok(())
+}
+
+/// Write the release bundle for an opened pack and return its directory.
+pub fn create_bundle(pack: &Path, load: &PackLoad, out: &Path, force: bool) -> Result<PathBuf> {
+    let meta = &load.manifest.meta;
+    let (Some(signature), Some(chain)) = (
+        load.files.get(SIGNATURE_PATH),
+        load.files.get(SIGNATURE_CHAIN_PATH),
+    ) else {
+        bail!(
+            "{} is not signed; rebuild it with signing enabled before releasing",
+            pack.display()
+        );
+    };
+    if !load.report.signature_ok {
+        bail!("{} has a signature that does not verify", pack.display());
+    }
+
+    let dir = out.join(&meta.pack_id).join(meta.version.to_string());
+    if dir.exists() {
+        if !force {
+            bail!(
+                "{} already exists; pass --force to replace it",
+                dir.display()
+            );
+        }
+        fs::remove_dir_all(&dir).with_context(|| format!("failed to remove {}", dir.display()))?;
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let partial = PartialOutput::new(&dir);
+
+    let stem = format!("{}-{}", meta.pack_id, meta.version);
+    let pack_name = format!("{stem}.gtpack");
+    // Copied and hashed as a stream: packs with large components should not be held in memory.
+    let pack_copy = dir.join(&pack_name);
+    fs::copy(pack, &pack_copy).with_context(|| {
+        format!(
+            "failed to copy {} to {}",
+            pack.display(),
+            pack_copy.display()
+        )
+    })?;
+    let pack_digest = files::sha256_file(&pack_copy)?;
+    let provenance = match load.files.get(PROVENANCE_PATH) {
+        Some(bytes) => Some(
+            serde_json::from_slice::<Provenance>(bytes)
+                .context("provenance.json in the pack is invalid")?,
+        ),
+        None => None,
+    };
+
+    let mut files = vec![
+        (format!("{pack_name}.sig"), signature.clone()),
+        (format!("{pack_name}.chain.pem"), chain.clone()),
+        ("sbom.greentic.json".to_string(), to_json(&load.sbom)?),
+        (
+            "sbom.cdx.json".to_string(),
+            to_json(&cyclonedx_sbom(load, &pack_digest))?,
+        ),
+        (
+            "provenance.intoto.json".to_string(),
+            to_json(&provenance_statement(
+                &load.manifest,
+                provenance.as_ref(),
+                &pack_name,
+                &pack_digest,
+            ))?,
+        ),
+        (
+            "build-report.json".to_string(),
+            to_json(&build_report(load, provenance.as_ref()))?,
+        ),
+        (
+            "CHANGELOG.md".to_string(),
+            changelog_skeleton(&load.manifest).into_bytes(),
+        ),
+    ];
+    let checksums = std::iter::once(format!("{pack_digest}  {pack_name}\n"))
+        .chain(
+            files
+                .iter()
+                .map(|(name, bytes)| format!("{}  {name}\n", sha256_hex(bytes))),
+        )
+        .collect::<String>();
+    files.push((CHECKSUMS_FILE.to_string(), checksums.into_bytes()));
+
+    for (name, bytes) in files {
+        let path = dir.join(&name);
+        fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
+        events::artifact(&path);
+    }
+    drop(partial);
+    Ok(dir)
+}
+
+// CycloneDX 1.5 view of the pack's own SBOM: every file in the pack with its BLAKE3 hash, and the
+// components with their versions.
+fn cyclonedx_sbom(load: &PackLoad, pack_digest: &str) -> JsonValue {
+    let meta = &load.manifest.meta;
+    let mut components = load
+        .manifest
+        .components
+        .iter()
+        .map(|component| {
+            json!({
+                "type": "library",
+                "bom-ref": format!("component:{}@{}", component.name, component.version),
+                "name": component.name,
+                "version": component.version.to_string(),
+                "hashes": [{ "alg": "BLAKE3", "content": component.hash_blake3 }],
+            })
+        })
+        .collect::<Vec<_>>();
+    components.extend(load.sbom.iter().map(|entry| {
+        json!({
+            "type": "file",
+            "bom-ref": format!("file:{}", entry.path),
+            "name": entry.path,
+            "mime-type": entry.media_type,
+            "hashes": [{ "alg": "BLAKE3", "content": entry.hash_blake3 }],
+        })
+    }));
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": now_rfc3339(),
+            "tools": { "components": [{
+                "type": "application",
+                "name": "greentic-dev",
+                "version": env!("CARGO_PKG_VERSION"),
+            }] },
+            "component": {
+                "type": "application",
+                "bom-ref": format!("pack:{}@{}", meta.pack_id, meta.version),
+                "name": meta.pack_id,
+                "version": meta.version.to_string(),
+                "description": meta.description,
+                "hashes": [{ "alg": "SHA-256", "content": pack_digest }],
+            },
+        },
+        "components": components,
+    })
+}
+
+// Unsigned in-toto statement with a SLSA v1 provenance predicate, built from the pack's
+// provenance.json.
+fn provenance_statement(
+    manifest: &PackManifest,
+    provenance: Option<&Provenance>,
+    pack_name: &str,
+    pack_digest: &str,
+) -> JsonValue {
+    let meta = &manifest.meta;
+    let dependencies = provenance
+        .and_then(|provenance| {
+            let repo = provenance.git_repo.as_ref()?;
+            let mut dependency = json!({ "uri": repo });
+            if let Some(commit) = &provenance.git_commit {
+                dependency["digest"] = json!({ "gitCommit": commit });
+            }
+            Some(vec![dependency])
+        })
+        .unwrap_or_default();
+    json!({
+        "_type": "https://in-toto.io/Statement/v1",
+        "subject": [{ "name": pack_name, "digest": { "sha256": pack_digest } }],
+        "predicateType": "https://slsa.dev/provenance/v1",
+        "predicate": {
+            "buildDefinition": {
+                "buildType": "https://greentic.ai/pack-build/v1",
+                "externalParameters": {
+                    "pack_id": meta.pack_id,
+                    "version": meta.version.to_string(),
+                    "entry_flows": meta.entry_flows,
+                },
+                "resolvedDependencies": dependencies,
+            },
+            "runDetails": {
+                "builder": {
+                    "id": provenance.map_or("unknown", |provenance| provenance.builder.as_str()),
+                },
+                "metadata": {
+                    "startedOn": provenance.map(|provenance| provenance.built_at_utc.as_str()),
+                },
+            },
+        },
+    })
+}
+
+fn build_report(load: &PackLoad, provenance: Option<&Provenance>) -> JsonValue {
+    let manifest = &load.manifest;
+    json!({
+        "pack_id": manifest.meta.pack_id,
+        "version": manifest.meta.version.to_string(),
+        "name": manifest.meta.name,
+        "released_at": now_rfc3339(),
+        "verification": {
+            "signature_ok": load.report.signature_ok,
+            "sbom_ok": load.report.sbom_ok,
+            "warnings": load.report.warnings,
+        },
+        "provenance": provenance,
+        "flows": manifest.flows.iter().map(|flow| json!({
+            "id": flow.id,
+            "kind": flow.kind,
+            "hash_blake3": flow.hash_blake3,
+        })).collect::<Vec<_>>(),
+        "components": manifest.components.iter().map(|component| json!({
+            "name": component.name,
+            "version": component.version.to_string(),
+            "hash_blake3": component.hash_blake3,
+        })).collect::<Vec<_>>(),
+        "files": load.sbom.len(),
+        "size_bytes": load.sbom.iter().map(|entry| entry.size).sum::<u64>(),
+    })
+}
+
+fn changelog_skeleton(manifest: &PackManifest) -> String {
+    let meta = &manifest.meta;
+    let date = OffsetDateTime::now_utc().date();
+    let mut out = format!(
+        "# {} {}\n\n_Released {date}._\n\n",
+        meta.pack_id, meta.version
+    );
+    for section in ["Added", "Changed", "Fixed", "Removed"] {
+        out.push_str(&format!("## {section}\n\n- \n\n"));
+    }
+    out.push_str("## Contents\n\n");
+    for flow in &manifest.flows {
+        out.push_str(&format!("- flow `{}` ({})\n", flow.id, flow.kind));
+    }
+    for component in &manifest.components {
+        out.push_str(&format!(
+            "- component `{}` {}\n",
+            component.name, component.version
+        ));
+    }
+    out
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Vec<u8>> {
+    let mut bytes = serde_json::to_vec_pretty(value)?;
+    bytes.push(b'\n');
+    Ok(bytes)
+}
+
+fn now_rfc3339() -> String {
+    OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| "unknown".into())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
@@ -0,0 +1,206 @@
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::repo::{RepoBinding, RepoPackKind, RepoPackSection};
+use serde::Deserialize;
+use toml_edit::{Array, ArrayOfTables, Item, Table, value};
+
+use crate::pack_cli::{PackRepoInitArgs, PackRepoStatusArgs};
+use crate::pack_meta::{read_document, read_typed, write_document};
+
+#[derive(Debug, Default, Deserialize)]
+struct RepoMeta {
+    repo: Option<RepoPackSection>,
+}
+
+/// Write a `[repo]` section declaring the pack's repo role, capabilities and first binding.
+pub fn init(args: &PackRepoInitArgs) -> Result<()> {
+    let kind = RepoPackKind::from(args.kind);
+    let binding = RepoBinding {
+        package: args.package.clone(),
+        world: args.world.clone(),
+        version: args.version.clone(),
+        component: args.component.clone(),
+        entrypoint: args.entrypoint.clone(),
+        profile: args.profile.clone(),
+    };
+
+    let mut doc = read_document(&args.meta)?;
+    if doc.contains_key("repo") && !args.force {
+        bail!(
+            "{} already has a [repo] section; pass --force to replace it",
+            args.meta.display()
+        );
+    }
+    doc.insert(
+        "repo",
+        Item::Table(repo_table(&kind, &args.capabilities, &binding)),
+    );
+
+    let section = toml::from_str::<RepoMeta>(&doc.to_string())
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?
+        .repo
+        .ok_or_else(|| anyhow!("repo section missing after update"))?;
+    section
+        .validate()
+        .with_context(|| format!("refusing to update {}", args.meta.display()))?;
+    write_document(&args.meta, &doc)?;
+    println!("Initialized {kind} repo section in {}", args.meta.display());
+    Ok(())
+}
+
+/// Validate the `[repo]` section and summarise the declared role, capabilities and bindings.
+pub fn status(args: &PackRepoStatusArgs) -> Result<()> {
+    let section = read_typed::<RepoMeta>(&args.meta)?
+        .repo
+        .ok_or_else(|| anyhow!("{} has no [repo] section", args.meta.display()))?;
+    let validation = section.validate().err().map(|err| err.to_string());
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "meta": args.meta.display().to_string(),
+                "valid": validation.is_none(),
+                "error": validation,
+                "repo": section,
+            }))?
+        );
+    } else {
+        println!("Repo role: {}", section.kind);
+        let key = role_key(&section.kind);
+        let capabilities = serde_json::to_value(&section.capabilities)?;
+        let bindings = serde_json::to_value(&section.bindings)?;
+        println!("Capabilities ({key}): {}", list_or_none(&capabilities[key]));
+        match bindings[key].as_array() {
+            Some(entries) if !entries.is_empty() => {
+                println!("Bindings ({key}):");
+                for entry in entries {
+                    println!(
+                        "  - {}/{}@{} -> {}#{}",
+                        entry["package"].as_str().unwrap_or_default(),
+                        entry["world"].as_str().unwrap_or_default(),
+                        entry["version"].as_str().unwrap_or_default(),
+                        entry["component"].as_str().unwrap_or_default(),
+                        entry["entrypoint"].as_str().unwrap_or_default(),
+                    );
+                }
+            }
+            _ => println!("Bindings ({key}): none"),
+        }
+        match &validation {
+            None => println!("✓ repo section is valid"),
+            Some(err) => println!("✗ {err}"),
+        }
+    }
+
+    if let Some(err) = validation {
+        bail!("invalid repo section in {}: {err}", args.meta.display());
+    }
+    Ok(())
+}
+
+// Capabilities and bindings are keyed by a short name per role (e.g. `scan` for `scanner`).
+fn role_key(kind: &RepoPackKind) -> &'static str {
+    match kind {
+        RepoPackKind::SourceProvider => "source",
+        RepoPackKind::Scanner => "scan",
+        RepoPackKind::Signing => "signing",
+        RepoPackKind::Attestation => "attestation",
+        RepoPackKind::PolicyEngine => "policy",
+        RepoPackKind::OciProvider => "oci",
+        RepoPackKind::BillingProvider => "billing",
+        RepoPackKind::SearchProvider => "search",
+        RepoPackKind::RecommendationProvider => "reco",
+    }
+}
+
+fn repo_table(kind: &RepoPackKind, capabilities: &[String], binding: &RepoBinding) -> Table {
+    let key = role_key(kind);
+    let mut table = Table::new();
+    table.insert("kind", value(kind.to_string()));
+
+    let mut caps = Table::new();
+    caps.insert(key, value(capabilities.iter().collect::<Array>()));
+    table.insert("capabilities", Item::Table(caps));
+
+    let mut entry = Table::new();
+    entry.insert("package", value(&binding.package));
+    entry.insert("world", value(&binding.world));
+    entry.insert("version", value(&binding.version));
+    entry.insert("component", value(&binding.component));
+    entry.insert("entrypoint", value(&binding.entrypoint));
+    if let Some(profile) = &binding.profile {
+        entry.insert("profile", value(profile));
+    }
+    let mut entries = ArrayOfTables::new();
+    entries.push(entry);
+    let mut bindings = Table::new();
+    bindings.set_implicit(true);
+    bindings.insert(key, Item::ArrayOfTables(entries));
+    table.insert("bindings", Item::Table(bindings));
+    table
+}
+
+fn list_or_none(value: &serde_json::Value) -> String {
+    match value.as_array() {
+        Some(items) if !items.is_empty() => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "none".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RepoMeta, repo_table};
+    use greentic_pack::repo::{RepoBinding, RepoPackKind};
+    use toml_edit::{DocumentMut, Item};
+
+    fn binding() -> RepoBinding {
+        RepoBinding {
+            package: "greentic:repo".to_string(),
+            world: "scanner".to_string(),
+            version: "1.0.0".to_string(),
+            component: "acme.scanner".to_string(),
+            entrypoint: "scan".to_string(),
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn generated_section_passes_schema_validation() {
+        let mut doc = "pack_id = \"dev.local.scanner\"\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        doc.insert(
+            "repo",
+            Item::Table(repo_table(
+                &RepoPackKind::Scanner,
+                &["sast".to_string()],
+                &binding(),
+            )),
+        );
+        let rendered = doc.to_string();
+        assert!(rendered.contains("[[repo.bindings.scan]]"));
+
+        let section = toml::from_str::<RepoMeta>(&rendered).unwrap().repo.unwrap();
+        assert_eq!(section.kind, RepoPackKind::Scanner);
+        assert_eq!(section.capabilities.scan, ["sast"]);
+        section.validate().unwrap();
+    }
+
+    #[test]
+    fn missing_capabilities_fail_validation() {
+        let mut doc = DocumentMut::new();
+        doc.insert(
+            "repo",
+            Item::Table(repo_table(&RepoPackKind::Signing, &[], &binding())),
+        );
+        let section = toml::from_str::<RepoMeta>(&doc.to_string())
+            .unwrap()
+            .repo
+            .unwrap();
+        assert!(section.validate().is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_types::{SecretKey, SecretRequirement};
+use serde_json::Value as JsonValue;
+
+use crate::component_manifest;
+use crate::events;
+use crate::pack_cli::PackSecretsInferArgs;
+
+pub const REQUIREMENTS_FILE: &str = "secret-requirements.json";
+const FLOW_DIR: &str = "flows";
+const COMPONENT_DIR: &str = "components";
+const URI_SCHEME: &str = "secrets://";
+
+/// Distinct secret references in a flow document: `secrets://` URIs and `{{ secrets.NAME }}`
+/// template references, as written.
+pub fn references(value: &JsonValue, found: &mut BTreeSet<String>) {
+    match value {
+        JsonValue::String(text) => {
+            for (start, _) in text.match_indices(URI_SCHEME) {
+                let uri = &text[start..];
+                let end = uri
+                    .find(|ch: char| ch.is_whitespace() || matches!(ch, '"' | '\'' | '}'))
+                    .unwrap_or(uri.len());
+                found.insert(uri[..end].to_string());
+            }
+            for (start, _) in text.match_indices("secrets.") {
+                // Only template references (`{{ secrets.NAME }}`), not prose or hostnames.
+                let before = text[..start].trim_end();
+                if !before.ends_with("{{") {
+                    continue;
+                }
+                let name = text[start + "secrets.".len()..]
+                    .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                    .next()
+                    .unwrap_or_default();
+                if !name.is_empty() {
+                    found.insert(format!("secrets.{name}"));
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter().for_each(|item| references(item, found)),
+        JsonValue::Object(map) => map.values().for_each(|item| references(item, found)),
+        _ => {}
+    }
+}
+
+/// The requirement key a reference resolves to: `NAME` for `secrets.NAME`, and
+/// `<category>/<name>` for `secrets://<env>/<tenant>/<team>/<category>/<name>[@version]`.
+pub fn reference_key(reference: &str) -> Option<String> {
+    if let Some(name) = reference.strip_prefix("secrets.") {
+        return Some(name.to_string());
+    }
+    let path = reference.strip_prefix(URI_SCHEME)?;
+    let segments = path.split('/').collect::<Vec<_>>();
+    let [_, _, _, category, name] = segments.as_slice() else {
+        return None;
+    };
+    let name = name.split_once('@').map_or(*name, |(name, _)| name);
+    Some(format!("{category}/{name}"))
+}
+
+/// Where a secret is needed, and whether any of those places requires it.
+#[derive(Debug, Default)]
+struct Usage {
+    required: bool,
+    sources: BTreeSet<String>,
+}
+
+/// `pack secrets infer`: collect the secrets the pack's flows reference and its components declare,
+/// add the missing ones to `secret-requirements.json`, and flag declared requirements that nothing
+/// uses. Existing entries are kept as written. With `--check`, nothing is written and missing
+/// requirements fail the command.
+pub fn infer(args: &PackSecretsInferArgs) -> Result<()> {
+    let usages = collect_usages(&args.dir)?;
+    let path = requirements_path(&args.dir);
+    let mut requirements = read_requirements(&path)?;
+
+    let declared = requirements
+        .iter()
+        .map(|requirement| requirement.key.as_str().to_string())
+        .collect::<BTreeSet<_>>();
+    for requirement in &requirements {
+        if !usages.contains_key(requirement.key.as_str()) {
+            println!(
+                "! {} is declared but no flow or component references it",
+                requirement.key.as_str()
+            );
+        }
+    }
+    let missing = usages
+        .iter()
+        .filter(|(key, _)| !declared.contains(*key))
+        .collect::<Vec<_>>();
+    for (key, usage) in &missing {
+        let sources = usage.sources.iter().cloned().collect::<Vec<_>>().join(", ");
+        let marker = if args.check { "✗" } else { "+" };
+        println!("{marker} {key} (used by {sources})");
+    }
+
+    if missing.is_empty() {
+        println!(
+            "✓ {} declares all {} secret(s) in use",
+            path.display(),
+            usages.len()
+        );
+        return Ok(());
+    }
+    if args.check {
+        bail!(
+            "{} secret(s) are missing from {}; run `greentic-dev pack secrets infer` to add them",
+            missing.len(),
+            path.display()
+        );
+    }
+    for (key, usage) in missing {
+        let mut requirement = SecretRequirement::default();
+        requirement.key =
+            SecretKey::new(key.as_str()).map_err(|err| anyhow!("secret `{key}`: {err}"))?;
+        requirement.required = usage.required;
+        requirements.push(requirement);
+    }
+    let mut rendered = serde_json::to_string_pretty(&requirements)?;
+    rendered.push('\n');
+    fs::write(&path, rendered).with_context(|| format!("failed to write {}", path.display()))?;
+    events::artifact(&path);
+    println!("Updated {}", path.display());
+    Ok(())
+}
+
+/// The pack's requirements file and the keys it declares that no flow or component uses.
+pub(crate) fn unused_requirements(dir: &Path) -> Result<(PathBuf, Vec<String>)> {
+    let usages = collect_usages(dir)?;
+    let path = requirements_path(dir);
+    let unused = read_requirements(&path)?
+        .into_iter()
+        .map(|requirement| requirement.key.as_str().to_string())
+        .filter(|key| !usages.contains_key(key))
+        .collect();
+    Ok((path, unused))
+}
+
+// The pack's existing requirements file, or a new one at the pack root.
+fn requirements_path(dir: &Path) -> PathBuf {
+    let assets = dir.join("assets").join(REQUIREMENTS_FILE);
+    if assets.is_file() {
+        assets
+    } else {
+        dir.join(REQUIREMENTS_FILE)
+    }
+}
+
+fn read_requirements(path: &Path) -> Result<Vec<SecretRequirement>> {
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+// Secret keys in use, from flow references (always required) and component declarations
+// (`capabilities.host.secrets.required`).
+fn collect_usages(dir: &Path) -> Result<BTreeMap<String, Usage>> {
+    let mut usages = BTreeMap::<String, Usage>::new();
+    let mut flows = Vec::new();
+    collect_flow_files(&dir.join(FLOW_DIR), &mut flows)?;
+    flows.sort();
+    for path in flows {
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let doc: JsonValue = serde_yaml_bw::from_str(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let mut found = BTreeSet::new();
+        if let Some(nodes) = doc.get("nodes") {
+            references(nodes, &mut found);
+        }
+        let source = display(dir, &path);
+        for reference in found {
+            let key = reference_key(&reference)
+                .ok_or_else(|| anyhow!("{source}: `{reference}` is not a valid secret URI"))?;
+            let usage = usages.entry(key).or_default();
+            usage.required = true;
+            usage.sources.insert(source.clone());
+        }
+    }
+
+    let component_dir = dir.join(COMPONENT_DIR);
+    if component_dir.is_dir() {
+        let mut components = fs::read_dir(&component_dir)
+            .with_context(|| format!("failed to read {}", component_dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        components.sort();
+        for component in components {
+            let path = component_manifest::manifest_path(&component);
+            if !path.is_file() {
+                continue;
+            }
+            let manifest = component_manifest::read_manifest(&path)?;
+            let name = manifest
+                .get("name")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| display(dir, &component));
+            let declared = manifest
+                .pointer("/capabilities/host/secrets/required")
+                .and_then(JsonValue::as_array);
+            for requirement in declared.into_iter().flatten() {
+                let Some(key) = requirement.get("key").and_then(JsonValue::as_str) else {
+                    continue;
+                };
+                let usage = usages.entry(key.to_string()).or_default();
+                usage.required |= requirement
+                    .get("required")
+                    .and_then(JsonValue::as_bool)
+                    .unwrap_or(true);
+                usage.sources.insert(name.clone());
+            }
+        }
+    }
+    Ok(usages)
+}
+
+fn collect_flow_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_flow_files(&path, paths)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("ygtc") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn display(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reference_key, references};
+    use serde_json::json;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn references_are_found_in_templates_and_uris() {
+        let node = json!({
+            "component.exec": {
+                "input": {
+                    "token": "{{ secrets.API_TOKEN }}",
+                    "db": ["secrets://dev/acme/_/configs/db@v2"],
+                    "note": "see secrets.md",
+                }
+            }
+        });
+        let mut found = BTreeSet::new();
+        references(&node, &mut found);
+        assert_eq!(
+            found.into_iter().collect::<Vec<_>>(),
+            ["secrets.API_TOKEN", "secrets://dev/acme/_/configs/db@v2"]
+        );
+    }
+
+    #[test]
+    fn references_map_to_requirement_keys() {
+        assert_eq!(
+            reference_key("secrets.API_TOKEN").as_deref(),
+            Some("API_TOKEN")
+        );
+        assert_eq!(
+            reference_key("secrets://dev/acme/_/configs/db@v2").as_deref(),
+            Some("configs/db")
+        );
+        assert_eq!(reference_key("secrets://dev/acme"), None);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::distributor::{
+    DevArtifactKind, DevDistributorClient, DevIntent, DevPublishRequest, DevResolveRequest,
+    DevResolveResponse, resolve_profile,
+};
+use crate::download::{CHUNK, DownloadOptions, Progress, fetch, hex};
+use crate::pack_cli::{PackPullArgs, PackPushArgs};
+use crate::pack_init::{handle_resolve_result, slugify};
+
+/// `pack push`: upload a gtpack to the distributor of the resolved profile, for the profile's
+/// tenant and environment. The pack is verified first, and the digest the distributor reports
+/// must match the local one.
+pub fn push(args: &PackPushArgs) -> Result<()> {
+    let load = open_pack(&args.pack, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
+    let pack_id = load.manifest.meta.pack_id.clone();
+    let version = load.manifest.meta.version.to_string();
+    let digest = file_digest(&args.pack)?;
+    let size = fs::metadata(&args.pack)
+        .with_context(|| format!("failed to read {}", args.pack.display()))?
+        .len();
+
+    let config = config::load_with_meta(None)?;
+    let profile = resolve_profile(&config, args.profile.as_deref())?;
+    let request = DevPublishRequest {
+        pack_id: pack_id.clone(),
+        version: version.clone(),
+        tenant_id: profile.tenant_id.clone(),
+        environment_id: profile.environment_id.clone(),
+        digest: digest.clone(),
+    };
+    println!(
+        "Pushing {pack_id}@{version} to {} (profile {}, tenant {}, environment {})",
+        profile.url, profile.name, profile.tenant_id, profile.environment_id
+    );
+    let client = DevDistributorClient::for_transfers(profile)?;
+    let file = File::open(&args.pack)
+        .with_context(|| format!("failed to open {}", args.pack.display()))?;
+    let reader = ProgressReader {
+        inner: file,
+        progress: Progress::new("uploading", Some(size)),
+    };
+    let response = client
+        .publish_pack(&request, reqwest::blocking::Body::sized(reader, size))
+        .map_err(|err| anyhow!("failed to push {pack_id}@{version}: {err}"))?;
+    if let Some(remote) = &response.digest
+        && !remote.eq_ignore_ascii_case(&digest)
+    {
+        bail!("the distributor stored {remote}, but the pushed pack is {digest}");
+    }
+    println!("✓ pushed {} ({digest})", response.coordinate);
+    Ok(())
+}
+
+/// `pack pull <pack-id>@<version>`: resolve the pack through the distributor and download it
+/// with [`fetch`], which resumes an interrupted pull and verifies the digest before moving the
+/// file into place.
+pub fn pull(args: &PackPullArgs) -> Result<()> {
+    let (pack_id, version) = args
+        .coordinate
+        .split_once('@')
+        .filter(|(id, version)| !id.is_empty() && !version.is_empty())
+        .ok_or_else(|| anyhow!("expected <pack-id>@<version>, got `{}`", args.coordinate))?;
+    let out = args
+        .out
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}-{version}.gtpack", slugify(pack_id))));
+    if out.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            out.display()
+        );
+    }
+
+    let (resolved, digest) = fetch_pack(
+        args.profile.as_deref(),
+        &args.coordinate,
+        &out,
+        args.limit_rate,
+    )?;
+    println!(
+        "✓ pulled {}@{} to {} ({digest})",
+        resolved.name,
+        resolved.version,
+        out.display()
+    );
+    Ok(())
+}
+
+/// Resolve `coordinate` as a pack through the distributor of `profile` and download it to `out`.
+/// Returns the resolution and the verified digest of the download.
+pub(crate) fn fetch_pack(
+    profile: Option<&str>,
+    coordinate: &str,
+    out: &Path,
+    limit_rate: Option<u64>,
+) -> Result<(DevResolveResponse, String)> {
+    let config = config::load_with_meta(None)?;
+    let profile = resolve_profile(&config, profile)?;
+    let client = DevDistributorClient::for_transfers(profile)?;
+    let resolved = handle_resolve_result(client.resolve(&DevResolveRequest {
+        coordinate: coordinate.to_string(),
+        intent: DevIntent::Dev,
+        platform: None,
+        features: Vec::new(),
+    }))?;
+    if resolved.kind != DevArtifactKind::Pack {
+        bail!(
+            "coordinate `{}` resolved to {:?}, expected pack",
+            resolved.coordinate,
+            resolved.kind
+        );
+    }
+
+    let options = DownloadOptions {
+        limit_rate,
+        ..DownloadOptions::default()
+    };
+    let downloaded = fetch(
+        || client.artifact_request(&resolved.artifact_download_path),
+        out,
+        resolved.digest.as_deref(),
+        &options,
+    )
+    .with_context(|| format!("failed to download {}", resolved.coordinate))?;
+    Ok((resolved, downloaded.sha256))
+}
+
+fn file_digest(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; CHUNK];
+    loop {
+        let read = file
+            .read(&mut buf)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("sha256:{}", hex(&hasher.finalize())))
+}
+
+struct ProgressReader<R> {
+    inner: R,
+    progress: Progress,
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 {
+            self.progress.finish();
+        } else {
+            self.progress.advance(read as u64);
+        }
+        Ok(read)
+    }
+}
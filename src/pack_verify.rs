@@ -1,8 +1,17 @@
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
 
-use anyhow::Result;
-use greentic_pack::reader::{PackVerifyResult, SigningPolicy, open_pack};
+use anyhow::{Context, Result, bail};
+use greentic_pack::reader::{PackLoad, PackVerifyResult, SigningPolicy, open_pack};
+use serde::Serialize;
 use serde_json::json;
+use wasmparser::{Parser, Payload, Validator, WasmFeatures};
+
+use crate::component_compat;
+use crate::pack_cli::{PackVerifyArgs, PreflightPolicy};
+use crate::util::parallel::parallel_map;
 
 #[derive(Debug, Clone, Copy)]
 pub enum VerifyPolicy {
@@ -48,10 +57,282 @@ pub fn run(pack_path: &Path, policy: VerifyPolicy, emit_json: bool) -> Result<()
     Ok(())
 }
 
+#[derive(Debug, Serialize)]
+struct BatchReport {
+    root: String,
+    ok: bool,
+    verified: usize,
+    failed: usize,
+    packs: Vec<PackOutcome>,
+}
+
+#[derive(Debug, Serialize)]
+struct PackOutcome {
+    /// Path relative to the verified directory.
+    pack: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pack_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Per-component results of `--deep`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<ComponentCheck>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComponentCheck {
+    component: String,
+    ok: bool,
+    /// blake3 of the wasm in the archive, when it is there.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blake3: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    world: Option<String>,
+    problems: Vec<String>,
+}
+
+/// `pack verify --all <DIR>`: verify every `.gtpack` under a directory, several at a time, and
+/// report the outcome per pack; `pack verify --pack <PACK> --deep` does the same for one pack.
+/// Fails when any pack fails.
+pub fn run_all(args: &PackVerifyArgs) -> Result<()> {
+    let (root, packs) = match (&args.all, &args.pack) {
+        (Some(root), _) => {
+            if !root.is_dir() {
+                bail!("{} is not a directory", root.display());
+            }
+            let mut packs = Vec::new();
+            collect_packs(root, &mut packs)?;
+            if packs.is_empty() {
+                bail!("no .gtpack files under {}", root.display());
+            }
+            packs.sort();
+            (root.as_path(), packs)
+        }
+        (None, Some(pack)) => (Path::new(""), vec![pack.clone()]),
+        (None, None) => bail!("pass --all <DIR> or --pack <PACK>"),
+    };
+
+    let policy = match PreflightPolicy::resolve(args.policy)? {
+        PreflightPolicy::Strict => VerifyPolicy::Strict,
+        PreflightPolicy::Devok => VerifyPolicy::DevOk,
+    };
+    let jobs = args
+        .jobs
+        .map(usize::from)
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+    let outcomes = verify_parallel(root, &packs, policy, args.deep, jobs);
+    let failed = outcomes.iter().filter(|outcome| !outcome.ok).count();
+    let report = BatchReport {
+        root: args
+            .all
+            .as_ref()
+            .or(args.pack.as_ref())
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        ok: failed == 0,
+        verified: outcomes.len() - failed,
+        failed,
+        packs: outcomes,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for outcome in &report.packs {
+            match &outcome.error {
+                None => println!(
+                    "✓ {} ({} {})",
+                    outcome.pack,
+                    outcome.pack_id.as_deref().unwrap_or("?"),
+                    outcome.version.as_deref().unwrap_or("?")
+                ),
+                Some(error) => println!("✗ {}: {error}", outcome.pack),
+            }
+            for warning in &outcome.warnings {
+                println!("    ! {warning}");
+            }
+            for check in &outcome.components {
+                if check.ok {
+                    println!(
+                        "    ✓ {} ({})",
+                        check.component,
+                        check.world.as_deref().unwrap_or("no declared world")
+                    );
+                } else {
+                    println!("    ✗ {}: {}", check.component, check.problems.join("; "));
+                }
+            }
+        }
+        println!(
+            "{} pack(s) checked, {} verified, {} failed",
+            report.packs.len(),
+            report.verified,
+            report.failed
+        );
+    }
+    if !report.ok {
+        bail!(
+            "{} of {} pack(s) under {} failed verification",
+            report.failed,
+            report.packs.len(),
+            report.root
+        );
+    }
+    Ok(())
+}
+
+fn verify_parallel(
+    root: &Path,
+    packs: &[PathBuf],
+    policy: VerifyPolicy,
+    deep: bool,
+    jobs: usize,
+) -> Vec<PackOutcome> {
+    parallel_map(packs, jobs, |pack| verify_one(root, pack, policy, deep))
+}
+
+fn verify_one(root: &Path, pack: &Path, policy: VerifyPolicy, deep: bool) -> PackOutcome {
+    let name = pack
+        .strip_prefix(root)
+        .unwrap_or(pack)
+        .display()
+        .to_string();
+    match open_pack(pack, policy.into()) {
+        Ok(load) => {
+            let mut warnings = load.report.warnings.clone();
+            let components = if deep {
+                check_components(&load, &mut warnings)
+            } else {
+                Vec::new()
+            };
+            let failed = components.iter().filter(|check| !check.ok).count();
+            PackOutcome {
+                pack: name,
+                ok: failed == 0,
+                pack_id: Some(load.manifest.meta.pack_id.clone()),
+                version: Some(load.manifest.meta.version.to_string()),
+                warnings,
+                error: (failed > 0).then(|| format!("{failed} component(s) failed deep checks")),
+                components,
+            }
+        }
+        Err(err) => PackOutcome {
+            pack: name,
+            ok: false,
+            pack_id: None,
+            version: None,
+            warnings: Vec::new(),
+            error: Some(err.message),
+            components: Vec::new(),
+        },
+    }
+}
+
+// `--deep`: every component the manifest lists must be in the archive with the recorded blake3,
+// be a valid wasm component, and export what its declared world requires. Worlds greentic-dev
+// does not know are reported as warnings, since their exports cannot be checked.
+fn check_components(load: &PackLoad, warnings: &mut Vec<String>) -> Vec<ComponentCheck> {
+    let mut checks = Vec::new();
+    for component in &load.manifest.components {
+        let mut check = ComponentCheck {
+            component: component.name.clone(),
+            ok: false,
+            blake3: None,
+            world: component.world.clone(),
+            problems: Vec::new(),
+        };
+        let Some(wasm) = load.files.get(&component.file_wasm) else {
+            check.problems.push(format!(
+                "{} is missing from the archive",
+                component.file_wasm
+            ));
+            checks.push(check);
+            continue;
+        };
+        let actual = blake3::hash(wasm).to_hex().to_string();
+        if !actual.eq_ignore_ascii_case(&component.hash_blake3) {
+            check.problems.push(format!(
+                "blake3 mismatch: manifest records {}, archive has {actual}",
+                component.hash_blake3
+            ));
+        }
+        check.blake3 = Some(actual);
+
+        match component_exports(wasm) {
+            Err(err) => check.problems.push(err),
+            Ok(exports) => {
+                if let Some(world) = &component.world {
+                    match component_compat::world_exports(world) {
+                        Ok(required) => {
+                            for missing in required.difference(&exports) {
+                                check
+                                    .problems
+                                    .push(format!("does not export {missing} required by {world}"));
+                            }
+                        }
+                        Err(_) => warnings.push(format!(
+                            "{}: exports not checked, world `{world}` is not one greentic-dev knows",
+                            component.name
+                        )),
+                    }
+                }
+            }
+        }
+        check.ok = check.problems.is_empty();
+        checks.push(check);
+    }
+    checks
+}
+
+/// Validate `wasm` as a component and list the names it exports at the top level.
+fn component_exports(wasm: &[u8]) -> Result<BTreeSet<String>, String> {
+    if !Parser::is_component(wasm) {
+        return Err("not a wasm component".to_string());
+    }
+    Validator::new_with_features(WasmFeatures::all())
+        .validate_all(wasm)
+        .map_err(|err| format!("invalid wasm component: {err}"))?;
+    let mut exports = BTreeSet::new();
+    // Nested modules and components have export sections of their own; only depth 0 counts.
+    let mut depth = 0usize;
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload.map_err(|err| format!("invalid wasm component: {err}"))? {
+            Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+            Payload::End(_) => depth = depth.saturating_sub(1),
+            Payload::ComponentExportSection(reader) if depth == 0 => {
+                for export in reader {
+                    let export = export.map_err(|err| format!("invalid wasm component: {err}"))?;
+                    exports.insert(export.name.0.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(exports)
+}
+
+fn collect_packs(dir: &Path, packs: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_packs(&path, packs)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("gtpack") {
+            packs.push(path);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{VerifyPolicy, run};
+    use super::{VerifyPolicy, check_components, collect_packs, run, verify_parallel};
     use crate::pack_build::{self, PackSigning};
+    use greentic_pack::reader::{SigningPolicy, open_pack};
 
     #[test]
     fn verify_can_emit_json_report() {
@@ -72,4 +353,91 @@ mod tests {
 
         run(&pack_path, VerifyPolicy::DevOk, true).unwrap();
     }
+
+    #[test]
+    fn batch_verification_reports_each_pack_in_order() {
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let temp = tempfile::tempdir().unwrap();
+        let good = temp.path().join("a/good.gtpack");
+        std::fs::create_dir_all(good.parent().unwrap()).unwrap();
+        pack_build::run(
+            &root.join("tests/fixtures/hello-pack/hello-flow.ygtc"),
+            &good,
+            PackSigning::Dev,
+            None,
+            Some(root.join("fixtures/components").as_path()),
+        )
+        .unwrap();
+        let broken = temp.path().join("b/nested/broken.gtpack");
+        std::fs::create_dir_all(broken.parent().unwrap()).unwrap();
+        std::fs::write(&broken, b"not a zip").unwrap();
+        std::fs::write(temp.path().join("b/notes.txt"), b"ignored").unwrap();
+
+        let mut packs = Vec::new();
+        collect_packs(temp.path(), &mut packs).unwrap();
+        packs.sort();
+        let outcomes = verify_parallel(temp.path(), &packs, VerifyPolicy::DevOk, true, 4);
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].pack, "a/good.gtpack");
+        assert!(outcomes[0].ok && outcomes[0].pack_id.is_some());
+        assert!(!outcomes[0].components.is_empty());
+        assert!(outcomes[0].components.iter().all(|check| check.ok));
+        assert_eq!(outcomes[1].pack, "b/nested/broken.gtpack");
+        assert!(!outcomes[1].ok && outcomes[1].error.is_some());
+    }
+
+    #[test]
+    fn deep_checks_catch_hash_world_and_archive_mismatches() {
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let temp = tempfile::tempdir().unwrap();
+        let pack = temp.path().join("deep.gtpack");
+        pack_build::run(
+            &root.join("tests/fixtures/hello-pack/hello-flow.ygtc"),
+            &pack,
+            PackSigning::Dev,
+            None,
+            Some(root.join("fixtures/components").as_path()),
+        )
+        .unwrap();
+        let mut load = open_pack(&pack, SigningPolicy::DevOk).unwrap();
+        let mut warnings = Vec::new();
+        let checks = check_components(&load, &mut warnings);
+        assert!(checks.iter().all(|check| check.ok), "{checks:?}");
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        let mut component = load.manifest.components[0].clone();
+        component.hash_blake3 = "00".repeat(32);
+        component.world = Some("greentic:component/component@0.6.0".to_string());
+        let mut missing = component.clone();
+        missing.name = "missing".to_string();
+        missing.file_wasm = "components/missing.wasm".to_string();
+        let mut unknown = load.manifest.components[0].clone();
+        unknown.world = Some("acme:widgets/widget@1.0.0".to_string());
+        load.manifest.components = vec![component, missing, unknown];
+
+        let checks = check_components(&load, &mut warnings);
+        assert!(!checks[0].ok);
+        assert!(
+            checks[0].problems[0].starts_with("blake3 mismatch"),
+            "{:?}",
+            checks[0].problems
+        );
+        assert!(
+            checks[0].problems[1..]
+                .iter()
+                .any(|problem| problem.contains("does not export greentic:component/node@0.6.0")),
+            "{:?}",
+            checks[0].problems
+        );
+        assert_eq!(
+            checks[1].problems,
+            ["components/missing.wasm is missing from the archive"]
+        );
+        assert!(checks[2].ok);
+        assert!(
+            warnings[0].contains("world `acme:widgets/widget@1.0.0` is not one greentic-dev knows"),
+            "{warnings:?}"
+        );
+    }
 }
@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use greentic_pack::PackKind;
+use serde::Deserialize;
+
+pub const PACK_YAML: &str = "pack.yaml";
+
+/// The parts of a `pack.yaml` workspace that `pack build --in` reads. Components, dependencies,
+/// assets and extensions are left to greentic-pack: the dev resolver finds components from the
+/// flows.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackYaml {
+    pub pack_id: String,
+    pub version: String,
+    #[serde(default)]
+    pub kind: Option<PackKind>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Recorded as the pack's vendor.
+    #[serde(default)]
+    pub publisher: Option<String>,
+    pub flows: Vec<PackYamlFlow>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackYamlFlow {
+    pub id: String,
+    /// Relative to the pack directory.
+    pub file: PathBuf,
+    #[serde(default)]
+    pub entrypoints: Vec<String>,
+}
+
+impl PackYaml {
+    /// Read `<dir>/pack.yaml`. Every flow must be listed once.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(PACK_YAML);
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let pack: Self = serde_yaml_bw::from_str(&raw)
+            .with_context(|| format!("invalid pack workspace {}", path.display()))?;
+        if pack.flows.is_empty() {
+            bail!("{} lists no flows", path.display());
+        }
+        let mut ids = BTreeSet::new();
+        for flow in &pack.flows {
+            if !ids.insert(flow.id.as_str()) {
+                bail!("{} lists flow `{}` twice", path.display(), flow.id);
+            }
+        }
+        Ok(pack)
+    }
+
+    /// Flows that declare entrypoints, or every flow when none does.
+    pub fn entry_flows(&self) -> Vec<String> {
+        let declared = self
+            .flows
+            .iter()
+            .filter(|flow| !flow.entrypoints.is_empty())
+            .map(|flow| flow.id.clone())
+            .collect::<Vec<_>>();
+        if declared.is_empty() {
+            self.flows.iter().map(|flow| flow.id.clone()).collect()
+        } else {
+            declared
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> PackYaml {
+        serde_yaml_bw::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn entry_flows_are_those_declaring_entrypoints() {
+        let pack = parse(
+            r#"
+pack_id: dev.local.shop
+version: 0.1.0
+kind: application
+flows:
+  - id: main
+    file: flows/main.ygtc
+    entrypoints: [default]
+  - id: billing
+    file: flows/billing.ygtc
+components: []
+"#,
+        );
+        assert_eq!(pack.entry_flows(), ["main"]);
+        assert_eq!(pack.kind, Some(PackKind::Application));
+
+        let pack = parse(
+            "pack_id: p\nversion: 0.1.0\nflows:\n  - id: a\n    file: a.ygtc\n  - id: b\n    file: b.ygtc\n",
+        );
+        assert_eq!(pack.entry_flows(), ["a", "b"]);
+    }
+}
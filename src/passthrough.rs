@@ -15,7 +15,7 @@ pub enum ToolchainChannel {
 
 impl ToolchainChannel {
     pub fn from_executable_name(name: &str) -> Self {
-        let stem = name.strip_suffix(".exe").unwrap_or(name);
+        let stem = strip_exe_suffix(name);
         if stem == "greentic-dev-dev" {
             Self::Development
         } else {
@@ -24,6 +24,18 @@ impl ToolchainChannel {
     }
 }
 
+// Windows file names are case-insensitive, so `GREENTIC-DEV-DEV.EXE` selects the dev channel too.
+fn strip_exe_suffix(name: &str) -> &str {
+    match name.len().checked_sub(4) {
+        Some(split)
+            if name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(".exe") =>
+        {
+            &name[..split]
+        }
+        _ => name,
+    }
+}
+
 pub fn current_toolchain_channel() -> ToolchainChannel {
     let executable_name = env::args_os()
         .next()
@@ -76,8 +88,8 @@ pub fn resolve_binary_for_channel(name: &str, channel: ToolchainChannel) -> Resu
     );
     if let Ok(path) = env::var(&env_key) {
         let pb = PathBuf::from(path);
-        if pb.exists() {
-            return Ok(pb);
+        if let Some(found) = existing_executable(&pb) {
+            return Ok(found);
         }
         bail!(
             "{}",
@@ -106,6 +118,21 @@ pub fn resolve_binary_for_channel(name: &str, channel: ToolchainChannel) -> Resu
     )
 }
 
+// On Windows an override may name the binary without its `.exe` suffix, as PATH lookups allow.
+fn existing_executable(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+    let suffix = env::consts::EXE_SUFFIX;
+    if suffix.is_empty() || path.extension().is_some() {
+        return None;
+    }
+    let mut with_suffix = path.as_os_str().to_owned();
+    with_suffix.push(suffix);
+    let with_suffix = PathBuf::from(with_suffix);
+    with_suffix.exists().then_some(with_suffix)
+}
+
 pub fn run_passthrough(bin: &Path, args: &[OsString], verbose: bool) -> Result<ExitStatus> {
     let locale = crate::i18n::select_locale(None);
     if verbose {
@@ -127,12 +154,21 @@ pub fn run_passthrough(bin: &Path, args: &[OsString], verbose: bool) -> Result<E
             .status();
     }
 
-    Command::new(bin)
-        .args(args)
+    let mut command = Command::new(bin);
+    command.args(args);
+    run_command(command)
+}
+
+/// Run a prepared delegated-tool command on the terminal's stdio, as [`run_passthrough`] does.
+pub fn run_command(mut command: Command) -> Result<ExitStatus> {
+    let locale = crate::i18n::select_locale(None);
+    let bin = PathBuf::from(command.get_program());
+    command
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .status()
+        .spawn()
+        .and_then(crate::interrupt::wait_child)
         .map_err(|e| {
             anyhow!(crate::i18n::tf(
                 &locale,
@@ -423,6 +459,14 @@ mod tests {
             ToolchainChannel::from_executable_name("greentic-dev-dev.exe"),
             ToolchainChannel::Development
         );
+        assert_eq!(
+            ToolchainChannel::from_executable_name("GREENTIC-DEV-DEV.EXE"),
+            ToolchainChannel::Stable
+        );
+        assert_eq!(
+            ToolchainChannel::from_executable_name("greentic-dev-dev.EXE"),
+            ToolchainChannel::Development
+        );
     }
 
     #[test]
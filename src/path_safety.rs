@@ -4,10 +4,15 @@ use anyhow::{Context, Result};
 
 /// Normalize a user-supplied path and ensure it stays within an allowed root.
 /// Reject absolute paths and any that escape via `..`.
+///
+/// Both sides are canonicalized, so symlinks and (on Windows) junctions are followed before the
+/// containment check: a junction inside `root` that points elsewhere is rejected. On Windows the
+/// returned path keeps the `\\?\` verbatim prefix from `canonicalize`, which lifts the 260
+/// character `MAX_PATH` limit for later file operations; use [`display_path`] when showing it.
 pub fn normalize_under_root(root: &Path, candidate: &Path) -> Result<PathBuf> {
     let canonical_root = root
         .canonicalize()
-        .with_context(|| format!("failed to canonicalize root {}", root.display()))?;
+        .with_context(|| format!("failed to canonicalize root {}", display_path(root)))?;
 
     let resolved = if candidate.is_absolute() {
         candidate.to_path_buf()
@@ -17,15 +22,80 @@ pub fn normalize_under_root(root: &Path, candidate: &Path) -> Result<PathBuf> {
 
     let canon = resolved
         .canonicalize()
-        .with_context(|| format!("failed to canonicalize {}", resolved.display()))?;
+        .with_context(|| format!("failed to canonicalize {}", display_path(&resolved)))?;
 
     if !canon.starts_with(&canonical_root) {
         anyhow::bail!(
             "path escapes root ({}): {}",
-            canonical_root.display(),
-            canon.display()
+            display_path(&canonical_root),
+            display_path(&canon)
         );
     }
 
     Ok(canon)
 }
+
+/// Render a path for messages without the Windows verbatim prefix (`\\?\C:\x` becomes `C:\x`,
+/// `\\?\UNC\server\share` becomes `\\server\share`). Other platforms print it unchanged.
+pub fn display_path(path: &Path) -> String {
+    let text = path.display().to_string();
+    if cfg!(windows) {
+        if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+            return format!(r"\\{rest}");
+        }
+        if let Some(rest) = text.strip_prefix(r"\\?\") {
+            return rest.to_string();
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_under_root;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn accepts_paths_inside_root_and_rejects_parent_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        fs::create_dir_all(root.join("flows")).unwrap();
+        fs::write(root.join("flows/main.ygtc"), "").unwrap();
+
+        let ok = normalize_under_root(&root, Path::new("flows/main.ygtc")).unwrap();
+        assert!(ok.ends_with(Path::new("flows").join("main.ygtc")));
+
+        let err = normalize_under_root(&root.join("flows"), Path::new("../flows/../.."))
+            .expect_err("escapes root");
+        assert!(err.to_string().contains("path escapes root"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_pointing_outside_root_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("root");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        let err = normalize_under_root(&root, Path::new("link")).expect_err("link escapes root");
+        assert!(err.to_string().contains("path escapes root"), "{err}");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn messages_drop_the_verbatim_prefix() {
+        use super::display_path;
+        assert_eq!(
+            display_path(Path::new(r"\\?\C:\work\pack")),
+            r"C:\work\pack"
+        );
+        assert_eq!(
+            display_path(Path::new(r"\\?\UNC\server\share\pack")),
+            r"\\server\share\pack"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use semver::VersionReq;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::cli::{RegistryCommand, RegistryExportArgs, RegistryImportArgs};
+use crate::component_resolver::ComponentResolver;
+use crate::dev_runner::registry::{ComponentStub, DescribeRegistry, REGISTRY_PATH};
+use crate::events;
+use crate::state_lock::StateLock;
+
+pub fn run(command: RegistryCommand) -> Result<()> {
+    match command {
+        RegistryCommand::Export(args) => export(&args),
+        RegistryCommand::Import(args) => import(&args),
+    }
+}
+
+fn export(args: &RegistryExportArgs) -> Result<()> {
+    // Start from what was imported earlier so a re-export round-trips, then let local
+    // components (which have the wasm at hand) override it.
+    let workspace = Path::new(REGISTRY_PATH);
+    let mut registry = if workspace.exists() {
+        DescribeRegistry::load(workspace)?
+    } else {
+        DescribeRegistry::empty()
+    };
+    if args.component_dir.is_dir() {
+        collect_components(&args.component_dir, &mut registry)?;
+    } else if registry.is_empty() {
+        bail!(
+            "component directory {} does not exist (use --component-dir)",
+            args.component_dir.display()
+        );
+    }
+    if registry.is_empty() {
+        bail!("no component schemas to export");
+    }
+
+    registry.save(&args.file)?;
+    println!(
+        "Exported {} component(s) to {}",
+        registry.len(),
+        args.file.display()
+    );
+    Ok(())
+}
+
+fn import(args: &RegistryImportArgs) -> Result<()> {
+    let incoming = DescribeRegistry::load(&args.file)?;
+    let target = Path::new(REGISTRY_PATH);
+    let dir = target.parent().expect("registry path has a parent");
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let _lock = StateLock::acquire(dir)?;
+
+    let mut registry = if target.exists() && !args.replace {
+        DescribeRegistry::load(target)?
+    } else {
+        DescribeRegistry::empty()
+    };
+    let (mut added, mut updated) = (0, 0);
+    for (name, stub) in incoming.iter() {
+        match registry.insert(name, stub.clone()) {
+            Some(_) => updated += 1,
+            None => added += 1,
+        }
+    }
+    registry.save(target)?;
+    println!(
+        "Imported {} component(s) into {REGISTRY_PATH} ({added} added, {updated} updated)",
+        incoming.len()
+    );
+    Ok(())
+}
+
+fn collect_components(component_dir: &Path, registry: &mut DescribeRegistry) -> Result<()> {
+    let mut names = fs::read_dir(component_dir)
+        .with_context(|| format!("failed to read {}", component_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let resolver = ComponentResolver::new(Some(component_dir.to_path_buf()));
+    for name in names {
+        let component = match resolver.resolve_component(&name, &VersionReq::STAR) {
+            Ok(component) => component,
+            Err(err) => {
+                events::warn(format_args!("skipping {name}: {err:#}"));
+                continue;
+            }
+        };
+        let Some(schema) = &component.schema_json else {
+            events::warn(format_args!(
+                "component {} does not describe a config schema",
+                component.name
+            ));
+            continue;
+        };
+        let parsed: JsonValue = serde_json::from_str(schema)
+            .with_context(|| format!("component {} has an invalid schema", component.name))?;
+        let defaults = serde_json::from_value(schema_defaults(&parsed))?;
+        registry.insert(
+            component.name.clone(),
+            ComponentStub {
+                schema: schema.clone(),
+                defaults,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Top-level property defaults declared by a node config schema, or null when there are none.
+fn schema_defaults(schema: &JsonValue) -> JsonValue {
+    let defaults = schema
+        .get("properties")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, property)| Some((key.clone(), property.get("default")?.clone())))
+        .collect::<Map<_, _>>();
+    if defaults.is_empty() {
+        JsonValue::Null
+    } else {
+        JsonValue::Object(defaults)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::schema_defaults;
+    use serde_json::json;
+
+    #[test]
+    fn defaults_come_from_top_level_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "greeting": { "type": "string", "default": "hi" },
+                "name": { "type": "string" }
+            }
+        });
+        assert_eq!(schema_defaults(&schema), json!({ "greeting": "hi" }));
+        assert!(schema_defaults(&json!({ "type": "object" })).is_null());
+    }
+}
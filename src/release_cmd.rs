@@ -17,8 +17,8 @@ use time::format_description::well_known::Rfc3339;
 use crate::cli::{
     ReleaseGenerateArgs, ReleaseLatestArgs, ReleasePromoteArgs, ReleasePublishArgs, ReleaseViewArgs,
 };
-use crate::install::block_on_maybe_runtime;
 use crate::passthrough::{ToolchainChannel, delegated_binary_name_for_channel};
+use crate::runtime;
 use crate::toolchain_catalogue::GREENTIC_TOOLCHAIN_PACKAGES;
 
 const DEFAULT_OAUTH_USER: &str = "oauth2";
@@ -49,7 +49,7 @@ pub struct ToolchainPackage {
 
 pub fn generate(args: ReleaseGenerateArgs) -> Result<()> {
     let resolver = CargoSearchVersionResolver;
-    let source = block_on_maybe_runtime(load_source_manifest(
+    let source = runtime::block_on(load_source_manifest(
         &args.repo,
         &args.from,
         args.token.as_deref(),
@@ -112,7 +112,7 @@ fn bootstrap_source_manifest_if_needed<R: CrateVersionResolver>(
         }
         auth => auth,
     };
-    block_on_maybe_runtime(async {
+    runtime::block_on(async {
         let client = oci_client();
         let source_ref = parse_reference(repo, tag)?;
         push_manifest_layer(&client, &source_ref, &auth, &manifest).await
@@ -149,7 +149,7 @@ pub fn publish(args: ReleasePublishArgs) -> Result<()> {
     }
 
     let auth = registry_auth(args.token.as_deref())?;
-    block_on_maybe_runtime(async {
+    runtime::block_on(async {
         let client = oci_client();
         let release_ref = parse_reference(&args.repo, &release)?;
         if !args.force && manifest_exists(&client, &release_ref, &auth).await? {
@@ -210,7 +210,7 @@ fn publish_manifest_input(
         .context("pass --release or --manifest")?;
     let from = args.from.as_deref().unwrap_or("latest");
     let resolver = CargoSearchVersionResolver;
-    let source = block_on_maybe_runtime(load_source_manifest(
+    let source = runtime::block_on(load_source_manifest(
         &args.repo,
         from,
         args.token.as_deref(),
@@ -255,7 +255,7 @@ pub fn promote(args: ReleasePromoteArgs) -> Result<()> {
     }
 
     let auth = registry_auth(args.token.as_deref())?;
-    block_on_maybe_runtime(async {
+    runtime::block_on(async {
         let client = oci_client();
         let source_ref = parse_reference(&args.repo, &args.release)?;
         let target_ref = parse_reference(&args.repo, &args.tag)?;
@@ -289,7 +289,7 @@ pub fn promote(args: ReleasePromoteArgs) -> Result<()> {
 
 pub fn view(args: ReleaseViewArgs) -> Result<()> {
     let tag = release_view_tag(&args)?;
-    let manifest = block_on_maybe_runtime(load_source_manifest(
+    let manifest = runtime::block_on(load_source_manifest(
         &args.repo,
         &tag,
         args.token.as_deref(),
@@ -322,7 +322,7 @@ pub fn latest(args: ReleaseLatestArgs) -> Result<()> {
     }
 
     let auth = registry_auth(args.token.as_deref())?;
-    block_on_maybe_runtime(async {
+    runtime::block_on(async {
         let client = oci_client();
         let latest_ref = parse_reference(&args.repo, "latest")?;
         if !args.force && manifest_exists(&client, &latest_ref, &auth).await? {
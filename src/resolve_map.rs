@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::component_manifest::{self, blake3_digest};
+use crate::passthrough::{ToolchainChannel, resolve_binary_for_channel};
+use crate::state_lock::write_atomic;
+use crate::watch_cmd;
+
+/// Workspace file naming where the resolver finds components other than the component directory.
+pub const RESOLVE_MAP_PATH: &str = ".greentic/resolve.toml";
+/// Components built from Cargo projects, one directory per component, relative to the workspace
+/// root.
+pub const CARGO_CACHE_DIR: &str = ".greentic/cache/cargo";
+const TARGET: &str = "wasm32-wasip2";
+const STAGED_WASM: &str = "component.wasm";
+const FINGERPRINT: &str = "fingerprint";
+// The parts of a Cargo project whose contents decide whether it is built again.
+const SOURCES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "build.rs",
+    "component.manifest.json",
+    "src",
+    "wit",
+];
+
+/// `.greentic/resolve.toml`:
+///
+/// ```toml
+/// [components]
+/// "dev.acme.greeter" = { path = "../greeter" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResolveMap {
+    #[serde(default)]
+    pub components: BTreeMap<String, ComponentSource>,
+}
+
+/// A Cargo project with a `component.manifest.json` at its root, built for `wasm32-wasip2` when
+/// the component is resolved. A relative path is relative to the workspace root.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentSource {
+    pub path: PathBuf,
+}
+
+impl ResolveMap {
+    /// Read the map; without the file, nothing is mapped.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("invalid {}", path.display()))
+    }
+}
+
+/// A component built from its Cargo project and staged for `prepare_component`.
+#[derive(Debug, Clone)]
+pub struct CargoBuild {
+    /// Holds the project's manifest, pointing at the built wasm, and the wasm.
+    pub dir: PathBuf,
+    /// Whether cargo ran; the staged build is reused while the project's sources are unchanged.
+    pub built: bool,
+    /// The project files the build depends on.
+    pub sources: Vec<PathBuf>,
+}
+
+/// Build the component `name` from `project` into `<cache_dir>/<name>`, unless the build staged
+/// there is from the same sources.
+pub fn build(name: &str, project: &Path, cache_dir: &Path) -> Result<CargoBuild> {
+    if !project.join("Cargo.toml").is_file() {
+        bail!(
+            "{} is not a Cargo project (no Cargo.toml)",
+            project.display()
+        );
+    }
+    let manifest_path = component_manifest::manifest_path(project);
+    if !manifest_path.is_file() {
+        bail!("{} has no {}", project.display(), manifest_path.display());
+    }
+    let sources = source_files(project)?;
+    let fingerprint = fingerprint(project, &sources)?;
+    let dir = cache_dir.join(name);
+    let staged = dir.join(STAGED_WASM).is_file()
+        && component_manifest::manifest_path(&dir).is_file()
+        && fs::read_to_string(dir.join(FINGERPRINT)).is_ok_and(|staged| staged == fingerprint);
+    if staged {
+        return Ok(CargoBuild {
+            dir,
+            built: false,
+            sources,
+        });
+    }
+
+    let wasm = cargo_build(project)?;
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    fs::copy(&wasm, dir.join(STAGED_WASM))
+        .with_context(|| format!("failed to copy {}", wasm.display()))?;
+    let mut manifest = component_manifest::read_manifest(&manifest_path)?;
+    manifest["artifacts"]["component_wasm"] = JsonValue::from(STAGED_WASM);
+    manifest["hashes"]["component_wasm"] = JsonValue::from(blake3_digest(&dir.join(STAGED_WASM))?);
+    component_manifest::write_manifest(&component_manifest::manifest_path(&dir), &manifest)?;
+    write_atomic(&dir.join(FINGERPRINT), fingerprint)?;
+    Ok(CargoBuild {
+        dir,
+        built: true,
+        sources,
+    })
+}
+
+// Every existing file under the project's `SOURCES`, sorted.
+fn source_files(project: &Path) -> Result<Vec<PathBuf>> {
+    Ok(watch_cmd::scan(project)?
+        .into_keys()
+        .filter(|relative| {
+            relative
+                .components()
+                .next()
+                .is_some_and(|first| SOURCES.iter().any(|source| first.as_os_str() == *source))
+        })
+        .map(|relative| project.join(relative))
+        .collect())
+}
+
+// blake3 over each source's path (relative to the project) and contents, and the build target.
+fn fingerprint(project: &Path, sources: &[PathBuf]) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(TARGET.as_bytes());
+    for source in sources {
+        let relative = source.strip_prefix(project).unwrap_or(source);
+        let contents =
+            fs::read(source).with_context(|| format!("failed to read {}", source.display()))?;
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&(contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+// `cargo build --release --target wasm32-wasip2`; the wasm is the `.wasm` artifact cargo reports.
+// Diagnostics go to the terminal as usual.
+fn cargo_build(project: &Path) -> Result<PathBuf> {
+    let cargo = resolve_binary_for_channel("cargo", ToolchainChannel::Stable)?;
+    let manifest = project.join("Cargo.toml");
+    let output = Command::new(cargo)
+        .args(["build", "--release", "--target", TARGET])
+        .args([
+            "--message-format",
+            "json-render-diagnostics",
+            "--manifest-path",
+        ])
+        .arg(&manifest)
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("failed to run cargo for {}", manifest.display()))?;
+    if !output.status.success() {
+        bail!(
+            "`cargo build --release --target {TARGET}` failed for {}",
+            manifest.display()
+        );
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<JsonValue>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .flat_map(|message| message["filenames"].as_array().cloned().unwrap_or_default())
+        .filter_map(|file| file.as_str().map(PathBuf::from))
+        .rfind(|file| {
+            file.extension()
+                .is_some_and(|extension| extension == "wasm")
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "cargo built no .wasm for {} (is the crate a cdylib?)",
+                manifest.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_follow_source_contents_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path();
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::create_dir_all(project.join("target")).unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"greeter\"\n",
+        )
+        .unwrap();
+        fs::write(project.join("src/lib.rs"), "// v1\n").unwrap();
+        let first = fingerprint(project, &source_files(project).unwrap()).unwrap();
+
+        // Build output is not a source.
+        fs::write(project.join("target/out.wasm"), "wasm").unwrap();
+        assert_eq!(
+            fingerprint(project, &source_files(project).unwrap()).unwrap(),
+            first
+        );
+
+        fs::write(project.join("src/lib.rs"), "// v2\n").unwrap();
+        assert_ne!(
+            fingerprint(project, &source_files(project).unwrap()).unwrap(),
+            first
+        );
+    }
+
+    #[test]
+    fn maps_components_to_cargo_projects() {
+        let map: ResolveMap =
+            toml::from_str("[components]\n\"dev.acme.greeter\" = { path = \"../greeter\" }\n")
+                .unwrap();
+        assert_eq!(
+            map.components["dev.acme.greeter"].path,
+            PathBuf::from("../greeter")
+        );
+        assert!(toml::from_str::<ResolveMap>("[components]\nx = { git = \"url\" }\n").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! `pack run --capture`: limit how much of each node's output ends up in the `--json` trace, so
+//! runs over large or sensitive payloads do not copy them into logs and CI artifacts.
+
+use std::ffi::OsString;
+
+use anyhow::{Context, Result, anyhow, bail};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::config::CaptureDefaults;
+
+/// Capture settings as written on the command line or in `[defaults.capture]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureSpec {
+    pub nodes: Option<Vec<String>>,
+    pub fields: Option<Vec<String>>,
+    pub max_bytes: Option<usize>,
+}
+
+impl CaptureSpec {
+    /// Parse one `--capture nodes=<glob>,fields=<jsonpath>,max-bytes=<n>` value into `self`.
+    /// `nodes` and `fields` may repeat; each occurrence adds a pattern.
+    fn parse_into(&mut self, value: &str) -> Result<()> {
+        for part in value
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("--capture expects key=value pairs, got `{part}`"))?;
+            match key.trim() {
+                "nodes" => self
+                    .nodes
+                    .get_or_insert_with(Vec::new)
+                    .push(value.to_string()),
+                "fields" => self
+                    .fields
+                    .get_or_insert_with(Vec::new)
+                    .push(value.to_string()),
+                "max-bytes" => {
+                    self.max_bytes = Some(value.parse().with_context(|| {
+                        format!("--capture max-bytes must be a byte count, got `{value}`")
+                    })?);
+                }
+                other => {
+                    bail!("unknown --capture key `{other}` (expected nodes, fields or max-bytes)")
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill the settings the command line left out from the config defaults.
+    pub fn with_defaults(self, defaults: &CaptureDefaults) -> Self {
+        Self {
+            nodes: self.nodes.or_else(|| defaults.nodes.clone()),
+            fields: self.fields.or_else(|| defaults.fields.clone()),
+            max_bytes: self.max_bytes.or(defaults.max_bytes),
+        }
+    }
+
+    pub fn compile(&self) -> Result<CaptureFilter> {
+        let nodes = match &self.nodes {
+            Some(patterns) => {
+                let mut builder = GlobSetBuilder::new();
+                for pattern in patterns {
+                    builder
+                        .add(Glob::new(pattern).with_context(|| {
+                            format!("invalid --capture nodes glob `{pattern}`")
+                        })?);
+                }
+                Some(builder.build()?)
+            }
+            None => None,
+        };
+        let fields = self
+            .fields
+            .iter()
+            .flatten()
+            .map(|path| FieldPath::parse(path).map(|parsed| (path.clone(), parsed)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CaptureFilter {
+            nodes,
+            fields,
+            max_bytes: self.max_bytes,
+        })
+    }
+}
+
+/// Split every `--capture <SPEC>` out of `pack run` arguments; the runner never sees them.
+pub fn take_capture(run_args: &[OsString]) -> Result<(Option<CaptureSpec>, Vec<OsString>)> {
+    let mut spec: Option<CaptureSpec> = None;
+    let mut rest = Vec::with_capacity(run_args.len());
+    let mut iter = run_args.iter();
+    while let Some(arg) = iter.next() {
+        let value = match arg.to_str() {
+            Some("--capture") => match iter.next().and_then(|value| value.to_str()) {
+                Some(value) => value,
+                None => bail!("--capture needs a value such as nodes=<glob>,max-bytes=<n>"),
+            },
+            Some(arg) if arg.starts_with("--capture=") => &arg["--capture=".len()..],
+            _ => {
+                rest.push(arg.clone());
+                continue;
+            }
+        };
+        spec.get_or_insert_with(CaptureSpec::default)
+            .parse_into(value)?;
+    }
+    Ok((spec, rest))
+}
+
+/// Compiled capture settings applied to the trace of a run result.
+#[derive(Debug)]
+pub struct CaptureFilter {
+    nodes: Option<GlobSet>,
+    fields: Vec<(String, FieldPath)>,
+    max_bytes: Option<usize>,
+}
+
+impl CaptureFilter {
+    /// Rewrite the `output` of every trace entry:
+    /// - nodes that match no `nodes` glob lose their output;
+    /// - with `fields`, the output becomes an object from each path to what it selects (paths
+    ///   that select nothing are left out, wildcard paths give an array);
+    /// - an output still larger than `max-bytes` as JSON is dropped and its size recorded in
+    ///   `output_truncated`.
+    pub fn apply(&self, result: &mut JsonValue) {
+        let Some(trace) = result.get_mut("trace").and_then(JsonValue::as_array_mut) else {
+            return;
+        };
+        for entry in trace.iter_mut().filter_map(JsonValue::as_object_mut) {
+            let node_id = entry
+                .get("node_id")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default();
+            if let Some(nodes) = &self.nodes
+                && !nodes.is_match(node_id)
+            {
+                entry.remove("output");
+                continue;
+            }
+            let Some(output) = entry.remove("output") else {
+                continue;
+            };
+            let output = if self.fields.is_empty() {
+                output
+            } else {
+                JsonValue::Object(
+                    self.fields
+                        .iter()
+                        .filter_map(|(raw, path)| {
+                            path.select(&output).map(|value| (raw.clone(), value))
+                        })
+                        .collect::<Map<_, _>>(),
+                )
+            };
+            let size = serde_json::to_vec(&output).map_or(0, |bytes| bytes.len());
+            match self.max_bytes {
+                Some(max) if size > max => {
+                    entry.insert("output_truncated".to_string(), size.into());
+                }
+                _ => {
+                    entry.insert("output".to_string(), output);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// The JSONPath subset `--capture fields=` understands: `$` followed by `.key`, `['key']`,
+/// `[N]`, `.*` and `[*]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FieldPath {
+    segments: Vec<Segment>,
+}
+
+impl FieldPath {
+    fn parse(raw: &str) -> Result<Self> {
+        let invalid = || anyhow!("unsupported --capture fields path `{raw}`");
+        let mut rest = raw.strip_prefix('$').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                let key = &after[..end];
+                segments.push(match key {
+                    "" => return Err(invalid()),
+                    "*" => Segment::Wildcard,
+                    key => Segment::Key(key.to_string()),
+                });
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(invalid)?;
+                let inner = &after[..end];
+                segments.push(if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|key| key.strip_suffix('\''))
+                    .or_else(|| {
+                        inner
+                            .strip_prefix('"')
+                            .and_then(|key| key.strip_suffix('"'))
+                    })
+                {
+                    Segment::Key(key.to_string())
+                } else {
+                    Segment::Index(inner.parse().map_err(|_| invalid())?)
+                });
+                rest = &after[end + 1..];
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    fn select(&self, value: &JsonValue) -> Option<JsonValue> {
+        let mut matches = vec![value];
+        for segment in &self.segments {
+            matches = matches
+                .into_iter()
+                .flat_map(|value| -> Vec<&JsonValue> {
+                    match (segment, value) {
+                        (Segment::Key(key), JsonValue::Object(map)) => {
+                            map.get(key).into_iter().collect()
+                        }
+                        (Segment::Index(index), JsonValue::Array(items)) => {
+                            items.get(*index).into_iter().collect()
+                        }
+                        (Segment::Wildcard, JsonValue::Object(map)) => map.values().collect(),
+                        (Segment::Wildcard, JsonValue::Array(items)) => items.iter().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+        if self.segments.contains(&Segment::Wildcard) {
+            Some(JsonValue::Array(matches.into_iter().cloned().collect()))
+        } else {
+            matches.first().map(|value| (*value).clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CaptureSpec, FieldPath, take_capture};
+    use crate::config::CaptureDefaults;
+    use serde_json::json;
+    use std::ffi::OsString;
+
+    #[test]
+    fn capture_flags_are_parsed_and_removed() {
+        let args = [
+            "--pack",
+            "demo.gtpack",
+            "--capture",
+            "nodes=http_*,max-bytes=64",
+        ]
+        .into_iter()
+        .chain(["--capture=fields=$.id", "--json"])
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+        let (spec, rest) = take_capture(&args).unwrap();
+        assert_eq!(
+            spec,
+            Some(CaptureSpec {
+                nodes: Some(vec!["http_*".to_string()]),
+                fields: Some(vec!["$.id".to_string()]),
+                max_bytes: Some(64),
+            })
+        );
+        assert_eq!(rest, ["--pack", "demo.gtpack", "--json"]);
+        assert!(take_capture(&[OsString::from("--capture=size=1")]).is_err());
+    }
+
+    #[test]
+    fn command_line_settings_override_config_defaults() {
+        let spec = CaptureSpec {
+            max_bytes: Some(10),
+            ..CaptureSpec::default()
+        }
+        .with_defaults(&CaptureDefaults {
+            nodes: Some(vec!["*".to_string()]),
+            fields: None,
+            max_bytes: Some(4096),
+        });
+        assert_eq!(spec.nodes, Some(vec!["*".to_string()]));
+        assert_eq!(spec.max_bytes, Some(10));
+    }
+
+    #[test]
+    fn field_paths_select_keys_indices_and_wildcards() {
+        let value =
+            json!({"user": {"id": 7, "email": "a@b"}, "items": [{"sku": "x"}, {"sku": "y"}]});
+        let select = |path: &str| FieldPath::parse(path).unwrap().select(&value);
+        assert_eq!(select("$.user.id"), Some(json!(7)));
+        assert_eq!(select("$['user']['email']"), Some(json!("a@b")));
+        assert_eq!(select("$.items[1].sku"), Some(json!("y")));
+        assert_eq!(select("$.items[*].sku"), Some(json!(["x", "y"])));
+        assert_eq!(select("$.missing"), None);
+        assert!(FieldPath::parse("user.id").is_err());
+        assert!(FieldPath::parse("$.items[x]").is_err());
+    }
+
+    #[test]
+    fn filter_rewrites_trace_outputs() {
+        let filter = CaptureSpec {
+            nodes: Some(vec!["fetch_*".to_string()]),
+            fields: Some(vec!["$.id".to_string(), "$.body".to_string()]),
+            max_bytes: Some(32),
+        }
+        .compile()
+        .unwrap();
+        let mut result = json!({
+            "status": "succeeded",
+            "trace": [
+                {"node_id": "fetch_user", "status": "succeeded", "output": {"id": 1, "secret": "s"}},
+                {"node_id": "fetch_page", "status": "succeeded", "output": {"id": 2, "body": "x".repeat(64)}},
+                {"node_id": "render", "status": "succeeded", "output": {"html": "<p>"}},
+            ],
+        });
+        filter.apply(&mut result);
+        assert_eq!(result["trace"][0]["output"], json!({"$.id": 1}));
+        assert_eq!(result["trace"][1].get("output"), None);
+        assert_eq!(result["trace"][1]["output_truncated"], json!(86));
+        assert_eq!(result["trace"][2].get("output"), None);
+    }
+}
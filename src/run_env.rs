@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+use crate::pack_preflight::read_env_file;
+
+/// Split every `--env-file <FILE>` out of `pack run` arguments; the runner never sees them.
+pub fn take_env_files(run_args: &[OsString]) -> Result<(Vec<PathBuf>, Vec<OsString>)> {
+    let mut files = Vec::new();
+    let mut rest = Vec::with_capacity(run_args.len());
+    let mut iter = run_args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.to_str() {
+            Some("--env-file") => match iter.next() {
+                Some(path) => files.push(PathBuf::from(path)),
+                None => bail!("--env-file needs a file"),
+            },
+            Some(arg) if arg.starts_with("--env-file=") => {
+                files.push(PathBuf::from(&arg["--env-file=".len()..]));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((files, rest))
+}
+
+/// The variables of dotenv-style `files`, in order; a later file overrides an earlier one.
+pub fn load(files: &[PathBuf]) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for file in files {
+        vars.extend(read(file)?);
+    }
+    Ok(vars)
+}
+
+fn read(path: &Path) -> Result<BTreeMap<String, String>> {
+    let vars = read_env_file(path)?;
+    if let Some(key) = vars.keys().find(|key| !is_env_name(key)) {
+        bail!("{}: `{key}` is not a valid variable name", path.display());
+    }
+    Ok(vars)
+}
+
+pub(crate) fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn env_files_are_taken_out_of_the_runner_arguments() {
+        let (files, rest) = take_env_files(&args(&[
+            "--pack",
+            "p.gtpack",
+            "--env-file",
+            ".env",
+            "--env-file=.env.dev",
+            "--json",
+        ]))
+        .unwrap();
+        assert_eq!(files, [PathBuf::from(".env"), PathBuf::from(".env.dev")]);
+        assert_eq!(rest, args(&["--pack", "p.gtpack", "--json"]));
+        assert!(take_env_files(&args(&["--env-file"])).is_err());
+    }
+
+    #[test]
+    fn later_files_override_earlier_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join(".env");
+        let dev = dir.path().join(".env.dev");
+        fs::write(&base, "API_URL=https://prod\nAPI_TOKEN=\"base\"\n").unwrap();
+        fs::write(&dev, "# local overrides\nexport API_TOKEN=dev\n").unwrap();
+        let vars = load(&[base.clone(), dev]).unwrap();
+        assert_eq!(vars["API_URL"], "https://prod");
+        assert_eq!(vars["API_TOKEN"], "dev");
+
+        fs::write(&base, "API-TOKEN=x\n").unwrap();
+        let err = load(&[base]).unwrap_err().to_string();
+        assert!(
+            err.contains("`API-TOKEN` is not a valid variable name"),
+            "{err}"
+        );
+    }
+}
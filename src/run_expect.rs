@@ -0,0 +1,221 @@
+//! `pack run --expect-status` / `--expect-output-contains`: quick assertions on a run's result, so
+//! a script can smoke-test a pack without writing `flow test` case files.
+
+use std::ffi::OsString;
+
+use anyhow::{Result, bail};
+use serde_json::Value as JsonValue;
+
+use crate::flow_test;
+use crate::run_result::{CheckedRun, RunReport, RunStatus};
+
+/// What `pack run` was asked to check about the run result.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunExpectations {
+    pub status: Option<RunStatus>,
+    /// `(JSON pointer into the output, value)`: the output must hold the value there.
+    pub output: Vec<(String, JsonValue)>,
+}
+
+impl RunExpectations {
+    /// What about `report` does not match, one line per unmet expectation.
+    pub fn check(&self, report: &RunReport) -> Vec<String> {
+        let mut failures = Vec::new();
+        if let Some(status) = self.status
+            && report.status != status
+        {
+            let mut failure = format!(
+                "status: expected {}, got {}",
+                flow_test::status_name(status),
+                flow_test::status_name(report.status)
+            );
+            if let Some(error) = &report.error {
+                failure.push_str(&format!(" ({}: {})", error.code, error.message));
+            }
+            failures.push(failure);
+        }
+        for (pointer, expected) in &self.output {
+            let path = format!("output{pointer}");
+            match report
+                .output
+                .as_ref()
+                .and_then(|output| output.pointer(pointer))
+            {
+                Some(actual) => flow_test::compare(&path, expected, actual, &mut failures),
+                None => failures.push(format!("{path}: missing")),
+            }
+        }
+        failures
+    }
+
+    /// Report on stderr how the run measured up, and return the exit code: 0 only when the result
+    /// matched the schema and every expectation, whatever the runner itself exited with.
+    pub fn conclude(&self, checked: &CheckedRun) -> i32 {
+        let Some(report) = &checked.report else {
+            return 1;
+        };
+        let failures = self.check(report);
+        if failures.is_empty() {
+            eprintln!("expectations met");
+            return 0;
+        }
+        for failure in &failures {
+            eprintln!("expectation failed: {failure}");
+        }
+        1
+    }
+}
+
+/// Split `--expect-status` and every `--expect-output-contains` out of `pack run` arguments; the
+/// runner never sees them.
+pub fn take_expectations(
+    run_args: &[OsString],
+) -> Result<(Option<RunExpectations>, Vec<OsString>)> {
+    let mut expectations: Option<RunExpectations> = None;
+    let mut rest = Vec::with_capacity(run_args.len());
+    let mut iter = run_args.iter();
+    while let Some(arg) = iter.next() {
+        let Some((flag, value)) = split_flag(arg, &mut iter)? else {
+            rest.push(arg.clone());
+            continue;
+        };
+        let expectations = expectations.get_or_insert_with(RunExpectations::default);
+        match flag {
+            "--expect-status" => {
+                if expectations.status.is_some() {
+                    bail!("--expect-status given more than once");
+                }
+                expectations.status = Some(parse_status(&value)?);
+            }
+            _ => expectations.output.push(parse_output(&value)?),
+        }
+    }
+    Ok((expectations, rest))
+}
+
+// `--flag value` or `--flag=value` for the two expectation flags; `None` for other arguments.
+fn split_flag<'a>(
+    arg: &'a OsString,
+    iter: &mut impl Iterator<Item = &'a OsString>,
+) -> Result<Option<(&'static str, String)>> {
+    const FLAGS: [(&str, &str); 2] = [
+        ("--expect-status", "Success or Failure"),
+        (
+            "--expect-output-contains",
+            "<json-pointer>=<value>, such as /greeting=\"hi\"",
+        ),
+    ];
+    let Some(arg) = arg.to_str() else {
+        return Ok(None);
+    };
+    for (flag, expected) in FLAGS {
+        if arg == flag {
+            return match iter.next().and_then(|value| value.to_str()) {
+                Some(value) => Ok(Some((flag, value.to_string()))),
+                None => bail!("{flag} needs a value: {expected}"),
+            };
+        }
+        if let Some(value) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Ok(Some((flag, value.to_string())));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_status(raw: &str) -> Result<RunStatus> {
+    match raw.to_ascii_lowercase().as_str() {
+        "success" | "succeeded" => Ok(RunStatus::Succeeded),
+        "failure" | "failed" => Ok(RunStatus::Failed),
+        _ => bail!("--expect-status must be Success or Failure, not `{raw}`"),
+    }
+}
+
+// `<pointer>=<value>`. The value is read as JSON when it parses (`3`, `true`, `"hi"`, `{...}`),
+// as a plain string otherwise.
+fn parse_output(raw: &str) -> Result<(String, JsonValue)> {
+    let Some((pointer, value)) = raw.split_once('=') else {
+        bail!("--expect-output-contains needs <json-pointer>=<value>, got `{raw}`");
+    };
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        bail!("`{pointer}` is not a JSON pointer; it must start with `/`");
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| JsonValue::from(value));
+    Ok((pointer.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn expectation_flags_are_taken_out_of_the_runner_args() {
+        let (expectations, rest) = take_expectations(&args(&[
+            "--pack",
+            "a.gtpack",
+            "--expect-status",
+            "Success",
+            "--expect-output-contains",
+            "/greeting=hi",
+            "--expect-output-contains=/count=3",
+        ]))
+        .unwrap();
+        assert_eq!(rest, args(&["--pack", "a.gtpack"]));
+        let expectations = expectations.unwrap();
+        assert_eq!(expectations.status, Some(RunStatus::Succeeded));
+        assert_eq!(
+            expectations.output,
+            [
+                ("/greeting".to_string(), json!("hi")),
+                ("/count".to_string(), json!(3))
+            ]
+        );
+
+        let (expectations, _) = take_expectations(&args(&["--pack", "a.gtpack"])).unwrap();
+        assert!(expectations.is_none());
+        assert!(take_expectations(&args(&["--expect-status", "done"])).is_err());
+        assert!(take_expectations(&args(&["--expect-output-contains", "greeting=hi"])).is_err());
+        assert!(take_expectations(&args(&["--expect-status"])).is_err());
+    }
+
+    #[test]
+    fn unmet_expectations_are_each_reported() {
+        let report: RunReport = serde_json::from_value(json!({
+            "schema_version": 1,
+            "status": "succeeded",
+            "output": {"greeting": "hi", "user": {"name": "Ada", "id": 7}}
+        }))
+        .unwrap();
+        let expectations = RunExpectations {
+            status: Some(RunStatus::Succeeded),
+            output: vec![
+                ("/greeting".to_string(), json!("hi")),
+                ("/user".to_string(), json!({"name": "Ada"})),
+            ],
+        };
+        assert!(expectations.check(&report).is_empty());
+
+        let expectations = RunExpectations {
+            status: Some(RunStatus::Failed),
+            output: vec![
+                ("/greeting".to_string(), json!("bye")),
+                ("/missing".to_string(), json!(1)),
+            ],
+        };
+        assert_eq!(
+            expectations.check(&report),
+            [
+                "status: expected failed, got succeeded",
+                "output/greeting: expected \"bye\", got \"hi\"",
+                "output/missing: missing",
+            ]
+        );
+    }
+}
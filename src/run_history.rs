@@ -0,0 +1,766 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::cli::{RunsCommand, RunsDiffArgs, RunsListArgs, RunsShowArgs};
+use crate::mock_scenario::Scenario;
+use crate::pack_encrypt;
+use crate::run_env;
+use crate::secrets_keyring;
+use crate::state_lock::StateLock;
+
+const RUN_LOG: &str = ".greentic/runs.jsonl";
+const MOCK_ENV_PREFIX: &str = "MOCK_";
+const INPUT_FLAGS: &[&str] = &["--input", "--input-file"];
+/// Stands in for the run input in recorded arguments; a replay is given the input again.
+const REDACTED_INPUT: &str = "<redacted>";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: u64,
+    pub started_at: String,
+    pub pack: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+    pub status: RunStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<String>,
+    /// Arguments forwarded to greentic-runner-cli, kept so the run can be replayed. The input
+    /// is left out; only `input_hash` records it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// `MOCK_*` settings that were in effect for the run.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mocks: BTreeMap<String, String>,
+    /// Mock scenario (`pack run --scenario`) the run used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+        })
+    }
+}
+
+/// Append a finished `pack run` to the local run log. `run_args` are the `pack run` arguments
+/// before `--decrypt-key` and `--env-file` are applied, so the record names the pack as given and
+/// a replay decrypts it and reads the env files again. A scenario's mock settings replace the
+/// ambient ones; its secrets are never recorded.
+pub fn record_pack_run(
+    run_args: &[OsString],
+    scenario: Option<&Scenario>,
+    started_at: OffsetDateTime,
+    duration: Duration,
+    status: &ExitStatus,
+) -> Result<RunRecord> {
+    let mocks = match scenario {
+        Some(scenario) => scenario.mocks.clone(),
+        None => std::env::vars()
+            .filter(|(key, _)| key.starts_with(MOCK_ENV_PREFIX))
+            .collect(),
+    };
+    let scenario = scenario.map(|scenario| scenario.name.clone());
+    record_run(
+        run_args, mocks, scenario, None, started_at, duration, status,
+    )
+}
+
+fn record_run(
+    run_args: &[OsString],
+    mocks: BTreeMap<String, String>,
+    scenario: Option<String>,
+    replay_of: Option<u64>,
+    started_at: OffsetDateTime,
+    duration: Duration,
+    status: &ExitStatus,
+) -> Result<RunRecord> {
+    let log = PathBuf::from(RUN_LOG);
+    let pack = arg_value(run_args, &["--pack", "-p"]);
+    append_record(&log, |id| {
+        Ok(RunRecord {
+            id,
+            started_at: started_at
+                .format(&Rfc3339)
+                .context("format run timestamp")?,
+            pack_digest: pack
+                .as_deref()
+                .and_then(|pack| file_digest(Path::new(pack))),
+            pack: pack.unwrap_or_default(),
+            entry: arg_value(run_args, &["--entry"]),
+            input_hash: input_hash(run_args),
+            status: if status.success() {
+                RunStatus::Succeeded
+            } else {
+                RunStatus::Failed
+            },
+            exit_code: status.code(),
+            duration_ms: duration.as_millis() as u64,
+            artifacts: arg_value(run_args, &["--artifacts"]),
+            args: redact_input(run_args),
+            mocks,
+            scenario,
+            replay_of,
+        })
+    })
+}
+
+/// `pack run --replay <ID> [--input <JSON> | --input-file <FILE>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayRequest {
+    pub id: u64,
+    /// The input flag and its value, given again because the history does not keep inputs.
+    pub input: Option<(String, String)>,
+}
+
+/// The replay requested with `pack run --replay <ID>`, if any. Replays reuse the recorded
+/// arguments verbatim, so arguments other than the input are rejected rather than silently
+/// ignored.
+pub fn replay_request(run_args: &[OsString]) -> Result<Option<ReplayRequest>> {
+    let Some(raw) = arg_value(run_args, &["--replay"]) else {
+        return Ok(None);
+    };
+    let input = INPUT_FLAGS
+        .iter()
+        .find_map(|flag| arg_value(run_args, &[flag]).map(|value| (flag.to_string(), value)));
+    let expected = |flag: &str| {
+        if run_args.iter().any(|arg| arg == flag) {
+            2
+        } else {
+            1
+        }
+    };
+    let input_len = input.as_ref().map_or(0, |(flag, _)| expected(flag));
+    if run_args.len() != expected("--replay") + input_len {
+        bail!("--replay can only be combined with --input or --input-file");
+    }
+    let id = raw
+        .parse()
+        .with_context(|| format!("invalid run id `{raw}`"))?;
+    Ok(Some(ReplayRequest { id, input }))
+}
+
+/// Re-run a recorded `pack run` with the same runner arguments (entry, input, artifacts) and
+/// `MOCK_*` settings, after checking the pack on disk still has the recorded digest.
+pub fn replay(request: ReplayRequest, runner: &Path) -> Result<ExitStatus> {
+    let id = request.id;
+    let original = find_record(Path::new(RUN_LOG), id)?;
+    if original.args.is_empty() {
+        bail!("run {id} was recorded without its runner arguments and cannot be replayed");
+    }
+    let args = restore_input(&original, request.input)?;
+    let current = file_digest(Path::new(&original.pack));
+    if original.pack_digest.is_some() && current != original.pack_digest {
+        bail!(
+            "pack {} changed since run {id} (recorded {}, now {}); rebuild or restore it to replay",
+            original.pack,
+            or_dash(&original.pack_digest),
+            or_dash(&current)
+        );
+    }
+
+    eprintln!(
+        "Replaying run {id} ({} entry {})",
+        original.pack,
+        original.entry.as_deref().unwrap_or("-")
+    );
+    // Kept until the runner exits; the decrypted copy is removed when it drops.
+    let (_decrypted, runner_args) = pack_encrypt::take_decrypt_key(&args)?;
+    let (env_files, runner_args) = run_env::take_env_files(&runner_args)?;
+    let mut command = Command::new(runner);
+    command
+        .args(&runner_args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    // Only the recorded mock settings apply; anything set in the current shell is dropped.
+    for (key, _) in std::env::vars().filter(|(key, _)| key.starts_with(MOCK_ENV_PREFIX)) {
+        command.env_remove(key);
+    }
+    command.envs(&original.mocks);
+    // Secrets are not recorded; take them from the scenario, env files and keychain as they are now.
+    let scenario_secrets = match &original.scenario {
+        Some(name) => Scenario::load(name)?.secrets,
+        None => BTreeMap::new(),
+    };
+    let env = run_env::load(&env_files)?;
+    let keychain = secrets_keyring::runner_env(&runner_args, &|key| {
+        scenario_secrets.contains_key(key)
+            || env.contains_key(key)
+            || std::env::var_os(key).is_some()
+    })?;
+    command.envs(keychain).envs(scenario_secrets).envs(env);
+
+    let started_at = OffsetDateTime::now_utc();
+    let clock = Instant::now();
+    let status = command
+        .status()
+        .with_context(|| format!("failed to execute {}", runner.display()))?;
+    let record = record_run(
+        &args,
+        original.mocks.clone(),
+        original.scenario.clone(),
+        Some(id),
+        started_at,
+        clock.elapsed(),
+        &status,
+    )?;
+    if record.status != original.status {
+        eprintln!(
+            "Replay {} {} but run {id} {}; compare with `greentic-dev runs diff {id} {}`",
+            record.id, record.status, original.status, record.id
+        );
+    }
+    Ok(status)
+}
+
+pub fn run(command: RunsCommand) -> Result<()> {
+    let log = PathBuf::from(RUN_LOG);
+    match command {
+        RunsCommand::List(args) => list(&log, &args),
+        RunsCommand::Show(args) => show(&log, &args),
+        RunsCommand::Diff(args) => diff(&log, &args),
+    }
+}
+
+fn list(log: &Path, args: &RunsListArgs) -> Result<()> {
+    let records = read_log(log)?;
+    let skip = records.len().saturating_sub(args.limit);
+    let recent = records.into_iter().skip(skip).rev().collect::<Vec<_>>();
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&recent)?);
+        return Ok(());
+    }
+    if recent.is_empty() {
+        println!("No runs recorded yet in {}", log.display());
+        return Ok(());
+    }
+    println!(
+        "{:>4}  {:<20}  {:<9}  {:>8}  {:<16}  PACK",
+        "ID", "STARTED", "STATUS", "MS", "ENTRY"
+    );
+    for record in recent {
+        println!(
+            "{:>4}  {:<20}  {:<9}  {:>8}  {:<16}  {}",
+            record.id,
+            record.started_at,
+            record.status,
+            record.duration_ms,
+            record.entry.as_deref().unwrap_or("-"),
+            record.pack
+        );
+    }
+    Ok(())
+}
+
+fn show(log: &Path, args: &RunsShowArgs) -> Result<()> {
+    let record = find_record(log, args.id)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&record)?);
+        return Ok(());
+    }
+    println!("Run {}", record.id);
+    println!("  started:   {}", record.started_at);
+    println!("  pack:      {}", record.pack);
+    println!("  digest:    {}", or_dash(&record.pack_digest));
+    println!("  entry:     {}", or_dash(&record.entry));
+    println!("  input:     {}", or_dash(&record.input_hash));
+    match record.exit_code {
+        Some(code) => println!("  status:    {} (exit {code})", record.status),
+        None => println!("  status:    {}", record.status),
+    }
+    println!("  duration:  {} ms", record.duration_ms);
+    println!("  artifacts: {}", or_dash(&record.artifacts));
+    if let Some(scenario) = &record.scenario {
+        println!("  scenario:  {scenario}");
+    }
+    for (key, value) in &record.mocks {
+        println!("  mock:      {key}={value}");
+    }
+    if let Some(original) = record.replay_of {
+        println!("  replay of: {original}");
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize)]
+struct RunDiff {
+    a: u64,
+    b: u64,
+    fields: BTreeMap<&'static str, (String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artifacts: Option<ArtifactDiff>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ArtifactDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<String>,
+}
+
+fn diff(log: &Path, args: &RunsDiffArgs) -> Result<()> {
+    let a = find_record(log, args.a)?;
+    let b = find_record(log, args.b)?;
+    let diff = diff_records(&a, &b)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+    println!("Run {} -> run {}", a.id, b.id);
+    if diff.fields.is_empty() {
+        println!("  pack, entry, input and outcome are identical");
+    }
+    for (field, (before, after)) in &diff.fields {
+        println!("  {field:<12} {before} -> {after}");
+    }
+    match &diff.artifacts {
+        Some(artifacts) => {
+            for path in &artifacts.added {
+                println!("  + {path}");
+            }
+            for path in &artifacts.removed {
+                println!("  - {path}");
+            }
+            for path in &artifacts.changed {
+                println!("  ~ {path}");
+            }
+        }
+        None if a.artifacts.is_some() && a.artifacts == b.artifacts => {
+            println!("  (both runs wrote to the same artifacts directory; contents not compared)")
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+fn diff_records(a: &RunRecord, b: &RunRecord) -> Result<RunDiff> {
+    let mut diff = RunDiff {
+        a: a.id,
+        b: b.id,
+        ..RunDiff::default()
+    };
+    let mut compare = |field: &'static str, before: String, after: String| {
+        if before != after {
+            diff.fields.insert(field, (before, after));
+        }
+    };
+    compare("pack", a.pack.clone(), b.pack.clone());
+    compare(
+        "pack_digest",
+        or_dash(&a.pack_digest),
+        or_dash(&b.pack_digest),
+    );
+    compare("entry", or_dash(&a.entry), or_dash(&b.entry));
+    compare("input_hash", or_dash(&a.input_hash), or_dash(&b.input_hash));
+    compare("status", a.status.to_string(), b.status.to_string());
+    compare(
+        "exit_code",
+        a.exit_code.map_or("-".into(), |c| c.to_string()),
+        b.exit_code.map_or("-".into(), |c| c.to_string()),
+    );
+    compare("mocks", render_mocks(&a.mocks), render_mocks(&b.mocks));
+    compare("scenario", or_dash(&a.scenario), or_dash(&b.scenario));
+    compare(
+        "duration_ms",
+        a.duration_ms.to_string(),
+        b.duration_ms.to_string(),
+    );
+
+    if let (Some(dir_a), Some(dir_b)) = (&a.artifacts, &b.artifacts)
+        && dir_a != dir_b
+        && Path::new(dir_a).is_dir()
+        && Path::new(dir_b).is_dir()
+    {
+        diff.artifacts = Some(diff_artifacts(Path::new(dir_a), Path::new(dir_b))?);
+    }
+    Ok(diff)
+}
+
+fn diff_artifacts(a: &Path, b: &Path) -> Result<ArtifactDiff> {
+    let before = hash_tree(a)?;
+    let after = hash_tree(b)?;
+    let mut diff = ArtifactDiff::default();
+    for (path, hash) in &after {
+        match before.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(old) if old != hash => diff.changed.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned()
+        .collect();
+    Ok(diff)
+}
+
+fn hash_tree(root: &Path) -> Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Some(digest) = file_digest(&path) {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                hashes.insert(relative.to_string_lossy().replace('\\', "/"), digest);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn find_record(log: &Path, id: u64) -> Result<RunRecord> {
+    read_log(log)?
+        .into_iter()
+        .find(|record| record.id == id)
+        .ok_or_else(|| anyhow!("no run with id {id} in {}", log.display()))
+}
+
+fn read_log(log: &Path) -> Result<Vec<RunRecord>> {
+    if !log.exists() {
+        return Ok(Vec::new());
+    }
+    let raw =
+        fs::read_to_string(log).with_context(|| format!("failed to read {}", log.display()))?;
+    raw.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{} is not a valid run record", log.display(), idx + 1))
+        })
+        .collect()
+}
+
+// The next id is read and the record appended under one lock, so concurrent runs never share an
+// id.
+fn append_record(log: &Path, record: impl FnOnce(u64) -> Result<RunRecord>) -> Result<RunRecord> {
+    let parent = log.parent().unwrap_or_else(|| Path::new("."));
+    let _lock = StateLock::acquire(parent)?;
+    let id = read_log(log)?.last().map_or(1, |record| record.id + 1);
+    let record = record(id)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .with_context(|| format!("failed to open {}", log.display()))?;
+    writeln!(file, "{}", serde_json::to_string(&record)?)
+        .with_context(|| format!("failed to write {}", log.display()))?;
+    Ok(record)
+}
+
+// sha256 of the inline input, or of the input file's contents.
+fn input_hash(run_args: &[OsString]) -> Option<String> {
+    if let Some(input) = arg_value(run_args, &["--input"]) {
+        return Some(format!("sha256:{}", sha256_hex(input.as_bytes())));
+    }
+    arg_value(run_args, &["--input-file"]).and_then(|path| file_digest(Path::new(&path)))
+}
+
+// The arguments with the value of each input flag replaced by `REDACTED_INPUT`.
+fn redact_input(run_args: &[OsString]) -> Vec<String> {
+    let mut args = Vec::with_capacity(run_args.len());
+    let mut iter = run_args
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned());
+    while let Some(arg) = iter.next() {
+        if INPUT_FLAGS.contains(&arg.as_str()) {
+            args.push(arg);
+            if iter.next().is_some() {
+                args.push(REDACTED_INPUT.to_string());
+            }
+        } else if let Some(flag) = INPUT_FLAGS
+            .iter()
+            .find(|flag| arg.starts_with(&format!("{flag}=")))
+        {
+            args.push(format!("{flag}={REDACTED_INPUT}"));
+        } else {
+            args.push(arg);
+        }
+    }
+    args
+}
+
+// The recorded arguments with the input the replay was given in place of the redacted one. The
+// input must be the one the original run had.
+fn restore_input(original: &RunRecord, input: Option<(String, String)>) -> Result<Vec<OsString>> {
+    let id = original.id;
+    let redacted = original
+        .args
+        .iter()
+        .any(|arg| arg == REDACTED_INPUT || arg.ends_with(&format!("={REDACTED_INPUT}")));
+    let Some((flag, value)) = input else {
+        if redacted {
+            bail!(
+                "run {id} had an input, which the history does not keep; pass it again with --input or --input-file"
+            );
+        }
+        return Ok(original.args.iter().map(OsString::from).collect());
+    };
+    let Some(recorded) = &original.input_hash else {
+        bail!("run {id} had no input");
+    };
+    let given = input_hash(&[OsString::from(&flag), OsString::from(&value)]);
+    if given.as_ref() != Some(recorded) {
+        bail!(
+            "the input differs from run {id}'s (recorded {recorded}, given {})",
+            or_dash(&given)
+        );
+    }
+    let mut args = Vec::with_capacity(original.args.len());
+    let mut iter = original.args.iter();
+    while let Some(arg) = iter.next() {
+        let separate = INPUT_FLAGS.contains(&arg.as_str());
+        let inline = INPUT_FLAGS
+            .iter()
+            .any(|input| arg.starts_with(&format!("{input}=")));
+        if !separate && !inline {
+            args.push(OsString::from(arg));
+            continue;
+        }
+        if separate {
+            iter.next();
+        }
+        args.push(OsString::from(&flag));
+        args.push(OsString::from(&value));
+    }
+    Ok(args)
+}
+
+// Accepts both `--flag value` and `--flag=value`.
+pub(crate) fn arg_value(args: &[OsString], names: &[&str]) -> Option<String> {
+    let mut iter = args.iter().filter_map(|arg| arg.to_str());
+    while let Some(arg) = iter.next() {
+        for name in names {
+            if arg == *name {
+                return iter.next().map(str::to_string);
+            }
+            if let Some(value) = arg.strip_prefix(&format!("{name}=")) {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+fn file_digest(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    Some(format!("sha256:{}", sha256_hex(&bytes)))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn render_mocks(mocks: &BTreeMap<String, String>) -> String {
+    if mocks.is_empty() {
+        return "-".to_string();
+    }
+    mocks
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn or_dash(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        REDACTED_INPUT, ReplayRequest, RunRecord, RunStatus, append_record, arg_value,
+        diff_records, input_hash, read_log, redact_input, replay_request, restore_input,
+    };
+    use std::ffi::OsString;
+
+    fn record(id: u64, status: RunStatus, artifacts: Option<String>) -> RunRecord {
+        RunRecord {
+            id,
+            started_at: "2024-01-01T00:00:00Z".to_string(),
+            pack: "demo.gtpack".to_string(),
+            pack_digest: Some("sha256:aa".to_string()),
+            entry: Some("main".to_string()),
+            input_hash: None,
+            status,
+            exit_code: Some(if status == RunStatus::Succeeded { 0 } else { 1 }),
+            duration_ms: 10,
+            artifacts,
+            args: vec!["--pack".to_string(), "demo.gtpack".to_string()],
+            mocks: Default::default(),
+            scenario: None,
+            replay_of: None,
+        }
+    }
+
+    #[test]
+    fn arg_values_support_both_spellings() {
+        let args = ["--pack", "demo.gtpack", "--entry=main", "--offline"]
+            .iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            arg_value(&args, &["--pack", "-p"]).as_deref(),
+            Some("demo.gtpack")
+        );
+        assert_eq!(arg_value(&args, &["--entry"]).as_deref(), Some("main"));
+        assert_eq!(arg_value(&args, &["--input"]), None);
+    }
+
+    #[test]
+    fn replay_must_stand_alone() {
+        let parse = |values: &[&str]| {
+            let args = values.iter().map(OsString::from).collect::<Vec<_>>();
+            replay_request(&args)
+        };
+        let id = |values: &[&str]| parse(values).unwrap().map(|request| request.id);
+        assert_eq!(id(&["--replay", "7"]), Some(7));
+        assert_eq!(id(&["--replay=7"]), Some(7));
+        assert_eq!(id(&["--pack", "demo.gtpack"]), None);
+        assert_eq!(
+            parse(&["--replay", "7", "--input", "{}"]).unwrap(),
+            Some(ReplayRequest {
+                id: 7,
+                input: Some(("--input".to_string(), "{}".to_string())),
+            })
+        );
+        assert!(parse(&["--replay", "7", "--input-file=in.json"]).is_ok());
+        assert!(parse(&["--replay", "7", "--offline"]).is_err());
+        assert!(parse(&["--replay", "7", "--input", "{}", "--offline"]).is_err());
+        assert!(parse(&["--replay", "latest"]).is_err());
+    }
+
+    #[test]
+    fn inputs_are_redacted_and_given_again_on_replay() {
+        let args = ["--pack", "demo.gtpack", "--input", r#"{"token":"s3cret"}"#]
+            .iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        let mut original = record(1, RunStatus::Succeeded, None);
+        original.args = redact_input(&args);
+        original.input_hash = input_hash(&args);
+        assert_eq!(
+            original.args,
+            ["--pack", "demo.gtpack", "--input", REDACTED_INPUT]
+        );
+        assert_eq!(
+            redact_input(&[OsString::from("--input-file=in.json")]),
+            [format!("--input-file={REDACTED_INPUT}")]
+        );
+
+        let err = restore_input(&original, None).unwrap_err();
+        assert!(err.to_string().contains("pass it again"), "{err}");
+        let err =
+            restore_input(&original, Some(("--input".to_string(), "{}".to_string()))).unwrap_err();
+        assert!(err.to_string().contains("differs from run 1"), "{err}");
+        let restored = restore_input(
+            &original,
+            Some(("--input".to_string(), r#"{"token":"s3cret"}"#.to_string())),
+        )
+        .unwrap();
+        assert_eq!(restored, args);
+    }
+
+    #[test]
+    fn records_without_replay_fields_still_parse() {
+        let line = r#"{"id":1,"started_at":"2024-01-01T00:00:00Z","pack":"demo.gtpack","status":"succeeded","duration_ms":5}"#;
+        let record: RunRecord = serde_json::from_str(line).unwrap();
+        assert!(record.args.is_empty() && record.mocks.is_empty());
+    }
+
+    #[test]
+    fn log_round_trips_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join(".greentic/runs.jsonl");
+        assert!(read_log(&log).unwrap().is_empty());
+        append_record(&log, |id| Ok(record(id, RunStatus::Succeeded, None))).unwrap();
+        append_record(&log, |id| Ok(record(id, RunStatus::Failed, None))).unwrap();
+        let records = read_log(&log).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].status, RunStatus::Failed);
+        assert_eq!(records[1].id, 2);
+    }
+
+    #[test]
+    fn concurrent_appends_get_distinct_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join(".greentic/runs.jsonl");
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    append_record(&log, |id| Ok(record(id, RunStatus::Succeeded, None))).unwrap()
+                });
+            }
+        });
+        let mut ids = read_log(&log)
+            .unwrap()
+            .iter()
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn diff_reports_changed_fields_and_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a_dir, b_dir) = (dir.path().join("a"), dir.path().join("b"));
+        std::fs::create_dir_all(&a_dir).unwrap();
+        std::fs::create_dir_all(&b_dir).unwrap();
+        std::fs::write(a_dir.join("out.json"), "1").unwrap();
+        std::fs::write(b_dir.join("out.json"), "2").unwrap();
+        std::fs::write(b_dir.join("trace.json"), "{}").unwrap();
+
+        let a = record(1, RunStatus::Succeeded, Some(a_dir.display().to_string()));
+        let b = record(2, RunStatus::Failed, Some(b_dir.display().to_string()));
+        let diff = diff_records(&a, &b).unwrap();
+        assert_eq!(
+            diff.fields["status"],
+            ("succeeded".to_string(), "failed".to_string())
+        );
+        assert!(!diff.fields.contains_key("entry"));
+        let artifacts = diff.artifacts.unwrap();
+        assert_eq!(artifacts.changed, ["out.json"]);
+        assert_eq!(artifacts.added, ["trace.json"]);
+        assert!(artifacts.removed.is_empty());
+    }
+}
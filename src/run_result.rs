@@ -0,0 +1,274 @@
+//! The versioned JSON contract for `pack run --json` output. greentic-dev checks the runner's
+//! result against the embedded schema before printing it, so tooling reading that output can rely
+//! on its shape whichever runner version produced it.
+
+use std::ffi::OsString;
+use std::io::Read;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::run_capture::CaptureFilter;
+use crate::run_usage::{self, Usage};
+
+/// Version of the result contract; bumped on any incompatible change to the schema.
+pub const SCHEMA_VERSION: u64 = 1;
+pub const SCHEMA: &str = include_str!("../schemas/pack-run-result.schema.json");
+
+/// How a `pack run --json` invocation ended.
+#[derive(Debug)]
+pub struct CheckedRun {
+    pub status: ExitStatus,
+    /// Whether the runner's output matched the result schema (and was printed).
+    pub conforms: bool,
+    /// The printed result, when it matched.
+    pub report: Option<RunReport>,
+}
+
+impl CheckedRun {
+    /// Exit code for greentic-dev: the runner's, or 1 when a successful run broke the contract.
+    pub fn exit_code(&self) -> i32 {
+        match self.status.code() {
+            Some(0) if !self.conforms => 1,
+            code => code.unwrap_or(1),
+        }
+    }
+}
+
+/// A result that matched the schema, as types. Fields the schema leaves optional are `None` or
+/// empty when the runner left them out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub schema_version: u64,
+    pub status: RunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<JsonValue>,
+    #[serde(default)]
+    pub trace: Vec<TraceEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RunError>,
+    /// The run's total resource usage; see [`run_usage::total`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub node_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    pub status: TraceStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<JsonValue>,
+    /// Size in bytes of an output dropped by `--capture max-bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_truncated: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RunError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunError {
+    pub code: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<JsonValue>,
+}
+
+/// `pack run --output-schema`: print the schema instead of running anything.
+pub fn wants_schema(run_args: &[OsString]) -> bool {
+    run_args.iter().any(|arg| arg == "--output-schema")
+}
+
+/// Only machine-readable runs are held to the contract; human output passes through untouched.
+pub fn wants_json(run_args: &[OsString]) -> bool {
+    run_args.iter().any(|arg| arg == "--json")
+}
+
+pub fn print_schema() {
+    print!("{SCHEMA}");
+}
+
+/// Run the runner with its stdout captured, then print the result only when it matches the
+/// schema, with `capture` applied to its trace and its total usage added. The per-node usage
+/// summary goes to stderr. A mismatch is reported on stderr instead.
+pub fn run_checked(command: Command, capture: &CaptureFilter) -> Result<CheckedRun> {
+    let (status, stdout, wall) = capture_stdout(command)?;
+    match check(&stdout) {
+        Ok(mut result) => {
+            capture.apply(&mut result);
+            run_usage::total(&mut result, wall);
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            let report = serde_json::from_value::<RunReport>(result).ok();
+            if let Some(report) = &report {
+                eprint!("{}", run_usage::summary(report));
+            }
+            Ok(CheckedRun {
+                status,
+                conforms: true,
+                report,
+            })
+        }
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            if !stdout.trim().is_empty() {
+                eprintln!("runner output was:\n{}", stdout.trim_end());
+            }
+            Ok(CheckedRun {
+                status,
+                conforms: false,
+                report: None,
+            })
+        }
+    }
+}
+
+/// Run the runner with its stdout captured and return its result without printing anything. A
+/// failed flow is a report with [`RunStatus::Failed`]; output that breaks the contract is an error.
+pub fn run_report(command: Command) -> Result<RunReport> {
+    let (status, stdout, wall) = capture_stdout(command)?;
+    let mut result = check(&stdout).with_context(|| {
+        format!(
+            "greentic-runner-cli exited with {status}; its output was:\n{}",
+            stdout.trim_end()
+        )
+    })?;
+    run_usage::total(&mut result, wall);
+    serde_json::from_value(result).context("failed to read the pack run result")
+}
+
+/// The runner's exit status and stdout, and how long it ran.
+fn capture_stdout(mut command: Command) -> Result<(ExitStatus, String, Duration)> {
+    let started = Instant::now();
+    let mut child = command
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("failed to start greentic-runner-cli")?;
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .expect("runner stdout is piped")
+        .read_to_string(&mut stdout)
+        .context("failed to read the runner's output")?;
+    let status = crate::interrupt::wait_child(child).context("failed to wait for the runner")?;
+    Ok((status, stdout, started.elapsed()))
+}
+
+/// [`check`] a runner result and read it into a [`RunReport`].
+pub fn parse(stdout: &str) -> Result<RunReport> {
+    serde_json::from_value(check(stdout)?).context("failed to read the pack run result")
+}
+
+/// Parse and validate a runner result, stamping `schema_version` when the runner left it out.
+pub fn check(stdout: &str) -> Result<JsonValue> {
+    let mut result: JsonValue = serde_json::from_str(stdout.trim())
+        .context("runner output is not a JSON document (pack run result v1)")?;
+    let schema: JsonValue =
+        serde_json::from_str(SCHEMA).context("embedded run result schema is invalid JSON")?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|err| anyhow!("embedded run result schema does not compile: {err}"))?;
+    let errors = validator
+        .iter_errors(&result)
+        .map(|err| {
+            let path = err.instance_path().to_string();
+            if path.is_empty() {
+                format!("  - {err}")
+            } else {
+                format!("  - {path}: {err}")
+            }
+        })
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        bail!(
+            "runner output does not match the pack run result schema (v{SCHEMA_VERSION}):\n{}",
+            errors.join("\n")
+        );
+    }
+    if let Some(object) = result.as_object_mut() {
+        object
+            .entry("schema_version")
+            .or_insert(SCHEMA_VERSION.into());
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunStatus, TraceStatus, check, parse};
+    use serde_json::json;
+
+    #[test]
+    fn stamps_the_schema_version_on_conforming_results() {
+        let result = check(
+            r#"{"status": "succeeded", "output": {"ok": true},
+                "trace": [{"node_id": "start", "status": "succeeded", "duration_ms": 3}]}"#,
+        )
+        .unwrap();
+        assert_eq!(result["schema_version"], json!(1));
+        assert_eq!(result["trace"][0]["node_id"], json!("start"));
+    }
+
+    #[test]
+    fn rejects_failures_without_an_error_envelope() {
+        let err = check(r#"{"status": "failed"}"#).unwrap_err().to_string();
+        assert!(err.contains("\"error\" is a required property"), "{err}");
+
+        let err = check(r#"{"status": "succeeded", "trace": [{"status": "done"}]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/trace/0"), "{err}");
+        assert!(check("not json").is_err());
+        assert!(check(r#"{"status": "succeeded", "schema_version": 2}"#).is_err());
+    }
+
+    #[test]
+    fn parses_results_into_typed_reports() {
+        let report = parse(
+            r#"{"status": "failed", "flow": "greet",
+                "trace": [{"node_id": "start", "status": "failed", "duration_ms": 3,
+                           "error": {"code": "E_NODE", "message": "boom"}}],
+                "error": {"code": "E_NODE", "message": "boom", "node_id": "start"}}"#,
+        )
+        .unwrap();
+        assert_eq!(report.schema_version, 1);
+        assert_eq!(report.status, RunStatus::Failed);
+        assert_eq!(report.flow.as_deref(), Some("greet"));
+        assert_eq!(report.trace[0].status, TraceStatus::Failed);
+        assert_eq!(report.trace[0].duration_ms, Some(3));
+        assert_eq!(report.error.unwrap().node_id.as_deref(), Some("start"));
+        assert!(parse(r#"{"status": "failed"}"#).is_err());
+    }
+}
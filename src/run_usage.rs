@@ -0,0 +1,216 @@
+//! Resource usage in `pack run --json` results: what each node cost, as far as the runner reports
+//! it, and the run's total, which greentic-dev adds so expensive nodes stand out right after a run.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::download::human_bytes;
+use crate::run_result::RunReport;
+
+/// Usage of one node (`trace[].usage`) or the whole run (`usage`). Fields the runner does not
+/// measure are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wall_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_bytes_read: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_bytes_written: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_calls: Option<u64>,
+}
+
+impl Usage {
+    /// Fold a node's usage into a total: counters add up, peak memory is the largest peak. Wall
+    /// time is measured for the whole run instead.
+    fn add(&mut self, other: &Usage) {
+        fn sum(total: &mut Option<u64>, value: Option<u64>) {
+            if let Some(value) = value {
+                *total = Some(total.unwrap_or(0).saturating_add(value));
+            }
+        }
+        sum(&mut self.fuel, other.fuel);
+        sum(&mut self.artifact_bytes_read, other.artifact_bytes_read);
+        sum(
+            &mut self.artifact_bytes_written,
+            other.artifact_bytes_written,
+        );
+        sum(&mut self.http_calls, other.http_calls);
+        if let Some(peak) = other.peak_memory_bytes {
+            self.peak_memory_bytes = Some(self.peak_memory_bytes.map_or(peak, |p| p.max(peak)));
+        }
+    }
+
+    /// Fields of `self` that are unset, taken from `other`.
+    fn or(self, other: Usage) -> Usage {
+        Usage {
+            wall_ms: self.wall_ms.or(other.wall_ms),
+            fuel: self.fuel.or(other.fuel),
+            peak_memory_bytes: self.peak_memory_bytes.or(other.peak_memory_bytes),
+            artifact_bytes_read: self.artifact_bytes_read.or(other.artifact_bytes_read),
+            artifact_bytes_written: self.artifact_bytes_written.or(other.artifact_bytes_written),
+            http_calls: self.http_calls.or(other.http_calls),
+        }
+    }
+}
+
+fn usage_of(value: &JsonValue) -> Usage {
+    value
+        .get("usage")
+        .and_then(|usage| serde_json::from_value(usage.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Set a checked result's top-level `usage` to the run's total. The run's wall time is `wall`, as
+/// measured around the runner; anything the runner already put in `usage` is kept.
+pub fn total(result: &mut JsonValue, wall: Duration) {
+    let mut total = Usage::default();
+    for entry in result
+        .get("trace")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+    {
+        total.add(&usage_of(entry));
+    }
+    total.wall_ms = Some(u64::try_from(wall.as_millis()).unwrap_or(u64::MAX));
+    let usage = usage_of(result).or(total);
+    if let (Some(object), Ok(usage)) = (result.as_object_mut(), serde_json::to_value(usage)) {
+        object.insert("usage".to_string(), usage);
+    }
+}
+
+/// A table of per-node and total usage, for stderr after a run; empty for a run without a trace.
+pub fn summary(report: &RunReport) -> String {
+    if report.trace.is_empty() {
+        return String::new();
+    }
+    let mut rows =
+        vec![["node", "wall", "fuel", "memory", "read", "written", "http"].map(str::to_string)];
+    for entry in &report.trace {
+        let usage = Usage {
+            wall_ms: entry
+                .usage
+                .as_ref()
+                .and_then(|usage| usage.wall_ms)
+                .or(entry.duration_ms),
+            ..entry.usage.clone().unwrap_or_default()
+        };
+        rows.push(row(&entry.node_id, &usage));
+    }
+    if let Some(usage) = &report.usage {
+        rows.push(row("total", usage));
+    }
+
+    let mut widths = [0; 7];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::from("Resource usage\n");
+    for row in &rows {
+        let mut line = String::from(" ");
+        for (cell, width) in row.iter().zip(widths) {
+            let _ = write!(line, " {cell:<width$}");
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn row(name: &str, usage: &Usage) -> [String; 7] {
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    [
+        name.to_string(),
+        or_dash(usage.wall_ms.map(|ms| format!("{ms} ms"))),
+        or_dash(usage.fuel.map(|fuel| fuel.to_string())),
+        or_dash(usage.peak_memory_bytes.map(human_bytes)),
+        or_dash(usage.artifact_bytes_read.map(human_bytes)),
+        or_dash(usage.artifact_bytes_written.map(human_bytes)),
+        or_dash(usage.http_calls.map(|calls| calls.to_string())),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_result::parse;
+    use serde_json::json;
+
+    fn sample() -> JsonValue {
+        json!({
+            "status": "succeeded",
+            "trace": [
+                {"node_id": "fetch", "status": "succeeded", "duration_ms": 40,
+                 "usage": {"fuel": 1000, "peak_memory_bytes": 2_097_152, "http_calls": 2,
+                           "artifact_bytes_written": 512}},
+                {"node_id": "render", "status": "succeeded", "duration_ms": 5,
+                 "usage": {"fuel": 250, "peak_memory_bytes": 1_048_576}},
+                {"node_id": "skip", "status": "skipped"}
+            ]
+        })
+    }
+
+    #[test]
+    fn totals_add_counters_and_keep_the_largest_peak() {
+        let mut result = sample();
+        total(&mut result, Duration::from_millis(52));
+        assert_eq!(
+            result["usage"],
+            json!({
+                "wall_ms": 52,
+                "fuel": 1250,
+                "peak_memory_bytes": 2_097_152,
+                "artifact_bytes_written": 512,
+                "http_calls": 2
+            })
+        );
+
+        // What the runner reports itself wins over the computed total.
+        let mut result = sample();
+        result["usage"] = json!({"fuel": 9000});
+        total(&mut result, Duration::from_millis(52));
+        assert_eq!(result["usage"]["fuel"], 9000);
+        assert_eq!(result["usage"]["http_calls"], 2);
+    }
+
+    #[test]
+    fn summaries_list_every_node_and_the_total() {
+        let mut result = sample();
+        total(&mut result, Duration::from_millis(52));
+        let report = parse(&result.to_string()).unwrap();
+        let summary = summary(&report);
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Resource usage");
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            ["node", "wall", "fuel", "memory", "read", "written", "http"]
+        );
+        assert_eq!(
+            lines[2].split_whitespace().collect::<Vec<_>>(),
+            [
+                "fetch", "40", "ms", "1000", "2.0", "MiB", "-", "512", "B", "2"
+            ]
+        );
+        assert_eq!(
+            lines[4].split_whitespace().collect::<Vec<_>>(),
+            ["skip", "-", "-", "-", "-", "-", "-"]
+        );
+        assert_eq!(
+            lines[5].split_whitespace().collect::<Vec<_>>(),
+            [
+                "total", "52", "ms", "1250", "2.0", "MiB", "-", "512", "B", "2"
+            ]
+        );
+    }
+}
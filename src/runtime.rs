@@ -0,0 +1,119 @@
+//! The process-wide tokio runtime. Distributor and OCI calls, the Ctrl-C listener and the watch
+//! loops all run on this one multi-threaded runtime instead of a runtime built per call.
+//!
+//! Ctrl-C cancels [`shutdown`]. Work started through [`block_on`] or [`pause`] is dropped at its
+//! next await, so downloads stop writing, and the caller stays blocked while [`crate::interrupt`]
+//! cleans up and exits. Nothing reports an interrupt as a command error.
+
+use std::future::Future;
+use std::sync::{LazyLock, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio_util::sync::CancellationToken;
+
+static SHUTDOWN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+/// The shared runtime, started on first use.
+pub fn runtime() -> Result<&'static Runtime> {
+    static RUNTIME: OnceLock<Result<Runtime, String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            Builder::new_multi_thread()
+                .thread_name("greentic-dev-rt")
+                .enable_all()
+                .build()
+                .map_err(|err| err.to_string())
+        })
+        .as_ref()
+        .map_err(|err| anyhow!("failed to start the tokio runtime: {err}"))
+}
+
+/// Cancelled once Ctrl-C arrives. Async work that outlives a single call can select on it, or
+/// on a child token, to stop early.
+pub fn shutdown() -> &'static CancellationToken {
+    &SHUTDOWN
+}
+
+/// Run `future` to completion from synchronous code: on the shared runtime, or on the current
+/// one (via `block_in_place`) when called from inside a multi-threaded runtime.
+pub fn block_on<F, T>(future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let run = until_cancelled(shutdown(), future);
+    let outcome = match Handle::try_current() {
+        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(run)),
+        Err(_) => runtime()?.block_on(run),
+    };
+    outcome.unwrap_or_else(|| wait_for_exit())
+}
+
+/// Sleep for `duration` on the runtime timer; on Ctrl-C, stop sleeping and wait for the
+/// interrupt handler to exit.
+pub fn pause(duration: Duration) {
+    let slept = block_on(async {
+        tokio::time::sleep(duration).await;
+        Ok(())
+    });
+    // Only a runtime that failed to start can get here; fall back to a plain sleep.
+    if slept.is_err() {
+        thread::sleep(duration);
+    }
+}
+
+// `None` when `token` is cancelled first; `future` is dropped at its pending await.
+async fn until_cancelled<F: Future>(token: &CancellationToken, future: F) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => None,
+        output = future => Some(output),
+    }
+}
+
+// The interrupt handler stops child processes, removes partial outputs and exits the process;
+// returning to the caller instead would race it.
+fn wait_for_exit() -> ! {
+    loop {
+        thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_on_works_inside_and_outside_a_runtime() {
+        assert_eq!(block_on(async { Ok(1) }).unwrap(), 1);
+        let nested = runtime()
+            .unwrap()
+            .block_on(async { tokio::spawn(async { block_on(async { Ok(2) }) }).await })
+            .unwrap();
+        assert_eq!(nested.unwrap(), 2);
+        assert!(block_on(async { Err::<(), _>(anyhow!("boom")) }).is_err());
+    }
+
+    #[test]
+    fn cancellation_drops_pending_work() {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let outcome = runtime().unwrap().block_on(async {
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                cancel.cancel();
+            });
+            until_cancelled(&token, tokio::time::sleep(Duration::from_secs(60))).await
+        });
+        assert!(outcome.is_none());
+        assert_eq!(
+            runtime()
+                .unwrap()
+                .block_on(until_cancelled(&token.child_token(), async { 3 })),
+            None,
+            "a cancelled parent cancels its children"
+        );
+    }
+}
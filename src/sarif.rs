@@ -0,0 +1,258 @@
+//! SARIF 2.1.0 output for validation errors, so GitHub code scanning and other CI tools can
+//! annotate the offending YAML lines. Used by `pack build`, `flow validate` and `flow lint` with
+//! `--output sarif`.
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use greentic_flow::error::{FlowError, FlowErrorLocation};
+use serde_json::{Value as JsonValue, json};
+
+pub const SCHEMA_URI: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_URI: &str = "https://github.com/greenticai/greentic-dev";
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Sarif,
+}
+
+/// Rules for flow validation failures reported by greentic-flow, one per kind of error.
+pub const FLOW_RULES: &[(&str, &str)] = &[
+    ("flow-yaml", "the flow is not valid YAML"),
+    ("flow-schema", "the flow does not match the flow schema"),
+    ("flow-type", "the flow declares an unknown flow type"),
+    (
+        "flow-identifier",
+        "a flow or node id is not a valid identifier",
+    ),
+    (
+        "flow-node-shape",
+        "a node needs exactly one component key plus optional node keys",
+    ),
+    ("flow-component-key", "a node's component key is malformed"),
+    ("flow-routing", "a node's routing block is invalid"),
+    (
+        "flow-missing-node",
+        "routing names a node the flow does not have",
+    ),
+    (
+        "flow-internal",
+        "greentic-flow failed while loading the flow",
+    ),
+];
+
+/// One result: a rule violated in a file, at a line (and column) when it is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub message: String,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn new(rule: impl Into<String>, message: impl Into<String>, file: &Path) -> Self {
+        Self {
+            rule: rule.into(),
+            message: message.into(),
+            file: file.to_path_buf(),
+            line: None,
+            column: None,
+        }
+    }
+
+    pub fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+/// A SARIF log with a single run of greentic-dev. Every result is an error.
+pub fn log(rules: &[(&str, &str)], diagnostics: &[Diagnostic]) -> JsonValue {
+    let rules = rules
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": "error" }
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut location = json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri(&diagnostic.file) }
+                }
+            });
+            if let Some(line) = diagnostic.line {
+                let mut region = json!({ "startLine": line });
+                if let Some(column) = diagnostic.column {
+                    region["startColumn"] = column.into();
+                }
+                location["physicalLocation"]["region"] = region;
+            }
+            json!({
+                "ruleId": diagnostic.rule,
+                "level": "error",
+                "message": { "text": diagnostic.message },
+                "locations": [location]
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "$schema": SCHEMA_URI,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "greentic-dev",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_URI,
+                    "rules": rules
+                }
+            },
+            "results": results
+        }]
+    })
+}
+
+pub fn print(rules: &[(&str, &str)], diagnostics: &[Diagnostic]) -> anyhow::Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&log(rules, diagnostics))?
+    );
+    Ok(())
+}
+
+// SARIF wants URIs: forward slashes, relative paths kept relative to the checkout.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    path.strip_prefix("./").map(str::to_string).unwrap_or(path)
+}
+
+/// The 1-based line of the `<node_id>:` key in a flow's `nodes` mapping.
+pub fn node_line(source: &str, node_id: &str) -> Option<usize> {
+    let mut in_nodes = false;
+    for (index, line) in source.lines().enumerate() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        if indent == 0 && !trimmed.is_empty() && !trimmed.starts_with('#') {
+            in_nodes = trimmed == "nodes:";
+            continue;
+        }
+        if in_nodes {
+            let key = trimmed.strip_suffix(':').unwrap_or_default();
+            let key = key.trim_matches(|c| c == '"' || c == '\'');
+            if key == node_id {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
+/// The 1-based line of the first occurrence of `needle`.
+pub fn line_of(source: &str, needle: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| line.contains(needle))
+        .map(|index| index + 1)
+}
+
+/// Results for a greentic-flow validation error somewhere in `err`'s chain, or `None` when the
+/// error is not a flow validation failure.
+pub fn flow_error_diagnostics(
+    err: &anyhow::Error,
+    file: &Path,
+    source: &str,
+) -> Option<Vec<Diagnostic>> {
+    let flow_error = err.downcast_ref::<FlowError>()?;
+    let (rule, location, node) = match flow_error {
+        FlowError::Yaml { location, .. } => ("flow-yaml", location, None),
+        FlowError::Schema {
+            details, location, ..
+        } if !details.is_empty() => {
+            return Some(
+                details
+                    .iter()
+                    .map(|detail| located("flow-schema", &detail.message, file, &detail.location))
+                    .collect(),
+            );
+        }
+        FlowError::Schema { location, .. } => ("flow-schema", location, None),
+        FlowError::UnknownFlowType { location, .. } => ("flow-type", location, None),
+        FlowError::InvalidIdentifier { location, .. } => ("flow-identifier", location, None),
+        FlowError::NodeComponentShape { node_id, location } => {
+            ("flow-node-shape", location, Some(node_id))
+        }
+        FlowError::BadComponentKey {
+            node_id, location, ..
+        } => ("flow-component-key", location, Some(node_id)),
+        FlowError::Routing {
+            node_id, location, ..
+        } => ("flow-routing", location, Some(node_id)),
+        FlowError::MissingNode {
+            node_id, location, ..
+        } => ("flow-missing-node", location, Some(node_id)),
+        FlowError::Internal { location, .. } => ("flow-internal", location, None),
+    };
+    let mut diagnostic = located(rule, &flow_error.to_string(), file, location);
+    if diagnostic.line.is_none() {
+        diagnostic.line = node.and_then(|node| node_line(source, node));
+    }
+    Some(vec![diagnostic])
+}
+
+fn located(rule: &str, message: &str, file: &Path, location: &FlowErrorLocation) -> Diagnostic {
+    Diagnostic {
+        column: location.line.and(location.col),
+        ..Diagnostic::new(rule, message, file).at_line(location.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOW: &str =
+        "id: greet\nnodes:\n  start:\n    component.exec: {}\n  \"done\":\n    out: {}\n";
+
+    #[test]
+    fn finds_node_keys_only_under_nodes() {
+        assert_eq!(node_line(FLOW, "start"), Some(3));
+        assert_eq!(node_line(FLOW, "done"), Some(5));
+        assert_eq!(node_line(FLOW, "greet"), None);
+        assert_eq!(node_line(FLOW, "component.exec"), None);
+    }
+
+    #[test]
+    fn logs_results_with_regions_when_the_line_is_known() {
+        let diagnostics = [
+            Diagnostic::new(
+                "unknown-route",
+                "routes nowhere",
+                Path::new("./flows/main.ygtc"),
+            )
+            .at_line(Some(3)),
+            Diagnostic::new(
+                "unused-secret",
+                "unused",
+                Path::new("secret-requirements.json"),
+            ),
+        ];
+        let log = log(&[("unknown-route", "routes nowhere")], &diagnostics);
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "unknown-route");
+        let location = &run["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "flows/main.ygtc");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert!(run["results"][1]["locations"][0]["physicalLocation"]["region"].is_null());
+    }
+}
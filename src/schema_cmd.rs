@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use semver::VersionReq;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::cli::{SchemaCommand, SchemaExportArgs};
+use crate::component_resolver::ComponentResolver;
+use crate::events;
+use crate::pack_build;
+
+const FLOW_SCHEMA: &str = include_str!("../schemas/ygtc.flow.schema.json");
+
+#[derive(Debug, Default, Serialize)]
+struct ExportIndex {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flow: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pack_meta: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<ComponentSchemaEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComponentSchemaEntry {
+    component: String,
+    version: String,
+    schema: String,
+}
+
+pub fn run(command: SchemaCommand) -> Result<()> {
+    match command {
+        SchemaCommand::Export(args) => export(&args),
+    }
+}
+
+fn export(args: &SchemaExportArgs) -> Result<()> {
+    // No selector means "everything".
+    let all = !(args.flows || args.components || args.pack_meta);
+    fs::create_dir_all(&args.out)
+        .with_context(|| format!("failed to create {}", args.out.display()))?;
+
+    let mut index = ExportIndex::default();
+    if all || args.flows {
+        let schema: JsonValue =
+            serde_json::from_str(FLOW_SCHEMA).context("embedded flow schema is invalid JSON")?;
+        index.flow = Some(write_schema(&args.out, "flow.schema.json", &schema)?);
+    }
+    if all || args.pack_meta {
+        index.pack_meta = Some(write_schema(
+            &args.out,
+            "pack-meta.schema.json",
+            &pack_build::pack_meta_schema(),
+        )?);
+    }
+    if all || args.components {
+        index.components = export_components(&args.component_dir, &args.out)?;
+    }
+
+    write_schema(&args.out, "index.json", &serde_json::to_value(&index)?)?;
+    print_summary(&args.out, &index);
+    Ok(())
+}
+
+fn export_components(component_dir: &Path, out: &Path) -> Result<Vec<ComponentSchemaEntry>> {
+    if !component_dir.is_dir() {
+        bail!(
+            "component directory {} does not exist (use --component-dir)",
+            component_dir.display()
+        );
+    }
+    let mut names = fs::read_dir(component_dir)
+        .with_context(|| format!("failed to read {}", component_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let resolver = ComponentResolver::new(Some(component_dir.to_path_buf()));
+    let mut entries = Vec::new();
+    for name in names {
+        let component = match resolver.resolve_component(&name, &VersionReq::STAR) {
+            Ok(component) => component,
+            Err(err) => {
+                events::warn(format_args!("skipping {name}: {err:#}"));
+                continue;
+            }
+        };
+        let Some(schema_json) = &component.schema_json else {
+            events::warn(format_args!(
+                "component {} does not describe a config schema",
+                component.name
+            ));
+            continue;
+        };
+        let mut schema: JsonValue = serde_json::from_str(schema_json)
+            .with_context(|| format!("component {} has an invalid schema", component.name))?;
+        if let Some(map) = schema.as_object_mut() {
+            map.entry("title")
+                .or_insert_with(|| JsonValue::String(format!("{} node config", component.name)));
+        }
+        let file = PathBuf::from("components").join(format!("{}.schema.json", component.name));
+        entries.push(ComponentSchemaEntry {
+            component: component.name.clone(),
+            version: component.version.to_string(),
+            schema: write_schema(out, &file.to_string_lossy(), &schema)?,
+        });
+    }
+    Ok(entries)
+}
+
+fn write_schema(out: &Path, relative: &str, schema: &JsonValue) -> Result<String> {
+    let path = out.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let mut contents = serde_json::to_string_pretty(schema)?;
+    contents.push('\n');
+    fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(relative.replace('\\', "/"))
+}
+
+fn print_summary(out: &Path, index: &ExportIndex) {
+    println!("Schemas written to {}", out.display());
+    if let Some(flow) = &index.flow {
+        println!("  flow:      {flow}");
+        println!(
+            "  add `# yaml-language-server: $schema={}` to the top of a .ygtc file",
+            out.join(flow).display()
+        );
+    }
+    if let Some(meta) = &index.pack_meta {
+        println!("  pack meta: {meta}");
+    }
+    for entry in &index.components {
+        println!(
+            "  component: {} {} -> {}",
+            entry.component, entry.version, entry.schema
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FLOW_SCHEMA;
+    use crate::pack_build::pack_meta_schema;
+
+    #[test]
+    fn embedded_flow_schema_is_json() {
+        let schema: serde_json::Value = serde_json::from_str(FLOW_SCHEMA).unwrap();
+        assert!(schema.get("properties").is_some());
+    }
+
+    #[test]
+    fn pack_meta_schema_describes_sections() {
+        let schema = pack_meta_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for key in ["pack_id", "messaging", "events", "repo", "interfaces"] {
+            assert!(properties.contains_key(key), "missing {key}");
+        }
+    }
+}
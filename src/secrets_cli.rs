@@ -1,4 +1,5 @@
 use std::ffi::OsString;
+use std::io::{self, IsTerminal, Read};
 use std::path::PathBuf;
 
 use anyhow::{Context, Result, bail};
@@ -6,11 +7,30 @@ use clap::{Args, Subcommand};
 
 use crate::i18n;
 use crate::passthrough::{resolve_binary, run_passthrough};
+use crate::secrets_keyring;
+use crate::secrets_seed;
 
 #[derive(Subcommand, Debug)]
 pub enum SecretsCommand {
     /// cli.command.secrets.init.about
     Init(SecretsInitArgs),
+    /// cli.command.secrets.seed.about
+    #[command(subcommand)]
+    Seed(SecretsSeedCommand),
+    /// cli.command.secrets.set.about
+    Set(SecretsSetArgs),
+    /// cli.command.secrets.get.about
+    Get(SecretsKeyArgs),
+    /// cli.command.secrets.rm.about
+    Rm(SecretsKeyArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SecretsSeedCommand {
+    /// cli.command.secrets.seed.init.about
+    Init(SecretsSeedInitArgs),
+    /// cli.command.secrets.seed.validate.about
+    Validate(SecretsSeedValidateArgs),
 }
 
 #[derive(Args, Debug, Clone)]
@@ -23,12 +43,96 @@ pub struct SecretsInitArgs {
     pub passthrough: Vec<String>,
 }
 
+#[derive(Args, Debug, Clone)]
+pub struct SecretsSeedInitArgs {
+    /// cli.command.secrets.seed.init.pack
+    #[arg(short = 'p', long = "pack")]
+    pub pack: PathBuf,
+    /// cli.command.secrets.seed.init.environment
+    #[arg(long = "environment", default_value = "local")]
+    pub environment: String,
+    /// cli.command.secrets.seed.init.tenant
+    #[arg(long = "tenant", default_value = "tenant-local")]
+    pub tenant: String,
+    /// cli.command.secrets.seed.init.team
+    #[arg(long = "team")]
+    pub team: Option<String>,
+    /// cli.command.secrets.seed.init.out
+    #[arg(short = 'o', long = "out")]
+    pub out: Option<PathBuf>,
+    /// cli.command.secrets.seed.init.force
+    #[arg(long = "force")]
+    pub force: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SecretsSeedValidateArgs {
+    /// cli.command.secrets.seed.validate.file
+    pub file: PathBuf,
+    /// cli.command.secrets.seed.validate.pack
+    #[arg(short = 'p', long = "pack")]
+    pub pack: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SecretsSetArgs {
+    /// cli.command.secrets.set.key
+    pub key: String,
+    /// cli.command.secrets.set.stdin
+    #[arg(long = "stdin")]
+    pub stdin: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SecretsKeyArgs {
+    /// cli.command.secrets.key
+    pub key: String,
+}
+
 pub fn run_secrets_command(cmd: SecretsCommand, locale: &str) -> Result<()> {
     match cmd {
         SecretsCommand::Init(args) => run_init(&args, locale),
+        SecretsCommand::Seed(SecretsSeedCommand::Init(args)) => secrets_seed::init(&args),
+        SecretsCommand::Seed(SecretsSeedCommand::Validate(args)) => secrets_seed::validate(&args),
+        SecretsCommand::Set(args) => run_set(&args),
+        SecretsCommand::Get(args) => match secrets_keyring::get(&args.key)? {
+            Some(value) => {
+                println!("{value}");
+                Ok(())
+            }
+            None => bail!("no keychain entry for `{}`", args.key),
+        },
+        SecretsCommand::Rm(args) => {
+            if !secrets_keyring::remove(&args.key)? {
+                bail!("no keychain entry for `{}`", args.key);
+            }
+            println!("Removed {} from the keychain", args.key);
+            Ok(())
+        }
     }
 }
 
+// Values are prompted for or piped in, never taken from the command line, so they stay out of
+// shell history.
+fn run_set(args: &SecretsSetArgs) -> Result<()> {
+    let value = if args.stdin || !io::stdin().is_terminal() {
+        let mut value = String::new();
+        io::stdin()
+            .read_to_string(&mut value)
+            .context("failed to read the secret from stdin")?;
+        value.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        rpassword::prompt_password(format!("{}: ", args.key))
+            .context("failed to read the secret")?
+    };
+    if value.is_empty() {
+        bail!("refusing to store an empty value for `{}`", args.key);
+    }
+    secrets_keyring::set(&args.key, &value)?;
+    println!("Stored {} in the keychain", args.key);
+    Ok(())
+}
+
 fn run_init(args: &SecretsInitArgs, locale: &str) -> Result<()> {
     let bin = resolve_binary("greentic-secrets")?;
     let mut argv = vec![
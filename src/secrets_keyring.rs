@@ -0,0 +1,157 @@
+//! `SECRETS_BACKEND=keyring`: local-dev secrets kept in the OS keychain (macOS Keychain, Windows
+//! Credential Manager, the Linux kernel keyring) instead of shell profiles.
+//!
+//! The runner only reads secrets from its environment, so `pack run` looks up the secrets the
+//! pack requires in the keychain and passes them to the runner as variables.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use keyring::Entry;
+
+use crate::pack_preflight::secret_requirements;
+use crate::run_env::is_env_name;
+use crate::run_history::arg_value;
+
+/// Keychain service every entry is stored under; the entry's account is the secret name.
+pub const SERVICE: &str = "greentic-dev";
+pub const BACKEND_VAR: &str = "SECRETS_BACKEND";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretsBackend {
+    Env,
+    Keyring,
+}
+
+impl SecretsBackend {
+    /// The backend `SECRETS_BACKEND` selects; `env` when it is unset.
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::var(BACKEND_VAR).ok().as_deref())
+    }
+
+    fn parse(value: Option<&str>) -> Result<Self> {
+        match value.map(str::trim) {
+            None | Some("" | "env") => Ok(Self::Env),
+            Some("keyring") => Ok(Self::Keyring),
+            Some(other) => bail!("unknown {BACKEND_VAR} `{other}`; expected `env` or `keyring`"),
+        }
+    }
+}
+
+fn entry(key: &str) -> Result<Entry> {
+    if !is_env_name(key) {
+        bail!("`{key}` is not a valid secret name; use letters, digits and `_`");
+    }
+    Entry::new(SERVICE, key).with_context(|| format!("failed to open keychain entry `{key}`"))
+}
+
+pub fn set(key: &str, value: &str) -> Result<()> {
+    entry(key)?
+        .set_password(value)
+        .with_context(|| format!("failed to store `{key}` in the keychain"))
+}
+
+/// The stored value of `key`, or `None` when the keychain has no such entry.
+pub fn get(key: &str) -> Result<Option<String>> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("failed to read `{key}` from the keychain")),
+    }
+}
+
+/// Delete `key`; false when there was nothing to delete.
+pub fn remove(key: &str) -> Result<bool> {
+    match entry(key)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(err) => Err(err).with_context(|| format!("failed to remove `{key}` from the keychain")),
+    }
+}
+
+/// Extra runner environment for `pack run <runner_args>`. With the keyring backend that is every
+/// secret the pack requires and `provided` does not already supply, read from the keychain, plus
+/// `SECRETS_BACKEND=env` since the runner itself only knows the env backend. Otherwise it is empty.
+pub fn runner_env(
+    runner_args: &[OsString],
+    provided: &dyn Fn(&str) -> bool,
+) -> Result<BTreeMap<String, String>> {
+    if SecretsBackend::from_env()? != SecretsBackend::Keyring {
+        return Ok(BTreeMap::new());
+    }
+    // A pack that cannot be opened is left for the runner to report.
+    let required = arg_value(runner_args, &["--pack", "-p"])
+        .and_then(|pack| open_pack(Path::new(&pack), SigningPolicy::DevOk).ok())
+        .map(|load| secret_requirements(&load.manifest).into_keys().collect())
+        .unwrap_or_default();
+    let mut env = resolve(required, provided, &get)?;
+    env.insert(BACKEND_VAR.to_string(), "env".to_string());
+    Ok(env)
+}
+
+fn resolve(
+    required: Vec<String>,
+    provided: &dyn Fn(&str) -> bool,
+    lookup: &dyn Fn(&str) -> Result<Option<String>>,
+) -> Result<BTreeMap<String, String>> {
+    let mut env = BTreeMap::new();
+    // Keys that are not variable names cannot reach the runner through its environment anyway.
+    for key in required
+        .into_iter()
+        .filter(|key| is_env_name(key) && !provided(key))
+    {
+        if let Some(value) = lookup(&key)? {
+            env.insert(key, value);
+        }
+    }
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_defaults_to_env() {
+        assert_eq!(SecretsBackend::parse(None).unwrap(), SecretsBackend::Env);
+        assert_eq!(
+            SecretsBackend::parse(Some("env")).unwrap(),
+            SecretsBackend::Env
+        );
+        assert_eq!(
+            SecretsBackend::parse(Some("keyring")).unwrap(),
+            SecretsBackend::Keyring
+        );
+        let err = SecretsBackend::parse(Some("vault"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown SECRETS_BACKEND `vault`"), "{err}");
+    }
+
+    #[test]
+    fn keychain_only_fills_secrets_nothing_else_provides() {
+        let required = ["API_TOKEN", "DB_PASSWORD", "UNSET", "slack/bot"]
+            .map(str::to_string)
+            .to_vec();
+        let env = resolve(required, &|key| key == "DB_PASSWORD", &|key| {
+            Ok((key != "UNSET").then(|| format!("{key}-from-keychain")))
+        })
+        .unwrap();
+        assert_eq!(
+            env,
+            BTreeMap::from([(
+                "API_TOKEN".to_string(),
+                "API_TOKEN-from-keychain".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn secret_names_must_be_variable_names() {
+        let err = get("API-TOKEN").unwrap_err().to_string();
+        assert!(err.contains("not a valid secret name"), "{err}");
+    }
+}
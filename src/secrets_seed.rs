@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use greentic_secrets_spec::{SeedDoc, SeedEntry, SeedValue};
+use greentic_types::{SecretFormat, SecretKey, SecretRequirement};
+use serde_json::Value as JsonValue;
+
+use crate::events;
+use crate::pack_plan::declared_secrets;
+use crate::secrets_cli::{SecretsSeedInitArgs, SecretsSeedValidateArgs};
+
+/// Value written for every entry of a new seed; `seed validate` rejects entries that still have it.
+pub const PLACEHOLDER: &str = "CHANGE_ME";
+
+const URI_SCHEME: &str = "secrets://";
+// Requirement keys without a category resolve under this one, as in greentic-secrets.
+const DEFAULT_CATEGORY: &str = "configs";
+
+/// The parts of a `secrets://<env>/<tenant>/<team>/<category>/<name>[@version]` URI a seed entry
+/// is matched on. A `_` team means no team.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedUri {
+    pub env: String,
+    pub tenant: String,
+    pub team: Option<String>,
+    /// `<category>/<name>`, lowercased.
+    pub key: String,
+}
+
+impl SeedUri {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let path = uri.strip_prefix(URI_SCHEME)?;
+        let segments = path.split('/').collect::<Vec<_>>();
+        let [env, tenant, team, category, name] = segments.as_slice() else {
+            return None;
+        };
+        let name = name.split_once('@').map_or(*name, |(name, _)| name);
+        if [env, tenant, team, category, &name]
+            .iter()
+            .any(|segment| segment.is_empty())
+        {
+            return None;
+        }
+        Some(Self {
+            env: env.to_string(),
+            tenant: tenant.to_string(),
+            team: (*team != "_").then(|| team.to_string()),
+            key: format!("{category}/{name}").to_ascii_lowercase(),
+        })
+    }
+}
+
+/// The `<category>/<name>` a requirement key resolves to: lowercased, under `configs/` when the
+/// key has no category.
+pub fn requirement_key(key: &SecretKey) -> String {
+    let key = key.as_str().to_ascii_lowercase();
+    if key.contains('/') {
+        key
+    } else {
+        format!("{DEFAULT_CATEGORY}/{key}")
+    }
+}
+
+/// The secret requirements of a pack: its `secret-requirements.json` (or the requirements in its
+/// manifest), falling back to what its components declare.
+pub fn pack_requirements(pack: &Path) -> Result<Vec<SecretRequirement>> {
+    let load = open_pack(pack, SigningPolicy::DevOk).map_err(|err| anyhow!(err.message))?;
+    if let Some(requirements) = declared_secrets(&load)? {
+        return Ok(requirements);
+    }
+    // Components declare full requirements (scope, format, description); the first declaration
+    // of a key wins, and a key is required when any component requires it.
+    let mut requirements: Vec<SecretRequirement> = Vec::new();
+    for component in &load.manifest.components {
+        let declared = component
+            .capabilities
+            .as_ref()
+            .and_then(|caps| caps.pointer("/host/secrets/required"))
+            .and_then(JsonValue::as_array);
+        for value in declared.into_iter().flatten() {
+            let requirement: SecretRequirement = serde_json::from_value(value.clone())
+                .with_context(|| {
+                    format!(
+                        "component {} has an invalid secret requirement",
+                        component.name
+                    )
+                })?;
+            match requirements
+                .iter_mut()
+                .find(|existing| existing.key == requirement.key)
+            {
+                Some(existing) => existing.required |= requirement.required,
+                None => requirements.push(requirement),
+            }
+        }
+    }
+    Ok(requirements)
+}
+
+/// A seed with one placeholder entry per requirement, scoped to the requirement's own scope when
+/// it declares one and to `env`/`tenant`/`team` otherwise.
+pub fn skeleton(
+    requirements: &[SecretRequirement],
+    env: &str,
+    tenant: &str,
+    team: Option<&str>,
+) -> SeedDoc {
+    let entries = requirements
+        .iter()
+        .map(|requirement| {
+            let (env, tenant, team) = match &requirement.scope {
+                Some(scope) => (
+                    scope.env.as_str(),
+                    scope.tenant.as_str(),
+                    scope.team.as_deref(),
+                ),
+                None => (env, tenant, team),
+            };
+            let format = requirement.format.clone().unwrap_or(SecretFormat::Text);
+            let value = match format {
+                SecretFormat::Text => SeedValue::Text {
+                    text: PLACEHOLDER.to_string(),
+                },
+                SecretFormat::Json => SeedValue::Json {
+                    json: PLACEHOLDER.into(),
+                },
+                SecretFormat::Bytes => SeedValue::BytesB64 {
+                    bytes_b64: PLACEHOLDER.to_string(),
+                },
+            };
+            SeedEntry {
+                uri: format!(
+                    "{URI_SCHEME}{env}/{tenant}/{}/{}",
+                    team.unwrap_or("_"),
+                    requirement_key(&requirement.key)
+                ),
+                format,
+                value,
+                description: requirement.description.clone(),
+            }
+        })
+        .collect();
+    SeedDoc { entries }
+}
+
+/// What is wrong with a seed: `errors` fail validation, `warnings` do not.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SeedReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Check a seed on its own (URIs, duplicates, values matching their format, leftover
+/// placeholders) and, given requirements, against them: every required secret has an entry in
+/// the requirement's format, and every entry is for a requirement.
+pub fn check(seed: &SeedDoc, requirements: Option<&[SecretRequirement]>) -> SeedReport {
+    let mut report = SeedReport::default();
+    let mut seen = BTreeSet::new();
+    let mut parsed = Vec::new();
+    for entry in &seed.entries {
+        let Some(uri) = SeedUri::parse(&entry.uri) else {
+            report.errors.push(format!(
+                "{}: not a secrets://<env>/<tenant>/<team>/<category>/<name> URI",
+                entry.uri
+            ));
+            continue;
+        };
+        if !seen.insert(entry.uri.clone()) {
+            report
+                .errors
+                .push(format!("{}: seeded more than once", entry.uri));
+        }
+        if let Some(problem) = value_problem(entry) {
+            report.errors.push(format!("{}: {problem}", entry.uri));
+        }
+        parsed.push((entry, uri));
+    }
+
+    let Some(requirements) = requirements else {
+        return report;
+    };
+    let mut used = BTreeSet::new();
+    for requirement in requirements {
+        let key = requirement_key(&requirement.key);
+        let matching = parsed
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, uri))| {
+                uri.key == key
+                    && requirement.scope.as_ref().is_none_or(|scope| {
+                        uri.env == scope.env && uri.tenant == scope.tenant && uri.team == scope.team
+                    })
+            })
+            .collect::<Vec<_>>();
+        if matching.is_empty() {
+            let message = format!("{} has no entry", requirement.key.as_str());
+            if requirement.required {
+                report.errors.push(message);
+            } else {
+                report.warnings.push(format!("{message} (optional)"));
+            }
+        }
+        for (index, (entry, _)) in matching {
+            used.insert(index);
+            if let Some(format) = &requirement.format
+                && *format != entry.format
+            {
+                report.errors.push(format!(
+                    "{}: {} expects {} but the entry is {}",
+                    entry.uri,
+                    requirement.key.as_str(),
+                    format_name(format),
+                    format_name(&entry.format)
+                ));
+            }
+        }
+    }
+    for (index, (entry, _)) in parsed.iter().enumerate() {
+        if !used.contains(&index) {
+            report
+                .warnings
+                .push(format!("{}: the pack does not require it", entry.uri));
+        }
+    }
+    report
+}
+
+fn value_problem(entry: &SeedEntry) -> Option<String> {
+    let placeholder = match (&entry.format, &entry.value) {
+        (SecretFormat::Text, SeedValue::Text { text }) => text == PLACEHOLDER,
+        (SecretFormat::Json, SeedValue::Json { json }) => json.as_str() == Some(PLACEHOLDER),
+        (SecretFormat::Bytes, SeedValue::BytesB64 { bytes_b64 }) => {
+            if bytes_b64 != PLACEHOLDER && STANDARD.decode(bytes_b64).is_err() {
+                return Some("bytes_b64 is not valid base64".to_string());
+            }
+            bytes_b64 == PLACEHOLDER
+        }
+        (format, _) => {
+            return Some(format!(
+                "the value does not match format {}",
+                format_name(format)
+            ));
+        }
+    };
+    placeholder.then(|| format!("still has the {PLACEHOLDER} placeholder"))
+}
+
+fn format_name(format: &SecretFormat) -> &'static str {
+    match format {
+        SecretFormat::Text => "text",
+        SecretFormat::Json => "json",
+        SecretFormat::Bytes => "bytes",
+    }
+}
+
+/// `secrets seed init`: write a seed skeleton for a pack's secret requirements.
+pub fn init(args: &SecretsSeedInitArgs) -> Result<()> {
+    let requirements = pack_requirements(&args.pack)?;
+    if requirements.is_empty() {
+        bail!("{} requires no secrets", args.pack.display());
+    }
+    let seed = skeleton(
+        &requirements,
+        &args.environment,
+        &args.tenant,
+        args.team.as_deref(),
+    );
+    let rendered = serde_yaml_bw::to_string(&seed).context("failed to render the seed")?;
+    let Some(out) = &args.out else {
+        print!("{rendered}");
+        return Ok(());
+    };
+    if out.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            out.display()
+        );
+    }
+    fs::write(out, rendered).with_context(|| format!("failed to write {}", out.display()))?;
+    events::artifact(out);
+    println!(
+        "Wrote {} entr(ies) to {}; replace each {PLACEHOLDER} value, then run `greentic-dev secrets seed validate {}`",
+        seed.entries.len(),
+        out.display(),
+        out.display()
+    );
+    Ok(())
+}
+
+/// `secrets seed validate`: check a seed file, against a pack's requirements with `--pack`.
+pub fn validate(args: &SecretsSeedValidateArgs) -> Result<()> {
+    let data = fs::read_to_string(&args.file)
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let seed: SeedDoc = serde_yaml_bw::from_str(&data)
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+    let requirements = args.pack.as_deref().map(pack_requirements).transpose()?;
+    let report = check(&seed, requirements.as_deref());
+    for warning in &report.warnings {
+        println!("! {warning}");
+    }
+    for error in &report.errors {
+        println!("✗ {error}");
+    }
+    if !report.errors.is_empty() {
+        bail!(
+            "{} problem(s) in {}",
+            report.errors.len(),
+            args.file.display()
+        );
+    }
+    println!(
+        "✓ {}: {} entr(ies) valid",
+        args.file.display(),
+        seed.entries.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SeedUri, check, skeleton};
+    use greentic_secrets_spec::SeedValue;
+    use greentic_types::{SecretFormat, SecretKey, SecretRequirement};
+
+    fn requirement(key: &str, required: bool, format: Option<SecretFormat>) -> SecretRequirement {
+        let mut requirement = SecretRequirement::default();
+        requirement.key = SecretKey::new(key).unwrap();
+        requirement.required = required;
+        requirement.format = format;
+        requirement
+    }
+
+    #[test]
+    fn uris_resolve_to_requirement_keys() {
+        assert_eq!(
+            SeedUri::parse("secrets://dev/acme/_/configs/DB@v2"),
+            Some(SeedUri {
+                env: "dev".to_string(),
+                tenant: "acme".to_string(),
+                team: None,
+                key: "configs/db".to_string(),
+            })
+        );
+        assert_eq!(SeedUri::parse("secrets://dev/acme/configs/db"), None);
+        assert_eq!(SeedUri::parse("env://API_TOKEN"), None);
+    }
+
+    #[test]
+    fn skeletons_need_filling_in_before_they_validate() {
+        let requirements = vec![
+            requirement("API_TOKEN", true, None),
+            requirement("certs/client", false, Some(SecretFormat::Bytes)),
+        ];
+        let mut seed = skeleton(&requirements, "local", "tenant-local", None);
+        assert_eq!(
+            seed.entries
+                .iter()
+                .map(|entry| entry.uri.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "secrets://local/tenant-local/_/configs/api_token",
+                "secrets://local/tenant-local/_/certs/client",
+            ]
+        );
+        assert_eq!(check(&seed, Some(&requirements)).errors.len(), 2);
+
+        seed.entries[0].value = SeedValue::Text {
+            text: "s3cret".to_string(),
+        };
+        seed.entries.pop();
+        let report = check(&seed, Some(&requirements));
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.warnings, ["certs/client has no entry (optional)"]);
+    }
+
+    #[test]
+    fn seeds_are_checked_against_requirement_formats() {
+        let mut seed = skeleton(
+            &[requirement("API_TOKEN", true, None)],
+            "local",
+            "tenant-local",
+            None,
+        );
+        seed.entries[0].value = SeedValue::Text {
+            text: "s3cret".to_string(),
+        };
+        let requirements = [requirement("API_TOKEN", true, Some(SecretFormat::Json))];
+        let report = check(&seed, Some(&requirements));
+        assert_eq!(
+            report.errors,
+            [
+                "secrets://local/tenant-local/_/configs/api_token: API_TOKEN expects json but the entry is text"
+            ]
+        );
+        assert!(check(&seed, None).errors.is_empty());
+    }
+}
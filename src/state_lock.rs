@@ -0,0 +1,144 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+/// Seconds to wait for a state lock unless `--lock-timeout` says otherwise.
+pub const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 60;
+
+const LOCK_FILE: &str = ".lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static LOCK_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_LOCK_TIMEOUT_SECS);
+
+/// Set how long [`StateLock::acquire`] waits for another process to release a lock.
+pub fn set_timeout(timeout: Duration) {
+    LOCK_TIMEOUT_SECS.store(timeout.as_secs(), Ordering::Relaxed);
+}
+
+fn timeout() -> Duration {
+    Duration::from_secs(LOCK_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
+/// Exclusive advisory lock on a directory shared between greentic-dev processes (the component
+/// caches, `.greentic/`). The lock is a `.lock` file in the directory and is released on drop.
+#[derive(Debug)]
+pub struct StateLock {
+    _file: File,
+}
+
+impl StateLock {
+    /// Lock `dir`, creating it if needed, and wait up to the `--lock-timeout` for other holders.
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        Self::acquire_with_timeout(dir, timeout())
+    }
+
+    pub fn acquire_with_timeout(dir: &Path, timeout: Duration) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(LOCK_FILE);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => bail!(
+                    "timed out after {}s waiting for {}; another greentic-dev process is using {} (raise --lock-timeout to wait longer)",
+                    timeout.as_secs(),
+                    path.display(),
+                    dir.display()
+                ),
+                Err(TryLockError::Error(err)) => {
+                    return Err(err).with_context(|| format!("failed to lock {}", path.display()));
+                }
+            }
+        }
+    }
+}
+
+/// Write `contents` through a sibling temp file and a rename so readers never see a partial
+/// file. The file keeps the permissions it had; a new one gets the umask's, like any other.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let existing = fs::metadata(path).ok().map(|meta| meta.permissions());
+    let mut builder = tempfile::Builder::new();
+    // Temp files are owner-only by default; asking for 0666 lets the umask decide instead.
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    let tmp = builder
+        .tempfile_in(&dir)
+        .with_context(|| format!("failed to create a temp file in {}", dir.display()))?;
+    if let Some(permissions) = existing {
+        fs::set_permissions(tmp.path(), permissions)
+            .with_context(|| format!("failed to set permissions on {}", tmp.path().display()))?;
+    }
+    fs::write(tmp.path(), contents)
+        .with_context(|| format!("failed to write {}", tmp.path().display()))?;
+    tmp.persist(path)
+        .map_err(|err| anyhow!("failed to replace {}: {}", path.display(), err.error))?;
+    crate::events::artifact(path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StateLock, write_atomic};
+    use std::time::Duration;
+
+    #[test]
+    fn second_holder_times_out_until_the_first_is_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let held = StateLock::acquire_with_timeout(dir.path(), Duration::ZERO).unwrap();
+        let err = StateLock::acquire_with_timeout(dir.path(), Duration::from_millis(100))
+            .expect_err("lock is held");
+        assert!(err.to_string().contains("--lock-timeout"), "{err}");
+        drop(held);
+        StateLock::acquire_with_timeout(dir.path(), Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn atomic_write_replaces_existing_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, "old").unwrap();
+        write_atomic(&path, "new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_keeps_permissions_and_follows_the_umask() {
+        use std::fs::{self, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mode =
+            |path: &std::path::Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let path = dir.path().join("manifest.json");
+        write_atomic(&path, "new").unwrap();
+        let plain = dir.path().join("plain.json");
+        fs::File::create(&plain).unwrap();
+        assert_eq!(mode(&path), mode(&plain));
+
+        fs::set_permissions(&path, Permissions::from_mode(0o640)).unwrap();
+        write_atomic(&path, "again").unwrap();
+        assert_eq!(mode(&path), 0o640);
+    }
+}
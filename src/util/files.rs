@@ -0,0 +1,122 @@
+//! Whole-file digests and comparisons that read through a fixed-size buffer, so memory use does
+//! not grow with the file (component wasm and packs can be hundreds of megabytes).
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+const CHUNK: usize = 64 * 1024;
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("failed to open {}", path.display()))
+}
+
+/// Lowercase hex BLAKE3 digest of a file.
+pub fn blake3_file(path: &Path) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher
+        .update_reader(open(path)?)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Lowercase hex SHA-256 digest of a file.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut reader = open(path)?;
+    let mut buffer = vec![0; CHUNK];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Whether two files have identical contents.
+pub fn same_contents(a: &Path, b: &Path) -> Result<bool> {
+    let (mut a_file, mut b_file) = (open(a)?, open(b)?);
+    if a_file.metadata()?.len() != b_file.metadata()?.len() {
+        return Ok(false);
+    }
+    let (mut a_buf, mut b_buf) = (vec![0; CHUNK], vec![0; CHUNK]);
+    loop {
+        let read = read_full(&mut a_file, &mut a_buf)
+            .with_context(|| format!("failed to read {}", a.display()))?;
+        let other = read_full(&mut b_file, &mut b_buf)
+            .with_context(|| format!("failed to read {}", b.display()))?;
+        if a_buf[..read] != b_buf[..other] {
+            return Ok(false);
+        }
+        if read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+// Fill `buffer` unless the reader runs out first; returns how much was read.
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blake3_file, same_contents, sha256_file};
+    use sha2::{Digest, Sha256};
+    use std::fs;
+
+    #[test]
+    fn digests_match_in_memory_hashes_across_chunk_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.wasm");
+        let bytes = (0..200_000u32).map(|n| (n % 251) as u8).collect::<Vec<_>>();
+        fs::write(&path, &bytes).unwrap();
+
+        assert_eq!(
+            blake3_file(&path).unwrap(),
+            blake3::hash(&bytes).to_hex().to_string()
+        );
+        let expected = Sha256::digest(&bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        assert_eq!(sha256_file(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn comparison_spots_late_differences() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = vec![7u8; 150_000];
+        let mut changed = bytes.clone();
+        changed[149_999] = 8;
+        let (a, b, c) = (
+            dir.path().join("a"),
+            dir.path().join("b"),
+            dir.path().join("c"),
+        );
+        fs::write(&a, &bytes).unwrap();
+        fs::write(&b, &bytes).unwrap();
+        fs::write(&c, &changed).unwrap();
+
+        assert!(same_contents(&a, &b).unwrap());
+        assert!(!same_contents(&a, &c).unwrap());
+    }
+}
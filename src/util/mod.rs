@@ -1 +1,3 @@
+pub mod files;
+pub mod parallel;
 pub mod process;
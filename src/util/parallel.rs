@@ -0,0 +1,62 @@
+//! A small scoped worker pool for the commands that fan work out over `--jobs` threads (pack
+//! builds, `pack verify --all`, `flow test`, `mcp doctor`).
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Run `f` over `items` on up to `jobs` threads. Workers take the next item until none are left;
+/// the results keep the input order.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().expect("results lock")[index] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .expect("results lock")
+        .into_iter()
+        .map(|result| result.expect("every item ran"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parallel_map;
+
+    #[test]
+    fn results_keep_the_input_order() {
+        // Earlier items take longest, so workers finish them last.
+        let items = (0..16u64).collect::<Vec<_>>();
+        let results = parallel_map(&items, 4, |item| {
+            std::thread::sleep(std::time::Duration::from_millis(16 - item));
+            item * 10
+        });
+        assert_eq!(
+            results,
+            items.iter().map(|item| item * 10).collect::<Vec<_>>()
+        );
+        assert!(parallel_map(&[] as &[u64], 4, |item| *item).is_empty());
+        assert_eq!(parallel_map(&[1, 2], 0, |item| item + 1), [2, 3]);
+    }
+}
@@ -30,6 +30,18 @@ impl CommandSpec {
             stderr: StreamMode::Inherit,
         }
     }
+
+    /// Run `script` through the platform shell: `sh -c` on Unix, `cmd /C` on Windows.
+    pub fn shell(script: impl Into<OsString>) -> Self {
+        let (program, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let mut spec = Self::new(program);
+        spec.args = vec![OsString::from(flag), script.into()];
+        spec
+    }
 }
 
 pub struct CommandOutput {
@@ -54,7 +66,8 @@ pub fn run(spec: CommandSpec) -> Result<CommandOutput> {
             command.stdout(Stdio::inherit());
             command.stderr(Stdio::inherit());
             let status = command
-                .status()
+                .spawn()
+                .and_then(crate::interrupt::wait_child)
                 .with_context(|| format!("failed to spawn `{}`", spec.program.to_string_lossy()))?;
             Ok(CommandOutput {
                 status,
@@ -81,28 +94,29 @@ pub fn run(spec: CommandSpec) -> Result<CommandOutput> {
 #[cfg(test)]
 mod tests {
     use super::{CommandSpec, StreamMode, run};
-    use std::ffi::OsString;
 
     #[test]
     fn capture_mode_collects_stdout_and_stderr() {
-        let mut spec = CommandSpec::new("sh");
-        spec.args = vec![
-            OsString::from("-c"),
-            OsString::from("printf hello; printf world >&2"),
-        ];
+        let script = if cfg!(windows) {
+            "echo hello& echo world 1>&2"
+        } else {
+            "echo hello; echo world >&2"
+        };
+        let mut spec = CommandSpec::shell(script);
         spec.stdout = StreamMode::Capture;
         spec.stderr = StreamMode::Capture;
 
         let output = run(spec).unwrap();
         assert!(output.status.success());
-        assert_eq!(output.stdout.unwrap(), b"hello");
-        assert_eq!(output.stderr.unwrap(), b"world");
+        let stdout = String::from_utf8(output.stdout.unwrap()).unwrap();
+        let stderr = String::from_utf8(output.stderr.unwrap()).unwrap();
+        assert_eq!(stdout.trim(), "hello");
+        assert_eq!(stderr.trim(), "world");
     }
 
     #[test]
     fn inherit_mode_returns_status_without_buffers() {
-        let mut spec = CommandSpec::new("sh");
-        spec.args = vec![OsString::from("-c"), OsString::from("exit 0")];
+        let spec = CommandSpec::shell("exit 0");
 
         let output = run(spec).unwrap();
         assert!(output.status.success());
@@ -112,8 +126,7 @@ mod tests {
 
     #[test]
     fn mixed_modes_are_rejected() {
-        let mut spec = CommandSpec::new("sh");
-        spec.args = vec![OsString::from("-c"), OsString::from("exit 0")];
+        let mut spec = CommandSpec::shell("exit 0");
         spec.stdout = StreamMode::Capture;
         spec.stderr = StreamMode::Inherit;
 
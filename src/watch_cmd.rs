@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result, anyhow, bail};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::cli::WatchArgs;
+use crate::runtime;
+
+// Directories that only hold build output or tool state; changes there never trigger actions.
+const IGNORED_DIRS: &[&str] = &[".git", ".greentic", "target", "dist", "node_modules"];
+
+#[derive(Debug, Deserialize)]
+struct DevConfig {
+    watch: Option<WatchConfig>,
+}
+
+/// `watch:` section of `dev.yaml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchConfig {
+    /// Quiet period after the last change before an action runs.
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// How often the workspace is scanned for changes.
+    #[serde(default = "default_poll_ms")]
+    pub poll_ms: u64,
+    /// Actions keyed by name, run in name order when several are due.
+    pub actions: BTreeMap<String, WatchAction>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchAction {
+    /// Workspace-relative globs, e.g. `flows/**`.
+    pub paths: Vec<String>,
+    /// greentic-dev invocations run in order, e.g. `check` or `pack build --in .`. The action
+    /// stops at the first one that fails.
+    pub run: Vec<String>,
+}
+
+fn default_debounce_ms() -> u64 {
+    300
+}
+
+fn default_poll_ms() -> u64 {
+    500
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failed,
+}
+
+#[derive(Debug, Default)]
+struct ActionState {
+    // Time of the most recent change not yet handled.
+    pending_since: Option<Instant>,
+    last: Option<(Outcome, SystemTime)>,
+}
+
+struct CompiledAction {
+    name: String,
+    globs: GlobSet,
+    commands: Vec<Vec<String>>,
+}
+
+pub(crate) type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// Load and validate the `watch:` section of `dev.yaml`.
+pub fn load_config(path: &Path) -> Result<WatchConfig> {
+    let raw = fs::read_to_string(path).with_context(|| {
+        format!(
+            "failed to read {}; add a `watch:` section to configure `greentic-dev watch`",
+            path.display()
+        )
+    })?;
+    let config: DevConfig =
+        serde_yaml_bw::from_str(&raw).with_context(|| format!("invalid {}", path.display()))?;
+    let config = config
+        .watch
+        .ok_or_else(|| anyhow!("{} has no `watch:` section", path.display()))?;
+    if config.actions.is_empty() {
+        bail!("{} defines no watch actions", path.display());
+    }
+    Ok(config)
+}
+
+fn compile(config: &WatchConfig) -> Result<Vec<CompiledAction>> {
+    config
+        .actions
+        .iter()
+        .map(|(name, action)| {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &action.paths {
+                builder.add(
+                    Glob::new(pattern)
+                        .with_context(|| format!("action `{name}`: invalid glob `{pattern}`"))?,
+                );
+            }
+            let commands = action
+                .run
+                .iter()
+                .map(|line| {
+                    shlex::split(line)
+                        .filter(|argv| !argv.is_empty())
+                        .ok_or_else(|| anyhow!("action `{name}`: cannot parse command `{line}`"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if commands.is_empty() {
+                bail!("action `{name}` has nothing to run");
+            }
+            Ok(CompiledAction {
+                name: name.clone(),
+                globs: builder.build()?,
+                commands,
+            })
+        })
+        .collect()
+}
+
+/// `greentic-dev watch`: run the configured actions whenever their files change.
+pub fn run(args: WatchArgs) -> Result<()> {
+    let config = load_config(&args.config)?;
+    let actions = compile(&config)?;
+    let root = std::env::current_dir().context("failed to resolve workspace root")?;
+    let exe = std::env::current_exe().context("failed to locate the greentic-dev binary")?;
+    let debounce = Duration::from_millis(config.debounce_ms);
+    let poll = Duration::from_millis(config.poll_ms);
+
+    let mut states = actions
+        .iter()
+        .map(|action| (action.name.clone(), ActionState::default()))
+        .collect::<BTreeMap<_, _>>();
+    let mut snapshot = scan(&root)?;
+    println!(
+        "Watching {} for {} action(s); press Ctrl-C to stop",
+        root.display(),
+        actions.len()
+    );
+    if args.initial {
+        for action in &actions {
+            states.get_mut(&action.name).expect("state").pending_since = Some(Instant::now());
+        }
+    }
+    render_status(&states);
+
+    loop {
+        let current = scan(&root)?;
+        let changed = changed_paths(&snapshot, &current);
+        snapshot = current;
+        for action in &actions {
+            if changed.iter().any(|path| action.globs.is_match(path)) {
+                states.get_mut(&action.name).expect("state").pending_since = Some(Instant::now());
+            }
+        }
+
+        for action in &actions {
+            let state = states.get_mut(&action.name).expect("state");
+            let due = state
+                .pending_since
+                .is_some_and(|since| since.elapsed() >= debounce);
+            if !due {
+                continue;
+            }
+            state.pending_since = None;
+            println!();
+            let outcome = run_action(&exe, action);
+            state.last = Some((outcome, SystemTime::now()));
+            render_status(&states);
+        }
+        runtime::pause(poll);
+    }
+}
+
+fn run_action(exe: &Path, action: &CompiledAction) -> Outcome {
+    for argv in &action.commands {
+        println!("▶ [{}] greentic-dev {}", action.name, argv.join(" "));
+        let status = ProcessCommand::new(exe)
+            .args(argv)
+            .spawn()
+            .and_then(crate::interrupt::wait_child);
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                println!(
+                    "✗ [{}] exited with {}",
+                    action.name,
+                    status.code().unwrap_or(1)
+                );
+                return Outcome::Failed;
+            }
+            Err(err) => {
+                println!("✗ [{}] failed to start: {err}", action.name);
+                return Outcome::Failed;
+            }
+        }
+    }
+    Outcome::Passed
+}
+
+fn status_line(states: &BTreeMap<String, ActionState>) -> String {
+    states
+        .iter()
+        .map(|(name, state)| {
+            let last = match &state.last {
+                Some((outcome, at)) => {
+                    let marker = match outcome {
+                        Outcome::Passed => "✓",
+                        Outcome::Failed => "✗",
+                    };
+                    let at = time::OffsetDateTime::from(*at);
+                    format!(
+                        "{marker} {:02}:{:02}:{:02}",
+                        at.hour(),
+                        at.minute(),
+                        at.second()
+                    )
+                }
+                None => "–".to_string(),
+            };
+            let pending = if state.pending_since.is_some() {
+                " …"
+            } else {
+                ""
+            };
+            format!("[{name} {last}{pending}]")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// On a terminal the status line is redrawn in place; otherwise each update is its own line.
+fn render_status(states: &BTreeMap<String, ActionState>) {
+    let line = status_line(states);
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = write!(stdout, "\r\x1b[2K{line}");
+    } else {
+        let _ = writeln!(stdout, "{line}");
+    }
+    let _ = stdout.flush();
+}
+
+pub(crate) fn scan(root: &Path) -> Result<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    scan_dir(root, root, &mut snapshot)?;
+    Ok(snapshot)
+}
+
+fn scan_dir(root: &Path, dir: &Path, snapshot: &mut Snapshot) -> Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // Directories can disappear between listing and reading while files are being saved.
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let ignored = IGNORED_DIRS
+                .iter()
+                .any(|name| entry.file_name() == std::ffi::OsStr::new(name));
+            if !ignored {
+                scan_dir(root, &path, snapshot)?;
+            }
+        } else if let Ok(modified) = entry.metadata().and_then(|meta| meta.modified()) {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            snapshot.insert(relative, modified);
+        }
+    }
+    Ok(())
+}
+
+// Paths added, removed or modified between two scans.
+pub(crate) fn changed_paths(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(*modified))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionState, Outcome, Snapshot, changed_paths, compile, load_config, status_line};
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    fn config() -> super::WatchConfig {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dev.yaml");
+        std::fs::write(
+            &path,
+            r#"
+watch:
+  debounce_ms: 100
+  actions:
+    flows:
+      paths: ["flows/**", "pack.toml"]
+      run: ["check", "pack build --in . --out 'dist/my pack.gtpack'"]
+    gui:
+      paths: ["gui/**"]
+      run: ["gui stage"]
+"#,
+        )
+        .unwrap();
+        load_config(&path).unwrap()
+    }
+
+    #[test]
+    fn actions_match_their_globs() {
+        let config = config();
+        assert_eq!(config.debounce_ms, 100);
+        assert_eq!(config.poll_ms, 500);
+        let actions = compile(&config).unwrap();
+        let flows = &actions[0];
+        assert!(flows.globs.is_match(Path::new("flows/nested/main.ygtc")));
+        assert!(flows.globs.is_match(Path::new("pack.toml")));
+        assert!(!flows.globs.is_match(Path::new("gui/index.html")));
+        assert_eq!(
+            flows.commands[1],
+            ["pack", "build", "--in", ".", "--out", "dist/my pack.gtpack"]
+        );
+    }
+
+    #[test]
+    fn missing_watch_section_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dev.yaml");
+        std::fs::write(&path, "other: {}\n").unwrap();
+        let err = load_config(&path).unwrap_err();
+        assert!(err.to_string().contains("no `watch:` section"));
+    }
+
+    #[test]
+    fn changes_cover_edits_additions_and_removals() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        let before = Snapshot::from([
+            (PathBuf::from("a"), t0),
+            (PathBuf::from("b"), t0),
+            (PathBuf::from("c"), t0),
+        ]);
+        let after = Snapshot::from([
+            (PathBuf::from("a"), t0),
+            (PathBuf::from("b"), t1),
+            (PathBuf::from("d"), t0),
+        ]);
+        let mut changed = changed_paths(&before, &after);
+        changed.sort();
+        assert_eq!(changed, [Path::new("b"), Path::new("c"), Path::new("d")]);
+    }
+
+    #[test]
+    fn status_line_shows_last_result_per_action() {
+        let states = BTreeMap::from([
+            (
+                "flows".to_string(),
+                ActionState {
+                    pending_since: None,
+                    last: Some((Outcome::Failed, SystemTime::UNIX_EPOCH)),
+                },
+            ),
+            ("gui".to_string(), ActionState::default()),
+        ]);
+        assert_eq!(status_line(&states), "[flows ✗ 00:00:00] [gui –]");
+    }
+}
@@ -72,7 +72,8 @@ pub fn execute(
                 .stdin(Stdio::inherit())
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
-                .status()?;
+                .spawn()
+                .and_then(crate::interrupt::wait_child)?;
             if !status.success() {
                 bail!(
                     "{}",
@@ -174,7 +174,8 @@ fn run_interactive_delegate(
         command.env(EMBEDDED_WIZARD_ROOT_ZERO_ACTION_ENV, "back");
     }
     let status = command
-        .status()
+        .spawn()
+        .and_then(crate::interrupt::wait_child)
         .map_err(|e| anyhow::anyhow!("failed to execute {}: {e}", bin.display()))?;
     if !status.success() {
         bail!(
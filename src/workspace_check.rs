@@ -0,0 +1,380 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use greentic_flow::flow_bundle::load_and_validate_bundle;
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::capability_policy::{CapabilityPolicy, POLICY_PATH};
+use crate::cli::CheckArgs;
+use crate::component_manifest;
+use crate::flow_calls;
+use crate::flow_contract::FlowContract;
+use crate::interrupt::PartialOutput;
+use crate::pack_build::{self, PackBuildOptions, PackSigning};
+use crate::pack_preflight::{self, CheckStatus};
+
+/// Exit code when every check passed (warnings allowed).
+pub const EXIT_OK: i32 = 0;
+/// Exit code when at least one check failed.
+pub const EXIT_FAILED: i32 = 1;
+/// Exit code when the checks could not run at all (not a workspace, unreadable files).
+pub const EXIT_ERROR: i32 = 2;
+
+const FLOW_DIR: &str = "flows";
+const COMPONENT_DIR: &str = "components";
+const PACK_META: &str = "pack.toml";
+
+#[derive(Debug, Serialize)]
+struct Finding {
+    stage: &'static str,
+    subject: String,
+    status: CheckStatus,
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    ok: bool,
+    failed: usize,
+    warnings: usize,
+    findings: Vec<Finding>,
+}
+
+struct WorkspaceFlow {
+    path: PathBuf,
+    id: String,
+    doc: JsonValue,
+}
+
+/// `greentic-dev check`: validate flows and components, dry-run a pack build per flow, and check
+/// the capability policy and required secrets, with one aggregated report.
+pub fn run(args: &CheckArgs) -> Result<i32> {
+    let findings = check_workspace(args, &|key| std::env::var(key).ok())?;
+    let failed = count(&findings, CheckStatus::Fail);
+    let report = CheckReport {
+        ok: failed == 0,
+        failed,
+        warnings: count(&findings, CheckStatus::Warn),
+        findings,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for finding in &report.findings {
+            let marker = match finding.status {
+                CheckStatus::Ok => "✓",
+                CheckStatus::Warn => "!",
+                CheckStatus::Fail => "✗",
+            };
+            println!(
+                "{marker} {:<9} {}: {}",
+                finding.stage, finding.subject, finding.detail
+            );
+        }
+        println!(
+            "{} check(s), {} failed, {} warning(s)",
+            report.findings.len(),
+            report.failed,
+            report.warnings
+        );
+    }
+    Ok(if report.ok { EXIT_OK } else { EXIT_FAILED })
+}
+
+fn count(findings: &[Finding], status: CheckStatus) -> usize {
+    findings
+        .iter()
+        .filter(|finding| finding.status == status)
+        .count()
+}
+
+fn check_workspace(args: &CheckArgs, env: &dyn Fn(&str) -> Option<String>) -> Result<Vec<Finding>> {
+    let flow_dir = Path::new(FLOW_DIR);
+    let component_dir = Path::new(COMPONENT_DIR);
+    let meta = Path::new(PACK_META);
+    if !flow_dir.is_dir() && !component_dir.is_dir() {
+        anyhow::bail!(
+            "no {FLOW_DIR}/ or {COMPONENT_DIR}/ directory here; run `greentic-dev check` from the workspace root"
+        );
+    }
+    let meta = meta.is_file().then_some(meta);
+    let component_dir = component_dir.is_dir().then_some(component_dir);
+
+    let mut findings = Vec::new();
+    let flows = check_flows(flow_dir, meta, &mut findings)?;
+    if let Some(dir) = component_dir {
+        check_components(dir, &mut findings)?;
+    }
+    let policy_ok = check_policy_file(&mut findings);
+
+    let secrets = match &args.secrets_env {
+        Some(path) => Some(pack_preflight::read_env_file(path)?),
+        None => None,
+    };
+    let source = args
+        .secrets_env
+        .as_ref()
+        .map(|path| format!("{} + environment", path.display()))
+        .unwrap_or_else(|| "environment".to_string());
+    let lookup = |key: &str| {
+        secrets
+            .as_ref()
+            .and_then(|vars| vars.get(key).cloned())
+            .or_else(|| env(key))
+    };
+
+    let out_dir = tempfile::tempdir().context("failed to create a scratch directory")?;
+    let _scratch = PartialOutput::new(out_dir.path());
+    for flow in &flows {
+        let subject = flow.path.display().to_string();
+        if policy_ok && !check_flow_policy(flow, meta, component_dir, &mut findings) {
+            findings.push(finding(
+                "build",
+                &subject,
+                CheckStatus::Warn,
+                "skipped: capability policy violations",
+            ));
+            continue;
+        }
+        let output = out_dir.path().join(format!("{}.gtpack", flow.id));
+        if let Err(err) = pack_build::run_with_options(
+            &flow.path,
+            &output,
+            PackSigning::None,
+            meta,
+            component_dir,
+            PackBuildOptions::default(),
+        ) {
+            findings.push(finding(
+                "build",
+                &subject,
+                CheckStatus::Fail,
+                format!("{err:#}"),
+            ));
+            continue;
+        }
+        findings.push(finding("build", &subject, CheckStatus::Ok, "pack builds"));
+
+        match open_pack(&output, SigningPolicy::DevOk) {
+            Ok(load) => {
+                for check in pack_preflight::secret_checks(&load.manifest, &source, &lookup) {
+                    findings.push(finding(check.name, &subject, check.status, check.detail));
+                }
+            }
+            Err(err) => findings.push(finding(
+                "secrets",
+                &subject,
+                CheckStatus::Fail,
+                format!("built pack could not be opened: {}", err.message),
+            )),
+        }
+    }
+    Ok(findings)
+}
+
+fn finding(
+    stage: &'static str,
+    subject: &str,
+    status: CheckStatus,
+    detail: impl Into<String>,
+) -> Finding {
+    Finding {
+        stage,
+        subject: subject.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+// Validate every flow under `flows/` and resolve `flow.call` targets across all of them.
+fn check_flows(
+    flow_dir: &Path,
+    meta: Option<&Path>,
+    findings: &mut Vec<Finding>,
+) -> Result<Vec<WorkspaceFlow>> {
+    let mut paths = Vec::new();
+    collect_flow_files(flow_dir, &mut paths)?;
+    paths.sort();
+
+    let mut flows = Vec::new();
+    for path in paths {
+        let subject = path.display().to_string();
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let validated = serde_yaml_bw::from_str::<JsonValue>(&source)
+            .map_err(anyhow::Error::from)
+            .and_then(|doc| {
+                let bundle = load_and_validate_bundle(&source, Some(&path))?;
+                FlowContract::from_flow_doc(&doc).compile()?;
+                Ok((bundle.id, doc))
+            });
+        match validated {
+            Ok((id, doc)) => {
+                findings.push(finding("flow", &subject, CheckStatus::Ok, "valid"));
+                flows.push(WorkspaceFlow { path, id, doc });
+            }
+            Err(err) => findings.push(finding(
+                "flow",
+                &subject,
+                CheckStatus::Fail,
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    let mut targets = flow_calls::import_targets(meta)?;
+    for flow in &flows {
+        targets.add_flow(&flow.id, &flow.doc);
+    }
+    for flow in &flows {
+        for error in flow_calls::check(&flow.id, &flow.doc, &targets) {
+            findings.push(finding(
+                "flow",
+                &flow.path.display().to_string(),
+                CheckStatus::Fail,
+                error,
+            ));
+        }
+    }
+    Ok(flows)
+}
+
+fn collect_flow_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_flow_files(&path, paths)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("ygtc") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Validate each component manifest and flag anything `component doctor --fix` would change.
+fn check_components(component_dir: &Path, findings: &mut Vec<Finding>) -> Result<()> {
+    let mut dirs = fs::read_dir(component_dir)
+        .with_context(|| format!("failed to read {}", component_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    dirs.sort();
+
+    for dir in dirs {
+        let path = component_manifest::manifest_path(&dir);
+        let subject = path.display().to_string();
+        if !path.is_file() {
+            findings.push(finding(
+                "component",
+                &subject,
+                CheckStatus::Warn,
+                "no component manifest",
+            ));
+            continue;
+        }
+        let mut manifest = match component_manifest::read_manifest(&path) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                findings.push(finding(
+                    "component",
+                    &subject,
+                    CheckStatus::Fail,
+                    format!("{err:#}"),
+                ));
+                continue;
+            }
+        };
+        let issues = component_manifest::schema_issues(&manifest);
+        if !issues.is_empty() {
+            for issue in issues {
+                findings.push(finding(
+                    "component",
+                    &subject,
+                    CheckStatus::Fail,
+                    issue.to_string(),
+                ));
+            }
+            continue;
+        }
+        let fixable = component_manifest::fix_manifest(&mut manifest, &dir)?;
+        if fixable.is_empty() {
+            findings.push(finding("component", &subject, CheckStatus::Ok, "valid"));
+        } else {
+            let fields = fixable
+                .iter()
+                .map(|edit| edit.field.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            findings.push(finding(
+                "component",
+                &subject,
+                CheckStatus::Warn,
+                format!("fixable with `component doctor --fix`: {fields}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// A policy file that does not parse fails once here instead of once per flow.
+fn check_policy_file(findings: &mut Vec<Finding>) -> bool {
+    match CapabilityPolicy::load(Path::new(POLICY_PATH)) {
+        Ok(_) => true,
+        Err(err) => {
+            findings.push(finding(
+                "policy",
+                POLICY_PATH,
+                CheckStatus::Fail,
+                format!("{err:#}"),
+            ));
+            false
+        }
+    }
+}
+
+// Returns false when the flow's components violate the capability policy.
+fn check_flow_policy(
+    flow: &WorkspaceFlow,
+    meta: Option<&Path>,
+    component_dir: Option<&Path>,
+    findings: &mut Vec<Finding>,
+) -> bool {
+    let subject = flow.path.display().to_string();
+    let explanation = match pack_build::explain_capabilities(&flow.path, meta, component_dir) {
+        Ok(explanation) => explanation,
+        // Resolution problems are reported by the build step.
+        Err(_) => return true,
+    };
+    if explanation.policy.is_none() {
+        return true;
+    }
+    let mut ok = true;
+    for entry in &explanation.components {
+        for violation in &entry.violations {
+            ok = false;
+            findings.push(finding(
+                "policy",
+                &subject,
+                CheckStatus::Fail,
+                format!("{}: {violation}", entry.component),
+            ));
+        }
+    }
+    if ok {
+        findings.push(finding(
+            "policy",
+            &subject,
+            CheckStatus::Ok,
+            format!("pack {} complies with {POLICY_PATH}", explanation.pack_id),
+        ));
+    }
+    ok
+}
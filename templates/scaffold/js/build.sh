@@ -0,0 +1,13 @@
+#!/usr/bin/env sh
+# Build {{id}} into component.wasm and record its hash in component.manifest.json.
+set -eu
+cd "$(dirname "$0")"
+
+[ -d node_modules ] || npm install --no-audit --no-fund
+npm run build
+
+if command -v greentic-dev >/dev/null 2>&1; then
+  greentic-dev component hash update .
+else
+  echo "greentic-dev not found; run \`greentic-dev component hash update .\` before packing" >&2
+fi
@@ -0,0 +1,48 @@
+// Implements the `node` interface of greentic:component/component@0.6.0 for {{id}}.
+// Byte payloads are Uint8Arrays. Throwing a NodeError-shaped object returns `err`.
+
+// An empty CBOR map: a schema that accepts any payload.
+const anyCbor = () => ({
+  schema: { tag: "inline-cbor", val: new Uint8Array([0xa0]) },
+  contentType: "application/cbor",
+  schemaVersion: undefined,
+});
+
+export const node = {
+  describe() {
+    return {
+      name: "{{id}}",
+      version: "0.1.0",
+      summary: "Echoes its input",
+      capabilities: [],
+      ops: [
+        {
+          name: "{{operation}}",
+          summary: "Return the input payload unchanged",
+          input: anyCbor(),
+          output: anyCbor(),
+          examples: [],
+        },
+      ],
+      schemas: [],
+      setup: undefined,
+    };
+  },
+
+  invoke(op, envelope) {
+    if (op !== "{{operation}}") {
+      throw {
+        code: "UNKNOWN_OP",
+        message: `unknown operation \`${op}\``,
+        retryable: false,
+        backoffMs: undefined,
+        details: undefined,
+      };
+    }
+    return {
+      ok: true,
+      outputCbor: envelope.payloadCbor,
+      outputMetadataCbor: undefined,
+    };
+  },
+};
@@ -0,0 +1,14 @@
+#!/usr/bin/env sh
+# Build {{id}} into component.wasm and record its hash in component.manifest.json.
+set -eu
+cd "$(dirname "$0")"
+
+rustup target add wasm32-wasip2 >/dev/null 2>&1 || true
+cargo build --release --target wasm32-wasip2
+cp target/wasm32-wasip2/release/{{crate_name}}.wasm component.wasm
+
+if command -v greentic-dev >/dev/null 2>&1; then
+  greentic-dev component hash update .
+else
+  echo "greentic-dev not found; run \`greentic-dev component hash update .\` before packing" >&2
+fi
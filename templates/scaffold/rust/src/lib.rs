@@ -0,0 +1,61 @@
+wit_bindgen::generate!({
+    path: "wit",
+    world: "{{name}}",
+});
+
+use exports::greentic::component::node::{
+    ComponentDescriptor, Guest, InvocationEnvelope, InvocationResult, IoSchema, NodeError, Op,
+    SchemaSource,
+};
+
+/// An empty CBOR map: a schema that accepts any payload.
+const ANY_CBOR_SCHEMA: &[u8] = &[0xa0];
+
+struct Component;
+
+impl Guest for Component {
+    fn describe() -> ComponentDescriptor {
+        ComponentDescriptor {
+            name: "{{id}}".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            summary: Some("Echoes its input".to_string()),
+            capabilities: Vec::new(),
+            ops: vec![Op {
+                name: "{{operation}}".to_string(),
+                summary: Some("Return the input payload unchanged".to_string()),
+                input: any_cbor(),
+                output: any_cbor(),
+                examples: Vec::new(),
+            }],
+            schemas: Vec::new(),
+            setup: None,
+        }
+    }
+
+    fn invoke(op: String, envelope: InvocationEnvelope) -> Result<InvocationResult, NodeError> {
+        match op.as_str() {
+            "{{operation}}" => Ok(InvocationResult {
+                ok: true,
+                output_cbor: envelope.payload_cbor,
+                output_metadata_cbor: None,
+            }),
+            other => Err(NodeError {
+                code: "UNKNOWN_OP".to_string(),
+                message: format!("unknown operation `{other}`"),
+                retryable: false,
+                backoff_ms: None,
+                details: None,
+            }),
+        }
+    }
+}
+
+fn any_cbor() -> IoSchema {
+    IoSchema {
+        schema: SchemaSource::InlineCbor(ANY_CBOR_SCHEMA.to_vec()),
+        content_type: "application/cbor".to_string(),
+        schema_version: None,
+    }
+}
+
+export!(Component);
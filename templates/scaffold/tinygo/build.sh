@@ -0,0 +1,14 @@
+#!/usr/bin/env sh
+# Build {{id}} into component.wasm and record its hash in component.manifest.json.
+set -eu
+cd "$(dirname "$0")"
+
+[ -f go.sum ] || go mod tidy
+go tool wit-bindgen-go generate --world {{name}} --out internal ./wit
+tinygo build -target=wasip2 -no-debug --wit-package ./wit --wit-world {{name}} -o component.wasm .
+
+if command -v greentic-dev >/dev/null 2>&1; then
+  greentic-dev component hash update .
+else
+  echo "greentic-dev not found; run \`greentic-dev component hash update .\` before packing" >&2
+fi
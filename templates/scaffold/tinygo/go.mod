@@ -0,0 +1,10 @@
+module {{name}}
+
+go 1.24
+
+tool go.bytecodealliance.org/cmd/wit-bindgen-go
+
+require (
+	go.bytecodealliance.org v0.7.0
+	go.bytecodealliance.org/cm v0.3.0
+)
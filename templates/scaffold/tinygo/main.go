@@ -0,0 +1,56 @@
+// Package main implements {{id}}, a Greentic component. The bindings under internal/ are
+// generated from wit/ by build.sh.
+package main
+
+import (
+	"{{name}}/internal/greentic/component/node"
+
+	"go.bytecodealliance.org/cm"
+)
+
+// anyCborSchema is an empty CBOR map: a schema that accepts any payload.
+var anyCborSchema = []byte{0xa0}
+
+type invokeResult = cm.Result[node.NodeErrorShape, node.InvocationResult, node.NodeError]
+
+func init() {
+	node.Exports.Describe = describe
+	node.Exports.Invoke = invoke
+}
+
+func describe() node.ComponentDescriptor {
+	return node.ComponentDescriptor{
+		Name:    "{{id}}",
+		Version: "0.1.0",
+		Summary: cm.Some("Echoes its input"),
+		Ops: cm.ToList([]node.Op{{
+			Name:    "{{operation}}",
+			Summary: cm.Some("Return the input payload unchanged"),
+			Input:   anyCbor(),
+			Output:  anyCbor(),
+		}}),
+	}
+}
+
+func invoke(op string, envelope node.InvocationEnvelope) invokeResult {
+	if op != "{{operation}}" {
+		return cm.Err[invokeResult](node.NodeError{
+			Code:    "UNKNOWN_OP",
+			Message: "unknown operation `" + op + "`",
+		})
+	}
+	return cm.OK[invokeResult](node.InvocationResult{
+		OK:         true,
+		OutputCbor: envelope.PayloadCbor,
+	})
+}
+
+func anyCbor() node.IOSchema {
+	return node.IOSchema{
+		Schema:      node.SchemaSourceInlineCbor(cm.ToList(anyCborSchema)),
+		ContentType: "application/cbor",
+	}
+}
+
+// main is required by TinyGo but never called; the host calls the exports.
+func main() {}
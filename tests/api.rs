@@ -0,0 +1,134 @@
+#![cfg(unix)]
+
+mod support;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+use anyhow::{Context, Result};
+use greentic_dev::api::{self, BuildRequest, ExecutionContext, RunRequest, RunStatus, TraceStatus};
+use serde_json::json;
+use support::{WORKDIR_LOCK, Workspace, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn built_packs_report_what_inspect_reads_back() -> Result<()> {
+    let workspace = Workspace::new("api-build")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let built = {
+        let _guard = WORKDIR_LOCK.lock().unwrap();
+        let prev = std::env::current_dir()?;
+        std::env::set_current_dir(&workspace.root)?;
+        let built = api::build_pack(&BuildRequest {
+            component_dir: Some(component_dir.parent().context("component root")?.into()),
+            ..BuildRequest::new(&flow_path, workspace.root.join("dist/hello.gtpack"))
+        });
+        std::env::set_current_dir(prev)?;
+        built?
+    };
+    assert_eq!(built.pack, workspace.root.join("dist/hello.gtpack"));
+    assert!(!built.manifest_hash_blake3.is_empty());
+    assert!(!built.timings.is_empty());
+
+    let inspected = api::inspect_pack(&built.pack)?;
+    assert_eq!(inspected.flows.len(), 1);
+    assert_eq!(inspected.components.len(), 1);
+    assert_eq!(inspected.components[0].name, "dev.greentic.echo");
+    assert!(inspected.sbom_ok);
+    assert!(api::inspect_pack(&workspace.root.join("missing.gtpack")).is_err());
+    Ok(())
+}
+
+#[test]
+fn runs_return_the_runner_result_as_a_report() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let args_file = dir.path().join("args");
+    let stub = dir.path().join("greentic-runner-cli");
+    fs::write(
+        &stub,
+        format!(
+            "#!/bin/sh\necho \"$@\" > {}\ncat <<'EOF'\n{}\nEOF\nexit 1\n",
+            args_file.display(),
+            r#"{"status": "failed", "trace": [{"node_id": "start", "status": "failed"}], "error": {"code": "E_NODE", "message": "boom"}}"#
+        ),
+    )?;
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755))?;
+
+    let report = api::run_pack(&RunRequest {
+        entry: Some("greet".to_string()),
+        input: Some(json!({"text": "hi"})),
+        context: ExecutionContext {
+            runner: Some(stub),
+            ..ExecutionContext::default()
+        },
+        ..RunRequest::new("missing.gtpack")
+    })?;
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.schema_version, 1);
+    assert_eq!(report.trace[0].status, TraceStatus::Failed);
+    assert_eq!(
+        report.error.map(|error| error.code).as_deref(),
+        Some("E_NODE")
+    );
+    assert_eq!(
+        fs::read_to_string(&args_file)?.trim(),
+        r#"--pack missing.gtpack --entry greet --input {"text":"hi"} --json"#
+    );
+    Ok(())
+}
+
+#[test]
+fn runs_in_separate_contexts_do_not_share_state() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let stub = dir.path().join("greentic-runner-cli");
+    fs::write(
+        &stub,
+        r#"#!/bin/sh
+sleep 0.2
+cat <<EOF
+{"status": "succeeded", "output": {"pwd": "$PWD", "home": "$HOME", "cache": "$WASMTIME_CACHE_DIR", "pack": "$2"}}
+EOF
+"#,
+    )?;
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755))?;
+
+    let roots = ["a", "b", "c"].map(|name| dir.path().join(name));
+    let reports = std::thread::scope(|scope| {
+        let runs = roots
+            .iter()
+            .map(|root| {
+                let stub = stub.clone();
+                scope.spawn(move || {
+                    api::run_pack(&RunRequest {
+                        context: ExecutionContext {
+                            runner: Some(stub),
+                            ..ExecutionContext::isolated(root)
+                        },
+                        ..RunRequest::new("app.gtpack")
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        runs.into_iter()
+            .map(|run| run.join().unwrap())
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    for (root, report) in roots.iter().zip(reports) {
+        let output = report.output.context("output")?;
+        assert_eq!(output["pwd"], root.canonicalize()?.display().to_string());
+        assert_eq!(
+            output["home"],
+            root.join(".greentic/home").display().to_string()
+        );
+        assert_eq!(
+            output["cache"],
+            root.join(".greentic/cache/wasmtime").display().to_string()
+        );
+        assert_eq!(
+            output["pack"],
+            root.join("app.gtpack").display().to_string()
+        );
+    }
+    Ok(())
+}
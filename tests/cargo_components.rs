@@ -0,0 +1,97 @@
+mod support;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use support::{Workspace, copy_fixture_component, load_gtpack, write_pack_flow};
+
+fn build(root: &Path, cargo: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(root)
+        .args([
+            "pack",
+            "build",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--out",
+            "dist/hello.gtpack",
+            "--timings",
+        ])
+        .env("GREENTIC_DEV_BIN_CARGO", cargo)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn mapped_components_are_built_from_their_cargo_project_once_per_change() {
+    let workspace = Workspace::new("cargo-components").unwrap();
+    let root = workspace.root.as_path();
+    // The echo fixture becomes a Cargo project outside the component directory, and its wasm what
+    // the stub cargo reports having built.
+    let fixture = copy_fixture_component(&workspace, false).unwrap();
+    let project = root.join("echo-rs");
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::create_dir_all(project.join("target/wasm32-wasip2/release")).unwrap();
+    fs::write(
+        project.join("Cargo.toml"),
+        "[package]\nname = \"echo-rs\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    fs::write(project.join("src/lib.rs"), "// v1\n").unwrap();
+    fs::rename(
+        fixture.join("component.manifest.json"),
+        project.join("component.manifest.json"),
+    )
+    .unwrap();
+    let built = project.join("target/wasm32-wasip2/release/echo_rs.wasm");
+    fs::rename(fixture.join("component.wasm"), &built).unwrap();
+    fs::remove_dir_all(root.join("components")).unwrap();
+    write_pack_flow(&workspace, "hello-flow").unwrap();
+    fs::create_dir_all(root.join(".greentic")).unwrap();
+    fs::write(
+        root.join(".greentic/resolve.toml"),
+        "[components]\n\"dev.greentic.echo\" = { path = \"echo-rs\" }\n",
+    )
+    .unwrap();
+
+    let log = root.join("cargo.log");
+    let cargo = root.join("cargo");
+    fs::write(
+        &cargo,
+        format!(
+            "#!/bin/sh\necho \"$*\" >> {}\necho '{{\"reason\":\"compiler-artifact\",\"filenames\":[\"{}\"]}}'\n",
+            log.display(),
+            built.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&cargo, fs::Permissions::from_mode(0o755)).unwrap();
+    let runs = || fs::read_to_string(&log).unwrap_or_default().lines().count();
+
+    let output = build(root, &cargo);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(runs(), 1);
+    let invocation = fs::read_to_string(&log).unwrap();
+    assert!(
+        invocation.starts_with("build --release --target wasm32-wasip2"),
+        "{invocation}"
+    );
+    let (manifest, _) = load_gtpack(&root.join("dist/hello.gtpack")).unwrap();
+    assert_eq!(manifest.components[0].name, "dev.greentic.echo");
+    assert!(
+        root.join(".greentic/cache/cargo/dev.greentic.echo/component.wasm")
+            .is_file()
+    );
+
+    // Unchanged sources reuse the staged build.
+    let output = build(root, &cargo);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(runs(), 1);
+
+    fs::write(project.join("src/lib.rs"), "// v2\n").unwrap();
+    let output = build(root, &cargo);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(runs(), 2);
+}
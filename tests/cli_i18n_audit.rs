@@ -35,4 +35,7 @@ fn assert_no_hardcoded_clap_help(path: &Path) {
 fn clap_help_text_is_routed_through_i18n() {
     assert_no_hardcoded_clap_help(Path::new("src/cli.rs"));
     assert_no_hardcoded_clap_help(Path::new("src/secrets_cli.rs"));
+    assert_no_hardcoded_clap_help(Path::new("src/pack_cli.rs"));
+    assert_no_hardcoded_clap_help(Path::new("src/flow_cli.rs"));
+    assert_no_hardcoded_clap_help(Path::new("src/component_manifest_cli.rs"));
 }
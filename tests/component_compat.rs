@@ -0,0 +1,83 @@
+mod support;
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use anyhow::Result;
+use serde_json::Value;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+const ECHO_WASM: &str = "fixtures/components/dev.greentic.echo/component.wasm";
+
+fn compat(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["component", "compat"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn single_components_are_checked_against_a_world() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = compat(
+        root,
+        &[
+            ECHO_WASM,
+            "--world",
+            "greentic:component/component@0.4.0",
+            "--json",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["missing_exports"], serde_json::json!([]));
+    assert_eq!(report["extra_imports"], serde_json::json!([]));
+
+    // The default world is the current one, which the 0.4.0 fixture predates.
+    let output = compat(root, &[ECHO_WASM]);
+    assert!(!output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("missing export: greentic:component/node@0.6.0"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("extra export: greentic:component/node@0.4.0"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn pack_matrix_covers_every_runner_world() -> Result<()> {
+    let workspace = Workspace::new("component-compat")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let output = compat(
+        &workspace.root,
+        &["--pack", pack.to_str().unwrap(), "--json"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let rows: Value = serde_json::from_slice(&output.stdout)?;
+    let row = &rows[0];
+    assert_eq!(row["component"], "dev.greentic.echo");
+    let worlds = row["worlds"].as_array().unwrap();
+    assert_eq!(worlds.len(), 3);
+    let fits = worlds
+        .iter()
+        .filter(|compat| {
+            compat["missing_exports"].as_array().unwrap().is_empty()
+                && compat["extra_imports"].as_array().unwrap().is_empty()
+        })
+        .map(|compat| compat["world"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(fits, ["greentic:component/component@0.4.0"]);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::component_lock::{ComponentLock, LOCK_PATH};
+use greentic_dev::pack_build::PackBuildOptions;
+use support::{
+    Workspace, build_pack, build_pack_with_options, copy_fixture_component, write_pack_flow,
+};
+
+#[test]
+fn builds_pin_components_and_fail_on_drift_until_the_lock_is_updated() -> Result<()> {
+    let workspace = Workspace::new("component-lock")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let component_root = component_dir.parent().expect("component root");
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let lock_path = workspace.root.join(LOCK_PATH);
+
+    build_pack(&workspace, &flow_path, component_root)?;
+    let pinned = ComponentLock::load(&lock_path)?.expect("lock written by the build");
+    let echo = pinned
+        .components
+        .get("dev.greentic.echo")
+        .expect("echo pinned")
+        .clone();
+    assert!(echo.hash.starts_with("blake3:"), "{}", echo.hash);
+
+    // Same resolution: the build passes and leaves the lock alone.
+    build_pack(&workspace, &flow_path, component_root)?;
+    assert_eq!(ComponentLock::load(&lock_path)?, Some(pinned.clone()));
+
+    // A lock from another machine that pinned a different wasm.
+    let mut foreign = pinned.clone();
+    foreign
+        .components
+        .get_mut("dev.greentic.echo")
+        .expect("echo pinned")
+        .hash = format!("blake3:{}", "0".repeat(64));
+    fs::write(&lock_path, toml::to_string(&foreign)?)?;
+    let err = build_pack(&workspace, &flow_path, component_root).unwrap_err();
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("differs from greentic-dev.lock"),
+        "{message}"
+    );
+    assert!(message.contains("dev.greentic.echo"), "{message}");
+
+    build_pack_with_options(
+        &workspace,
+        &flow_path,
+        component_root,
+        PackBuildOptions {
+            update_lock: true,
+            ..PackBuildOptions::default()
+        },
+    )?;
+    assert_eq!(
+        ComponentLock::load(&lock_path)?.map(|lock| lock.components),
+        Some(pinned.components)
+    );
+    Ok(())
+}
@@ -0,0 +1,142 @@
+mod support;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value as JsonValue;
+use support::{Workspace, copy_fixture_component, write_pack_flow};
+
+fn greentic_dev(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(root)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn build(root: &Path) -> Output {
+    greentic_dev(
+        root,
+        &[
+            "pack",
+            "build",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--component-dir",
+            "components",
+            "--out",
+            "dist/hello.gtpack",
+            "--timings",
+        ],
+    )
+}
+
+#[test]
+fn pin_rewrites_flow_refs_sidecars_and_stale_lock_entries() {
+    let workspace = Workspace::new("component-pin").unwrap();
+    let root = workspace.root.as_path();
+    copy_fixture_component(&workspace, false).unwrap();
+    let flow = write_pack_flow(&workspace, "hello-flow").unwrap();
+    let sidecar = root.join("flows/hello-flow.ygtc.resolve.json");
+    fs::write(
+        &sidecar,
+        r#"{
+  "schema_version": 1,
+  "flow": "hello-flow.ygtc",
+  "nodes": {
+    "start": { "source": { "kind": "oci", "ref": "oci://ghcr.io/acme/echo:0.1.0", "digest": "sha256:00" } }
+  }
+}
+"#,
+    )
+    .unwrap();
+    let output = build(root);
+    assert!(output.status.success(), "{output:?}");
+    assert!(
+        fs::read_to_string(root.join("greentic-dev.lock"))
+            .unwrap()
+            .contains("dev.greentic.echo")
+    );
+
+    // The fixture is 0.1.0, so an exact pin to it keeps the lock entry and the build working.
+    let pin = |version: &str| {
+        greentic_dev(
+            root,
+            &[
+                "component",
+                "pin",
+                "dev.greentic.echo",
+                version,
+                "--flow",
+                "flows/hello-flow.ygtc",
+            ],
+        )
+    };
+    let output = pin("0.1.0");
+    assert!(output.status.success(), "{output:?}");
+    let source = fs::read_to_string(&flow).unwrap();
+    assert_eq!(
+        source
+            .matches("component: \"dev.greentic.echo@=0.1.0\"")
+            .count(),
+        2,
+        "{source}"
+    );
+    let output = build(root);
+    assert!(output.status.success(), "{output:?}");
+
+    let output = pin("0.2.0");
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "Pinned `dev.greentic.echo` to `=0.2.0` in flows/hello-flow.ygtc (nodes: log, start)"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Dropped `dev.greentic.echo` 0.1.0 from greentic-dev.lock"),
+        "{stdout}"
+    );
+    assert!(
+        !fs::read_to_string(root.join("greentic-dev.lock"))
+            .unwrap()
+            .contains("dev.greentic.echo")
+    );
+    let resolve: JsonValue = serde_json::from_str(&fs::read_to_string(&sidecar).unwrap()).unwrap();
+    assert_eq!(
+        resolve["nodes"]["start"]["source"]["ref"],
+        "oci://ghcr.io/acme/echo:0.2.0"
+    );
+    assert!(resolve["nodes"]["start"]["source"].get("digest").is_none());
+    // Only 0.1.0 is on disk.
+    let output = build(root);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("does not satisfy requirement `=0.2.0`"),
+        "{stderr}"
+    );
+
+    let output = greentic_dev(
+        root,
+        &[
+            "component",
+            "unpin",
+            "dev.greentic.echo",
+            "--flow",
+            "flows/hello-flow.ygtc",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        fs::read_to_string(&flow).unwrap(),
+        fs::read_to_string(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hello-pack/hello-flow.ygtc")
+        )
+        .unwrap()
+    );
+    let output = build(root);
+    assert!(output.status.success(), "{output:?}");
+}
@@ -0,0 +1,207 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::component_resolver::{ComponentResolver, OciOptions};
+use greentic_dev::explain::{self, RESOLVE_COMPONENT_NOT_FOUND, RESOLVE_VERSION_MISMATCH};
+use semver::VersionReq;
+use support::{Workspace, build_pack, copy_fixture_component};
+
+#[test]
+fn malformed_manifests_name_the_file_and_field() -> Result<()> {
+    let workspace = Workspace::new("resolver-manifest-schema")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["version"] = serde_json::json!(1);
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    let resolver = ComponentResolver::new(component_dir.parent().map(Into::into));
+    let err = resolver
+        .resolve_component("dev.greentic.echo", &VersionReq::STAR)
+        .expect_err("invalid manifest");
+    let message = format!("{err:#}");
+    assert!(
+        message.contains(&manifest_path.display().to_string()),
+        "{message}"
+    );
+    assert!(
+        message.contains("`/version`: expected string, found integer"),
+        "{message}"
+    );
+    Ok(())
+}
+
+#[test]
+fn resolution_failures_carry_an_explainable_code() -> Result<()> {
+    let workspace = Workspace::new("resolver-error-codes")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let resolver = ComponentResolver::new(component_dir.parent().map(Into::into));
+
+    let missing = resolver
+        .resolve_component("dev.greentic.nope", &VersionReq::STAR)
+        .expect_err("missing component");
+    assert_eq!(
+        explain::code_of(&missing),
+        Some(RESOLVE_COMPONENT_NOT_FOUND)
+    );
+    assert!(
+        format!("{missing:#}").contains("greentic-dev explain RESOLVE_COMPONENT_NOT_FOUND"),
+        "{missing:#}"
+    );
+
+    let too_old = resolver
+        .resolve_component("dev.greentic.echo", &VersionReq::parse(">=99")?)
+        .expect_err("version mismatch");
+    assert_eq!(explain::code_of(&too_old), Some(RESOLVE_VERSION_MISMATCH));
+    assert!(explain::explanation(RESOLVE_COMPONENT_NOT_FOUND).is_some());
+    Ok(())
+}
+
+#[test]
+fn concurrent_nodes_prepare_a_shared_component_once() -> Result<()> {
+    let workspace = Workspace::new("resolver-concurrent")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let resolver = ComponentResolver::new(component_dir.parent().map(Into::into));
+
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                resolver
+                    .resolve_component("dev.greentic.echo", &VersionReq::STAR)
+                    .expect("resolve")
+            });
+        }
+    });
+    assert_eq!(resolver.prepared_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn pack_build_reports_the_first_failing_node_in_flow_order() -> Result<()> {
+    let workspace = Workspace::new("resolver-error-order")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let components = [
+        "dev.greentic.echo",
+        "dev.greentic.echo",
+        "missing.first",
+        "dev.greentic.echo",
+        "missing.second",
+        "dev.greentic.echo",
+    ];
+    let mut flow = String::from("id: many-nodes\ntype: messaging\nstart: n0\nnodes:\n");
+    for (index, component) in components.iter().enumerate() {
+        let routing = if index + 1 < components.len() {
+            format!("      - to: n{}\n", index + 1)
+        } else {
+            "      - out: true\n".to_string()
+        };
+        flow.push_str(&format!(
+            "  n{index}:\n    component.exec:\n      component: \"{component}\"\n      op: \"echo\"\n      input:\n        message: \"hi\"\n    routing:\n{routing}"
+        ));
+    }
+    let flow_path = workspace.root.join("many-nodes.ygtc");
+    fs::write(&flow_path, flow)?;
+
+    // Nodes resolve in parallel; the reported failure must not depend on which finishes first.
+    for _ in 0..3 {
+        let err = build_pack(
+            &workspace,
+            &flow_path,
+            component_dir.parent().expect("component root"),
+        )
+        .expect_err("missing components");
+        let message = format!("{err:#}");
+        assert!(message.contains("missing.first"), "{message}");
+        assert!(!message.contains("missing.second"), "{message}");
+    }
+    Ok(())
+}
+
+fn cached_oci_component(workspace: &Workspace, digest_hex: &str) -> Result<OciOptions> {
+    let component_dir = copy_fixture_component(workspace, false)?;
+    let cache_dir = workspace.root.join(".greentic/cache/oci");
+    fs::create_dir_all(&cache_dir)?;
+    fs::rename(&component_dir, cache_dir.join(digest_hex))?;
+    Ok(OciOptions {
+        cache_dir,
+        offline: true,
+        allow_tags: false,
+        limit_rate: None,
+    })
+}
+
+#[test]
+fn digest_pinned_oci_refs_resolve_from_the_cache_offline() -> Result<()> {
+    let workspace = Workspace::new("resolver-oci-cache")?;
+    let digest_hex = "4d9cd7b7373637bacd212174a26ed0361fe8f3b9f8f2781ca6aa5ecee64d5019";
+    let oci = cached_oci_component(&workspace, digest_hex)?;
+    let resolver = ComponentResolver::new(None).with_oci(oci);
+
+    let reference = format!("oci://ghcr.io/greentic/components/echo@sha256:{digest_hex}");
+    let component = resolver.resolve_component(&reference, &VersionReq::STAR)?;
+    assert_eq!(component.name, "dev.greentic.echo");
+    assert!(
+        component
+            .wasm_path
+            .starts_with(workspace.root.join(".greentic/cache/oci"))
+    );
+    // The second lookup is served from the resolver without touching the cache again.
+    resolver.resolve_component(&reference, &VersionReq::STAR)?;
+    assert_eq!(resolver.prepared_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn offline_oci_resolution_needs_a_cached_digest() -> Result<()> {
+    let workspace = Workspace::new("resolver-oci-offline")?;
+    let oci = cached_oci_component(&workspace, &"a".repeat(64))?;
+    let resolver = ComponentResolver::new(None).with_oci(OciOptions {
+        allow_tags: true,
+        ..oci
+    });
+
+    let missing = format!(
+        "oci://ghcr.io/greentic/components/echo@sha256:{}",
+        "b".repeat(64)
+    );
+    let err = resolver
+        .resolve_component(&missing, &VersionReq::STAR)
+        .expect_err("digest not cached");
+    assert!(
+        format!("{err:#}").contains("offline mode could not find"),
+        "{err:#}"
+    );
+
+    let err = resolver
+        .resolve_component(
+            "oci://ghcr.io/greentic/components/echo:latest",
+            &VersionReq::STAR,
+        )
+        .expect_err("tags need the network");
+    assert!(format!("{err:#}").contains("pin by digest"), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn tag_refs_need_allow_tags() -> Result<()> {
+    let workspace = Workspace::new("resolver-oci-tags")?;
+    let oci = cached_oci_component(&workspace, &"a".repeat(64))?;
+    let resolver = ComponentResolver::new(None).with_oci(OciOptions {
+        offline: false,
+        ..oci
+    });
+    let err = resolver
+        .resolve_component(
+            "oci://ghcr.io/greentic/components/echo:latest",
+            &VersionReq::STAR,
+        )
+        .expect_err("tag refs are rejected");
+    assert!(
+        format!("{err:#}").contains("digest pin required"),
+        "{err:#}"
+    );
+    Ok(())
+}
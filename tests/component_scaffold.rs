@@ -0,0 +1,42 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str::contains;
+use serde_json::Value as JsonValue;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn scaffold_generates_a_js_project_and_refuses_to_overwrite_it() {
+    let temp = tempdir().expect("tempdir");
+    let dir = temp.path().to_str().expect("utf-8 path");
+
+    cargo_bin_cmd!("greentic-dev")
+        .args(["component", "scaffold", "echo-bot", "--lang", "js"])
+        .args(["--id", "acme.echo", "--dir", dir])
+        .assert()
+        .success()
+        .stdout(contains("Scaffolded JavaScript component `acme.echo`"));
+
+    let project = temp.path().join("echo-bot");
+    for file in [
+        "package.json",
+        "src/component.js",
+        "build.sh",
+        "wit/world.wit",
+        "wit/deps/greentic-component-0.6.0/package.wit",
+    ] {
+        assert!(project.join(file).is_file(), "missing {file}");
+    }
+    let manifest: JsonValue = serde_json::from_str(
+        &fs::read_to_string(project.join("component.manifest.json")).expect("manifest"),
+    )
+    .expect("manifest json");
+    assert_eq!(manifest["id"], "acme.echo");
+    assert_eq!(manifest["world"], "greentic:component/component@0.6.0");
+    assert!(manifest["dev_flows"]["default"].is_object());
+
+    cargo_bin_cmd!("greentic-dev")
+        .args(["component", "scaffold", "echo-bot", "--dir", dir])
+        .assert()
+        .failure()
+        .stderr(contains("already exists and is not empty"));
+}
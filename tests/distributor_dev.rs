@@ -7,9 +7,20 @@ use greentic_dev::pack_init::{PackInitIntent, run, run_component_add};
 use httpmock::MockServer;
 use once_cell::sync::Lazy;
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use std::sync::Mutex;
 use tempfile::tempdir;
 
+fn sha256(bytes: &[u8]) -> String {
+    format!(
+        "sha256:{}",
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    )
+}
+
 static ENV_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
 fn write_config(base: &Path, url: &str) -> PathBuf {
@@ -48,6 +59,7 @@ fn distributor_component_and_pack_flows() -> Result<()> {
         std::env::set_current_dir(workspace.path()).unwrap();
 
         let server = MockServer::start();
+        let digest = sha256(b"wasm-bytes");
         let resolve_body = json!({
             "kind": "component",
             "name": "component-llm-openai",
@@ -55,7 +67,7 @@ fn distributor_component_and_pack_flows() -> Result<()> {
             "coordinate": "component://greentic/component-llm-openai@^0.3",
             "artifact_id": "artifacts/sha256:abc123",
             "artifact_download_path": "/v1/artifact/artifacts/sha256:abc123",
-            "digest": "sha256:abc123",
+            "digest": digest,
             "license": {
                 "license_type": "free",
                 "id": null,
@@ -103,9 +115,10 @@ token = ""
             "manifest should include component entry"
         );
 
-        let cache_path = temp_home
-            .path()
-            .join(".greentic/cache/components/sha256-abc123/artifact.wasm");
+        let cache_path = temp_home.path().join(format!(
+            ".greentic/cache/components/{}/artifact.wasm",
+            digest.replace(':', "-")
+        ));
         assert!(cache_path.exists(), "cached artifact should exist");
     }
 
@@ -118,6 +131,17 @@ token = ""
         let workspace = tempdir().unwrap();
         std::env::set_current_dir(workspace.path()).unwrap();
 
+        let mut data: Vec<u8> = Vec::new();
+        {
+            let cursor = std::io::Cursor::new(&mut data);
+            let mut zip = zip::ZipWriter::new(cursor);
+            let opts = zip::write::FileOptions::<()>::default();
+            zip.add_directory("flows/", opts).unwrap();
+            zip.start_file("flows/demo/flow.ygtc", opts).unwrap();
+            zip.write_all(b"flow").unwrap();
+            zip.finish().unwrap();
+        }
+
         let server = MockServer::start();
         let resolve_body = json!({
             "kind": "pack",
@@ -126,7 +150,7 @@ token = ""
             "coordinate": "pack://org/demo-pack@1.0.0",
             "artifact_id": "artifacts/sha256:pack123",
             "artifact_download_path": "/v1/artifact/artifacts/sha256:pack123",
-            "digest": "sha256:pack123",
+            "digest": sha256(&data),
             "license": {
                 "license_type": "free",
                 "id": null,
@@ -142,17 +166,6 @@ token = ""
                 .json_body(resolve_body.clone());
         });
 
-        let mut data: Vec<u8> = Vec::new();
-        {
-            let cursor = std::io::Cursor::new(&mut data);
-            let mut zip = zip::ZipWriter::new(cursor);
-            let opts = zip::write::FileOptions::<()>::default();
-            zip.add_directory("flows/", opts).unwrap();
-            zip.start_file("flows/demo/flow.ygtc", opts).unwrap();
-            zip.write_all(b"flow").unwrap();
-            zip.finish().unwrap();
-        }
-
         server.mock(|when, then| {
             when.method("GET")
                 .path("/v1/artifact/artifacts/sha256:pack123");
@@ -0,0 +1,126 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::thread;
+
+use serde_json::Value as JsonValue;
+
+fn stub(dir: &Path, name: &str, version_output: &str) {
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\necho '{version_output}'\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn doctor(dir: &Path, pack_version: &str) -> Output {
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let own = env!("CARGO_PKG_VERSION");
+    stub(
+        &bin,
+        "greentic-pack",
+        &format!("greentic-pack {pack_version}"),
+    );
+    stub(
+        &bin,
+        "greentic-component",
+        &format!("greentic-component {own}"),
+    );
+    stub(
+        &bin,
+        "greentic-runner-cli",
+        &format!("greentic-runner-cli v{own}"),
+    );
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["doctor", "--json"])
+        .env("HOME", dir)
+        .env("NO_PROXY", "127.0.0.1")
+        .env("XDG_CACHE_HOME", dir.join("cache"))
+        .env("GREENTIC_DEV_CONFIG_FILE", dir.join("config.toml"))
+        .env("GREENTIC_DEV_BIN_GREENTIC_PACK", bin.join("greentic-pack"))
+        .env("GREENTIC_DEV_BIN_PACKC", bin.join("packc"))
+        .env(
+            "GREENTIC_DEV_BIN_GREENTIC_COMPONENT",
+            bin.join("greentic-component"),
+        )
+        .env(
+            "GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI",
+            bin.join("greentic-runner-cli"),
+        )
+        .env("GREENTIC_DEV_BIN_GREENTIC_GUI", bin.join("greentic-gui"))
+        .env(
+            "GREENTIC_DEV_BIN_GREENTIC_SECRETS",
+            bin.join("greentic-secrets"),
+        )
+        .output()
+        .unwrap()
+}
+
+fn finding<'a>(report: &'a JsonValue, subject: &str) -> &'a JsonValue {
+    report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|finding| finding["subject"] == subject)
+        .unwrap_or_else(|| panic!("no finding for {subject}: {report:#}"))
+}
+
+#[test]
+fn reports_tools_config_and_distributor_reachability() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf);
+        stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+    });
+    fs::write(
+        dir.path().join("config.toml"),
+        format!("[distributor.profiles.local]\nbase_url = \"http://127.0.0.1:{port}\"\n"),
+    )
+    .unwrap();
+
+    let output = doctor(dir.path(), env!("CARGO_PKG_VERSION"));
+    server.join().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let report: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], true);
+    assert_eq!(finding(&report, "greentic-pack")["status"], "ok");
+    assert_eq!(finding(&report, "greentic-runner-cli")["status"], "ok");
+    // Optional tools only warn when they are missing.
+    assert_eq!(finding(&report, "greentic-gui")["status"], "warn");
+    assert_eq!(finding(&report, "config.toml")["status"], "ok");
+    let profile = finding(&report, "profile local");
+    assert_eq!(profile["status"], "ok");
+    assert!(
+        profile["detail"].as_str().unwrap().contains("HTTP 404"),
+        "{profile}"
+    );
+}
+
+#[test]
+fn incompatible_tools_and_broken_config_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("config.toml"), "[distributor\n").unwrap();
+
+    let output = doctor(dir.path(), "0.1.0");
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let report: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+    let pack = finding(&report, "greentic-pack");
+    assert_eq!(pack["status"], "fail");
+    assert!(
+        pack["detail"].as_str().unwrap().contains("not compatible"),
+        "{pack}"
+    );
+    assert_eq!(finding(&report, "config.toml")["status"], "fail");
+}
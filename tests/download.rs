@@ -0,0 +1,147 @@
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::download::{DownloadOptions, fetch};
+use httpmock::MockServer;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+fn sha256(bytes: &[u8]) -> String {
+    format!(
+        "sha256:{}",
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    )
+}
+
+fn can_bind() -> bool {
+    if std::net::TcpListener::bind("127.0.0.1:0").is_err() {
+        eprintln!("Skipping test; cannot bind local port in this environment");
+        return false;
+    }
+    true
+}
+
+#[test]
+fn interrupted_downloads_resume_from_the_partial_file() -> Result<()> {
+    if !can_bind() {
+        return Ok(());
+    }
+    let body: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let temp = TempDir::new()?;
+    let dest = temp.path().join("component.wasm");
+    fs::write(temp.path().join("component.wasm.partial"), &body[..70_000])?;
+
+    let server = MockServer::start();
+    let resumed = server.mock(|when, then| {
+        when.method("GET")
+            .path("/blob")
+            .header("range", "bytes=70000-");
+        then.status(206)
+            .header("content-range", "bytes 70000-199999/200000")
+            .body(&body[70_000..]);
+    });
+    let client = reqwest::blocking::Client::new();
+    let url = server.url("/blob");
+    let downloaded = fetch(
+        || client.get(&url),
+        &dest,
+        Some(&sha256(&body)),
+        &DownloadOptions::default(),
+    )?;
+
+    resumed.assert();
+    assert_eq!(fs::read(&dest)?, body);
+    assert_eq!(downloaded.size, body.len() as u64);
+    assert!(!temp.path().join("component.wasm.partial").exists());
+    Ok(())
+}
+
+#[test]
+fn servers_without_range_support_restart_the_download() -> Result<()> {
+    if !can_bind() {
+        return Ok(());
+    }
+    let body = b"complete body".to_vec();
+    let temp = TempDir::new()?;
+    let dest = temp.path().join("pack.gtpack");
+    fs::write(
+        temp.path().join("pack.gtpack.partial"),
+        b"stale bytes from before",
+    )?;
+
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method("GET").path("/pack");
+        then.status(200).body(&body);
+    });
+    let client = reqwest::blocking::Client::new();
+    let url = server.url("/pack");
+    fetch(
+        || client.get(&url),
+        &dest,
+        Some(&sha256(&body)),
+        &DownloadOptions::default(),
+    )?;
+    assert_eq!(fs::read(&dest)?, body);
+    Ok(())
+}
+
+#[test]
+fn digest_mismatches_discard_the_download() -> Result<()> {
+    if !can_bind() {
+        return Ok(());
+    }
+    let temp = TempDir::new()?;
+    let dest = temp.path().join("pack.gtpack");
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method("GET").path("/pack");
+        then.status(200).body("tampered");
+    });
+    let client = reqwest::blocking::Client::new();
+    let url = server.url("/pack");
+    let err = fetch(
+        || client.get(&url),
+        &dest,
+        Some(&sha256(b"original")),
+        &DownloadOptions::default(),
+    )
+    .expect_err("digest mismatch");
+    assert!(err.to_string().contains("digest mismatch"), "{err:#}");
+    assert!(!dest.exists());
+    assert!(!temp.path().join("pack.gtpack.partial").exists());
+    Ok(())
+}
+
+#[test]
+fn rate_limited_downloads_take_at_least_size_over_rate() -> Result<()> {
+    if !can_bind() {
+        return Ok(());
+    }
+    let body = vec![7u8; 8 * 1024];
+    let temp = TempDir::new()?;
+    let dest = temp.path().join("slow.bin");
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method("GET").path("/slow");
+        then.status(200).body(&body);
+    });
+    let client = reqwest::blocking::Client::new();
+    let url = server.url("/slow");
+    let started = std::time::Instant::now();
+    fetch(
+        || client.get(&url),
+        &dest,
+        None,
+        &DownloadOptions {
+            limit_rate: Some(16 * 1024),
+            ..DownloadOptions::default()
+        },
+    )?;
+    assert!(started.elapsed() >= std::time::Duration::from_millis(450));
+    assert_eq!(fs::read(&dest)?, body);
+    Ok(())
+}
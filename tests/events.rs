@@ -0,0 +1,91 @@
+use std::fs;
+use std::process::Command;
+
+use serde_json::Value;
+
+const FLOW: &str = r#"id: main
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: missing
+"#;
+
+fn events(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .collect()
+}
+
+#[test]
+fn commands_stream_phases_warnings_and_artifacts() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.ygtc"), FLOW).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir.path())
+        .args(["--events", "stdout", "flow", "graph", "main.ygtc"])
+        .args(["--out", "main.mmd"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let events = events(&output.stdout);
+    let kinds = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            "phase_started",
+            "warning",
+            "artifact_written",
+            "phase_finished"
+        ]
+    );
+    assert_eq!(events[0]["phase"], "flow graph");
+    assert!(events[0]["ts"].is_string());
+    assert!(
+        events[1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("routes to `missing`")
+    );
+    assert_eq!(events[2]["path"], "main.mmd");
+    assert_eq!(events[3]["ok"], true);
+    assert!(events[3]["duration_ms"].is_number());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Wrote main.mmd"));
+}
+
+#[test]
+fn failing_commands_finish_their_phase_not_ok() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.ygtc"), FLOW).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir.path())
+        .args(["--events", "-", "flow", "lint", "main.ygtc"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    let events = events(&output.stdout);
+    let last = events.last().unwrap();
+    assert_eq!(last["event"], "phase_finished");
+    assert_eq!(last["phase"], "flow lint");
+    assert_eq!(last["ok"], false);
+}
+
+#[test]
+fn without_events_nothing_is_emitted() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.ygtc"), FLOW).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir.path())
+        .args(["flow", "graph", "main.ygtc", "--out", "main.mmd"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert!(events(&output.stdout).is_empty());
+}
@@ -0,0 +1,50 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::flow_contract;
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde_json::json;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn built_packs_carry_the_flow_input_schema() -> Result<()> {
+    let workspace = Workspace::new("flow-contract")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let mut flow = fs::read_to_string(&flow_path)?;
+    flow.push_str("input_schema:\n  type: object\n  required: [message]\n");
+    fs::write(&flow_path, flow)?;
+
+    let pack_path = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    let load = open_pack(&pack_path, SigningPolicy::DevOk).expect("open pack");
+    let (flow_id, contract) = flow_contract::from_pack(&load, None)?;
+    assert_eq!(flow_id, "hello-flow");
+    contract.validate_input(&json!({ "message": "hi" }))?;
+    assert!(contract.validate_input(&json!({})).is_err());
+    Ok(())
+}
+
+#[test]
+fn invalid_flow_schemas_fail_the_build() -> Result<()> {
+    let workspace = Workspace::new("flow-contract-invalid")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let mut flow = fs::read_to_string(&flow_path)?;
+    flow.push_str("output_schema:\n  type: 12\n");
+    fs::write(&flow_path, flow)?;
+
+    let err = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )
+    .expect_err("invalid schema");
+    assert!(format!("{err:#}").contains("output_schema does not compile"));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn flow_lint(dir: &Path, flows: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["flow", "lint"])
+        .args(flows)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn findings_fail_the_lint_until_their_rules_are_disabled() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("main.ygtc"),
+        r#"id: main
+start: start
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: loop
+  loop:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: loop
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("secret-requirements.json"),
+        r#"[{"key": "API_TOKEN", "required": true}]"#,
+    )
+    .unwrap();
+
+    let output = flow_lint(dir.path(), &["main.ygtc"]);
+    assert!(!output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("main: node `loop` has no path to an end")
+            && stdout.contains("[missing-end]"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("secret `API_TOKEN` is declared but no flow or component references it"),
+        "{stdout}"
+    );
+
+    fs::create_dir(dir.path().join(".greentic")).unwrap();
+    fs::write(
+        dir.path().join(".greentic/lint.toml"),
+        "[rules]\nmissing-end = false\nunused-secret = false\n",
+    )
+    .unwrap();
+    let output = flow_lint(dir.path(), &["main.ygtc"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("no lint findings"));
+}
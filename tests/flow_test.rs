@@ -0,0 +1,117 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde_json::Value as JsonValue;
+
+// Fails every run that has a mocked HTTP outage; otherwise echoes the entry flow and input.
+const RUNNER: &str = r#"#!/bin/sh
+if [ -n "$MOCK_HTTP_FAIL_PATTERN" ]; then
+  echo '{"status": "failed", "error": {"code": "UPSTREAM", "message": "mock 500"}, "trace": [{"node_id": "start", "status": "failed"}]}'
+  exit 1
+fi
+echo "{\"status\": \"succeeded\", \"output\": {\"entry\": \"$4\", \"input\": $6}, \"trace\": [{\"node_id\": \"start\", \"status\": \"succeeded\"}, {\"node_id\": \"log\", \"status\": \"succeeded\"}]}"
+"#;
+
+fn workspace() -> (tempfile::TempDir, PathBuf) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = tempfile::tempdir().unwrap();
+    let flows = dir.path().join("flows");
+    fs::create_dir_all(flows.join("tests")).unwrap();
+    fs::copy(
+        root.join("tests/fixtures/hello-pack/hello-flow.ygtc"),
+        flows.join("hello.ygtc"),
+    )
+    .unwrap();
+    fs::write(
+        flows.join("tests/happy.yaml"),
+        "name: greets Ada\ninput: {name: Ada}\nexpect:\n  output:\n    entry: hello-flow\n    input: {name: Ada}\n  visits: [start, log]\n",
+    )
+    .unwrap();
+    fs::write(
+        flows.join("tests/outage.yaml"),
+        "mocks:\n  MOCK_HTTP_FAIL_PATTERN: \".*\"\nexpect:\n  status: failed\n  error: UPSTREAM\n",
+    )
+    .unwrap();
+    let component = dir.path().join("components/dev.greentic.echo");
+    fs::create_dir_all(&component).unwrap();
+    for file in ["component.manifest.json", "component.wasm"] {
+        fs::copy(
+            root.join("fixtures/components/dev.greentic.echo")
+                .join(file),
+            component.join(file),
+        )
+        .unwrap();
+    }
+    let stub = dir.path().join("greentic-runner-cli");
+    fs::write(&stub, RUNNER).unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    (dir, stub)
+}
+
+fn flow_test(dir: &Path, stub: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args([
+            "flow",
+            "test",
+            "flows/hello.ygtc",
+            "--component-dir",
+            "components",
+        ])
+        .args(args)
+        .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", stub)
+        // Ambient mock settings must not leak into cases.
+        .env("MOCK_HTTP_FAIL_PATTERN", "^/")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn cases_run_with_their_own_mocks() {
+    let (dir, stub) = workspace();
+    let output = flow_test(dir.path(), &stub, &["--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let outcomes: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    let outcomes = outcomes.as_array().unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0]["case"], "greets Ada");
+    assert_eq!(outcomes[1]["case"], "outage");
+    assert!(outcomes.iter().all(|outcome| outcome["ok"] == true));
+}
+
+#[test]
+fn failures_are_reported_and_written_as_junit() {
+    let (dir, stub) = workspace();
+    fs::write(
+        dir.path().join("flows/tests/wrong.yaml"),
+        "input: {name: Bo}\nexpect:\n  output:\n    input: {name: Ada}\n",
+    )
+    .unwrap();
+    let output = flow_test(dir.path(), &stub, &["--junit", "report.xml"]);
+    assert!(!output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✗ wrong"), "{stdout}");
+    assert!(
+        stdout.contains("output.input.name: expected \"Ada\", got \"Bo\""),
+        "{stdout}"
+    );
+    assert!(stdout.contains("3 case(s): 2 passed, 1 failed"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 3 flow test(s) failed"), "{stderr}");
+
+    let junit = fs::read_to_string(dir.path().join("report.xml")).unwrap();
+    assert!(
+        junit.contains("<testsuite name=\"hello-flow\" tests=\"3\" failures=\"1\" errors=\"0\""),
+        "{junit}"
+    );
+    assert!(
+        junit.contains(
+            "<failure message=\"output.input.name: expected &quot;Ada&quot;, got &quot;Bo&quot;\">"
+        ),
+        "{junit}"
+    );
+}
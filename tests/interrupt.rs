@@ -0,0 +1,99 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn signal(pid: &str, sig: &str) -> bool {
+    Command::new("kill")
+        .args([sig, pid])
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[test]
+fn ctrl_c_stops_delegated_children_and_exits_130() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("child.pid");
+    let stub = dir.path().join("greentic-pack");
+    // A child that ignores SIGINT, so it has to be killed once the grace period is over.
+    fs::write(
+        &stub,
+        format!(
+            "#!/bin/sh\ntrap '' INT\necho $$ > {}\nexec sleep 60\n",
+            pid_file.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let cli = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .args(["pack", "doctor"])
+        .env("GREENTIC_DEV_BIN_GREENTIC_PACK", &stub)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let started = Instant::now();
+    let child_pid = loop {
+        if let Ok(pid) = fs::read_to_string(&pid_file)
+            && !pid.trim().is_empty()
+        {
+            break pid.trim().to_string();
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(30),
+            "stub never ran"
+        );
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    assert!(signal(&cli.id().to_string(), "-INT"));
+    let output = cli.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(130));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("interrupted"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!signal(&child_pid, "-0"), "child {child_pid} still running");
+}
+
+#[test]
+fn ctrl_c_ends_a_watch_loop_mid_poll() {
+    use std::io::{BufRead, BufReader};
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("dev.yaml"),
+        "watch:\n  poll_ms: 600000\n  actions:\n    check:\n      paths: [\"flows/**\"]\n      run: [\"check\"]\n",
+    )
+    .unwrap();
+    let mut cli = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .arg("watch")
+        .current_dir(dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(cli.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("Watching") {
+        line.clear();
+        assert!(
+            stdout.read_line(&mut line).unwrap() > 0,
+            "watch never started"
+        );
+    }
+
+    let started = Instant::now();
+    assert!(signal(&cli.id().to_string(), "-INT"));
+    let output = cli.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(130));
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
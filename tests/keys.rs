@@ -0,0 +1,126 @@
+mod support;
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde_json::Value as JsonValue;
+use support::{Workspace, copy_fixture_component, write_pack_flow};
+
+fn greentic_dev(workspace: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(workspace)
+        .args(args)
+        .env("GREENTIC_DEV_KEYS_DIR", workspace.join("keys"))
+        .env("GREENTIC_DEV_KEY_PASSPHRASE", "correct horse")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn packs_are_signed_with_a_named_keystore_key() {
+    let workspace = Workspace::new("keys-sign").unwrap();
+    let root = workspace.root.as_path();
+    copy_fixture_component(&workspace, false).unwrap();
+    write_pack_flow(&workspace, "hello-flow").unwrap();
+
+    let output = greentic_dev(
+        root,
+        &[
+            "keys",
+            "generate",
+            "release",
+            "--keystore",
+            "--subject",
+            "Acme Releases",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let output = greentic_dev(root, &["keys", "list", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let keys: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(keys[0]["name"], "release");
+    assert_eq!(keys[0]["backend"], "keystore");
+    assert!(keys[0].get("sealed").is_none(), "{keys}");
+    let fingerprint = keys[0]["fingerprint"].as_str().unwrap().to_string();
+
+    let output = greentic_dev(
+        root,
+        &[
+            "pack",
+            "build",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--component-dir",
+            "components",
+            "--out",
+            "dist/hello.gtpack",
+            "--sign-key",
+            "release",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    // Dev-signed packs are rejected under the strict policy; named keys are not dev certificates.
+    let load = open_pack(&root.join("dist/hello.gtpack"), SigningPolicy::Strict)
+        .map_err(|err| err.message)
+        .unwrap();
+    assert!(load.report.signature_ok);
+    let chain = String::from_utf8(load.files["signatures/chain.pem"].clone()).unwrap();
+    let output = greentic_dev(
+        root,
+        &[
+            "keys",
+            "export",
+            "release",
+            "--public",
+            "--out",
+            "release.pem",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        std::fs::read_to_string(root.join("release.pem")).unwrap(),
+        chain
+    );
+
+    // The export imports under a new name with the same key.
+    let output = greentic_dev(
+        root,
+        &["keys", "export", "release", "--out", "release.key.json"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let output = greentic_dev(
+        root,
+        &[
+            "keys",
+            "import",
+            "release.key.json",
+            "--name",
+            "ci",
+            "--keystore",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let output = greentic_dev(root, &["keys", "list", "--json"]);
+    let keys: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(keys[0]["name"], "ci");
+    assert_eq!(keys[0]["fingerprint"], fingerprint.as_str());
+
+    let output = greentic_dev(
+        root,
+        &[
+            "pack",
+            "build",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--sign-key",
+            "missing",
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no signing key named `missing`"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,135 @@
+mod support;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value as JsonValue;
+use support::{Workspace, copy_fixture_component, load_gtpack, write_pack_flow};
+
+const ORG_DEFAULTS: &str = r#"
+[org]
+name = "acme"
+
+[pack.meta]
+vendor = "Acme Corp"
+license = "Apache-2.0"
+
+[lint.rules]
+unused-secret = false
+
+[trust]
+policy = "strict"
+
+[distributor]
+default_profile = "acme"
+
+[distributor.profiles.acme]
+base_url = "https://distributor.acme.example"
+token = "env:ACME_DISTRIBUTOR_TOKEN"
+"#;
+
+fn greentic_dev(workspace: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(workspace)
+        .args(args)
+        .env("HOME", workspace.join("home"))
+        .env("XDG_CONFIG_HOME", workspace.join("xdg"))
+        .env_remove("GREENTIC_DEV_CONFIG_FILE")
+        .env_remove("GREENTIC_CONFIG_FILE")
+        .env_remove("GREENTIC_CONFIG")
+        .env_remove("GREENTIC_DISTRIBUTOR_PROFILE")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn synced_org_defaults_apply_under_the_local_config() {
+    let workspace = Workspace::new("org-sync").unwrap();
+    let root = workspace.root.as_path();
+    copy_fixture_component(&workspace, false).unwrap();
+    write_pack_flow(&workspace, "hello-flow").unwrap();
+    fs::write(root.join("acme.toml"), ORG_DEFAULTS).unwrap();
+    let config_dir = root.join("xdg/greentic-dev");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[trust]\npolicy = \"devok\"\n",
+    )
+    .unwrap();
+
+    let output = greentic_dev(root, &["org", "sync", "acme.toml"]);
+    assert!(output.status.success(), "{output:?}");
+    let text = stdout(&output);
+    assert!(
+        text.contains("Synced organization defaults `acme`"),
+        "{text}"
+    );
+    assert!(
+        text.contains("registry endpoints: acme (default)"),
+        "{text}"
+    );
+    assert!(
+        text.contains("your config overrides 1 of them: trust.policy"),
+        "{text}"
+    );
+    let synced = fs::read_to_string(config_dir.join("org.toml")).unwrap();
+    assert!(synced.contains("source = \"acme.toml\""), "{synced}");
+
+    let output = greentic_dev(root, &["config", "list", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let listing: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    let entry = |key: &str| {
+        listing["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["key"] == key)
+            .cloned()
+            .unwrap_or_else(|| panic!("no {key} in {listing}"))
+    };
+    assert_eq!(entry("pack.meta.vendor")["source"], "org");
+    assert_eq!(entry("trust.policy")["value"], "devok");
+    assert_eq!(entry("trust.policy")["source"], "xdg");
+    let output = greentic_dev(root, &["config", "get", "distributor.default_profile"]);
+    assert_eq!(stdout(&output).trim(), "acme");
+
+    // Pack metadata the pack leaves unset comes from the organization.
+    let output = greentic_dev(
+        root,
+        &[
+            "pack",
+            "build",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--component-dir",
+            "components",
+            "--out",
+            "dist/hello.gtpack",
+            "--timings",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let (manifest, _) = load_gtpack(&root.join("dist/hello.gtpack")).unwrap();
+    assert_eq!(manifest.meta.vendor.as_deref(), Some("Acme Corp"));
+    assert_eq!(manifest.meta.license.as_deref(), Some("Apache-2.0"));
+
+    // Without a source the recorded one is synced again; a rejected document leaves the layer as is.
+    fs::write(
+        root.join("acme.toml"),
+        format!("{ORG_DEFAULTS}\n[tools.greentic-component]\npath = \"/tmp/tool\"\n"),
+    )
+    .unwrap();
+    let output = greentic_dev(root, &["org", "sync"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("`tools` cannot be set"), "{stderr}");
+    assert_eq!(
+        fs::read_to_string(config_dir.join("org.toml")).unwrap(),
+        synced
+    );
+}
@@ -0,0 +1,45 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn greentic_dev(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(args)
+        .env("GREENTIC_DEV_BIN_GREENTIC_PACK", dir.join("greentic-pack"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn sign_is_backed_up_and_undone() {
+    let dir = tempfile::tempdir().unwrap();
+    // Stands in for `greentic-pack sign`: rewrites the pack given with --pack.
+    let stub = dir.path().join("greentic-pack");
+    fs::write(
+        &stub,
+        "#!/bin/sh\nwhile [ \"$1\" != --pack ]; do shift; done\necho signed > \"$2\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    let pack = dir.path().join("demo.gtpack");
+    fs::write(&pack, "original").unwrap();
+
+    let signed = greentic_dev(dir.path(), &["pack", "sign", "--pack", "demo.gtpack"]);
+    assert!(signed.status.success(), "{signed:?}");
+    assert_eq!(fs::read_to_string(&pack).unwrap(), "signed\n");
+    assert!(String::from_utf8_lossy(&signed.stderr).contains("greentic-dev undo"));
+
+    let listed = greentic_dev(dir.path(), &["undo", "--list"]);
+    assert!(String::from_utf8_lossy(&listed.stdout).contains("pack sign"));
+
+    let undone = greentic_dev(dir.path(), &["undo"]);
+    assert!(undone.status.success(), "{undone:?}");
+    assert_eq!(fs::read_to_string(&pack).unwrap(), "original");
+
+    let again = greentic_dev(dir.path(), &["undo"]);
+    assert!(!again.status.success());
+}
@@ -0,0 +1,76 @@
+mod support;
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+fn request_http_client(component_dir: &Path) -> Result<()> {
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["capabilities"] = serde_json::json!({
+        "wasi": { "random": true },
+        "host": { "http": { "client": true, "server": false } }
+    });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+fn write_policy(workspace: &Workspace, policy: &str) -> Result<()> {
+    let dir = workspace.root.join(".greentic");
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("policy.yaml"), policy)?;
+    Ok(())
+}
+
+#[test]
+fn build_fails_when_components_exceed_the_policy() -> Result<()> {
+    let workspace = Workspace::new("pack-capability-deny")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    request_http_client(&component_dir)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    write_policy(
+        &workspace,
+        "default:\n  wasi: [clocks]\n  network:\n    client: false\n",
+    )?;
+
+    let err = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )
+    .expect_err("policy violations fail the build");
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("WASI capability `random` is not allowed"),
+        "{message}"
+    );
+    assert!(
+        message.contains("outbound HTTP (http.client) is not allowed"),
+        "{message}"
+    );
+    assert!(!workspace.root.join("dist/test.gtpack").exists());
+    Ok(())
+}
+
+#[test]
+fn pack_specific_policy_overrides_the_default() -> Result<()> {
+    let workspace = Workspace::new("pack-capability-allow")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    request_http_client(&component_dir)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    write_policy(
+        &workspace,
+        "default:\n  network: {}\npacks:\n  dev.local.hello-flow:\n    wasi: [random]\n    network:\n      client: true\n",
+    )?;
+
+    let pack_path = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    assert!(pack_path.exists());
+    Ok(())
+}
@@ -0,0 +1,70 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_build::PackBuildOptions;
+use greentic_dev::pack_changelog::{Change, changelog, render_markdown};
+use support::{
+    Workspace, build_pack, build_pack_with_options, copy_fixture_component, write_pack_flow,
+};
+
+#[test]
+fn changelog_reports_component_flow_and_secret_changes() -> Result<()> {
+    let workspace = Workspace::new("pack-changelog")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let component_root = component_dir
+        .parent()
+        .expect("component root")
+        .to_path_buf();
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let built = build_pack(&workspace, &flow_path, &component_root)?;
+    let old = workspace.root.join("old.gtpack");
+    fs::copy(&built, &old)?;
+
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["version"] = "0.2.0".into();
+    manifest["capabilities"]["host"]["secrets"] = serde_json::json!({
+        "required": [{
+            "key": "WEATHER_API_KEY",
+            "required": true,
+            "scope": { "env": "dev", "tenant": "demo" },
+            "format": "text"
+        }]
+    });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    let flow = fs::read_to_string(&flow_path)?.replace("Smoke test finished", "Done");
+    fs::write(&flow_path, flow)?;
+    // The component version bump differs from greentic-dev.lock, so accept it explicitly.
+    let (new, _) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        &component_root,
+        PackBuildOptions {
+            update_lock: true,
+            ..PackBuildOptions::default()
+        },
+    )?;
+
+    let diff = changelog(&old, &new)?;
+    let component = &diff.components[0];
+    assert_eq!(component.name, "dev.greentic.echo");
+    assert_eq!(component.change, Change::Updated);
+    assert_eq!(component.old_version.as_deref(), Some("0.1.0"));
+    assert_eq!(component.new_version.as_deref(), Some("0.2.0"));
+    assert_eq!(diff.flows.len(), 1);
+    assert_eq!(diff.flows[0].change, Change::Updated);
+    assert_eq!(diff.secrets_added, ["WEATHER_API_KEY"]);
+    assert!(diff.secrets_removed.is_empty());
+
+    let markdown = render_markdown(&diff);
+    assert!(markdown.contains("- Updated `dev.greentic.echo` 0.1.0 → 0.2.0"));
+    assert!(markdown.contains("- Changed `hello-flow`"));
+    assert!(markdown.contains("- New `WEATHER_API_KEY`"));
+
+    let unchanged = render_markdown(&changelog(&new, &new)?);
+    assert!(unchanged.contains("No changes"), "{unchanged}");
+    Ok(())
+}
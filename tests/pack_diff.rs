@@ -0,0 +1,79 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_build::PackBuildOptions;
+use greentic_dev::pack_changelog::Change;
+use greentic_dev::pack_diff::{diff, render};
+use support::{
+    Workspace, build_pack, build_pack_with_options, copy_fixture_component, write_pack_flow,
+};
+
+#[test]
+fn diff_lists_flow_component_and_sbom_changes() -> Result<()> {
+    let workspace = Workspace::new("pack-diff")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let component_root = component_dir
+        .parent()
+        .expect("component root")
+        .to_path_buf();
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let built = build_pack(&workspace, &flow_path, &component_root)?;
+    let old = workspace.root.join("old.gtpack");
+    fs::copy(&built, &old)?;
+
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["version"] = "0.2.0".into();
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    // Reformatting alone is not a semantic change; the new message is.
+    let flow = fs::read_to_string(&flow_path)?
+        .replace("Smoke test finished", "Done")
+        .replace("description: ", "# reviewed\ndescription: ");
+    fs::write(&flow_path, flow)?;
+    let (new, _) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        &component_root,
+        PackBuildOptions {
+            update_lock: true,
+            ..PackBuildOptions::default()
+        },
+    )?;
+
+    let changes = diff(&old, &new)?;
+    assert_eq!(changes.flows.len(), 1);
+    assert_eq!(changes.flows[0].change, Change::Updated);
+    let paths = changes.flows[0]
+        .changes
+        .iter()
+        .map(|change| change.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["nodes.log[\"component.exec\"].input.message"]);
+    assert_eq!(changes.components[0].old_version.as_deref(), Some("0.1.0"));
+    assert_eq!(changes.components[0].new_version.as_deref(), Some("0.2.0"));
+    assert!(
+        changes
+            .sbom
+            .iter()
+            .any(|entry| entry.change == Change::Updated && entry.path.ends_with(".ygtc")),
+        "{:?}",
+        changes.sbom
+    );
+
+    let text = render(&changes);
+    assert!(text.contains("~ hello-flow"), "{text}");
+    assert!(
+        text.contains(
+            "~ nodes.log[\"component.exec\"].input.message: \"Smoke test finished\" → \"Done\""
+        ),
+        "{text}"
+    );
+    assert!(text.contains("~ dev.greentic.echo 0.1.0 → 0.2.0"), "{text}");
+
+    let same = render(&diff(&new, &new)?);
+    assert!(same.contains("No differences."), "{same}");
+    Ok(())
+}
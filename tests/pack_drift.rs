@@ -0,0 +1,146 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_build::PackBuildOptions;
+use greentic_dev::pack_changelog::Change;
+use greentic_dev::pack_cli::PackDriftArgs;
+use greentic_dev::pack_drift::{drift, render, run};
+use httpmock::MockServer;
+use serde_json::json;
+use support::{
+    Workspace, build_pack, build_pack_with_options, copy_fixture_component, write_pack_flow,
+};
+
+#[test]
+fn drift_reports_component_flow_and_secret_changes() -> Result<()> {
+    let workspace = Workspace::new("pack-drift")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let component_root = component_dir
+        .parent()
+        .expect("component root")
+        .to_path_buf();
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let built = build_pack(&workspace, &flow_path, &component_root)?;
+    let published = workspace.root.join("published.gtpack");
+    fs::copy(&built, &published)?;
+
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["version"] = "0.2.0".into();
+    manifest["capabilities"]["host"]["secrets"] = json!({
+        "required": [{
+            "key": "API_TOKEN",
+            "required": true,
+            "scope": { "env": "dev", "tenant": "demo" },
+            "format": "text"
+        }]
+    });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    let flow = fs::read_to_string(&flow_path)?.replace("Smoke test finished", "Done");
+    fs::write(&flow_path, flow)?;
+    let (local, _) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        &component_root,
+        PackBuildOptions {
+            update_lock: true,
+            ..PackBuildOptions::default()
+        },
+    )?;
+
+    let report = drift(&local, &published, "published.gtpack")?;
+    assert_eq!(report.components.len(), 1);
+    assert_eq!(report.components[0].old_version.as_deref(), Some("0.1.0"));
+    assert_eq!(report.components[0].new_version.as_deref(), Some("0.2.0"));
+    assert_eq!(report.flows.len(), 1);
+    assert_eq!(report.flows[0].change, Change::Updated);
+    assert_eq!(report.secrets.len(), 1);
+    assert_eq!(report.secrets[0].key, "API_TOKEN");
+    assert_eq!(report.secrets[0].change, Change::Added);
+
+    let text = render(&report);
+    assert!(text.contains("~ dev.greentic.echo 0.1.0 → 0.2.0"), "{text}");
+    assert!(text.contains("~ hello-flow "), "{text}");
+    assert!(text.contains("+ API_TOKEN"), "{text}");
+
+    let same = render(&drift(&published, &published, "published.gtpack")?);
+    assert!(same.contains("No drift"), "{same}");
+    Ok(())
+}
+
+#[test]
+fn drift_pulls_the_baseline_from_the_distributor() -> Result<()> {
+    if std::net::TcpListener::bind("127.0.0.1:0").is_err() {
+        eprintln!("Skipping test; cannot bind local port in this environment");
+        return Ok(());
+    }
+    let workspace = Workspace::new("pack-drift-distributor")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let server = MockServer::start();
+    let config_path = workspace.root.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[distributor.profiles.prod]
+url = "{}"
+tenant_id = "acme"
+environment_id = "prod"
+"#,
+            server.base_url()
+        ),
+    )?;
+    unsafe { std::env::set_var("GREENTIC_CONFIG", &config_path) };
+
+    let resolve = server.mock(|when, then| {
+        when.method("POST")
+            .path("/v1/resolve")
+            .json_body_includes(r#"{"coordinate": "hello@latest"}"#);
+        then.status(200).json_body(json!({
+            "kind": "pack",
+            "name": "hello",
+            "version": "0.1.0",
+            "coordinate": "pack://acme/hello@0.1.0",
+            "artifact_id": "artifacts/hello",
+            "artifact_download_path": "/v1/artifact/artifacts/hello",
+            "digest": null,
+            "license": {
+                "license_type": "free",
+                "id": null,
+                "requires_acceptance": false,
+                "checkout_url": null
+            },
+            "metadata": {}
+        }));
+    });
+    server.mock(|when, then| {
+        when.method("GET").path("/v1/artifact/artifacts/hello");
+        then.status(200).body(fs::read(&pack).expect("pack bytes"));
+    });
+
+    let mut args = PackDriftArgs {
+        pack: pack.clone(),
+        against: "distributor://hello@latest".to_string(),
+        profile: Some("prod".to_string()),
+        json: true,
+    };
+    run(&args)?;
+    resolve.assert();
+
+    args.against = "distributor://hello".to_string();
+    let err = run(&args).expect_err("missing version");
+    assert!(err.to_string().contains("<pack-id>@<version>"), "{err}");
+    // The config file goes away with the workspace; pack builds in other tests load the config.
+    unsafe { std::env::remove_var("GREENTIC_CONFIG") };
+    Ok(())
+}
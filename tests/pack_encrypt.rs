@@ -0,0 +1,151 @@
+mod support;
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use greentic_dev::pack_cli::{EncryptScope, PackKeygenArgs};
+use greentic_dev::pack_encrypt::{decrypt, encrypt, encryption_manifest, keygen, take_decrypt_key};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use support::{Workspace, build_pack, copy_fixture_component, load_gtpack, write_pack_flow};
+
+fn new_key(path: &Path) -> Result<String> {
+    keygen(&PackKeygenArgs {
+        out: path.to_path_buf(),
+        force: false,
+    })?;
+    let contents = fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .find_map(|line| line.strip_prefix("# public key: "))
+        .expect("public key comment")
+        .to_string())
+}
+
+#[test]
+fn encrypted_flows_decrypt_back_to_the_signed_pack() -> Result<()> {
+    let workspace = Workspace::new("pack-encrypt")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    let alice_key = workspace.root.join("alice.key");
+    let alice = new_key(&alice_key)?;
+    let mallory_key = workspace.root.join("mallory.key");
+    new_key(&mallory_key)?;
+
+    let encrypted = workspace.root.join("dist/test.encrypted.gtpack");
+    let manifest = encrypt(&pack, &encrypted, &[alice], &[EncryptScope::Flows])?;
+    assert!(!manifest.entries.is_empty());
+    let (_, original) = load_gtpack(&pack)?;
+    let (_, sealed) = load_gtpack(&encrypted)?;
+    for entry in &manifest.entries {
+        assert_ne!(original[entry], sealed[entry], "{entry} left in the clear");
+    }
+    assert_eq!(
+        encryption_manifest(&encrypted)?.expect("encrypted").entries,
+        manifest.entries
+    );
+    assert!(open_pack(&encrypted, SigningPolicy::DevOk).is_err());
+
+    let err = decrypt(
+        &encrypted,
+        &mallory_key,
+        &workspace.root.join("nope.gtpack"),
+    )
+    .expect_err("wrong key");
+    assert!(format!("{err:#}").contains("not encrypted for"), "{err:#}");
+
+    let args = [
+        "--pack",
+        encrypted.to_str().unwrap(),
+        "--decrypt-key",
+        alice_key.to_str().unwrap(),
+    ]
+    .map(OsString::from);
+    let (decrypted, rest) = take_decrypt_key(&args)?;
+    let decrypted = decrypted.expect("decrypted pack");
+    assert_eq!(
+        rest,
+        [OsString::from("--pack"), decrypted.path.clone().into()]
+    );
+    let load = open_pack(&decrypted.path, SigningPolicy::DevOk)
+        .map_err(|err| anyhow::anyhow!(err.message))?;
+    assert!(load.report.signature_ok);
+    let (_, restored) = load_gtpack(&decrypted.path)?;
+    assert_eq!(restored, original);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_of_encrypted_packs_record_the_encrypted_pack_and_replay() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::{Command, Output};
+
+    let workspace = Workspace::new("pack-encrypt-replay")?;
+    let root = workspace.root.as_path();
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    let key = root.join("alice.key");
+    let recipient = new_key(&key)?;
+    let encrypted = root.join("dist/test.encrypted.gtpack");
+    encrypt(&pack, &encrypted, &[recipient], &[EncryptScope::Flows])?;
+    // The stub runner keeps a copy of the pack it was given.
+    let stub = root.join("runner");
+    fs::write(&stub, "#!/bin/sh\ncp \"$2\" seen.gtpack\n")?;
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755))?;
+    let pack_run = |args: &[&str]| -> Output {
+        Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+            .current_dir(root)
+            .args(["pack", "run"])
+            .args(args)
+            .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", &stub)
+            .output()
+            .unwrap()
+    };
+    let seen_decrypted = || -> Result<()> {
+        let seen = root.join("seen.gtpack");
+        assert!(encryption_manifest(&seen)?.is_none());
+        let load =
+            open_pack(&seen, SigningPolicy::DevOk).map_err(|err| anyhow::anyhow!(err.message))?;
+        assert!(load.report.signature_ok);
+        fs::remove_file(seen)?;
+        Ok(())
+    };
+
+    let output = pack_run(&[
+        "--pack",
+        encrypted.to_str().unwrap(),
+        "--decrypt-key",
+        key.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    seen_decrypted()?;
+    let log = fs::read_to_string(root.join(".greentic/runs.jsonl"))?;
+    let record: serde_json::Value = serde_json::from_str(log.lines().next().unwrap())?;
+    assert_eq!(record["pack"], encrypted.to_str().unwrap());
+    assert_eq!(
+        record["args"],
+        serde_json::json!([
+            "--pack",
+            encrypted.to_str().unwrap(),
+            "--decrypt-key",
+            key.to_str().unwrap()
+        ])
+    );
+
+    let output = pack_run(&["--replay", "1"]);
+    assert!(output.status.success(), "{output:?}");
+    seen_decrypted()?;
+    Ok(())
+}
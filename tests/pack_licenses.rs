@@ -0,0 +1,60 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::capability_policy::CapabilityPolicy;
+use greentic_dev::pack_licenses::{SubjectKind, scan};
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use serde_json::json;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn component_licenses_are_checked_against_the_policy() -> Result<()> {
+    let workspace = Workspace::new("pack-licenses")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    let mut load =
+        open_pack(&pack, SigningPolicy::DevOk).map_err(|err| anyhow::anyhow!(err.message))?;
+
+    let component = load.manifest.components[0].name.clone();
+    let unchecked = scan(&load, None);
+    assert_eq!(unchecked.violations().count(), 0);
+    let entry = unchecked
+        .entries
+        .iter()
+        .find(|entry| entry.kind == SubjectKind::Component)
+        .expect("component entry");
+    assert_eq!(entry.license, None);
+
+    // What `license` and `[annotations.licenses]` in pack.toml produce.
+    load.manifest.meta.license = Some("MIT".to_string());
+    load.manifest.meta.annotations.insert(
+        "licenses".to_string(),
+        json!({ component.as_str(): "GPL-3.0-only" }),
+    );
+
+    let policy_path = workspace.root.join("policy.yaml");
+    fs::write(
+        &policy_path,
+        "default:\n  licenses:\n    allow: [MIT, Apache-2.0]\n    deny: [GPL-3.0-only]\n    allow_unknown: false\n",
+    )?;
+    let policy = CapabilityPolicy::load(&policy_path)?.expect("policy");
+    let licenses = policy
+        .for_pack(&load.manifest.meta.pack_id)
+        .and_then(|policy| policy.licenses.as_ref());
+    let report = scan(&load, licenses);
+    let violations = report.violations().collect::<Vec<_>>();
+    assert_eq!(violations.len(), 1, "{report:?}");
+    assert_eq!(violations[0].subject, component);
+    assert_eq!(
+        violations[0].violation.as_deref(),
+        Some("`GPL-3.0-only` is denied")
+    );
+    Ok(())
+}
@@ -0,0 +1,133 @@
+mod support;
+
+use std::fs;
+use std::process::Command;
+
+use anyhow::Result;
+use greentic_dev::pack_build::{BackfilledOperation, PackBuildOptions};
+use support::{Workspace, build_pack_with_options, copy_fixture_component, write_pack_flow};
+
+fn strip_operations(flow_path: &std::path::Path) -> Result<()> {
+    let flow = fs::read_to_string(flow_path)?;
+    let stripped = flow
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("op:"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(flow_path, stripped + "\n")?;
+    Ok(())
+}
+
+#[test]
+fn missing_operations_are_backfilled_and_reported() -> Result<()> {
+    let workspace = Workspace::new("pack-ops-backfill")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    strip_operations(&flow_path)?;
+
+    let (_, report) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+        PackBuildOptions::default(),
+    )?;
+    assert_eq!(
+        report.backfilled_operations,
+        vec![
+            BackfilledOperation {
+                node_id: "start".to_string(),
+                component: "dev.greentic.echo".to_string(),
+                operation: "echo".to_string(),
+            },
+            BackfilledOperation {
+                node_id: "log".to_string(),
+                component: "dev.greentic.echo".to_string(),
+                operation: "echo".to_string(),
+            },
+        ]
+    );
+    let recorded = fs::read_to_string(workspace.root.join(".greentic/build_report.json"))?;
+    assert!(recorded.contains("\"node_id\": \"start\""));
+    Ok(())
+}
+
+#[test]
+fn require_operations_rejects_missing_operations() -> Result<()> {
+    let workspace = Workspace::new("pack-ops-require")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    strip_operations(&flow_path)?;
+
+    let err = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+        PackBuildOptions {
+            require_operations: true,
+            ..PackBuildOptions::default()
+        },
+    )
+    .expect_err("build should fail without operations");
+    let message = format!("{err:#}");
+    assert!(message.contains("flow set-operation"), "{message}");
+    assert!(message.contains("node `start`"), "{message}");
+    Ok(())
+}
+
+#[test]
+fn require_operations_flag_fails_the_cli_build() -> Result<()> {
+    let workspace = Workspace::new("pack-ops-require-cli")?;
+    copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let build = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+            .current_dir(&workspace.root)
+            .args([
+                "pack",
+                "build",
+                "--flow",
+                "flows/hello-flow.ygtc",
+                "--component-dir",
+                "components",
+                "--out",
+                "dist/hello.gtpack",
+            ])
+            .args(extra)
+            .output()
+    };
+
+    // The fixture flow names its operations, so requiring them changes nothing.
+    let output = build(&["--require-operations"])?;
+    assert!(output.status.success(), "{output:?}");
+
+    strip_operations(&flow_path)?;
+    let output = build(&["--require-operations"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("node `start`"), "{stderr}");
+    assert!(stderr.contains("flow set-operation"), "{stderr}");
+
+    // Without the flag the local build defaults the operations.
+    let output = build(&["--timings"])?;
+    assert!(output.status.success(), "{output:?}");
+    Ok(())
+}
+
+#[test]
+fn explicit_operations_are_not_reported() -> Result<()> {
+    let workspace = Workspace::new("pack-ops-explicit")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+
+    let (_, report) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+        PackBuildOptions {
+            require_operations: true,
+            ..PackBuildOptions::default()
+        },
+    )?;
+    assert!(report.backfilled_operations.is_empty());
+    Ok(())
+}
@@ -0,0 +1,143 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_changelog::Change;
+use greentic_dev::pack_plan::{load_plan, plan_summary};
+use greentic_dev::pack_plan_baseline::{self, drift, render};
+use greentic_dev::pack_plan_terraform::write_module;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn summary_lists_components_with_the_flows_using_them() -> Result<()> {
+    let workspace = Workspace::new("pack-plan-summary")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let summary = plan_summary(&pack, "tenant-local", "local")?;
+    assert_eq!(summary.tenant, "tenant-local");
+    assert_eq!(summary.environment, "local");
+    assert!(!summary.components.is_empty());
+    for component in &summary.components {
+        assert!(component.wasm_bytes > 0, "{component:?}");
+        assert!(!component.flows.is_empty(), "{component:?}");
+    }
+    assert_eq!(
+        summary.resources.wasm_bytes,
+        summary.components.iter().map(|c| c.wasm_bytes).sum::<u64>()
+    );
+    assert!(
+        summary
+            .warnings
+            .iter()
+            .all(|warning| !warning.contains("not used by any flow")),
+        "{:?}",
+        summary.warnings
+    );
+    Ok(())
+}
+
+#[test]
+fn terraform_export_declares_secret_variables() -> Result<()> {
+    let workspace = Workspace::new("pack-plan-terraform")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["capabilities"]["host"]["secrets"] = serde_json::json!({
+        "required": [{
+            "key": "WEATHER_API_KEY",
+            "required": true,
+            "scope": { "env": "dev", "tenant": "demo" },
+            "format": "text"
+        }]
+    });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let summary = plan_summary(&pack, "tenant-local", "local")?;
+    let secret = summary
+        .secrets
+        .iter()
+        .find(|secret| secret.key == "WEATHER_API_KEY")
+        .unwrap_or_else(|| panic!("{:?}", summary.secrets));
+    assert_eq!(secret.components, ["dev.greentic.echo"]);
+    assert!(summary.warnings.is_empty(), "{:?}", summary.warnings);
+
+    let (load, plan) = load_plan(&pack, "acme", "staging")?;
+    let out = workspace.root.join("infra");
+    let written = write_module(&load.manifest, &plan, &out)?;
+    assert_eq!(written.len(), 4);
+
+    let variables = fs::read_to_string(out.join("variables.tf"))?;
+    assert!(variables.contains("variable \"secret_weather_api_key\""));
+    assert!(variables.contains("sensitive   = true"));
+    assert!(variables.contains("default     = \"acme\""));
+    let main = fs::read_to_string(out.join("main.tf"))?;
+    assert!(main.contains("resource \"terraform_data\" \"component_dev_greentic_echo\""));
+    assert!(main.contains("secrets     = [\"WEATHER_API_KEY\"]"));
+    assert!(main.contains("WEATHER_API_KEY = var.secret_weather_api_key"));
+    Ok(())
+}
+
+#[test]
+fn baseline_diff_reports_drift_against_a_saved_summary() -> Result<()> {
+    let workspace = Workspace::new("pack-plan-baseline")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let summary = plan_summary(&pack, "tenant-local", "local")?;
+    let baseline_path = workspace.root.join("plan.json");
+    fs::write(&baseline_path, serde_json::to_string_pretty(&summary)?)?;
+    let saved = pack_plan_baseline::load(&baseline_path)?;
+    let same = drift(&saved, &summary, &baseline_path);
+    assert!(same.is_empty(), "{same:?}");
+    assert!(render(&same).contains("No drift"));
+
+    let mut edited: serde_json::Value = serde_json::from_str(&fs::read_to_string(&baseline_path)?)?;
+    edited["components"][0]["version"] = "0.0.1".into();
+    edited["connectors"] = serde_json::json!(["webchat (webchat) -> hello-flow"]);
+    edited["secrets"] = serde_json::json!([
+        { "key": "OLD_TOKEN", "required": true, "components": [] }
+    ]);
+    fs::write(&baseline_path, edited.to_string())?;
+    let saved = pack_plan_baseline::load(&baseline_path)?;
+    let changed = drift(&saved, &summary, &baseline_path);
+    assert_eq!(changed.len(), 3, "{changed:?}");
+    let component = &changed.components[0];
+    assert_eq!(component.change, Change::Updated);
+    assert_eq!(component.baseline.as_deref(), Some("0.0.1"));
+    assert_eq!(changed.connectors[0].name, "webchat");
+    assert_eq!(changed.connectors[0].change, Change::Removed);
+    assert_eq!(changed.secrets[0].name, "OLD_TOKEN");
+    let rendered = render(&changed);
+    assert!(
+        rendered.contains("- webchat (webchat) -> hello-flow"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("- OLD_TOKEN required"), "{rendered}");
+
+    fs::write(&baseline_path, "{\"components\": 3}")?;
+    let err = pack_plan_baseline::load(&baseline_path).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("is not a saved plan"),
+        "{err:#}"
+    );
+    Ok(())
+}
@@ -0,0 +1,79 @@
+mod support;
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin_cmd;
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git");
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn provenance_commit_is_checked_against_a_local_repository() -> Result<()> {
+    let workspace = Workspace::new("pack-provenance")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    git(&workspace.root, &["init", "--quiet"]);
+    git(&workspace.root, &["add", "."]);
+    git(&workspace.root, &["commit", "--quiet", "-m", "initial"]);
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let output = cargo_bin_cmd!("greentic-dev")
+        .current_dir(&workspace.root)
+        .arg("pack")
+        .arg("provenance")
+        .arg(&pack)
+        .args(["--repo", ".", "--json"])
+        .output()?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let commit = report["provenance"]["git_commit"]
+        .as_str()
+        .expect("provenance commit");
+    let checks = report["checks"].as_array().expect("checks");
+    let commit_check = checks
+        .iter()
+        .find(|check| check["name"] == "commit")
+        .expect("commit check");
+    assert_eq!(commit_check["status"], "ok", "{commit_check}");
+    assert!(commit_check["detail"].as_str().unwrap().contains(commit));
+    assert_eq!(report["signature"]["dev_key"], true);
+
+    let elsewhere = workspace.root.join("elsewhere");
+    std::fs::create_dir_all(&elsewhere)?;
+    git(&elsewhere, &["init", "--quiet"]);
+    let output = cargo_bin_cmd!("greentic-dev")
+        .current_dir(&workspace.root)
+        .arg("pack")
+        .arg("provenance")
+        .arg(&pack)
+        .args(["--repo", "elsewhere"])
+        .output()?;
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✗ commit"), "{stdout}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("provenance checks failed"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
@@ -0,0 +1,83 @@
+mod support;
+
+use std::fs;
+use std::process::Command;
+
+use serde_json::Value as JsonValue;
+use support::{Workspace, copy_fixture_component, load_gtpack, write_pack_flow};
+
+#[test]
+fn prune_leaves_out_components_only_unreachable_nodes_use() {
+    let workspace = Workspace::new("pack-prune").unwrap();
+    let root = workspace.root.as_path();
+    let echo = copy_fixture_component(&workspace, false).unwrap();
+    // A second component, used only by a node nothing routes to.
+    let legacy = root.join("components/dev.greentic.legacy");
+    fs::create_dir_all(&legacy).unwrap();
+    for entry in fs::read_dir(&echo).unwrap() {
+        let entry = entry.unwrap();
+        fs::copy(entry.path(), legacy.join(entry.file_name())).unwrap();
+    }
+    let manifest_path = legacy.join("component.manifest.json");
+    let mut manifest: JsonValue =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    manifest["id"] = "dev.greentic.legacy".into();
+    fs::write(&manifest_path, manifest.to_string()).unwrap();
+    let flow = write_pack_flow(&workspace, "hello-flow").unwrap();
+    let mut source = fs::read_to_string(&flow).unwrap();
+    source.push_str(
+        "  legacy:\n    component.exec:\n      component: \"dev.greentic.legacy\"\n      op: \"echo\"\n      input:\n        message: \"unused\"\n    routing:\n      - out: true\n",
+    );
+    fs::write(&flow, source).unwrap();
+
+    let build = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+            .current_dir(root)
+            .args([
+                "pack",
+                "build",
+                "--flow",
+                "flows/hello-flow.ygtc",
+                "--component-dir",
+                "components",
+                "--out",
+                "dist/hello.gtpack",
+            ])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    let components = || {
+        let (manifest, _) = load_gtpack(&root.join("dist/hello.gtpack")).unwrap();
+        let mut names = manifest
+            .components
+            .iter()
+            .map(|component| component.name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    let output = build(&["--timings"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(components(), ["dev.greentic.echo", "dev.greentic.legacy"]);
+
+    let output = build(&["--prune"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("pruned 1 component(s) no entry flow reaches:"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- dev.greentic.legacy@0.1.0 (nodes: legacy)"),
+        "{stdout}"
+    );
+    assert_eq!(components(), ["dev.greentic.echo"]);
+
+    let output = build(&["--prune", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let report: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["pruned"][0]["name"], "dev.greentic.legacy");
+    assert_eq!(report["pruned"][0]["nodes"][0], "legacy");
+}
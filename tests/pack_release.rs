@@ -0,0 +1,58 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_release::create_bundle;
+use greentic_pack::reader::{SigningPolicy, open_pack};
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn release_bundle_contains_pack_signature_sbom_and_checksums() -> Result<()> {
+    let workspace = Workspace::new("pack-release-bundle")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let load =
+        open_pack(&pack, SigningPolicy::DevOk).map_err(|err| anyhow::anyhow!(err.message))?;
+    let out = workspace.root.join("release");
+    let dir = create_bundle(&pack, &load, &out, false)?;
+    let meta = &load.manifest.meta;
+    assert_eq!(dir, out.join(&meta.pack_id).join(meta.version.to_string()));
+
+    let stem = format!("{}-{}.gtpack", meta.pack_id, meta.version);
+    let checksums = fs::read_to_string(dir.join("SHA256SUMS"))?;
+    for name in [
+        stem.clone(),
+        format!("{stem}.sig"),
+        format!("{stem}.chain.pem"),
+        "sbom.greentic.json".to_string(),
+        "sbom.cdx.json".to_string(),
+        "provenance.intoto.json".to_string(),
+        "build-report.json".to_string(),
+        "CHANGELOG.md".to_string(),
+    ] {
+        assert!(dir.join(&name).is_file(), "{name} missing");
+        assert!(checksums.contains(&format!("  {name}\n")), "{checksums}");
+    }
+    assert_eq!(fs::read(dir.join(&stem))?, fs::read(&pack)?);
+
+    let cdx: serde_json::Value = serde_json::from_slice(&fs::read(dir.join("sbom.cdx.json"))?)?;
+    assert_eq!(cdx["bomFormat"], "CycloneDX");
+    let statement: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("provenance.intoto.json"))?)?;
+    assert_eq!(statement["subject"][0]["name"], stem.as_str());
+    let report: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("build-report.json"))?)?;
+    assert_eq!(report["verification"]["signature_ok"], true);
+
+    let err = create_bundle(&pack, &load, &out, false).expect_err("existing bundle");
+    assert!(err.to_string().contains("--force"), "{err}");
+    create_bundle(&pack, &load, &out, true)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn pack_run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["pack", "run"])
+        .args(args)
+        .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", dir.join("runner"))
+        .env("API_URL", "from-shell")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn env_files_reach_the_runner_and_replays() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = dir.path().join("runner");
+    fs::write(
+        &stub,
+        "#!/bin/sh\necho \"args: $*\"\necho \"token: $API_TOKEN\"\necho \"url: $API_URL\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(
+        dir.path().join(".env"),
+        "API_TOKEN=base\nAPI_URL=https://dev\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join(".env.dev"), "API_TOKEN=\"dev\"\n").unwrap();
+
+    let output = pack_run(
+        dir.path(),
+        &[
+            "--pack",
+            "missing.gtpack",
+            "--env-file",
+            ".env",
+            "--env-file=.env.dev",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("args: --pack missing.gtpack\n"), "{stdout}");
+    assert!(stdout.contains("token: dev\n"), "{stdout}");
+    assert!(stdout.contains("url: https://dev\n"), "{stdout}");
+
+    // Replays read the env files again, as they are now.
+    fs::write(dir.path().join(".env.dev"), "API_TOKEN=rotated\n").unwrap();
+    let output = pack_run(dir.path(), &["--replay", "1"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("token: rotated\n"), "{stdout}");
+
+    let output = pack_run(
+        dir.path(),
+        &["--pack", "missing.gtpack", "--env-file", "absent.env"],
+    );
+    assert!(!output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("absent.env"));
+}
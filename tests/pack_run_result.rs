@@ -0,0 +1,198 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde_json::Value as JsonValue;
+
+fn runner_stub(dir: &Path, result: &str, code: i32) -> PathBuf {
+    let stub = dir.join("greentic-runner-cli");
+    fs::write(
+        &stub,
+        format!("#!/bin/sh\ncat <<'EOF'\n{result}\nEOF\nexit {code}\n"),
+    )
+    .unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    stub
+}
+
+fn pack_run(dir: &Path, stub: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["pack", "run"])
+        .args(args)
+        .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", stub)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn output_schema_is_printed_without_a_runner() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = pack_run(
+        dir.path(),
+        &dir.path().join("missing"),
+        &["--output-schema"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let schema: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        schema["$id"],
+        "https://greenticai.github.io/greentic-dev/schemas/pack-run-result.v1.schema.json"
+    );
+}
+
+#[test]
+fn json_results_are_validated_and_versioned() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = runner_stub(
+        dir.path(),
+        r#"{"status": "succeeded", "output": {"greeting": "hi"}, "trace": [{"node_id": "start", "status": "succeeded"}]}"#,
+        0,
+    );
+    let output = pack_run(dir.path(), &stub, &["--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let result: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["schema_version"], 1);
+    assert_eq!(result["output"]["greeting"], "hi");
+}
+
+#[test]
+fn nonconforming_results_fail_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = runner_stub(dir.path(), r#"{"status": "done"}"#, 0);
+    let output = pack_run(dir.path(), &stub, &["--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("does not match the pack run result schema (v1)"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("/status"), "{stderr}");
+
+    // Without --json the runner's output passes through unchecked.
+    let output = pack_run(dir.path(), &stub, &[]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("\"done\""));
+}
+
+#[test]
+fn capture_filters_trim_trace_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = runner_stub(
+        dir.path(),
+        r#"{"status": "succeeded", "trace": [
+            {"node_id": "lookup", "status": "succeeded", "output": {"id": 7, "email": "a@example.com"}},
+            {"node_id": "render", "status": "succeeded", "output": {"html": "<p>hi</p>"}}
+        ]}"#,
+        0,
+    );
+    let config = dir.path().join("config.toml");
+    fs::write(&config, "[defaults.capture]\nfields = [\"$.id\"]\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir.path())
+        .args(["pack", "run", "--json", "--capture", "nodes=look*"])
+        .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", &stub)
+        .env("GREENTIC_DEV_CONFIG_FILE", &config)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let result: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["trace"][0]["output"], serde_json::json!({"$.id": 7}));
+    assert!(result["trace"][1].get("output").is_none());
+}
+
+#[test]
+fn runs_report_per_node_and_total_usage() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = runner_stub(
+        dir.path(),
+        r#"{"status": "succeeded", "trace": [
+            {"node_id": "fetch", "status": "succeeded", "duration_ms": 30,
+             "usage": {"fuel": 900, "peak_memory_bytes": 65536, "http_calls": 3}},
+            {"node_id": "store", "status": "succeeded", "duration_ms": 4,
+             "usage": {"fuel": 100, "artifact_bytes_written": 2048}}
+        ]}"#,
+        0,
+    );
+    let output = pack_run(dir.path(), &stub, &["--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let result: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    let usage = &result["usage"];
+    assert_eq!(usage["fuel"], 1000);
+    assert_eq!(usage["peak_memory_bytes"], 65536);
+    assert_eq!(usage["artifact_bytes_written"], 2048);
+    assert_eq!(usage["http_calls"], 3);
+    assert!(usage["wall_ms"].is_u64(), "{usage}");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Resource usage"), "{stderr}");
+    for node in ["fetch", "store", "total"] {
+        assert!(
+            stderr
+                .lines()
+                .any(|line| line.trim_start().starts_with(node)),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn expectations_decide_the_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = runner_stub(
+        dir.path(),
+        r#"{"status": "succeeded", "output": {"greeting": "hi", "count": 3}}"#,
+        0,
+    );
+    // The checks need the JSON result, so they imply --json.
+    let output = pack_run(
+        dir.path(),
+        &stub,
+        &[
+            "--expect-status",
+            "Success",
+            "--expect-output-contains",
+            "/greeting=hi",
+            "--expect-output-contains=/count=3",
+        ],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let result: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["schema_version"], 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expectations met"));
+
+    let output = pack_run(
+        dir.path(),
+        &stub,
+        &[
+            "--json",
+            "--expect-status",
+            "Failure",
+            "--expect-output-contains",
+            "/greeting=\"bye\"",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expectation failed: status: expected failed, got succeeded"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("expectation failed: output/greeting: expected \"bye\", got \"hi\""),
+        "{stderr}"
+    );
+
+    // A failed run that was expected to fail passes.
+    let stub = runner_stub(
+        dir.path(),
+        r#"{"status": "failed", "error": {"code": "NODE_FAILED", "message": "boom"}}"#,
+        1,
+    );
+    let output = pack_run(dir.path(), &stub, &["--expect-status", "failure"]);
+    assert!(output.status.success(), "{output:?}");
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::{Value as JsonValue, json};
+
+fn infer(dir: &Path, extra: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .args(["pack", "secrets", "infer", "--in"])
+        .arg(dir)
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn infers_requirements_from_flows_and_components() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("flows")).unwrap();
+    fs::write(
+        dir.path().join("flows/main.ygtc"),
+        r#"id: main
+start: fetch
+nodes:
+  fetch:
+    component.exec:
+      component: acme.http
+      input:
+        token: "{{ secrets.API_TOKEN }}"
+        db: "secrets://dev/acme/_/configs/db"
+    routing:
+      - out: true
+"#,
+    )
+    .unwrap();
+    fs::create_dir_all(dir.path().join("components/mailer")).unwrap();
+    fs::write(
+        dir.path().join("components/mailer/component.manifest.json"),
+        json!({
+            "name": "mailer",
+            "capabilities": {"host": {"secrets": {"required": [
+                {"key": "SMTP_PASSWORD", "required": false}
+            ]}}}
+        })
+        .to_string(),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("secret-requirements.json"),
+        json!([{"key": "OLD_KEY", "description": "kept as written"}]).to_string(),
+    )
+    .unwrap();
+
+    let checked = infer(dir.path(), &["--check"]);
+    assert!(!checked.status.success(), "{checked:?}");
+
+    let inferred = infer(dir.path(), &[]);
+    assert!(inferred.status.success(), "{inferred:?}");
+    let stdout = String::from_utf8_lossy(&inferred.stdout);
+    assert!(
+        stdout.contains("OLD_KEY is declared but no flow"),
+        "{stdout}"
+    );
+
+    let written: JsonValue = serde_json::from_str(
+        &fs::read_to_string(dir.path().join("secret-requirements.json")).unwrap(),
+    )
+    .unwrap();
+    let keys = written
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry["key"].as_str().unwrap(), entry["required"].clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        [
+            ("OLD_KEY", json!(true)),
+            ("API_TOKEN", json!(true)),
+            ("SMTP_PASSWORD", json!(false)),
+            ("configs/db", json!(true)),
+        ]
+    );
+    assert_eq!(written[0]["description"], "kept as written");
+
+    let rechecked = infer(dir.path(), &["--check"]);
+    assert!(rechecked.status.success(), "{rechecked:?}");
+}
@@ -17,6 +17,8 @@ fn pack_build_run_verify_smoke() {
     let temp = tempfile::tempdir().expect("tempdir");
     let pack_path = temp.path().join("smoke.gtpack");
     let artifacts_dir = temp.path().join("artifacts");
+    // A leftover from a killed build is replaced, and nothing is left beside the pack.
+    fs::write(temp.path().join("smoke.gtpack.tmp"), b"stale").expect("stale tmp");
     pack_build::run(
         &flow_path,
         &pack_path,
@@ -25,6 +27,7 @@ fn pack_build_run_verify_smoke() {
         Some(component_dir.as_path()),
     )
     .expect("pack build");
+    assert!(!temp.path().join("smoke.gtpack.tmp").exists());
 
     let runner_cli = write_runner_cli_stub(temp.path());
     let mut cmd = cargo_bin_cmd!("greentic-dev");
@@ -0,0 +1,50 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_build::PackBuildOptions;
+use support::{Workspace, build_pack_with_options, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn builds_record_phase_timings_in_the_report() -> Result<()> {
+    let workspace = Workspace::new("pack-timings")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let (_, report) = build_pack_with_options(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+        PackBuildOptions::default(),
+    )?;
+
+    let phases = report
+        .timings
+        .iter()
+        .map(|timing| timing.phase.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(phases.first(), Some(&"flow parse"));
+    assert!(
+        phases
+            .iter()
+            .any(|phase| phase.starts_with("resolve ") && phase.contains("dev.greentic.echo")),
+        "{phases:?}"
+    );
+    for phase in [
+        "schema validation",
+        "metadata and policy",
+        "artifact collection",
+        "signing and zip write",
+    ] {
+        assert!(phases.contains(&phase), "missing {phase}: {phases:?}");
+    }
+
+    let recorded: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        workspace.root.join(".greentic/build_report.json"),
+    )?)?;
+    assert_eq!(
+        recorded["timings"].as_array().map(Vec::len),
+        Some(report.timings.len())
+    );
+    Ok(())
+}
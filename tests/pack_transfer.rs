@@ -0,0 +1,104 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::pack_cli::{PackPullArgs, PackPushArgs};
+use greentic_dev::pack_transfer::{pull, push};
+use httpmock::MockServer;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn packs_round_trip_through_the_distributor() -> Result<()> {
+    if std::net::TcpListener::bind("127.0.0.1:0").is_err() {
+        eprintln!("Skipping test; cannot bind local port in this environment");
+        return Ok(());
+    }
+    let workspace = Workspace::new("pack-transfer")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+    let bytes = fs::read(&pack)?;
+    let digest = format!(
+        "sha256:{}",
+        Sha256::digest(&bytes)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    );
+
+    let server = MockServer::start();
+    let config_path = workspace.root.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+[distributor.profiles.staging]
+url = "{}"
+tenant_id = "acme"
+environment_id = "staging"
+"#,
+            server.base_url()
+        ),
+    )?;
+    unsafe { std::env::set_var("GREENTIC_CONFIG", &config_path) };
+
+    let upload = server.mock(|when, then| {
+        when.method("PUT")
+            .path_includes("/v1/packs/")
+            .query_param("tenant_id", "acme")
+            .query_param("environment_id", "staging")
+            .query_param("digest", digest.as_str());
+        then.status(200).json_body(json!({
+            "coordinate": "pack://acme/hello@0.1.0",
+            "digest": digest.clone(),
+        }));
+    });
+    push(&PackPushArgs {
+        pack: pack.clone(),
+        profile: Some("staging".to_string()),
+    })?;
+    upload.assert();
+
+    server.mock(|when, then| {
+        when.method("POST").path("/v1/resolve");
+        then.status(200).json_body(json!({
+            "kind": "pack",
+            "name": "hello",
+            "version": "0.1.0",
+            "coordinate": "pack://acme/hello@0.1.0",
+            "artifact_id": "artifacts/hello",
+            "artifact_download_path": "/v1/artifact/artifacts/hello",
+            "digest": digest.clone(),
+            "license": {
+                "license_type": "free",
+                "id": null,
+                "requires_acceptance": false,
+                "checkout_url": null
+            },
+            "metadata": {}
+        }));
+    });
+    server.mock(|when, then| {
+        when.method("GET").path("/v1/artifact/artifacts/hello");
+        then.status(200).body(bytes.clone());
+    });
+    let out = workspace.root.join("pulled.gtpack");
+    let pull_args = PackPullArgs {
+        coordinate: "hello@0.1.0".to_string(),
+        out: Some(out.clone()),
+        profile: Some("staging".to_string()),
+        force: false,
+        limit_rate: None,
+    };
+    pull(&pull_args)?;
+    assert_eq!(fs::read(&out)?, bytes);
+    assert!(pull(&pull_args).is_err(), "existing files need --force");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+mod support;
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use support::{Workspace, copy_fixture_component, write_pack_flow};
+
+struct Watcher {
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Watcher {
+    // Wait for the next "built in" line and return it, failing on a build error.
+    fn next_build(&self) -> String {
+        loop {
+            let line = self
+                .lines
+                .recv_timeout(Duration::from_secs(60))
+                .expect("watcher produced no build");
+            assert!(!line.contains("build failed"), "{line}");
+            if line.contains("built in") {
+                return line;
+            }
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// Rewrite a file with the same contents plus a trailing newline, so its mtime and size change.
+fn touch(path: &std::path::Path) -> Result<()> {
+    let mut contents = fs::read_to_string(path)?;
+    contents.push('\n');
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[test]
+fn watch_rebuilds_on_changes_and_reuses_unchanged_components() -> Result<()> {
+    let workspace = Workspace::new("pack-watch")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(&workspace.root)
+        .args([
+            "pack",
+            "build",
+            "--watch",
+            "--flow",
+            "flows/hello-flow.ygtc",
+            "--component-dir",
+            "components",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (tx, lines) = mpsc::channel();
+    for stream in [
+        Box::new(child.stdout.take().unwrap()) as Box<dyn std::io::Read + Send>,
+        Box::new(child.stderr.take().unwrap()),
+    ] {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        });
+    }
+    let watcher = Watcher { child, lines };
+
+    let first = watcher.next_build();
+    assert!(first.contains("1 component(s) re-resolved"), "{first}");
+    let pack = workspace.root.join("dist/hello-flow.gtpack");
+    assert!(pack.is_file());
+
+    touch(&flow_path)?;
+    let second = watcher.next_build();
+    assert!(second.contains("0 component(s) re-resolved"), "{second}");
+
+    touch(&component_dir.join("component.manifest.json"))?;
+    let third = watcher.next_build();
+    assert!(third.contains("1 component(s) re-resolved"), "{third}");
+    Ok(())
+}
@@ -0,0 +1,134 @@
+mod support;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value as JsonValue;
+use support::{Workspace, copy_fixture_component, load_gtpack};
+
+const PACK_YAML: &str = r#"pack_id: dev.local.shop
+version: 0.2.0
+kind: application
+publisher: Acme
+
+flows:
+  - id: main
+    file: flows/main.ygtc
+    entrypoints: [default]
+  - id: billing
+    file: flows/billing.ygtc
+  - id: archive
+    file: flows/archive.ygtc
+
+components: []
+"#;
+
+fn exec_node(component: &str, routing: &str) -> String {
+    format!(
+        "    component.exec:\n      component: \"{component}\"\n      op: \"echo\"\n      input:\n        message: \"hi\"\n    routing:\n      - {routing}\n"
+    )
+}
+
+fn greentic_dev(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(root)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn pack_yaml_workspaces_build_every_flow_into_one_pack() {
+    let workspace = Workspace::new("pack-workspace-build").unwrap();
+    let root = workspace.root.as_path();
+    // The fixture lands in <root>/components; the pack keeps its own under shop/components.
+    let echo = copy_fixture_component(&workspace, false).unwrap();
+    let shop = root.join("shop");
+    for name in ["dev.greentic.echo", "dev.greentic.legacy"] {
+        let dest = shop.join("components").join(name);
+        fs::create_dir_all(&dest).unwrap();
+        for entry in fs::read_dir(&echo).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), dest.join(entry.file_name())).unwrap();
+        }
+        let manifest_path = dest.join("component.manifest.json");
+        let mut manifest: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        manifest["id"] = name.into();
+        fs::write(&manifest_path, manifest.to_string()).unwrap();
+    }
+    fs::create_dir_all(shop.join("flows")).unwrap();
+    fs::write(shop.join("pack.yaml"), PACK_YAML).unwrap();
+    fs::write(
+        shop.join("flows/main.ygtc"),
+        format!(
+            "id: main\ntype: messaging\nstart: start\nnodes:\n  start:\n{}  handoff:\n    flow.call:\n      flow: billing\n    routing:\n      - out: true\n",
+            exec_node("dev.greentic.echo", "to: handoff")
+        ),
+    )
+    .unwrap();
+    fs::write(
+        shop.join("flows/billing.ygtc"),
+        format!(
+            "id: billing\ntype: messaging\nstart: charge\nnodes:\n  charge:\n{}",
+            exec_node("dev.greentic.echo", "out: true")
+        ),
+    )
+    .unwrap();
+    fs::write(
+        shop.join("flows/archive.ygtc"),
+        format!(
+            "id: archive\ntype: messaging\nstart: old\nnodes:\n  old:\n{}",
+            exec_node("dev.greentic.legacy", "out: true")
+        ),
+    )
+    .unwrap();
+    let components = || {
+        let (manifest, _) = load_gtpack(&root.join("dist/shop.gtpack")).unwrap();
+        let mut names = manifest
+            .components
+            .iter()
+            .map(|component| component.name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        (manifest, names)
+    };
+
+    let output = greentic_dev(root, &["pack", "build", "--in", "shop", "--timings"]);
+    assert!(output.status.success(), "{output:?}");
+    let (manifest, names) = components();
+    // The echo component two flows use is packaged once.
+    assert_eq!(names, ["dev.greentic.echo", "dev.greentic.legacy"]);
+    let flows = manifest
+        .flows
+        .iter()
+        .map(|flow| flow.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(flows, ["archive", "billing", "main"]);
+    assert_eq!(manifest.meta.entry_flows, ["main"]);
+    assert_eq!(manifest.meta.pack_id, "dev.local.shop");
+    assert_eq!(manifest.meta.version.to_string(), "0.2.0");
+    assert_eq!(manifest.meta.vendor.as_deref(), Some("Acme"));
+
+    // Only the archive flow, which nothing calls, uses the legacy component.
+    let output = greentic_dev(
+        root,
+        &["pack", "build", "--in", "shop", "--prune", "--json"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    let report: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["pruned"][0]["name"], "dev.greentic.legacy");
+    assert_eq!(report["pruned"][0]["nodes"][0], "archive/old");
+    assert_eq!(components().1, ["dev.greentic.echo"]);
+
+    let listed = PACK_YAML.replace("id: archive", "id: archived");
+    fs::write(shop.join("pack.yaml"), listed).unwrap();
+    let output = greentic_dev(root, &["pack", "build", "--in", "shop", "--timings"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("declares flow `archive`, but pack.yaml lists it as `archived`"),
+        "{stderr}"
+    );
+}
@@ -1,7 +1,67 @@
-use greentic_dev::registry::DescribeRegistry;
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::cargo::cargo_bin_cmd;
+use greentic_dev::registry::{DescribeRegistry, REGISTRY_PATH};
+use predicates::str::contains;
+use serde_json::json;
+use support::{Workspace, copy_fixture_component};
 
 #[test]
 fn can_get_stub_schema() {
     let registry = DescribeRegistry::new();
     assert!(registry.get_schema("oauth").is_some());
 }
+
+#[test]
+fn exported_registry_imports_into_another_workspace() -> Result<()> {
+    let catalog = Workspace::new("registry-export")?;
+    copy_fixture_component(&catalog, false)?;
+    cargo_bin_cmd!("greentic-dev")
+        .current_dir(&catalog.root)
+        .args(["registry", "export", "registry.json"])
+        .assert()
+        .success()
+        .stdout(contains("Exported 1 component(s)"));
+    let exported = catalog.root.join("registry.json");
+
+    let consumer = Workspace::new("registry-import")?;
+    cargo_bin_cmd!("greentic-dev")
+        .current_dir(&consumer.root)
+        .args(["registry", "import", exported.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("1 added, 0 updated"));
+
+    let registry = DescribeRegistry::for_workspace(&consumer.root)?;
+    assert!(registry.get_schema("dev.greentic.echo").is_some());
+    assert!(registry.get_schema("oauth").is_some(), "built-ins are kept");
+    assert_eq!(
+        fs::read_to_string(consumer.root.join(REGISTRY_PATH))?,
+        fs::read_to_string(&exported)?
+    );
+    Ok(())
+}
+
+#[test]
+fn import_rejects_schemas_that_do_not_compile() -> Result<()> {
+    let workspace = Workspace::new("registry-invalid")?;
+    let file = workspace.root.join("broken.json");
+    fs::write(
+        &file,
+        serde_json::to_string(&json!({
+            "version": 1,
+            "components": { "broken": { "schema": { "type": 42 } } }
+        }))?,
+    )?;
+    cargo_bin_cmd!("greentic-dev")
+        .current_dir(&workspace.root)
+        .args(["registry", "import", file.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(contains("component `broken`"));
+    assert!(!workspace.root.join(REGISTRY_PATH).exists());
+    Ok(())
+}
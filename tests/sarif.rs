@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+fn greentic_dev(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn sarif_results(output: &Output) -> Vec<Value> {
+    let log: Value = serde_json::from_slice(&output.stdout).expect("SARIF log on stdout");
+    assert_eq!(log["version"], "2.1.0");
+    log["runs"][0]["results"].as_array().unwrap().clone()
+}
+
+fn start_line(result: &Value) -> &Value {
+    &result["locations"][0]["physicalLocation"]["region"]["startLine"]
+}
+
+#[test]
+fn lint_findings_point_at_their_nodes() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("main.ygtc"),
+        r#"id: main
+start: start
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - out: true
+  orphan:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - out: true
+"#,
+    )
+    .unwrap();
+
+    let output = greentic_dev(
+        dir.path(),
+        &["flow", "lint", "main.ygtc", "--output", "sarif"],
+    );
+    assert!(!output.status.success(), "{output:?}");
+    let results = sarif_results(&output);
+    assert_eq!(results.len(), 1, "{results:?}");
+    assert_eq!(results[0]["ruleId"], "unreachable-node");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "main.ygtc"
+    );
+    assert_eq!(start_line(&results[0]), 8);
+}
+
+#[test]
+fn validate_reports_invalid_flows_and_passes_valid_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("good.ygtc"),
+        r#"id: good
+type: messaging
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - out: true
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("bad.ygtc"),
+        r#"id: bad
+type: messaging
+nodes:
+  start:
+    component.exec: { component: dev.greentic.echo }
+    routing:
+      - to: missing
+"#,
+    )
+    .unwrap();
+
+    let output = greentic_dev(
+        dir.path(),
+        &["flow", "validate", "good.ygtc", "--output", "sarif"],
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert!(sarif_results(&output).is_empty());
+
+    let output = greentic_dev(
+        dir.path(),
+        &[
+            "flow",
+            "validate",
+            "good.ygtc",
+            "bad.ygtc",
+            "--output=sarif",
+        ],
+    );
+    assert!(!output.status.success(), "{output:?}");
+    let results = sarif_results(&output);
+    assert_eq!(results.len(), 1, "{results:?}");
+    assert!(
+        results[0]["ruleId"].as_str().unwrap().starts_with("flow-"),
+        "{results:?}"
+    );
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "bad.ygtc"
+    );
+    assert!(start_line(&results[0]).is_u64(), "{results:?}");
+}
@@ -0,0 +1,48 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn pack_run(dir: &Path, backend: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .current_dir(dir)
+        .args(["pack", "run", "--pack", "missing.gtpack"])
+        .env("GREENTIC_DEV_BIN_GREENTIC_RUNNER_CLI", dir.join("runner"))
+        .env("SECRETS_BACKEND", backend)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn the_runner_keeps_its_env_backend() {
+    let dir = tempfile::tempdir().unwrap();
+    let stub = dir.path().join("runner");
+    fs::write(&stub, "#!/bin/sh\necho \"backend: $SECRETS_BACKEND\"\n").unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = pack_run(dir.path(), "keyring");
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("backend: env\n"), "{stdout}");
+
+    let output = pack_run(dir.path(), "vault");
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unknown SECRETS_BACKEND `vault`"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn secret_names_are_checked_before_the_keychain_is_touched() {
+    let output = Command::new(env!("CARGO_BIN_EXE_greentic-dev"))
+        .args(["secrets", "get", "api-token"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a valid secret name"), "{stderr}");
+}
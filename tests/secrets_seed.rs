@@ -0,0 +1,71 @@
+mod support;
+
+use std::fs;
+
+use anyhow::Result;
+use greentic_dev::secrets_seed::{PLACEHOLDER, check, pack_requirements, skeleton};
+use greentic_secrets_spec::{SeedDoc, SeedValue};
+use support::{Workspace, build_pack, copy_fixture_component, write_pack_flow};
+
+#[test]
+fn seed_skeleton_covers_the_pack_requirements_once_filled_in() -> Result<()> {
+    let workspace = Workspace::new("secrets-seed")?;
+    let component_dir = copy_fixture_component(&workspace, false)?;
+    let manifest_path = component_dir.join("component.manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    manifest["capabilities"]["host"]["secrets"] = serde_json::json!({
+        "required": [
+            {
+                "key": "WEATHER_API_KEY",
+                "required": true,
+                "scope": { "env": "dev", "tenant": "demo" },
+                "format": "text"
+            },
+            {
+                "key": "WEATHER_REGION",
+                "required": false,
+                "scope": { "env": "dev", "tenant": "demo" },
+                "format": "text"
+            }
+        ]
+    });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let pack = build_pack(
+        &workspace,
+        &flow_path,
+        component_dir.parent().expect("component root"),
+    )?;
+
+    let requirements = pack_requirements(&pack)?;
+    let seed = skeleton(&requirements, "local", "tenant-local", None);
+    let seed_path = workspace.root.join("seed.yaml");
+    fs::write(&seed_path, serde_yaml_bw::to_string(&seed)?)?;
+    let mut seed: SeedDoc = serde_yaml_bw::from_str(&fs::read_to_string(&seed_path)?)?;
+    assert_eq!(
+        seed.entries
+            .iter()
+            .map(|entry| entry.uri.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "secrets://dev/demo/_/configs/weather_api_key",
+            "secrets://dev/demo/_/configs/weather_region",
+        ]
+    );
+    assert!(
+        check(&seed, Some(&requirements))
+            .errors
+            .iter()
+            .all(|error| error.contains(PLACEHOLDER))
+    );
+
+    seed.entries.retain(|entry| entry.uri.ends_with("api_key"));
+    seed.entries[0].value = SeedValue::Text {
+        text: "k-123".to_string(),
+    };
+    let report = check(&seed, Some(&requirements));
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(report.warnings, ["WEATHER_REGION has no entry (optional)"]);
+    Ok(())
+}
@@ -8,7 +8,7 @@ use std::sync::Mutex;
 
 use anyhow::{Context, Result};
 use blake3::Hasher;
-use greentic_dev::pack_build::{self, PackSigning};
+use greentic_dev::pack_build::{self, PackBuildOptions, PackBuildReport, PackSigning};
 use greentic_pack::builder::PackManifest;
 use serde_json::json;
 use tempfile::TempDir;
@@ -127,22 +127,38 @@ pub fn build_pack(
     flow_path: &Path,
     component_dir: &Path,
 ) -> Result<PathBuf> {
+    build_pack_with_options(
+        workspace,
+        flow_path,
+        component_dir,
+        PackBuildOptions::default(),
+    )
+    .map(|(pack_path, _)| pack_path)
+}
+
+pub fn build_pack_with_options(
+    workspace: &Workspace,
+    flow_path: &Path,
+    component_dir: &Path,
+    options: PackBuildOptions,
+) -> Result<(PathBuf, PackBuildReport)> {
     let _guard = WORKDIR_LOCK.lock().unwrap();
     let prev = std::env::current_dir().context("current_dir")?;
     std::env::set_current_dir(&workspace.root).context("set cwd")?;
 
     let pack_path = workspace.root.join("dist").join("test.gtpack");
-    pack_build::run(
+    let result = pack_build::run_with_options(
         flow_path,
         &pack_path,
         PackSigning::Dev,
         None,
         Some(component_dir),
+        options,
     )
-    .context("pack build")?;
+    .context("pack build");
 
     std::env::set_current_dir(prev).context("restore cwd")?;
-    Ok(pack_path)
+    Ok((pack_path, result?))
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,55 @@
+mod support;
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use greentic_dev::cli::CheckArgs;
+use greentic_dev::workspace_check::{self, EXIT_FAILED, EXIT_OK};
+use support::{WORKDIR_LOCK, Workspace, copy_fixture_component, write_pack_flow};
+
+fn check(workspace: &Workspace) -> Result<i32> {
+    let _guard = WORKDIR_LOCK.lock().unwrap();
+    let prev = std::env::current_dir().context("current_dir")?;
+    std::env::set_current_dir(&workspace.root).context("set cwd")?;
+    let result = workspace_check::run(&CheckArgs {
+        secrets_env: None,
+        json: true,
+    });
+    std::env::set_current_dir(prev).context("restore cwd")?;
+    result
+}
+
+#[test]
+fn clean_workspace_passes() -> Result<()> {
+    let workspace = Workspace::new("workspace-check-clean")?;
+    copy_fixture_component(&workspace, false)?;
+    write_pack_flow(&workspace, "hello-flow")?;
+
+    assert_eq!(check(&workspace)?, EXIT_OK);
+    Ok(())
+}
+
+#[test]
+fn broken_flows_fail_the_check() -> Result<()> {
+    let workspace = Workspace::new("workspace-check-broken")?;
+    copy_fixture_component(&workspace, false)?;
+    let flow_path = write_pack_flow(&workspace, "hello-flow")?;
+    let flow = fs::read_to_string(&flow_path)?;
+    fs::write(
+        workspace.root.join("flows/caller.ygtc"),
+        flow.replace("hello-flow", "caller").replace(
+            "nodes:",
+            "nodes:\n  handoff:\n    flow.call:\n      flow: missing\n",
+        ),
+    )?;
+
+    assert_eq!(check(&workspace)?, EXIT_FAILED);
+    Ok(())
+}
+
+#[test]
+fn outside_a_workspace_is_an_error() -> Result<()> {
+    let workspace = Workspace::new("workspace-check-empty")?;
+    assert!(check(&workspace).is_err());
+    Ok(())
+}